@@ -0,0 +1,110 @@
+//! Eyedropper that samples color of the pixel under mouse cursor
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread::spawn;
+use arboard::Clipboard;
+use mouse_position::mouse_position::Mouse;
+use screenshots::Screen;
+use streamduck_core::core::button::{Component, parse_button_to_component, parse_unique_button_to_component};
+use streamduck_core::core::methods::CoreHandle;
+use streamduck_core::core::UniqueButton;
+use streamduck_core::modules::components::ComponentDefinition;
+use streamduck_core::thread::rendering::{ButtonBackground, ButtonText, Color, RendererComponent, RendererComponentBuilder};
+use streamduck_core::thread::util::TextAlignment;
+
+/// Clipboard is kept alive, on X11 copied text disappears once clipboard gets dropped
+static CLIPBOARD: Mutex<Option<Clipboard>> = Mutex::new(None);
+
+pub fn add_definition(map: &mut HashMap<String, ComponentDefinition>) {
+    map.insert(ColorPickerComponent::NAME.to_string(), ComponentDefinition {
+        display_name: "Color Picker".to_string(),
+        description: "Copies hex code of the color under mouse cursor and shows the color on the button".to_string(),
+        default_looks: RendererComponentBuilder::new()
+            .background(ButtonBackground::Solid((50, 50, 50, 255)))
+            .add_text(ButtonText {
+                text: "#".to_string(),
+                font: "default".to_string(),
+                scale: (30.0, 30.0),
+                alignment: TextAlignment::Center,
+                padding: 0,
+                offset: (0.0, 0.0),
+                color: (255, 255, 255, 255),
+                shadow: None,
+                auto_fit: false,
+                word_wrap: false,
+                marquee_speed: 0.0
+            })
+            .build(),
+        requires: vec![],
+        conflicts_with: vec![]
+    });
+}
+
+/// Component that picks color under mouse cursor on press
+#[derive(Serialize, Deserialize, Default)]
+pub struct ColorPickerComponent {}
+
+impl Component for ColorPickerComponent {
+    const NAME: &'static str = "color_picker";
+}
+
+/// Reads color of the pixel under mouse cursor
+fn sample_cursor_color() -> Result<Color, String> {
+    let (x, y) = match Mouse::get_mouse_position() {
+        Mouse::Position { x, y } => (x, y),
+        Mouse::Error => return Err("Failed to get mouse position".to_string())
+    };
+
+    let screen = Screen::from_point(x, y).map_err(|e| e.to_string())?;
+    let image = screen.capture_area(x - screen.display_info.x, y - screen.display_info.y, 1, 1).map_err(|e| e.to_string())?;
+
+    let pixel = image.get_pixel_checked(0, 0).ok_or("Captured image is empty")?;
+    Ok((pixel[0], pixel[1], pixel[2], 255))
+}
+
+fn copy_to_clipboard(text: String) -> Result<(), String> {
+    let mut lock = CLIPBOARD.lock().unwrap();
+
+    if lock.is_none() {
+        *lock = Some(Clipboard::new().map_err(|e| e.to_string())?);
+    }
+
+    lock.as_mut().unwrap().set_text(text).map_err(|e| e.to_string())
+}
+
+pub fn action(core: &CoreHandle, button: &UniqueButton) {
+    if let Ok(_) = parse_unique_button_to_component::<ColorPickerComponent>(button) {
+        let core = core.clone();
+        let button = button.clone();
+
+        spawn(move || {
+            let color = match sample_cursor_color() {
+                Ok(color) => color,
+                Err(err) => {
+                    log::warn!("Failed to pick color: {}", err);
+                    return;
+                }
+            };
+
+            let hex = format!("#{:02X}{:02X}{:02X}", color.0, color.1, color.2);
+
+            if let Err(err) = copy_to_clipboard(hex.clone()) {
+                log::warn!("Failed to copy {} to clipboard: {}", hex, err);
+            }
+
+            // Showing picked color on the button as confirmation
+            {
+                let mut handle = button.write().unwrap();
+
+                if let Ok(mut renderer) = parse_button_to_component::<RendererComponent>(&handle) {
+                    renderer.background = ButtonBackground::Solid(color);
+                    handle.insert_component(renderer).ok();
+                }
+            }
+
+            core.core().mark_for_redraw();
+        });
+    }
+}
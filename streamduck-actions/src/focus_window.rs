@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Command;
+use std::thread::spawn;
+use streamduck_core::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
+use streamduck_core::core::UniqueButton;
+use streamduck_core::modules::components::{ComponentDefinition, map_ui_values, UIFieldType, UIFieldValue, UIValue};
+use streamduck_core::thread::rendering::{ButtonBackground, ButtonText, RendererComponent, RendererComponentBuilder};
+use streamduck_core::thread::util::TextAlignment;
+use crate::window_backend::{detect_backend, WindowInfo};
+
+pub fn add_definition(map: &mut HashMap<String, ComponentDefinition>) {
+    map.insert(FocusWindowComponent::NAME.to_string(), ComponentDefinition {
+        display_name: "Focus Window".to_string(),
+        description: "Brings window with matching title or class to front".to_string(),
+        default_looks: window_looks(),
+        requires: vec![],
+        conflicts_with: vec![LaunchOrFocusComponent::NAME.to_string()]
+    });
+
+    map.insert(LaunchOrFocusComponent::NAME.to_string(), ComponentDefinition {
+        display_name: "Launch or Focus".to_string(),
+        description: "Brings window with matching title or class to front, launches the application if there's no such window".to_string(),
+        default_looks: window_looks(),
+        requires: vec![],
+        conflicts_with: vec![FocusWindowComponent::NAME.to_string()]
+    });
+}
+
+fn window_looks() -> RendererComponent {
+    RendererComponentBuilder::new()
+        .background(ButtonBackground::Solid((50, 50, 50, 255)))
+        .add_text(ButtonText {
+            text: "[ ]".to_string(),
+            font: "default".to_string(),
+            scale: (30.0, 30.0),
+            alignment: TextAlignment::Center,
+            padding: 0,
+            offset: (0.0, 0.0),
+            color: (255, 255, 255, 255),
+            shadow: None,
+            auto_fit: false,
+            word_wrap: false,
+            marquee_speed: 0.0
+        })
+        .build()
+}
+
+fn string_value(name: &str, display_name: &str, description: &str, value: &str) -> UIValue {
+    UIValue {
+        name: name.to_string(),
+        display_name: display_name.to_string(),
+        description: description.to_string(),
+        ty: UIFieldType::InputFieldString,
+        value: UIFieldValue::InputFieldString(value.to_string())
+    }
+}
+
+fn matcher_values(matcher: &WindowMatcher) -> Vec<UIValue> {
+    vec![
+        string_value("title", "Window Title", "Part of window title to look for, case insensitive", &matcher.title),
+        string_value("class", "Window Class", "Part of window class, or app ID on Wayland, to look for, case insensitive", &matcher.class),
+    ]
+}
+
+pub fn get_values(button: &Button) -> Vec<UIValue> {
+    if let Ok(component) = parse_button_to_component::<FocusWindowComponent>(button) {
+        return matcher_values(&component.matcher);
+    }
+
+    if let Ok(component) = parse_button_to_component::<LaunchOrFocusComponent>(button) {
+        let mut fields = matcher_values(&component.matcher);
+        fields.push(string_value("command", "Command", "Command that launches the application, splits arguments by spaces", &component.command));
+        return fields;
+    }
+
+    vec![]
+}
+
+fn set_matcher_values(matcher: &mut WindowMatcher, change_map: &HashMap<String, UIValue>) {
+    if let Some(value) = change_map.get("title") {
+        if let Ok(title) = value.value.try_into_string() {
+            matcher.title = title;
+        }
+    }
+
+    if let Some(value) = change_map.get("class") {
+        if let Ok(class) = value.value.try_into_string() {
+            matcher.class = class;
+        }
+    }
+}
+
+pub fn set_values(button: &mut Button, value: Vec<UIValue>) {
+    let change_map = map_ui_values(value);
+
+    if let Ok(mut component) = parse_button_to_component::<FocusWindowComponent>(button) {
+        set_matcher_values(&mut component.matcher, &change_map);
+        button.insert_component(component).ok();
+    }
+
+    if let Ok(mut component) = parse_button_to_component::<LaunchOrFocusComponent>(button) {
+        set_matcher_values(&mut component.matcher, &change_map);
+
+        if let Some(value) = change_map.get("command") {
+            if let Ok(command) = value.value.try_into_string() {
+                component.command = command;
+            }
+        }
+
+        button.insert_component(component).ok();
+    }
+}
+
+/// Focuses first matching window, returns false if there's no such window
+fn focus_matching(matcher: &WindowMatcher) -> bool {
+    if matcher.title.is_empty() && matcher.class.is_empty() {
+        return false;
+    }
+
+    let backend = if let Some(backend) = detect_backend() {
+        backend
+    } else {
+        log::warn!("Window management isn't supported in this session");
+        return false;
+    };
+
+    let windows = match backend.windows() {
+        Ok(windows) => windows,
+        Err(err) => {
+            log::warn!("Failed to list windows: {}", err);
+            return false;
+        }
+    };
+
+    let window: Option<&WindowInfo> = windows.iter().find(|x| x.matches(&matcher.title, &matcher.class));
+
+    if let Some(window) = window {
+        if let Err(err) = backend.focus(window) {
+            log::warn!("Failed to focus window '{}': {}", window.title, err);
+        }
+
+        true
+    } else {
+        false
+    }
+}
+
+pub fn action(button: &UniqueButton) {
+    if let Ok(component) = parse_unique_button_to_component::<FocusWindowComponent>(button) {
+        spawn(move || {
+            if !focus_matching(&component.matcher) {
+                log::info!("No window matches title '{}' and class '{}'", component.matcher.title, component.matcher.class);
+            }
+        });
+    }
+
+    if let Ok(component) = parse_unique_button_to_component::<LaunchOrFocusComponent>(button) {
+        spawn(move || {
+            if focus_matching(&component.matcher) {
+                return;
+            }
+
+            let mut command = component.command.split(" ").filter(|x| !x.is_empty());
+
+            if let Some(program) = command.next() {
+                if let Err(err) = Command::new(program).args(command).spawn() {
+                    log::warn!("Launching of application failed: {}", err);
+                }
+            }
+        });
+    }
+}
+
+/// Window title and class to look for
+#[derive(Serialize, Deserialize, Hash, Default, Debug, Clone)]
+pub struct WindowMatcher {
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub class: String,
+}
+
+#[derive(Serialize, Deserialize, Hash, Default, Debug)]
+pub struct FocusWindowComponent {
+    #[serde(flatten)]
+    pub matcher: WindowMatcher,
+}
+
+impl Component for FocusWindowComponent {
+    const NAME: &'static str = "focus_window";
+}
+
+#[derive(Serialize, Deserialize, Hash, Default, Debug)]
+pub struct LaunchOrFocusComponent {
+    #[serde(flatten)]
+    pub matcher: WindowMatcher,
+    #[serde(default)]
+    pub command: String,
+}
+
+impl Component for LaunchOrFocusComponent {
+    const NAME: &'static str = "launch_or_focus";
+}
@@ -22,9 +22,14 @@ pub fn add_definition(map: &mut HashMap<String, ComponentDefinition>) {
                 padding: 0,
                 offset: (0.0, 0.0),
                 color: (255, 255, 255, 255),
-                shadow: None
+                shadow: None,
+                auto_fit: false,
+                word_wrap: false,
+                marquee_speed: 0.0
             })
-            .build()
+            .build(),
+        requires: vec![],
+        conflicts_with: vec![]
     });
 }
 
@@ -1,5 +1,8 @@
 mod run_command;
 mod key_sequence;
+mod focus_window;
+mod window_backend;
+mod color_picker;
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -9,13 +12,15 @@ use std::time::Duration;
 use enigo::{Enigo, KeyboardControllable};
 use streamduck_core::core::button::{Button, Component};
 use streamduck_core::core::methods::CoreHandle;
-use streamduck_core::modules::components::{ComponentDefinition, UIValue};
+use streamduck_core::modules::components::{ComponentDefinition, FieldError, UIValue};
 use streamduck_core::modules::events::SDCoreEvent;
 use streamduck_core::modules::{ModuleManager, PluginMetadata, SDModule};
 use streamduck_core::util::straight_copy;
 use streamduck_core::versions::{CORE, CORE_EVENTS};
 use crate::key_sequence::{KeyAction, KeySequenceComponent};
 use crate::run_command::RunCommandComponent;
+use crate::focus_window::{FocusWindowComponent, LaunchOrFocusComponent};
+use crate::color_picker::ColorPickerComponent;
 
 pub fn init_module(module_manager: &Arc<ModuleManager>) {
     module_manager.add_module(Arc::new(Box::new(ActionsModule::new() )));
@@ -76,6 +81,8 @@ impl SDModule for ActionsModule {
 
         run_command::add_definition(&mut map);
         key_sequence::add_definition(&mut map);
+        focus_window::add_definition(&mut map);
+        color_picker::add_definition(&mut map);
 
         map
     }
@@ -90,6 +97,18 @@ impl SDModule for ActionsModule {
                 button.insert_component(KeySequenceComponent::default()).ok();
             }
 
+            FocusWindowComponent::NAME => {
+                button.insert_component(FocusWindowComponent::default()).ok();
+            }
+
+            LaunchOrFocusComponent::NAME => {
+                button.insert_component(LaunchOrFocusComponent::default()).ok();
+            }
+
+            ColorPickerComponent::NAME => {
+                button.insert_component(ColorPickerComponent::default()).ok();
+            }
+
             _ => {}
         }
     }
@@ -104,6 +123,18 @@ impl SDModule for ActionsModule {
                 button.remove_component::<KeySequenceComponent>();
             }
 
+            FocusWindowComponent::NAME => {
+                button.remove_component::<FocusWindowComponent>();
+            }
+
+            LaunchOrFocusComponent::NAME => {
+                button.remove_component::<LaunchOrFocusComponent>();
+            }
+
+            ColorPickerComponent::NAME => {
+                button.remove_component::<ColorPickerComponent>();
+            }
+
             _ => {}
         }
     }
@@ -111,6 +142,9 @@ impl SDModule for ActionsModule {
     fn paste_component(&self, _: CoreHandle, reference_button: &Button, new_button: &mut Button) {
         straight_copy(reference_button, new_button, RunCommandComponent::NAME);
         straight_copy(reference_button, new_button, KeySequenceComponent::NAME);
+        straight_copy(reference_button, new_button, FocusWindowComponent::NAME);
+        straight_copy(reference_button, new_button, LaunchOrFocusComponent::NAME);
+        straight_copy(reference_button, new_button, ColorPickerComponent::NAME);
     }
 
     fn component_values(&self, _: CoreHandle, button: &Button, name: &str) -> Vec<UIValue> {
@@ -123,11 +157,15 @@ impl SDModule for ActionsModule {
                 key_sequence::get_values(button)
             }
 
+            FocusWindowComponent::NAME | LaunchOrFocusComponent::NAME => {
+                focus_window::get_values(button)
+            }
+
             _ => vec![],
         }
     }
 
-    fn set_component_value(&self, _: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) {
+    fn set_component_value(&self, _: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
         match name {
             RunCommandComponent::NAME => {
                 run_command::set_values(button, value)
@@ -137,22 +175,33 @@ impl SDModule for ActionsModule {
                 key_sequence::set_values(button, value)
             }
 
+            FocusWindowComponent::NAME | LaunchOrFocusComponent::NAME => {
+                focus_window::set_values(button, value)
+            }
+
             _ => {}
         }
+
+        Ok(())
     }
 
     fn listening_for(&self) -> Vec<String> {
         vec![
             RunCommandComponent::NAME.to_string(),
-            KeySequenceComponent::NAME.to_string()
+            KeySequenceComponent::NAME.to_string(),
+            FocusWindowComponent::NAME.to_string(),
+            LaunchOrFocusComponent::NAME.to_string(),
+            ColorPickerComponent::NAME.to_string()
         ]
     }
 
-    fn event(&self, _: CoreHandle, event: SDCoreEvent) {
+    fn event(&self, core: CoreHandle, event: SDCoreEvent) {
         match event {
             SDCoreEvent::ButtonAction { pressed_button, .. } => {
                 run_command::action(&pressed_button);
                 key_sequence::action(&pressed_button, &self.key_transmitter);
+                focus_window::action(&pressed_button);
+                color_picker::action(&core, &pressed_button);
             }
 
             _ => {}
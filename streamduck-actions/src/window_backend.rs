@@ -0,0 +1,231 @@
+//! Backends that list and focus windows on different platforms
+
+use std::process::Command;
+
+/// Window that can be focused
+#[derive(Debug, Clone)]
+pub struct WindowInfo {
+    /// Backend specific identifier of the window
+    pub id: String,
+    pub title: String,
+    /// Window class on X11 and Windows, app ID on Wayland
+    pub class: String,
+}
+
+impl WindowInfo {
+    /// Checks if window matches title and class, matching is case insensitive and by substring, empty fields match anything
+    pub fn matches(&self, title: &str, class: &str) -> bool {
+        contains_ignore_case(&self.title, title) && contains_ignore_case(&self.class, class)
+    }
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    needle.is_empty() || haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+/// Platform specific way of managing windows
+pub trait WindowBackend {
+    /// Lists windows that are currently open
+    fn windows(&self) -> Result<Vec<WindowInfo>, String>;
+
+    /// Brings window to front and focuses it
+    fn focus(&self, window: &WindowInfo) -> Result<(), String>;
+}
+
+/// Picks backend for current platform and session
+pub fn detect_backend() -> Option<Box<dyn WindowBackend>> {
+    #[cfg(target_os = "windows")]
+    {
+        return Some(Box::new(win32::Win32Backend));
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        if std::env::var_os("SWAYSOCK").is_some() {
+            return Some(Box::new(SwayBackend));
+        }
+
+        if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+            return Some(Box::new(HyprlandBackend));
+        }
+
+        if std::env::var_os("DISPLAY").is_some() {
+            return Some(Box::new(X11Backend));
+        }
+
+        None
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|err| format!("Failed to run {}: {}", program, err))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!("{} returned {}", program, output.status))
+    }
+}
+
+/// X11 backend, uses wmctrl
+pub struct X11Backend;
+
+impl WindowBackend for X11Backend {
+    fn windows(&self) -> Result<Vec<WindowInfo>, String> {
+        let output = run("wmctrl", &["-lx"])?;
+
+        // Lines look like "0x03a00003  0 firefox.Firefox  hostname Title of the window"
+        Ok(output.lines()
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+
+                let id = parts.next()?.to_string();
+                let _desktop = parts.next()?;
+                let class = parts.next()?.to_string();
+                let _host = parts.next()?;
+
+                Some(WindowInfo {
+                    id,
+                    title: parts.collect::<Vec<&str>>().join(" "),
+                    class
+                })
+            })
+            .collect())
+    }
+
+    fn focus(&self, window: &WindowInfo) -> Result<(), String> {
+        run("wmctrl", &["-ia", &window.id]).map(|_| ())
+    }
+}
+
+/// Sway backend, uses swaymsg
+pub struct SwayBackend;
+
+impl SwayBackend {
+    fn collect(node: &serde_json::Value, windows: &mut Vec<WindowInfo>) {
+        if node["pid"].is_number() {
+            if let Some(id) = node["id"].as_u64() {
+                windows.push(WindowInfo {
+                    id: id.to_string(),
+                    title: node["name"].as_str().unwrap_or_default().to_string(),
+                    class: node["app_id"].as_str()
+                        .or(node["window_properties"]["class"].as_str())
+                        .unwrap_or_default()
+                        .to_string()
+                });
+            }
+        }
+
+        for key in ["nodes", "floating_nodes"] {
+            if let Some(children) = node[key].as_array() {
+                for child in children {
+                    Self::collect(child, windows);
+                }
+            }
+        }
+    }
+}
+
+impl WindowBackend for SwayBackend {
+    fn windows(&self) -> Result<Vec<WindowInfo>, String> {
+        let output = run("swaymsg", &["-t", "get_tree"])?;
+        let tree: serde_json::Value = serde_json::from_str(&output).map_err(|err| err.to_string())?;
+
+        let mut windows = vec![];
+        Self::collect(&tree, &mut windows);
+        Ok(windows)
+    }
+
+    fn focus(&self, window: &WindowInfo) -> Result<(), String> {
+        run("swaymsg", &[&format!("[con_id={}]", window.id), "focus"]).map(|_| ())
+    }
+}
+
+/// Hyprland backend, uses hyprctl
+pub struct HyprlandBackend;
+
+impl WindowBackend for HyprlandBackend {
+    fn windows(&self) -> Result<Vec<WindowInfo>, String> {
+        let output = run("hyprctl", &["clients", "-j"])?;
+        let clients: Vec<serde_json::Value> = serde_json::from_str(&output).map_err(|err| err.to_string())?;
+
+        Ok(clients.iter()
+            .filter_map(|client| Some(WindowInfo {
+                id: client["address"].as_str()?.to_string(),
+                title: client["title"].as_str().unwrap_or_default().to_string(),
+                class: client["class"].as_str().unwrap_or_default().to_string()
+            }))
+            .collect())
+    }
+
+    fn focus(&self, window: &WindowInfo) -> Result<(), String> {
+        run("hyprctl", &["dispatch", "focuswindow", &format!("address:{}", window.id)]).map(|_| ())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use windows_sys::Win32::Foundation::{BOOL, HWND, LPARAM};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{EnumWindows, GetClassNameW, GetWindowTextW, IsIconic, IsWindowVisible, SetForegroundWindow, ShowWindow, SW_RESTORE};
+    use super::{WindowBackend, WindowInfo};
+
+    /// Windows backend, uses Win32 API
+    pub struct Win32Backend;
+
+    unsafe extern "system" fn enum_window(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let windows = &mut *(lparam as *mut Vec<WindowInfo>);
+
+        if IsWindowVisible(hwnd) == 0 {
+            return 1;
+        }
+
+        let mut title = [0u16; 512];
+        let title_len = GetWindowTextW(hwnd, title.as_mut_ptr(), title.len() as i32);
+
+        if title_len <= 0 {
+            return 1;
+        }
+
+        let mut class = [0u16; 256];
+        let class_len = GetClassNameW(hwnd, class.as_mut_ptr(), class.len() as i32);
+
+        windows.push(WindowInfo {
+            id: (hwnd as isize).to_string(),
+            title: String::from_utf16_lossy(&title[..title_len as usize]),
+            class: String::from_utf16_lossy(&class[..class_len.max(0) as usize])
+        });
+
+        1
+    }
+
+    impl WindowBackend for Win32Backend {
+        fn windows(&self) -> Result<Vec<WindowInfo>, String> {
+            let mut windows: Vec<WindowInfo> = vec![];
+
+            unsafe {
+                EnumWindows(Some(enum_window), &mut windows as *mut Vec<WindowInfo> as LPARAM);
+            }
+
+            Ok(windows)
+        }
+
+        fn focus(&self, window: &WindowInfo) -> Result<(), String> {
+            let hwnd = window.id.parse::<isize>().map_err(|err| err.to_string())? as HWND;
+
+            unsafe {
+                if IsIconic(hwnd) != 0 {
+                    ShowWindow(hwnd, SW_RESTORE);
+                }
+
+                if SetForegroundWindow(hwnd) == 0 {
+                    return Err("Window refused to be focused".to_string());
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Buffer of recent custom events
+
+use std::collections::VecDeque;
+use std::sync::RwLock;
+use serde::{Deserialize, Serialize};
+use streamduck_core::socket::{parse_packet_to_data, SocketData, SocketHandle, SocketListener, SocketPacket};
+
+/// Maximum amount of events that are kept in history
+pub const HISTORY_CAPACITY: usize = 100;
+
+static HISTORY: RwLock<VecDeque<CustomEvent>> = RwLock::new(VecDeque::new());
+
+/// Custom event sent by external services (chat bots, donation trackers and such) or other plugins
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq)]
+pub struct CustomEvent {
+    /// Name of the event, used for filtering, eg. "donation"
+    pub name: String,
+    /// Text that will be displayed on feed keys
+    pub text: String,
+}
+
+impl SocketData for CustomEvent {
+    const NAME: &'static str = "custom_event";
+}
+
+/// Adds event to history, oldest events get dropped once capacity is reached
+pub fn push_event(event: CustomEvent) {
+    let mut history = HISTORY.write().unwrap();
+
+    history.push_front(event);
+    history.truncate(HISTORY_CAPACITY);
+}
+
+/// Retrieves most recent events that pass the filter, newest first
+pub fn recent_events(filter: &[String], count: usize) -> Vec<CustomEvent> {
+    HISTORY.read().unwrap().iter()
+        .filter(|x| filter.is_empty() || filter.contains(&x.name))
+        .take(count)
+        .cloned()
+        .collect()
+}
+
+/// Socket listener that records custom event packets, doesn't send any response
+pub struct HistoryListener;
+
+impl SocketListener for HistoryListener {
+    fn message(&self, _: SocketHandle, packet: SocketPacket) {
+        if let Ok(event) = parse_packet_to_data::<CustomEvent>(&packet) {
+            log::debug!("Recorded custom event '{}'", event.name);
+            push_event(event);
+        }
+    }
+}
@@ -0,0 +1,320 @@
+pub mod history;
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use streamduck_core::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
+use streamduck_core::core::methods::CoreHandle;
+use streamduck_core::core::UniqueButton;
+use streamduck_core::font::get_font_from_collection;
+use streamduck_core::image::{DynamicImage, GenericImageView};
+use streamduck_core::modules::components::{ComponentDefinition, FieldError, map_ui_values, UIFieldType, UIFieldValue, UIValue};
+use streamduck_core::modules::events::SDCoreEvent;
+use streamduck_core::modules::{ModuleManager, PluginMetadata, SDModule, SDModulePointer};
+use streamduck_core::socket::SocketManager;
+use streamduck_core::thread::rendering::{ButtonBackground, RendererComponentBuilder};
+use streamduck_core::thread::rendering::custom::RenderingManager;
+use streamduck_core::thread::util::{calculate_bounds_for_text, render_aligned_text_on_image, TextAlignment};
+use streamduck_core::util::rusttype::Scale;
+use streamduck_core::util::straight_copy;
+use streamduck_core::versions::{CORE, CORE_EVENTS, PLUGIN_API, RENDERING, SDMODULE_TRAIT, SOCKET_API};
+use streamduck_core_derive::component;
+use crate::history::{CustomEvent, HistoryListener, recent_events};
+
+/// Speed of text scrolling in pixels per second
+const SCROLL_SPEED: u128 = 30;
+
+#[no_mangle]
+pub fn get_metadata() -> PluginMetadata {
+    PluginMetadata::from_literals(
+        "activity",
+        "TheJebForge",
+        "Shows recent custom events like donations or chat commands on a key",
+        "0.1",
+        &[
+            PLUGIN_API,
+            SDMODULE_TRAIT,
+            CORE,
+            CORE_EVENTS,
+            RENDERING,
+            SOCKET_API
+        ]
+    )
+}
+
+#[no_mangle]
+pub fn get_module() -> SDModulePointer {
+    Box::into_raw(Box::new(ActivityModule))
+}
+
+#[no_mangle]
+pub fn register(socket_manager: Arc<SocketManager>, _: Arc<RenderingManager>, _: Arc<ModuleManager>) {
+    socket_manager.add_listener(Box::new(HistoryListener));
+}
+
+fn millis_now() -> u128 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_millis()).unwrap_or(0)
+}
+
+pub struct ActivityModule;
+
+impl ActivityModule {
+    /// Picks events to display and index of the one that should be currently shown
+    fn current_entry(component: &ActivityFeedComponent) -> Option<(usize, Vec<CustomEvent>)> {
+        let events = recent_events(&component.filter_list(), component.count.max(1) as usize);
+
+        if events.is_empty() {
+            return None;
+        }
+
+        let rotation = (millis_now() / (component.interval.max(1) as u128 * 1000)) as usize;
+        Some(((rotation + component.offset) % events.len(), events))
+    }
+
+    /// Horizontal offset of scrolling text, [None] if text fits the key
+    fn scroll_offset(text_width: u32, frame_width: u32) -> Option<f32> {
+        if text_width + 8 <= frame_width {
+            return None;
+        }
+
+        let distance = (text_width + frame_width) as u128;
+        let travelled = (millis_now() * SCROLL_SPEED / 1000) % distance;
+
+        Some(frame_width as f32 - travelled as f32)
+    }
+}
+
+impl SDModule for ActivityModule {
+    fn name(&self) -> String {
+        "activity".to_string()
+    }
+
+    fn components(&self) -> HashMap<String, ComponentDefinition> {
+        let mut map = HashMap::new();
+
+        map.insert(ActivityFeedComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Activity Feed".to_string(),
+            description: "Rotates through recent custom events, press to show next one".to_string(),
+            default_looks: RendererComponentBuilder::new()
+                .background(ButtonBackground::Solid((25, 25, 35, 255)))
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map
+    }
+
+    fn add_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            ActivityFeedComponent::NAME => {
+                button.insert_component(ActivityFeedComponent::default()).ok();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn remove_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            ActivityFeedComponent::NAME => {
+                button.remove_component::<ActivityFeedComponent>();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn paste_component(&self, _: CoreHandle, reference_button: &Button, new_button: &mut Button) {
+        straight_copy(reference_button, new_button, ActivityFeedComponent::NAME);
+    }
+
+    fn component_values(&self, _: CoreHandle, button: &Button, name: &str) -> Vec<UIValue> {
+        match name {
+            ActivityFeedComponent::NAME => {
+                if let Ok(component) = parse_button_to_component::<ActivityFeedComponent>(button) {
+                    vec![
+                        UIValue {
+                            name: "filter".to_string(),
+                            display_name: "Event Filter".to_string(),
+                            description: "Comma separated names of events to show, shows all events if empty".to_string(),
+                            ty: UIFieldType::InputFieldString,
+                            value: UIFieldValue::InputFieldString(component.filter)
+                        },
+                        UIValue {
+                            name: "count".to_string(),
+                            display_name: "Event Count".to_string(),
+                            description: "How many of the most recent events to rotate through".to_string(),
+                            ty: UIFieldType::InputFieldUnsignedInteger,
+                            value: UIFieldValue::InputFieldUnsignedInteger(component.count)
+                        },
+                        UIValue {
+                            name: "interval".to_string(),
+                            display_name: "Rotation Interval".to_string(),
+                            description: "Amount of seconds each event is shown for".to_string(),
+                            ty: UIFieldType::InputFieldUnsignedInteger,
+                            value: UIFieldValue::InputFieldUnsignedInteger(component.interval)
+                        },
+                    ]
+                } else {
+                    vec![]
+                }
+            }
+
+            _ => vec![],
+        }
+    }
+
+    fn set_component_value(&self, _: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+        match name {
+            ActivityFeedComponent::NAME => {
+                if let Ok(mut component) = parse_button_to_component::<ActivityFeedComponent>(button) {
+                    let change_map = map_ui_values(value);
+
+                    if let Some(value) = change_map.get("filter") {
+                        if let Ok(filter) = value.value.try_into_string() {
+                            component.filter = filter;
+                        }
+                    }
+
+                    if let Some(value) = change_map.get("count") {
+                        if let Ok(count) = value.value.try_into_u32() {
+                            component.count = count.max(1);
+                        }
+                    }
+
+                    if let Some(value) = change_map.get("interval") {
+                        if let Ok(interval) = value.value.try_into_u32() {
+                            component.interval = interval.max(1);
+                        }
+                    }
+
+                    button.insert_component(component).ok();
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn listening_for(&self) -> Vec<String> {
+        vec![ActivityFeedComponent::NAME.to_string()]
+    }
+
+    fn event(&self, _: CoreHandle, event: SDCoreEvent) {
+        match event {
+            SDCoreEvent::ButtonAction { pressed_button, .. } => {
+                if let Ok(mut component) = parse_unique_button_to_component::<ActivityFeedComponent>(&pressed_button) {
+                    component.offset = component.offset.wrapping_add(1);
+                    pressed_button.write().unwrap().insert_component(component).ok();
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn render(&self, core: CoreHandle, button: &UniqueButton, frame: &mut DynamicImage) {
+        if let Ok(component) = parse_unique_button_to_component::<ActivityFeedComponent>(button) {
+            let size = core.core().image_size;
+            let font = if let Some(font) = get_font_from_collection("default") { font } else { return; };
+
+            if let Some((index, events)) = Self::current_entry(&component) {
+                let event = &events[index];
+                let scale = Scale::uniform(18.0);
+
+                render_aligned_text_on_image(size, frame, font.as_ref(), &event.name, Scale::uniform(11.0), TextAlignment::TopCenter, 4, (0.0, 0.0), (160, 160, 200, 255));
+
+                let (text_width, _) = calculate_bounds_for_text(font.as_ref(), &event.text, scale);
+                if let Some(offset) = Self::scroll_offset(text_width, frame.width()) {
+                    render_aligned_text_on_image(size, frame, font.as_ref(), &event.text, scale, TextAlignment::MiddleLeft, 0, (offset, 0.0), (255, 255, 255, 255));
+                } else {
+                    render_aligned_text_on_image(size, frame, font.as_ref(), &event.text, scale, TextAlignment::Center, 0, (0.0, 0.0), (255, 255, 255, 255));
+                }
+
+                let position = format!("{}/{}", index + 1, events.len());
+                render_aligned_text_on_image(size, frame, font.as_ref(), &position, Scale::uniform(11.0), TextAlignment::BottomCenter, 4, (0.0, 0.0), (160, 160, 160, 255));
+            } else {
+                render_aligned_text_on_image(size, frame, font.as_ref(), "No activity", Scale::uniform(12.0), TextAlignment::Center, 0, (0.0, 0.0), (130, 130, 130, 255));
+            }
+        }
+    }
+
+    fn render_hash(&self, core: CoreHandle, button: &UniqueButton, hash: &mut Box<dyn Hasher>) {
+        if let Ok(component) = parse_unique_button_to_component::<ActivityFeedComponent>(button) {
+            if let Some((index, events)) = Self::current_entry(&component) {
+                index.hash(hash);
+                events.hash(hash);
+
+                // Text only moves while it doesn't fit the key
+                if let Some(font) = get_font_from_collection("default") {
+                    let (text_width, _) = calculate_bounds_for_text(font.as_ref(), &events[index].text, Scale::uniform(18.0));
+
+                    if let Some(offset) = Self::scroll_offset(text_width, core.core().image_size.0 as u32) {
+                        (offset as i32).hash(hash);
+                    }
+                }
+            } else {
+                0.hash(hash);
+            }
+        }
+    }
+
+    fn refresh_rate(&self, core: CoreHandle, button: &UniqueButton) -> Option<f32> {
+        let component = parse_unique_button_to_component::<ActivityFeedComponent>(button).ok()?;
+        let (index, events) = Self::current_entry(&component)?;
+        let font = get_font_from_collection("default")?;
+
+        // Scrolling text moves a pixel per frame
+        let (text_width, _) = calculate_bounds_for_text(font.as_ref(), &events[index].text, Scale::uniform(18.0));
+        Self::scroll_offset(text_width, core.core().image_size.0 as u32).map(|_| SCROLL_SPEED as f32)
+    }
+
+    fn metadata(&self) -> PluginMetadata {
+        get_metadata()
+    }
+}
+
+/// Component that shows recent custom events
+#[component("activity_feed")]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ActivityFeedComponent {
+    /// Comma separated list of event names
+    #[serde(default)]
+    pub filter: String,
+    #[serde(default = "default_count")]
+    pub count: u32,
+    /// Seconds between rotations
+    #[serde(default = "default_interval")]
+    pub interval: u32,
+    /// Amount of entries skipped by pressing the key
+    #[serde(default)]
+    pub offset: usize,
+}
+
+fn default_count() -> u32 { 5 }
+fn default_interval() -> u32 { 5 }
+
+impl Default for ActivityFeedComponent {
+    fn default() -> Self {
+        Self {
+            filter: "".to_string(),
+            count: default_count(),
+            interval: default_interval(),
+            offset: 0
+        }
+    }
+}
+
+impl ActivityFeedComponent {
+    fn filter_list(&self) -> Vec<String> {
+        self.filter.split(',')
+            .map(|x| x.trim().to_string())
+            .filter(|x| !x.is_empty())
+            .collect()
+    }
+}
@@ -0,0 +1,132 @@
+//! Broadcasting of busy state to external services
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use serde_json::json;
+use crate::BusySettings;
+
+/// Timeout for any network operation made while broadcasting
+const BROADCAST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Sends busy state to webhook and MQTT broker, if they're configured
+pub fn broadcast(settings: &BusySettings, client_id: &str) {
+    if !settings.webhook_url.is_empty() {
+        let result = ureq::post(&settings.webhook_url)
+            .timeout(BROADCAST_TIMEOUT)
+            .send_json(json!({ "busy": settings.busy }));
+
+        if let Err(err) = result {
+            log::warn!("Failed to send busy state to webhook: {}", err);
+        }
+    }
+
+    if !settings.mqtt_host.is_empty() {
+        let payload = if settings.busy { "busy" } else { "free" };
+
+        if let Err(err) = mqtt_publish(settings, client_id, payload.as_bytes()) {
+            log::warn!("Failed to publish busy state to MQTT broker: {}", err);
+        }
+    }
+}
+
+/// Encodes remaining length of MQTT packet
+fn encode_length(mut length: usize, buffer: &mut Vec<u8>) {
+    loop {
+        let mut byte = (length % 128) as u8;
+        length /= 128;
+
+        if length > 0 {
+            byte |= 0x80;
+        }
+
+        buffer.push(byte);
+
+        if length == 0 {
+            break;
+        }
+    }
+}
+
+/// Encodes UTF-8 string as MQTT string
+fn encode_string(str: &str, buffer: &mut Vec<u8>) {
+    buffer.extend((str.len() as u16).to_be_bytes());
+    buffer.extend(str.as_bytes());
+}
+
+/// Connects to first reachable address of the host, giving each address [BROADCAST_TIMEOUT] to respond
+fn connect(host: &str, port: u16) -> std::io::Result<TcpStream> {
+    let mut last_error = std::io::Error::new(std::io::ErrorKind::NotFound, "host didn't resolve to any address");
+
+    for address in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&address, BROADCAST_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_error = err
+        }
+    }
+
+    Err(last_error)
+}
+
+/// Publishes a retained message with QoS 0 using MQTT 3.1.1, connection is closed right after
+fn mqtt_publish(settings: &BusySettings, client_id: &str, payload: &[u8]) -> std::io::Result<()> {
+    let mut stream = connect(&settings.mqtt_host, settings.mqtt_port)?;
+    stream.set_read_timeout(Some(BROADCAST_TIMEOUT))?;
+    stream.set_write_timeout(Some(BROADCAST_TIMEOUT))?;
+
+    // CONNECT
+    let mut variable = vec![];
+    encode_string("MQTT", &mut variable);
+    variable.push(4); // Protocol level 3.1.1
+
+    // Password can only be sent along with username
+    let has_username = !settings.mqtt_username.is_empty();
+    let has_password = has_username && !settings.mqtt_password.is_empty();
+
+    let mut flags = 0x02; // Clean session
+    if has_username {
+        flags |= 0x80;
+    }
+    if has_password {
+        flags |= 0x40;
+    }
+    variable.push(flags);
+
+    variable.extend(0u16.to_be_bytes()); // No keep alive
+    encode_string(client_id, &mut variable);
+
+    if has_username {
+        encode_string(&settings.mqtt_username, &mut variable);
+    }
+    if has_password {
+        encode_string(&settings.mqtt_password, &mut variable);
+    }
+
+    let mut packet = vec![0x10];
+    encode_length(variable.len(), &mut packet);
+    packet.extend(variable);
+    stream.write_all(&packet)?;
+
+    // CONNACK
+    let mut connack = [0u8; 4];
+    stream.read_exact(&mut connack)?;
+
+    if connack[0] != 0x20 || connack[3] != 0 {
+        return Err(std::io::Error::new(std::io::ErrorKind::ConnectionRefused, format!("broker refused connection with code {}", connack[3])));
+    }
+
+    // PUBLISH, retained so new subscribers get current state
+    let mut variable = vec![];
+    encode_string(&settings.mqtt_topic, &mut variable);
+    variable.extend(payload);
+
+    let mut packet = vec![0x31];
+    encode_length(variable.len(), &mut packet);
+    packet.extend(variable);
+    stream.write_all(&packet)?;
+
+    // DISCONNECT
+    stream.write_all(&[0xE0, 0x00])?;
+
+    Ok(())
+}
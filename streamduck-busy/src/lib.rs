@@ -0,0 +1,427 @@
+mod broadcast;
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+use std::thread::spawn;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use streamduck_core::config::Config;
+use streamduck_core::core::button::{Button, Component, parse_unique_button_to_component};
+use streamduck_core::core::manager::CoreManager;
+use streamduck_core::core::methods::CoreHandle;
+use streamduck_core::core::UniqueButton;
+use streamduck_core::image::{DynamicImage, GenericImage, GenericImageView, Rgba};
+use streamduck_core::modules::components::{ComponentDefinition, FieldError, map_ui_values, UIFieldType, UIFieldValue, UIValue};
+use streamduck_core::modules::events::SDCoreEvent;
+use streamduck_core::modules::{ModuleManager, PluginMetadata, SDModule, SDModulePointer};
+use streamduck_core::socket::{send_socket_message, SocketManager, SocketPacket};
+use streamduck_core::thread::rendering::{ButtonBackground, ButtonText, Color, RendererComponentBuilder};
+use streamduck_core::thread::rendering::custom::RenderingManager;
+use streamduck_core::thread::util::TextAlignment;
+use streamduck_core::util::straight_copy;
+use streamduck_core::versions::{CONFIG, CORE, CORE_EVENTS, PLUGIN_API, RENDERING, SDMODULE_TRAIT, SOCKET_API};
+use streamduck_core_derive::{component, plugin_config};
+
+#[no_mangle]
+pub fn get_metadata() -> PluginMetadata {
+    PluginMetadata::from_literals(
+        "busy",
+        "TheJebForge",
+        "Global busy status that recolors keys and gets broadcast to other services",
+        "0.1",
+        &[
+            PLUGIN_API,
+            SDMODULE_TRAIT,
+            CORE,
+            CONFIG,
+            CORE_EVENTS,
+            RENDERING,
+            SOCKET_API
+        ]
+    )
+}
+
+#[no_mangle]
+pub fn get_module() -> SDModulePointer {
+    Box::into_raw(Box::new(BusyModule::default()))
+}
+
+#[no_mangle]
+pub fn register(_: Arc<SocketManager>, _: Arc<RenderingManager>, _: Arc<ModuleManager>) {}
+
+/// Settings of busy module, also keeps the busy state itself so it survives restarts
+#[plugin_config("busy")]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BusySettings {
+    #[serde(default)]
+    pub busy: bool,
+    #[serde(default = "default_busy_color")]
+    pub busy_color: Color,
+    /// URL that will receive POST request with busy state
+    #[serde(default)]
+    pub webhook_url: String,
+    #[serde(default)]
+    pub mqtt_host: String,
+    #[serde(default = "default_mqtt_port")]
+    pub mqtt_port: u16,
+    #[serde(default = "default_mqtt_topic")]
+    pub mqtt_topic: String,
+    /// Username for MQTT broker, not sent if empty
+    #[serde(default)]
+    pub mqtt_username: String,
+    /// Password for MQTT broker, not sent if empty
+    #[serde(default)]
+    pub mqtt_password: String,
+}
+
+fn default_busy_color() -> Color { (220, 30, 30, 255) }
+fn default_mqtt_port() -> u16 { 1883 }
+fn default_mqtt_topic() -> String { "streamduck/busy".to_string() }
+
+impl Default for BusySettings {
+    fn default() -> Self {
+        Self {
+            busy: false,
+            busy_color: default_busy_color(),
+            webhook_url: "".to_string(),
+            mqtt_host: "".to_string(),
+            mqtt_port: default_mqtt_port(),
+            mqtt_topic: default_mqtt_topic(),
+            mqtt_username: "".to_string(),
+            mqtt_password: "".to_string()
+        }
+    }
+}
+
+/// Name of the packet that's sent to socket clients when busy state changes
+pub const BUSY_STATUS_PACKET: &'static str = "busy_status";
+
+pub struct BusyModule {
+    /// Cached settings, so rendering doesn't have to parse config every frame
+    settings: RwLock<Option<BusySettings>>,
+    /// MQTT client id, unique per instance so multiple daemons don't kick each other off the broker
+    client_id: String,
+}
+
+impl Default for BusyModule {
+    fn default() -> Self {
+        let suffix: String = thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(8)
+            .map(char::from)
+            .collect();
+
+        Self {
+            settings: Default::default(),
+            client_id: format!("streamduck-busy-{}", suffix)
+        }
+    }
+}
+
+impl BusyModule {
+    fn get_settings(&self, config: &Arc<Config>) -> BusySettings {
+        if let Some(settings) = self.settings.read().unwrap().as_ref() {
+            return settings.clone();
+        }
+
+        let settings: BusySettings = config.get_plugin_settings().unwrap_or_default();
+        *self.settings.write().unwrap() = Some(settings.clone());
+        settings
+    }
+
+    fn save_settings(&self, config: &Arc<Config>, settings: BusySettings) {
+        *self.settings.write().unwrap() = Some(settings.clone());
+        config.set_plugin_settings(settings);
+    }
+
+    /// Sets busy state, saves it and broadcasts it everywhere
+    fn set_busy(&self, config: &Arc<Config>, socket_manager: &Arc<SocketManager>, busy: bool) {
+        let mut settings = self.get_settings(config);
+        settings.busy = busy;
+        self.save_settings(config, settings.clone());
+
+        send_socket_message(socket_manager, SocketPacket {
+            ty: BUSY_STATUS_PACKET.to_string(),
+            requester: None,
+            data: Some(json!({ "busy": busy }))
+        });
+
+        let client_id = self.client_id.clone();
+        spawn(move || broadcast::broadcast(&settings, &client_id));
+    }
+}
+
+impl SDModule for BusyModule {
+    fn name(&self) -> String {
+        "busy".to_string()
+    }
+
+    fn components(&self) -> HashMap<String, ComponentDefinition> {
+        let mut map = HashMap::new();
+
+        map.insert(BusyToggleComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Busy Toggle".to_string(),
+            description: "Toggles global busy status".to_string(),
+            default_looks: RendererComponentBuilder::new()
+                .background(ButtonBackground::Solid((40, 40, 40, 255)))
+                .add_text(ButtonText {
+                    text: "ON AIR".to_string(),
+                    font: "default".to_string(),
+                    scale: (16.0, 16.0),
+                    alignment: TextAlignment::Center,
+                    padding: 0,
+                    offset: (0.0, 0.0),
+                    color: (255, 255, 255, 255),
+                    shadow: None,
+                    auto_fit: false,
+                    word_wrap: false,
+                    marquee_speed: 0.0
+                })
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map.insert(BusyIndicatorComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Busy Indicator".to_string(),
+            description: "Recolors the key while busy status is on".to_string(),
+            default_looks: RendererComponentBuilder::new()
+                .background(ButtonBackground::Solid((40, 40, 40, 255)))
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map
+    }
+
+    fn add_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            BusyToggleComponent::NAME => {
+                button.insert_component(BusyToggleComponent::default()).ok();
+            }
+
+            BusyIndicatorComponent::NAME => {
+                button.insert_component(BusyIndicatorComponent::default()).ok();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn remove_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            BusyToggleComponent::NAME => {
+                button.remove_component::<BusyToggleComponent>();
+            }
+
+            BusyIndicatorComponent::NAME => {
+                button.remove_component::<BusyIndicatorComponent>();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn paste_component(&self, _: CoreHandle, reference_button: &Button, new_button: &mut Button) {
+        straight_copy(reference_button, new_button, BusyToggleComponent::NAME);
+        straight_copy(reference_button, new_button, BusyIndicatorComponent::NAME);
+    }
+
+    fn component_values(&self, _: CoreHandle, _: &Button, _: &str) -> Vec<UIValue> {
+        vec![]
+    }
+
+    fn set_component_value(&self, _: CoreHandle, _: &mut Button, _: &str, _: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+        Ok(())
+    }
+
+    fn listening_for(&self) -> Vec<String> {
+        vec![
+            BusyToggleComponent::NAME.to_string(),
+            BusyIndicatorComponent::NAME.to_string()
+        ]
+    }
+
+    fn settings(&self, core_manager: Arc<CoreManager>) -> Vec<UIValue> {
+        let settings = self.get_settings(&core_manager.config);
+
+        vec![
+            UIValue {
+                name: "busy".to_string(),
+                display_name: "Busy".to_string(),
+                description: "Current busy status".to_string(),
+                ty: UIFieldType::Checkbox { disabled: false },
+                value: UIFieldValue::Checkbox(settings.busy)
+            },
+            UIValue {
+                name: "busy_color".to_string(),
+                display_name: "Busy Color".to_string(),
+                description: "Color of indicator keys while busy".to_string(),
+                ty: UIFieldType::Color,
+                value: settings.busy_color.into()
+            },
+            UIValue {
+                name: "webhook_url".to_string(),
+                display_name: "Webhook URL".to_string(),
+                description: "URL that receives POST request with busy state on every change, disabled if empty".to_string(),
+                ty: UIFieldType::InputFieldString,
+                value: UIFieldValue::InputFieldString(settings.webhook_url)
+            },
+            UIValue {
+                name: "mqtt_host".to_string(),
+                display_name: "MQTT Host".to_string(),
+                description: "MQTT broker to publish busy state to, disabled if empty".to_string(),
+                ty: UIFieldType::InputFieldString,
+                value: UIFieldValue::InputFieldString(settings.mqtt_host)
+            },
+            UIValue {
+                name: "mqtt_port".to_string(),
+                display_name: "MQTT Port".to_string(),
+                description: "Port of MQTT broker".to_string(),
+                ty: UIFieldType::InputFieldUnsignedInteger,
+                value: UIFieldValue::InputFieldUnsignedInteger(settings.mqtt_port as u32)
+            },
+            UIValue {
+                name: "mqtt_topic".to_string(),
+                display_name: "MQTT Topic".to_string(),
+                description: "Topic that will receive retained \"busy\" or \"free\" message".to_string(),
+                ty: UIFieldType::InputFieldString,
+                value: UIFieldValue::InputFieldString(settings.mqtt_topic)
+            },
+            UIValue {
+                name: "mqtt_username".to_string(),
+                display_name: "MQTT Username".to_string(),
+                description: "Username for MQTT broker, optional".to_string(),
+                ty: UIFieldType::InputFieldString,
+                value: UIFieldValue::InputFieldString(settings.mqtt_username)
+            },
+            UIValue {
+                name: "mqtt_password".to_string(),
+                display_name: "MQTT Password".to_string(),
+                description: "Password for MQTT broker, only sent along with username".to_string(),
+                ty: UIFieldType::InputFieldString,
+                value: UIFieldValue::InputFieldString(settings.mqtt_password)
+            },
+        ]
+    }
+
+    fn set_setting(&self, core_manager: Arc<CoreManager>, value: Vec<UIValue>) {
+        let mut settings = self.get_settings(&core_manager.config);
+        let change_map = map_ui_values(value);
+
+        let previous_busy = settings.busy;
+        if let Some(value) = change_map.get("busy") {
+            if let Ok(busy) = value.value.try_into_bool() {
+                settings.busy = busy;
+            }
+        }
+
+        if let Some(value) = change_map.get("busy_color") {
+            if let Ok(color) = value.value.try_into_color() {
+                settings.busy_color = color;
+            }
+        }
+
+        if let Some(value) = change_map.get("webhook_url") {
+            if let Ok(url) = value.value.try_into_string() {
+                settings.webhook_url = url;
+            }
+        }
+
+        if let Some(value) = change_map.get("mqtt_host") {
+            if let Ok(host) = value.value.try_into_string() {
+                settings.mqtt_host = host;
+            }
+        }
+
+        if let Some(value) = change_map.get("mqtt_port") {
+            if let Ok(port) = value.value.try_into_u32() {
+                settings.mqtt_port = port as u16;
+            }
+        }
+
+        if let Some(value) = change_map.get("mqtt_topic") {
+            if let Ok(topic) = value.value.try_into_string() {
+                settings.mqtt_topic = topic;
+            }
+        }
+
+        if let Some(value) = change_map.get("mqtt_username") {
+            if let Ok(username) = value.value.try_into_string() {
+                settings.mqtt_username = username;
+            }
+        }
+
+        if let Some(value) = change_map.get("mqtt_password") {
+            if let Ok(password) = value.value.try_into_string() {
+                settings.mqtt_password = password;
+            }
+        }
+
+        let busy = settings.busy;
+        self.save_settings(&core_manager.config, settings);
+
+        if busy != previous_busy {
+            self.set_busy(&core_manager.config, &core_manager.socket_manager, busy);
+        }
+    }
+
+    fn event(&self, core: CoreHandle, event: SDCoreEvent) {
+        match event {
+            SDCoreEvent::ButtonAction { pressed_button, .. } => {
+                if let Ok(_) = parse_unique_button_to_component::<BusyToggleComponent>(&pressed_button) {
+                    let config = core.config();
+                    let busy = self.get_settings(&config).busy;
+                    self.set_busy(&config, &core.core().socket_manager, !busy);
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn render(&self, core: CoreHandle, button: &UniqueButton, frame: &mut DynamicImage) {
+        let is_indicator = parse_unique_button_to_component::<BusyIndicatorComponent>(button).is_ok()
+            || parse_unique_button_to_component::<BusyToggleComponent>(button).is_ok();
+
+        if is_indicator {
+            let settings = self.get_settings(&core.config());
+
+            if settings.busy {
+                let (width, height) = frame.dimensions();
+                let color = settings.busy_color;
+
+                for x in 0..width {
+                    for y in 0..height {
+                        frame.put_pixel(x, y, Rgba([color.0, color.1, color.2, 255]));
+                    }
+                }
+            }
+        }
+    }
+
+    fn render_hash(&self, core: CoreHandle, _: &UniqueButton, hash: &mut Box<dyn Hasher>) {
+        let settings = self.get_settings(&core.config());
+
+        settings.busy.hash(hash);
+        settings.busy_color.hash(hash);
+    }
+
+    fn metadata(&self) -> PluginMetadata {
+        get_metadata()
+    }
+}
+
+/// Component that toggles busy state on press, also acts as an indicator
+#[component("busy_toggle")]
+#[derive(Serialize, Deserialize, Default)]
+pub struct BusyToggleComponent {}
+
+/// Component that recolors the key while busy
+#[component("busy_indicator")]
+#[derive(Serialize, Deserialize, Default)]
+pub struct BusyIndicatorComponent {}
@@ -0,0 +1,384 @@
+//! Minimal clients for Jenkins, GitHub Actions and GitLab CI APIs
+
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Timeout for any request made to CI server
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default address of GitHub API
+const GITHUB_API: &'static str = "https://api.github.com";
+
+/// Default address of GitLab instance
+const GITLAB_URL: &'static str = "https://gitlab.com";
+
+/// CI service that runs the pipeline
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum CiProvider {
+    GitHubActions,
+    Jenkins,
+}
+
+impl Default for CiProvider {
+    fn default() -> Self {
+        CiProvider::GitHubActions
+    }
+}
+
+impl CiProvider {
+    pub const VARIANTS: [&'static str; 2] = ["GitHub Actions", "Jenkins"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CiProvider::GitHubActions => "GitHub Actions",
+            CiProvider::Jenkins => "Jenkins",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<CiProvider> {
+        match name {
+            "GitHub Actions" => Some(CiProvider::GitHubActions),
+            "Jenkins" => Some(CiProvider::Jenkins),
+            _ => None
+        }
+    }
+}
+
+/// Pipeline definition
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Hash, PartialEq, Eq)]
+pub struct CiPipeline {
+    pub provider: CiProvider,
+    /// Jenkins server address, or GitHub API address for GitHub Enterprise, default API is used if empty
+    #[serde(default)]
+    pub url: String,
+    /// Repository in "owner/name" format for GitHub, ignored by Jenkins
+    #[serde(default)]
+    pub repository: String,
+    /// Workflow file name or ID for GitHub, job name for Jenkins
+    #[serde(default)]
+    pub pipeline: String,
+    /// Git ref to dispatch the workflow on, ignored by Jenkins
+    #[serde(default)]
+    pub git_ref: String,
+    /// Username for Jenkins, ignored by GitHub
+    #[serde(default)]
+    pub user: String,
+    /// Personal access token for GitHub or API token for Jenkins
+    #[serde(default)]
+    pub token: String,
+}
+
+/// Service that hosts the repository, used for branch status
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum RepoProvider {
+    GitHub,
+    GitLab,
+}
+
+impl Default for RepoProvider {
+    fn default() -> Self {
+        RepoProvider::GitHub
+    }
+}
+
+impl RepoProvider {
+    pub const VARIANTS: [&'static str; 2] = ["GitHub", "GitLab"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RepoProvider::GitHub => "GitHub",
+            RepoProvider::GitLab => "GitLab",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<RepoProvider> {
+        match name {
+            "GitHub" => Some(RepoProvider::GitHub),
+            "GitLab" => Some(RepoProvider::GitLab),
+            _ => None
+        }
+    }
+}
+
+/// Branch of a repository to show latest pipeline status of
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Hash, PartialEq, Eq)]
+pub struct CiBranch {
+    pub provider: RepoProvider,
+    /// GitHub Enterprise API address or self-hosted GitLab address, default is used if empty
+    #[serde(default)]
+    pub url: String,
+    /// Repository in "owner/name" format, or full project path for GitLab
+    #[serde(default)]
+    pub repository: String,
+    /// Branch name, runs of all branches are considered if empty
+    #[serde(default)]
+    pub branch: String,
+}
+
+/// Latest run of a branch
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatestRun {
+    pub status: RunStatus,
+    /// Web page of the run
+    pub url: String,
+}
+
+/// Status of the latest run of a pipeline
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum RunStatus {
+    Queued,
+    Running,
+    Success,
+    Failure,
+    Cancelled,
+    Unknown,
+}
+
+impl RunStatus {
+    /// Color that represents the status on a key
+    pub fn color(&self) -> (u8, u8, u8, u8) {
+        match self {
+            RunStatus::Queued => (150, 150, 150, 255),
+            RunStatus::Running => (230, 180, 40, 255),
+            RunStatus::Success => (60, 180, 75, 255),
+            RunStatus::Failure => (210, 50, 50, 255),
+            RunStatus::Cancelled => (110, 110, 110, 255),
+            RunStatus::Unknown => (70, 70, 70, 255),
+        }
+    }
+
+    /// Short label of the status
+    pub fn label(&self) -> &'static str {
+        match self {
+            RunStatus::Queued => "Queued",
+            RunStatus::Running => "Running",
+            RunStatus::Success => "Success",
+            RunStatus::Failure => "Failed",
+            RunStatus::Cancelled => "Cancelled",
+            RunStatus::Unknown => "Unknown",
+        }
+    }
+}
+
+/// Error that might occur while talking to CI server
+#[derive(Debug)]
+pub enum CiError {
+    /// Server refused the request due to rate limiting, contains amount of time to wait before next request
+    RateLimited(Duration),
+    RequestError(String),
+    MalformedResponse,
+}
+
+impl From<ureq::Error> for CiError {
+    fn from(err: ureq::Error) -> Self {
+        if let ureq::Error::Status(code, response) = &err {
+            if *code == 403 || *code == 429 {
+                if let Some(wait) = rate_limit_wait(response) {
+                    return CiError::RateLimited(wait);
+                }
+            }
+        }
+
+        CiError::RequestError(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for CiError {
+    fn from(err: std::io::Error) -> Self {
+        CiError::RequestError(err.to_string())
+    }
+}
+
+/// Reads how long should client wait from rate limit headers of the response
+fn rate_limit_wait(response: &ureq::Response) -> Option<Duration> {
+    if let Some(retry_after) = response.header("retry-after").and_then(|x| x.parse::<u64>().ok()) {
+        return Some(Duration::from_secs(retry_after));
+    }
+
+    if response.header("x-ratelimit-remaining") == Some("0") {
+        if let Some(reset) = response.header("x-ratelimit-reset").and_then(|x| x.parse::<u64>().ok()) {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|x| x.as_secs())
+                .unwrap_or(0);
+
+            return Some(Duration::from_secs(reset.saturating_sub(now).max(1)));
+        }
+    }
+
+    None
+}
+
+/// Reads status of GitHub workflow run
+fn github_status(run: &Value) -> RunStatus {
+    match (run["status"].as_str(), run["conclusion"].as_str()) {
+        (Some("completed"), Some("success")) => RunStatus::Success,
+        (Some("completed"), Some("cancelled")) | (Some("completed"), Some("skipped")) => RunStatus::Cancelled,
+        (Some("completed"), _) => RunStatus::Failure,
+        (Some("in_progress"), _) => RunStatus::Running,
+        (Some("queued"), _) | (Some("waiting"), _) | (Some("requested"), _) | (Some("pending"), _) => RunStatus::Queued,
+        _ => RunStatus::Unknown
+    }
+}
+
+impl CiPipeline {
+    fn api_url(&self) -> &str {
+        if self.url.is_empty() {
+            GITHUB_API
+        } else {
+            self.url.trim_end_matches('/')
+        }
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        let request = ureq::request(method, url).timeout(REQUEST_TIMEOUT);
+
+        match self.provider {
+            CiProvider::GitHubActions => {
+                let request = request.set("Accept", "application/vnd.github+json");
+
+                if self.token.is_empty() {
+                    request
+                } else {
+                    request.set("Authorization", &format!("Bearer {}", self.token))
+                }
+            }
+
+            CiProvider::Jenkins => {
+                if self.user.is_empty() {
+                    request
+                } else {
+                    request.set("Authorization", &format!("Basic {}", base64::encode(format!("{}:{}", self.user, self.token))))
+                }
+            }
+        }
+    }
+
+    /// Triggers a new run of the pipeline
+    pub fn trigger(&self) -> Result<(), CiError> {
+        match self.provider {
+            CiProvider::GitHubActions => {
+                let git_ref = if self.git_ref.is_empty() { "main" } else { &self.git_ref };
+
+                self.request("POST", &format!("{}/repos/{}/actions/workflows/{}/dispatches", self.api_url(), self.repository, self.pipeline))
+                    .send_json(json!({ "ref": git_ref }))?;
+            }
+
+            CiProvider::Jenkins => {
+                self.request("POST", &format!("{}/job/{}/build", self.url.trim_end_matches('/'), self.pipeline))
+                    .call()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Retrieves status of the latest run of the pipeline
+    pub fn latest_status(&self) -> Result<RunStatus, CiError> {
+        match self.provider {
+            CiProvider::GitHubActions => {
+                let response: Value = self.request("GET", &format!("{}/repos/{}/actions/workflows/{}/runs?per_page=1", self.api_url(), self.repository, self.pipeline))
+                    .call()?
+                    .into_json()?;
+
+                let runs = response["workflow_runs"].as_array().ok_or(CiError::MalformedResponse)?;
+
+                Ok(runs.first().map_or(RunStatus::Unknown, github_status))
+            }
+
+            CiProvider::Jenkins => {
+                let response: Value = self.request("GET", &format!("{}/job/{}/lastBuild/api/json", self.url.trim_end_matches('/'), self.pipeline))
+                    .call()?
+                    .into_json()?;
+
+                if response["building"].as_bool().unwrap_or(false) {
+                    return Ok(RunStatus::Running);
+                }
+
+                Ok(match response["result"].as_str() {
+                    Some("SUCCESS") => RunStatus::Success,
+                    Some("FAILURE") | Some("UNSTABLE") => RunStatus::Failure,
+                    Some("ABORTED") | Some("NOT_BUILT") => RunStatus::Cancelled,
+                    _ => RunStatus::Unknown
+                })
+            }
+        }
+    }
+}
+
+impl CiBranch {
+    /// Retrieves latest pipeline run of the branch, token is used if not empty
+    pub fn latest_run(&self, token: &str) -> Result<LatestRun, CiError> {
+        match self.provider {
+            RepoProvider::GitHub => {
+                let api = if self.url.is_empty() { GITHUB_API } else { self.url.trim_end_matches('/') };
+
+                let mut request = ureq::get(&format!("{}/repos/{}/actions/runs", api, self.repository))
+                    .timeout(REQUEST_TIMEOUT)
+                    .set("Accept", "application/vnd.github+json")
+                    .query("per_page", "1");
+
+                if !self.branch.is_empty() {
+                    request = request.query("branch", &self.branch);
+                }
+
+                if !token.is_empty() {
+                    request = request.set("Authorization", &format!("Bearer {}", token));
+                }
+
+                let response: Value = request.call()?.into_json()?;
+                let runs = response["workflow_runs"].as_array().ok_or(CiError::MalformedResponse)?;
+
+                Ok(match runs.first() {
+                    Some(run) => LatestRun {
+                        status: github_status(run),
+                        url: run["html_url"].as_str().unwrap_or_default().to_string()
+                    },
+                    None => LatestRun {
+                        status: RunStatus::Unknown,
+                        url: "".to_string()
+                    }
+                })
+            }
+
+            RepoProvider::GitLab => {
+                let base = if self.url.is_empty() { GITLAB_URL } else { self.url.trim_end_matches('/') };
+                let project = self.repository.replace('/', "%2F");
+
+                let mut request = ureq::get(&format!("{}/api/v4/projects/{}/pipelines", base, project))
+                    .timeout(REQUEST_TIMEOUT)
+                    .query("per_page", "1");
+
+                if !self.branch.is_empty() {
+                    request = request.query("ref", &self.branch);
+                }
+
+                if !token.is_empty() {
+                    request = request.set("PRIVATE-TOKEN", token);
+                }
+
+                let response: Value = request.call()?.into_json()?;
+                let pipelines = response.as_array().ok_or(CiError::MalformedResponse)?;
+
+                Ok(match pipelines.first() {
+                    Some(pipeline) => LatestRun {
+                        status: match pipeline["status"].as_str() {
+                            Some("success") => RunStatus::Success,
+                            Some("failed") => RunStatus::Failure,
+                            Some("canceled") | Some("skipped") => RunStatus::Cancelled,
+                            Some("running") => RunStatus::Running,
+                            Some("created") | Some("waiting_for_resource") | Some("preparing") | Some("pending") | Some("scheduled") | Some("manual") => RunStatus::Queued,
+                            _ => RunStatus::Unknown
+                        },
+                        url: pipeline["web_url"].as_str().unwrap_or_default().to_string()
+                    },
+                    None => LatestRun {
+                        status: RunStatus::Unknown,
+                        url: "".to_string()
+                    }
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,660 @@
+mod api;
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{sleep, spawn};
+use std::process::Command;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use streamduck_core::config::Config;
+use streamduck_core::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
+use streamduck_core::core::manager::CoreManager;
+use streamduck_core::core::methods::CoreHandle;
+use streamduck_core::core::UniqueButton;
+use streamduck_core::font::get_font_from_collection;
+use streamduck_core::image::{DynamicImage, GenericImageView};
+use streamduck_core::modules::components::{ComponentDefinition, FieldError, map_ui_values, UIFieldType, UIFieldValue, UIValue};
+use streamduck_core::modules::events::SDCoreEvent;
+use streamduck_core::modules::{ModuleManager, PluginMetadata, SDModule, SDModulePointer};
+use streamduck_core::socket::SocketManager;
+use streamduck_core::thread::rendering::{ButtonBackground, ButtonText, RendererComponentBuilder};
+use streamduck_core::thread::rendering::custom::RenderingManager;
+use streamduck_core::thread::util::{render_aligned_text_on_image, render_filled_rect_on_image, TextAlignment};
+use streamduck_core::util::rusttype::Scale;
+use streamduck_core::util::straight_copy;
+use streamduck_core::versions::{CONFIG, CORE_EVENTS, PLUGIN_API, RENDERING, SDMODULE_TRAIT};
+use streamduck_core_derive::{component, plugin_config};
+use crate::api::{CiBranch, CiError, CiPipeline, CiProvider, LatestRun, RepoProvider, RunStatus};
+
+/// Polling interval when pipeline is idle
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Polling interval when pipeline has a run in progress
+const ACTIVE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Maximum interval that backoff can grow to after repeated errors
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+#[no_mangle]
+pub fn get_metadata() -> PluginMetadata {
+    PluginMetadata::from_literals(
+        "ci",
+        "TheJebForge",
+        "Triggers and monitors CI pipelines on Jenkins, GitHub Actions and GitLab CI",
+        "0.1",
+        &[
+            PLUGIN_API,
+            SDMODULE_TRAIT,
+            CONFIG,
+            CORE_EVENTS,
+            RENDERING
+        ]
+    )
+}
+
+#[no_mangle]
+pub fn get_module() -> SDModulePointer {
+    Box::into_raw(Box::new(CiModule::new()))
+}
+
+#[no_mangle]
+pub fn register(_: Arc<SocketManager>, _: Arc<RenderingManager>, _: Arc<ModuleManager>) {}
+
+/// Polling state of a pipeline
+struct PipelineState {
+    status: Option<RunStatus>,
+    next_poll: Instant,
+    backoff: Duration,
+}
+
+impl Default for PipelineState {
+    fn default() -> Self {
+        Self {
+            status: None,
+            next_poll: Instant::now(),
+            backoff: IDLE_POLL_INTERVAL
+        }
+    }
+}
+
+type StateMap = Arc<RwLock<HashMap<CiPipeline, PipelineState>>>;
+
+/// Polling state of a branch
+struct BranchState {
+    run: Option<LatestRun>,
+    next_poll: Instant,
+    backoff: Duration,
+}
+
+impl Default for BranchState {
+    fn default() -> Self {
+        Self {
+            run: None,
+            next_poll: Instant::now(),
+            backoff: IDLE_POLL_INTERVAL
+        }
+    }
+}
+
+type BranchMap = Arc<RwLock<HashMap<CiBranch, BranchState>>>;
+
+/// Tokens used by branch status buttons
+#[plugin_config("ci")]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CiSettings {
+    #[serde(default)]
+    pub github_token: String,
+    #[serde(default)]
+    pub gitlab_token: String,
+}
+
+impl CiSettings {
+    fn token(&self, provider: RepoProvider) -> &str {
+        match provider {
+            RepoProvider::GitHub => &self.github_token,
+            RepoProvider::GitLab => &self.gitlab_token,
+        }
+    }
+}
+
+pub struct CiModule {
+    states: StateMap,
+    branches: BranchMap,
+    /// Settings last read from config, polling thread doesn't have access to config
+    settings: Arc<RwLock<Option<CiSettings>>>,
+    /// Polling thread stops once this is false
+    running: Arc<AtomicBool>,
+}
+
+impl CiModule {
+    pub fn new() -> CiModule {
+        let states: StateMap = Default::default();
+
+        let branches: BranchMap = Default::default();
+        let settings: Arc<RwLock<Option<CiSettings>>> = Default::default();
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread_states = states.clone();
+        let thread_branches = branches.clone();
+        let thread_settings = settings.clone();
+        let thread_running = running.clone();
+        spawn(move || {
+            while thread_running.load(Ordering::Relaxed) {
+                let due: Vec<CiPipeline> = thread_states.read().unwrap().iter()
+                    .filter(|(_, state)| state.next_poll <= Instant::now())
+                    .map(|(pipeline, _)| pipeline.clone())
+                    .collect();
+
+                for pipeline in due {
+                    let result = pipeline.latest_status();
+
+                    let mut lock = thread_states.write().unwrap();
+                    let state = lock.entry(pipeline.clone()).or_default();
+
+                    match result {
+                        Ok(status) => {
+                            state.status = Some(status);
+                            state.backoff = IDLE_POLL_INTERVAL;
+                            state.next_poll = Instant::now() + match status {
+                                RunStatus::Queued | RunStatus::Running => ACTIVE_POLL_INTERVAL,
+                                _ => IDLE_POLL_INTERVAL
+                            };
+                        }
+
+                        Err(CiError::RateLimited(wait)) => {
+                            log::warn!("Rate limited by {}, waiting {}s", pipeline.provider.name(), wait.as_secs());
+                            state.next_poll = Instant::now() + wait;
+                        }
+
+                        Err(err) => {
+                            log::debug!("Failed to retrieve status of {}: {:?}", pipeline.pipeline, err);
+                            state.status = None;
+                            state.next_poll = Instant::now() + state.backoff;
+                            state.backoff = (state.backoff * 2).min(MAX_BACKOFF);
+                        }
+                    }
+                }
+
+                let due: Vec<CiBranch> = thread_branches.read().unwrap().iter()
+                    .filter(|(_, state)| state.next_poll <= Instant::now())
+                    .map(|(branch, _)| branch.clone())
+                    .collect();
+
+                for branch in due {
+                    let token = thread_settings.read().unwrap().as_ref()
+                        .map(|x| x.token(branch.provider).to_string())
+                        .unwrap_or_default();
+
+                    let result = branch.latest_run(&token);
+
+                    let mut lock = thread_branches.write().unwrap();
+                    let state = lock.entry(branch.clone()).or_default();
+
+                    match result {
+                        Ok(run) => {
+                            state.next_poll = Instant::now() + match run.status {
+                                RunStatus::Queued | RunStatus::Running => ACTIVE_POLL_INTERVAL,
+                                _ => IDLE_POLL_INTERVAL
+                            };
+                            state.run = Some(run);
+                            state.backoff = IDLE_POLL_INTERVAL;
+                        }
+
+                        Err(CiError::RateLimited(wait)) => {
+                            log::warn!("Rate limited by {}, waiting {}s", branch.provider.name(), wait.as_secs());
+                            state.next_poll = Instant::now() + wait;
+                        }
+
+                        Err(err) => {
+                            log::debug!("Failed to retrieve pipelines of {}: {:?}", branch.repository, err);
+                            state.run = None;
+                            state.next_poll = Instant::now() + state.backoff;
+                            state.backoff = (state.backoff * 2).min(MAX_BACKOFF);
+                        }
+                    }
+                }
+
+                sleep(Duration::from_secs(1));
+            }
+        });
+
+        CiModule {
+            states,
+            branches,
+            settings,
+            running
+        }
+    }
+
+    /// Reads settings from config and caches them for polling thread
+    fn load_settings(&self, config: &Arc<Config>) -> CiSettings {
+        let settings: CiSettings = config.get_plugin_settings().unwrap_or_default();
+        *self.settings.write().unwrap() = Some(settings.clone());
+        settings
+    }
+
+    /// Gets latest known run of the branch, starts watching the branch if it wasn't watched before
+    fn get_run(&self, core: &CoreHandle, branch: &CiBranch) -> Option<LatestRun> {
+        if let Some(state) = self.branches.read().unwrap().get(branch) {
+            return state.run.clone();
+        }
+
+        if self.settings.read().unwrap().is_none() {
+            self.load_settings(&core.config());
+        }
+
+        self.branches.write().unwrap().insert(branch.clone(), BranchState::default());
+        None
+    }
+
+    /// Gets last known status of the pipeline, starts watching the pipeline if it wasn't watched before
+    fn get_status(&self, pipeline: &CiPipeline) -> Option<RunStatus> {
+        if let Some(state) = self.states.read().unwrap().get(pipeline) {
+            return state.status;
+        }
+
+        self.states.write().unwrap().insert(pipeline.clone(), PipelineState::default());
+        None
+    }
+
+    /// Makes polling thread check the pipeline soon, used after triggering a run
+    fn poll_soon(&self, pipeline: &CiPipeline) {
+        let mut lock = self.states.write().unwrap();
+        let state = lock.entry(pipeline.clone()).or_default();
+
+        let soon = Instant::now() + ACTIVE_POLL_INTERVAL;
+        if state.next_poll > soon {
+            state.next_poll = soon;
+        }
+    }
+}
+
+impl Drop for CiModule {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+impl SDModule for CiModule {
+    fn name(&self) -> String {
+        "ci".to_string()
+    }
+
+    fn components(&self) -> HashMap<String, ComponentDefinition> {
+        let mut map = HashMap::new();
+
+        map.insert(CiTriggerComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "CI Pipeline".to_string(),
+            description: "Triggers a pipeline or workflow and displays status of its latest run".to_string(),
+            default_looks: RendererComponentBuilder::new()
+                .background(ButtonBackground::Solid((40, 40, 40, 255)))
+                .add_text(ButtonText {
+                    text: "CI".to_string(),
+                    font: "default".to_string(),
+                    scale: (25.0, 25.0),
+                    alignment: TextAlignment::Center,
+                    padding: 0,
+                    offset: (0.0, -5.0),
+                    color: (255, 255, 255, 255),
+                    shadow: None,
+                    auto_fit: false,
+                    word_wrap: false,
+                    marquee_speed: 0.0
+                })
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map.insert(CiStatusComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "CI Branch Status".to_string(),
+            description: "Displays status of latest pipeline on a GitHub or GitLab branch, opens the run when pressed".to_string(),
+            default_looks: RendererComponentBuilder::new()
+                .background(ButtonBackground::Solid((40, 40, 40, 255)))
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map
+    }
+
+    fn add_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            CiTriggerComponent::NAME => {
+                button.insert_component(CiTriggerComponent::default()).ok();
+            }
+
+            CiStatusComponent::NAME => {
+                button.insert_component(CiStatusComponent::default()).ok();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn remove_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            CiTriggerComponent::NAME => {
+                button.remove_component::<CiTriggerComponent>();
+            }
+
+            CiStatusComponent::NAME => {
+                button.remove_component::<CiStatusComponent>();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn paste_component(&self, _: CoreHandle, reference_button: &Button, new_button: &mut Button) {
+        straight_copy(reference_button, new_button, CiTriggerComponent::NAME);
+        straight_copy(reference_button, new_button, CiStatusComponent::NAME);
+    }
+
+    fn component_values(&self, _: CoreHandle, button: &Button, name: &str) -> Vec<UIValue> {
+        match name {
+            CiTriggerComponent::NAME => {
+                if let Ok(component) = parse_button_to_component::<CiTriggerComponent>(button) {
+                    get_values(&component)
+                } else {
+                    vec![]
+                }
+            }
+
+            CiStatusComponent::NAME => {
+                if let Ok(component) = parse_button_to_component::<CiStatusComponent>(button) {
+                    get_branch_values(&component)
+                } else {
+                    vec![]
+                }
+            }
+
+            _ => vec![],
+        }
+    }
+
+    fn set_component_value(&self, _: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+        match name {
+            CiTriggerComponent::NAME => {
+                if let Ok(mut component) = parse_button_to_component::<CiTriggerComponent>(button) {
+                    set_values(&mut component, value);
+                    button.insert_component(component).ok();
+                }
+            }
+
+            CiStatusComponent::NAME => {
+                if let Ok(mut component) = parse_button_to_component::<CiStatusComponent>(button) {
+                    set_branch_values(&mut component, value);
+                    button.insert_component(component).ok();
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn listening_for(&self) -> Vec<String> {
+        vec![
+            CiTriggerComponent::NAME.to_string(),
+            CiStatusComponent::NAME.to_string()
+        ]
+    }
+
+    fn settings(&self, core_manager: Arc<CoreManager>) -> Vec<UIValue> {
+        let settings = self.load_settings(&core_manager.config);
+
+        vec![
+            string_field("github_token", "GitHub Token", "Personal access token used by branch status buttons, needed for private repositories", &settings.github_token),
+            string_field("gitlab_token", "GitLab Token", "Access token with read_api scope used by branch status buttons", &settings.gitlab_token),
+        ]
+    }
+
+    fn set_setting(&self, core_manager: Arc<CoreManager>, value: Vec<UIValue>) {
+        let mut settings = self.load_settings(&core_manager.config);
+        let change_map = map_ui_values(value);
+
+        for (name, field) in [
+            ("github_token", &mut settings.github_token),
+            ("gitlab_token", &mut settings.gitlab_token),
+        ] {
+            if let Some(value) = change_map.get(name) {
+                if let Ok(str) = value.value.try_into_string() {
+                    *field = str.trim().to_string();
+                }
+            }
+        }
+
+        *self.settings.write().unwrap() = Some(settings.clone());
+        core_manager.config.set_plugin_settings(settings);
+
+        // Polling again with new tokens
+        for state in self.branches.write().unwrap().values_mut() {
+            state.next_poll = Instant::now();
+            state.backoff = IDLE_POLL_INTERVAL;
+        }
+    }
+
+    fn event(&self, core: CoreHandle, event: SDCoreEvent) {
+        match event {
+            SDCoreEvent::ButtonAction { pressed_button, .. } => {
+                if let Ok(component) = parse_unique_button_to_component::<CiTriggerComponent>(&pressed_button) {
+                    let pipeline = component.pipeline;
+
+                    if let Err(err) = pipeline.trigger() {
+                        log::warn!("Failed to trigger {}: {:?}", pipeline.pipeline, err);
+                    }
+
+                    self.poll_soon(&pipeline);
+                }
+
+                if let Ok(component) = parse_unique_button_to_component::<CiStatusComponent>(&pressed_button) {
+                    if let Some(run) = self.get_run(&core, &component.branch) {
+                        if !run.url.is_empty() {
+                            open_url(&run.url);
+                        }
+                    }
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn render(&self, core: CoreHandle, button: &UniqueButton, frame: &mut DynamicImage) {
+        if let Ok(component) = parse_unique_button_to_component::<CiTriggerComponent>(button) {
+            let status = self.get_status(&component.pipeline).unwrap_or(RunStatus::Unknown);
+            let color = status.color();
+
+            // Status border around the key
+            let (width, height) = frame.dimensions();
+            let thickness = (width / 14).max(1);
+
+            render_filled_rect_on_image(frame, (0, 0), (width, thickness), color);
+            render_filled_rect_on_image(frame, (0, (height - thickness) as i32), (width, thickness), color);
+            render_filled_rect_on_image(frame, (0, 0), (thickness, height), color);
+            render_filled_rect_on_image(frame, ((width - thickness) as i32, 0), (thickness, height), color);
+
+            if let Some(font) = get_font_from_collection("default") {
+                render_aligned_text_on_image(core.core().image_size, frame, font.as_ref(), status.label(), Scale::uniform(12.0), TextAlignment::BottomCenter, thickness + 2, (0.0, 0.0), color);
+            }
+        }
+
+        if let Ok(component) = parse_unique_button_to_component::<CiStatusComponent>(button) {
+            let status = self.get_run(&core, &component.branch).map_or(RunStatus::Unknown, |x| x.status);
+
+            let (width, height) = frame.dimensions();
+            render_filled_rect_on_image(frame, (0, 0), (width, height), status.color());
+
+            if let Some(font) = get_font_from_collection("default") {
+                let white = (255, 255, 255, 255);
+                render_aligned_text_on_image(core.core().image_size, frame, font.as_ref(), status.label(), Scale::uniform(16.0), TextAlignment::Center, 0, (0.0, 0.0), white);
+
+                if !component.branch.branch.is_empty() {
+                    render_aligned_text_on_image(core.core().image_size, frame, font.as_ref(), &component.branch.branch, Scale::uniform(11.0), TextAlignment::BottomCenter, 4, (0.0, 0.0), white);
+                }
+            }
+        }
+    }
+
+    fn render_hash(&self, core: CoreHandle, button: &UniqueButton, hash: &mut Box<dyn Hasher>) {
+        if let Ok(component) = parse_unique_button_to_component::<CiTriggerComponent>(button) {
+            self.get_status(&component.pipeline).hash(hash);
+        }
+
+        if let Ok(component) = parse_unique_button_to_component::<CiStatusComponent>(button) {
+            self.get_run(&core, &component.branch).map(|x| x.status).hash(hash);
+        }
+    }
+
+    fn metadata(&self) -> PluginMetadata {
+        get_metadata()
+    }
+}
+
+/// Component that triggers a pipeline and displays its status
+#[component("ci_trigger")]
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct CiTriggerComponent {
+    #[serde(default)]
+    pub pipeline: CiPipeline,
+}
+
+/// Component that displays status of latest pipeline run of a branch
+#[component("ci_status")]
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct CiStatusComponent {
+    #[serde(default)]
+    pub branch: CiBranch,
+}
+
+/// Opens the address in default browser
+fn open_url(url: &str) {
+    #[cfg(target_os = "windows")]
+    let result = Command::new("cmd").args(["/C", "start", "", url]).spawn();
+
+    #[cfg(target_os = "macos")]
+    let result = Command::new("open").arg(url).spawn();
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let result = Command::new("xdg-open").arg(url).spawn();
+
+    if let Err(err) = result {
+        log::warn!("Failed to open {}: {}", url, err);
+    }
+}
+
+fn string_field(name: &str, display_name: &str, description: &str, value: &str) -> UIValue {
+    UIValue {
+        name: name.to_string(),
+        display_name: display_name.to_string(),
+        description: description.to_string(),
+        ty: UIFieldType::InputFieldString,
+        value: UIFieldValue::InputFieldString(value.to_string())
+    }
+}
+
+fn get_values(component: &CiTriggerComponent) -> Vec<UIValue> {
+    let pipeline = &component.pipeline;
+
+    let mut fields = vec![
+        UIValue {
+            name: "provider".to_string(),
+            display_name: "Provider".to_string(),
+            description: "CI service that runs the pipeline".to_string(),
+            ty: UIFieldType::Choice(CiProvider::VARIANTS.iter().map(|x| x.to_string()).collect()),
+            value: UIFieldValue::Choice(pipeline.provider.name().to_string())
+        }
+    ];
+
+    match pipeline.provider {
+        CiProvider::GitHubActions => {
+            fields.push(string_field("repository", "Repository", "Repository in owner/name format", &pipeline.repository));
+            fields.push(string_field("pipeline", "Workflow", "Workflow file name or ID, eg. build.yml", &pipeline.pipeline));
+            fields.push(string_field("git_ref", "Ref", "Branch or tag to run the workflow on, main if empty", &pipeline.git_ref));
+            fields.push(string_field("token", "Token", "Personal access token with actions permission", &pipeline.token));
+            fields.push(string_field("url", "API URL", "GitHub Enterprise API address, leave empty for github.com", &pipeline.url));
+        }
+
+        CiProvider::Jenkins => {
+            fields.push(string_field("url", "Server URL", "Address of Jenkins server", &pipeline.url));
+            fields.push(string_field("pipeline", "Job", "Name of the job to build", &pipeline.pipeline));
+            fields.push(string_field("user", "User", "Jenkins username", &pipeline.user));
+            fields.push(string_field("token", "API Token", "API token of the user", &pipeline.token));
+        }
+    }
+
+    fields
+}
+
+fn set_values(component: &mut CiTriggerComponent, value: Vec<UIValue>) {
+    let change_map = map_ui_values(value);
+    let pipeline = &mut component.pipeline;
+
+    if let Some(value) = change_map.get("provider") {
+        if let Ok(provider) = value.value.try_into_string() {
+            if let Some(provider) = CiProvider::from_name(&provider) {
+                pipeline.provider = provider;
+            }
+        }
+    }
+
+    for (name, field) in [
+        ("url", &mut pipeline.url),
+        ("repository", &mut pipeline.repository),
+        ("pipeline", &mut pipeline.pipeline),
+        ("git_ref", &mut pipeline.git_ref),
+        ("user", &mut pipeline.user),
+        ("token", &mut pipeline.token),
+    ] {
+        if let Some(value) = change_map.get(name) {
+            if let Ok(str) = value.value.try_into_string() {
+                *field = str;
+            }
+        }
+    }
+}
+
+fn get_branch_values(component: &CiStatusComponent) -> Vec<UIValue> {
+    let branch = &component.branch;
+
+    vec![
+        UIValue {
+            name: "provider".to_string(),
+            display_name: "Provider".to_string(),
+            description: "Service that hosts the repository, token is taken from plugin settings".to_string(),
+            ty: UIFieldType::Choice(RepoProvider::VARIANTS.iter().map(|x| x.to_string()).collect()),
+            value: UIFieldValue::Choice(branch.provider.name().to_string())
+        },
+        string_field("repository", "Repository", "Repository in owner/name format, or project path for GitLab", &branch.repository),
+        string_field("branch", "Branch", "Branch to show pipeline status of, latest pipeline of any branch is shown if empty", &branch.branch),
+        string_field("url", "Server URL", "GitHub Enterprise API address or self-hosted GitLab address, leave empty for github.com or gitlab.com", &branch.url),
+    ]
+}
+
+fn set_branch_values(component: &mut CiStatusComponent, value: Vec<UIValue>) {
+    let change_map = map_ui_values(value);
+    let branch = &mut component.branch;
+
+    if let Some(value) = change_map.get("provider") {
+        if let Ok(provider) = value.value.try_into_string() {
+            if let Some(provider) = RepoProvider::from_name(&provider) {
+                branch.provider = provider;
+            }
+        }
+    }
+
+    for (name, field) in [
+        ("url", &mut branch.url),
+        ("repository", &mut branch.repository),
+        ("branch", &mut branch.branch),
+    ] {
+        if let Some(value) = change_map.get(name) {
+            if let Ok(str) = value.value.try_into_string() {
+                *field = str.trim().to_string();
+            }
+        }
+    }
+}
@@ -0,0 +1,1075 @@
+//! Non-interactive subcommands for scripting
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Cursor;
+use std::process::exit;
+use std::sync::Arc;
+use image::{DynamicImage, GenericImage, GenericImageView, ImageFormat};
+use image::io::Reader;
+use streamduck_client::daemon::daemon_data::assets::{AddImageResult, CleanupUnusedImagesResult, FinishImageUploadResult, GetImageResult, ListImagesResult, StartImageUploadResult, UploadImageChunkResult};
+use streamduck_client::daemon::daemon_data::buttons::{CopyButtonResult, GetButtonResult, MoveButtonResult, PasteButtonResult};
+use streamduck_client::daemon::daemon_data::clients::DisconnectClientResult;
+use streamduck_client::daemon::daemon_data::config::{DiffDeviceConfigResult, ExportAllConfigsResult, ExportDeviceConfigResult, ImportAllConfigsResult, ImportDeviceConfigResult, RestoreDeviceConfigBackupResult};
+use streamduck_client::daemon::daemon_data::devices::{DeviceType, GetDeviceResult, SetBrightnessResult, SetDeviceMirrorResult, SetDeviceOrientationResult, SetDeviceStartupResult, SetMirroredLayoutResult, SetPanelTransitionResult};
+use streamduck_client::daemon::daemon_data::folders::{CleanupFoldersResult, DeleteFolderResult, FindButtonsResult, GetFolderTreeResult, ListFoldersResult, RenameFolderResult};
+use streamduck_client::daemon::daemon_data::history::{RedoResult, UndoResult};
+use streamduck_client::daemon::daemon_data::layout::{ApplyLayoutResult, ExportLayoutResult, InstantiateTemplateResult};
+use streamduck_client::daemon::daemon_data::modules::{GetGlobalPluginSettingsResult, ReenablePluginResult, SendModuleMessageResult, SetGlobalPluginSettingsResult};
+use streamduck_client::daemon::daemon_data::ops::{DoButtonActionResult, RestartDaemonResult, ShutdownDaemonResult};
+use streamduck_client::daemon::daemon_data::notifications::{DismissNotificationResult, ShowNotificationResult};
+use streamduck_client::daemon::daemon_data::panels::{GetButtonImagesResult, GetStackBreadcrumbsResult, PopToScreenResult, RenderButtonPreviewResult, SetPanelResult};
+use streamduck_client::daemon::daemon_data::status::DegradedComponent;
+use streamduck_client::daemon::daemon_data::presets::{ApplyPresetResult, RemovePresetResult, SavePresetResult};
+use streamduck_client::daemon::transfer::TRANSFER_CHUNK_SIZE;
+use streamduck_client::{SDSyncEventClient, SDSyncRequestClient};
+use streamduck_core::core::button::Button;
+use streamduck_core::logging::LogFilter;
+use streamduck_core::modules::folders::FolderNode;
+use streamduck_core::portable::PortableButton;
+use streamduck_core::presets::ComponentPreset;
+use streamduck_core::review::{ButtonChange, ComponentChange};
+use streamduck_core::socket::SocketPacket;
+use streamduck_core::thread::orientation::DeviceOrientation;
+use streamduck_core::thread::transition::PanelTransition;
+
+pub const SUBCOMMANDS: &'static [(&'static str, &'static str)] = &[
+    ("devices", "- lists all streamdeck devices"),
+    ("set-brightness", "<serial> <0-255> - sets brightness for device"),
+    ("set-transition", "<serial> <none|slide|fade|default> - sets panel transition for device, default uses global setting"),
+    ("set-orientation", "<serial> <0|90|180|270> - sets clockwise rotation of device that is mounted sideways or upside down"),
+    ("set-left-handed", "<serial> <on|off> [--flip-images] - mirrors key layout of device horizontally, flipping images too if --flip-images is set"),
+    ("set-startup", "<serial> <folder id|none> [path] - sets folder that opens once device connects, and buttons from JSON file that get triggered after it"),
+    ("mirror", "<serial> <source serial|none> - makes device show and control current screen of source device, none stops mirroring"),
+    ("press", "<serial> <key index> - simulates a press on a button"),
+    ("notify", "<serial> <text> [seconds] [key index...] - shows notification over current screen, on all keys if none specified, prints its ID"),
+    ("dismiss-notification", "<serial> <id> - removes notification before it disappears on its own"),
+    ("stack", "<serial> - lists panels on the stack with their index and folder ID"),
+    ("pop-to", "<serial> <index> - closes panels until panel with provided index is on top, 0 is root"),
+    ("set-panel", "<serial> <path> [folder id] - replaces all buttons of current screen or a folder with ones from JSON panel file"),
+    ("folders", "<serial> - lists folders with their ID, name, button count and whether they're orphaned"),
+    ("folder-tree", "<serial> - prints folder hierarchy of a device"),
+    ("find-buttons", "<serial> [--component <name>] [text] - finds buttons in all folders that have the component and contain the text"),
+    ("rename-folder", "<serial> <id> <name> - renames folder and buttons that open it"),
+    ("delete-folder", "<serial> <id> - deletes folder with folders inside of it and buttons that open it"),
+    ("cleanup-folders", "<serial> - deletes folders that can't be reached from root screen anymore"),
+    ("export-config", "<serial> <path> [--with-images] - exports device config into a file, optionally embedding used images"),
+    ("diff-config", "<serial> <path> - shows changes that importing device config from a file would make"),
+    ("import-config", "<serial> <path> [--dry-run] - imports device config from a file, dry run lists problems and changes without importing"),
+    ("restore-config", "<serial> [backup number] - restores device config from backup, most recent if number is omitted"),
+    ("export-all", "<path> - exports all device configs, settings and images into a .tar.gz archive"),
+    ("import-all", "<path> - imports archive made by export-all"),
+    ("apply-layout", "<serial> <path> [--dry-run] - applies YAML layout definition, printing changes that were made"),
+    ("export-layout", "<serial> <path> - exports current layout as YAML layout definition"),
+    ("instantiate-template", "<serial> <path> [NAME=value...] [--dry-run] - fills in variables of YAML layout template and applies it"),
+    ("presets", "- lists saved component presets"),
+    ("save-preset", "<name> <serial> <key index> <component> [field...] [--propagate] - saves fields of a component on a button as preset, all fields if none specified"),
+    ("remove-preset", "<name> - removes component preset"),
+    ("apply-preset", "<serial> <key index> <name> - applies component preset onto a button"),
+    ("global-settings", "<name> [json] - prints global plugin settings, or replaces them if JSON is provided"),
+    ("send-message", "<topic> <json> [serial] - sends message to modules subscribed to the topic, optionally related to a device"),
+    ("plugin-dependencies", "- lists loaded modules in load order with their dependencies"),
+    ("reenable-plugin", "<name> - enables plugin that was disabled after panicking too many times"),
+    ("plugin-requests", "- lists requests that plugins have registered"),
+    ("plugin-request", "<name> [json] - sends request registered by plugin, printing data of the response"),
+    ("move-button", "<serial> <from> <to> [--swap] - moves button on current screen, swapping it with button on destination if --swap is set"),
+    ("copy-button", "<serial> <key index> <path> - saves button with its folders and images into a snippet file"),
+    ("paste-button", "<serial> <key index> <path> - pastes button from snippet file, can be made on another device"),
+    ("images", "<serial> - lists images in device image collection with their dimensions and size"),
+    ("add-image", "<serial> <path> - uploads image file into device image collection, printing its identifier"),
+    ("get-image", "<serial> <identifier> <path> - downloads image from device image collection into a file"),
+    ("cleanup-images", "[serial] - removes images that aren't used by any button, from all devices if serial isn't specified"),
+    ("screenshot", "<serial> <path> - saves image of all buttons on current screen as PNG"),
+    ("preview", "<serial> <key index> <button path> <image path> [size] - renders button from JSON file as it would look on the device, saving it as PNG"),
+    ("status", "- prints version, uptime, devices, modules and problems of daemon"),
+    ("shutdown", "- saves all configs and stops daemon, has to be allowed with allow_shutdown_requests in daemon config"),
+    ("restart", "- saves all configs and restarts daemon, has to be allowed with allow_shutdown_requests in daemon config"),
+    ("clients", "- lists clients connected to daemon"),
+    ("disconnect-client", "<id> - disconnects a client from daemon"),
+    ("logs", "[count] [--device <serial>] [--module <name>] [--level <level>] - prints recent log records of daemon, 100 if count isn't specified"),
+    ("undo", "<serial> - undoes last button edit on current screen of device"),
+    ("redo", "<serial> - redoes last undone button edit on current screen of device"),
+    ("service", "<install|uninstall|status> [daemon path] - makes daemon start at login, using current directory as its working directory"),
+    ("listen-events", "- prints events from daemon as JSON, one per line"),
+    ("help", "- prints this message"),
+];
+
+/// Prints usage into stderr and exits with error code
+pub(crate) fn usage_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}", message, usage());
+    exit(2)
+}
+
+/// Prints error into stderr and exits with error code
+pub(crate) fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    exit(1)
+}
+
+pub fn usage() -> String {
+    let mut usage = "Usage: streamduck [<subcommand> [args]]\nRuns interactive prompt if no subcommand is provided\n\nSubcommands:\n".to_string();
+
+    for (name, description) in SUBCOMMANDS {
+        usage += &format!("  {} {}\n", name, description);
+    }
+
+    usage
+}
+
+fn arg<'a>(args: &'a [String], index: usize, name: &str) -> &'a str {
+    args.get(index).map(|x| x.as_str()).unwrap_or_else(|| usage_error(&format!("Missing argument: {}", name)))
+}
+
+/// Runs a subcommand, exits the process with error code on failure
+pub fn run(client: Arc<dyn SDSyncRequestClient>, event_client: impl FnOnce() -> Arc<dyn SDSyncEventClient>, args: &[String]) {
+    let command = arg(args, 0, "subcommand");
+
+    match command {
+        "devices" => devices(&client),
+        "set-brightness" => set_brightness(&client, arg(args, 1, "serial"), arg(args, 2, "brightness")),
+        "set-transition" => set_transition(&client, arg(args, 1, "serial"), arg(args, 2, "transition")),
+        "set-orientation" => set_orientation(&client, arg(args, 1, "serial"), arg(args, 2, "degrees")),
+        "set-left-handed" => set_left_handed(&client, arg(args, 1, "serial"), arg(args, 2, "state"), args.get(3).map_or(false, |x| x == "--flip-images")),
+        "set-startup" => set_startup(&client, arg(args, 1, "serial"), arg(args, 2, "folder id"), args.get(3).map(|x| x.as_str())),
+        "mirror" => mirror(&client, arg(args, 1, "serial"), arg(args, 2, "source")),
+        "press" => press(&client, arg(args, 1, "serial"), arg(args, 2, "key index")),
+        "notify" => notify(&client, arg(args, 1, "serial"), arg(args, 2, "text"), args.get(3).map(|x| x.as_str()).unwrap_or("5"), &args[4.min(args.len())..]),
+        "dismiss-notification" => dismiss_notification(&client, arg(args, 1, "serial"), arg(args, 2, "id")),
+        "stack" => stack(&client, arg(args, 1, "serial")),
+        "pop-to" => pop_to(&client, arg(args, 1, "serial"), arg(args, 2, "index")),
+        "set-panel" => set_panel(&client, arg(args, 1, "serial"), arg(args, 2, "path"), args.get(3).cloned()),
+        "folders" => folders(&client, arg(args, 1, "serial")),
+        "folder-tree" => folder_tree(&client, arg(args, 1, "serial")),
+        "find-buttons" => find_buttons(&client, arg(args, 1, "serial"), &args[2.min(args.len())..]),
+        "rename-folder" => rename_folder(&client, arg(args, 1, "serial"), arg(args, 2, "id"), arg(args, 3, "name")),
+        "delete-folder" => delete_folder(&client, arg(args, 1, "serial"), arg(args, 2, "id")),
+        "cleanup-folders" => cleanup_folders(&client, arg(args, 1, "serial")),
+        "export-config" => export_config(&client, arg(args, 1, "serial"), arg(args, 2, "path"), args.get(3).map_or(false, |x| x == "--with-images")),
+        "diff-config" => diff_config(&client, arg(args, 1, "serial"), arg(args, 2, "path")),
+        "import-config" => import_config(&client, arg(args, 1, "serial"), arg(args, 2, "path"), args.get(3).map_or(false, |x| x == "--dry-run")),
+        "restore-config" => restore_config(&client, arg(args, 1, "serial"), args.get(2).map(|x| x.as_str()).unwrap_or("1")),
+        "export-all" => export_all(&client, arg(args, 1, "path")),
+        "import-all" => import_all(&client, arg(args, 1, "path")),
+        "apply-layout" => apply_layout(&client, arg(args, 1, "serial"), arg(args, 2, "path"), args.get(3).map_or(false, |x| x == "--dry-run")),
+        "export-layout" => export_layout(&client, arg(args, 1, "serial"), arg(args, 2, "path")),
+        "instantiate-template" => instantiate_template(&client, arg(args, 1, "serial"), arg(args, 2, "path"), &args[3.min(args.len())..]),
+        "presets" => presets(&client),
+        "save-preset" => save_preset(&client, arg(args, 1, "name"), arg(args, 2, "serial"), arg(args, 3, "key index"), arg(args, 4, "component"), &args[5.min(args.len())..]),
+        "remove-preset" => remove_preset(&client, arg(args, 1, "name")),
+        "apply-preset" => apply_preset(&client, arg(args, 1, "serial"), arg(args, 2, "key index"), arg(args, 3, "name")),
+        "global-settings" => global_settings(&client, arg(args, 1, "name"), args.get(2).map(|x| x.as_str())),
+        "send-message" => send_message(&client, arg(args, 1, "topic"), arg(args, 2, "json"), args.get(3).map(|x| x.as_str())),
+        "plugin-dependencies" => plugin_dependencies(&client),
+        "reenable-plugin" => reenable_plugin(&client, arg(args, 1, "name")),
+        "plugin-requests" => plugin_requests(&client),
+        "plugin-request" => plugin_request(&client, arg(args, 1, "name"), args.get(2).map(|x| x.as_str())),
+        "move-button" => move_button(&client, arg(args, 1, "serial"), arg(args, 2, "from"), arg(args, 3, "to"), args.get(4).map_or(false, |x| x == "--swap")),
+        "copy-button" => copy_button(&client, arg(args, 1, "serial"), arg(args, 2, "key index"), arg(args, 3, "path")),
+        "paste-button" => paste_button(&client, arg(args, 1, "serial"), arg(args, 2, "key index"), arg(args, 3, "path")),
+        "images" => images(&client, arg(args, 1, "serial")),
+        "add-image" => add_image(&client, arg(args, 1, "serial"), arg(args, 2, "path")),
+        "get-image" => get_image(&client, arg(args, 1, "serial"), arg(args, 2, "identifier"), arg(args, 3, "path")),
+        "cleanup-images" => cleanup_images(&client, args.get(1).map(|x| x.as_str())),
+        "screenshot" => screenshot(&client, arg(args, 1, "serial"), arg(args, 2, "path")),
+        "preview" => preview(&client, arg(args, 1, "serial"), arg(args, 2, "key index"), arg(args, 3, "button path"), arg(args, 4, "image path"), args.get(5).map(|x| x.as_str())),
+        "status" => status(&client),
+        "shutdown" => shutdown(&client),
+        "restart" => restart(&client),
+        "clients" => clients(&client),
+        "disconnect-client" => disconnect_client(&client, arg(args, 1, "id")),
+        "logs" => logs(&client, &args[1.min(args.len())..]),
+        "undo" => undo(&client, arg(args, 1, "serial")),
+        "redo" => redo(&client, arg(args, 1, "serial")),
+        "listen-events" => listen_events(event_client()),
+        "help" | "--help" | "-h" => println!("{}", usage()),
+        _ => usage_error(&format!("Unknown subcommand: {}", command)),
+    }
+}
+
+fn devices(client: &Arc<dyn SDSyncRequestClient>) {
+    let devices = client.device_list().unwrap_or_else(|err| fail(&format!("Failed to list devices: {:?}", err)));
+
+    for device in devices {
+        println!("{}\t{}\t{}\t{}",
+                 device.serial_number,
+                 device.device_type,
+                 if device.managed { "managed" } else { "unmanaged" },
+                 if device.online { "online" } else { "offline" }
+        );
+    }
+}
+
+fn set_brightness(client: &Arc<dyn SDSyncRequestClient>, serial: &str, brightness: &str) {
+    let brightness = brightness.parse::<u8>().unwrap_or_else(|_| usage_error("Brightness should be a number from 0 to 255"));
+
+    match client.set_brightness(serial, brightness).unwrap_or_else(|err| fail(&format!("Failed to set brightness: {:?}", err))) {
+        SetBrightnessResult::DeviceNotFound => fail("Device not found"),
+        SetBrightnessResult::Set => {}
+    }
+}
+
+fn set_transition(client: &Arc<dyn SDSyncRequestClient>, serial: &str, transition: &str) {
+    let transition = match transition.to_lowercase().as_str() {
+        "none" => Some(PanelTransition::None),
+        "slide" => Some(PanelTransition::Slide),
+        "fade" => Some(PanelTransition::Fade),
+        "default" => None,
+        _ => usage_error("Transition should be one of none, slide, fade or default"),
+    };
+
+    match client.set_panel_transition(serial, transition).unwrap_or_else(|err| fail(&format!("Failed to set transition: {:?}", err))) {
+        SetPanelTransitionResult::DeviceNotFound => fail("Device not found"),
+        SetPanelTransitionResult::Set => {}
+    }
+}
+
+fn set_orientation(client: &Arc<dyn SDSyncRequestClient>, serial: &str, degrees: &str) {
+    let orientation = degrees.parse::<u16>().ok()
+        .and_then(DeviceOrientation::from_degrees)
+        .unwrap_or_else(|| usage_error("Orientation should be one of 0, 90, 180 or 270"));
+
+    match client.set_device_orientation(serial, orientation).unwrap_or_else(|err| fail(&format!("Failed to set orientation: {:?}", err))) {
+        SetDeviceOrientationResult::DeviceNotFound => fail("Device not found"),
+        SetDeviceOrientationResult::Set => {}
+    }
+}
+
+fn set_left_handed(client: &Arc<dyn SDSyncRequestClient>, serial: &str, state: &str, flip_images: bool) {
+    let mirrored = match state {
+        "on" => true,
+        "off" => false,
+        _ => usage_error("State should be on or off"),
+    };
+
+    match client.set_mirrored_layout(serial, mirrored, flip_images).unwrap_or_else(|err| fail(&format!("Failed to set left-handed layout: {:?}", err))) {
+        SetMirroredLayoutResult::DeviceNotFound => fail("Device not found"),
+        SetMirroredLayoutResult::Set => {}
+    }
+}
+
+fn set_startup(client: &Arc<dyn SDSyncRequestClient>, serial: &str, folder_id: &str, path: Option<&str>) {
+    let folder_id = if folder_id == "none" { None } else { Some(folder_id) };
+
+    let actions: Vec<Button> = match path {
+        Some(path) => {
+            let file = fs::read_to_string(path).unwrap_or_else(|err| fail(&format!("Failed to read file: {}", err)));
+            serde_json::from_str(&file).unwrap_or_else(|err| fail(&format!("Invalid startup actions: {}", err)))
+        }
+        None => vec![]
+    };
+
+    match client.set_device_startup(serial, folder_id, actions).unwrap_or_else(|err| fail(&format!("Failed to set startup: {:?}", err))) {
+        SetDeviceStartupResult::DeviceNotFound => fail("Device not found"),
+        SetDeviceStartupResult::FolderNotFound => fail("Folder not found"),
+        SetDeviceStartupResult::Set => {}
+    }
+}
+
+fn mirror(client: &Arc<dyn SDSyncRequestClient>, serial: &str, source: &str) {
+    let source = if source == "none" { None } else { Some(source) };
+
+    match client.set_device_mirror(serial, source).unwrap_or_else(|err| fail(&format!("Failed to set mirror: {:?}", err))) {
+        SetDeviceMirrorResult::DeviceNotFound => fail("Device not found"),
+        SetDeviceMirrorResult::SameDevice => fail("Device can't mirror itself"),
+        SetDeviceMirrorResult::SourceIsMirroring => fail("Source device is mirroring another device"),
+        SetDeviceMirrorResult::DeviceIsMirrored => fail("Device is mirrored by other devices"),
+        SetDeviceMirrorResult::Set => {}
+    }
+}
+
+fn press(client: &Arc<dyn SDSyncRequestClient>, serial: &str, key: &str) {
+    let key = key.parse::<u8>().unwrap_or_else(|_| usage_error("Key index should be a number"));
+
+    match client.do_button_action(serial, key).unwrap_or_else(|err| fail(&format!("Failed to press button: {:?}", err))) {
+        DoButtonActionResult::DeviceNotFound => fail("Device not found"),
+        DoButtonActionResult::Activated => {}
+    }
+}
+
+fn notify(client: &Arc<dyn SDSyncRequestClient>, serial: &str, text: &str, duration: &str, keys: &[String]) {
+    let duration = duration.parse::<f32>().unwrap_or_else(|_| usage_error("Duration should be a number of seconds"));
+    let keys = keys.iter()
+        .map(|x| x.parse::<u8>().unwrap_or_else(|_| usage_error("Key index should be a number")))
+        .collect();
+
+    match client.show_notification(serial, text, None, duration, keys).unwrap_or_else(|err| fail(&format!("Failed to show notification: {:?}", err))) {
+        ShowNotificationResult::DeviceNotFound => fail("Device not found"),
+        ShowNotificationResult::InvalidKeys => fail("None of the keys exist on the device"),
+        ShowNotificationResult::Shown(id) => println!("{}", id),
+    }
+}
+
+fn dismiss_notification(client: &Arc<dyn SDSyncRequestClient>, serial: &str, id: &str) {
+    match client.dismiss_notification(serial, id).unwrap_or_else(|err| fail(&format!("Failed to dismiss notification: {:?}", err))) {
+        DismissNotificationResult::DeviceNotFound => fail("Device not found"),
+        DismissNotificationResult::NotificationNotFound => fail("Notification not found"),
+        DismissNotificationResult::Dismissed => {}
+    }
+}
+
+fn stack(client: &Arc<dyn SDSyncRequestClient>, serial: &str) {
+    match client.get_stack_breadcrumbs(serial).unwrap_or_else(|err| fail(&format!("Failed to get stack: {:?}", err))) {
+        GetStackBreadcrumbsResult::DeviceNotFound => fail("Device not found"),
+        GetStackBreadcrumbsResult::Breadcrumbs(breadcrumbs) => {
+            for (index, breadcrumb) in breadcrumbs.into_iter().enumerate() {
+                println!("{}\t{}\t{}", index, breadcrumb.display_name, breadcrumb.folder_id.as_deref().unwrap_or("-"));
+            }
+        }
+    }
+}
+
+fn pop_to(client: &Arc<dyn SDSyncRequestClient>, serial: &str, index: &str) {
+    let index = index.parse::<usize>().unwrap_or_else(|_| usage_error("Index should be a number"));
+
+    match client.pop_to_screen(serial, index).unwrap_or_else(|err| fail(&format!("Failed to pop screens: {:?}", err))) {
+        PopToScreenResult::DeviceNotFound => fail("Device not found"),
+        PopToScreenResult::InvalidIndex => fail("There's no panel with that index"),
+        PopToScreenResult::Popped => {}
+    }
+}
+
+fn set_panel(client: &Arc<dyn SDSyncRequestClient>, serial: &str, path: &str, folder_id: Option<String>) {
+    let file = fs::read_to_string(path).unwrap_or_else(|err| fail(&format!("Failed to read file: {}", err)));
+    let panel = serde_json::from_str(&file).unwrap_or_else(|err| fail(&format!("Failed to parse panel: {}", err)));
+
+    match client.set_panel(serial, panel, folder_id).unwrap_or_else(|err| fail(&format!("Failed to set panel: {:?}", err))) {
+        SetPanelResult::DeviceNotFound => fail("Device not found"),
+        SetPanelResult::NoScreen => fail("Device has no screen"),
+        SetPanelResult::FolderNotFound => fail("Folder not found"),
+        SetPanelResult::Set => {}
+    }
+}
+
+fn folders(client: &Arc<dyn SDSyncRequestClient>, serial: &str) {
+    match client.list_folders(serial).unwrap_or_else(|err| fail(&format!("Failed to list folders: {:?}", err))) {
+        ListFoldersResult::DeviceNotFound => fail("Device not found"),
+        ListFoldersResult::Folders(folders) => {
+            for folder in folders {
+                println!("{}\t{}\t{}\t{}",
+                         folder.id,
+                         folder.name,
+                         folder.button_count,
+                         if folder.orphaned { "orphaned" } else { "used" }
+                );
+            }
+        }
+    }
+}
+
+fn folder_tree(client: &Arc<dyn SDSyncRequestClient>, serial: &str) {
+    fn print_node(node: &FolderNode, depth: usize) {
+        println!("{}{}\t{}\t{} buttons{}",
+                 "  ".repeat(depth),
+                 node.key.map_or("-".to_string(), |x| x.to_string()),
+                 node.name,
+                 node.button_count,
+                 if node.reference { ", see above" } else { "" }
+        );
+
+        for child in &node.children {
+            print_node(child, depth + 1);
+        }
+    }
+
+    match client.folder_tree(serial, false).unwrap_or_else(|err| fail(&format!("Failed to get folder tree: {:?}", err))) {
+        GetFolderTreeResult::DeviceNotFound => fail("Device not found"),
+        GetFolderTreeResult::Tree(tree) => {
+            print_node(&tree.root, 0);
+
+            for node in &tree.orphaned {
+                println!("orphaned:");
+                print_node(node, 1);
+            }
+        }
+    }
+}
+
+fn find_buttons(client: &Arc<dyn SDSyncRequestClient>, serial: &str, args: &[String]) {
+    let mut component = None;
+    let mut query = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--component" => component = Some(args.next().cloned().unwrap_or_else(|| usage_error("Missing argument: name"))),
+            text => query = Some(text.to_string()),
+        }
+    }
+
+    match client.find_buttons(serial, component.as_deref(), query.as_deref()).unwrap_or_else(|err| fail(&format!("Failed to find buttons: {:?}", err))) {
+        FindButtonsResult::DeviceNotFound => fail("Device not found"),
+        FindButtonsResult::Found(buttons) => {
+            for found in buttons {
+                let path = found.path.iter()
+                    .map(|x| x.name.as_str())
+                    .collect::<Vec<&str>>();
+
+                println!("/{}\t{}\t{}",
+                         path.join("/"),
+                         found.key,
+                         found.button.component_names().join(", ")
+                );
+            }
+        }
+    }
+}
+
+fn rename_folder(client: &Arc<dyn SDSyncRequestClient>, serial: &str, id: &str, name: &str) {
+    match client.rename_folder(serial, id, name).unwrap_or_else(|err| fail(&format!("Failed to rename folder: {:?}", err))) {
+        RenameFolderResult::DeviceNotFound => fail("Device not found"),
+        RenameFolderResult::FolderNotFound => fail("Folder not found"),
+        RenameFolderResult::Renamed => {}
+    }
+}
+
+fn delete_folder(client: &Arc<dyn SDSyncRequestClient>, serial: &str, id: &str) {
+    match client.delete_folder(serial, id).unwrap_or_else(|err| fail(&format!("Failed to delete folder: {:?}", err))) {
+        DeleteFolderResult::DeviceNotFound => fail("Device not found"),
+        DeleteFolderResult::FolderNotFound => fail("Folder not found"),
+        DeleteFolderResult::Deleted => {}
+    }
+}
+
+fn cleanup_folders(client: &Arc<dyn SDSyncRequestClient>, serial: &str) {
+    match client.cleanup_folders(serial).unwrap_or_else(|err| fail(&format!("Failed to clean up folders: {:?}", err))) {
+        CleanupFoldersResult::DeviceNotFound => fail("Device not found"),
+        CleanupFoldersResult::Removed(ids) => {
+            for id in ids {
+                println!("{}", id);
+            }
+        }
+    }
+}
+
+fn export_config(client: &Arc<dyn SDSyncRequestClient>, serial: &str, path: &str, include_images: bool) {
+    match client.export_device_config(serial, include_images).unwrap_or_else(|err| fail(&format!("Failed to export device config: {:?}", err))) {
+        ExportDeviceConfigResult::DeviceNotFound => fail("Device not found"),
+        ExportDeviceConfigResult::FailedToCompress => fail("Failed to compress data"),
+        ExportDeviceConfigResult::Exported(config) => {
+            if let Err(err) = fs::write(path, config) {
+                fail(&format!("Failed to write file: {}", err));
+            }
+        }
+    }
+}
+
+fn import_config(client: &Arc<dyn SDSyncRequestClient>, serial: &str, path: &str, dry_run: bool) {
+    let file = fs::read_to_string(path).unwrap_or_else(|err| fail(&format!("Failed to read file: {}", err)));
+
+    match client.import_device_config(serial, file, dry_run).unwrap_or_else(|err| fail(&format!("Failed to import device config: {:?}", err))) {
+        ImportDeviceConfigResult::DeviceNotFound => fail("Device not found"),
+        ImportDeviceConfigResult::InvalidConfig => fail("Invalid device config"),
+        ImportDeviceConfigResult::FailedToSave => fail("Failed to save config"),
+        ImportDeviceConfigResult::Imported => {}
+        ImportDeviceConfigResult::Reviewed(review) => {
+            for component in review.unknown_components {
+                println!("Unknown component: {}", component);
+            }
+
+            for module in review.unknown_modules {
+                println!("Unknown module: {}", module);
+            }
+
+            for image in review.missing_images {
+                println!("Missing image: {}", image);
+            }
+
+            for change in review.changes {
+                println!("{:?}", change);
+            }
+        }
+    }
+}
+
+fn diff_config(client: &Arc<dyn SDSyncRequestClient>, serial: &str, path: &str) {
+    let file = fs::read_to_string(path).unwrap_or_else(|err| fail(&format!("Failed to read file: {}", err)));
+
+    match client.diff_device_config(serial, file).unwrap_or_else(|err| fail(&format!("Failed to diff device config: {:?}", err))) {
+        DiffDeviceConfigResult::DeviceNotFound => fail("Device not found"),
+        DiffDeviceConfigResult::InvalidConfig => fail("Invalid device config"),
+        DiffDeviceConfigResult::Diff(diff) => {
+            if let Some((from, to)) = diff.brightness {
+                println!("brightness: {} -> {}", from, to);
+            }
+
+            if let Some(name) = diff.root_name {
+                println!("root renamed: {}", name);
+            }
+
+            for folder in diff.folders_added {
+                println!("+ folder {} ({})", folder.name, folder.id);
+            }
+
+            for folder in diff.folders_removed {
+                println!("- folder {} ({})", folder.name, folder.id);
+            }
+
+            for button in diff.buttons {
+                let location = format!("{}:{}", button.folder.as_deref().unwrap_or("root"), button.key);
+
+                match button.change {
+                    ButtonChange::Added(_) => println!("+ {}", location),
+                    ButtonChange::Removed(_) => println!("- {}", location),
+                    ButtonChange::Changed(components) => {
+                        println!("~ {}", location);
+
+                        for component in components {
+                            match component {
+                                ComponentChange::Added { component, .. } => println!("    + {}", component),
+                                ComponentChange::Removed { component, .. } => println!("    - {}", component),
+                                ComponentChange::Changed { component, from, to } => println!("    ~ {}: {} -> {}", component, from, to),
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn restore_config(client: &Arc<dyn SDSyncRequestClient>, serial: &str, backup: &str) {
+    let backup = backup.parse::<usize>().unwrap_or_else(|_| usage_error("Backup number should be a number"));
+
+    match client.restore_device_config_backup(serial, backup).unwrap_or_else(|err| fail(&format!("Failed to restore device config: {:?}", err))) {
+        RestoreDeviceConfigBackupResult::DeviceNotFound => fail("Device not found"),
+        RestoreDeviceConfigBackupResult::BackupNotFound => fail("Backup not found"),
+        RestoreDeviceConfigBackupResult::FailedToRestore => fail("Failed to restore config"),
+        RestoreDeviceConfigBackupResult::Restored => {}
+    }
+}
+
+fn export_all(client: &Arc<dyn SDSyncRequestClient>, path: &str) {
+    match client.export_all_configs().unwrap_or_else(|err| fail(&format!("Failed to export configs: {:?}", err))) {
+        ExportAllConfigsResult::FailedToExport => fail("Failed to export configs"),
+        ExportAllConfigsResult::Exported(bundle) => {
+            let bundle = base64::decode(bundle).unwrap_or_else(|_| fail("Daemon sent invalid archive"));
+
+            if let Err(err) = fs::write(path, bundle) {
+                fail(&format!("Failed to write file: {}", err));
+            }
+        }
+    }
+}
+
+fn import_all(client: &Arc<dyn SDSyncRequestClient>, path: &str) {
+    let file = fs::read(path).unwrap_or_else(|err| fail(&format!("Failed to read file: {}", err)));
+
+    match client.import_all_configs(base64::encode(file)).unwrap_or_else(|err| fail(&format!("Failed to import configs: {:?}", err))) {
+        ImportAllConfigsResult::InvalidBundle => fail("Invalid archive"),
+        ImportAllConfigsResult::FailedToSave => fail("Failed to save configs"),
+        ImportAllConfigsResult::Imported(serials) => {
+            for serial in serials {
+                println!("{}", serial);
+            }
+        }
+    }
+}
+
+fn apply_layout(client: &Arc<dyn SDSyncRequestClient>, serial: &str, path: &str, dry_run: bool) {
+    let file = fs::read_to_string(path).unwrap_or_else(|err| fail(&format!("Failed to read file: {}", err)));
+
+    match client.apply_layout(serial, file, dry_run).unwrap_or_else(|err| fail(&format!("Failed to apply layout: {:?}", err))) {
+        ApplyLayoutResult::DeviceNotFound => fail("Device not found"),
+        ApplyLayoutResult::InvalidLayout(reason) => fail(&format!("Invalid layout: {}", reason)),
+        ApplyLayoutResult::FailedToSave => fail("Failed to save config"),
+        ApplyLayoutResult::Applied(changes) => {
+            for change in changes {
+                println!("{:?}", change);
+            }
+        }
+    }
+}
+
+fn export_layout(client: &Arc<dyn SDSyncRequestClient>, serial: &str, path: &str) {
+    match client.export_layout(serial).unwrap_or_else(|err| fail(&format!("Failed to export layout: {:?}", err))) {
+        ExportLayoutResult::DeviceNotFound => fail("Device not found"),
+        ExportLayoutResult::FailedToExport => fail("Failed to export layout"),
+        ExportLayoutResult::Exported(layout) => {
+            if let Err(err) = fs::write(path, layout) {
+                fail(&format!("Failed to write file: {}", err));
+            }
+        }
+    }
+}
+
+fn instantiate_template(client: &Arc<dyn SDSyncRequestClient>, serial: &str, path: &str, rest: &[String]) {
+    let file = fs::read_to_string(path).unwrap_or_else(|err| fail(&format!("Failed to read file: {}", err)));
+
+    let mut variables = HashMap::new();
+    let mut dry_run = false;
+
+    for arg in rest {
+        if arg == "--dry-run" {
+            dry_run = true;
+        } else if let Some((name, value)) = arg.split_once('=') {
+            variables.insert(name.to_string(), value.to_string());
+        } else {
+            usage_error(&format!("Expected NAME=value, got '{}'", arg));
+        }
+    }
+
+    match client.instantiate_template(serial, file, variables, dry_run).unwrap_or_else(|err| fail(&format!("Failed to instantiate template: {:?}", err))) {
+        InstantiateTemplateResult::DeviceNotFound => fail("Device not found"),
+        InstantiateTemplateResult::InvalidTemplate(reason) => fail(&format!("Invalid template: {}", reason)),
+        InstantiateTemplateResult::MissingVariables(names) => fail(&format!("Missing values for variables: {}", names.join(", "))),
+        InstantiateTemplateResult::FailedToSave => fail("Failed to save config"),
+        InstantiateTemplateResult::Applied(changes) => {
+            for change in changes {
+                println!("{:?}", change);
+            }
+        }
+    }
+}
+
+fn presets(client: &Arc<dyn SDSyncRequestClient>) {
+    let presets = client.list_presets().unwrap_or_else(|err| fail(&format!("Failed to list presets: {:?}", err)));
+
+    let mut names: Vec<&String> = presets.keys().collect();
+    names.sort();
+
+    for name in names {
+        let preset = &presets[name];
+        println!("{}	{}	{}", name, preset.component, preset.fields.keys().cloned().collect::<Vec<String>>().join(", "));
+    }
+}
+
+fn save_preset(client: &Arc<dyn SDSyncRequestClient>, name: &str, serial: &str, key: &str, component: &str, rest: &[String]) {
+    let key = key.parse::<u8>().unwrap_or_else(|_| usage_error("Key index should be a number"));
+    let propagate = rest.iter().any(|x| x == "--propagate");
+    let fields: Vec<String> = rest.iter().filter(|x| *x != "--propagate").cloned().collect();
+
+    let button = match client.get_button(serial, key).unwrap_or_else(|err| fail(&format!("Failed to get button: {:?}", err))) {
+        GetButtonResult::DeviceNotFound => fail("Device not found"),
+        GetButtonResult::NoButton => fail("No button on that key"),
+        GetButtonResult::Button(button) => button,
+    };
+
+    let preset = ComponentPreset::from_button(&button, component, &fields).unwrap_or_else(|| fail("Button doesn't have that component"));
+
+    match client.save_preset(name, preset, propagate).unwrap_or_else(|err| fail(&format!("Failed to save preset: {:?}", err))) {
+        SavePresetResult::Saved(updated) => {
+            if propagate {
+                println!("Updated {} buttons", updated);
+            }
+        }
+    }
+}
+
+fn remove_preset(client: &Arc<dyn SDSyncRequestClient>, name: &str) {
+    match client.remove_preset(name).unwrap_or_else(|err| fail(&format!("Failed to remove preset: {:?}", err))) {
+        RemovePresetResult::PresetNotFound => fail("Preset not found"),
+        RemovePresetResult::Removed => {}
+    }
+}
+
+fn apply_preset(client: &Arc<dyn SDSyncRequestClient>, serial: &str, key: &str, name: &str) {
+    let key = key.parse::<u8>().unwrap_or_else(|_| usage_error("Key index should be a number"));
+
+    match client.apply_preset(serial, key, name).unwrap_or_else(|err| fail(&format!("Failed to apply preset: {:?}", err))) {
+        ApplyPresetResult::DeviceNotFound => fail("Device not found"),
+        ApplyPresetResult::PresetNotFound => fail("Preset not found"),
+        ApplyPresetResult::NoButton => fail("No button on that key"),
+        ApplyPresetResult::Incompatible => fail("Button doesn't have component that preset is for"),
+        ApplyPresetResult::Applied => {}
+    }
+}
+
+fn global_settings(client: &Arc<dyn SDSyncRequestClient>, name: &str, settings: Option<&str>) {
+    if let Some(settings) = settings {
+        let settings = serde_json::from_str(settings).unwrap_or_else(|err| usage_error(&format!("Invalid JSON: {}", err)));
+
+        match client.set_global_plugin_settings(name, settings).unwrap_or_else(|err| fail(&format!("Failed to set settings: {:?}", err))) {
+            SetGlobalPluginSettingsResult::Set => {}
+        }
+    } else {
+        match client.get_global_plugin_settings(name).unwrap_or_else(|err| fail(&format!("Failed to get settings: {:?}", err))) {
+            GetGlobalPluginSettingsResult::NotFound => fail("Settings not found"),
+            GetGlobalPluginSettingsResult::Settings(settings) => println!("{}", serde_json::to_string_pretty(&settings).unwrap()),
+        }
+    }
+}
+
+fn send_message(client: &Arc<dyn SDSyncRequestClient>, topic: &str, data: &str, serial: Option<&str>) {
+    let data = serde_json::from_str(data).unwrap_or_else(|err| usage_error(&format!("Invalid JSON: {}", err)));
+
+    match client.send_module_message(topic, data, serial).unwrap_or_else(|err| fail(&format!("Failed to send message: {:?}", err))) {
+        SendModuleMessageResult::DeviceNotFound => fail("Device not found"),
+        SendModuleMessageResult::Sent(count) => println!("Received by {} subscriptions", count),
+    }
+}
+
+fn plugin_dependencies(client: &Arc<dyn SDSyncRequestClient>) {
+    for module in client.get_plugin_dependencies().unwrap_or_else(|err| fail(&format!("Failed to get plugin dependencies: {:?}", err))) {
+        println!("{} {}", module.name, module.version);
+
+        for dependency in module.dependencies {
+            let resolved = dependency.resolved_version.unwrap_or_else(|| "missing".to_string());
+            println!("  {} {} -> {}", dependency.name, dependency.version, resolved);
+        }
+    }
+}
+
+fn reenable_plugin(client: &Arc<dyn SDSyncRequestClient>, name: &str) {
+    match client.reenable_plugin(name).unwrap_or_else(|err| fail(&format!("Failed to enable plugin: {:?}", err))) {
+        ReenablePluginResult::PluginNotFound => fail("Plugin not found"),
+        ReenablePluginResult::NotDisabled => println!("Plugin wasn't disabled"),
+        ReenablePluginResult::Reenabled => {}
+    }
+}
+
+fn plugin_requests(client: &Arc<dyn SDSyncRequestClient>) {
+    for name in client.list_plugin_requests().unwrap_or_else(|err| fail(&format!("Failed to list plugin requests: {:?}", err))) {
+        println!("{}", name);
+    }
+}
+
+fn plugin_request(client: &Arc<dyn SDSyncRequestClient>, name: &str, data: Option<&str>) {
+    if !name.contains('/') {
+        usage_error("Plugin request names are namespaced as plugin_name/request_name");
+    }
+
+    let data = data.map(|x| serde_json::from_str(x).unwrap_or_else(|err| usage_error(&format!("Invalid JSON: {}", err))));
+
+    let response = client.send_packet(SocketPacket {
+        ty: name.to_string(),
+        requester: None,
+        data
+    }).unwrap_or_else(|err| fail(&format!("Failed to send request: {:?}", err)));
+
+    if let Some(data) = response.data {
+        println!("{}", serde_json::to_string_pretty(&data).unwrap());
+    }
+}
+
+fn move_button(client: &Arc<dyn SDSyncRequestClient>, serial: &str, from: &str, to: &str, swap: bool) {
+    let from = from.parse::<u8>().unwrap_or_else(|_| usage_error("Key index should be a number"));
+    let to = to.parse::<u8>().unwrap_or_else(|_| usage_error("Key index should be a number"));
+
+    match client.move_button(serial, from, to, swap).unwrap_or_else(|err| fail(&format!("Failed to move button: {:?}", err))) {
+        MoveButtonResult::DeviceNotFound => fail("Device not found"),
+        MoveButtonResult::FailedToMove => fail("No button to move, or destination is taken (use --swap)"),
+        MoveButtonResult::Moved => {}
+    }
+}
+
+fn copy_button(client: &Arc<dyn SDSyncRequestClient>, serial: &str, key: &str, path: &str) {
+    let key = key.parse::<u8>().unwrap_or_else(|_| usage_error("Key index should be a number"));
+
+    match client.copy_button(serial, key).unwrap_or_else(|err| fail(&format!("Failed to copy button: {:?}", err))) {
+        CopyButtonResult::DeviceNotFound => fail("Device not found"),
+        CopyButtonResult::NoButton => fail("No button on that key"),
+        CopyButtonResult::Copied(button) => {
+            if let Err(err) = fs::write(path, serde_json::to_string_pretty(&button).unwrap()) {
+                fail(&format!("Failed to write file: {}", err));
+            }
+        }
+    }
+}
+
+fn paste_button(client: &Arc<dyn SDSyncRequestClient>, serial: &str, key: &str, path: &str) {
+    let key = key.parse::<u8>().unwrap_or_else(|_| usage_error("Key index should be a number"));
+
+    let file = fs::read_to_string(path).unwrap_or_else(|err| fail(&format!("Failed to read file: {}", err)));
+    let button: PortableButton = serde_json::from_str(&file).unwrap_or_else(|err| fail(&format!("Invalid snippet: {}", err)));
+
+    match client.paste_portable_button(serial, key, button).unwrap_or_else(|err| fail(&format!("Failed to paste button: {:?}", err))) {
+        PasteButtonResult::DeviceNotFound => fail("Device not found"),
+        PasteButtonResult::FailedToPaste => fail("Failed to paste button"),
+        PasteButtonResult::Pasted => {}
+    }
+}
+
+fn images(client: &Arc<dyn SDSyncRequestClient>, serial: &str) {
+    match client.list_images(serial).unwrap_or_else(|err| fail(&format!("Failed to list images: {:?}", err))) {
+        ListImagesResult::DeviceNotFound => fail("Device not found"),
+        ListImagesResult::Images(images) => {
+            let mut images: Vec<_> = images.into_iter().collect();
+            images.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            for (identifier, image) in images {
+                println!("{}\t{}x{}\t{} bytes{}", identifier, image.width, image.height, image.size, if image.animated { "\tanimated" } else { "" });
+            }
+        }
+    }
+}
+
+fn add_image(client: &Arc<dyn SDSyncRequestClient>, serial: &str, path: &str) {
+    let bytes = fs::read(path).unwrap_or_else(|err| fail(&format!("Failed to read image: {}", err)));
+
+    // Big images are sent in chunks, so they don't have to fit into a single packet
+    if bytes.len() > TRANSFER_CHUNK_SIZE {
+        return upload_image(client, serial, &bytes);
+    }
+
+    match client.add_image(serial, &base64::encode(bytes)).unwrap_or_else(|err| fail(&format!("Failed to add image: {:?}", err))) {
+        AddImageResult::DeviceNotFound => fail("Device not found"),
+        AddImageResult::InvalidData => fail("Image format isn't supported"),
+        AddImageResult::Added(identifier) => println!("{}", identifier),
+    }
+}
+
+fn upload_image(client: &Arc<dyn SDSyncRequestClient>, serial: &str, bytes: &[u8]) {
+    let upload_id = match client.start_image_upload(serial).unwrap_or_else(|err| fail(&format!("Failed to start upload: {:?}", err))) {
+        StartImageUploadResult::DeviceNotFound => fail("Device not found"),
+        StartImageUploadResult::Started(upload_id) => upload_id,
+    };
+
+    for chunk in bytes.chunks(TRANSFER_CHUNK_SIZE) {
+        match client.upload_image_chunk(&upload_id, &base64::encode(chunk)).unwrap_or_else(|err| fail(&format!("Failed to upload image: {:?}", err))) {
+            UploadImageChunkResult::UploadNotFound => fail("Upload has timed out"),
+            UploadImageChunkResult::InvalidData => fail("Failed to upload image"),
+            UploadImageChunkResult::TooLarge => fail("Image is too large"),
+            UploadImageChunkResult::Received(_) => {}
+        }
+    }
+
+    match client.finish_image_upload(&upload_id).unwrap_or_else(|err| fail(&format!("Failed to upload image: {:?}", err))) {
+        FinishImageUploadResult::UploadNotFound => fail("Upload has timed out"),
+        FinishImageUploadResult::DeviceNotFound => fail("Device not found"),
+        FinishImageUploadResult::InvalidData => fail("Image format isn't supported"),
+        FinishImageUploadResult::Added(identifier) => println!("{}", identifier),
+    }
+}
+
+fn get_image(client: &Arc<dyn SDSyncRequestClient>, serial: &str, identifier: &str, path: &str) {
+    let mut bytes = vec![];
+
+    loop {
+        match client.get_image(serial, identifier, bytes.len()).unwrap_or_else(|err| fail(&format!("Failed to get image: {:?}", err))) {
+            GetImageResult::DeviceNotFound => fail("Device not found"),
+            GetImageResult::NotFound => fail("Image not found"),
+            GetImageResult::Chunk { data, total_size, .. } => {
+                let chunk = base64::decode(data).unwrap_or_else(|_| fail("Received invalid image data"));
+
+                if chunk.is_empty() && bytes.len() < total_size {
+                    fail("Image changed during download");
+                }
+
+                bytes.extend(chunk);
+
+                if bytes.len() >= total_size {
+                    break;
+                }
+            }
+        }
+    }
+
+    fs::write(path, bytes).unwrap_or_else(|err| fail(&format!("Failed to write image: {}", err)));
+}
+
+fn cleanup_images(client: &Arc<dyn SDSyncRequestClient>, serial: Option<&str>) {
+    match client.cleanup_unused_images(serial).unwrap_or_else(|err| fail(&format!("Failed to clean up images: {:?}", err))) {
+        CleanupUnusedImagesResult::DeviceNotFound => fail("Device not found"),
+        CleanupUnusedImagesResult::FailedToCleanup(err) => fail(&format!("Failed to clean up images: {}", err)),
+        CleanupUnusedImagesResult::Cleaned(cleanup) => {
+            for identifier in &cleanup.removed {
+                println!("{}", identifier);
+            }
+
+            println!("Removed {} images, reclaimed {} bytes", cleanup.removed.len(), cleanup.reclaimed_bytes);
+        }
+    }
+}
+
+fn status(client: &Arc<dyn SDSyncRequestClient>) {
+    let status = client.daemon_status().unwrap_or_else(|err| fail(&format!("Failed to get daemon status: {:?}", err)));
+
+    println!("Version: {} (socket API {})", status.version, status.socket_api);
+    println!("Uptime: {}s", status.uptime);
+    println!("Safe mode: {}", if status.safe_mode { "on" } else { "off" });
+    println!("Device configs: {}", status.paths.device_configs.display());
+    println!("Plugins: {}", status.paths.plugins.display());
+
+    println!("\nDevices:");
+    for device in status.devices {
+        println!("  {}\t{}\t{}", device.serial_number, device.device_type, if device.online { "online" } else { "offline" });
+    }
+
+    println!("\nModules:");
+    for module in status.modules {
+        println!("  {}\t{}\t{}", module.name, module.version, if module.disabled { "disabled" } else if module.plugin { "plugin" } else { "built-in" });
+    }
+
+    if !status.degraded.is_empty() {
+        println!("\nProblems:");
+        for component in status.degraded {
+            match component {
+                DegradedComponent::SafeMode => println!("  Running in safe mode"),
+                DegradedComponent::PluginDisabled(name) => println!("  Plugin '{}' was disabled after panicking", name),
+                DegradedComponent::PluginFailed { name, reason } => println!("  Plugin '{}' failed to load: {}", name, reason),
+                DegradedComponent::DeviceOffline(serial) => println!("  Device {} is offline", serial),
+            }
+        }
+    }
+}
+
+fn shutdown(client: &Arc<dyn SDSyncRequestClient>) {
+    match client.shutdown_daemon().unwrap_or_else(|err| fail(&format!("Failed to shut down daemon: {:?}", err))) {
+        ShutdownDaemonResult::NotAllowed => fail("Shutdown requests aren't allowed in daemon config"),
+        ShutdownDaemonResult::ShuttingDown => {}
+    }
+}
+
+fn restart(client: &Arc<dyn SDSyncRequestClient>) {
+    match client.restart_daemon().unwrap_or_else(|err| fail(&format!("Failed to restart daemon: {:?}", err))) {
+        RestartDaemonResult::NotAllowed => fail("Restart requests aren't allowed in daemon config"),
+        RestartDaemonResult::Restarting => {}
+    }
+}
+
+fn clients(client: &Arc<dyn SDSyncRequestClient>) {
+    let clients = client.list_clients().unwrap_or_else(|err| fail(&format!("Failed to list clients: {:?}", err)));
+
+    for info in clients {
+        println!("{}\t{}\t{}\t{}\t{} requests\t{}{}",
+                 info.id,
+                 info.transport,
+                 info.name.as_deref().unwrap_or("unidentified"),
+                 info.version.as_deref().unwrap_or("-"),
+                 info.request_count,
+                 if info.has_session { "events, session" } else if info.receives_events { "events" } else { "no events" },
+                 if info.read_only { ", read-only" } else { "" }
+        );
+    }
+}
+
+fn disconnect_client(client: &Arc<dyn SDSyncRequestClient>, id: &str) {
+    let id = id.parse::<u64>().unwrap_or_else(|_| usage_error("Client ID should be a number"));
+
+    match client.disconnect_client(id).unwrap_or_else(|err| fail(&format!("Failed to disconnect client: {:?}", err))) {
+        DisconnectClientResult::ClientNotFound => fail("Client not found"),
+        DisconnectClientResult::Disconnected => {}
+    }
+}
+
+fn logs(client: &Arc<dyn SDSyncRequestClient>, args: &[String]) {
+    let mut limit = 100;
+    let mut filter = LogFilter::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--device" => filter.device = Some(args.next().cloned().unwrap_or_else(|| usage_error("Missing argument: serial"))),
+            "--module" => filter.module = Some(args.next().cloned().unwrap_or_else(|| usage_error("Missing argument: name"))),
+            "--level" => filter.level = Some(args.next().cloned().unwrap_or_else(|| usage_error("Missing argument: level"))),
+            count => limit = count.parse::<usize>().unwrap_or_else(|_| usage_error("Count should be a number")),
+        }
+    }
+
+    let records = client.get_recent_logs(limit, filter).unwrap_or_else(|err| fail(&format!("Failed to get logs: {:?}", err)));
+
+    for record in records {
+        println!("{}	{}	{}	{}	{}",
+                 record.timestamp,
+                 record.level,
+                 record.device.as_deref().unwrap_or("-"),
+                 record.module.as_deref().unwrap_or("-"),
+                 record.message
+        );
+    }
+}
+
+fn undo(client: &Arc<dyn SDSyncRequestClient>, serial: &str) {
+    match client.undo(serial).unwrap_or_else(|err| fail(&format!("Failed to undo: {:?}", err))) {
+        UndoResult::DeviceNotFound => fail("Device not found"),
+        UndoResult::NothingToUndo => fail("Nothing to undo"),
+        UndoResult::DifferentScreen => fail("Edited panel isn't on screen"),
+        UndoResult::FailedToApply => fail("Failed to restore button"),
+        UndoResult::Undone(key) => println!("{}", key),
+    }
+}
+
+fn redo(client: &Arc<dyn SDSyncRequestClient>, serial: &str) {
+    match client.redo(serial).unwrap_or_else(|err| fail(&format!("Failed to redo: {:?}", err))) {
+        RedoResult::DeviceNotFound => fail("Device not found"),
+        RedoResult::NothingToRedo => fail("Nothing to redo"),
+        RedoResult::DifferentScreen => fail("Edited panel isn't on screen"),
+        RedoResult::FailedToApply => fail("Failed to change button"),
+        RedoResult::Redone(key) => println!("{}", key),
+    }
+}
+
+/// Amount of key columns for device type, [None] if unknown
+fn columns_for(device_type: &DeviceType) -> Option<u32> {
+    match device_type {
+        DeviceType::Mini => Some(3),
+        DeviceType::Original | DeviceType::OriginalV2 | DeviceType::MK2 => Some(5),
+        DeviceType::XL => Some(8),
+        DeviceType::Unknown => None,
+    }
+}
+
+fn screenshot(client: &Arc<dyn SDSyncRequestClient>, serial: &str, path: &str) {
+    let device = match client.get_device(serial).unwrap_or_else(|err| fail(&format!("Failed to get device: {:?}", err))) {
+        GetDeviceResult::Found(device) => device,
+        GetDeviceResult::NotFound => fail("Device not found"),
+    };
+
+    let images = match client.get_button_images(serial).unwrap_or_else(|err| fail(&format!("Failed to get button images: {:?}", err))) {
+        GetButtonImagesResult::Images(images) => images,
+        GetButtonImagesResult::DeviceNotFound => fail("Device not found"),
+    };
+
+    let decoded: Vec<(u8, DynamicImage)> = images.into_iter()
+        .filter_map(|(key, data)| {
+            let bytes = base64::decode(data).ok()?;
+            let image = Reader::new(Cursor::new(bytes)).with_guessed_format().ok()?.decode().ok()?;
+            Some((key, image))
+        })
+        .collect();
+
+    let (key_width, key_height) = decoded.first().map(|(_, x)| x.dimensions()).unwrap_or((72, 72));
+    let max_key = decoded.iter().map(|(key, _)| *key as u32).max().unwrap_or(0);
+
+    let columns = columns_for(&device.device_type).unwrap_or_else(|| ((max_key + 1) as f32).sqrt().ceil().max(1.0) as u32);
+    let rows = (max_key / columns) + 1;
+
+    let gap = 4;
+    let mut result = DynamicImage::new_rgba8(columns * (key_width + gap) + gap, rows * (key_height + gap) + gap);
+
+    for (key, image) in decoded {
+        let key = key as u32;
+        let x = gap + (key % columns) * (key_width + gap);
+        let y = gap + (key / columns) * (key_height + gap);
+
+        result.copy_from(&image, x, y).ok();
+    }
+
+    if let Err(err) = result.save_with_format(path, ImageFormat::Png) {
+        fail(&format!("Failed to save screenshot: {}", err));
+    }
+}
+
+fn preview(client: &Arc<dyn SDSyncRequestClient>, serial: &str, key: &str, button_path: &str, image_path: &str, size: Option<&str>) {
+    let key = key.parse::<u8>().unwrap_or_else(|_| usage_error("Key index should be a number"));
+    let size = size.map(|x| x.parse::<u32>().unwrap_or_else(|_| usage_error("Size should be a number"))).unwrap_or(0);
+
+    let file = fs::read_to_string(button_path).unwrap_or_else(|err| fail(&format!("Failed to read file: {}", err)));
+    let button = serde_json::from_str(&file).unwrap_or_else(|err| fail(&format!("Failed to parse button: {}", err)));
+
+    let data = match client.render_button_preview(serial, key, button, (size, size)).unwrap_or_else(|err| fail(&format!("Failed to render button: {:?}", err))) {
+        RenderButtonPreviewResult::DeviceNotFound => fail("Device not found"),
+        RenderButtonPreviewResult::NoRenderer => fail("Button doesn't have renderer component"),
+        RenderButtonPreviewResult::Image(data) => data,
+    };
+
+    let bytes = base64::decode(data).unwrap_or_else(|err| fail(&format!("Failed to decode image: {}", err)));
+
+    if let Err(err) = fs::write(image_path, bytes) {
+        fail(&format!("Failed to save preview: {}", err));
+    }
+}
+
+fn listen_events(client: Arc<dyn SDSyncEventClient>) {
+    loop {
+        match client.get_event() {
+            Ok(event) => {
+                if let Ok(json) = serde_json::to_string(&event) {
+                    println!("{}", json);
+                }
+            }
+
+            Err(err) => fail(&format!("Failed to read event: {:?}", err)),
+        }
+    }
+}
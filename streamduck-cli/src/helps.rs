@@ -8,7 +8,7 @@ pub const COMMANDS: &'static [(&'static str, &'static str)] = &[
     ("config save all", "- saves all device configs"),
     ("config save", "[<serial>] - saves device config for specific/selected device"),
     ("config import", "[<serial>] <path> - imports device config from a file for specific/selected device"),
-    ("config export", "[<serial>] <path> - exports device config into a file for specific/selected device"),
+    ("config export", "[<serial>] <path> [with-images] - exports device config into a file for specific/selected device, optionally embedding used images"),
     ("brightness", "[<serial>] <0-100> - sets brightness for device"),
     ("back", "[<serial>] [drop] - navigates back, even if there's no button for that, if drop is included, goes to root screen"),
     ("press", "[<serial>] <key index> - simulates a press on a button"),
@@ -1,18 +1,29 @@
 mod prompt;
 mod helps;
+mod commands;
+mod service;
 
 use std::env;
 use std::sync::Arc;
-use streamduck_client::SDSyncRequestClient;
+use streamduck_client::{SDSyncEventClient, SDSyncRequestClient};
 use crate::prompt::prompt;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
+    // Service management doesn't need daemon to be running
+    if args.get(1).map_or(false, |x| x == "service") {
+        service::run(&args[2..]);
+        return;
+    }
+
     let client = get_client(&args);
+    client.identify("streamduck-cli", env!("CARGO_PKG_VERSION"), false).ok();
 
     if args.len() <= 1 {
         prompt(client);
+    } else {
+        commands::run(client, get_event_client, &args[1..]);
     }
 }
 
@@ -24,4 +35,14 @@ fn get_client(_args: &Vec<String>) -> Arc<dyn SDSyncRequestClient> {
 #[cfg(target_family = "unix")]
 fn get_client(_args: &Vec<String>) -> Arc<dyn SDSyncRequestClient> {
     streamduck_client::unix::UnixClient::new().expect("Failed to connect to daemon, is it up?").as_request()
-}
\ No newline at end of file
+}
+
+#[cfg(target_family = "windows")]
+fn get_event_client() -> Arc<dyn SDSyncEventClient> {
+    streamduck_client::windows::WinEventClient::new().expect("Failed to connect to daemon, is it up?")
+}
+
+#[cfg(target_family = "unix")]
+fn get_event_client() -> Arc<dyn SDSyncEventClient> {
+    streamduck_client::unix::UnixClient::new_for_events().expect("Failed to connect to daemon, is it up?")
+}
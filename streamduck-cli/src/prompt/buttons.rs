@@ -1,6 +1,7 @@
 use std::str::Split;
 use streamduck_client::daemon::daemon_data::buttons::{AddComponentResult, AddComponentValueResult, ClearButtonResult, GetComponentValuesResult, NewButtonFromComponentResult, NewButtonResult, RemoveComponentResult, RemoveComponentValueResult, SetComponentValueResult};
 use streamduck_core::modules::components::{map_ui_path_values, UIFieldType, UIFieldValue, UIPathValue};
+use streamduck_core::thread::rendering::color_to_hex;
 use crate::prompt::ClientRef;
 use crate::prompt::utils::parse_string_to_value;
 
@@ -40,6 +41,7 @@ pub fn button_from(client: ClientRef, current_sn: &str, mut args: Split<&str>) {
                         NewButtonFromComponentResult::DeviceNotFound => println!("button from: Device not found"),
                         NewButtonFromComponentResult::FailedToCreate => println!("button from: Failed to create button"),
                         NewButtonFromComponentResult::ComponentNotFound => println!("button from: Component not found"),
+                        NewButtonFromComponentResult::Incompatible(err) => println!("button from: Component can't be added, {:?}", err),
                         NewButtonFromComponentResult::Created => {
                             client.commit_changes(current_sn).expect("Failed to commit changes");
                             println!("button from: Created button");
@@ -112,6 +114,7 @@ pub fn button_add_component(client: ClientRef, current_sn: &str, mut args: Split
                 match result {
                     AddComponentResult::DeviceNotFound => println!("button component add: Device not found"),
                     AddComponentResult::FailedToAdd => println!("button component add: Failed to add"),
+                    AddComponentResult::Incompatible(err) => println!("button component add: Component can't be added, {:?}", err),
                     AddComponentResult::Added => {
                         client.commit_changes(current_sn).expect("Failed to commit changes");
                         println!("button component add: Added");
@@ -137,6 +140,7 @@ pub fn button_remove_component(client: ClientRef, current_sn: &str, mut args: Sp
                 match result {
                     RemoveComponentResult::DeviceNotFound => println!("button component remove: Device not found"),
                     RemoveComponentResult::FailedToRemove => println!("button component remove: Failed to remove"),
+                    RemoveComponentResult::Incompatible(err) => println!("button component remove: Component can't be removed, {:?}", err),
                     RemoveComponentResult::Removed => {
                         client.commit_changes(current_sn).expect("Failed to commit changes");
                         println!("button component remove: Removed");
@@ -259,6 +263,11 @@ pub fn button_component_params_set(client: ClientRef, current_sn: &str, mut args
                                     match result {
                                         SetComponentValueResult::DeviceNotFound => println!("button component params set: Device not found"),
                                         SetComponentValueResult::FailedToSet => println!("button component params set: Failed to set value"),
+                                        SetComponentValueResult::InvalidValues(errors) => {
+                                            for error in errors {
+                                                println!("button component params set: {}: {}", error.path, error.message);
+                                            }
+                                        }
                                         SetComponentValueResult::Set => {
                                             client.commit_changes(current_sn).expect("Failed to commit changes");
                                             println!("button component params set: Parameter set")
@@ -311,6 +320,11 @@ pub fn button_component_params_upload(client: ClientRef, current_sn: &str, mut a
                                         match result {
                                             SetComponentValueResult::DeviceNotFound => println!("button component params upload: Device not found"),
                                             SetComponentValueResult::FailedToSet => println!("button component params upload: Failed to upload image"),
+                                            SetComponentValueResult::InvalidValues(errors) => {
+                                                for error in errors {
+                                                    println!("button component params upload: {}: {}", error.path, error.message);
+                                                }
+                                            }
                                             SetComponentValueResult::Set => {
                                                 client.commit_changes(current_sn).expect("Failed to commit changes");
                                                 println!("button component params upload: Uploaded image")
@@ -400,6 +414,16 @@ pub fn button_component_list_params(client: ClientRef, current_sn: &str, mut arg
                                         println!("{}Value: {},{}", tabs, i1, i2);
                                     }
 
+                                    UIFieldValue::InputFieldMultiline(s) => {
+                                        println!("{}Type: Multiline String", tabs);
+
+                                        if let UIFieldType::InputFieldMultiline { language_hint: Some(language) } = &item.ty {
+                                            println!("{}Language: {}", tabs, language);
+                                        }
+
+                                        println!("{}Value: {}", tabs, s.replace('\n', "\\n"));
+                                    }
+
                                     UIFieldValue::InputFieldUnsignedInteger(u) => {
                                         println!("{}Type: Positive Integer", tabs);
                                         println!("{}Value: {}", tabs, u);
@@ -421,7 +445,7 @@ pub fn button_component_list_params(client: ClientRef, current_sn: &str, mut arg
 
                                     UIFieldValue::Color(c1, c2, c3, c4) => {
                                         println!("{}Type: Color", tabs);
-                                        println!("{}Value: {},{},{},{}", tabs, c1, c2, c3, c4);
+                                        println!("{}Value: {},{},{},{} ({})", tabs, c1, c2, c3, c4, color_to_hex(&(c1, c2, c3, c4)));
                                     }
 
 
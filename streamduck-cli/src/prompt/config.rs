@@ -55,13 +55,14 @@ pub fn import_config(client: ClientRef, mut args: Split<&str>, current_sn: &Stri
         if let Some(path) = args.next() {
             if let Ok(path) = PathBuf::from_str(path) {
                 if let Ok(file) = fs::read_to_string(path) {
-                    let result = client.import_device_config(current_sn, file).expect("Failed to import device config");
+                    let result = client.import_device_config(current_sn, file, false).expect("Failed to import device config");
 
                     match result {
                         ImportDeviceConfigResult::DeviceNotFound => println!("config import: Device not found"),
                         ImportDeviceConfigResult::InvalidConfig => println!("config import: Invalid device config"),
                         ImportDeviceConfigResult::FailedToSave => println!("config import: Failed to save config"),
                         ImportDeviceConfigResult::Imported => println!("config import: Imported"),
+                        ImportDeviceConfigResult::Reviewed(_) => {}
                     }
                 } else {
                     println!("config import: Failed to read file");
@@ -81,7 +82,8 @@ pub fn export_config(client: ClientRef, mut args: Split<&str>, current_sn: &Stri
     if !current_sn.is_empty() {
         if let Some(path) = args.next() {
             if let Ok(path) = PathBuf::from_str(path) {
-                let data = client.export_device_config(current_sn).expect("Failed to export device config");
+                let include_images = args.next() == Some("with-images");
+                let data = client.export_device_config(current_sn, include_images).expect("Failed to export device config");
 
                 match data {
                     ExportDeviceConfigResult::DeviceNotFound => println!("config export: Device not found"),
@@ -237,7 +237,7 @@ pub fn prompt(client: Arc<dyn SDSyncRequestClient>) {
                                                     match result {
                                                         CopyButtonResult::DeviceNotFound => println!("button copy: Device not found"),
                                                         CopyButtonResult::NoButton => println!("button copy: No button to copy"),
-                                                        CopyButtonResult::Copied => println!("button copy: Copied"),
+                                                        CopyButtonResult::Copied(_) => println!("button copy: Copied"),
                                                     }
                                                 } else {
                                                     println!("button copy: Input valid key index (0-255)")
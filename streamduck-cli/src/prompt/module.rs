@@ -1,6 +1,7 @@
 use std::str::Split;
 use streamduck_client::daemon::daemon_data::modules::{AddModuleValueResult, GetModuleValuesResult, RemoveModuleValueResult, SetModuleValueResult};
 use streamduck_core::modules::components::{map_ui_path_values, UIFieldType, UIFieldValue, UIPathValue};
+use streamduck_core::thread::rendering::color_to_hex;
 use crate::prompt::ClientRef;
 use crate::prompt::utils::{parse_string_to_value, print_table};
 
@@ -242,6 +243,16 @@ pub fn module_list_params(client: ClientRef, mut args: Split<&str>) {
                                 println!("{}Value: {},{}", tabs, i1, i2);
                             }
 
+                            UIFieldValue::InputFieldMultiline(s) => {
+                                println!("{}Type: Multiline String", tabs);
+
+                                if let UIFieldType::InputFieldMultiline { language_hint: Some(language) } = &item.ty {
+                                    println!("{}Language: {}", tabs, language);
+                                }
+
+                                println!("{}Value: {}", tabs, s.replace('\n', "\\n"));
+                            }
+
                             UIFieldValue::InputFieldUnsignedInteger(u) => {
                                 println!("{}Type: Positive Integer", tabs);
                                 println!("{}Value: {}", tabs, u);
@@ -263,7 +274,7 @@ pub fn module_list_params(client: ClientRef, mut args: Split<&str>) {
 
                             UIFieldValue::Color(c1, c2, c3, c4) => {
                                 println!("{}Type: Color", tabs);
-                                println!("{}Value: {},{},{},{}", tabs, c1, c2, c3, c4);
+                                println!("{}Value: {},{},{},{} ({})", tabs, c1, c2, c3, c4, color_to_hex(&(c1, c2, c3, c4)));
                             }
 
 
@@ -1,4 +1,5 @@
 use streamduck_core::modules::components::{UIFieldType, UIFieldValue};
+use streamduck_core::thread::rendering::parse_color;
 
 pub fn print_table(table: Vec<Vec<&str>>, first_separator: &str, separator: &str) {
     let mut max_len = vec![];
@@ -73,6 +74,11 @@ pub fn parse_string_to_value<T>(value: &str, ty: &UIFieldType) -> Option<UIField
             Some(UIFieldValue::InputFieldString(value.to_string()))
         }
 
+        UIFieldType::InputFieldMultiline { .. } => {
+            // Prompt reads single line, so line breaks are entered as \n
+            Some(UIFieldValue::InputFieldMultiline(value.replace("\\n", "\n")))
+        }
+
         UIFieldType::InputFieldFloat2 => {
             let mut parts = value.split(",");
 
@@ -141,24 +147,12 @@ pub fn parse_string_to_value<T>(value: &str, ty: &UIFieldType) -> Option<UIField
         }
 
         UIFieldType::Color => {
-            let mut parts = value.split(",");
-
-            if let Ok(c1) = (parts.next().unwrap_or_default()).parse::<u8>() {
-                if let Ok(c2) = (parts.next().unwrap_or_default()).parse::<u8>() {
-                    if let Ok(c3) = (parts.next().unwrap_or_default()).parse::<u8>() {
-                        if let Ok(c4) = (parts.next().unwrap_or_default()).parse::<u8>() {
-                            Some(UIFieldValue::Color(c1, c2, c3, c4))
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    }
-                } else {
+            match parse_color(value) {
+                Ok((c1, c2, c3, c4)) => Some(UIFieldValue::Color(c1, c2, c3, c4)),
+                Err(err) => {
+                    println!("{}", err);
                     None
                 }
-            } else {
-                None
             }
         }
 
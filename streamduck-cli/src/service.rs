@@ -0,0 +1,303 @@
+//! Installing daemon to start at login
+//!
+//! Daemon is started per user, inside of user's session. On Windows it's a logon task instead of
+//! a system service, because services run in session 0 where named pipe of the daemon isn't
+//! accessible to clients of logged in user. On macOS it's a launchd agent and on Linux a systemd user unit.
+//!
+//! Daemon reads its config from working directory, so directory that install was called from is
+//! used as working directory of the daemon
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::commands::{fail, usage_error};
+
+/// Identifier the daemon is registered under
+const SERVICE_NAME: &str = "org.streamduck.daemon";
+
+/// Name of daemon executable
+#[cfg(target_family = "windows")]
+const DAEMON_EXECUTABLE: &str = "streamduck_daemon.exe";
+
+/// Name of daemon executable
+#[cfg(target_family = "unix")]
+const DAEMON_EXECUTABLE: &str = "streamduck_daemon";
+
+/// Runs service subcommand
+pub fn run(args: &[String]) {
+    match args.get(0).map(|x| x.as_str()) {
+        Some("install") => {
+            let daemon = args.get(1).map(PathBuf::from).unwrap_or_else(default_daemon_path);
+
+            if !daemon.exists() {
+                fail(&format!("Daemon executable not found at {}, provide path to it", daemon.display()));
+            }
+
+            let daemon = daemon.canonicalize().unwrap_or_else(|err| fail(&format!("Failed to resolve daemon path: {}", err)));
+            let working_directory = env::current_dir().unwrap_or_else(|err| fail(&format!("Failed to get current directory: {}", err)));
+
+            install(&daemon, &working_directory);
+            println!("Daemon will now start at login, working directory is {}", working_directory.display());
+        }
+
+        Some("uninstall") => {
+            uninstall();
+            println!("Daemon will no longer start at login");
+        }
+
+        Some("status") => status(),
+
+        _ => usage_error("Expected install, uninstall or status"),
+    }
+}
+
+/// Daemon executable next to CLI executable
+fn default_daemon_path() -> PathBuf {
+    env::current_exe().ok()
+        .and_then(|x| x.parent().map(|x| x.join(DAEMON_EXECUTABLE)))
+        .unwrap_or_else(|| PathBuf::from(DAEMON_EXECUTABLE))
+}
+
+/// Runs a command, failing with its output if it didn't succeed
+fn run_command(command: &mut Command) -> String {
+    let output = command.output().unwrap_or_else(|err| fail(&format!("Failed to run {:?}: {}", command, err)));
+
+    if !output.status.success() {
+        fail(&format!("{:?} failed: {}", command, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+/// Escapes text for XML and plist files
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(target_os = "windows")]
+fn install(daemon: &Path, working_directory: &Path) {
+    // Task definition has to be XML to set working directory, schtasks expects it in UTF-16
+    let definition = format!(r#"<?xml version="1.0" encoding="UTF-16"?>
+<Task version="1.2" xmlns="http://schemas.microsoft.com/windows/2004/02/mit/task">
+  <RegistrationInfo>
+    <Description>Streamduck daemon</Description>
+  </RegistrationInfo>
+  <Triggers>
+    <LogonTrigger>
+      <Enabled>true</Enabled>
+      <UserId>{user}</UserId>
+    </LogonTrigger>
+  </Triggers>
+  <Principals>
+    <Principal id="Author">
+      <UserId>{user}</UserId>
+      <LogonType>InteractiveToken</LogonType>
+      <RunLevel>LeastPrivilege</RunLevel>
+    </Principal>
+  </Principals>
+  <Settings>
+    <MultipleInstancesPolicy>IgnoreNew</MultipleInstancesPolicy>
+    <DisallowStartIfOnBatteries>false</DisallowStartIfOnBatteries>
+    <StopIfGoingOnBatteries>false</StopIfGoingOnBatteries>
+    <ExecutionTimeLimit>PT0S</ExecutionTimeLimit>
+    <RestartOnFailure>
+      <Interval>PT1M</Interval>
+      <Count>3</Count>
+    </RestartOnFailure>
+  </Settings>
+  <Actions Context="Author">
+    <Exec>
+      <Command>{command}</Command>
+      <WorkingDirectory>{directory}</WorkingDirectory>
+    </Exec>
+  </Actions>
+</Task>
+"#,
+        user = escape_xml(&env::var("USERNAME").unwrap_or_default()),
+        command = escape_xml(&daemon.display().to_string()),
+        directory = escape_xml(&working_directory.display().to_string())
+    );
+
+    let path = env::temp_dir().join("streamduck-task.xml");
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend(definition.encode_utf16().flat_map(|x| x.to_le_bytes()));
+
+    std::fs::write(&path, bytes).unwrap_or_else(|err| fail(&format!("Failed to write task definition: {}", err)));
+
+    run_command(Command::new("schtasks").args(["/Create", "/F", "/TN", SERVICE_NAME, "/XML"]).arg(&path));
+    std::fs::remove_file(&path).ok();
+
+    run_command(Command::new("schtasks").args(["/Run", "/TN", SERVICE_NAME]));
+}
+
+#[cfg(target_os = "windows")]
+fn uninstall() {
+    Command::new("schtasks").args(["/End", "/TN", SERVICE_NAME]).output().ok();
+    run_command(Command::new("schtasks").args(["/Delete", "/F", "/TN", SERVICE_NAME]));
+}
+
+#[cfg(target_os = "windows")]
+fn status() {
+    match Command::new("schtasks").args(["/Query", "/FO", "LIST", "/TN", SERVICE_NAME]).output() {
+        Ok(output) if output.status.success() => {
+            let output = String::from_utf8_lossy(&output.stdout);
+            let state = output.lines()
+                .find_map(|x| x.strip_prefix("Status:"))
+                .map(|x| x.trim().to_string())
+                .unwrap_or_else(|| "Unknown".to_string());
+
+            println!("Installed\t{}", state);
+        }
+
+        _ => println!("Not installed"),
+    }
+}
+
+/// Path to launchd agent definition
+#[cfg(target_os = "macos")]
+fn agent_path() -> PathBuf {
+    let home = env::var("HOME").unwrap_or_else(|_| fail("HOME is not set"));
+    Path::new(&home).join("Library/LaunchAgents").join(format!("{}.plist", SERVICE_NAME))
+}
+
+#[cfg(target_os = "macos")]
+fn install(daemon: &Path, working_directory: &Path) {
+    let path = agent_path();
+
+    let definition = format!(r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{command}</string>
+    </array>
+    <key>WorkingDirectory</key>
+    <string>{directory}</string>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+    </dict>
+    <key>ProcessType</key>
+    <string>Interactive</string>
+</dict>
+</plist>
+"#,
+        label = SERVICE_NAME,
+        command = escape_xml(&daemon.display().to_string()),
+        directory = escape_xml(&working_directory.display().to_string())
+    );
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).ok();
+    }
+
+    // Unloading previous definition if it's being reinstalled
+    Command::new("launchctl").arg("unload").arg(&path).output().ok();
+
+    std::fs::write(&path, definition).unwrap_or_else(|err| fail(&format!("Failed to write launchd agent: {}", err)));
+    run_command(Command::new("launchctl").args(["load", "-w"]).arg(&path));
+}
+
+#[cfg(target_os = "macos")]
+fn uninstall() {
+    let path = agent_path();
+
+    if !path.exists() {
+        fail("Daemon isn't installed");
+    }
+
+    Command::new("launchctl").args(["unload", "-w"]).arg(&path).output().ok();
+    std::fs::remove_file(&path).unwrap_or_else(|err| fail(&format!("Failed to remove launchd agent: {}", err)));
+}
+
+#[cfg(target_os = "macos")]
+fn status() {
+    if !agent_path().exists() {
+        println!("Not installed");
+        return;
+    }
+
+    match Command::new("launchctl").args(["list", SERVICE_NAME]).output() {
+        Ok(output) if output.status.success() => {
+            let output = String::from_utf8_lossy(&output.stdout);
+            let running = output.lines().any(|x| x.trim_start().starts_with("\"PID\""));
+
+            println!("Installed\t{}", if running { "running" } else { "stopped" });
+        }
+
+        _ => println!("Installed\tnot loaded"),
+    }
+}
+
+/// Path to systemd user unit
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+fn unit_path() -> PathBuf {
+    let config = env::var("XDG_CONFIG_HOME").map(PathBuf::from)
+        .or_else(|_| env::var("HOME").map(|x| Path::new(&x).join(".config")))
+        .unwrap_or_else(|_| fail("HOME is not set"));
+
+    config.join("systemd/user/streamduck.service")
+}
+
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+fn install(daemon: &Path, working_directory: &Path) {
+    let path = unit_path();
+
+    let definition = format!("[Unit]
+Description=Streamduck daemon
+
+[Service]
+ExecStart=\"{}\"
+WorkingDirectory={}
+Restart=on-failure
+
+[Install]
+WantedBy=default.target
+", daemon.display(), working_directory.display());
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).ok();
+    }
+
+    std::fs::write(&path, definition).unwrap_or_else(|err| fail(&format!("Failed to write systemd unit: {}", err)));
+
+    run_command(Command::new("systemctl").args(["--user", "daemon-reload"]));
+    run_command(Command::new("systemctl").args(["--user", "enable", "--now", "streamduck.service"]));
+}
+
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+fn uninstall() {
+    let path = unit_path();
+
+    if !path.exists() {
+        fail("Daemon isn't installed");
+    }
+
+    Command::new("systemctl").args(["--user", "disable", "--now", "streamduck.service"]).output().ok();
+    std::fs::remove_file(&path).unwrap_or_else(|err| fail(&format!("Failed to remove systemd unit: {}", err)));
+    Command::new("systemctl").args(["--user", "daemon-reload"]).output().ok();
+}
+
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+fn status() {
+    if !unit_path().exists() {
+        println!("Not installed");
+        return;
+    }
+
+    let state = Command::new("systemctl").args(["--user", "is-active", "streamduck.service"]).output()
+        .map(|x| String::from_utf8_lossy(&x.stdout).trim().to_string())
+        .unwrap_or_else(|_| "unknown".to_string());
+
+    println!("Installed\t{}", state);
+}
@@ -0,0 +1,99 @@
+//! Builder for batch requests, allows to send many requests in a single round trip
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use streamduck_core::socket::{parse_packet_to_data, SocketData, SocketPacket};
+use streamduck_daemon::daemon_data::ops::BatchResult;
+use crate::{SDClientError, SDSyncRequestClient};
+
+/// Builder of a batch of requests, requests are executed by daemon in order they were added
+#[derive(Default)]
+pub struct RequestBatch {
+    requests: Vec<SocketPacket>
+}
+
+impl RequestBatch {
+    /// Creates new empty batch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds request to the batch, returns index of response for the request
+    pub fn add<T: SocketData + Serialize>(&mut self, request: &T) -> Result<usize, SDClientError> {
+        self.requests.push(SocketPacket {
+            ty: T::NAME.to_string(),
+            requester: None,
+            data: Some(serde_json::to_value(request)?)
+        });
+
+        Ok(self.requests.len() - 1)
+    }
+
+    /// Adds request without any parameters to the batch, returns index of response for the request
+    pub fn add_without_data<T: SocketData>(&mut self) -> usize {
+        self.requests.push(SocketPacket {
+            ty: T::NAME.to_string(),
+            requester: None,
+            data: None
+        });
+
+        self.requests.len() - 1
+    }
+
+    /// Amount of requests in the batch
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Checks if batch has no requests
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Sends the batch using provided client
+    pub fn send(self, client: &dyn SDSyncRequestClient) -> Result<BatchResponses, SDClientError> {
+        Ok(BatchResponses {
+            responses: client.batch(self.requests)?.responses
+        })
+    }
+}
+
+impl From<RequestBatch> for Vec<SocketPacket> {
+    fn from(batch: RequestBatch) -> Self {
+        batch.requests
+    }
+}
+
+/// Responses of a sent batch
+pub struct BatchResponses {
+    pub responses: Vec<Option<SocketPacket>>
+}
+
+impl BatchResponses {
+    /// Parses response at specified index into response type
+    pub fn get<T: SocketData + DeserializeOwned>(&self, index: usize) -> Result<T, SDClientError> {
+        if let Some(Some(packet)) = self.responses.get(index) {
+            Ok(parse_packet_to_data(packet)?)
+        } else {
+            Err(SDClientError::Custom(format!("No response for request at index {}", index)))
+        }
+    }
+
+    /// Amount of responses
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    /// Checks if there's no responses
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+}
+
+impl From<BatchResult> for BatchResponses {
+    fn from(result: BatchResult) -> Self {
+        BatchResponses {
+            responses: result.responses
+        }
+    }
+}
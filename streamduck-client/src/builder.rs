@@ -0,0 +1,177 @@
+//! Typed builder for buttons, composes known core components without touching raw component JSON
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use streamduck_core::core::button::{Button, Component};
+use streamduck_core::thread::rendering::{ButtonBackground, ButtonText, ButtonTextShadow, Color, RendererComponent, RendererComponentBuilder};
+use streamduck_core::thread::util::TextAlignment;
+use streamduck_daemon::daemon_data::buttons::{SetButton, SetButtonResult};
+use crate::batch::RequestBatch;
+use crate::{SDClientError, SDSyncRequestClient};
+
+/// Name of the component provided by actions module that runs commands
+pub const RUN_COMMAND_COMPONENT: &'static str = "run_command";
+
+/// Fluent builder for buttons, produces a button that can be set in a single request
+pub struct ButtonBuilder {
+    renderer: Option<RendererComponentBuilder>,
+    button: Button,
+}
+
+impl ButtonBuilder {
+    /// Creates new builder for an empty button
+    pub fn new() -> Self {
+        Self {
+            renderer: None,
+            button: Button::new()
+        }
+    }
+
+    fn renderer_builder(mut self, func: impl FnOnce(RendererComponentBuilder) -> RendererComponentBuilder) -> Self {
+        let builder = self.renderer.take().unwrap_or_default();
+        self.renderer = Some(func(builder)); self
+    }
+
+    /// Sets custom renderer that should draw the button
+    pub fn renderer(self, renderer: &str) -> Self {
+        self.renderer_builder(|b| b.renderer(renderer))
+    }
+
+    /// Sets background of the button
+    pub fn background(self, background: ButtonBackground) -> Self {
+        self.renderer_builder(|b| b.background(background))
+    }
+
+    /// Sets solid color background
+    pub fn background_color(self, color: Color) -> Self {
+        self.background(ButtonBackground::Solid(color))
+    }
+
+    /// Sets horizontal gradient background
+    pub fn background_horizontal_gradient(self, start: Color, end: Color) -> Self {
+        self.background(ButtonBackground::HorizontalGradient(start, end))
+    }
+
+    /// Sets vertical gradient background
+    pub fn background_vertical_gradient(self, start: Color, end: Color) -> Self {
+        self.background(ButtonBackground::VerticalGradient(start, end))
+    }
+
+    /// Sets background to an image that already exists in device's image collection
+    pub fn background_image(self, identifier: &str) -> Self {
+        self.background(ButtonBackground::ExistingImage(identifier.to_string()))
+    }
+
+    /// Adds centered white text with default font
+    pub fn text(self, text: &str) -> Self {
+        self.add_text(ButtonText {
+            text: text.to_string(),
+            font: "default".to_string(),
+            scale: (15.0, 15.0),
+            alignment: TextAlignment::Center,
+            padding: 0,
+            offset: (0.0, 0.0),
+            color: (255, 255, 255, 255),
+            shadow: None,
+            auto_fit: false,
+            word_wrap: false,
+            marquee_speed: 0.0
+        })
+    }
+
+    /// Adds text with specified color, scale and alignment
+    pub fn styled_text(self, text: &str, color: Color, scale: f32, alignment: TextAlignment, shadow: Option<ButtonTextShadow>) -> Self {
+        self.add_text(ButtonText {
+            text: text.to_string(),
+            font: "default".to_string(),
+            scale: (scale, scale),
+            alignment,
+            padding: 0,
+            offset: (0.0, 0.0),
+            color,
+            shadow,
+            auto_fit: false,
+            word_wrap: false,
+            marquee_speed: 0.0
+        })
+    }
+
+    /// Adds a fully defined text object
+    pub fn add_text(self, text: ButtonText) -> Self {
+        self.renderer_builder(|b| b.add_text(text))
+    }
+
+    /// Sets if renderer should cache the button
+    pub fn caching(self, cache: bool) -> Self {
+        self.renderer_builder(|b| b.caching(cache))
+    }
+
+    /// Adds run command component with provided command line
+    pub fn on_press_command(self, command: &str) -> Self {
+        self.raw_component(RUN_COMMAND_COMPONENT, json!({
+            "simple_command": command,
+            "advanced_command": [],
+            "use_advanced": false
+        }))
+    }
+
+    /// Adds run command component with program and separate arguments
+    pub fn on_press_command_args(self, program: &str, args: &[&str]) -> Self {
+        let mut command = vec![program.to_string()];
+        command.extend(args.iter().map(|x| x.to_string()));
+
+        self.raw_component(RUN_COMMAND_COMPONENT, json!({
+            "simple_command": "",
+            "advanced_command": command,
+            "use_advanced": true
+        }))
+    }
+
+    /// Adds a typed component to the button
+    pub fn component<T: Component + Serialize>(mut self, component: T) -> Self {
+        self.button.insert_component(component).ok(); self
+    }
+
+    /// Adds component with raw JSON data, for components that client doesn't know types of
+    pub fn raw_component(mut self, name: &str, data: Value) -> Self {
+        self.button.0.insert(name.to_string(), data); self
+    }
+
+    /// Builds the button
+    pub fn build(self) -> Button {
+        let mut button = self.button;
+
+        if let Some(renderer) = self.renderer {
+            button.insert_component::<RendererComponent>(renderer.build()).ok();
+        }
+
+        button
+    }
+
+    /// Builds the button and sets it on the device's current screen with a single request
+    pub fn submit(self, client: &dyn SDSyncRequestClient, serial_number: &str, key: u8) -> Result<SetButtonResult, SDClientError> {
+        client.set_button(serial_number, key, self.build())
+    }
+
+    /// Builds the button and adds request to set it into the batch, returns index of the response
+    pub fn submit_in(self, batch: &mut RequestBatch, serial_number: &str, key: u8) -> Result<usize, SDClientError> {
+        batch.add(&SetButton {
+            serial_number: serial_number.to_string(),
+            key,
+            button: self.build(),
+            revision: None
+        })
+    }
+}
+
+impl Default for ButtonBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<ButtonBuilder> for Button {
+    fn from(builder: ButtonBuilder) -> Self {
+        builder.build()
+    }
+}
@@ -1,23 +1,45 @@
 use std::collections::HashMap;
-use std::io::Error;
+use std::io::{Error, ErrorKind};
 use std::string::FromUtf8Error;
 use std::sync::Arc;
 
+use serde_json::Value;
+use streamduck_core::config::ConfigMilestone;
 use streamduck_core::core::button::Button;
 use streamduck_core::core::RawButtonPanel;
 use streamduck_core::modules::components::{ComponentDefinition, UIPathValue};
 use streamduck_core::modules::events::SDGlobalEvent;
+use streamduck_core::modules::dependencies::DependencyNode;
 use streamduck_core::modules::PluginMetadata;
-use streamduck_core::socket::{SocketError, SocketPacket};
+use streamduck_core::logging::{LogFilter, LogRecord};
+use streamduck_core::portable::PortableButton;
+use streamduck_core::presets::ComponentPreset;
+use streamduck_core::socket::{ClientInfo, SocketError, SocketPacket};
+use streamduck_core::thread::orientation::DeviceOrientation;
+use streamduck_core::thread::transition::PanelTransition;
 pub use streamduck_daemon as daemon;
-use streamduck_daemon::daemon_data::assets::{AddImageResult, ListImagesResult, RemoveImageResult};
-use streamduck_daemon::daemon_data::buttons::{AddComponentResult, AddComponentValueResult, ClearButtonResult, ClipboardStatusResult, CopyButtonResult, GetButtonResult, GetComponentValuesResult, NewButtonFromComponentResult, NewButtonResult, PasteButtonResult, RemoveComponentResult, RemoveComponentValueResult, SetButtonResult, SetComponentValueResult};
-use streamduck_daemon::daemon_data::config::{ExportDeviceConfigResult, GetDeviceConfigResult, ImportDeviceConfigResult, ReloadDeviceConfigResult, ReloadDeviceConfigsResult, SaveDeviceConfigResult, SaveDeviceConfigsResult};
-use streamduck_daemon::daemon_data::devices::{AddDeviceResult, Device, GetDeviceResult, RemoveDeviceResult, SetBrightnessResult};
-use streamduck_daemon::daemon_data::modules::{AddModuleValueResult, GetModuleValuesResult, RemoveModuleValueResult, SetModuleValueResult};
-use streamduck_daemon::daemon_data::ops::{CommitChangesToConfigResult, DoButtonActionResult};
-use streamduck_daemon::daemon_data::panels::{DropStackToRootResult, ForciblyPopScreenResult, GetButtonImagesResult, GetCurrentScreenResult, GetStackNamesResult, GetStackResult, PopScreenResult, PushScreenResult, ReplaceScreenResult, ResetStackResult};
-
+use streamduck_daemon::daemon_data::assets::{AddImageResult, CleanupUnusedImagesResult, FinishImageUploadResult, GetImageResult, ListImagesResult, RemoveImageResult, StartImageUploadResult, UploadImageChunkResult};
+use streamduck_daemon::daemon_data::auth::AuthenticateResult;
+use streamduck_daemon::daemon_data::buttons::{AddComponentResult, AddComponentValueResult, ClearButtonResult, ClipboardStatusResult, CopyButtonResult, GetButtonResult, GetComponentValuesResult, MoveButtonResult, NewButtonFromComponentResult, NewButtonResult, PasteButtonResult, RemoveComponentResult, RemoveComponentValueResult, SetButtonResult, SetComponentValueResult};
+use streamduck_daemon::daemon_data::clients::{DisconnectClientResult, IdentifyClientResult};
+use streamduck_daemon::daemon_data::config::{CreateConfigMilestoneResult, DiffDeviceConfigResult, ExportAllConfigsResult, ExportDeviceConfigResult, GetDeviceConfigResult, ImportAllConfigsResult, ImportDeviceConfigResult, ReloadDeviceConfigResult, ReloadDeviceConfigsResult, RestoreConfigMilestoneResult, RestoreDeviceConfigBackupResult, SaveDeviceConfigResult, SaveDeviceConfigsResult};
+use streamduck_daemon::daemon_data::devices::{AddDeviceResult, Device, GetDeviceResult, RemoveDeviceResult, SetBrightnessResult, SetDeviceMirrorResult, SetDeviceOrientationResult, SetDeviceStartupResult, SetMirroredLayoutResult, SetPanelTransitionResult};
+use streamduck_daemon::daemon_data::folders::{CleanupFoldersResult, DeleteFolderResult, FindButtonsResult, GetFolderTreeResult, ListFoldersResult, RenameFolderResult};
+use streamduck_daemon::daemon_data::history::{RedoResult, UndoResult};
+use streamduck_daemon::daemon_data::modules::{AddModuleValueResult, GetGlobalPluginSettingsResult, GetModuleValuesResult, ReenablePluginResult, RemoveModuleValueResult, SendModuleMessageResult, SetGlobalPluginSettingsResult, SetModuleValueResult};
+use streamduck_daemon::daemon_data::logs::StreamLogsResult;
+use streamduck_daemon::daemon_data::notifications::{DismissNotificationResult, ShowNotificationResult};
+use streamduck_daemon::daemon_data::ops::{BatchResult, CommitChangesToConfigResult, DoButtonActionResult, GetSafeModeStatus, RestartDaemonResult, SetSafeModeOnNextStartResult, ShutdownDaemonResult};
+use streamduck_daemon::daemon_data::layout::{ApplyLayoutResult, ExportLayoutResult, InstantiateTemplateResult};
+use streamduck_daemon::daemon_data::presets::{ApplyPresetResult, RemovePresetResult, SavePresetResult};
+use streamduck_daemon::daemon_data::remote::{RegisterRemoteModuleResult, RemoteModuleResponseResult, UnregisterRemoteModuleResult};
+use streamduck_daemon::daemon_data::status::GetDaemonStatus;
+use streamduck_daemon::daemon_data::revisions::GetScreenRevisionsResult;
+use streamduck_daemon::daemon_data::sessions::{ResumeSessionResult, StartSessionResult};
+use streamduck_daemon::daemon_data::panels::{DropStackToRootResult, ForciblyPopScreenResult, GetButtonImagesResult, GetCurrentScreenResult, GetStackBreadcrumbsResult, GetStackNamesResult, GetStackResult, PopScreenResult, PopToScreenResult, PushScreenResult, RenderButtonPreviewResult, ReplaceScreenResult, ResetStackResult, SetPanelResult};
+use streamduck_daemon::remote::RemoteModuleCall;
+
+use crate::util::RequestOptions;
 #[cfg(target_family = "unix")]
 pub mod unix;
 
@@ -25,6 +47,13 @@ pub mod unix;
 pub mod windows;
 
 pub mod util;
+pub mod builder;
+pub mod batch;
+
+/// Environment variable that overrides path of unix domain socket
+pub const SOCKET_PATH_ENV: &str = "STREAMDUCK_SOCKET";
+/// Environment variable with token that clients authenticate with after connecting
+pub const TOKEN_ENV: &str = "STREAMDUCK_TOKEN";
 
 /// Trait that combines both types of clients
 pub trait SDSyncClient: SDSyncUpcastRequestClient + SDSyncUpcastEventClient {}
@@ -43,13 +72,23 @@ pub trait SDSyncUpcastEventClient: SDSyncEventClient {
 pub trait SDSyncEventClient: Send + Sync {
     /// Retrieves an event from daemon, depending on implementation might block
     fn get_event(&self) -> Result<SDGlobalEvent, SDClientError>;
+    /// Retrieves a call for remote module that was registered with session of this connection, skipping events
+    fn get_remote_call(&self) -> Result<RemoteModuleCall, SDClientError>;
 }
 
 /// Trait that defines synchronous request client
 pub trait SDSyncRequestClient: Send + Sync {
+    // Options
+    /// Sets options that will be applied to all following requests
+    fn set_request_options(&self, options: RequestOptions);
+    /// Retrieves options that are currently applied to requests
+    fn request_options(&self) -> RequestOptions;
+
     // Version
     /// Retrieves version of the daemon socket API
     fn version(&self) -> Result<String, SDClientError>;
+    /// Authenticates connection with token from daemon config, required before other requests if daemon has a token
+    fn authenticate(&self, token: &str) -> Result<AuthenticateResult, SDClientError>;
 
     // Device management
     /// Device list
@@ -74,23 +113,87 @@ pub trait SDSyncRequestClient: Send + Sync {
     /// Gets device config for a device
     fn get_device_config(&self, serial_number: &str) -> Result<GetDeviceConfigResult, SDClientError>;
 
-    /// Imports device config from string
-    fn import_device_config(&self, serial_number: &str, config: String) -> Result<ImportDeviceConfigResult, SDClientError>;
-    /// Exports device config into string
-    fn export_device_config(&self, serial_number: &str) -> Result<ExportDeviceConfigResult, SDClientError>;
+    /// Imports device config from string, dry run only reviews the config without applying it
+    fn import_device_config(&self, serial_number: &str, config: String, dry_run: bool) -> Result<ImportDeviceConfigResult, SDClientError>;
+    /// Compares config in import format with current config of the device, without applying it
+    fn diff_device_config(&self, serial_number: &str, config: String) -> Result<DiffDeviceConfigResult, SDClientError>;
+    /// Exports device config into string, optionally embedding data of images used by the layout
+    fn export_device_config(&self, serial_number: &str, include_images: bool) -> Result<ExportDeviceConfigResult, SDClientError>;
+    /// Restores device config from rotated backup, 1 being the most recent
+    fn restore_device_config_backup(&self, serial_number: &str, backup: usize) -> Result<RestoreDeviceConfigBackupResult, SDClientError>;
+
+    /// Saves snapshot of all device configs and plugin settings under a name
+    fn create_config_milestone(&self, name: &str) -> Result<CreateConfigMilestoneResult, SDClientError>;
+    /// Lists saved config milestones
+    fn list_config_milestones(&self) -> Result<Vec<ConfigMilestone>, SDClientError>;
+    /// Restores all device configs and plugin settings from a milestone
+    fn restore_config_milestone(&self, name: &str) -> Result<RestoreConfigMilestoneResult, SDClientError>;
+
+    /// Exports all device configs, daemon settings, plugin settings and images into base64 encoded archive
+    fn export_all_configs(&self) -> Result<ExportAllConfigsResult, SDClientError>;
+    /// Imports archive made by [export_all_configs](SDSyncRequestClient::export_all_configs)
+    fn import_all_configs(&self, bundle: String) -> Result<ImportAllConfigsResult, SDClientError>;
+
+    /// Applies YAML layout definition to device, only changing what differs from current config. Dry run only lists the changes
+    fn apply_layout(&self, serial_number: &str, layout: String, dry_run: bool) -> Result<ApplyLayoutResult, SDClientError>;
+    /// Exports current layout of device as YAML layout definition
+    fn export_layout(&self, serial_number: &str) -> Result<ExportLayoutResult, SDClientError>;
+    /// Instantiates YAML layout template with provided variable values and applies it to device. Dry run only lists the changes
+    fn instantiate_template(&self, serial_number: &str, template: String, variables: HashMap<String, String>, dry_run: bool) -> Result<InstantiateTemplateResult, SDClientError>;
+    /// Lists all folders of device with amount of buttons in them and whether they're orphaned
+    fn list_folders(&self, serial_number: &str) -> Result<ListFoldersResult, SDClientError>;
+    /// Gets whole folder hierarchy of a device as a tree, optionally with buttons of every panel
+    fn folder_tree(&self, serial_number: &str, include_buttons: bool) -> Result<GetFolderTreeResult, SDClientError>;
+    /// Finds buttons on root screen and in all folders that have the component and contain the text in component values
+    fn find_buttons(&self, serial_number: &str, component_name: Option<&str>, query: Option<&str>) -> Result<FindButtonsResult, SDClientError>;
+    /// Renames folder, buttons that open the folder are renamed too
+    fn rename_folder(&self, serial_number: &str, id: &str, name: &str) -> Result<RenameFolderResult, SDClientError>;
+    /// Deletes folder together with folders inside of it, buttons that open the folder are removed
+    fn delete_folder(&self, serial_number: &str, id: &str) -> Result<DeleteFolderResult, SDClientError>;
+    /// Deletes folders that can no longer be reached from root screen
+    fn cleanup_folders(&self, serial_number: &str) -> Result<CleanupFoldersResult, SDClientError>;
+    /// Lists all saved component presets
+    fn list_presets(&self) -> Result<HashMap<String, ComponentPreset>, SDClientError>;
+    /// Creates or updates component preset, propagate also updates all buttons that matched previous version of the preset
+    fn save_preset(&self, name: &str, preset: ComponentPreset, propagate: bool) -> Result<SavePresetResult, SDClientError>;
+    /// Removes component preset
+    fn remove_preset(&self, name: &str) -> Result<RemovePresetResult, SDClientError>;
+    /// Applies component preset onto a button on current screen
+    fn apply_preset(&self, serial_number: &str, key: u8, name: &str) -> Result<ApplyPresetResult, SDClientError>;
 
 
     /// Sets device brightness, usually 0-100, but different for each device
     fn set_brightness(&self, serial_number: &str, brightness: u8) -> Result<SetBrightnessResult, SDClientError>;
 
+    /// Sets transition of device's panels, global transition is used if transition isn't provided
+    fn set_panel_transition(&self, serial_number: &str, transition: Option<PanelTransition>) -> Result<SetPanelTransitionResult, SDClientError>;
+    /// Makes device mirror current screen of source device, stops mirroring if source is [None]
+    fn set_device_mirror(&self, serial_number: &str, source: Option<&str>) -> Result<SetDeviceMirrorResult, SDClientError>;
+    /// Sets rotation of the device, for devices that are mounted sideways or upside down
+    fn set_device_orientation(&self, serial_number: &str, orientation: DeviceOrientation) -> Result<SetDeviceOrientationResult, SDClientError>;
+    /// Mirrors key layout of the device horizontally, optionally flipping images too
+    fn set_mirrored_layout(&self, serial_number: &str, mirrored: bool, flip_images: bool) -> Result<SetMirroredLayoutResult, SDClientError>;
+    /// Sets folder that gets opened and buttons that get triggered once device connects
+    fn set_device_startup(&self, serial_number: &str, folder_id: Option<&str>, actions: Vec<Button>) -> Result<SetDeviceStartupResult, SDClientError>;
+
     /// Lists saved images on device
     fn list_images(&self, serial_number: &str) -> Result<ListImagesResult, SDClientError>;
     /// Adds new image to device config
     fn add_image(&self, serial_number: &str, image_data: &str) -> Result<AddImageResult, SDClientError>;
     /// Removes image from device config
     fn remove_image(&self, serial_number: &str, identifier: &str) -> Result<RemoveImageResult, SDClientError>;
-
-    /// Gets names of fonts currently loaded by daemon
+    /// Gets chunk of image file from device image collection, starting at offset
+    fn get_image(&self, serial_number: &str, identifier: &str, offset: usize) -> Result<GetImageResult, SDClientError>;
+    /// Starts chunked upload of an image into device config
+    fn start_image_upload(&self, serial_number: &str) -> Result<StartImageUploadResult, SDClientError>;
+    /// Sends next base64 encoded chunk of image upload
+    fn upload_image_chunk(&self, upload_id: &str, data: &str) -> Result<UploadImageChunkResult, SDClientError>;
+    /// Finishes image upload, adding uploaded image into device config
+    fn finish_image_upload(&self, upload_id: &str) -> Result<FinishImageUploadResult, SDClientError>;
+    /// Removes images that aren't used anywhere from device, or from all devices if serial isn't specified
+    fn cleanup_unused_images(&self, serial_number: Option<&str>) -> Result<CleanupUnusedImagesResult, SDClientError>;
+
+    /// Gets names of fonts available to daemon, by family, PostScript or file name
     fn list_fonts(&self) -> Result<Vec<String>, SDClientError>;
 
     // Module management
@@ -98,32 +201,60 @@ pub trait SDSyncRequestClient: Send + Sync {
     fn list_modules(&self) -> Result<Vec<PluginMetadata>, SDClientError>;
     /// Lists all components that were introduced by modules
     fn list_components(&self) -> Result<HashMap<String, HashMap<String, ComponentDefinition>>, SDClientError>;
+    /// Lists names of requests that plugins have registered, which can be sent with [SDSyncRequestClient::send_packet]
+    fn list_plugin_requests(&self) -> Result<Vec<String>, SDClientError>;
+    /// Lists loaded modules in order they were loaded, with their dependencies and versions they were resolved to
+    fn get_plugin_dependencies(&self) -> Result<Vec<DependencyNode>, SDClientError>;
+    /// Enables plugin that was disabled after panicking too many times
+    fn reenable_plugin(&self, name: &str) -> Result<ReenablePluginResult, SDClientError>;
+    /// Registers module implemented by the client, calls of the module are sent to connection of the session, see [SDSyncEventClient::get_remote_call]
+    fn register_remote_module(&self, session_token: &str, metadata: PluginMetadata, components: HashMap<String, ComponentDefinition>, listening_for: Vec<String>) -> Result<RegisterRemoteModuleResult, SDClientError>;
+    /// Removes module that was registered by a client
+    fn unregister_remote_module(&self, name: &str) -> Result<UnregisterRemoteModuleResult, SDClientError>;
+    /// Answers call that was sent to remote module
+    fn respond_to_remote_call(&self, module: &str, call_id: u64, data: Value) -> Result<RemoteModuleResponseResult, SDClientError>;
 
     /// Gets module settings
     fn get_module_values(&self, module_name: &str) -> Result<GetModuleValuesResult, SDClientError>;
+    /// Gets settings of every module that has any, by module name
+    fn list_module_settings(&self) -> Result<HashMap<String, Vec<UIPathValue>>, SDClientError>;
     /// Adds element to module setting
     fn add_module_value(&self, module_name: &str, path: &str) -> Result<AddModuleValueResult, SDClientError>;
     /// Removes element from module setting
     fn remove_module_value(&self, module_name: &str, path: &str, index: usize) -> Result<RemoveModuleValueResult, SDClientError>;
     /// Sets module settings
     fn set_module_value(&self, module_name: &str, value: UIPathValue) -> Result<SetModuleValueResult, SDClientError>;
+    /// Gets global plugin settings, that are shared between all devices
+    fn get_global_plugin_settings(&self, name: &str) -> Result<GetGlobalPluginSettingsResult, SDClientError>;
+    /// Sets global plugin settings, that are shared between all devices
+    fn set_global_plugin_settings(&self, name: &str, settings: Value) -> Result<SetGlobalPluginSettingsResult, SDClientError>;
+    /// Sends message to modules that are subscribed to the topic, optionally related to a device
+    fn send_module_message(&self, topic: &str, data: Value, serial_number: Option<&str>) -> Result<SendModuleMessageResult, SDClientError>;
 
     // Panel management
     /// Gets stack of a device
     fn get_stack(&self, serial_number: &str) -> Result<GetStackResult, SDClientError>;
     /// Gets stack names of a device
     fn get_stack_names(&self, serial_number: &str) -> Result<GetStackNamesResult, SDClientError>;
+    /// Gets breadcrumbs of current stack, with folder IDs of opened folders
+    fn get_stack_breadcrumbs(&self, serial_number: &str) -> Result<GetStackBreadcrumbsResult, SDClientError>;
     /// Gets current screen of a device
     fn get_current_screen(&self, serial_number: &str) -> Result<GetCurrentScreenResult, SDClientError>;
     /// Gets current images rendered on a device
     fn get_button_images(&self, serial_number: &str) -> Result<GetButtonImagesResult, SDClientError>;
+    /// Renders button that isn't on the screen with renderer of a device, size of 0 uses resolution of the device
+    fn render_button_preview(&self, serial_number: &str, key: u8, button: Button, size: (u32, u32)) -> Result<RenderButtonPreviewResult, SDClientError>;
 
+    /// Gets revisions of current screen of a device, for detecting edits made by other clients
+    fn screen_revisions(&self, serial_number: &str) -> Result<GetScreenRevisionsResult, SDClientError>;
     /// Gets a button from current screen of a device
     fn get_button(&self, serial_number: &str, key: u8) -> Result<GetButtonResult, SDClientError>;
     /// Sets a button on current screen of a device
     fn set_button(&self, serial_number: &str, key: u8, button: Button) -> Result<SetButtonResult, SDClientError>;
     /// Clears a button from current screen of a device
     fn clear_button(&self, serial_number: &str, key: u8) -> Result<ClearButtonResult, SDClientError>;
+    /// Moves button to another position on current screen, swapping it with button on destination if swap is true
+    fn move_button(&self, serial_number: &str, from: u8, to: u8, swap: bool) -> Result<MoveButtonResult, SDClientError>;
 
     /// Returns status of clipboard on daemon
     fn clipboard_status(&self) -> Result<ClipboardStatusResult, SDClientError>;
@@ -131,6 +262,8 @@ pub trait SDSyncRequestClient: Send + Sync {
     fn copy_button(&self, serial_number: &str, key: u8) -> Result<CopyButtonResult, SDClientError>;
     /// Pastes button from daemon's clipboard
     fn paste_button(&self, serial_number: &str, key: u8) -> Result<PasteButtonResult, SDClientError>;
+    /// Pastes button that was previously copied, can be from another device or a saved snippet
+    fn paste_portable_button(&self, serial_number: &str, key: u8, data: PortableButton) -> Result<PasteButtonResult, SDClientError>;
 
     /// Creates a new empty button on current screen of a device
     fn new_button(&self, serial_number: &str, key: u8) -> Result<NewButtonResult, SDClientError>;
@@ -158,10 +291,14 @@ pub trait SDSyncRequestClient: Send + Sync {
     fn forcibly_pop_screen(&self, serial_number: &str) -> Result<ForciblyPopScreenResult, SDClientError>;
     /// Replaces current screen on a device
     fn replace_screen(&self, serial_number: &str, screen: RawButtonPanel) -> Result<ReplaceScreenResult, SDClientError>;
+    /// Replaces all buttons of current screen, or of a folder if folder ID is provided, at once
+    fn set_panel(&self, serial_number: &str, panel: RawButtonPanel, folder_id: Option<String>) -> Result<SetPanelResult, SDClientError>;
     /// Resets stack and sets provided screen as root screen
     fn reset_stack(&self, serial_number: &str, screen: RawButtonPanel) -> Result<ResetStackResult, SDClientError>;
     /// Drops stack to root screen
     fn drop_stack_to_root(&self, serial_number: &str) -> Result<DropStackToRootResult, SDClientError>;
+    /// Pops screens until screen at provided stack index is on top, root screen has index 0
+    fn pop_to_screen(&self, serial_number: &str, index: usize) -> Result<PopToScreenResult, SDClientError>;
 
     /// Commits all changes to stack to device config, should be called after each change/sequence of changes, otherwise all changes will be lost on reconnect
     fn commit_changes(&self, serial_number: &str) -> Result<CommitChangesToConfigResult, SDClientError>;
@@ -169,12 +306,81 @@ pub trait SDSyncRequestClient: Send + Sync {
     /// Simulate a press on a button on current screen for a device
     fn do_button_action(&self, serial_number: &str, key: u8) -> Result<DoButtonActionResult, SDClientError>;
 
+    /// Shows notification over current panel of a device, icon is image identifier or base64 image, duration of 0 keeps it until dismissed. Covers all keys if keys are empty
+    fn show_notification(&self, serial_number: &str, text: &str, icon: Option<&str>, duration: f32, keys: Vec<u8>) -> Result<ShowNotificationResult, SDClientError>;
+
+    /// Removes notification before it disappears on its own
+    fn dismiss_notification(&self, serial_number: &str, id: &str) -> Result<DismissNotificationResult, SDClientError>;
+
+    /// Executes multiple requests in order with a single round trip, see [batch::RequestBatch]
+    fn batch(&self, requests: Vec<SocketPacket>) -> Result<BatchResult, SDClientError>;
+
+    /// Gets version, uptime, loaded modules, devices, config paths and degraded components of the daemon
+    fn daemon_status(&self) -> Result<GetDaemonStatus, SDClientError>;
+    /// Gets safe mode status of the daemon
+    fn safe_mode_status(&self) -> Result<GetSafeModeStatus, SDClientError>;
+    /// Makes daemon start in safe mode next time, with only built-in modules, no animations and known good device configs
+    fn set_safe_mode_on_next_start(&self, enabled: bool) -> Result<SetSafeModeOnNextStartResult, SDClientError>;
+    /// Saves all configs and shuts down the daemon, has to be allowed in daemon config
+    fn shutdown_daemon(&self) -> Result<ShutdownDaemonResult, SDClientError>;
+    /// Saves all configs and restarts the daemon, has to be allowed in daemon config
+    fn restart_daemon(&self) -> Result<RestartDaemonResult, SDClientError>;
+
+    /// Starts a session for this connection, token can be used to resume event subscription after reconnecting
+    fn start_session(&self) -> Result<StartSessionResult, SDClientError>;
+    /// Resumes previous session on this connection, events missed while disconnected will be delivered afterwards
+    fn resume_session(&self, token: &str) -> Result<ResumeSessionResult, SDClientError>;
+
+    /// Reports name and version of this client to daemon, shown when listing clients.
+    /// Read-only connection can only make requests that don't change anything, and it can't be undone
+    fn identify(&self, name: &str, version: &str, read_only: bool) -> Result<IdentifyClientResult, SDClientError>;
+    /// Lists clients currently connected to daemon
+    fn list_clients(&self) -> Result<Vec<ClientInfo>, SDClientError>;
+    /// Disconnects a client from daemon
+    fn disconnect_client(&self, id: u64) -> Result<DisconnectClientResult, SDClientError>;
+
+    /// Gets up to limit of most recent log records that daemon captured and pass the filter
+    fn get_recent_logs(&self, limit: usize, filter: LogFilter) -> Result<Vec<LogRecord>, SDClientError>;
+    /// Starts streaming new log records that pass the filter to this connection as "log" packets, stops streaming if filter is [None]
+    fn stream_logs(&self, filter: Option<LogFilter>) -> Result<StreamLogsResult, SDClientError>;
+
+    /// Undoes last button edit made on a device, only works while edited panel is on screen
+    fn undo(&self, serial_number: &str) -> Result<UndoResult, SDClientError>;
+    /// Redoes last undone button edit on a device
+    fn redo(&self, serial_number: &str) -> Result<RedoResult, SDClientError>;
+
     /// Sends a custom packet to daemon and returns response, for use with plugins that utilize socket functionality
     fn send_packet(&self, packet: SocketPacket) -> Result<SocketPacket, SDClientError>;
+    /// Sends a custom packet to daemon and returns response, using provided options instead of client's options
+    fn send_packet_with_options(&self, packet: SocketPacket, options: &RequestOptions) -> Result<SocketPacket, SDClientError>;
     /// Sends a custom packet to daemon and returns response, for use with plugins that utilize socket functionality
     fn send_packet_without_response(&self, packet: SocketPacket) -> Result<(), SDClientError>;
 }
 
+/// Runs provided function with options applied to requests made inside of it, restores previous options afterwards
+pub fn with_request_options<T>(client: &dyn SDSyncRequestClient, options: RequestOptions, func: impl FnOnce(&dyn SDSyncRequestClient) -> T) -> T {
+    let previous = client.request_options();
+    client.set_request_options(options);
+
+    let result = func(client);
+
+    client.set_request_options(previous);
+    result
+}
+
+/// Authenticates client with token from [TOKEN_ENV] environment variable, if it's set
+pub(crate) fn authenticate_from_env(client: &dyn SDSyncRequestClient) -> Result<(), Error> {
+    if let Ok(token) = std::env::var(TOKEN_ENV) {
+        match client.authenticate(&token) {
+            Ok(AuthenticateResult::InvalidToken) => return Err(Error::new(ErrorKind::PermissionDenied, "Daemon rejected the token")),
+            Err(err) => return Err(Error::new(ErrorKind::Other, format!("Failed to authenticate: {:?}", err))),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
 /// Errors that could happen with the client
 #[derive(Debug)]
 pub enum SDClientError {
@@ -182,6 +388,14 @@ pub enum SDClientError {
     SerializeError(serde_json::Error),
     SocketError(streamduck_core::socket::SocketError),
     UTF8Error(std::string::FromUtf8Error),
+    /// Daemon didn't respond in time
+    Timeout,
+    /// Request was cancelled with a cancellation token
+    Cancelled,
+    /// Daemon requires authentication before processing requests
+    Unauthorized,
+    /// Connection is read-only and request would change something
+    PermissionDenied,
     Custom(String)
 }
 
@@ -2,40 +2,68 @@ use std::collections::HashMap;
 use std::io::BufReader;
 use std::ops::DerefMut;
 use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
 use std::sync::{Arc, RwLock, RwLockWriteGuard};
 use rand::distributions::Alphanumeric;
 use rand::Rng;
 
+use serde_json::Value;
+use streamduck_core::config::ConfigMilestone;
 use streamduck_core::core::button::Button;
 use streamduck_core::core::RawButtonPanel;
 use streamduck_core::modules::components::{ComponentDefinition, UIPathValue};
 use streamduck_core::modules::events::SDGlobalEvent;
+use streamduck_core::modules::dependencies::DependencyNode;
 use streamduck_core::modules::PluginMetadata;
+use streamduck_core::portable::PortableButton;
+use streamduck_core::logging::{LogFilter, LogRecord};
+use streamduck_core::presets::ComponentPreset;
+use streamduck_core::thread::orientation::DeviceOrientation;
+use streamduck_core::thread::transition::PanelTransition;
 use streamduck_core::versions::SOCKET_API;
-use streamduck_core::socket::{send_packet_as_is, SocketPacket};
-use streamduck_daemon::daemon_data::assets::{AddImage, AddImageResult, ListFonts, ListImages, ListImagesResult, RemoveImage, RemoveImageResult};
-use streamduck_daemon::daemon_data::buttons::{AddComponent, AddComponentResult, AddComponentValue, AddComponentValueResult, ClearButton, ClearButtonResult, ClipboardStatusResult, CopyButton, CopyButtonResult, GetButton, GetButtonResult, GetComponentValues, GetComponentValuesResult, NewButton, NewButtonFromComponent, NewButtonFromComponentResult, NewButtonResult, PasteButton, PasteButtonResult, RemoveComponent, RemoveComponentResult, RemoveComponentValue, RemoveComponentValueResult, SetButton, SetButtonResult, SetComponentValue, SetComponentValueResult};
-use streamduck_daemon::daemon_data::config::{ExportDeviceConfig, ExportDeviceConfigResult, GetDeviceConfig, GetDeviceConfigResult, ImportDeviceConfig, ImportDeviceConfigResult, ReloadDeviceConfig, ReloadDeviceConfigResult, ReloadDeviceConfigsResult, SaveDeviceConfig, SaveDeviceConfigResult, SaveDeviceConfigsResult};
-use streamduck_daemon::daemon_data::devices::{AddDevice, AddDeviceResult, Device, GetDevice, GetDeviceResult, ListDevices, RemoveDevice, RemoveDeviceResult, SetBrightness, SetBrightnessResult};
-use streamduck_daemon::daemon_data::modules::{AddModuleValue, AddModuleValueResult, GetModuleValues, GetModuleValuesResult, ListComponents, ListModules, RemoveModuleValue, RemoveModuleValueResult, SetModuleValue, SetModuleValueResult};
-use streamduck_daemon::daemon_data::ops::{CommitChangesToConfig, CommitChangesToConfigResult, DoButtonAction, DoButtonActionResult};
-use streamduck_daemon::daemon_data::panels::{DropStackToRoot, DropStackToRootResult, ForciblyPopScreen, ForciblyPopScreenResult, GetButtonImages, GetButtonImagesResult, GetCurrentScreen, GetCurrentScreenResult, GetStack, GetStackNames, GetStackNamesResult, GetStackResult, PopScreen, PopScreenResult, PushScreen, PushScreenResult, ReplaceScreen, ReplaceScreenResult, ResetStack, ResetStackResult};
+use streamduck_core::socket::{ClientInfo, send_packet_as_is, SocketPacket};
+use streamduck_daemon::daemon_data::auth::{Authenticate, AuthenticateResult};
+use streamduck_daemon::daemon_data::assets::{AddImage, AddImageResult, CleanupUnusedImages, CleanupUnusedImagesResult, FinishImageUpload, FinishImageUploadResult, GetImage, GetImageResult, ListFonts, ListImages, ListImagesResult, RemoveImage, RemoveImageResult, StartImageUpload, StartImageUploadResult, UploadImageChunk, UploadImageChunkResult};
+use streamduck_daemon::daemon_data::buttons::{AddComponent, AddComponentResult, AddComponentValue, AddComponentValueResult, ClearButton, ClearButtonResult, ClipboardStatusResult, CopyButton, CopyButtonResult, GetButton, GetButtonResult, GetComponentValues, GetComponentValuesResult, MoveButton, MoveButtonResult, NewButton, NewButtonFromComponent, NewButtonFromComponentResult, NewButtonResult, PasteButton, PasteButtonResult, RemoveComponent, RemoveComponentResult, RemoveComponentValue, RemoveComponentValueResult, SetButton, SetButtonResult, SetComponentValue, SetComponentValueResult};
+use streamduck_daemon::daemon_data::clients::{DisconnectClient, DisconnectClientResult, IdentifyClient, IdentifyClientResult, ListClients};
+use streamduck_daemon::daemon_data::config::{CreateConfigMilestone, CreateConfigMilestoneResult, DiffDeviceConfig, DiffDeviceConfigResult, ExportAllConfigsResult, ExportDeviceConfig, ExportDeviceConfigResult, GetDeviceConfig, GetDeviceConfigResult, ImportAllConfigs, ImportAllConfigsResult, ImportDeviceConfig, ImportDeviceConfigResult, ListConfigMilestones, ReloadDeviceConfig, ReloadDeviceConfigResult, ReloadDeviceConfigsResult, RestoreConfigMilestone, RestoreConfigMilestoneResult, RestoreDeviceConfigBackup, RestoreDeviceConfigBackupResult, SaveDeviceConfig, SaveDeviceConfigResult, SaveDeviceConfigsResult};
+use streamduck_daemon::daemon_data::devices::{AddDevice, AddDeviceResult, Device, GetDevice, GetDeviceResult, ListDevices, RemoveDevice, RemoveDeviceResult, SetBrightness, SetBrightnessResult, SetDeviceMirror, SetDeviceMirrorResult, SetDeviceOrientation, SetDeviceOrientationResult, SetDeviceStartup, SetDeviceStartupResult, SetMirroredLayout, SetMirroredLayoutResult, SetPanelTransition, SetPanelTransitionResult};
+use streamduck_daemon::daemon_data::folders::{CleanupFolders, CleanupFoldersResult, DeleteFolder, DeleteFolderResult, FindButtons, FindButtonsResult, GetFolderTree, GetFolderTreeResult, ListFolders, ListFoldersResult, RenameFolder, RenameFolderResult};
+use streamduck_daemon::daemon_data::history::{Redo, RedoResult, Undo, UndoResult};
+use streamduck_daemon::daemon_data::modules::{AddModuleValue, AddModuleValueResult, GetGlobalPluginSettings, GetGlobalPluginSettingsResult, GetModuleValues, GetModuleValuesResult, GetPluginDependencies, ListComponents, ListModuleSettings, ListModules, ListPluginRequests, ReenablePlugin, ReenablePluginResult, RemoveModuleValue, RemoveModuleValueResult, SendModuleMessage, SendModuleMessageResult, SetGlobalPluginSettings, SetGlobalPluginSettingsResult, SetModuleValue, SetModuleValueResult};
+use streamduck_daemon::daemon_data::logs::{GetRecentLogs, GetRecentLogsResult, StreamLogs, StreamLogsResult};
+use streamduck_daemon::daemon_data::notifications::{DismissNotification, DismissNotificationResult, ShowNotification, ShowNotificationResult};
+use streamduck_daemon::daemon_data::ops::{Batch, BatchResult, CommitChangesToConfig, CommitChangesToConfigResult, DoButtonAction, DoButtonActionResult, GetSafeModeStatus, RestartDaemonResult, SetSafeModeOnNextStart, SetSafeModeOnNextStartResult, ShutdownDaemonResult};
+use streamduck_daemon::daemon_data::layout::{ApplyLayout, ApplyLayoutResult, ExportLayout, ExportLayoutResult, InstantiateTemplate, InstantiateTemplateResult};
+use streamduck_daemon::daemon_data::presets::{ApplyPreset, ApplyPresetResult, ListPresets, RemovePreset, RemovePresetResult, SavePreset, SavePresetResult};
+use streamduck_daemon::daemon_data::remote::{RegisterRemoteModule, RegisterRemoteModuleResult, RemoteModuleResponse, RemoteModuleResponseResult, UnregisterRemoteModule, UnregisterRemoteModuleResult};
+use streamduck_daemon::daemon_data::status::GetDaemonStatus;
+use streamduck_daemon::daemon_data::revisions::{GetScreenRevisions, GetScreenRevisionsResult};
+use streamduck_daemon::daemon_data::sessions::{ResumeSession, ResumeSessionResult, StartSessionResult};
+use streamduck_daemon::daemon_data::panels::{DropStackToRoot, DropStackToRootResult, ForciblyPopScreen, ForciblyPopScreenResult, GetButtonImages, GetButtonImagesResult, GetCurrentScreen, GetCurrentScreenResult, GetStack, GetStackBreadcrumbs, GetStackBreadcrumbsResult, GetStackNames, GetStackNamesResult, GetStackResult, PopScreen, PopScreenResult, PopToScreen, PopToScreenResult, PushScreen, PushScreenResult, RenderButtonPreview, RenderButtonPreviewResult, ReplaceScreen, ReplaceScreenResult, ResetStack, ResetStackResult, SetPanel, SetPanelResult};
 use streamduck_daemon::daemon_data::SocketAPIVersion;
+use streamduck_daemon::remote::{REMOTE_CALL_PACKET, RemoteModuleCall};
 use streamduck_daemon::UNIX_SOCKET_PATH;
 
-use crate::{SDSyncRequestClient, SDClientError, SDSyncEventClient, SDSyncClient, SDSyncUpcastRequestClient, SDSyncUpcastEventClient};
-use crate::util::{process_request, process_request_without_data, read_response, read_socket};
+use crate::{authenticate_from_env, SOCKET_PATH_ENV, SDSyncRequestClient, SDClientError, SDSyncEventClient, SDSyncClient, SDSyncUpcastRequestClient, SDSyncUpcastEventClient};
+use crate::util::{process_request, process_request_without_data, read_response_with_options, read_socket, RequestOptions};
 
 /// Unix Socket based Streamduck client
 pub struct UnixClient {
-    connection: RwLock<BufReader<UnixStream>>
+    connection: RwLock<BufReader<UnixStream>>,
+    options: RwLock<RequestOptions>,
 }
 
 #[allow(dead_code)]
 impl UnixClient {
     fn make_client() -> Result<UnixClient, std::io::Error> {
+        let path = std::env::var_os(SOCKET_PATH_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(UNIX_SOCKET_PATH));
+
         let client = UnixClient {
-            connection: RwLock::new(BufReader::new(UnixStream::connect(UNIX_SOCKET_PATH)?))
+            connection: RwLock::new(BufReader::new(UnixStream::connect(path)?)),
+            options: Default::default()
         };
 
         let daemon_version = client.version().expect("Failed to retrieve version");
@@ -44,6 +72,8 @@ impl UnixClient {
             println!("[Warning] Version of client library doesn't match daemon API version. Client: {}, Daemon: {}", SOCKET_API.1, daemon_version);
         }
 
+        authenticate_from_env(&client)?;
+
         Ok(client)
     }
 
@@ -65,23 +95,43 @@ impl UnixClient {
     fn get_handle(&self) -> RwLockWriteGuard<BufReader<UnixStream>> {
         self.connection.write().unwrap()
     }
+
+    fn get_options(&self) -> RequestOptions {
+        self.options.read().unwrap().clone()
+    }
 }
 
 impl SDSyncRequestClient for UnixClient {
+    fn set_request_options(&self, options: RequestOptions) {
+        *self.options.write().unwrap() = options;
+    }
+
+    fn request_options(&self) -> RequestOptions {
+        self.get_options()
+    }
+
     fn version(&self) -> Result<String, SDClientError> {
-        let response: SocketAPIVersion = process_request_without_data(self.get_handle().deref_mut())?;
+        let response: SocketAPIVersion = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
 
         Ok(response.version)
     }
 
+    fn authenticate(&self, token: &str) -> Result<AuthenticateResult, SDClientError> {
+        let response: AuthenticateResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &Authenticate {
+            token: token.to_string()
+        })?;
+
+        Ok(response)
+    }
+
     fn device_list(&self) -> Result<Vec<Device>, SDClientError> {
-        let response: ListDevices = process_request_without_data(self.get_handle().deref_mut())?;
+        let response: ListDevices = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
 
         Ok(response.devices)
     }
 
     fn get_device(&self, serial_number: &str) -> Result<GetDeviceResult, SDClientError> {
-        let response: GetDeviceResult = process_request(self.get_handle().deref_mut(), &GetDevice {
+        let response: GetDeviceResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetDevice {
             serial_number: serial_number.to_string()
         })?;
 
@@ -89,7 +139,7 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn add_device(&self, serial_number: &str) -> Result<AddDeviceResult, SDClientError> {
-        let response: AddDeviceResult = process_request(self.get_handle().deref_mut(), &AddDevice {
+        let response: AddDeviceResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &AddDevice {
             serial_number: serial_number.to_string()
         })?;
 
@@ -97,7 +147,7 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn remove_device(&self, serial_number: &str) -> Result<RemoveDeviceResult, SDClientError> {
-        let response: RemoveDeviceResult = process_request(self.get_handle().deref_mut(), &RemoveDevice {
+        let response: RemoveDeviceResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &RemoveDevice {
             serial_number: serial_number.to_string()
         })?;
 
@@ -105,13 +155,13 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn reload_device_configs(&self) -> Result<ReloadDeviceConfigsResult, SDClientError> {
-        let response: ReloadDeviceConfigsResult = process_request_without_data(self.get_handle().deref_mut())?;
+        let response: ReloadDeviceConfigsResult = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
 
         Ok(response)
     }
 
     fn reload_device_config(&self, serial_number: &str) -> Result<ReloadDeviceConfigResult, SDClientError> {
-        let response: ReloadDeviceConfigResult = process_request(self.get_handle().deref_mut(), &ReloadDeviceConfig {
+        let response: ReloadDeviceConfigResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ReloadDeviceConfig {
             serial_number: serial_number.to_string()
         })?;
 
@@ -119,13 +169,13 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn save_device_configs(&self) -> Result<SaveDeviceConfigsResult, SDClientError> {
-        let response: SaveDeviceConfigsResult = process_request_without_data(self.get_handle().deref_mut())?;
+        let response: SaveDeviceConfigsResult = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
 
         Ok(response)
     }
 
     fn save_device_config(&self, serial_number: &str) -> Result<SaveDeviceConfigResult, SDClientError> {
-        let response: SaveDeviceConfigResult = process_request(self.get_handle().deref_mut(), &SaveDeviceConfig {
+        let response: SaveDeviceConfigResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &SaveDeviceConfig {
             serial_number: serial_number.to_string()
         })?;
 
@@ -133,15 +183,25 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn get_device_config(&self, serial_number: &str) -> Result<GetDeviceConfigResult, SDClientError> {
-        let response: GetDeviceConfigResult = process_request(self.get_handle().deref_mut(), &GetDeviceConfig {
+        let response: GetDeviceConfigResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetDeviceConfig {
             serial_number: serial_number.to_string()
         })?;
 
         Ok(response)
     }
 
-    fn import_device_config(&self, serial_number: &str, config: String) -> Result<ImportDeviceConfigResult, SDClientError> {
-        let response: ImportDeviceConfigResult = process_request(self.get_handle().deref_mut(), &ImportDeviceConfig {
+    fn import_device_config(&self, serial_number: &str, config: String, dry_run: bool) -> Result<ImportDeviceConfigResult, SDClientError> {
+        let response: ImportDeviceConfigResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ImportDeviceConfig {
+            serial_number: serial_number.to_string(),
+            config,
+            dry_run
+        })?;
+
+        Ok(response)
+    }
+
+    fn diff_device_config(&self, serial_number: &str, config: String) -> Result<DiffDeviceConfigResult, SDClientError> {
+        let response: DiffDeviceConfigResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &DiffDeviceConfig {
             serial_number: serial_number.to_string(),
             config
         })?;
@@ -149,16 +209,180 @@ impl SDSyncRequestClient for UnixClient {
         Ok(response)
     }
 
-    fn export_device_config(&self, serial_number: &str) -> Result<ExportDeviceConfigResult, SDClientError> {
-        let response: ExportDeviceConfigResult = process_request(self.get_handle().deref_mut(), &ExportDeviceConfig {
+    fn export_device_config(&self, serial_number: &str, include_images: bool) -> Result<ExportDeviceConfigResult, SDClientError> {
+        let response: ExportDeviceConfigResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ExportDeviceConfig {
+            serial_number: serial_number.to_string(),
+            include_images
+        })?;
+
+        Ok(response)
+    }
+
+    fn restore_device_config_backup(&self, serial_number: &str, backup: usize) -> Result<RestoreDeviceConfigBackupResult, SDClientError> {
+        let response: RestoreDeviceConfigBackupResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &RestoreDeviceConfigBackup {
+            serial_number: serial_number.to_string(),
+            backup
+        })?;
+
+        Ok(response)
+    }
+
+    fn create_config_milestone(&self, name: &str) -> Result<CreateConfigMilestoneResult, SDClientError> {
+        let response: CreateConfigMilestoneResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &CreateConfigMilestone {
+            name: name.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn list_config_milestones(&self) -> Result<Vec<ConfigMilestone>, SDClientError> {
+        let response: ListConfigMilestones = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+
+        Ok(response.milestones)
+    }
+
+    fn restore_config_milestone(&self, name: &str) -> Result<RestoreConfigMilestoneResult, SDClientError> {
+        let response: RestoreConfigMilestoneResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &RestoreConfigMilestone {
+            name: name.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn export_all_configs(&self) -> Result<ExportAllConfigsResult, SDClientError> {
+        let response: ExportAllConfigsResult = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+
+        Ok(response)
+    }
+
+    fn import_all_configs(&self, bundle: String) -> Result<ImportAllConfigsResult, SDClientError> {
+        let response: ImportAllConfigsResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ImportAllConfigs {
+            bundle
+        })?;
+
+        Ok(response)
+    }
+
+    fn apply_layout(&self, serial_number: &str, layout: String, dry_run: bool) -> Result<ApplyLayoutResult, SDClientError> {
+        let response: ApplyLayoutResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ApplyLayout {
+            serial_number: serial_number.to_string(),
+            layout,
+            dry_run
+        })?;
+
+        Ok(response)
+    }
+
+    fn export_layout(&self, serial_number: &str) -> Result<ExportLayoutResult, SDClientError> {
+        let response: ExportLayoutResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ExportLayout {
+            serial_number: serial_number.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn instantiate_template(&self, serial_number: &str, template: String, variables: HashMap<String, String>, dry_run: bool) -> Result<InstantiateTemplateResult, SDClientError> {
+        let response: InstantiateTemplateResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &InstantiateTemplate {
+            serial_number: serial_number.to_string(),
+            template,
+            variables,
+            dry_run
+        })?;
+
+        Ok(response)
+    }
+
+    fn list_folders(&self, serial_number: &str) -> Result<ListFoldersResult, SDClientError> {
+        let response: ListFoldersResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ListFolders {
             serial_number: serial_number.to_string()
         })?;
 
         Ok(response)
     }
 
+    fn folder_tree(&self, serial_number: &str, include_buttons: bool) -> Result<GetFolderTreeResult, SDClientError> {
+        let response: GetFolderTreeResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetFolderTree {
+            serial_number: serial_number.to_string(),
+            include_buttons
+        })?;
+
+        Ok(response)
+    }
+
+    fn find_buttons(&self, serial_number: &str, component_name: Option<&str>, query: Option<&str>) -> Result<FindButtonsResult, SDClientError> {
+        let response: FindButtonsResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &FindButtons {
+            serial_number: serial_number.to_string(),
+            component_name: component_name.map(|x| x.to_string()),
+            query: query.map(|x| x.to_string())
+        })?;
+
+        Ok(response)
+    }
+
+    fn rename_folder(&self, serial_number: &str, id: &str, name: &str) -> Result<RenameFolderResult, SDClientError> {
+        let response: RenameFolderResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &RenameFolder {
+            serial_number: serial_number.to_string(),
+            id: id.to_string(),
+            name: name.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn delete_folder(&self, serial_number: &str, id: &str) -> Result<DeleteFolderResult, SDClientError> {
+        let response: DeleteFolderResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &DeleteFolder {
+            serial_number: serial_number.to_string(),
+            id: id.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn cleanup_folders(&self, serial_number: &str) -> Result<CleanupFoldersResult, SDClientError> {
+        let response: CleanupFoldersResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &CleanupFolders {
+            serial_number: serial_number.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn list_presets(&self) -> Result<HashMap<String, ComponentPreset>, SDClientError> {
+        let response: ListPresets = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+
+
+        Ok(response.presets)
+    }
+
+    fn save_preset(&self, name: &str, preset: ComponentPreset, propagate: bool) -> Result<SavePresetResult, SDClientError> {
+        let response: SavePresetResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &SavePreset {
+            name: name.to_string(),
+            preset,
+            propagate
+        })?;
+
+        Ok(response)
+    }
+
+    fn remove_preset(&self, name: &str) -> Result<RemovePresetResult, SDClientError> {
+        let response: RemovePresetResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &RemovePreset {
+            name: name.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn apply_preset(&self, serial_number: &str, key: u8, name: &str) -> Result<ApplyPresetResult, SDClientError> {
+        let response: ApplyPresetResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ApplyPreset {
+            serial_number: serial_number.to_string(),
+            key,
+            name: name.to_string()
+        })?;
+
+        Ok(response)
+    }
+
     fn set_brightness(&self, serial_number: &str, brightness: u8) -> Result<SetBrightnessResult, SDClientError> {
-        let response: SetBrightnessResult = process_request(self.get_handle().deref_mut(), &SetBrightness {
+        let response: SetBrightnessResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &SetBrightness {
             serial_number: serial_number.to_string(),
             brightness
         })?;
@@ -166,8 +390,55 @@ impl SDSyncRequestClient for UnixClient {
         Ok(response)
     }
 
+    fn set_panel_transition(&self, serial_number: &str, transition: Option<PanelTransition>) -> Result<SetPanelTransitionResult, SDClientError> {
+        let response: SetPanelTransitionResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &SetPanelTransition {
+            serial_number: serial_number.to_string(),
+            transition
+        })?;
+
+        Ok(response)
+    }
+
+    fn set_device_mirror(&self, serial_number: &str, source: Option<&str>) -> Result<SetDeviceMirrorResult, SDClientError> {
+        let response: SetDeviceMirrorResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &SetDeviceMirror {
+            serial_number: serial_number.to_string(),
+            source: source.map(|x| x.to_string())
+        })?;
+
+        Ok(response)
+    }
+
+    fn set_device_orientation(&self, serial_number: &str, orientation: DeviceOrientation) -> Result<SetDeviceOrientationResult, SDClientError> {
+        let response: SetDeviceOrientationResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &SetDeviceOrientation {
+            serial_number: serial_number.to_string(),
+            orientation
+        })?;
+
+        Ok(response)
+    }
+
+    fn set_mirrored_layout(&self, serial_number: &str, mirrored: bool, flip_images: bool) -> Result<SetMirroredLayoutResult, SDClientError> {
+        let response: SetMirroredLayoutResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &SetMirroredLayout {
+            serial_number: serial_number.to_string(),
+            mirrored,
+            flip_images
+        })?;
+
+        Ok(response)
+    }
+
+    fn set_device_startup(&self, serial_number: &str, folder_id: Option<&str>, actions: Vec<Button>) -> Result<SetDeviceStartupResult, SDClientError> {
+        let response: SetDeviceStartupResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &SetDeviceStartup {
+            serial_number: serial_number.to_string(),
+            folder_id: folder_id.map(|x| x.to_string()),
+            actions
+        })?;
+
+        Ok(response)
+    }
+
     fn list_images(&self, serial_number: &str) -> Result<ListImagesResult, SDClientError> {
-        let response: ListImagesResult = process_request(self.get_handle().deref_mut(), &ListImages {
+        let response: ListImagesResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ListImages {
             serial_number: serial_number.to_string()
         })?;
 
@@ -175,7 +446,7 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn add_image(&self, serial_number: &str, image_data: &str) -> Result<AddImageResult, SDClientError> {
-        let response: AddImageResult = process_request(self.get_handle().deref_mut(), &AddImage {
+        let response: AddImageResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &AddImage {
             serial_number: serial_number.to_string(),
             image_data: image_data.to_string()
         })?;
@@ -184,7 +455,7 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn remove_image(&self, serial_number: &str, identifier: &str) -> Result<RemoveImageResult, SDClientError> {
-        let response: RemoveImageResult = process_request(self.get_handle().deref_mut(), &RemoveImage {
+        let response: RemoveImageResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &RemoveImage {
             serial_number: serial_number.to_string(),
             image_identifier: identifier.to_string()
         })?;
@@ -192,34 +463,132 @@ impl SDSyncRequestClient for UnixClient {
         Ok(response)
     }
 
+    fn get_image(&self, serial_number: &str, identifier: &str, offset: usize) -> Result<GetImageResult, SDClientError> {
+        let response: GetImageResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetImage {
+            serial_number: serial_number.to_string(),
+            image_identifier: identifier.to_string(),
+            offset
+        })?;
+
+        Ok(response)
+    }
+
+    fn start_image_upload(&self, serial_number: &str) -> Result<StartImageUploadResult, SDClientError> {
+        let response: StartImageUploadResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &StartImageUpload {
+            serial_number: serial_number.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn upload_image_chunk(&self, upload_id: &str, data: &str) -> Result<UploadImageChunkResult, SDClientError> {
+        let response: UploadImageChunkResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &UploadImageChunk {
+            upload_id: upload_id.to_string(),
+            data: data.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn finish_image_upload(&self, upload_id: &str) -> Result<FinishImageUploadResult, SDClientError> {
+        let response: FinishImageUploadResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &FinishImageUpload {
+            upload_id: upload_id.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn cleanup_unused_images(&self, serial_number: Option<&str>) -> Result<CleanupUnusedImagesResult, SDClientError> {
+        let response: CleanupUnusedImagesResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &CleanupUnusedImages {
+            serial_number: serial_number.map(|x| x.to_string())
+        })?;
+
+        Ok(response)
+    }
+
     fn list_fonts(&self) -> Result<Vec<String>, SDClientError> {
-        let response: ListFonts = process_request_without_data(self.get_handle().deref_mut())?;
+        let response: ListFonts = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
 
         Ok(response.font_names)
     }
 
     fn list_modules(&self) -> Result<Vec<PluginMetadata>, SDClientError> {
-        let response: ListModules = process_request_without_data(self.get_handle().deref_mut())?;
+        let response: ListModules = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
 
         Ok(response.modules)
     }
 
     fn list_components(&self) -> Result<HashMap<String, HashMap<String, ComponentDefinition>>, SDClientError> {
-        let response: ListComponents = process_request_without_data(self.get_handle().deref_mut())?;
+        let response: ListComponents = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
 
         Ok(response.components)
     }
 
+    fn list_plugin_requests(&self) -> Result<Vec<String>, SDClientError> {
+        let response: ListPluginRequests = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+
+        Ok(response.requests)
+    }
+
+    fn get_plugin_dependencies(&self) -> Result<Vec<DependencyNode>, SDClientError> {
+        let response: GetPluginDependencies = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+
+        Ok(response.modules)
+    }
+
+    fn reenable_plugin(&self, name: &str) -> Result<ReenablePluginResult, SDClientError> {
+        let response: ReenablePluginResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ReenablePlugin {
+            name: name.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn register_remote_module(&self, session_token: &str, metadata: PluginMetadata, components: HashMap<String, ComponentDefinition>, listening_for: Vec<String>) -> Result<RegisterRemoteModuleResult, SDClientError> {
+        let response: RegisterRemoteModuleResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &RegisterRemoteModule {
+            session_token: session_token.to_string(),
+            metadata,
+            components,
+            listening_for
+        })?;
+
+        Ok(response)
+    }
+
+    fn unregister_remote_module(&self, name: &str) -> Result<UnregisterRemoteModuleResult, SDClientError> {
+        let response: UnregisterRemoteModuleResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &UnregisterRemoteModule {
+            name: name.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn respond_to_remote_call(&self, module: &str, call_id: u64, data: Value) -> Result<RemoteModuleResponseResult, SDClientError> {
+        let response: RemoteModuleResponseResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &RemoteModuleResponse {
+            module: module.to_string(),
+            call_id,
+            data
+        })?;
+
+        Ok(response)
+    }
+
     fn get_module_values(&self, module_name: &str) -> Result<GetModuleValuesResult, SDClientError> {
-        let response: GetModuleValuesResult = process_request(self.get_handle().deref_mut(), &GetModuleValues {
+        let response: GetModuleValuesResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetModuleValues {
             module_name: module_name.to_string()
         })?;
 
         Ok(response)
     }
 
+    fn list_module_settings(&self) -> Result<HashMap<String, Vec<UIPathValue>>, SDClientError> {
+        let response: ListModuleSettings = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+
+        Ok(response.settings)
+    }
+
     fn add_module_value(&self, module_name: &str, path: &str) -> Result<AddModuleValueResult, SDClientError> {
-        let response: AddModuleValueResult = process_request(self.get_handle().deref_mut(), &AddModuleValue {
+        let response: AddModuleValueResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &AddModuleValue {
             module_name: module_name.to_string(),
             path: path.to_string()
         })?;
@@ -228,7 +597,7 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn remove_module_value(&self, module_name: &str, path: &str, index: usize) -> Result<RemoveModuleValueResult, SDClientError> {
-        let response: RemoveModuleValueResult = process_request(self.get_handle().deref_mut(), &RemoveModuleValue {
+        let response: RemoveModuleValueResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &RemoveModuleValue {
             module_name: module_name.to_string(),
             path: path.to_string(),
             index
@@ -238,7 +607,7 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn set_module_value(&self, module_name: &str, value: UIPathValue) -> Result<SetModuleValueResult, SDClientError> {
-        let response: SetModuleValueResult = process_request(self.get_handle().deref_mut(), &SetModuleValue {
+        let response: SetModuleValueResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &SetModuleValue {
             module_name: module_name.to_string(),
             value
         })?;
@@ -246,8 +615,35 @@ impl SDSyncRequestClient for UnixClient {
         Ok(response)
     }
 
+    fn get_global_plugin_settings(&self, name: &str) -> Result<GetGlobalPluginSettingsResult, SDClientError> {
+        let response: GetGlobalPluginSettingsResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetGlobalPluginSettings {
+            name: name.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn set_global_plugin_settings(&self, name: &str, settings: Value) -> Result<SetGlobalPluginSettingsResult, SDClientError> {
+        let response: SetGlobalPluginSettingsResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &SetGlobalPluginSettings {
+            name: name.to_string(),
+            settings
+        })?;
+
+        Ok(response)
+    }
+
+    fn send_module_message(&self, topic: &str, data: Value, serial_number: Option<&str>) -> Result<SendModuleMessageResult, SDClientError> {
+        let response: SendModuleMessageResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &SendModuleMessage {
+            topic: topic.to_string(),
+            data,
+            serial_number: serial_number.map(|x| x.to_string())
+        })?;
+
+        Ok(response)
+    }
+
     fn get_stack(&self, serial_number: &str) -> Result<GetStackResult, SDClientError> {
-        let response: GetStackResult = process_request(self.get_handle().deref_mut(), &GetStack {
+        let response: GetStackResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetStack {
             serial_number: serial_number.to_string()
         })?;
 
@@ -255,7 +651,15 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn get_stack_names(&self, serial_number: &str) -> Result<GetStackNamesResult, SDClientError> {
-        let response: GetStackNamesResult = process_request(self.get_handle().deref_mut(), &GetStackNames {
+        let response: GetStackNamesResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetStackNames {
+            serial_number: serial_number.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn get_stack_breadcrumbs(&self, serial_number: &str) -> Result<GetStackBreadcrumbsResult, SDClientError> {
+        let response: GetStackBreadcrumbsResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetStackBreadcrumbs {
             serial_number: serial_number.to_string()
         })?;
 
@@ -263,7 +667,7 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn get_current_screen(&self, serial_number: &str) -> Result<GetCurrentScreenResult, SDClientError> {
-        let response: GetCurrentScreenResult = process_request(self.get_handle().deref_mut(), &GetCurrentScreen {
+        let response: GetCurrentScreenResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetCurrentScreen {
             serial_number: serial_number.to_string()
         })?;
 
@@ -271,7 +675,27 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn get_button_images(&self, serial_number: &str) -> Result<GetButtonImagesResult, SDClientError> {
-        let response: GetButtonImagesResult = process_request(self.get_handle().deref_mut(), &GetButtonImages {
+        let response: GetButtonImagesResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetButtonImages {
+            serial_number: serial_number.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn render_button_preview(&self, serial_number: &str, key: u8, button: Button, size: (u32, u32)) -> Result<RenderButtonPreviewResult, SDClientError> {
+        let response: RenderButtonPreviewResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &RenderButtonPreview {
+            serial_number: serial_number.to_string(),
+            key,
+            button,
+            width: size.0,
+            height: size.1
+        })?;
+
+        Ok(response)
+    }
+
+    fn screen_revisions(&self, serial_number: &str) -> Result<GetScreenRevisionsResult, SDClientError> {
+        let response: GetScreenRevisionsResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetScreenRevisions {
             serial_number: serial_number.to_string()
         })?;
 
@@ -279,7 +703,7 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn get_button(&self, serial_number: &str, key: u8) -> Result<GetButtonResult, SDClientError> {
-        let response: GetButtonResult = process_request(self.get_handle().deref_mut(), &GetButton {
+        let response: GetButtonResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetButton {
             serial_number: serial_number.to_string(),
             key
         })?;
@@ -288,32 +712,46 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn set_button(&self, serial_number: &str, key: u8, button: Button) -> Result<SetButtonResult, SDClientError> {
-        let response: SetButtonResult = process_request(self.get_handle().deref_mut(), &SetButton {
+        let response: SetButtonResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &SetButton {
             serial_number: serial_number.to_string(),
             key,
-            button
+            button,
+            revision: None
         })?;
 
         Ok(response)
     }
 
     fn clear_button(&self, serial_number: &str, key: u8) -> Result<ClearButtonResult, SDClientError> {
-        let response: ClearButtonResult = process_request(self.get_handle().deref_mut(), &ClearButton {
+        let response: ClearButtonResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ClearButton {
             serial_number: serial_number.to_string(),
-            key
+            key,
+            revision: None
+        })?;
+
+        Ok(response)
+    }
+
+    fn move_button(&self, serial_number: &str, from: u8, to: u8, swap: bool) -> Result<MoveButtonResult, SDClientError> {
+        let response: MoveButtonResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &MoveButton {
+            serial_number: serial_number.to_string(),
+            from,
+            to,
+            swap,
+            revision: None
         })?;
 
         Ok(response)
     }
 
     fn clipboard_status(&self) -> Result<ClipboardStatusResult, SDClientError> {
-        let response: ClipboardStatusResult = process_request_without_data(self.get_handle().deref_mut())?;
+        let response: ClipboardStatusResult = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
 
         Ok(response)
     }
 
     fn copy_button(&self, serial_number: &str, key: u8) -> Result<CopyButtonResult, SDClientError> {
-        let response: CopyButtonResult = process_request(self.get_handle().deref_mut(), &CopyButton {
+        let response: CopyButtonResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &CopyButton {
             serial_number: serial_number.to_string(),
             key
         })?;
@@ -322,45 +760,61 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn paste_button(&self, serial_number: &str, key: u8) -> Result<PasteButtonResult, SDClientError> {
-        let response: PasteButtonResult = process_request(self.get_handle().deref_mut(), &PasteButton {
+        let response: PasteButtonResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &PasteButton {
             serial_number: serial_number.to_string(),
-            key
+            key,
+            data: None,
+            revision: None
+        })?;
+
+        Ok(response)
+    }
+
+    fn paste_portable_button(&self, serial_number: &str, key: u8, data: PortableButton) -> Result<PasteButtonResult, SDClientError> {
+        let response: PasteButtonResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &PasteButton {
+            serial_number: serial_number.to_string(),
+            key,
+            data: Some(data),
+            revision: None
         })?;
 
         Ok(response)
     }
 
     fn new_button(&self, serial_number: &str, key: u8) -> Result<NewButtonResult, SDClientError> {
-        let response: NewButtonResult = process_request(self.get_handle().deref_mut(), &NewButton {
+        let response: NewButtonResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &NewButton {
             serial_number: serial_number.to_string(),
-            key
+            key,
+            revision: None
         })?;
 
         Ok(response)
     }
 
     fn new_button_from_component(&self, serial_number: &str, key: u8, component_name: &str) -> Result<NewButtonFromComponentResult, SDClientError> {
-        let response: NewButtonFromComponentResult = process_request(self.get_handle().deref_mut(), &NewButtonFromComponent {
+        let response: NewButtonFromComponentResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &NewButtonFromComponent {
             serial_number: serial_number.to_string(),
             key,
-            component_name: component_name.to_string()
+            component_name: component_name.to_string(),
+            revision: None
         })?;
 
         Ok(response)
     }
 
     fn add_component(&self, serial_number: &str, key: u8, component_name: &str) -> Result<AddComponentResult, SDClientError> {
-        let response: AddComponentResult = process_request(self.get_handle().deref_mut(), &AddComponent {
+        let response: AddComponentResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &AddComponent {
             serial_number: serial_number.to_string(),
             key,
-            component_name: component_name.to_string()
+            component_name: component_name.to_string(),
+            revision: None
         })?;
 
         Ok(response)
     }
 
     fn get_component_values(&self, serial_number: &str, key: u8, component_name: &str) -> Result<GetComponentValuesResult, SDClientError> {
-        let response: GetComponentValuesResult = process_request(self.get_handle().deref_mut(), &GetComponentValues {
+        let response: GetComponentValuesResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetComponentValues {
             serial_number: serial_number.to_string(),
             key,
             component_name: component_name.to_string()
@@ -370,51 +824,55 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn add_component_value(&self, serial_number: &str, key: u8, component_name: &str, path: &str) -> Result<AddComponentValueResult, SDClientError> {
-        let response: AddComponentValueResult = process_request(self.get_handle().deref_mut(), &AddComponentValue {
+        let response: AddComponentValueResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &AddComponentValue {
             serial_number: serial_number.to_string(),
             key,
             component_name: component_name.to_string(),
-            path: path.to_string()
+            path: path.to_string(),
+            revision: None
         })?;
 
         Ok(response)
     }
 
     fn remove_component_value(&self, serial_number: &str, key: u8, component_name: &str, path: &str, index: usize) -> Result<RemoveComponentValueResult, SDClientError> {
-        let response: RemoveComponentValueResult = process_request(self.get_handle().deref_mut(), &RemoveComponentValue {
+        let response: RemoveComponentValueResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &RemoveComponentValue {
             serial_number: serial_number.to_string(),
             key,
             component_name: component_name.to_string(),
             path: path.to_string(),
-            index
+            index,
+            revision: None
         })?;
 
         Ok(response)
     }
 
     fn set_component_value(&self, serial_number: &str, key: u8, component_name: &str, value: UIPathValue) -> Result<SetComponentValueResult, SDClientError> {
-        let response: SetComponentValueResult = process_request(self.get_handle().deref_mut(), &SetComponentValue {
+        let response: SetComponentValueResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &SetComponentValue {
             serial_number: serial_number.to_string(),
             key,
             component_name: component_name.to_string(),
-            value
+            value,
+            revision: None
         })?;
 
         Ok(response)
     }
 
     fn remove_component(&self, serial_number: &str, key: u8, component_name: &str) -> Result<RemoveComponentResult, SDClientError> {
-        let response: RemoveComponentResult = process_request(self.get_handle().deref_mut(), &RemoveComponent {
+        let response: RemoveComponentResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &RemoveComponent {
             serial_number: serial_number.to_string(),
             key,
-            component_name: component_name.to_string()
+            component_name: component_name.to_string(),
+            revision: None
         })?;
 
         Ok(response)
     }
 
     fn push_screen(&self, serial_number: &str, screen: RawButtonPanel) -> Result<PushScreenResult, SDClientError> {
-        let response: PushScreenResult = process_request(self.get_handle().deref_mut(), &PushScreen {
+        let response: PushScreenResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &PushScreen {
             serial_number: serial_number.to_string(),
             screen
         })?;
@@ -423,7 +881,7 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn pop_screen(&self, serial_number: &str) -> Result<PopScreenResult, SDClientError> {
-        let response: PopScreenResult = process_request(self.get_handle().deref_mut(), &PopScreen {
+        let response: PopScreenResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &PopScreen {
             serial_number: serial_number.to_string()
         })?;
 
@@ -431,7 +889,7 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn forcibly_pop_screen(&self, serial_number: &str) -> Result<ForciblyPopScreenResult, SDClientError> {
-        let response: ForciblyPopScreenResult = process_request(self.get_handle().deref_mut(), &ForciblyPopScreen {
+        let response: ForciblyPopScreenResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ForciblyPopScreen {
             serial_number: serial_number.to_string()
         })?;
 
@@ -439,7 +897,7 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn replace_screen(&self, serial_number: &str, screen: RawButtonPanel) -> Result<ReplaceScreenResult, SDClientError> {
-        let response: ReplaceScreenResult = process_request(self.get_handle().deref_mut(), &ReplaceScreen {
+        let response: ReplaceScreenResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ReplaceScreen {
             serial_number: serial_number.to_string(),
             screen
         })?;
@@ -447,8 +905,19 @@ impl SDSyncRequestClient for UnixClient {
         Ok(response)
     }
 
+    fn set_panel(&self, serial_number: &str, panel: RawButtonPanel, folder_id: Option<String>) -> Result<SetPanelResult, SDClientError> {
+        let response: SetPanelResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &SetPanel {
+            serial_number: serial_number.to_string(),
+            panel,
+            folder_id,
+            revision: None
+        })?;
+
+        Ok(response)
+    }
+
     fn reset_stack(&self, serial_number: &str, screen: RawButtonPanel) -> Result<ResetStackResult, SDClientError> {
-        let response: ResetStackResult = process_request(self.get_handle().deref_mut(), &ResetStack {
+        let response: ResetStackResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ResetStack {
             serial_number: serial_number.to_string(),
             screen
         })?;
@@ -457,15 +926,24 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn drop_stack_to_root(&self, serial_number: &str) -> Result<DropStackToRootResult, SDClientError> {
-        let response: DropStackToRootResult = process_request(self.get_handle().deref_mut(), &DropStackToRoot {
+        let response: DropStackToRootResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &DropStackToRoot {
             serial_number: serial_number.to_string()
         })?;
 
         Ok(response)
     }
 
+    fn pop_to_screen(&self, serial_number: &str, index: usize) -> Result<PopToScreenResult, SDClientError> {
+        let response: PopToScreenResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &PopToScreen {
+            serial_number: serial_number.to_string(),
+            index
+        })?;
+
+        Ok(response)
+    }
+
     fn commit_changes(&self, serial_number: &str) -> Result<CommitChangesToConfigResult, SDClientError> {
-        let response: CommitChangesToConfigResult = process_request(self.get_handle().deref_mut(), &CommitChangesToConfig {
+        let response: CommitChangesToConfigResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &CommitChangesToConfig {
             serial_number: serial_number.to_string()
         })?;
 
@@ -473,7 +951,7 @@ impl SDSyncRequestClient for UnixClient {
     }
 
     fn do_button_action(&self, serial_number: &str, key: u8) -> Result<DoButtonActionResult, SDClientError> {
-        let response: DoButtonActionResult = process_request(self.get_handle().deref_mut(), &DoButtonAction {
+        let response: DoButtonActionResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &DoButtonAction {
             serial_number: serial_number.to_string(),
             key
         })?;
@@ -481,14 +959,151 @@ impl SDSyncRequestClient for UnixClient {
         Ok(response)
     }
 
-    fn send_packet(&self, mut packet: SocketPacket) -> Result<SocketPacket, SDClientError> {
+    fn show_notification(&self, serial_number: &str, text: &str, icon: Option<&str>, duration: f32, keys: Vec<u8>) -> Result<ShowNotificationResult, SDClientError> {
+        let response: ShowNotificationResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ShowNotification {
+            serial_number: serial_number.to_string(),
+            text: text.to_string(),
+            icon: icon.map(|x| x.to_string()),
+            duration,
+            keys
+        })?;
+
+        Ok(response)
+    }
+
+    fn dismiss_notification(&self, serial_number: &str, id: &str) -> Result<DismissNotificationResult, SDClientError> {
+        let response: DismissNotificationResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &DismissNotification {
+            serial_number: serial_number.to_string(),
+            id: id.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn batch(&self, requests: Vec<SocketPacket>) -> Result<BatchResult, SDClientError> {
+        let response: BatchResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &Batch {
+            requests
+        })?;
+
+        Ok(response)
+    }
+
+    fn daemon_status(&self) -> Result<GetDaemonStatus, SDClientError> {
+        let response: GetDaemonStatus = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+
+        Ok(response)
+    }
+
+    fn safe_mode_status(&self) -> Result<GetSafeModeStatus, SDClientError> {
+        let response: GetSafeModeStatus = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+
+        Ok(response)
+    }
+
+    fn set_safe_mode_on_next_start(&self, enabled: bool) -> Result<SetSafeModeOnNextStartResult, SDClientError> {
+        let response: SetSafeModeOnNextStartResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &SetSafeModeOnNextStart {
+            enabled
+        })?;
+
+        Ok(response)
+    }
+
+    fn shutdown_daemon(&self) -> Result<ShutdownDaemonResult, SDClientError> {
+        let response: ShutdownDaemonResult = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+
+        Ok(response)
+    }
+
+    fn restart_daemon(&self) -> Result<RestartDaemonResult, SDClientError> {
+        let response: RestartDaemonResult = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+
+        Ok(response)
+    }
+
+    fn start_session(&self) -> Result<StartSessionResult, SDClientError> {
+        let response: StartSessionResult = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+
+        Ok(response)
+    }
+
+    fn resume_session(&self, token: &str) -> Result<ResumeSessionResult, SDClientError> {
+        let response: ResumeSessionResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &ResumeSession {
+            token: token.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn identify(&self, name: &str, version: &str, read_only: bool) -> Result<IdentifyClientResult, SDClientError> {
+        let response: IdentifyClientResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &IdentifyClient {
+            name: name.to_string(),
+            version: version.to_string(),
+            read_only
+        })?;
+
+        Ok(response)
+    }
+
+    fn list_clients(&self) -> Result<Vec<ClientInfo>, SDClientError> {
+        let response: ListClients = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+
+        Ok(response.clients)
+    }
+
+    fn disconnect_client(&self, id: u64) -> Result<DisconnectClientResult, SDClientError> {
+        let response: DisconnectClientResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &DisconnectClient {
+            id
+        })?;
+
+        Ok(response)
+    }
+
+    fn get_recent_logs(&self, limit: usize, filter: LogFilter) -> Result<Vec<LogRecord>, SDClientError> {
+        let response: GetRecentLogsResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetRecentLogs {
+            limit,
+            filter
+        })?;
+
+        Ok(response.logs)
+    }
+
+    fn stream_logs(&self, filter: Option<LogFilter>) -> Result<StreamLogsResult, SDClientError> {
+        let response: StreamLogsResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &StreamLogs {
+            enabled: filter.is_some(),
+            filter: filter.unwrap_or_default()
+        })?;
+
+        Ok(response)
+    }
+
+    fn undo(&self, serial_number: &str) -> Result<UndoResult, SDClientError> {
+        let response: UndoResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &Undo {
+            serial_number: serial_number.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn redo(&self, serial_number: &str) -> Result<RedoResult, SDClientError> {
+        let response: RedoResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &Redo {
+            serial_number: serial_number.to_string()
+        })?;
+
+        Ok(response)
+    }
+
+    fn send_packet(&self, packet: SocketPacket) -> Result<SocketPacket, SDClientError> {
+        self.send_packet_with_options(packet, &self.get_options())
+    }
+
+    fn send_packet_with_options(&self, mut packet: SocketPacket, options: &RequestOptions) -> Result<SocketPacket, SDClientError> {
         let id = rand::thread_rng().sample_iter(&Alphanumeric).take(20).map(char::from).collect::<String>();
         packet.requester = Some(id.clone());
 
         let mut handle = self.connection.write().unwrap();
         send_packet_as_is(handle.get_mut(), packet)?;
 
-        read_response(handle.deref_mut(), &id)
+        read_response_with_options(handle.deref_mut(), &id, options)
     }
 
     fn send_packet_without_response(&self, packet: SocketPacket) -> Result<(), SDClientError> {
@@ -510,6 +1125,18 @@ impl SDSyncEventClient for UnixClient {
             }
         }
     }
+
+    fn get_remote_call(&self) -> Result<RemoteModuleCall, SDClientError> {
+        loop {
+            let packet = read_socket(self.get_handle().deref_mut())?;
+
+            if packet.ty == REMOTE_CALL_PACKET {
+                if let Some(data) = packet.data {
+                    return Ok(serde_json::from_value(data)?);
+                }
+            }
+        }
+    }
 }
 
 
@@ -1,12 +1,16 @@
 use std::collections::HashMap;
-use std::io::{BufRead, BufReader, Read, Write};
-use std::ops::DerefMut;
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 use rand::distributions::Alphanumeric;
 use rand::Rng;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use streamduck_core::modules::components::ComponentDefinition;
 use streamduck_core::socket::{parse_packet_to_data, send_no_data_packet_with_requester, send_packet_with_requester, SocketData, SocketPacket};
+use streamduck_core::socket::PermissionDenied;
+use streamduck_daemon::daemon_data::auth::Unauthorized;
 use crate::SDClientError;
 
 /// Transforms module-component map into component map, if you don't care about module names for them
@@ -39,31 +43,199 @@ pub fn read_response(handle: &mut dyn BufRead, requester: &str) -> Result<Socket
     }
 }
 
-pub fn process_request<Req, Res, Han>(mut handle: &mut BufReader<Han>, request: &Req) -> Result<Res, SDClientError>
+/// Default timeout for requests
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often pending requests check for cancellation
+const CANCELLATION_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Options that are applied to requests
+#[derive(Clone, Debug)]
+pub struct RequestOptions {
+    /// How long to wait for a response, waits forever if [None]
+    pub timeout: Option<Duration>,
+    /// Token that can be used to cancel pending request from another thread
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl Default for RequestOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Some(DEFAULT_REQUEST_TIMEOUT),
+            cancellation: None
+        }
+    }
+}
+
+impl RequestOptions {
+    /// Options with specified timeout
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            cancellation: None
+        }
+    }
+
+    /// Sets cancellation token
+    pub fn cancellable(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token); self
+    }
+}
+
+/// Token for cancelling requests, can be cloned and shared between threads
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates new token
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels all requests that use this token
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Checks if token was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Resets the token, so it can be used for new requests
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Streams that support read timeouts
+pub trait TimeoutStream {
+    fn set_timeout(&mut self, timeout: Option<Duration>) -> std::io::Result<()>;
+}
+
+#[cfg(target_family = "unix")]
+impl TimeoutStream for std::os::unix::net::UnixStream {
+    fn set_timeout(&mut self, timeout: Option<Duration>) -> std::io::Result<()> {
+        self.set_read_timeout(timeout)
+    }
+}
+
+#[cfg(target_family = "windows")]
+impl TimeoutStream for named_pipe::PipeClient {
+    fn set_timeout(&mut self, timeout: Option<Duration>) -> std::io::Result<()> {
+        self.set_read_timeout(timeout);
+        Ok(())
+    }
+}
+
+/// Reads a packet, respecting deadline and cancellation
+fn read_socket_with_deadline<Han: Read + TimeoutStream>(handle: &mut BufReader<Han>, deadline: Option<Instant>, cancellation: Option<&CancellationToken>) -> Result<SocketPacket, SDClientError> {
+    let mut byte_array = vec![];
+
+    loop {
+        if cancellation.map_or(false, |x| x.is_cancelled()) {
+            return Err(SDClientError::Cancelled);
+        }
+
+        let wait = if let Some(deadline) = deadline {
+            let now = Instant::now();
+
+            if now >= deadline {
+                return Err(SDClientError::Timeout);
+            }
+
+            Some(deadline - now)
+        } else {
+            None
+        };
+
+        let wait = if cancellation.is_some() {
+            Some(wait.map_or(CANCELLATION_CHECK_INTERVAL, |x| x.min(CANCELLATION_CHECK_INTERVAL)))
+        } else {
+            wait
+        };
+
+        handle.get_mut().set_timeout(wait.map(|x| x.max(Duration::from_millis(1))))?;
+
+        match handle.read_until(0x4, &mut byte_array) {
+            Ok(0) => return Err(SDClientError::WriteError(ErrorKind::UnexpectedEof.into())),
+
+            Ok(_) => {
+                if byte_array.last() == Some(&0x4) {
+                    let line = String::from_utf8(byte_array)?;
+                    return Ok(serde_json::from_str(line.replace("\u{0004}", "").trim())?);
+                }
+            }
+
+            Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut | ErrorKind::Interrupted) => {}
+
+            Err(err) => return Err(err.into())
+        }
+    }
+}
+
+/// Reads response for specified requester, fails with [SDClientError::Timeout] or [SDClientError::Cancelled] according to options
+pub fn read_response_with_options<Han: Read + TimeoutStream>(handle: &mut BufReader<Han>, requester: &str, options: &RequestOptions) -> Result<SocketPacket, SDClientError> {
+    let deadline = options.timeout.map(|x| Instant::now() + x);
+
+    let result = loop {
+        match read_socket_with_deadline(handle, deadline, options.cancellation.as_ref()) {
+            Ok(packet) => {
+                if packet.requester.as_ref().unwrap_or(&"".to_string()) == requester {
+                    break Ok(packet);
+                }
+            }
+
+            // Remainder of a response to previously abandoned request
+            Err(SDClientError::SerializeError(_)) => {}
+
+            Err(err) => break Err(err)
+        }
+    };
+
+    handle.get_mut().set_timeout(None).ok();
+
+    result
+}
+
+/// Parses response data, fails with [SDClientError::Unauthorized] or [SDClientError::PermissionDenied] if daemon rejected the request
+fn parse_response<Res: SocketData + DeserializeOwned>(packet: &SocketPacket) -> Result<Res, SDClientError> {
+    if packet.ty == Unauthorized::NAME {
+        return Err(SDClientError::Unauthorized);
+    }
+
+    if packet.ty == PermissionDenied::NAME {
+        return Err(SDClientError::PermissionDenied);
+    }
+
+    Ok(parse_packet_to_data(packet)?)
+}
+
+pub fn process_request<Req, Res, Han>(handle: &mut BufReader<Han>, options: &RequestOptions, request: &Req) -> Result<Res, SDClientError>
     where
         Req: SocketData + Serialize,
         Res: SocketData + DeserializeOwned,
-        Han: Read + Write
+        Han: Read + Write + TimeoutStream
 {
     let id = rand::thread_rng().sample_iter(&Alphanumeric).take(20).map(char::from).collect::<String>();
 
     send_packet_with_requester(handle.get_mut(), &id, request)?;
 
-    let packet = read_response(handle.deref_mut(), &id)?;
+    let packet = read_response_with_options(handle, &id, options)?;
 
-    Ok(parse_packet_to_data(&packet)?)
+    parse_response(&packet)
 }
 
-pub fn process_request_without_data<Res, Han>(mut handle: &mut BufReader<Han>) -> Result<Res, SDClientError>
+pub fn process_request_without_data<Res, Han>(handle: &mut BufReader<Han>, options: &RequestOptions) -> Result<Res, SDClientError>
     where
         Res: SocketData + DeserializeOwned,
-        Han: Read + Write
+        Han: Read + Write + TimeoutStream
 {
     let id = rand::thread_rng().sample_iter(&Alphanumeric).take(20).map(char::from).collect::<String>();
 
     send_no_data_packet_with_requester::<Res>(handle.get_mut(), &id)?;
 
-    let packet = read_response(handle.deref_mut(), &id)?;
+    let packet = read_response_with_options(handle, &id, options)?;
 
-    Ok(parse_packet_to_data(&packet)?)
-}
\ No newline at end of file
+    parse_response(&packet)
+}
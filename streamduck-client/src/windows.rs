@@ -5,24 +5,45 @@ use std::sync::{Arc, RwLock, RwLockWriteGuard};
 use named_pipe::PipeClient;
 use rand::distributions::Alphanumeric;
 use rand::Rng;
+use serde_json::Value;
+use streamduck_core::config::ConfigMilestone;
 use streamduck_core::core::button::Button;
 use streamduck_core::core::RawButtonPanel;
 use streamduck_core::modules::components::{ComponentDefinition, UIPathValue};
 use streamduck_core::modules::events::SDGlobalEvent;
+use streamduck_core::modules::dependencies::DependencyNode;
 use streamduck_core::modules::PluginMetadata;
-use streamduck_core::socket::{send_packet_as_is, SocketPacket};
+use streamduck_core::portable::PortableButton;
+use streamduck_core::logging::{LogFilter, LogRecord};
+use streamduck_core::presets::ComponentPreset;
+use streamduck_core::thread::orientation::DeviceOrientation;
+use streamduck_core::thread::transition::PanelTransition;
+use streamduck_core::socket::{ClientInfo, send_packet_as_is, SocketPacket};
 use streamduck_core::versions::SOCKET_API;
-use streamduck_daemon::daemon_data::assets::{AddImage, AddImageResult, ListFonts, ListImages, ListImagesResult, RemoveImage, RemoveImageResult};
-use streamduck_daemon::daemon_data::buttons::{AddComponent, AddComponentResult, AddComponentValue, AddComponentValueResult, ClearButton, ClearButtonResult, ClipboardStatusResult, CopyButton, CopyButtonResult, GetButton, GetButtonResult, GetComponentValues, GetComponentValuesResult, NewButton, NewButtonFromComponent, NewButtonFromComponentResult, NewButtonResult, PasteButton, PasteButtonResult, RemoveComponent, RemoveComponentResult, RemoveComponentValue, RemoveComponentValueResult, SetButton, SetButtonResult, SetComponentValue, SetComponentValueResult};
-use streamduck_daemon::daemon_data::config::{ExportDeviceConfig, ExportDeviceConfigResult, GetDeviceConfig, GetDeviceConfigResult, ImportDeviceConfig, ImportDeviceConfigResult, ReloadDeviceConfig, ReloadDeviceConfigResult, ReloadDeviceConfigsResult, SaveDeviceConfig, SaveDeviceConfigResult, SaveDeviceConfigsResult};
-use streamduck_daemon::daemon_data::devices::{AddDevice, AddDeviceResult, Device, GetDevice, GetDeviceResult, ListDevices, RemoveDevice, RemoveDeviceResult, SetBrightness, SetBrightnessResult};
-use streamduck_daemon::daemon_data::modules::{AddModuleValue, AddModuleValueResult, GetModuleValues, GetModuleValuesResult, ListComponents, ListModules, RemoveModuleValue, RemoveModuleValueResult, SetModuleValue, SetModuleValueResult};
-use streamduck_daemon::daemon_data::ops::{CommitChangesToConfig, CommitChangesToConfigResult, DoButtonAction, DoButtonActionResult};
-use streamduck_daemon::daemon_data::panels::{DropStackToRoot, DropStackToRootResult, ForciblyPopScreen, ForciblyPopScreenResult, GetButtonImages, GetButtonImagesResult, GetCurrentScreen, GetCurrentScreenResult, GetStack, GetStackNames, GetStackNamesResult, GetStackResult, PopScreen, PopScreenResult, PushScreen, PushScreenResult, ReplaceScreen, ReplaceScreenResult, ResetStack, ResetStackResult};
+use streamduck_daemon::daemon_data::auth::{Authenticate, AuthenticateResult};
+use streamduck_daemon::daemon_data::assets::{AddImage, AddImageResult, CleanupUnusedImages, CleanupUnusedImagesResult, FinishImageUpload, FinishImageUploadResult, GetImage, GetImageResult, ListFonts, ListImages, ListImagesResult, RemoveImage, RemoveImageResult, StartImageUpload, StartImageUploadResult, UploadImageChunk, UploadImageChunkResult};
+use streamduck_daemon::daemon_data::buttons::{AddComponent, AddComponentResult, AddComponentValue, AddComponentValueResult, ClearButton, ClearButtonResult, ClipboardStatusResult, CopyButton, CopyButtonResult, GetButton, GetButtonResult, GetComponentValues, GetComponentValuesResult, MoveButton, MoveButtonResult, NewButton, NewButtonFromComponent, NewButtonFromComponentResult, NewButtonResult, PasteButton, PasteButtonResult, RemoveComponent, RemoveComponentResult, RemoveComponentValue, RemoveComponentValueResult, SetButton, SetButtonResult, SetComponentValue, SetComponentValueResult};
+use streamduck_daemon::daemon_data::clients::{DisconnectClient, DisconnectClientResult, IdentifyClient, IdentifyClientResult, ListClients};
+use streamduck_daemon::daemon_data::config::{CreateConfigMilestone, CreateConfigMilestoneResult, DiffDeviceConfig, DiffDeviceConfigResult, ExportAllConfigsResult, ExportDeviceConfig, ExportDeviceConfigResult, GetDeviceConfig, GetDeviceConfigResult, ImportAllConfigs, ImportAllConfigsResult, ImportDeviceConfig, ImportDeviceConfigResult, ListConfigMilestones, ReloadDeviceConfig, ReloadDeviceConfigResult, ReloadDeviceConfigsResult, RestoreConfigMilestone, RestoreConfigMilestoneResult, RestoreDeviceConfigBackup, RestoreDeviceConfigBackupResult, SaveDeviceConfig, SaveDeviceConfigResult, SaveDeviceConfigsResult};
+use streamduck_daemon::daemon_data::devices::{AddDevice, AddDeviceResult, Device, GetDevice, GetDeviceResult, ListDevices, RemoveDevice, RemoveDeviceResult, SetBrightness, SetBrightnessResult, SetDeviceMirror, SetDeviceMirrorResult, SetDeviceOrientation, SetDeviceOrientationResult, SetDeviceStartup, SetDeviceStartupResult, SetMirroredLayout, SetMirroredLayoutResult, SetPanelTransition, SetPanelTransitionResult};
+use streamduck_daemon::daemon_data::folders::{CleanupFolders, CleanupFoldersResult, DeleteFolder, DeleteFolderResult, FindButtons, FindButtonsResult, GetFolderTree, GetFolderTreeResult, ListFolders, ListFoldersResult, RenameFolder, RenameFolderResult};
+use streamduck_daemon::daemon_data::history::{Redo, RedoResult, Undo, UndoResult};
+use streamduck_daemon::daemon_data::modules::{AddModuleValue, AddModuleValueResult, GetGlobalPluginSettings, GetGlobalPluginSettingsResult, GetModuleValues, GetModuleValuesResult, GetPluginDependencies, ListComponents, ListModuleSettings, ListModules, ListPluginRequests, ReenablePlugin, ReenablePluginResult, RemoveModuleValue, RemoveModuleValueResult, SendModuleMessage, SendModuleMessageResult, SetGlobalPluginSettings, SetGlobalPluginSettingsResult, SetModuleValue, SetModuleValueResult};
+use streamduck_daemon::daemon_data::logs::{GetRecentLogs, GetRecentLogsResult, StreamLogs, StreamLogsResult};
+use streamduck_daemon::daemon_data::notifications::{DismissNotification, DismissNotificationResult, ShowNotification, ShowNotificationResult};
+use streamduck_daemon::daemon_data::ops::{Batch, BatchResult, CommitChangesToConfig, CommitChangesToConfigResult, DoButtonAction, DoButtonActionResult, GetSafeModeStatus, RestartDaemonResult, SetSafeModeOnNextStart, SetSafeModeOnNextStartResult, ShutdownDaemonResult};
+use streamduck_daemon::daemon_data::layout::{ApplyLayout, ApplyLayoutResult, ExportLayout, ExportLayoutResult, InstantiateTemplate, InstantiateTemplateResult};
+use streamduck_daemon::daemon_data::presets::{ApplyPreset, ApplyPresetResult, ListPresets, RemovePreset, RemovePresetResult, SavePreset, SavePresetResult};
+use streamduck_daemon::daemon_data::remote::{RegisterRemoteModule, RegisterRemoteModuleResult, RemoteModuleResponse, RemoteModuleResponseResult, UnregisterRemoteModule, UnregisterRemoteModuleResult};
+use streamduck_daemon::daemon_data::status::GetDaemonStatus;
+use streamduck_daemon::daemon_data::revisions::{GetScreenRevisions, GetScreenRevisionsResult};
+use streamduck_daemon::daemon_data::sessions::{ResumeSession, ResumeSessionResult, StartSessionResult};
+use streamduck_daemon::daemon_data::panels::{DropStackToRoot, DropStackToRootResult, ForciblyPopScreen, ForciblyPopScreenResult, GetButtonImages, GetButtonImagesResult, GetCurrentScreen, GetCurrentScreenResult, GetStack, GetStackBreadcrumbs, GetStackBreadcrumbsResult, GetStackNames, GetStackNamesResult, GetStackResult, PopScreen, PopScreenResult, PopToScreen, PopToScreenResult, PushScreen, PushScreenResult, RenderButtonPreview, RenderButtonPreviewResult, ReplaceScreen, ReplaceScreenResult, ResetStack, ResetStackResult, SetPanel, SetPanelResult};
 use streamduck_daemon::daemon_data::SocketAPIVersion;
+use streamduck_daemon::remote::{REMOTE_CALL_PACKET, RemoteModuleCall};
 use streamduck_daemon::{WINDOWS_EVENT_PIPE_NAME, WINDOWS_REQUEST_PIPE_NAME};
-use crate::{SDClientError, SDSyncEventClient, SDSyncRequestClient};
-use crate::util::{process_request, process_request_without_data, read_response, read_socket};
+use crate::{authenticate_from_env, SDClientError, SDSyncEventClient, SDSyncRequestClient, TOKEN_ENV};
+use crate::util::{process_request, process_request_without_data, read_response_with_options, read_socket, RequestOptions};
 
 /// Windows Named Pipe based Streamduck event client
 pub struct WinEventClient {
@@ -31,9 +52,24 @@ pub struct WinEventClient {
 
 impl WinEventClient {
     pub fn new() -> Result<Arc<dyn SDSyncEventClient>, std::io::Error> {
-        let client: Arc<dyn SDSyncEventClient> = Arc::new(WinEventClient {
+        let client = WinEventClient {
             connection: RwLock::new(BufReader::new(PipeClient::connect(WINDOWS_EVENT_PIPE_NAME)?))
-        });
+        };
+
+        // Event pipe doesn't send events until it's authenticated too
+        if let Ok(token) = std::env::var(TOKEN_ENV) {
+            let response: Result<AuthenticateResult, SDClientError> = process_request(client.get_handle().deref_mut(), &RequestOptions::default(), &Authenticate {
+                token
+            });
+
+            match response {
+                Ok(AuthenticateResult::InvalidToken) => return Err(std::io::Error::new(std::io::ErrorKind::PermissionDenied, "Daemon rejected the token")),
+                Err(err) => return Err(std::io::Error::new(std::io::ErrorKind::Other, format!("Failed to authenticate: {:?}", err))),
+                _ => {}
+            }
+        }
+
+        let client: Arc<dyn SDSyncEventClient> = Arc::new(client);
 
         Ok(client)
     }
@@ -55,17 +91,31 @@ impl SDSyncEventClient for WinEventClient {
             }
         }
     }
+
+    fn get_remote_call(&self) -> Result<RemoteModuleCall, SDClientError> {
+        loop {
+            let packet = read_socket(self.get_handle().deref_mut())?;
+
+            if packet.ty == REMOTE_CALL_PACKET {
+                if let Some(data) = packet.data {
+                    return Ok(serde_json::from_value(data)?);
+                }
+            }
+        }
+    }
 }
 
 /// Windows Named Pipe based Streamduck request client
 pub struct WinRequestClient {
-    connection: RwLock<BufReader<PipeClient>>
+    connection: RwLock<BufReader<PipeClient>>,
+    options: RwLock<RequestOptions>,
 }
 
 impl WinRequestClient {
     pub fn new() -> Result<Arc<dyn SDSyncRequestClient>, std::io::Error> {
         let client: Arc<dyn SDSyncRequestClient> = Arc::new(WinRequestClient {
-            connection: RwLock::new(BufReader::new(PipeClient::connect(WINDOWS_REQUEST_PIPE_NAME)?))
+            connection: RwLock::new(BufReader::new(PipeClient::connect(WINDOWS_REQUEST_PIPE_NAME)?)),
+            options: Default::default()
         });
 
         let daemon_version = client.version().expect("Failed to retrieve version");
@@ -74,139 +124,409 @@ impl WinRequestClient {
             println!("[Warning] Version of client library doesn't match daemon API version. Client: {}, Daemon: {}", SOCKET_API.1, daemon_version);
         }
 
+        authenticate_from_env(client.as_ref())?;
+
         Ok(client)
     }
 
     fn get_handle(&self) -> RwLockWriteGuard<BufReader<PipeClient>> {
         self.connection.write().unwrap()
     }
+
+    fn get_options(&self) -> RequestOptions {
+        self.options.read().unwrap().clone()
+    }
 }
 
 impl SDSyncRequestClient for WinRequestClient {
+    fn set_request_options(&self, options: RequestOptions) {
+        *self.options.write().unwrap() = options;
+    }
+
+    fn request_options(&self) -> RequestOptions {
+        self.get_options()
+    }
+
     fn version(&self) -> Result<String, SDClientError> {
-        let response: SocketAPIVersion = process_request_without_data(self.get_handle().deref_mut())?;
+        let response: SocketAPIVersion = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
         Ok(response.version)
     }
 
+    fn authenticate(&self, token: &str) -> Result<AuthenticateResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &Authenticate {
+            token: token.to_string()
+        })?)
+    }
+
     fn device_list(&self) -> Result<Vec<Device>, SDClientError> {
-        let response: ListDevices = process_request_without_data::<ListDevices, PipeClient>(self.get_handle().deref_mut())?;
+        let response: ListDevices = process_request_without_data::<ListDevices, PipeClient>(self.get_handle().deref_mut(), &self.get_options())?;
         Ok(response.devices)
     }
 
     fn get_device(&self, serial_number: &str) -> Result<GetDeviceResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &GetDevice {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &GetDevice {
             serial_number: serial_number.to_string()
         })?)
     }
 
     fn add_device(&self, serial_number: &str) -> Result<AddDeviceResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &AddDevice {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &AddDevice {
             serial_number: serial_number.to_string()
         })?)
     }
 
     fn remove_device(&self, serial_number: &str) -> Result<RemoveDeviceResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &RemoveDevice {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &RemoveDevice {
             serial_number: serial_number.to_string()
         })?)
     }
 
     fn reload_device_configs(&self) -> Result<ReloadDeviceConfigsResult, SDClientError> {
-        Ok(process_request_without_data(self.get_handle().deref_mut())?)
+        Ok(process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?)
     }
 
     fn reload_device_config(&self, serial_number: &str) -> Result<ReloadDeviceConfigResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &ReloadDeviceConfig {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ReloadDeviceConfig {
             serial_number: serial_number.to_string()
         })?)
     }
 
     fn save_device_configs(&self) -> Result<SaveDeviceConfigsResult, SDClientError> {
-        Ok(process_request_without_data(self.get_handle().deref_mut())?)
+        Ok(process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?)
     }
 
     fn save_device_config(&self, serial_number: &str) -> Result<SaveDeviceConfigResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &SaveDeviceConfig {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &SaveDeviceConfig {
             serial_number: serial_number.to_string()
         })?)
     }
 
     fn get_device_config(&self, serial_number: &str) -> Result<GetDeviceConfigResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &GetDeviceConfig {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &GetDeviceConfig {
             serial_number: serial_number.to_string()
         })?)
     }
 
-    fn import_device_config(&self, serial_number: &str, config: String) -> Result<ImportDeviceConfigResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &ImportDeviceConfig {
+    fn import_device_config(&self, serial_number: &str, config: String, dry_run: bool) -> Result<ImportDeviceConfigResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ImportDeviceConfig {
+            serial_number: serial_number.to_string(),
+            config,
+            dry_run
+        })?)
+    }
+
+    fn diff_device_config(&self, serial_number: &str, config: String) -> Result<DiffDeviceConfigResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &DiffDeviceConfig {
             serial_number: serial_number.to_string(),
             config
         })?)
     }
 
-    fn export_device_config(&self, serial_number: &str) -> Result<ExportDeviceConfigResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &ExportDeviceConfig {
+    fn export_device_config(&self, serial_number: &str, include_images: bool) -> Result<ExportDeviceConfigResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ExportDeviceConfig {
+            serial_number: serial_number.to_string(),
+            include_images
+        })?)
+    }
+
+    fn restore_device_config_backup(&self, serial_number: &str, backup: usize) -> Result<RestoreDeviceConfigBackupResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &RestoreDeviceConfigBackup {
+            serial_number: serial_number.to_string(),
+            backup
+        })?)
+    }
+
+    fn create_config_milestone(&self, name: &str) -> Result<CreateConfigMilestoneResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &CreateConfigMilestone {
+            name: name.to_string()
+        })?)
+    }
+
+    fn list_config_milestones(&self) -> Result<Vec<ConfigMilestone>, SDClientError> {
+        let response: ListConfigMilestones = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+
+        Ok(response.milestones)
+    }
+
+    fn restore_config_milestone(&self, name: &str) -> Result<RestoreConfigMilestoneResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &RestoreConfigMilestone {
+            name: name.to_string()
+        })?)
+    }
+
+    fn export_all_configs(&self) -> Result<ExportAllConfigsResult, SDClientError> {
+        Ok(process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?)
+    }
+
+    fn import_all_configs(&self, bundle: String) -> Result<ImportAllConfigsResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ImportAllConfigs {
+            bundle
+        })?)
+    }
+
+    fn apply_layout(&self, serial_number: &str, layout: String, dry_run: bool) -> Result<ApplyLayoutResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ApplyLayout {
+            serial_number: serial_number.to_string(),
+            layout,
+            dry_run
+        })?)
+    }
+
+    fn export_layout(&self, serial_number: &str) -> Result<ExportLayoutResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ExportLayout {
+            serial_number: serial_number.to_string()
+        })?)
+    }
+
+    fn instantiate_template(&self, serial_number: &str, template: String, variables: HashMap<String, String>, dry_run: bool) -> Result<InstantiateTemplateResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &InstantiateTemplate {
+            serial_number: serial_number.to_string(),
+            template,
+            variables,
+            dry_run
+        })?)
+    }
+
+    fn list_folders(&self, serial_number: &str) -> Result<ListFoldersResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ListFolders {
             serial_number: serial_number.to_string()
         })?)
     }
 
+    fn folder_tree(&self, serial_number: &str, include_buttons: bool) -> Result<GetFolderTreeResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &GetFolderTree {
+            serial_number: serial_number.to_string(),
+            include_buttons
+        })?)
+    }
+
+    fn find_buttons(&self, serial_number: &str, component_name: Option<&str>, query: Option<&str>) -> Result<FindButtonsResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &FindButtons {
+            serial_number: serial_number.to_string(),
+            component_name: component_name.map(|x| x.to_string()),
+            query: query.map(|x| x.to_string())
+        })?)
+    }
+
+    fn rename_folder(&self, serial_number: &str, id: &str, name: &str) -> Result<RenameFolderResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &RenameFolder {
+            serial_number: serial_number.to_string(),
+            id: id.to_string(),
+            name: name.to_string()
+        })?)
+    }
+
+    fn delete_folder(&self, serial_number: &str, id: &str) -> Result<DeleteFolderResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &DeleteFolder {
+            serial_number: serial_number.to_string(),
+            id: id.to_string()
+        })?)
+    }
+
+    fn cleanup_folders(&self, serial_number: &str) -> Result<CleanupFoldersResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &CleanupFolders {
+            serial_number: serial_number.to_string()
+        })?)
+    }
+
+    fn list_presets(&self) -> Result<HashMap<String, ComponentPreset>, SDClientError> {
+        let response: ListPresets = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+        Ok(response.presets)
+    }
+
+    fn save_preset(&self, name: &str, preset: ComponentPreset, propagate: bool) -> Result<SavePresetResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &SavePreset {
+            name: name.to_string(),
+            preset,
+            propagate
+        })?)
+    }
+
+    fn remove_preset(&self, name: &str) -> Result<RemovePresetResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &RemovePreset {
+            name: name.to_string()
+        })?)
+    }
+
+    fn apply_preset(&self, serial_number: &str, key: u8, name: &str) -> Result<ApplyPresetResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ApplyPreset {
+            serial_number: serial_number.to_string(),
+            key,
+            name: name.to_string()
+        })?)
+    }
+
     fn set_brightness(&self, serial_number: &str, brightness: u8) -> Result<SetBrightnessResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &SetBrightness {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &SetBrightness {
             serial_number: serial_number.to_string(),
             brightness
         })?)
     }
 
+    fn set_panel_transition(&self, serial_number: &str, transition: Option<PanelTransition>) -> Result<SetPanelTransitionResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &SetPanelTransition {
+            serial_number: serial_number.to_string(),
+            transition
+        })?)
+    }
+
+    fn set_device_mirror(&self, serial_number: &str, source: Option<&str>) -> Result<SetDeviceMirrorResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &SetDeviceMirror {
+            serial_number: serial_number.to_string(),
+            source: source.map(|x| x.to_string())
+        })?)
+    }
+
+    fn set_device_orientation(&self, serial_number: &str, orientation: DeviceOrientation) -> Result<SetDeviceOrientationResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &SetDeviceOrientation {
+            serial_number: serial_number.to_string(),
+            orientation
+        })?)
+    }
+
+    fn set_mirrored_layout(&self, serial_number: &str, mirrored: bool, flip_images: bool) -> Result<SetMirroredLayoutResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &SetMirroredLayout {
+            serial_number: serial_number.to_string(),
+            mirrored,
+            flip_images
+        })?)
+    }
+
+    fn set_device_startup(&self, serial_number: &str, folder_id: Option<&str>, actions: Vec<Button>) -> Result<SetDeviceStartupResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &SetDeviceStartup {
+            serial_number: serial_number.to_string(),
+            folder_id: folder_id.map(|x| x.to_string()),
+            actions
+        })?)
+    }
+
     fn list_images(&self, serial_number: &str) -> Result<ListImagesResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &ListImages {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ListImages {
             serial_number: serial_number.to_string()
         })?)
     }
 
     fn add_image(&self, serial_number: &str, image_data: &str) -> Result<AddImageResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &AddImage {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &AddImage {
             serial_number: serial_number.to_string(),
             image_data: image_data.to_string()
         })?)
     }
 
     fn remove_image(&self, serial_number: &str, identifier: &str) -> Result<RemoveImageResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &RemoveImage {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &RemoveImage {
             serial_number: serial_number.to_string(),
             image_identifier: identifier.to_string()
         })?)
     }
 
+    fn get_image(&self, serial_number: &str, identifier: &str, offset: usize) -> Result<GetImageResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &GetImage {
+            serial_number: serial_number.to_string(),
+            image_identifier: identifier.to_string(),
+            offset
+        })?)
+    }
+
+    fn start_image_upload(&self, serial_number: &str) -> Result<StartImageUploadResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &StartImageUpload {
+            serial_number: serial_number.to_string()
+        })?)
+    }
+
+    fn upload_image_chunk(&self, upload_id: &str, data: &str) -> Result<UploadImageChunkResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &UploadImageChunk {
+            upload_id: upload_id.to_string(),
+            data: data.to_string()
+        })?)
+    }
+
+    fn finish_image_upload(&self, upload_id: &str) -> Result<FinishImageUploadResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &FinishImageUpload {
+            upload_id: upload_id.to_string()
+        })?)
+    }
+
+    fn cleanup_unused_images(&self, serial_number: Option<&str>) -> Result<CleanupUnusedImagesResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &CleanupUnusedImages {
+            serial_number: serial_number.map(|x| x.to_string())
+        })?)
+    }
+
     fn list_fonts(&self) -> Result<Vec<String>, SDClientError> {
-        let response: ListFonts = process_request_without_data(self.get_handle().deref_mut())?;
+        let response: ListFonts = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
         Ok(response.font_names)
     }
 
     fn list_modules(&self) -> Result<Vec<PluginMetadata>, SDClientError> {
-        let response: ListModules = process_request_without_data(self.get_handle().deref_mut())?;
+        let response: ListModules = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
         Ok(response.modules)
     }
 
     fn list_components(&self) -> Result<HashMap<String, HashMap<String, ComponentDefinition>>, SDClientError> {
-        let response: ListComponents = process_request_without_data(self.get_handle().deref_mut())?;
+        let response: ListComponents = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
         Ok(response.components)
     }
 
+    fn list_plugin_requests(&self) -> Result<Vec<String>, SDClientError> {
+        let response: ListPluginRequests = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+        Ok(response.requests)
+    }
+
+    fn get_plugin_dependencies(&self) -> Result<Vec<DependencyNode>, SDClientError> {
+        let response: GetPluginDependencies = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+        Ok(response.modules)
+    }
+
+    fn reenable_plugin(&self, name: &str) -> Result<ReenablePluginResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ReenablePlugin {
+            name: name.to_string()
+        })?)
+    }
+
+    fn register_remote_module(&self, session_token: &str, metadata: PluginMetadata, components: HashMap<String, ComponentDefinition>, listening_for: Vec<String>) -> Result<RegisterRemoteModuleResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &RegisterRemoteModule {
+            session_token: session_token.to_string(),
+            metadata,
+            components,
+            listening_for
+        })?)
+    }
+
+    fn unregister_remote_module(&self, name: &str) -> Result<UnregisterRemoteModuleResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &UnregisterRemoteModule {
+            name: name.to_string()
+        })?)
+    }
+
+    fn respond_to_remote_call(&self, module: &str, call_id: u64, data: Value) -> Result<RemoteModuleResponseResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &RemoteModuleResponse {
+            module: module.to_string(),
+            call_id,
+            data
+        })?)
+    }
+
     fn get_module_values(&self, module_name: &str) -> Result<GetModuleValuesResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &GetModuleValues {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &GetModuleValues {
             module_name: module_name.to_string()
         })?)
     }
 
+    fn list_module_settings(&self) -> Result<HashMap<String, Vec<UIPathValue>>, SDClientError> {
+        let response: ListModuleSettings = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+        Ok(response.settings)
+    }
+
     fn add_module_value(&self, module_name: &str, path: &str) -> Result<AddModuleValueResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &AddModuleValue {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &AddModuleValue {
             module_name: module_name.to_string(),
             path: path.to_string()
         })?)
     }
 
     fn remove_module_value(&self, module_name: &str, path: &str, index: usize) -> Result<RemoveModuleValueResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &RemoveModuleValue {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &RemoveModuleValue {
             module_name: module_name.to_string(),
             path: path.to_string(),
             index
@@ -214,101 +534,170 @@ impl SDSyncRequestClient for WinRequestClient {
     }
 
     fn set_module_value(&self, module_name: &str, value: UIPathValue) -> Result<SetModuleValueResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &SetModuleValue {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &SetModuleValue {
             module_name: module_name.to_string(),
             value
         })?)
     }
 
+    fn get_global_plugin_settings(&self, name: &str) -> Result<GetGlobalPluginSettingsResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &GetGlobalPluginSettings {
+            name: name.to_string()
+        })?)
+    }
+
+    fn set_global_plugin_settings(&self, name: &str, settings: Value) -> Result<SetGlobalPluginSettingsResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &SetGlobalPluginSettings {
+            name: name.to_string(),
+            settings
+        })?)
+    }
+
+    fn send_module_message(&self, topic: &str, data: Value, serial_number: Option<&str>) -> Result<SendModuleMessageResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &SendModuleMessage {
+            topic: topic.to_string(),
+            data,
+            serial_number: serial_number.map(|x| x.to_string())
+        })?)
+    }
+
     fn get_stack(&self, serial_number: &str) -> Result<GetStackResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &GetStack {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &GetStack {
             serial_number: serial_number.to_string()
         })?)
     }
 
     fn get_stack_names(&self, serial_number: &str) -> Result<GetStackNamesResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &GetStackNames {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &GetStackNames {
+            serial_number: serial_number.to_string()
+        })?)
+    }
+
+    fn get_stack_breadcrumbs(&self, serial_number: &str) -> Result<GetStackBreadcrumbsResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &GetStackBreadcrumbs {
             serial_number: serial_number.to_string()
         })?)
     }
 
     fn get_current_screen(&self, serial_number: &str) -> Result<GetCurrentScreenResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &GetCurrentScreen {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &GetCurrentScreen {
             serial_number: serial_number.to_string()
         })?)
     }
 
     fn get_button_images(&self, serial_number: &str) -> Result<GetButtonImagesResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &GetButtonImages {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &GetButtonImages {
+            serial_number: serial_number.to_string()
+        })?)
+    }
+
+    fn render_button_preview(&self, serial_number: &str, key: u8, button: Button, size: (u32, u32)) -> Result<RenderButtonPreviewResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &RenderButtonPreview {
+            serial_number: serial_number.to_string(),
+            key,
+            button,
+            width: size.0,
+            height: size.1
+        })?)
+    }
+
+    fn screen_revisions(&self, serial_number: &str) -> Result<GetScreenRevisionsResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &GetScreenRevisions {
             serial_number: serial_number.to_string()
         })?)
     }
 
     fn get_button(&self, serial_number: &str, key: u8) -> Result<GetButtonResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &GetButton {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &GetButton {
             serial_number: serial_number.to_string(),
             key
         })?)
     }
 
     fn set_button(&self, serial_number: &str, key: u8, button: Button) -> Result<SetButtonResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &SetButton {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &SetButton {
             serial_number: serial_number.to_string(),
             key,
-            button
+            button,
+            revision: None
         })?)
     }
 
     fn clear_button(&self, serial_number: &str, key: u8) -> Result<ClearButtonResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &ClearButton {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ClearButton {
             serial_number: serial_number.to_string(),
-            key
+            key,
+            revision: None
+        })?)
+    }
+
+    fn move_button(&self, serial_number: &str, from: u8, to: u8, swap: bool) -> Result<MoveButtonResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &MoveButton {
+            serial_number: serial_number.to_string(),
+            from,
+            to,
+            swap,
+            revision: None
         })?)
     }
 
     fn clipboard_status(&self) -> Result<ClipboardStatusResult, SDClientError> {
-        Ok(process_request_without_data(self.get_handle().deref_mut())?)
+        Ok(process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?)
     }
 
     fn copy_button(&self, serial_number: &str, key: u8) -> Result<CopyButtonResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &CopyButton {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &CopyButton {
             serial_number: serial_number.to_string(),
             key
         })?)
     }
 
     fn paste_button(&self, serial_number: &str, key: u8) -> Result<PasteButtonResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &PasteButton {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &PasteButton {
             serial_number: serial_number.to_string(),
-            key
+            key,
+            data: None,
+            revision: None
+        })?)
+    }
+
+    fn paste_portable_button(&self, serial_number: &str, key: u8, data: PortableButton) -> Result<PasteButtonResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &PasteButton {
+            serial_number: serial_number.to_string(),
+            key,
+            data: Some(data),
+            revision: None
         })?)
     }
 
     fn new_button(&self, serial_number: &str, key: u8) -> Result<NewButtonResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &NewButton {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &NewButton {
             serial_number: serial_number.to_string(),
-            key
+            key,
+            revision: None
         })?)
     }
 
     fn new_button_from_component(&self, serial_number: &str, key: u8, component_name: &str) -> Result<NewButtonFromComponentResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &NewButtonFromComponent {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &NewButtonFromComponent {
             serial_number: serial_number.to_string(),
             key,
-            component_name: component_name.to_string()
+            component_name: component_name.to_string(),
+            revision: None
         })?)
     }
 
     fn add_component(&self, serial_number: &str, key: u8, component_name: &str) -> Result<AddComponentResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &AddComponent {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &AddComponent {
             serial_number: serial_number.to_string(),
             key,
-            component_name: component_name.to_string()
+            component_name: component_name.to_string(),
+            revision: None
         })?)
     }
 
     fn get_component_values(&self, serial_number: &str, key: u8, component_name: &str) -> Result<GetComponentValuesResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &GetComponentValues {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &GetComponentValues {
             serial_number: serial_number.to_string(),
             key,
             component_name: component_name.to_string()
@@ -316,100 +705,225 @@ impl SDSyncRequestClient for WinRequestClient {
     }
 
     fn add_component_value(&self, serial_number: &str, key: u8, component_name: &str, path: &str) -> Result<AddComponentValueResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &AddComponentValue {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &AddComponentValue {
             serial_number: serial_number.to_string(),
             key,
             component_name: component_name.to_string(),
-            path: path.to_string()
+            path: path.to_string(),
+            revision: None
         })?)
     }
 
     fn remove_component_value(&self, serial_number: &str, key: u8, component_name: &str, path: &str, index: usize) -> Result<RemoveComponentValueResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &RemoveComponentValue {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &RemoveComponentValue {
             serial_number: serial_number.to_string(),
             key,
             component_name: component_name.to_string(),
             path: path.to_string(),
-            index
+            index,
+            revision: None
         })?)
     }
 
     fn set_component_value(&self, serial_number: &str, key: u8, component_name: &str, value: UIPathValue) -> Result<SetComponentValueResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &SetComponentValue {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &SetComponentValue {
             serial_number: serial_number.to_string(),
             key,
             component_name: component_name.to_string(),
-            value
+            value,
+            revision: None
         })?)
     }
 
     fn remove_component(&self, serial_number: &str, key: u8, component_name: &str) -> Result<RemoveComponentResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &RemoveComponent {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &RemoveComponent {
             serial_number: serial_number.to_string(),
             key,
-            component_name: component_name.to_string()
+            component_name: component_name.to_string(),
+            revision: None
         })?)
     }
 
     fn push_screen(&self, serial_number: &str, screen: RawButtonPanel) -> Result<PushScreenResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &PushScreen {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &PushScreen {
             serial_number: serial_number.to_string(),
             screen
         })?)
     }
 
     fn pop_screen(&self, serial_number: &str) -> Result<PopScreenResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &PopScreen {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &PopScreen {
             serial_number: serial_number.to_string()
         })?)
     }
 
     fn forcibly_pop_screen(&self, serial_number: &str) -> Result<ForciblyPopScreenResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &ForciblyPopScreen {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ForciblyPopScreen {
             serial_number: serial_number.to_string()
         })?)
     }
 
     fn replace_screen(&self, serial_number: &str, screen: RawButtonPanel) -> Result<ReplaceScreenResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &ReplaceScreen {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ReplaceScreen {
             serial_number: serial_number.to_string(),
             screen
         })?)
     }
 
+    fn set_panel(&self, serial_number: &str, panel: RawButtonPanel, folder_id: Option<String>) -> Result<SetPanelResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &SetPanel {
+            serial_number: serial_number.to_string(),
+            panel,
+            folder_id,
+            revision: None
+        })?)
+    }
+
     fn reset_stack(&self, serial_number: &str, screen: RawButtonPanel) -> Result<ResetStackResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &ResetStack {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ResetStack {
             serial_number: serial_number.to_string(),
             screen
         })?)
     }
 
     fn drop_stack_to_root(&self, serial_number: &str) -> Result<DropStackToRootResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &DropStackToRoot {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &DropStackToRoot {
             serial_number: serial_number.to_string()
         })?)
     }
 
+    fn pop_to_screen(&self, serial_number: &str, index: usize) -> Result<PopToScreenResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &PopToScreen {
+            serial_number: serial_number.to_string(),
+            index
+        })?)
+    }
+
     fn commit_changes(&self, serial_number: &str) -> Result<CommitChangesToConfigResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &CommitChangesToConfig {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &CommitChangesToConfig {
             serial_number: serial_number.to_string()
         })?)
     }
 
     fn do_button_action(&self, serial_number: &str, key: u8) -> Result<DoButtonActionResult, SDClientError> {
-        Ok(process_request(self.get_handle().deref_mut(), &DoButtonAction {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &DoButtonAction {
             serial_number: serial_number.to_string(),
             key
         })?)
     }
 
-    fn send_packet(&self, mut packet: SocketPacket) -> Result<SocketPacket, SDClientError> {
+    fn show_notification(&self, serial_number: &str, text: &str, icon: Option<&str>, duration: f32, keys: Vec<u8>) -> Result<ShowNotificationResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ShowNotification {
+            serial_number: serial_number.to_string(),
+            text: text.to_string(),
+            icon: icon.map(|x| x.to_string()),
+            duration,
+            keys
+        })?)
+    }
+
+    fn dismiss_notification(&self, serial_number: &str, id: &str) -> Result<DismissNotificationResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &DismissNotification {
+            serial_number: serial_number.to_string(),
+            id: id.to_string()
+        })?)
+    }
+
+    fn batch(&self, requests: Vec<SocketPacket>) -> Result<BatchResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &Batch {
+            requests
+        })?)
+    }
+
+    fn daemon_status(&self) -> Result<GetDaemonStatus, SDClientError> {
+        Ok(process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?)
+    }
+
+    fn safe_mode_status(&self) -> Result<GetSafeModeStatus, SDClientError> {
+        Ok(process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?)
+    }
+
+    fn set_safe_mode_on_next_start(&self, enabled: bool) -> Result<SetSafeModeOnNextStartResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &SetSafeModeOnNextStart {
+            enabled
+        })?)
+    }
+
+    fn shutdown_daemon(&self) -> Result<ShutdownDaemonResult, SDClientError> {
+        Ok(process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?)
+    }
+
+    fn restart_daemon(&self) -> Result<RestartDaemonResult, SDClientError> {
+        Ok(process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?)
+    }
+
+    fn start_session(&self) -> Result<StartSessionResult, SDClientError> {
+        Ok(process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?)
+    }
+
+    fn resume_session(&self, token: &str) -> Result<ResumeSessionResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &ResumeSession {
+            token: token.to_string()
+        })?)
+    }
+
+    fn identify(&self, name: &str, version: &str, read_only: bool) -> Result<IdentifyClientResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &IdentifyClient {
+            name: name.to_string(),
+            version: version.to_string(),
+            read_only
+        })?)
+    }
+
+    fn list_clients(&self) -> Result<Vec<ClientInfo>, SDClientError> {
+        let response: ListClients = process_request_without_data(self.get_handle().deref_mut(), &self.get_options())?;
+        Ok(response.clients)
+    }
+
+    fn disconnect_client(&self, id: u64) -> Result<DisconnectClientResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &DisconnectClient {
+            id
+        })?)
+    }
+
+    fn get_recent_logs(&self, limit: usize, filter: LogFilter) -> Result<Vec<LogRecord>, SDClientError> {
+        let response: GetRecentLogsResult = process_request(self.get_handle().deref_mut(), &self.get_options(), &GetRecentLogs {
+            limit,
+            filter
+        })?;
+        Ok(response.logs)
+    }
+
+    fn stream_logs(&self, filter: Option<LogFilter>) -> Result<StreamLogsResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &StreamLogs {
+            enabled: filter.is_some(),
+            filter: filter.unwrap_or_default()
+        })?)
+    }
+
+    fn undo(&self, serial_number: &str) -> Result<UndoResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &Undo {
+            serial_number: serial_number.to_string()
+        })?)
+    }
+
+    fn redo(&self, serial_number: &str) -> Result<RedoResult, SDClientError> {
+        Ok(process_request(self.get_handle().deref_mut(), &self.get_options(), &Redo {
+            serial_number: serial_number.to_string()
+        })?)
+    }
+
+    fn send_packet(&self, packet: SocketPacket) -> Result<SocketPacket, SDClientError> {
+        self.send_packet_with_options(packet, &self.get_options())
+    }
+
+    fn send_packet_with_options(&self, mut packet: SocketPacket, options: &RequestOptions) -> Result<SocketPacket, SDClientError> {
         let id = rand::thread_rng().sample_iter(&Alphanumeric).take(20).map(char::from).collect::<String>();
         packet.requester = Some(id.clone());
 
         let mut handle = self.get_handle();
         send_packet_as_is(handle.get_mut(), packet)?;
-        read_response(handle.deref_mut(), &id)
+        read_response_with_options(handle.deref_mut(), &id, options)
     }
 
     fn send_packet_without_response(&self, packet: SocketPacket) -> Result<(), SDClientError> {
@@ -0,0 +1,7 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use streamduck_core::socket::parse_packet;
+
+fuzz_target!(|data: &[u8]| {
+    parse_packet(data).ok();
+});
@@ -0,0 +1,16 @@
+#![no_main]
+use std::io::BufReader;
+use libfuzzer_sys::fuzz_target;
+use streamduck_core::socket::{PacketError, read_packet};
+
+fuzz_target!(|data: &[u8]| {
+    // Small buffer makes packets span multiple reads
+    let mut reader = BufReader::with_capacity(16, data);
+
+    loop {
+        match read_packet(&mut reader) {
+            Err(PacketError::Closed) | Err(PacketError::Truncated) | Err(PacketError::ReadError(_)) => break,
+            _ => {}
+        }
+    }
+});
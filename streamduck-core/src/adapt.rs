@@ -0,0 +1,208 @@
+//! Remapping of layouts between device kinds with different key grids
+//!
+//! Buttons keep their row and column if they fit on the new grid, buttons that don't fit are moved
+//! into auto-generated folders, linked from the last free key of the panel they were on
+
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use streamdeck::Kind;
+use crate::config::DeviceConfig;
+use crate::core::button::{Button, Component};
+use crate::core::{ButtonMap, RawButtonPanel};
+use crate::layout::{FOLDERS_KEY, FolderMap, folders_of};
+use crate::modules::folders::{FolderComponent, FolderModule, FolderUpComponent};
+use crate::modules::SDModule;
+
+/// Display name of folders generated for buttons that didn't fit
+pub const OVERFLOW_FOLDER_NAME: &str = "More";
+
+/// Summary of what adaptation did to the layout
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AdaptReport {
+    /// Buttons that got a different key index
+    pub moved: usize,
+    /// Buttons that were moved into overflow folders
+    pub overflowed: usize,
+    /// Amount of folders that were generated for overflowing buttons
+    pub overflow_folders: usize,
+}
+
+impl AdaptReport {
+    /// Checks if adaptation changed anything
+    pub fn is_empty(&self) -> bool {
+        self.moved == 0 && self.overflowed == 0
+    }
+}
+
+/// Amount of key columns on device kind
+pub fn key_columns(kind: Kind) -> u8 {
+    match kind {
+        Kind::Mini => 3,
+        Kind::Xl => 8,
+        _ => 5,
+    }
+}
+
+/// Maps key from one device kind to another, keeping its row and column. [None] if key doesn't fit on the other grid
+pub fn map_key(key: u8, from: Kind, to: Kind) -> Option<u8> {
+    map_grid_key(key, key_columns(from), key_columns(to), to.keys())
+}
+
+/// Maps key from one grid to another, keeping its row and column. [None] if key doesn't fit on the other grid
+pub fn map_grid_key(key: u8, from_columns: u8, to_columns: u8, to_keys: u8) -> Option<u8> {
+    let row = key / from_columns;
+    let column = key % from_columns;
+
+    if column >= to_columns {
+        return None;
+    }
+
+    let mapped = row as u16 * to_columns as u16 + column as u16;
+
+    if mapped < to_keys as u16 {
+        Some(mapped as u8)
+    } else {
+        None
+    }
+}
+
+/// Remaps root layout and all folders of device config from one device kind to another
+pub fn adapt_config(config: &mut DeviceConfig, from: Kind, to: Kind) -> AdaptReport {
+    let mut report = AdaptReport::default();
+
+    if from.keys() == to.keys() && key_columns(from) == key_columns(to) {
+        return report;
+    }
+
+    let mut folders = folders_of(config);
+    let mut generated = FolderMap::new();
+
+    config.layout.buttons = adapt_buttons(std::mem::take(&mut config.layout.buttons), from, to, &folders, &mut generated, &mut report);
+
+    // Only adapting folders that existed before, generated folders already fit the device
+    let ids: Vec<String> = folders.keys().cloned().collect();
+
+    for id in ids {
+        let buttons = std::mem::take(&mut folders.get_mut(&id).unwrap().buttons);
+        let buttons = adapt_buttons(buttons, from, to, &folders, &mut generated, &mut report);
+        folders.get_mut(&id).unwrap().buttons = buttons;
+    }
+
+    if !report.is_empty() {
+        folders.extend(generated);
+        config.plugin_data.insert(FOLDERS_KEY.to_string(), serde_json::to_value(folders).unwrap());
+    }
+
+    report
+}
+
+fn adapt_buttons(buttons: ButtonMap, from: Kind, to: Kind, folders: &FolderMap, generated: &mut FolderMap, report: &mut AdaptReport) -> ButtonMap {
+    let from_columns = key_columns(from);
+    let to_columns = key_columns(to);
+    let to_keys = to.keys();
+    let to_rows = to_keys / to_columns;
+
+    let mut keys: Vec<u8> = buttons.keys().copied().collect();
+    keys.sort();
+
+    let mut buttons = buttons;
+    let mut result = ButtonMap::new();
+    let mut overflow = vec![];
+
+    for key in keys {
+        let button = buttons.remove(&key).unwrap();
+        let (row, column) = (key / from_columns, key % from_columns);
+
+        if row < to_rows && column < to_columns {
+            let new_key = row * to_columns + column;
+
+            if new_key != key {
+                report.moved += 1;
+            }
+
+            result.insert(new_key, button);
+        } else {
+            overflow.push(button);
+        }
+    }
+
+    if !overflow.is_empty() {
+        report.overflowed += overflow.len();
+
+        // Link to overflow folder takes last free key, or pushes last button into the folder if panel is full
+        let slot = (0..to_keys).rev().find(|key| !result.contains_key(key)).unwrap_or_else(|| {
+            let last = to_keys - 1;
+            overflow.insert(0, result.remove(&last).unwrap());
+            report.overflowed += 1;
+            last
+        });
+
+        result.insert(slot, overflow_folder(overflow, to_keys, folders, generated, report));
+    }
+
+    result
+}
+
+/// Puts buttons into a new folder with back button on first key, chaining more folders if needed. Returns button that opens the folder
+fn overflow_folder(mut buttons: Vec<Button>, key_count: u8, folders: &FolderMap, generated: &mut FolderMap, report: &mut AdaptReport) -> Button {
+    let id = new_folder_id(folders, generated);
+    generated.insert(id.clone(), RawButtonPanel::default());
+    report.overflow_folders += 1;
+
+    let mut contents = ButtonMap::new();
+    contents.insert(0, default_button(FolderUpComponent::NAME, FolderUpComponent {}));
+
+    let capacity = key_count as usize - 1;
+
+    if buttons.len() > capacity {
+        let rest = buttons.split_off(capacity - 1);
+        contents.insert(key_count - 1, overflow_folder(rest, key_count, folders, generated, report));
+    }
+
+    for (index, button) in buttons.into_iter().enumerate() {
+        contents.insert(index as u8 + 1, button);
+    }
+
+    generated.insert(id.clone(), RawButtonPanel {
+        display_name: OVERFLOW_FOLDER_NAME.to_string(),
+        data: Default::default(),
+        buttons: contents
+    });
+
+    default_button(FolderComponent::NAME, FolderComponent {
+        id,
+        name: OVERFLOW_FOLDER_NAME.to_string(),
+        thumbnail: false,
+        back_button: Default::default()
+    })
+}
+
+/// Creates button with the component and default looks that folder module defines for it
+fn default_button<T: Component + Serialize>(name: &str, component: T) -> Button {
+    let mut button = Button::new();
+
+    if let Some(definition) = FolderModule::default().components().remove(name) {
+        let mut looks = definition.default_looks;
+
+        if let Some(text) = looks.text.first_mut().filter(|_| name == FolderComponent::NAME) {
+            text.text = OVERFLOW_FOLDER_NAME.to_string();
+        }
+
+        button.insert_component(looks).ok();
+    }
+
+    button.insert_component(component).ok();
+    button
+}
+
+/// Generates random folder ID the same way folder module does, making sure it's not taken
+fn new_folder_id(folders: &FolderMap, generated: &FolderMap) -> String {
+    loop {
+        let id: String = rand::thread_rng().sample_iter(&Alphanumeric).take(16).map(char::from).collect();
+
+        if !folders.contains_key(&id) && !generated.contains_key(&id) {
+            return id;
+        }
+    }
+}
@@ -1,25 +1,91 @@
 //! Core and device configs
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::{Read, Write};
 use std::ops::Deref;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
 use image::{DynamicImage};
 use serde::{Serialize, Deserialize};
 use serde::de::DeserializeOwned;
 use crate::core::RawButtonPanel;
+use crate::core::button::Button;
 use serde_json::Value;
 use streamdeck::Kind;
 use crate::ImageCollection;
-use crate::images::{SDImage, SDSerializedImage};
+use crate::images::{ImageConversion, SDImage, SDSerializedImage};
+use crate::modules::core_module::CoreSettings;
 use crate::util::{hash_image, hash_str};
-use crate::thread::util::resize_for_streamdeck;
+use crate::thread::orientation::DeviceOrientation;
+use crate::thread::transition::PanelTransition;
 
+pub const CONFIG_FILE_PATH: &'static str = "config.toml";
 pub const DEFAULT_POOL_RATE: u32 = 1000;
 pub const DEFAULT_RECONNECT_TIME: f32 = 1.0;
 pub const DEFAULT_CONFIG_PATH: &'static str = "devices";
 pub const DEFAULT_PLUGIN_PATH: &'static str = "plugins";
 pub const DEFAULT_PLUGIN_SETTINGS_PATH: &'static str = "global.json";
+pub const DEFAULT_FONT_PATH: &'static str = "fonts";
+pub const DEFAULT_CONFIG_BACKUP_COUNT: usize = 5;
+pub const DEFAULT_AUTOSAVE_INTERVAL: f32 = 5.0;
+pub const MILESTONE_FOLDER: &'static str = "milestones";
+pub const IMAGE_FOLDER: &'static str = "images";
+pub const KNOWN_GOOD_SUFFIX: &'static str = ".good";
+
+/// Global keyboard shortcut that controls a device
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HotkeyBinding {
+    /// Key combination, eg. "Ctrl + Shift + F1"
+    pub keys: String,
+    /// Serial number of device that shortcut controls
+    pub serial: String,
+    #[serde(flatten)]
+    pub action: HotkeyAction,
+}
+
+/// Logical device made of several physical devices placed next to each other, see [crate::core::composite]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CompositeDevice {
+    /// Serial number that composite device is known by
+    pub serial: String,
+    /// Physical devices that make up composite device
+    pub members: Vec<CompositeMember>,
+}
+
+/// Physical device that is a part of composite device
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CompositeMember {
+    /// Serial number of the device
+    pub serial: String,
+    /// Column of composite grid that left-most keys of the device are in
+    #[serde(default)]
+    pub column: u8,
+    /// Row of composite grid that top keys of the device are in
+    #[serde(default)]
+    pub row: u8,
+}
+
+/// What happens when global keyboard shortcut is pressed
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum HotkeyAction {
+    /// Opens folder on top of current screen
+    PushFolder {
+        folder_id: String
+    },
+
+    /// Triggers button of current screen as if it was pressed
+    PressButton {
+        key: u8
+    },
+
+    /// Goes back to previous screen
+    PopScreen,
+}
 
 /// Reference counted [DeviceConfig]
 pub type UniqueDeviceConfig = Arc<RwLock<DeviceConfig>>;
@@ -37,6 +103,50 @@ pub struct Config {
     plugin_path: Option<PathBuf>,
     /// Path to plugin settings json
     plugin_settings_path: Option<PathBuf>,
+    /// Path to folder with user fonts
+    font_path: Option<PathBuf>,
+    /// If fonts installed in the system should be available too
+    system_fonts: Option<bool>,
+    /// Amount of rotated backups to keep for every device config
+    config_backup_count: Option<usize>,
+    /// Seconds to wait after last change before device config gets saved by daemon, 0 disables autosave
+    autosave_interval: Option<f32>,
+    /// If device configs should be reloaded when they're changed outside of the daemon
+    watch_device_configs: Option<bool>,
+    /// If folders that were open on devices should be opened again after daemon restarts
+    persist_stack: Option<bool>,
+    /// File format to save device configs in
+    device_config_format: Option<DeviceConfigFormat>,
+    /// Address to serve HTTP API on, eg. 127.0.0.1:8420. Addresses other than loopback require a socket token
+    http_address: Option<String>,
+    /// If built-in web UI should be served along with HTTP API
+    http_ui: Option<bool>,
+    /// If HTTP API should only allow requests that don't change anything, for dashboards and stream overlays
+    http_read_only: Option<bool>,
+    /// Address to serve Prometheus metrics on, eg. 127.0.0.1:9420
+    metrics_address: Option<String>,
+    /// If clients are allowed to shut down and restart the daemon
+    allow_shutdown_requests: Option<bool>,
+    /// Path of unix domain socket
+    socket_path: Option<PathBuf>,
+    /// User ID that should own unix domain socket
+    socket_owner: Option<u32>,
+    /// Group ID that should own unix domain socket
+    socket_group: Option<u32>,
+    /// Permission bits of unix domain socket, eg. 0o660 to only allow owner and group
+    socket_permissions: Option<u32>,
+    /// Token that clients have to authenticate with before making requests
+    socket_token: Option<String>,
+    /// Token that clients can authenticate with to only make requests that don't change anything
+    read_only_token: Option<String>,
+    /// Global keyboard shortcuts that control devices
+    hotkeys: Option<Vec<HotkeyBinding>>,
+    /// Logical devices made of several physical devices
+    composite_devices: Option<Vec<CompositeDevice>>,
+
+    /// If daemon is running in safe mode
+    #[serde(skip)]
+    safe_mode: RwLock<bool>,
 
     #[serde(skip)]
     pub plugin_settings: RwLock<HashMap<String, Value>>,
@@ -47,14 +157,18 @@ pub struct Config {
 
     /// Currently loaded image collections
     #[serde(skip)]
-    pub loaded_images: RwLock<HashMap<String, ImageCollection>>
+    pub loaded_images: RwLock<HashMap<String, ImageCollection>>,
+
+    /// Hashes of device config files as daemon last wrote or read them, for telling daemon's own saves from external changes
+    #[serde(skip)]
+    written_configs: RwLock<HashMap<String, String>>
 }
 
 #[allow(dead_code)]
 impl Config {
     /// Reads config and retrieves config struct
     pub fn get() -> Config {
-        let config: Config = if let Ok(content) = fs::read_to_string("config.toml") {
+        let config: Config = if let Ok(content) = fs::read_to_string(CONFIG_FILE_PATH) {
             if let Ok(config) = toml::from_str(&content) {
                 config
             } else {
@@ -94,6 +208,133 @@ impl Config {
         self.plugin_settings_path.clone().unwrap_or(PathBuf::from(DEFAULT_PLUGIN_SETTINGS_PATH))
     }
 
+    /// Font folder path, defaults to [DEFAULT_FONT_PATH] if not set
+    pub fn font_path(&self) -> PathBuf {
+        self.font_path.clone().unwrap_or(PathBuf::from(DEFAULT_FONT_PATH))
+    }
+
+    /// If system fonts should be loaded, disabled if not set
+    pub fn system_fonts(&self) -> bool {
+        self.system_fonts.unwrap_or(false)
+    }
+
+    /// Config backup count, defaults to [DEFAULT_CONFIG_BACKUP_COUNT] if not set
+    pub fn config_backup_count(&self) -> usize {
+        self.config_backup_count.unwrap_or(DEFAULT_CONFIG_BACKUP_COUNT)
+    }
+
+    /// Autosave debounce interval, defaults to [DEFAULT_AUTOSAVE_INTERVAL] if not set, [None] if autosave is disabled
+    pub fn autosave_interval(&self) -> Option<Duration> {
+        let interval = self.autosave_interval.unwrap_or(DEFAULT_AUTOSAVE_INTERVAL);
+
+        if interval > 0.0 {
+            Some(Duration::from_secs_f32(interval))
+        } else {
+            None
+        }
+    }
+
+    /// If device config folder should be watched for changes, disabled if not set
+    pub fn watch_device_configs(&self) -> bool {
+        self.watch_device_configs.unwrap_or(false)
+    }
+
+    /// If stack path of devices should be kept in device configs and restored on connect, disabled if not set
+    pub fn persist_stack(&self) -> bool {
+        self.persist_stack.unwrap_or(false)
+    }
+
+    /// Address of HTTP API, disabled if not set
+    pub fn http_address(&self) -> Option<&str> {
+        self.http_address.as_deref()
+    }
+
+    /// If web UI should be served by HTTP API, enabled if not set
+    pub fn http_ui(&self) -> bool {
+        self.http_ui.unwrap_or(true)
+    }
+
+    /// If HTTP API only allows requests that don't change anything, disabled if not set
+    pub fn http_read_only(&self) -> bool {
+        self.http_read_only.unwrap_or(false)
+    }
+
+    /// Address of metrics exporter, disabled if not set
+    pub fn metrics_address(&self) -> Option<&str> {
+        self.metrics_address.as_deref()
+    }
+
+    /// If shutdown and restart requests are allowed, disabled if not set
+    pub fn allow_shutdown_requests(&self) -> bool {
+        self.allow_shutdown_requests.unwrap_or(false)
+    }
+
+    /// Path of unix domain socket, daemon's default path is used if not set
+    pub fn socket_path(&self) -> Option<&Path> {
+        self.socket_path.as_deref()
+    }
+
+    /// User and group IDs that should own unix domain socket, unchanged if not set
+    pub fn socket_owner(&self) -> (Option<u32>, Option<u32>) {
+        (self.socket_owner, self.socket_group)
+    }
+
+    /// Permission bits of unix domain socket, unchanged if not set
+    pub fn socket_permissions(&self) -> Option<u32> {
+        self.socket_permissions
+    }
+
+    /// Token for authenticating clients, authentication isn't required if not set
+    pub fn socket_token(&self) -> Option<&str> {
+        self.socket_token.as_deref()
+    }
+
+    /// Token for authenticating read-only clients
+    pub fn read_only_token(&self) -> Option<&str> {
+        self.read_only_token.as_deref()
+    }
+
+    /// Global keyboard shortcuts, none if not set
+    pub fn hotkeys(&self) -> Vec<HotkeyBinding> {
+        self.hotkeys.clone().unwrap_or_default()
+    }
+
+    /// Composite devices, none if not set
+    pub fn composite_devices(&self) -> Vec<CompositeDevice> {
+        self.composite_devices.clone().unwrap_or_default()
+    }
+
+    /// Device config file format, defaults to [DeviceConfigFormat::Json] if not set
+    pub fn device_config_format(&self) -> DeviceConfigFormat {
+        self.device_config_format.unwrap_or_default()
+    }
+
+    /// Path to config file of a device in configured format
+    pub fn device_config_file(&self, serial: &str) -> PathBuf {
+        self.device_config_path().join(format!("{}.{}", serial, self.device_config_format().extension()))
+    }
+
+    /// Finds existing config file of a device, preferring configured format, so configs keep loading after format is changed
+    fn find_device_config_file(&self, serial: &str) -> Option<(PathBuf, DeviceConfigFormat)> {
+        let preferred = self.device_config_format();
+
+        std::iter::once(preferred)
+            .chain(DeviceConfigFormat::ALL.into_iter().filter(|x| *x != preferred))
+            .flat_map(|format| format.extensions().iter().map(move |extension| (format, *extension)))
+            .map(|(format, extension)| (self.device_config_path().join(format!("{}.{}", serial, extension)), format))
+            .find(|(path, _)| path.is_file())
+    }
+
+    /// If daemon is running in safe mode, with only built-in modules and animations disabled
+    pub fn safe_mode(&self) -> bool {
+        *self.safe_mode.read().unwrap()
+    }
+
+    /// Sets safe mode state
+    pub fn set_safe_mode(&self, safe_mode: bool) {
+        *self.safe_mode.write().unwrap() = safe_mode;
+    }
+
     /// Loads plugin settings from file
     pub fn load_plugin_settings(&self) {
         if let Ok(settings) = fs::read_to_string(self.plugin_settings_path()) {
@@ -121,6 +362,33 @@ impl Config {
         self.write_plugin_settings();
     }
 
+    /// Retrieves plugin settings by name without knowing their type
+    pub fn get_plugin_settings_value(&self, name: &str) -> Option<Value> {
+        self.plugin_settings.read().unwrap().get(name).cloned()
+    }
+
+    /// Sets plugin settings by name without knowing their type
+    pub fn set_plugin_settings_value(&self, name: &str, value: Value) {
+        let mut lock = self.plugin_settings.write().unwrap();
+        lock.insert(name.to_string(), value);
+        drop(lock);
+
+        self.write_plugin_settings();
+    }
+
+    /// Removes plugin settings, returns false if there weren't any
+    pub fn remove_plugin_settings(&self, name: &str) -> bool {
+        let mut lock = self.plugin_settings.write().unwrap();
+        let removed = lock.remove(name).is_some();
+        drop(lock);
+
+        if removed {
+            self.write_plugin_settings();
+        }
+
+        removed
+    }
+
     /// Writes plugin settings to file
     pub fn write_plugin_settings(&self) {
         let lock = self.plugin_settings.read().unwrap();
@@ -136,12 +404,9 @@ impl Config {
 
         let mut devices = self.loaded_configs.write().unwrap();
 
-        let mut path = self.device_config_path();
-        path.push(format!("{}.json", serial));
-
-        let content = fs::read_to_string(path)?;
-        let device = serde_json::from_str::<DeviceConfig>(&content)?;
+        let (path, format) = self.find_device_config_file(serial).ok_or(ConfigError::DeviceNotFound)?;
 
+        let device = self.read_device_config_file(&path, format)?;
 
         if let Some(device_config) = devices.get(serial) {
             *device_config.write().unwrap() = device;
@@ -158,29 +423,80 @@ impl Config {
     pub fn reload_device_configs(&self) -> Result<(), ConfigError> {
         let mut devices = self.loaded_configs.write().unwrap();
 
-        let dir = fs::read_dir(self.device_config_path())?;
+        let preferred = self.device_config_format();
+        let mut files = vec![];
 
-        for item in dir {
-            let item = item?;
-            if item.path().is_file() {
-                if let Some(extension) = item.path().extension() {
-                    if extension == "json" {
-                        let content = fs::read_to_string(item.path())?;
+        for item in fs::read_dir(self.device_config_path())? {
+            let path = item?.path();
 
-                        let device = serde_json::from_str::<DeviceConfig>(&content)?;
-                        let serial = device.serial.to_string();
+            if path.is_file() {
+                if let Some(format) = path.extension().and_then(|x| x.to_str()).and_then(DeviceConfigFormat::from_extension) {
+                    files.push((path, format));
+                }
+            }
+        }
 
-                        // Clearing image collection so it's fresh for reload
-                        self.get_image_collection(&device.serial).write().unwrap().clear();
-                        if let Some(device_config) = devices.get(&serial) {
-                            *device_config.write().unwrap() = device;
-                        } else {
-                            devices.insert(serial.to_string(), Arc::new(RwLock::new(device)));
-                        }
+        // Configured format goes last, so it wins if device has configs in several formats
+        files.sort_by_key(|(_, format)| *format == preferred);
 
-                        self.update_collection(devices.get(&serial).unwrap());
-                    }
+        for (path, format) in files {
+            let device = self.read_device_config_file(&path, format)?;
+            let serial = device.serial.to_string();
+
+            // Clearing image collection so it's fresh for reload
+            self.get_image_collection(&device.serial).write().unwrap().clear();
+            if let Some(device_config) = devices.get(&serial) {
+                *device_config.write().unwrap() = device;
+            } else {
+                devices.insert(serial.to_string(), Arc::new(RwLock::new(device)));
+            }
+
+            self.update_collection(devices.get(&serial).unwrap());
+        }
+
+        Ok(())
+    }
+
+    /// Copies all device config files as known good configs, should be called once daemon proved to work with current configs
+    pub fn mark_device_configs_good(&self) -> Result<(), ConfigError> {
+        for item in fs::read_dir(self.device_config_path())? {
+            let path = item?.path();
+
+            if path.is_file() && path.extension().and_then(|x| x.to_str()).and_then(DeviceConfigFormat::from_extension).is_some() {
+                let mut name = path.file_name().unwrap_or_default().to_os_string();
+                name.push(KNOWN_GOOD_SUFFIX);
+
+                fs::copy(&path, path.with_file_name(name))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces loaded device configs with known good configs, devices without known good config keep their current config
+    pub fn reload_known_good_device_configs(&self) -> Result<(), ConfigError> {
+        let mut devices = self.loaded_configs.write().unwrap();
+
+        for item in fs::read_dir(self.device_config_path())? {
+            let path = item?.path();
+
+            let format = path.to_string_lossy().strip_suffix(KNOWN_GOOD_SUFFIX)
+                .and_then(|x| Path::new(x).extension().and_then(|x| x.to_str()).and_then(DeviceConfigFormat::from_extension));
+
+            if let (true, Some(format)) = (path.is_file(), format) {
+                let device = self.read_device_config_file(&path, format)?;
+                let serial = device.serial.to_string();
+
+                log::info!("Using known good config for {}", serial);
+
+                self.get_image_collection(&serial).write().unwrap().clear();
+                if let Some(device_config) = devices.get(&serial) {
+                    *device_config.write().unwrap() = device;
+                } else {
+                    devices.insert(serial.to_string(), Arc::new(RwLock::new(device)));
                 }
+
+                self.update_collection(devices.get(&serial).unwrap());
             }
         }
 
@@ -193,11 +509,11 @@ impl Config {
 
         if let Some(device) = devices.get(serial).cloned() {
             self.update_collection(&device);
-            let mut path = self.device_config_path();
-            fs::create_dir_all(&path).ok();
-            path.push(format!("{}.json", serial));
+            fs::create_dir_all(self.device_config_path()).ok();
 
-            fs::write(path, serde_json::to_string(device.read().unwrap().deref()).unwrap())?;
+            let content = self.device_config_format().serialize(&self.store_images(device.read().unwrap().deref())?)?;
+            write_with_backups(&self.device_config_file(serial), &content, self.config_backup_count())?;
+            self.written_configs.write().unwrap().insert(serial.to_string(), hash_str(&content));
             Ok(())
         } else {
             Err(ConfigError::DeviceNotFound)
@@ -208,20 +524,411 @@ impl Config {
     pub fn save_device_configs(&self) -> Result<(), ConfigError> {
         let devices = self.loaded_configs.read().unwrap();
 
-        let path = self.device_config_path();
-        fs::create_dir_all(&path).ok();
+        fs::create_dir_all(self.device_config_path()).ok();
 
         for (serial, device) in devices.iter() {
             let device= device.clone();
             self.update_collection(&device);
-            let mut file_path = path.clone();
-            file_path.push(format!("{}.json", serial));
-            fs::write(file_path, serde_json::to_string(device.read().unwrap().deref()).unwrap())?;
+
+            let content = self.device_config_format().serialize(&self.store_images(device.read().unwrap().deref())?)?;
+            write_with_backups(&self.device_config_file(serial), &content, self.config_backup_count())?;
+            self.written_configs.write().unwrap().insert(serial.to_string(), hash_str(&content));
+        }
+
+        Ok(())
+    }
+
+    /// Checks if content is what daemon last wrote to or read from device config file of the serial
+    pub fn is_own_save(&self, serial: &str, content: &str) -> bool {
+        self.written_configs.read().unwrap().get(serial).map_or(false, |x| x == &hash_str(&content.to_string()))
+    }
+
+    /// Restores device config from a backup, 1 being the most recent backup, and reloads it.
+    /// Current config becomes the most recent backup, so restore can be undone
+    pub fn restore_device_config_backup(&self, serial: &str, backup: usize) -> Result<(), ConfigError> {
+        let path = self.device_config_file(serial);
+
+        let backup_path = backup_path(&path, backup);
+        if backup == 0 || !backup_path.is_file() {
+            return Err(ConfigError::BackupNotFound);
+        }
+
+        let content = fs::read_to_string(backup_path)?;
+        self.device_config_format().deserialize(&content)?;
+
+        write_with_backups(&path, &content, self.config_backup_count())?;
+
+        self.reload_device_config(serial)
+    }
+
+    /// Lists numbers of existing backups for device config, most recent first
+    pub fn device_config_backups(&self, serial: &str) -> Vec<usize> {
+        let path = self.device_config_file(serial);
+
+        (1..=self.config_backup_count())
+            .filter(|x| backup_path(&path, *x).is_file())
+            .collect()
+    }
+
+    /// Path to folder with config milestones, located inside device config folder
+    pub fn milestone_path(&self) -> PathBuf {
+        self.device_config_path().join(MILESTONE_FOLDER)
+    }
+
+    /// Path to folder with images of all devices, located inside device config folder
+    pub fn image_store_path(&self) -> PathBuf {
+        self.device_config_path().join(IMAGE_FOLDER)
+    }
+
+    /// Reads device config file, filling in images that are kept in image store
+    fn read_device_config_file(&self, path: &Path, format: DeviceConfigFormat) -> Result<DeviceConfig, ConfigError> {
+        let content = fs::read_to_string(path)?;
+        let mut device = format.deserialize(&content)?;
+        self.written_configs.write().unwrap().insert(device.serial.clone(), hash_str(&content));
+
+        for (identifier, hash) in std::mem::take(&mut device.stored_images) {
+            let image_path = self.image_store_path().join(format!("{}.json", hash));
+
+            match fs::read_to_string(&image_path).map_err(ConfigError::from).and_then(|x| Ok(serde_json::from_str::<SDSerializedImage>(&x)?)) {
+                Ok(image) => {
+                    device.images.insert(identifier, image);
+                }
+                Err(err) => log::warn!("Failed to load image {} of {} from {:?}: {:?}", identifier, device.serial, image_path, err),
+            }
+        }
+
+        Ok(device)
+    }
+
+    /// Writes images of device config into image store, returns copy of device config that only refers to images by content hash.
+    /// Images are named by content hash, so identical images of different devices are only stored once
+    fn store_images(&self, device: &DeviceConfig) -> Result<DeviceConfig, ConfigError> {
+        let mut device = device.clone();
+        fs::create_dir_all(self.image_store_path())?;
+
+        for (identifier, image) in std::mem::take(&mut device.images) {
+            let hash = hash_image(&image);
+            let image_path = self.image_store_path().join(format!("{}.json", hash));
+
+            if !image_path.is_file() {
+                write_with_backups(&image_path, &serde_json::to_string(&image)?, 0)?;
+            }
+
+            device.stored_images.insert(identifier, hash);
+        }
+
+        Ok(device)
+    }
+
+    /// Counts how many device configs refer to each image in image store, including config files that aren't loaded and their backups
+    pub fn image_reference_counts(&self) -> HashMap<String, usize> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+
+        if let Ok(dir) = fs::read_dir(self.image_store_path()) {
+            for item in dir.flatten() {
+                if let Some(hash) = item.file_name().to_string_lossy().strip_suffix(".json") {
+                    counts.insert(hash.to_string(), 0);
+                }
+            }
+        }
+
+        let mut hashes = vec![];
+
+        for device in self.get_all_device_configs() {
+            self.update_collection(&device);
+            hashes.extend(device.read().unwrap().images.values().map(hash_image).collect::<HashSet<String>>());
+        }
+
+        if let Ok(dir) = fs::read_dir(self.device_config_path()) {
+            for item in dir.flatten() {
+                let name = item.file_name().to_string_lossy().to_string();
+                // Backups, known good and disabled configs have suffixes after the extension
+                let format = name.split('.').skip(1).find_map(|x| DeviceConfigFormat::from_extension(x.trim_end_matches("_disabled")));
+
+                if let (true, Some(format)) = (item.path().is_file(), format) {
+                    if let Ok(device) = fs::read_to_string(item.path()).map_err(ConfigError::from).and_then(|x| format.deserialize(&x)) {
+                        hashes.extend(device.stored_images.into_values().collect::<HashSet<String>>());
+                    }
+                }
+            }
+        }
+
+        for hash in hashes {
+            *counts.entry(hash).or_default() += 1;
+        }
+
+        counts
+    }
+
+    /// Removes images that aren't used by layout or plugin data from device config, or from all loaded device configs if serial isn't specified.
+    /// Images in image store that no config refers to anymore are deleted afterwards
+    pub fn cleanup_unused_images(&self, serial: Option<&str>) -> Result<ImageCleanup, ConfigError> {
+        let devices = if let Some(serial) = serial {
+            vec![self.get_device_config(serial).ok_or(ConfigError::DeviceNotFound)?]
+        } else {
+            self.get_all_device_configs()
+        };
+
+        let mut removed = vec![];
+
+        for device in devices {
+            self.update_collection(&device);
+
+            let (serial, unused) = {
+                let handle = device.read().unwrap();
+                let referenced = handle.referenced_images();
+
+                (handle.serial.clone(), handle.images.keys().filter(|x| !referenced.contains(*x)).cloned().collect::<Vec<String>>())
+            };
+
+            if unused.is_empty() {
+                continue;
+            }
+
+            for identifier in &unused {
+                self.remove_image(&serial, identifier);
+            }
+
+            self.save_device_config(&serial)?;
+            removed.extend(unused);
+        }
+
+        let mut reclaimed_bytes = 0;
+
+        for (hash, count) in self.image_reference_counts() {
+            if count == 0 {
+                let image_path = self.image_store_path().join(format!("{}.json", hash));
+                let size = fs::metadata(&image_path).map(|x| x.len()).unwrap_or(0);
+
+                if fs::remove_file(&image_path).is_ok() {
+                    reclaimed_bytes += size;
+                }
+            }
         }
 
+        Ok(ImageCleanup {
+            removed,
+            reclaimed_bytes
+        })
+    }
+
+    /// Saves snapshot of all loaded device configs and plugin settings under specified name, overwrites milestone with same name
+    pub fn create_config_milestone(&self, name: &str) -> Result<(), ConfigError> {
+        let path = milestone_file(&self.milestone_path(), name)?;
+
+        let devices = self.loaded_configs.read().unwrap().values()
+            .map(|device| {
+                self.update_collection(device);
+                let device = device.read().unwrap();
+                (device.serial.clone(), device.clone())
+            })
+            .collect();
+
+        let snapshot = ConfigSnapshot {
+            milestone: ConfigMilestone {
+                name: name.to_string(),
+                created: SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_secs()).unwrap_or(0)
+            },
+            devices,
+            plugin_settings: self.plugin_settings.read().unwrap().clone()
+        };
+
+        fs::create_dir_all(self.milestone_path())?;
+        write_with_backups(&path, &serde_json::to_string(&snapshot)?, 0)?;
+
         Ok(())
     }
 
+    /// Lists all saved milestones, oldest first
+    pub fn list_config_milestones(&self) -> Vec<ConfigMilestone> {
+        let mut milestones = vec![];
+
+        if let Ok(dir) = fs::read_dir(self.milestone_path()) {
+            for item in dir.flatten() {
+                if item.path().extension().map_or(false, |x| x == "json") {
+                    if let Ok(content) = fs::read_to_string(item.path()) {
+                        match serde_json::from_str::<ConfigMilestone>(&content) {
+                            Ok(milestone) => milestones.push(milestone),
+                            Err(err) => log::warn!("Failed to read milestone {:?}: {}", item.path(), err),
+                        }
+                    }
+                }
+            }
+        }
+
+        milestones.sort_by_key(|x| x.created);
+        milestones
+    }
+
+    /// Replaces device configs and plugin settings with ones from milestone and saves them.
+    /// Previous device configs stay available as rotated backups
+    pub fn restore_config_milestone(&self, name: &str) -> Result<Vec<String>, ConfigError> {
+        let path = milestone_file(&self.milestone_path(), name)?;
+
+        if !path.is_file() {
+            return Err(ConfigError::MilestoneNotFound);
+        }
+
+        let mut snapshot: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+
+        let mut devices = vec![];
+        if let Some(Value::Object(configs)) = snapshot.get_mut("devices").map(Value::take) {
+            for (_, config) in configs {
+                devices.push(migrate_device_config(config)?);
+            }
+        }
+
+        let plugin_settings: HashMap<String, Value> = serde_json::from_value(snapshot["plugin_settings"].take()).unwrap_or_default();
+
+        let mut serials = vec![];
+        for device in devices {
+            let serial = device.serial.clone();
+
+            self.get_image_collection(&serial).write().unwrap().clear();
+            self.set_device_config(&serial, device);
+            self.save_device_config(&serial)?;
+
+            serials.push(serial);
+        }
+
+        *self.plugin_settings.write().unwrap() = plugin_settings;
+        self.write_plugin_settings();
+
+        Ok(serials)
+    }
+
+    /// Packs every device config, their images, daemon settings and plugin settings into a gzipped tar archive
+    pub fn export_bundle(&self) -> Result<Vec<u8>, ConfigError> {
+        let mut builder = tar::Builder::new(GzEncoder::new(vec![], Compression::default()));
+        let mut devices = vec![];
+
+        for device in self.get_all_device_configs() {
+            self.update_collection(&device);
+            let mut device = device.read().unwrap().clone();
+
+            for (identifier, image) in std::mem::take(&mut device.images) {
+                append_bundle_file(&mut builder, &format!("images/{}/{}.json", device.serial, identifier), &serde_json::to_vec(&image)?)?;
+            }
+
+            append_bundle_file(&mut builder, &format!("devices/{}.json", device.serial), &serde_json::to_vec(&device)?)?;
+            devices.push(device.serial);
+        }
+
+        append_bundle_file(&mut builder, "global.json", &serde_json::to_vec(self.plugin_settings.read().unwrap().deref())?)?;
+
+        let mut settings = toml::Value::try_from(self).map_err(|err| ConfigError::InvalidBundle(err.to_string()))?;
+
+        // Tokens shouldn't leave the machine with exported bundles
+        if let Some(table) = settings.as_table_mut() {
+            table.remove("socket_token");
+            table.remove("read_only_token");
+        }
+
+        let settings = toml::to_string(&settings).map_err(|err| ConfigError::InvalidBundle(err.to_string()))?;
+        append_bundle_file(&mut builder, CONFIG_FILE_PATH, settings.as_bytes())?;
+
+        let manifest = BundleManifest {
+            version: BUNDLE_VERSION,
+            created: SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_secs()).unwrap_or(0),
+            devices
+        };
+        append_bundle_file(&mut builder, "manifest.json", &serde_json::to_vec(&manifest)?)?;
+
+        Ok(builder.into_inner()?.finish()?)
+    }
+
+    /// Replaces device configs and plugin settings with ones from bundle and saves them, returns serials of imported devices.
+    /// Daemon settings are written to config file with paths of current installation kept, and apply after restart
+    pub fn import_bundle(&self, bundle: &[u8]) -> Result<Vec<String>, ConfigError> {
+        let mut archive = tar::Archive::new(GzDecoder::new(bundle));
+
+        let mut manifest: Option<BundleManifest> = None;
+        let mut devices: Vec<Value> = vec![];
+        let mut images: HashMap<String, HashMap<String, SDSerializedImage>> = HashMap::new();
+        let mut plugin_settings: Option<HashMap<String, Value>> = None;
+        let mut settings: Option<String> = None;
+
+        // Read errors here mean that archive is corrupted
+        let corrupted = |err: std::io::Error| ConfigError::InvalidBundle(err.to_string());
+
+        for entry in archive.entries().map_err(corrupted)? {
+            let mut entry = entry.map_err(corrupted)?;
+            let path = entry.path().map_err(corrupted)?.to_string_lossy().to_string();
+
+            let mut content = String::new();
+            entry.read_to_string(&mut content).map_err(corrupted)?;
+
+            match path.split('/').collect::<Vec<&str>>().as_slice() {
+                ["manifest.json"] => manifest = Some(serde_json::from_str(&content)?),
+                ["global.json"] => plugin_settings = Some(serde_json::from_str(&content)?),
+                [CONFIG_FILE_PATH] => settings = Some(content),
+                ["devices", file] if file.ends_with(".json") => devices.push(serde_json::from_str(&content)?),
+                ["images", serial, file] if file.ends_with(".json") => {
+                    images.entry(serial.to_string()).or_default()
+                        .insert(file.trim_end_matches(".json").to_string(), serde_json::from_str(&content)?);
+                }
+                _ => log::warn!("Skipping unknown file in config bundle: {}", path),
+            }
+        }
+
+        let manifest = manifest.ok_or_else(|| ConfigError::InvalidBundle("manifest is missing".to_string()))?;
+
+        if manifest.version > BUNDLE_VERSION {
+            return Err(ConfigError::UnsupportedVersion(manifest.version));
+        }
+
+        // Parsing everything before applying, so broken bundle doesn't leave configs half-imported
+        let mut configs = vec![];
+        for device in devices {
+            let mut device = migrate_device_config(device)?;
+            device.images.extend(images.remove(&device.serial).unwrap_or_default());
+            configs.push(device);
+        }
+
+        let settings = settings
+            .map(|x| toml::from_str::<Config>(&x))
+            .transpose()
+            .map_err(|err| ConfigError::InvalidBundle(err.to_string()))?;
+
+        let mut serials = vec![];
+        for device in configs {
+            let serial = device.serial.clone();
+
+            self.get_image_collection(&serial).write().unwrap().clear();
+            self.set_device_config(&serial, device);
+            self.save_device_config(&serial)?;
+
+            serials.push(serial);
+        }
+
+        if let Some(plugin_settings) = plugin_settings {
+            *self.plugin_settings.write().unwrap() = plugin_settings;
+            self.write_plugin_settings();
+        }
+
+        if let Some(mut settings) = settings {
+            settings.device_config_path = self.device_config_path.clone();
+            settings.plugin_path = self.plugin_path.clone();
+            settings.plugin_settings_path = self.plugin_settings_path.clone();
+            // Imported bundle shouldn't be able to expose the daemon on network or change who can control it
+            settings.http_address = self.http_address.clone();
+            settings.metrics_address = self.metrics_address.clone();
+            settings.allow_shutdown_requests = self.allow_shutdown_requests;
+            settings.socket_path = self.socket_path.clone();
+            settings.socket_owner = self.socket_owner;
+            settings.socket_group = self.socket_group;
+            settings.socket_permissions = self.socket_permissions;
+            settings.socket_token = self.socket_token.clone();
+            settings.read_only_token = self.read_only_token.clone();
+            settings.http_read_only = self.http_read_only;
+
+            let content = toml::to_string(&settings).map_err(|err| ConfigError::InvalidBundle(err.to_string()))?;
+            write_with_backups(Path::new(CONFIG_FILE_PATH), &content, self.config_backup_count())?;
+        }
+
+        Ok(serials)
+    }
+
     /// Retrieves device config for specified serial
     pub fn get_device_config(&self, serial: &str) -> Option<UniqueDeviceConfig> {
         self.loaded_configs.read().unwrap().get(serial).cloned()
@@ -245,38 +952,47 @@ impl Config {
 
     /// Disables a device config, so it will not be loaded by default
     pub fn disable_device_config(&self, serial: &str) -> bool {
-        let path = self.device_config_path();
-
-        let mut initial_path = path.clone();
-        initial_path.push(format!("{}.json", serial));
+        if let Some((initial_path, _)) = self.find_device_config_file(serial) {
+            let mut new_name = initial_path.file_name().unwrap_or_default().to_os_string();
+            new_name.push("_disabled");
 
-        let mut new_path = path.clone();
-        new_path.push(format!("{}.json_disabled", serial));
-
-        fs::rename(initial_path, new_path).is_ok()
+            fs::rename(&initial_path, initial_path.with_file_name(new_name)).is_ok()
+        } else {
+            false
+        }
     }
 
     /// Restores device config if it exists
     pub fn restore_device_config(&self, serial: &str) -> bool {
         let path = self.device_config_path();
 
-        let mut initial_path = path.clone();
-        initial_path.push(format!("{}.json_disabled", serial));
+        DeviceConfigFormat::ALL.iter()
+            .flat_map(|format| format.extensions().iter())
+            .map(|extension| path.join(format!("{}.{}", serial, extension)))
+            .any(|new_path| {
+                let mut initial_name = new_path.file_name().unwrap_or_default().to_os_string();
+                initial_name.push("_disabled");
 
-        let mut new_path = path.clone();
-        new_path.push(format!("{}.json", serial));
+                fs::rename(new_path.with_file_name(initial_name), &new_path).is_ok()
+            })
+    }
 
-        fs::rename(initial_path, new_path).is_ok()
+    /// Conversion that is applied to images added into image collections
+    pub fn image_conversion(&self) -> ImageConversion {
+        let settings: CoreSettings = self.get_plugin_settings().unwrap_or_default();
+        settings.renderer.image_conversion
     }
 
     /// Adds base64 image to device config image collection
     pub fn add_image(&self, serial: &str, image: String) -> Option<String> {
         if let Some(config) = self.get_device_config(serial) {
             let mut config_handle = config.write().unwrap();
-            let identifier = hash_str(&image);
 
-            if let Ok(image) = SDImage::from_base64(&image, config_handle.kind().image_size()) {
-                config_handle.images.insert(identifier.clone(), image.into());
+            if let Ok(image) = SDImage::from_base64_converted(&image, config_handle.kind().image_size(), self.image_conversion()) {
+                // Keyed by content, so uploading same image again doesn't duplicate it
+                let image: SDSerializedImage = image.into();
+                let identifier = hash_image(&image);
+                config_handle.images.insert(identifier.clone(), image);
                 drop(config_handle);
 
                 self.update_collection(&config);
@@ -293,7 +1009,7 @@ impl Config {
     pub fn add_image_encode(&self, serial: &str, image: DynamicImage) -> Option<String> {
         if let Some(config) = self.get_device_config(serial) {
             let mut config_handle = config.write().unwrap();
-            let serialized_image = SDImage::SingleImage(resize_for_streamdeck(config_handle.kind().image_size(), image)).into();
+            let serialized_image = SDImage::SingleImage(self.image_conversion().apply(config_handle.kind().image_size(), image)).into();
             let identifier = hash_image(&serialized_image);
             config_handle.images.insert(identifier.clone(), serialized_image);
             drop(config_handle);
@@ -305,6 +1021,41 @@ impl Config {
         None
     }
 
+    /// Adds images taken from another device config, keeping their identifiers. Images are resized for the device
+    pub fn add_serialized_images(&self, serial: &str, images: HashMap<String, SDSerializedImage>) -> bool {
+        if let Some(config) = self.get_device_config(serial) {
+            let mut config_handle = config.write().unwrap();
+            let size = config_handle.kind().image_size();
+            let conversion = self.image_conversion();
+
+            for (identifier, image) in images {
+                if config_handle.images.contains_key(&identifier) {
+                    continue;
+                }
+
+                let image = match image {
+                    SDSerializedImage::SingleImage(blob) => match SDImage::from_base64_converted(&blob, size, conversion) {
+                        Ok(image) => image.into(),
+                        Err(_) => continue,
+                    },
+                    animated => match SDImage::try_from(&animated) {
+                        Ok(image) if !image.has_size(size) => image.converted(size, conversion).into(),
+                        _ => animated,
+                    },
+                };
+
+                config_handle.images.insert(identifier, image);
+            }
+
+            drop(config_handle);
+
+            self.update_collection(&config);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Gets images from device config
     pub fn get_images(&self, serial: &str) -> Option<HashMap<String, SDSerializedImage>> {
         if let Some(config) = self.get_device_config(serial) {
@@ -356,11 +1107,18 @@ impl Config {
 
         if let Some(collection) = handle.get_mut(&device_config.serial) {
             let mut collection_handle = collection.write().unwrap();
+            let size = device_config.kind().image_size();
 
-            // Adding missing images from device config
+            // Adding missing images from device config, images are decoded and scaled for the device once here, so rendering doesn't have to
             for (key, image) in &device_config.images {
                 if !collection_handle.contains_key(key) {
-                    if let Ok(image) = image.try_into() {
+                    if let Ok(image) = SDImage::try_from(image) {
+                        let image = if image.has_size(size) {
+                            image
+                        } else {
+                            image.converted(size, self.image_conversion())
+                        };
+
                         collection_handle.insert(key.to_string(), image);
                     }
                 }
@@ -386,6 +1144,70 @@ impl Config {
     }
 }
 
+/// Path of milestone file, fails if name could escape milestone folder
+fn milestone_file(folder: &Path, name: &str) -> Result<PathBuf, ConfigError> {
+    if name.trim().is_empty() || name.contains(|c: char| matches!(c, '/' | '\\' | ':' | '\0')) || name.starts_with('.') {
+        return Err(ConfigError::InvalidMilestoneName);
+    }
+
+    Ok(folder.join(format!("{}.json", name)))
+}
+
+/// Adds file with specified content to archive
+fn append_bundle_file<W: Write>(builder: &mut tar::Builder<W>, path: &str, content: &[u8]) -> std::io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_secs()).unwrap_or(0));
+    header.set_cksum();
+
+    builder.append_data(&mut header, path, content)
+}
+
+/// Path of numbered backup for a file, eg. "serial.json.bak.1"
+fn backup_path(path: &Path, backup: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".bak.{}", backup));
+    path.with_file_name(name)
+}
+
+/// Shifts existing backups by one, dropping the oldest, and copies current file into first backup
+fn rotate_backups(path: &Path, count: usize) -> std::io::Result<()> {
+    if count == 0 || !path.is_file() {
+        return Ok(());
+    }
+
+    for index in (1..count).rev() {
+        let from = backup_path(path, index);
+
+        if from.is_file() {
+            fs::rename(from, backup_path(path, index + 1))?;
+        }
+    }
+
+    fs::copy(path, backup_path(path, 1))?;
+
+    Ok(())
+}
+
+/// Writes file into temporary file and then renames it over the original, so file is never left half-written.
+/// Keeps specified amount of previous versions as rotated backups
+fn write_with_backups(path: &Path, content: &str, backups: usize) -> std::io::Result<()> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let mut file = fs::File::create(&temp_path)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+
+    rotate_backups(path, backups)?;
+    fs::rename(temp_path, path)?;
+
+    Ok(())
+}
+
 /// Plugin Config trait for serialization and deserialization methods
 pub trait PluginConfig {
     const NAME: &'static str;
@@ -396,7 +1218,21 @@ pub trait PluginConfig {
 pub enum ConfigError {
     IoError(std::io::Error),
     ParseError(serde_json::Error),
-    DeviceNotFound
+    DeviceNotFound,
+    /// Config was made by newer version of Streamduck
+    UnsupportedVersion(u32),
+    /// Migration step failed to upgrade config
+    MigrationFailed(u32, String),
+    /// Requested config backup doesn't exist
+    BackupNotFound,
+    /// Milestone name is empty or contains characters that aren't allowed in file names
+    InvalidMilestoneName,
+    /// Requested milestone doesn't exist
+    MilestoneNotFound,
+    /// Config bundle is missing required files or contains invalid settings
+    InvalidBundle(String),
+    /// Device config couldn't be converted from or into TOML or YAML
+    FormatError(String),
 }
 
 impl From<std::io::Error> for ConfigError {
@@ -411,16 +1247,231 @@ impl From<serde_json::Error> for ConfigError {
     }
 }
 
+/// File format that device configs are stored in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeviceConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl Default for DeviceConfigFormat {
+    fn default() -> Self {
+        DeviceConfigFormat::Json
+    }
+}
+
+impl DeviceConfigFormat {
+    pub const ALL: [DeviceConfigFormat; 3] = [DeviceConfigFormat::Json, DeviceConfigFormat::Toml, DeviceConfigFormat::Yaml];
+
+    /// Extension used when saving configs
+    pub fn extension(&self) -> &'static str {
+        self.extensions()[0]
+    }
+
+    /// All extensions recognized for the format
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            DeviceConfigFormat::Json => &["json"],
+            DeviceConfigFormat::Toml => &["toml"],
+            DeviceConfigFormat::Yaml => &["yaml", "yml"],
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<DeviceConfigFormat> {
+        DeviceConfigFormat::ALL.into_iter().find(|x| x.extensions().contains(&extension))
+    }
+
+    /// Serializes device config, keys are sorted so files stay stable between saves and diff nicely
+    pub fn serialize(&self, config: &DeviceConfig) -> Result<String, ConfigError> {
+        let mut value = sort_keys(serde_json::to_value(config)?);
+
+        match self {
+            DeviceConfigFormat::Json => Ok(serde_json::to_string(&value)?),
+            DeviceConfigFormat::Toml => {
+                // TOML has no null, missing values are treated the same way by deserialization
+                strip_nulls(&mut value);
+                let value = toml::Value::try_from(value).map_err(|err| ConfigError::FormatError(err.to_string()))?;
+                toml::to_string_pretty(&value).map_err(|err| ConfigError::FormatError(err.to_string()))
+            }
+            DeviceConfigFormat::Yaml => serde_yaml::to_string(&value).map_err(|err| ConfigError::FormatError(err.to_string())),
+        }
+    }
+
+    /// Parses device config, migrating it to current version if needed
+    pub fn deserialize(&self, content: &str) -> Result<DeviceConfig, ConfigError> {
+        let value: Value = match self {
+            DeviceConfigFormat::Json => serde_json::from_str(content)?,
+            DeviceConfigFormat::Toml => toml::from_str(content).map_err(|err| ConfigError::FormatError(err.to_string()))?,
+            DeviceConfigFormat::Yaml => serde_yaml::from_str(content).map_err(|err| ConfigError::FormatError(err.to_string()))?,
+        };
+
+        migrate_device_config(value)
+    }
+}
+
+/// Rebuilds objects with keys in alphabetical order
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<(String, Value)> = object.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(entries.into_iter().map(|(key, value)| (key, sort_keys(value))).collect())
+        }
+        Value::Array(array) => Value::Array(array.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+/// Removes null fields from objects
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            object.retain(|_, value| !value.is_null());
+            object.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(array) => array.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+/// Current schema version of device configs
+pub const DEVICE_CONFIG_VERSION: u32 = 2;
+
+/// Migration step that upgrades raw device config by one version
+pub type DeviceConfigMigration = fn(&mut Value) -> Result<(), String>;
+
+/// Registry of migrations, migration at index N upgrades config from version N to N + 1
+pub const DEVICE_CONFIG_MIGRATIONS: [DeviceConfigMigration; DEVICE_CONFIG_VERSION as usize] = [
+    migrate_v0_to_v1,
+    migrate_v1_to_v2,
+];
+
+/// Version 0 configs could be missing images and plugin data
+fn migrate_v0_to_v1(config: &mut Value) -> Result<(), String> {
+    let object = config.as_object_mut().ok_or("config is not an object")?;
+
+    for key in ["images", "plugin_data"] {
+        if !object.get(key).map_or(false, |x| x.is_object()) {
+            object.insert(key.to_string(), Value::Object(Default::default()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Version 2 configs keep images in image store, older configs have them inline and are loaded as is
+fn migrate_v1_to_v2(config: &mut Value) -> Result<(), String> {
+    config.as_object_mut().ok_or("config is not an object")?;
+    Ok(())
+}
+
+/// Upgrades raw device config to current version step by step and parses it
+pub fn migrate_device_config(mut config: Value) -> Result<DeviceConfig, ConfigError> {
+    let mut version = config.get("version").and_then(|x| x.as_u64()).unwrap_or(0) as u32;
+
+    if version > DEVICE_CONFIG_VERSION {
+        return Err(ConfigError::UnsupportedVersion(version));
+    }
+
+    if version < DEVICE_CONFIG_VERSION {
+        log::info!("Migrating device config from version {} to {}", version, DEVICE_CONFIG_VERSION);
+    }
+
+    while version < DEVICE_CONFIG_VERSION {
+        DEVICE_CONFIG_MIGRATIONS[version as usize](&mut config)
+            .map_err(|err| ConfigError::MigrationFailed(version, err))?;
+
+        version += 1;
+
+        if let Some(object) = config.as_object_mut() {
+            object.insert("version".to_string(), Value::from(version));
+        }
+    }
+
+    Ok(serde_json::from_value(config)?)
+}
+
+/// Labeled snapshot of configs that can be restored later
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConfigMilestone {
+    pub name: String,
+    /// Unix timestamp of when milestone was created
+    pub created: u64,
+}
+
+/// Contents of milestone file
+#[derive(Serialize, Deserialize)]
+struct ConfigSnapshot {
+    #[serde(flatten)]
+    milestone: ConfigMilestone,
+    devices: HashMap<String, DeviceConfig>,
+    plugin_settings: HashMap<String, Value>,
+}
+
+/// Result of image cleanup
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ImageCleanup {
+    /// Identifiers of images that were removed from device configs
+    pub removed: Vec<String>,
+    /// Amount of bytes freed in image store
+    pub reclaimed_bytes: u64,
+}
+
+/// Current format version of config bundles
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Description of config bundle, stored as "manifest.json" inside of the archive
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BundleManifest {
+    pub version: u32,
+    /// Unix timestamp of when bundle was exported
+    pub created: u64,
+    /// Serial numbers of devices in the bundle
+    pub devices: Vec<String>,
+}
+
 /// Device config struct
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct DeviceConfig {
+    /// Schema version of the config, configs without it are treated as version 0
+    #[serde(default)]
+    pub version: u32,
     pub vid: u16,
     pub pid: u16,
     pub serial: String,
     pub brightness: u8,
     pub layout: RawButtonPanel,
     pub images: HashMap<String, SDSerializedImage>,
+    /// Content hashes of images that are kept in image store, only used in config files
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub stored_images: HashMap<String, String>,
     pub plugin_data: HashMap<String, Value>,
+    /// Transition override for the device, global renderer setting is used if not set
+    #[serde(default)]
+    pub transition: Option<PanelTransition>,
+    /// Serial number of the device that this device mirrors, see [crate::core::mirror]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirror_of: Option<String>,
+    /// Rotation of the device, for devices that are mounted sideways or upside down
+    #[serde(default)]
+    pub orientation: DeviceOrientation,
+    /// Key layout is mirrored horizontally, for left-handed use
+    #[serde(default)]
+    pub mirrored: bool,
+    /// Images get flipped horizontally when key layout is mirrored
+    #[serde(default)]
+    pub flip_images: bool,
+    /// Folder IDs of panels that were open on the device, only kept if daemon config has `persist_stack` enabled
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stack_path: Vec<String>,
+    /// Folder ID of folder that gets opened once device connects, saved stack takes priority if there's one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub startup_folder: Option<String>,
+    /// Buttons that get triggered after device connects and renders first frame, for things like setting lights
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub startup_actions: Vec<Button>,
 }
 
 impl DeviceConfig {
@@ -435,4 +1486,30 @@ impl DeviceConfig {
             _ => Kind::Original,
         }
     }
+
+    /// Identifiers of images from the collection that are mentioned anywhere in layout or plugin data
+    pub fn referenced_images(&self) -> HashSet<String> {
+        fn collect(value: &Value, images: &HashMap<String, SDSerializedImage>, found: &mut HashSet<String>) {
+            match value {
+                Value::String(str) if images.contains_key(str) => {
+                    found.insert(str.clone());
+                }
+                Value::Array(array) => array.iter().for_each(|x| collect(x, images, found)),
+                Value::Object(object) => object.values().for_each(|x| collect(x, images, found)),
+                _ => {}
+            }
+        }
+
+        let mut found = HashSet::new();
+
+        if let Ok(layout) = serde_json::to_value(&self.layout) {
+            collect(&layout, &self.images, &mut found);
+        }
+
+        for data in self.plugin_data.values() {
+            collect(data, &self.images, &mut found);
+        }
+
+        found
+    }
 }
\ No newline at end of file
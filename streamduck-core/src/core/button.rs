@@ -6,7 +6,7 @@ use serde_json::{Error, Value};
 use crate::core::UniqueButton;
 
 /// Button definition, it's simply a hashmap, but is used to represent all the components of the button
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 pub struct Button(pub HashMap<String, Value>);
 
 impl Button {
@@ -0,0 +1,158 @@
+//! Logical devices made of several physical devices
+//!
+//! Composite device is a virtual core with a grid big enough to fit all of its members at their placement.
+//! Members show their part of composite device's current screen, and presses on members are dispatched to composite device.
+//! Composite devices are defined in daemon config and added with [CoreManager::add_composite_device]
+
+use serde::{Deserialize, Serialize};
+use streamdeck::Kind;
+use crate::adapt::key_columns;
+use crate::config::CompositeDevice;
+use crate::core::UniqueButtonMap;
+use crate::core::manager::CoreManager;
+use crate::core::methods::{CoreHandle, get_visible_buttons};
+
+/// Reasons why composite device couldn't be added
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum CompositeError {
+    /// Composite device doesn't have any members
+    NoMembers,
+    /// Member device doesn't have a device config, so its kind is unknown
+    UnknownMember(String),
+    /// Member device is already a part of another composite device
+    MemberInUse(String),
+    /// Serial number of composite device is already used by another device
+    SerialInUse,
+    /// Composite grid has more keys than a single panel can have
+    TooManyKeys,
+}
+
+/// Physical device placed on grid of composite device
+#[derive(Debug, Clone)]
+pub struct PlacedMember {
+    pub serial: String,
+    pub kind: Kind,
+    pub column: u8,
+    pub row: u8,
+}
+
+impl PlacedMember {
+    fn columns(&self) -> u8 {
+        key_columns(self.kind)
+    }
+
+    fn rows(&self) -> u8 {
+        self.kind.keys() / self.columns()
+    }
+}
+
+/// Grid of composite device
+#[derive(Debug, Clone)]
+pub struct CompositeLayout {
+    pub columns: u8,
+    pub rows: u8,
+    pub members: Vec<PlacedMember>,
+}
+
+impl CompositeLayout {
+    /// Makes layout with grid that fits all of the members
+    pub fn new(members: Vec<PlacedMember>) -> Result<CompositeLayout, CompositeError> {
+        if members.is_empty() {
+            return Err(CompositeError::NoMembers);
+        }
+
+        let columns = members.iter().map(|x| x.column as u16 + x.columns() as u16).max().unwrap_or(0);
+        let rows = members.iter().map(|x| x.row as u16 + x.rows() as u16).max().unwrap_or(0);
+
+        if columns * rows > u8::MAX as u16 {
+            return Err(CompositeError::TooManyKeys);
+        }
+
+        Ok(CompositeLayout {
+            columns: columns as u8,
+            rows: rows as u8,
+            members
+        })
+    }
+
+    /// Amount of keys in composite grid
+    pub fn key_count(&self) -> u8 {
+        self.columns * self.rows
+    }
+
+    /// Checks if device is a part of the composite device
+    pub fn contains(&self, serial: &str) -> bool {
+        self.members.iter().any(|x| x.serial == serial)
+    }
+
+    /// Converts key of member device into key of composite device
+    pub fn member_to_composite(&self, serial: &str, key: u8) -> Option<u8> {
+        let member = self.members.iter().find(|x| x.serial == serial)?;
+
+        if key >= member.kind.keys() {
+            return None;
+        }
+
+        let row = member.row + key / member.columns();
+        let column = member.column + key % member.columns();
+
+        Some(row * self.columns + column)
+    }
+
+    /// Converts key of composite device into key of member device, [None] if member doesn't cover the key
+    pub fn composite_to_member(&self, serial: &str, key: u8) -> Option<u8> {
+        let member = self.members.iter().find(|x| x.serial == serial)?;
+
+        let row = (key / self.columns).checked_sub(member.row)?;
+        let column = (key % self.columns).checked_sub(member.column)?;
+
+        if row < member.rows() && column < member.columns() {
+            Some(row * member.columns() + column)
+        } else {
+            None
+        }
+    }
+}
+
+/// Places members of composite device definition using kinds from their device configs
+pub(crate) fn layout_from_definition(manager: &CoreManager, definition: &CompositeDevice) -> Result<CompositeLayout, CompositeError> {
+    let mut members = vec![];
+
+    for member in &definition.members {
+        let config = manager.config.get_device_config(&member.serial)
+            .ok_or_else(|| CompositeError::UnknownMember(member.serial.clone()))?;
+
+        let kind = config.read().unwrap().kind();
+
+        members.push(PlacedMember {
+            serial: member.serial.clone(),
+            kind,
+            column: member.column,
+            row: member.row
+        });
+    }
+
+    CompositeLayout::new(members)
+}
+
+/// Buttons of composite device's current screen that are on keys of specified member, [None] if core isn't a part of composite device
+pub fn composite_visible_buttons(core: &CoreHandle) -> Option<UniqueButtonMap> {
+    let manager = core.core.module_manager.core_manager()?;
+    let (composite, layout) = manager.composite_of(&core.core.serial_number)?;
+    let buttons = get_visible_buttons(&CoreHandle::wrap(composite))?;
+
+    Some(
+        buttons.into_iter()
+            .filter_map(|(key, button)| Some((layout.composite_to_member(&core.core.serial_number, key)?, button)))
+            .collect()
+    )
+}
+
+/// Composite device core and key that press on specified member key should go to, [None] if core isn't a part of composite device
+pub fn composite_press_destination(core: &CoreHandle, key: u8) -> Option<(CoreHandle, u8)> {
+    let manager = core.core.module_manager.core_manager()?;
+    let (composite, layout) = manager.composite_of(&core.core.serial_number)?;
+    let key = layout.member_to_composite(&core.core.serial_number, key)?;
+
+    Some((CoreHandle::wrap(composite), key))
+}
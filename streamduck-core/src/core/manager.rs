@@ -6,17 +6,31 @@ use std::thread::{sleep, spawn};
 use std::time::Duration;
 use crate::core::{RawButtonPanel, SDCore};
 use crate::core::methods::{CoreHandle, reset_stack, set_brightness};
+use crate::core::startup::{open_startup_panels, run_startup_actions};
+use crate::core::composite::{CompositeError, CompositeLayout, layout_from_definition};
+use crate::core::mirror::{check_mirror, MirrorError};
+use crate::thread::DeviceThreadCommunication;
 use hidapi::HidApi;
 use serde_json::Value;
-use crate::config::{Config, DeviceConfig};
+use crate::config::{CompositeDevice, Config, DEVICE_CONFIG_VERSION, DeviceConfig, UniqueDeviceConfig};
+use streamdeck::{Kind, pids};
 use crate::{connect, find_decks, ModuleManager, RenderingManager, SocketManager};
 use crate::util::{make_panel_unique};
+use crate::modules::schedules::start_schedules;
+use crate::modules::scheduler::schedule_task;
+
+/// Vendor ID of Elgato devices
+pub const ELGATO_VID: u16 = 0x0fd9;
 
 /// Core manager struct
 pub struct CoreManager {
     hid: RwLock<HidApi>,
     pub config: Arc<Config>,
     devices: RwLock<HashMap<String, DeviceData>>,
+    /// Serial numbers of mirroring devices mapped to serial numbers of devices they mirror
+    mirrors: RwLock<HashMap<String, String>>,
+    /// Layouts of composite devices by their serial numbers
+    composites: RwLock<HashMap<String, CompositeLayout>>,
     pub module_manager: Arc<ModuleManager>,
     pub render_manager: Arc<RenderingManager>,
     pub socket_manager: Arc<SocketManager>,
@@ -28,22 +42,42 @@ impl CoreManager {
     pub fn new(module_manager: Arc<ModuleManager>, render_manager: Arc<RenderingManager>, socket_manager: Arc<SocketManager>, config: Arc<Config>) -> Arc<CoreManager> {
         let hid = HidApi::new().expect("could not connect to hidapi");
 
-        Arc::new(CoreManager {
+        let core_manager = Arc::new(CoreManager {
             hid: RwLock::new(hid),
             config,
             devices: Default::default(),
+            mirrors: Default::default(),
+            composites: Default::default(),
             module_manager,
             render_manager,
             socket_manager
-        })
+        });
+
+        core_manager.module_manager.set_core_manager(&core_manager);
+
+        core_manager
     }
 
     /// Adds all devices from config to managed devices, used at start of the software
     pub fn add_devices_from_config(&self) {
+        let composite_devices = self.config.composite_devices();
+
         for config in self.config.get_all_device_configs() {
             let config_handle = config.read().unwrap();
+
+            // Composite devices aren't physical devices, so they shouldn't be reconnected to
+            if composite_devices.iter().any(|x| x.serial == config_handle.serial) {
+                continue;
+            }
+
             self.add_device(config_handle.vid, config_handle.pid, &config_handle.serial);
         }
+
+        for composite_device in composite_devices {
+            if let Err(err) = self.add_composite_device(&composite_device) {
+                log::error!("Failed to add composite device {}: {:?}", composite_device.serial, err);
+            }
+        }
     }
 
     /// Lists detected unmanaged devices
@@ -73,19 +107,23 @@ impl CoreManager {
 
             self.config.restore_device_config(serial);
 
+            if let Some(config) = self.config.get_device_config(serial) {
+                if let Some(source) = config.read().unwrap().mirror_of.clone() {
+                    self.mirrors.write().unwrap().insert(serial.to_string(), source);
+                }
+            }
+
             handle.insert(serial.to_string(), data.clone());
         }
     }
 
-    /// Connects to a device
-    pub fn connect_device(&self, vid: u16, pid: u16, serial: &str) -> Result<DeviceData, String> {
-        let hid_handle = self.hid.read().unwrap();
-        let collection = self.config.get_image_collection(serial);
-
-        let config = if let Some(config) = self.config.get_device_config(serial) {
+    /// Gets device config for serial, creates and saves a default one if it doesn't exist yet
+    fn get_or_create_device_config(&self, vid: u16, pid: u16, serial: &str) -> UniqueDeviceConfig {
+        if let Some(config) = self.config.get_device_config(serial) {
             config
         } else {
             self.config.set_device_config(serial, DeviceConfig {
+                version: DEVICE_CONFIG_VERSION,
                 vid,
                 pid,
                 serial: serial.to_string(),
@@ -96,11 +134,28 @@ impl CoreManager {
                     buttons: Default::default()
                 },
                 images: Default::default(),
-                plugin_data: Default::default()
+                stored_images: Default::default(),
+                plugin_data: Default::default(),
+                transition: None,
+                mirror_of: None,
+                orientation: Default::default(),
+                mirrored: false,
+                flip_images: false,
+                stack_path: vec![],
+                startup_folder: None,
+                startup_actions: vec![]
             });
             self.config.save_device_config(serial).ok();
             self.config.get_device_config(serial).unwrap()
-        };
+        }
+    }
+
+    /// Connects to a device
+    pub fn connect_device(&self, vid: u16, pid: u16, serial: &str) -> Result<DeviceData, String> {
+        let hid_handle = self.hid.read().unwrap();
+        let collection = self.config.get_image_collection(serial);
+
+        let config = self.get_or_create_device_config(vid, pid, serial);
 
         if let Ok((core, handler)) = connect(self.module_manager.clone(), self.render_manager.clone(), self.socket_manager.clone(), self.config.clone(), config.clone(), collection,&hid_handle, vid, pid, serial, self.config.pool_rate()) {
             spawn(move || {
@@ -121,11 +176,14 @@ impl CoreManager {
 
             let brightness = config_handle.brightness;
             let layout = config_handle.layout.clone();
+            let stack_path = config_handle.stack_path.clone();
 
             drop(config_handle);
 
             set_brightness(&core_handle, brightness);
             reset_stack(&core_handle, make_panel_unique(layout));
+            open_startup_panels(&core_handle, &stack_path);
+            start_schedules(&core_handle);
 
 
             let mut handle = self.devices.write().unwrap();
@@ -138,12 +196,134 @@ impl CoreManager {
         }
     }
 
+    /// Adds a virtual device that isn't backed by any hardware, for testing purposes
+    pub fn add_virtual_device(&self, kind: Kind, serial: &str) -> DeviceData {
+        let pid = match kind {
+            Kind::Original => pids::ORIGINAL,
+            Kind::OriginalV2 => pids::ORIGINAL_V2,
+            Kind::Mini => pids::MINI,
+            Kind::Xl => pids::XL,
+            Kind::Mk2 => pids::MK2,
+        };
+
+        let config = self.get_or_create_device_config(ELGATO_VID, pid, serial);
+        let collection = self.config.get_image_collection(serial);
+
+        let core = SDCore::new_virtual(self.module_manager.clone(), self.render_manager.clone(), self.socket_manager.clone(), self.config.clone(), config.clone(), collection, kind);
+
+        let data = DeviceData {
+            core: core.clone(),
+            vid: ELGATO_VID,
+            pid,
+            serial: serial.to_string()
+        };
+
+        let core_handle = CoreHandle::wrap(core);
+        let (layout, stack_path) = { let handle = config.read().unwrap(); (handle.layout.clone(), handle.stack_path.clone()) };
+        reset_stack(&core_handle, make_panel_unique(layout));
+        open_startup_panels(&core_handle, &stack_path);
+        start_schedules(&core_handle);
+
+        // Virtual devices don't render anything themselves, so startup actions run right away
+        schedule_task(&core_handle, Duration::ZERO, |core| run_startup_actions(&core));
+
+        self.devices.write().unwrap().insert(serial.to_string(), data.clone());
+
+        data
+    }
+
+    /// Adds composite device that combines physical devices into one grid, member devices should already have device configs
+    pub fn add_composite_device(&self, definition: &CompositeDevice) -> Result<DeviceData, CompositeError> {
+        if self.devices.read().unwrap().contains_key(&definition.serial) {
+            return Err(CompositeError::SerialInUse);
+        }
+
+        let composites = self.list_composites();
+
+        for member in &definition.members {
+            if composites.values().any(|x| x.contains(&member.serial)) {
+                return Err(CompositeError::MemberInUse(member.serial.clone()));
+            }
+        }
+
+        let layout = layout_from_definition(self, definition)?;
+
+        // Composite device uses identifiers of its first member, so images and rendering match what members expect, its grid comes from the layout
+        let first = &layout.members[0];
+        let (vid, pid) = self.config.get_device_config(&first.serial)
+            .map(|x| { let handle = x.read().unwrap(); (handle.vid, handle.pid) })
+            .unwrap_or((ELGATO_VID, pids::ORIGINAL));
+        let kind = first.kind;
+
+        let config = self.get_or_create_device_config(vid, pid, &definition.serial);
+        let collection = self.config.get_image_collection(&definition.serial);
+
+        let core = SDCore::new_virtual_with_grid(self.module_manager.clone(), self.render_manager.clone(), self.socket_manager.clone(), self.config.clone(), config.clone(), collection, kind, layout.columns, layout.rows);
+
+        let data = DeviceData {
+            core: core.clone(),
+            vid,
+            pid,
+            serial: definition.serial.clone()
+        };
+
+        self.composites.write().unwrap().insert(definition.serial.clone(), layout);
+
+        let core_handle = CoreHandle::wrap(core);
+        let (layout, stack_path) = { let handle = config.read().unwrap(); (handle.layout.clone(), handle.stack_path.clone()) };
+        reset_stack(&core_handle, make_panel_unique(layout));
+        open_startup_panels(&core_handle, &stack_path);
+        start_schedules(&core_handle);
+
+        // Virtual devices don't render anything themselves, so startup actions run right away
+        schedule_task(&core_handle, Duration::ZERO, |core| run_startup_actions(&core));
+
+        self.devices.write().unwrap().insert(definition.serial.clone(), data.clone());
+
+        Ok(data)
+    }
+
+    /// Lists layouts of composite devices by their serial numbers
+    pub fn list_composites(&self) -> HashMap<String, CompositeLayout> {
+        self.composites.read().unwrap().clone()
+    }
+
+    /// Gets core and layout of composite device that specified device is a part of
+    pub fn composite_of(&self, member: &str) -> Option<(Arc<SDCore>, CompositeLayout)> {
+        let (serial, layout) = self.composites.read().unwrap().iter()
+            .find(|(_, layout)| layout.contains(member))
+            .map(|(serial, layout)| (serial.clone(), layout.clone()))?;
+
+        Some((self.get_device(&serial)?.core, layout))
+    }
+
+    /// Gets cores of connected devices that make up specified composite device
+    pub fn composite_members(&self, serial: &str) -> Vec<Arc<SDCore>> {
+        let members: Vec<String> = self.composites.read().unwrap().get(serial)
+            .map(|x| x.members.iter().map(|x| x.serial.clone()).collect())
+            .unwrap_or_default();
+
+        members.into_iter()
+            .filter_map(|x| self.get_device(&x))
+            .map(|x| x.core)
+            .collect()
+    }
+
     /// Removes device from automatic reconnection and stops current connection to it
     pub fn remove_device(&self, serial: &str) {
         let mut handle = self.devices.write().unwrap();
         let data = handle.remove(serial);
 
         if let Some(data) = data {
+            self.mirrors.write().unwrap().remove(serial);
+
+            if self.composites.write().unwrap().remove(serial).is_some() {
+                // Members of removed composite device should show their own screens again
+                for member in handle.values() {
+                    member.core.send_commands(vec![DeviceThreadCommunication::RefreshScreen]);
+                }
+            }
+
             data.core.close();
             self.config.disable_device_config(serial);
             self.config.reload_device_configs().ok();
@@ -170,6 +350,63 @@ impl CoreManager {
         }
     }
 
+    /// Makes target device show and control current screen of source device, stops mirroring if source is [None]
+    pub fn set_mirror(&self, target: &str, source: Option<&str>) -> Result<(), MirrorError> {
+        if let Some(source) = source {
+            check_mirror(self, target, source)?;
+        } else if !self.devices.read().unwrap().contains_key(target) {
+            return Err(MirrorError::DeviceNotFound);
+        }
+
+        let mut mirrors = self.mirrors.write().unwrap();
+
+        if let Some(source) = source {
+            mirrors.insert(target.to_string(), source.to_string());
+        } else {
+            mirrors.remove(target);
+        }
+
+        drop(mirrors);
+
+        if let Some(config) = self.config.get_device_config(target) {
+            config.write().unwrap().mirror_of = source.map(|x| x.to_string());
+
+            if let Err(err) = self.config.save_device_config(target) {
+                log::error!("Failed to save mirror of {}: {:?}", target, err);
+            }
+        }
+
+        if let Some(device) = self.get_device(target) {
+            device.core.send_commands(vec![DeviceThreadCommunication::RefreshScreen]);
+        }
+
+        Ok(())
+    }
+
+    /// Lists serial numbers of mirroring devices mapped to serial numbers of devices they mirror
+    pub fn list_mirrors(&self) -> HashMap<String, String> {
+        self.mirrors.read().unwrap().clone()
+    }
+
+    /// Gets core of the device that specified device mirrors, [None] if device isn't mirroring or source isn't connected
+    pub fn mirror_source(&self, serial: &str) -> Option<Arc<SDCore>> {
+        let source = self.mirrors.read().unwrap().get(serial).cloned()?;
+        Some(self.get_device(&source)?.core)
+    }
+
+    /// Gets cores of connected devices that mirror specified device
+    pub fn mirror_targets(&self, serial: &str) -> Vec<Arc<SDCore>> {
+        let targets: Vec<String> = self.mirrors.read().unwrap().iter()
+            .filter(|(_, source)| *source == serial)
+            .map(|(target, _)| target.clone())
+            .collect();
+
+        targets.into_iter()
+            .filter_map(|x| self.get_device(&x))
+            .map(|x| x.core)
+            .collect()
+    }
+
     /// Starts running reconnection routine on current thread, probably spawn it out as a separate thread
     pub fn reconnect_routine(&self) {
         loop {
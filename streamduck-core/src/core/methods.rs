@@ -1,21 +1,28 @@
 use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
-use std::sync::{Arc, LockResult, MutexGuard};
-use image::{DynamicImage, Rgba};
+use std::sync::{Arc, LockResult, MutexGuard, RwLock};
+use image::{DynamicImage, GenericImageView, Rgba};
+use image::imageops::FilterType;
 use rusttype::Scale;
 use serde_json::{Map, Value};
-use crate::core::{ButtonPanel, UniqueButton};
+use crate::core::{ButtonPanel, Overlay, UniqueButton, UniqueButtonMap};
 use crate::{Config, ModuleManager, SDCore, SocketManager};
+use crate::config::DeviceConfig;
 use crate::util::{add_array_function, button_to_raw, change_from_path, convert_value_to_path, deserialize_panel, make_button_unique, panel_to_raw, remove_array_function, serialize_panel, set_value_function};
 use serde::de::Error as DeError;
 use serde_json::Error as JSONError;
 use crate::core::button::{Button, parse_unique_button_to_component};
 use crate::font::get_font_from_collection;
-use crate::modules::events::{core_event_to_global, SDCoreEvent};
-use crate::modules::{features_to_vec, send_core_event_to_modules, UniqueSDModule};
-use crate::modules::components::{UIPathValue, UIValue};
+use crate::modules::events::{core_event_to_global, SDCoreEvent, SDGlobalEvent};
+use crate::modules::{features_to_vec, send_core_event_to_modules, send_global_event_to_modules, UniqueSDModule};
+use crate::modules::components::{FieldError, UIPathValue, UIValue};
+use crate::modules::conditions::{button_condition_state, ConditionState};
+use crate::modules::carousel::advance_carousel;
+use crate::modules::radio::select_radio_button;
+use crate::modules::states::{advance_button_state, StateComponent};
 use crate::socket::send_event_to_socket;
 use crate::thread::DeviceThreadCommunication;
+use crate::thread::transition::{get_panel_transition, PanelTransition, TransitionDirection};
 use crate::thread::rendering::{draw_background, draw_foreground, draw_missing_texture, RendererComponent};
 use crate::thread::util::{image_from_solid, render_aligned_text_on_image, TextAlignment};
 use crate::versions::SUPPORTED_FEATURES;
@@ -93,6 +100,18 @@ impl CoreHandle {
         self.core.module_manager.clone()
     }
 
+    /// Returns handle to core of another managed device, [None] if device isn't connected
+    pub fn device_core(&self, serial_number: &str) -> Option<CoreHandle> {
+        self.required_feature("core_manager");
+        let device = self.core.module_manager.core_manager()?.get_device(serial_number)?;
+
+        Some(CoreHandle {
+            core: device.core,
+            module_name: self.module_name.clone(),
+            module_features: self.module_features.clone()
+        })
+    }
+
     /// Returns socket manager reference
     pub fn socket_manager(&self) -> Arc<SocketManager> {
         self.required_feature("socket_api");
@@ -177,7 +196,48 @@ pub fn clear_button(core: &CoreHandle, key: u8) -> bool {
     }
 }
 
-/// Adds component onto a button, returns success boolean
+/// Moves button on current screen to another position, returns success boolean.
+/// Button that's already on destination is moved to source position if swap is true, otherwise moving fails
+pub fn move_button(core: &CoreHandle, from: u8, to: u8, swap: bool) -> bool {
+    core.required_feature("core_methods");
+    if from == to {
+        return false;
+    }
+
+    if let Some(screen) = get_current_screen(core) {
+        let mut handle = screen.write().unwrap();
+
+        if !handle.buttons.contains_key(&from) || (handle.buttons.contains_key(&to) && !swap) {
+            return false;
+        }
+
+        let moved_button = handle.buttons.remove(&from).unwrap();
+        let swapped_button = handle.buttons.remove(&to);
+
+        handle.buttons.insert(to, moved_button.clone());
+        if let Some(swapped_button) = &swapped_button {
+            handle.buttons.insert(from, swapped_button.clone());
+        }
+
+        drop(handle);
+
+        send_core_event_to_modules(core, SDCoreEvent::ButtonMoved {
+            from,
+            to,
+            panel: screen.clone(),
+            moved_button,
+            swapped_button
+        }, core.module_manager().get_module_list().into_iter());
+
+        core.core.mark_for_redraw();
+
+        true
+    } else {
+        false
+    }
+}
+
+/// Adds component onto a button, returns success boolean. Fails if component isn't compatible with components of the button, see [ModuleManager::check_component_addition](crate::modules::ModuleManager::check_component_addition)
 pub fn add_component(core: &CoreHandle, key: u8, component_name: &str) -> bool {
     core.required_feature("core_methods");
 
@@ -191,7 +251,13 @@ pub fn add_component(core: &CoreHandle, key: u8, component_name: &str) -> bool {
             let mut button_handle = button.write().unwrap();
             drop(handle);
 
-            if !button_handle.component_names().contains(&component_name.to_string()) {
+            let component_names = button_handle.component_names();
+
+            if !component_names.contains(&component_name.to_string()) {
+                if module_manager.check_component_addition(&component_names, component_name).is_err() {
+                    return false;
+                }
+
                 let components = module_manager.read_component_map();
 
                 if let Some((_, module)) = components.get(component_name) {
@@ -252,8 +318,17 @@ pub fn get_component_values_with_paths(core: &CoreHandle, key: u8, component_nam
     }
 }
 
-/// Sets component values based on changes for component on a button
-pub fn set_component_value(core: &CoreHandle, key: u8, component_name: &str, value: Vec<UIValue>) -> bool {
+/// Reasons for component values not being set
+#[derive(Debug)]
+pub enum ComponentValueError {
+    /// Button, component or value path wasn't found, or value didn't match type of the field
+    Failed,
+    /// Module rejected the values
+    InvalidValues(Vec<FieldError>),
+}
+
+/// Sets component values based on changes for component on a button, button is left unchanged if module rejects the values
+pub fn set_component_value(core: &CoreHandle, key: u8, component_name: &str, value: Vec<UIValue>) -> Result<(), ComponentValueError> {
     core.required_feature("core_methods");
 
     let module_manager = core.module_manager();
@@ -270,7 +345,11 @@ pub fn set_component_value(core: &CoreHandle, key: u8, component_name: &str, val
                 let components = module_manager.read_component_map();
 
                 if let Some((_, module)) = components.get(component_name) {
-                    module.set_component_value(core.clone_for(&module), button_handle.deref_mut(), component_name, value);
+                    if let Err(errors) = module.set_component_value(core.clone_for(&module), button_handle.deref_mut(), component_name, value) {
+                        *button_handle = previous.read().unwrap().clone();
+                        return Err(ComponentValueError::InvalidValues(errors));
+                    }
+
                     drop(button_handle);
                     drop(components);
 
@@ -283,17 +362,17 @@ pub fn set_component_value(core: &CoreHandle, key: u8, component_name: &str, val
 
                     core.core.mark_for_redraw();
 
-                    return true;
+                    return Ok(());
                 }
             }
         }
     }
 
-    false
+    Err(ComponentValueError::Failed)
 }
 
 /// Adds new array element to a component value
-pub fn add_element_component_value(core: &CoreHandle, key: u8, component_name: &str, path: &str) -> bool {
+pub fn add_element_component_value(core: &CoreHandle, key: u8, component_name: &str, path: &str) -> Result<(), ComponentValueError> {
     if let Some(values) = get_component_values(core, key, component_name) {
         let (changes, success) = change_from_path(path, values, &add_array_function(), false);
 
@@ -301,18 +380,18 @@ pub fn add_element_component_value(core: &CoreHandle, key: u8, component_name: &
             if !changes.is_empty() {
                 set_component_value(core, key, component_name, changes)
             } else {
-                false
+                Err(ComponentValueError::Failed)
             }
         } else {
-            false
+            Err(ComponentValueError::Failed)
         }
     } else {
-        false
+        Err(ComponentValueError::Failed)
     }
 }
 
 /// Removes element from array in component value
-pub fn remove_element_component_value(core: &CoreHandle, key: u8, component_name: &str, path: &str, index: usize) -> bool {
+pub fn remove_element_component_value(core: &CoreHandle, key: u8, component_name: &str, path: &str, index: usize) -> Result<(), ComponentValueError> {
     if let Some(values) = get_component_values(core, key, component_name) {
         let (changes, success) = change_from_path(path, values, &remove_array_function(index), false);
 
@@ -320,18 +399,18 @@ pub fn remove_element_component_value(core: &CoreHandle, key: u8, component_name
             if !changes.is_empty() {
                 set_component_value(core, key, component_name, changes)
             } else {
-                false
+                Err(ComponentValueError::Failed)
             }
         } else {
-            false
+            Err(ComponentValueError::Failed)
         }
     } else {
-        false
+        Err(ComponentValueError::Failed)
     }
 }
 
 /// Sets value based on path for component value
-pub fn set_component_value_by_path(core: &CoreHandle, key: u8, component_name: &str, value: UIPathValue) -> bool {
+pub fn set_component_value_by_path(core: &CoreHandle, key: u8, component_name: &str, value: UIPathValue) -> Result<(), ComponentValueError> {
     if let Some(values) = get_component_values(core, key, component_name) {
         let (changes, success) = change_from_path(&value.path, values, &set_value_function(value.clone()), false);
 
@@ -339,17 +418,17 @@ pub fn set_component_value_by_path(core: &CoreHandle, key: u8, component_name: &
             if !changes.is_empty() {
                 set_component_value(core, key, component_name, changes)
             } else {
-                false
+                Err(ComponentValueError::Failed)
             }
         } else {
-            false
+            Err(ComponentValueError::Failed)
         }
     } else {
-        false
+        Err(ComponentValueError::Failed)
     }
 }
 
-/// Removes component from a button
+/// Removes component from a button, fails if other components of the button require it
 pub fn remove_component(core: &CoreHandle, key: u8, component_name: &str) -> bool {
     core.required_feature("core_methods");
 
@@ -363,7 +442,13 @@ pub fn remove_component(core: &CoreHandle, key: u8, component_name: &str) -> boo
             let mut button_handle = button.write().unwrap();
             drop(handle);
 
-            if button_handle.component_names().contains(&component_name.to_string()) {
+            let component_names = button_handle.component_names();
+
+            if component_names.contains(&component_name.to_string()) {
+                if module_manager.check_component_removal(&component_names, component_name).is_err() {
+                    return false;
+                }
+
                 let components = module_manager.read_component_map();
 
                 if let Some((_, module)) = components.get(component_name) {
@@ -406,6 +491,7 @@ pub fn paste_button(core: &CoreHandle, key: u8, reference_button: &Button) -> bo
 /// Pushes new panel into the stack
 pub fn push_screen(core: &CoreHandle, screen: ButtonPanel) {
     core.required_feature("core_methods");
+    start_transition(core, TransitionDirection::Forward);
     let mut stack = core.current_stack().unwrap();
 
     stack.push(screen.clone());
@@ -421,6 +507,7 @@ pub fn push_screen(core: &CoreHandle, screen: ButtonPanel) {
 /// Pops panel from stack
 pub fn pop_screen(core: &CoreHandle) {
     core.required_feature("core_methods");
+    start_transition(core, TransitionDirection::Backward);
     let mut stack = core.current_stack().unwrap();
 
     let old_panel = stack.pop();
@@ -435,6 +522,19 @@ pub fn pop_screen(core: &CoreHandle) {
     core.core.mark_for_redraw();
 }
 
+/// Tells device thread to animate from current screen to the screen that will be shown on next redraw
+fn start_transition(core: &CoreHandle, direction: TransitionDirection) {
+    let transition = get_panel_transition(core);
+
+    if transition == PanelTransition::None || core.core.is_closed() {
+        return;
+    }
+
+    if let Some(images) = get_button_images(core) {
+        core.core.send_commands(vec![DeviceThreadCommunication::StartTransition(transition, direction, images)]);
+    }
+}
+
 /// Returns first panel of the stack for saving purposes
 pub fn get_root_screen(core: &CoreHandle) -> ButtonPanel {
     core.required_feature("core_methods");
@@ -518,19 +618,36 @@ pub fn button_up(core: &CoreHandle, key: u8) {
 pub fn button_action(core: &CoreHandle, key: u8) {
     core.required_feature("core_methods");
     if let Some(screen) = get_current_screen(core) {
-        let handle = screen.read().unwrap();
-        if let Some(button) = handle.buttons.get(&key).cloned() {
-            drop(handle);
+        if let Some(button) = get_visible_button(core, key) {
+            // Hidden and disabled buttons don't react to presses
+            if button_condition_state(core, &button) != ConditionState::Shown {
+                return;
+            }
+
+            // Buttons with states get switched right after the press, so modules receive a copy of the button in state it was pressed in
+            let has_states = button.read().unwrap().contains(StateComponent::NAME);
+            let pressed_button = if has_states {
+                Arc::new(RwLock::new(button.read().unwrap().clone()))
+            } else {
+                button.clone()
+            };
 
             let event = SDCoreEvent::ButtonAction {
                 key,
                 panel: screen.clone(),
-                pressed_button: button.clone()
+                pressed_button: pressed_button.clone()
             };
 
-            send_core_event_to_modules(&core, event.clone(), core.module_manager().get_modules_for_components(button.read().unwrap().component_names().as_slice()).into_iter());
+            send_core_event_to_modules(&core, event.clone(), core.module_manager().get_modules_for_components(pressed_button.read().unwrap().component_names().as_slice()).into_iter());
             send_event_to_socket(&core.core.socket_manager, core_event_to_global(event, &core.core.serial_number));
 
+            if has_states {
+                advance_button_state(&button);
+            }
+
+            select_radio_button(core, key, &button);
+            advance_carousel(&button);
+
             core.core.mark_for_redraw();
         }
     }
@@ -556,117 +673,165 @@ pub fn get_current_screen(core: &CoreHandle) -> Option<ButtonPanel> {
     }
 }
 
-pub fn get_button_images(core: &CoreHandle) -> Option<HashMap<u8, DynamicImage>> {
-    let missing = draw_missing_texture(core.core.image_size);
-    let custom = {
-        let size = core.core.image_size;
-        let font = get_font_from_collection("default").unwrap();
-        let mut frame = image_from_solid(size, Rgba([55, 55, 55, 255]));
+/// Gets buttons that are currently visible on the device, that is buttons of current screen covered by overlays
+pub fn get_visible_buttons(core: &CoreHandle) -> Option<UniqueButtonMap> {
+    core.required_feature("core_methods");
+    let screen = get_current_screen(core)?;
+    let mut buttons = screen.read().unwrap().buttons.clone();
 
-        render_aligned_text_on_image(size, &mut frame, font.deref(), "Custom", Scale::uniform(16.0), TextAlignment::Center, 0, (0.0, -8.0), (255, 255, 255, 255));
-        render_aligned_text_on_image(size, &mut frame, font.deref(), "Renderer", Scale::uniform(16.0), TextAlignment::Center, 0, (0.0, 8.0), (255, 255, 255, 255));
+    for overlay in core.core.overlays.lock().unwrap().iter() {
+        buttons.extend(overlay.buttons.iter().map(|(key, button)| (*key, button.clone())));
+    }
 
-        frame
-    };
+    Some(buttons)
+}
+
+/// Gets button that is currently visible on specified position, either from top-most overlay that covers the key or from current screen
+pub fn get_visible_button(core: &CoreHandle, key: u8) -> Option<UniqueButton> {
+    core.required_feature("core_methods");
+    let overlays = core.core.overlays.lock().unwrap();
+
+    if let Some(button) = overlays.iter().rev().find_map(|x| x.buttons.get(&key)) {
+        return Some(button.clone());
+    }
+
+    drop(overlays);
+    get_button(core, key)
+}
+
+/// Puts overlay on top of all other overlays, replaces overlay with same id if it already exists
+pub fn push_overlay(core: &CoreHandle, id: &str, buttons: UniqueButtonMap) {
+    core.required_feature("core_methods");
+    let mut overlays = core.core.overlays.lock().unwrap();
+
+    overlays.retain(|x| x.id != id);
+    overlays.push(Overlay {
+        id: id.to_string(),
+        buttons
+    });
+    drop(overlays);
+
+    core.core.mark_for_redraw();
+}
+
+/// Removes overlay with specified id, keys that it covered will show whatever is below again
+pub fn remove_overlay(core: &CoreHandle, id: &str) -> bool {
+    core.required_feature("core_methods");
+    let mut overlays = core.core.overlays.lock().unwrap();
+
+    let count = overlays.len();
+    overlays.retain(|x| x.id != id);
+    let removed = overlays.len() != count;
+    drop(overlays);
 
-    let panel = get_current_screen(core)?;
-    let current_screen = panel.read().unwrap();
-    let buttons = current_screen.buttons.clone();
+    if removed {
+        core.core.mark_for_redraw();
+    }
+
+    removed
+}
 
-    let renderers = core.core.render_manager.read_renderers();
+/// Gets ids of current overlays, from bottom to top
+pub fn get_overlays(core: &CoreHandle) -> Vec<String> {
+    core.required_feature("core_methods");
+    core.core.overlays.lock().unwrap().iter().map(|x| x.id.clone()).collect()
+}
+
+/// Placeholder image for buttons that use custom renderers without representation
+fn draw_custom_renderer_placeholder(size: (usize, usize)) -> DynamicImage {
+    let font = get_font_from_collection("default").unwrap();
+    let mut frame = image_from_solid(size, Rgba([55, 55, 55, 255]));
+
+    render_aligned_text_on_image(size, &mut frame, font.deref(), "Custom", Scale::uniform(16.0), TextAlignment::Center, 0, (0.0, -8.0), (255, 255, 255, 255));
+    render_aligned_text_on_image(size, &mut frame, font.deref(), "Renderer", Scale::uniform(16.0), TextAlignment::Center, 0, (0.0, 8.0), (255, 255, 255, 255));
+
+    frame
+}
+
+/// Renders button the same way device thread would, returns None if button doesn't have renderer component
+fn draw_button(core: &CoreHandle, key: u8, button: &UniqueButton, missing: &DynamicImage, custom: &DynamicImage) -> Option<DynamicImage> {
+    let component = parse_unique_button_to_component::<RendererComponent>(button).ok()?;
+
+    let modules = core.module_manager().get_modules_for_rendering(&button.read().unwrap().component_names());
+    let modules = modules.into_values()
+        .filter(|x| !component.plugin_blacklist.contains(&x.name()))
+        .collect::<Vec<UniqueSDModule>>();
+
+    if component.renderer.is_empty() {
+        Some(draw_foreground(
+            &component,
+            button,
+            &modules,
+            draw_background(
+                &component,
+                core,
+                missing
+            ),
+            core
+        ))
+    } else {
+        let renderers = core.core.render_manager.read_renderers();
+
+        if let Some(image) = renderers.get(&component.renderer).and_then(|renderer| renderer.representation(key, button, core)) {
+            Some(image)
+        } else {
+            Some(custom.clone())
+        }
+    }
+}
+
+pub fn get_button_images(core: &CoreHandle) -> Option<HashMap<u8, DynamicImage>> {
+    let missing = draw_missing_texture(core.core.image_size);
+    let custom = draw_custom_renderer_placeholder(core.core.image_size);
+
+    let buttons = get_visible_buttons(core)?;
 
     Some(buttons.into_iter()
         .filter_map(|(key, button)| {
-            if let Ok(component) = parse_unique_button_to_component::<RendererComponent>(&button) {
-                let modules = core.module_manager().get_modules_for_rendering(&button.read().unwrap().component_names());
-                let modules = modules.into_values()
-                    .filter(|x| !component.plugin_blacklist.contains(&x.name()))
-                    .collect::<Vec<UniqueSDModule>>();
-
-                let image = if component.renderer.is_empty() {
-                    draw_foreground(
-                        &component,
-                        &button,
-                        &modules,
-                        draw_background(
-                            &component,
-                            core,
-                            &missing
-                        ),
-                        core
-                    )
-                } else {
-                    if let Some(renderer) = renderers.get(&component.renderer) {
-                        if let Some(image) = renderer.representation(key, &button, core) {
-                            image
-                        } else {
-                            custom.clone()
-                        }
-                    } else {
-                        custom.clone()
-                    }
-                };
-
-                Some((key, image))
-            } else {
-                None
-            }
+            draw_button(core, key, &button, &missing, &custom).map(|image| (key, image))
         })
         .collect())
 }
 
 pub fn get_button_image(core: &CoreHandle, key: u8) -> Option<DynamicImage> {
     let missing = draw_missing_texture(core.core.image_size);
-    let custom = {
-        let size = core.core.image_size;
-        let font = get_font_from_collection("default").unwrap();
-        let mut frame = image_from_solid(size, Rgba([55, 55, 55, 255]));
+    let custom = draw_custom_renderer_placeholder(core.core.image_size);
 
-        render_aligned_text_on_image(size, &mut frame, font.deref(), "Custom", Scale::uniform(16.0), TextAlignment::Center, 0, (0.0, -8.0), (255, 255, 255, 255));
-        render_aligned_text_on_image(size, &mut frame, font.deref(), "Renderer", Scale::uniform(16.0), TextAlignment::Center, 0, (0.0, 8.0), (255, 255, 255, 255));
+    let button = get_visible_button(core, key)?;
 
-        frame
-    };
-
-    let button = get_button(core, key)?;
-    let renderers = core.core.render_manager.read_renderers();
+    draw_button(core, key, &button, &missing, &custom)
+}
 
-    if let Ok(component) = parse_unique_button_to_component::<RendererComponent>(&button) {
-        let modules = core.module_manager().get_modules_for_rendering(&button.read().unwrap().component_names());
-        let modules = modules.into_values()
-            .filter(|x| !component.plugin_blacklist.contains(&x.name()))
-            .collect::<Vec<UniqueSDModule>>();
+/// Renders provided button with rendering pipeline of the device without it being on the screen, for previews.
+/// Button is drawn at resolution of the device and then scaled to provided size, so pixels look the same as they would on the device
+pub fn render_button_preview(core: &CoreHandle, key: u8, button: &Button, size: (u32, u32)) -> Option<DynamicImage> {
+    core.required_feature("core_methods");
+    let missing = draw_missing_texture(core.core.image_size);
+    let custom = draw_custom_renderer_placeholder(core.core.image_size);
 
-        let image = if component.renderer.is_empty() {
-            draw_foreground(
-                &component,
-                &button,
-                &modules,
-                draw_background(
-                    &component,
-                    core,
-                    &missing
-                ),
-                core
-            )
-        } else {
-            if let Some(renderer) = renderers.get(&component.renderer) {
-                if let Some(image) = renderer.representation(key, &button, core) {
-                    image
-                } else {
-                    custom.clone()
-                }
-            } else {
-                custom.clone()
-            }
-        };
+    let image = draw_button(core, key, &make_button_unique(button.clone()), &missing, &custom)?;
 
+    if image.dimensions() == size || size.0 == 0 || size.1 == 0 {
         Some(image)
     } else {
-        None
+        Some(image.resize_exact(size.0, size.1, FilterType::Nearest))
     }
 }
 
+/// Replaces all buttons of the panel at once, panel doesn't need to be on the stack.
+/// Sends a single event and redraws once, instead of event and redraw per button
+pub fn set_panel_buttons(core: &CoreHandle, panel: &ButtonPanel, buttons: UniqueButtonMap) {
+    core.required_feature("core_methods");
+    let old_buttons = std::mem::replace(&mut panel.write().unwrap().buttons, buttons);
+
+    send_core_event_to_modules(core, SDCoreEvent::PanelUpdated {
+        panel: panel.clone(),
+        old_buttons
+    }, core.module_manager().get_module_list().into_iter());
+
+    core.core.mark_for_redraw();
+}
+
 /// Replaces current screen with specified one
 pub fn replace_screen(core: &CoreHandle, screen: ButtonPanel) {
     core.required_feature("core_methods");
@@ -693,6 +858,21 @@ pub fn set_brightness(core: &CoreHandle, brightness: u8) {
     handle.brightness = brightness;
 }
 
+/// Sends custom event of the module to clients and other modules
+pub fn send_module_event(core: &CoreHandle, name: &str, data: Value) {
+    core.required_feature("core_methods");
+
+    let event = SDGlobalEvent::ModuleEvent {
+        serial_number: core.core.serial_number.clone(),
+        module: core.module_name.clone(),
+        name: name.to_string(),
+        data
+    };
+
+    send_event_to_socket(&core.core.socket_manager, event.clone());
+    send_global_event_to_modules(event, core.module_manager().get_module_list().into_iter());
+}
+
 /// Commits all changes to layout to device config so it can be later saved
 pub fn commit_changes(core: &CoreHandle) {
     core.required_feature("core_methods");
@@ -701,4 +881,14 @@ pub fn commit_changes(core: &CoreHandle) {
     let core = core.core();
     let mut handle = core.device_config.write().unwrap();
     handle.layout = panel_to_raw(&stack);
+}
+
+/// Gets copy of device config with uncommitted changes to layout, device config itself is left as is
+pub fn uncommitted_config(core: &CoreHandle) -> DeviceConfig {
+    core.required_feature("core_methods");
+    let stack = get_root_screen(core);
+
+    let mut config = core.core.device_config.read().unwrap().clone();
+    config.layout = panel_to_raw(&stack);
+    config
 }
\ No newline at end of file
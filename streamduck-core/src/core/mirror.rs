@@ -0,0 +1,78 @@
+//! Mirroring of one device onto another
+//!
+//! Mirroring device shows current screen of the source device instead of its own, with buttons keeping their row and column,
+//! presses on mirroring device are dispatched to the source device. Mirrors are set with [CoreManager::set_mirror]
+
+use serde::{Deserialize, Serialize};
+use crate::adapt::map_grid_key;
+use crate::core::UniqueButtonMap;
+use crate::core::manager::CoreManager;
+use crate::core::methods::{CoreHandle, get_visible_buttons};
+
+/// Reasons why mirror couldn't be set
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum MirrorError {
+    /// Either of devices isn't added to core manager
+    DeviceNotFound,
+    /// Device can't mirror itself
+    SameDevice,
+    /// Source device is already mirroring another device, mirrors can't be chained
+    SourceIsMirroring,
+    /// Device is mirrored by other devices, so it can't mirror anything itself
+    DeviceIsMirrored,
+}
+
+/// Core of the device that specified core mirrors, [None] if core isn't mirroring or source isn't connected
+pub fn mirror_source(core: &CoreHandle) -> Option<CoreHandle> {
+    let manager = core.core.module_manager.core_manager()?;
+    let source = manager.mirror_source(&core.core.serial_number)?;
+
+    Some(CoreHandle::wrap(source))
+}
+
+/// Buttons of source device's current screen placed on keys of the mirroring device, [None] if core isn't mirroring
+pub fn mirrored_visible_buttons(core: &CoreHandle) -> Option<UniqueButtonMap> {
+    let source = mirror_source(core)?;
+    let buttons = get_visible_buttons(&source)?;
+
+    Some(
+        buttons.into_iter()
+            .filter_map(|(key, button)| Some((map_grid_key(key, source.core.columns, core.core.columns, core.core.key_count)?, button)))
+            .collect()
+    )
+}
+
+/// Core and key that press on specified key should go to, [None] if core is mirroring and key doesn't exist on source device
+pub fn press_destination(core: &CoreHandle, key: u8) -> Option<(CoreHandle, u8)> {
+    if let Some(source) = mirror_source(core) {
+        let key = map_grid_key(key, core.core.columns, source.core.columns, source.core.key_count)?;
+        Some((source, key))
+    } else {
+        Some((core.clone(), key))
+    }
+}
+
+/// Validates that target device can start mirroring source device
+pub(crate) fn check_mirror(manager: &CoreManager, target: &str, source: &str) -> Result<(), MirrorError> {
+    if target == source {
+        return Err(MirrorError::SameDevice);
+    }
+
+    let devices = manager.list_added_devices();
+
+    if !devices.contains_key(target) || !devices.contains_key(source) {
+        return Err(MirrorError::DeviceNotFound);
+    }
+
+    let mirrors = manager.list_mirrors();
+
+    if mirrors.contains_key(source) {
+        return Err(MirrorError::SourceIsMirroring);
+    }
+
+    if mirrors.values().any(|x| x == target) {
+        return Err(MirrorError::DeviceIsMirrored);
+    }
+
+    Ok(())
+}
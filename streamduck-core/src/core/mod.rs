@@ -3,7 +3,19 @@ pub mod button;
 
 /// Methods for interacting with the core
 pub mod methods;
+/// Notifications shown over current panel
+pub mod notifications;
 pub mod manager;
+/// Mirroring of one device onto another
+pub mod mirror;
+/// Logical devices made of several physical devices
+pub mod composite;
+/// Revisions of current screen for detecting conflicting edits
+pub mod revisions;
+/// Opened folders that are restored after daemon restarts
+pub mod saved_stack;
+/// Startup folder and actions of devices
+pub mod startup;
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex, RwLock};
@@ -11,11 +23,17 @@ use std::sync::mpsc::{channel, Receiver};
 use streamdeck::{Kind, StreamDeck};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use crate::adapt::key_columns;
 use crate::config::{Config, UniqueDeviceConfig};
 use crate::core::button::Button;
 use crate::thread::{DeviceThreadCommunication, DeviceThreadHandle, spawn_device_thread};
 use crate::core::methods::{button_down, button_up, CoreHandle};
+use crate::core::composite::composite_press_destination;
+use crate::core::mirror::press_destination;
+use crate::core::revisions::Revisions;
 use crate::ImageCollection;
+use crate::logging::set_thread_log_context;
+use crate::metrics::record_key_event;
 use crate::modules::events::SDGlobalEvent;
 use crate::modules::ModuleManager;
 use crate::socket::{send_event_to_socket, SocketManager};
@@ -74,6 +92,15 @@ impl Into<UniqueButtonMap> for &Panel<UniqueButtonMap> {
     }
 }
 
+/// Temporary layer of buttons that covers some keys of whatever panel is currently shown
+#[derive(Clone)]
+pub struct Overlay {
+    /// Identifier used to update or remove the overlay
+    pub id: String,
+    /// Buttons that replace buttons of the panel
+    pub buttons: UniqueButtonMap
+}
+
 /// Core struct that contains all relevant information about streamdeck and manages the streamdeck
 #[allow(dead_code)]
 pub struct SDCore {
@@ -98,6 +125,12 @@ pub struct SDCore {
     /// Current panel stack
     pub current_stack: Mutex<Vec<ButtonPanel>>,
 
+    /// Overlays on top of current panel, last one has highest priority
+    pub overlays: Mutex<Vec<Overlay>>,
+
+    /// Revisions of current screen
+    pub revisions: Mutex<Revisions>,
+
     /// Image size supported by streamdeck
     pub image_size: (usize, usize),
 
@@ -110,6 +143,9 @@ pub struct SDCore {
     /// Key count of the streamdeck device
     pub key_count: u8,
 
+    /// Amount of key columns, composite devices have a grid of their own instead of the one their kind has
+    pub columns: u8,
+
     /// Pool rate of how often should the core read events from the device
     pub pool_rate: u32,
 
@@ -131,11 +167,14 @@ impl SDCore {
             config,
             device_config,
             current_stack: Mutex::new(vec![]),
+            overlays: Mutex::new(vec![]),
+            revisions: Default::default(),
             handles: Mutex::new(None),
             image_size: (0, 0),
             image_collection,
             kind: Kind::Original,
             key_count: 0,
+            columns: key_columns(Kind::Original),
             pool_rate: 0,
             should_close: RwLock::new(true)
         })
@@ -159,11 +198,14 @@ impl SDCore {
             config,
             device_config,
             current_stack: Mutex::new(vec![]),
+            overlays: Mutex::new(vec![]),
+            revisions: Default::default(),
             handles: Mutex::new(None),
             image_size: connection.image_size(),
             image_collection,
             kind: connection.kind(),
             key_count: connection.kind().keys(),
+            columns: key_columns(connection.kind()),
             pool_rate,
             should_close: RwLock::new(false)
         });
@@ -184,18 +226,65 @@ impl SDCore {
         })
     }
 
-    /// Tells device thread to refresh screen
+    /// Creates an instance of the core that isn't backed by any hardware, for testing purposes.
+    /// Core behaves like a connected device, but has no device thread, so images have to be retrieved with [methods::get_button_images]
+    pub fn new_virtual(module_manager: Arc<ModuleManager>, render_manager: Arc<RenderingManager>, socket_manager: Arc<SocketManager>, config: Arc<Config>, device_config: UniqueDeviceConfig, image_collection: ImageCollection, kind: Kind) -> Arc<SDCore> {
+        let columns = key_columns(kind);
+        Self::new_virtual_with_grid(module_manager, render_manager, socket_manager, config, device_config, image_collection, kind, columns, kind.keys() / columns)
+    }
+
+    /// Creates an instance of the core that isn't backed by any hardware and has a different grid than device kind has,
+    /// used for composite devices
+    pub fn new_virtual_with_grid(module_manager: Arc<ModuleManager>, render_manager: Arc<RenderingManager>, socket_manager: Arc<SocketManager>, config: Arc<Config>, device_config: UniqueDeviceConfig, image_collection: ImageCollection, kind: Kind, columns: u8, rows: u8) -> Arc<SDCore> {
+        let serial_number = device_config.read().unwrap().serial.to_string();
+        let image_size = kind.image_size();
+
+        send_event_to_socket(&socket_manager, SDGlobalEvent::DeviceConnected {
+            serial_number: serial_number.clone()
+        });
+
+        Arc::new(SDCore {
+            serial_number,
+            module_manager,
+            render_manager,
+            socket_manager,
+            config,
+            device_config,
+            current_stack: Mutex::new(vec![]),
+            overlays: Mutex::new(vec![]),
+            revisions: Default::default(),
+            handles: Mutex::new(None),
+            image_size,
+            image_collection,
+            kind,
+            key_count: columns * rows,
+            columns,
+            pool_rate: 0,
+            should_close: RwLock::new(false)
+        })
+    }
+
+    /// Tells device thread to refresh screen, devices that mirror this device or make up this composite device get refreshed too
     pub fn mark_for_redraw(&self) {
-        let handles = self.handles.lock().unwrap();
+        self.send_commands(vec![DeviceThreadCommunication::RefreshScreen]);
 
-        handles.as_ref().unwrap().renderer.send(vec![DeviceThreadCommunication::RefreshScreen]);
+        if let Some(manager) = self.module_manager.core_manager() {
+            let mirrors = manager.mirror_targets(&self.serial_number);
+            let members = manager.composite_members(&self.serial_number);
+
+            for target in mirrors.into_iter().chain(members) {
+                target.send_commands(vec![DeviceThreadCommunication::RefreshScreen]);
+            }
+        }
     }
 
-    /// Sends commands to streamdeck thread
+    /// Sends commands to streamdeck thread, does nothing for virtual devices
     pub fn send_commands(&self, commands: Vec<DeviceThreadCommunication>) {
         let handles = self.handles.lock().unwrap();
 
-        handles.as_ref().unwrap().renderer.send(commands);
+        if let Some(handles) = handles.as_ref() {
+            handles.renderer.send(commands);
+        }
     }
 
     /// Gets serial number of the core
@@ -216,6 +305,9 @@ impl SDCore {
 
         let mut lock = self.should_close.write().unwrap();
         *lock = true;
+        drop(lock);
+
+        self.module_manager.scheduler().cancel_core(self);
     }
 }
 
@@ -232,16 +324,27 @@ pub struct KeyHandler {
 impl KeyHandler {
     /// Runs the key handling loop in current thread
     pub fn run_loop(&self) {
+        set_thread_log_context(Some(&self.core.core.serial_number), None);
+
         loop {
             if self.core.core().is_closed() {
                 break
             }
 
             if let Ok((key, state)) = self.receiver.recv() {
-                if state {
-                    button_down(&self.core, key);
-                } else {
-                    button_up(&self.core, key);
+                record_key_event(&self.core.core.serial_number);
+
+                // Presses of composite device members go to composite device, presses of mirroring devices go to the device they mirror
+                let destination = composite_press_destination(&self.core, key)
+                    .map(Some)
+                    .unwrap_or_else(|| press_destination(&self.core, key));
+
+                if let Some((core, key)) = destination {
+                    if state {
+                        button_down(&core, key);
+                    } else {
+                        button_up(&core, key);
+                    }
                 }
             } else {
                 break;
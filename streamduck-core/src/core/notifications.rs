@@ -0,0 +1,145 @@
+//! Notifications that temporarily cover keys of a device with a message, shown as overlays on top of current panel
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::core::button::Button;
+use crate::core::methods::{CoreHandle, push_overlay, remove_overlay};
+use crate::core::UniqueButtonMap;
+use crate::thread::rendering::{ButtonBackground, ButtonText, Color, RendererComponentBuilder};
+use crate::thread::util::TextAlignment;
+use crate::util::make_button_unique;
+
+/// Prefix of overlay ids that notifications use
+pub const NOTIFICATION_PREFIX: &'static str = "notification/";
+
+/// Background of keys that notification covers
+pub const NOTIFICATION_BACKGROUND: Color = (30, 30, 30, 255);
+
+static NOTIFICATION_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Message that is shown over current panel for some time
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Notification {
+    pub text: String,
+    /// Identifier of image from image collection or base64 encoded image, shown on first covered key
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Seconds before notification disappears, 0 keeps it until it's dismissed
+    #[serde(default)]
+    pub duration: f32,
+    /// Keys that notification covers, covers all keys if empty
+    #[serde(default)]
+    pub keys: Vec<u8>,
+}
+
+/// Shows notification on top of current panel, returns id that can be used to dismiss it early
+pub fn show_notification(core: &CoreHandle, notification: Notification) -> String {
+    core.required_feature("core_methods");
+
+    let id = format!("{}{}", NOTIFICATION_PREFIX, NOTIFICATION_COUNTER.fetch_add(1, Ordering::SeqCst));
+
+    let key_count = core.core.key_count;
+    let mut keys: Vec<u8> = if notification.keys.is_empty() {
+        (0..key_count).collect()
+    } else {
+        notification.keys.iter().copied().filter(|x| *x < key_count).collect()
+    };
+    keys.sort();
+    keys.dedup();
+
+    let mut looks: HashMap<u8, (ButtonBackground, String)> = keys.iter()
+        .map(|key| (*key, (ButtonBackground::Solid(NOTIFICATION_BACKGROUND), "".to_string())))
+        .collect();
+
+    let mut text_keys = keys.as_slice();
+
+    if let (Some(icon), Some((first, rest))) = (&notification.icon, keys.split_first()) {
+        let background = if core.core.image_collection.read().unwrap().contains_key(icon) {
+            ButtonBackground::ExistingImage(icon.clone())
+        } else {
+            ButtonBackground::NewImage(icon.clone())
+        };
+
+        looks.insert(*first, (background, "".to_string()));
+
+        // Text is drawn over the icon only if there's no other key for it
+        if !rest.is_empty() {
+            text_keys = rest;
+        }
+    }
+
+    for (key, text) in text_keys.iter().zip(split_text(&notification.text, text_keys.len())) {
+        if let Some((_, key_text)) = looks.get_mut(key) {
+            *key_text = text;
+        }
+    }
+
+    let buttons: UniqueButtonMap = looks.into_iter()
+        .map(|(key, (background, text))| (key, make_button_unique(notification_button(background, text))))
+        .collect();
+
+    push_overlay(core, &id, buttons);
+
+    if notification.duration > 0.0 {
+        let overlay_id = id.clone();
+
+        core.core.module_manager.scheduler().schedule(core, Duration::from_secs_f32(notification.duration), None, Box::new(move |core| {
+            remove_overlay(&core, &overlay_id);
+        }));
+    }
+
+    id
+}
+
+/// Removes notification before its duration runs out, returns false if there's no such notification
+pub fn dismiss_notification(core: &CoreHandle, id: &str) -> bool {
+    core.required_feature("core_methods");
+
+    if !id.starts_with(NOTIFICATION_PREFIX) {
+        return false;
+    }
+
+    remove_overlay(core, id)
+}
+
+fn notification_button(background: ButtonBackground, text: String) -> Button {
+    let mut builder = RendererComponentBuilder::new()
+        .background(background);
+
+    if !text.is_empty() {
+        builder = builder.add_text(ButtonText {
+            text,
+            font: "default".to_string(),
+            scale: (18.0, 18.0),
+            alignment: TextAlignment::Center,
+            padding: 4,
+            offset: (0.0, 0.0),
+            color: (255, 255, 255, 255),
+            shadow: None,
+            auto_fit: true,
+            word_wrap: true,
+            marquee_speed: 0.0
+        });
+    }
+
+    let mut button = Button::new();
+    button.insert_component(builder.build()).ok();
+    button
+}
+
+/// Splits text into at most provided amount of parts with similar amount of words
+fn split_text(text: &str, parts: usize) -> Vec<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+
+    if parts <= 1 || words.len() <= 1 {
+        return vec![text.to_string()];
+    }
+
+    let per_part = (words.len() + parts - 1) / parts;
+
+    words.chunks(per_part)
+        .map(|x| x.join(" "))
+        .collect()
+}
@@ -0,0 +1,109 @@
+//! Revisions of current screen, for detecting edits that were made over outdated state
+//!
+//! Every edit of buttons or change of the stack increases revision of the device. Clients provide revision they've last seen
+//! when editing, so edits made by another client in the meantime aren't silently overwritten.
+//! Edits are announced with [SDGlobalEvent::ScreenChanged], tagged with the client that made them
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::core::methods::CoreHandle;
+use crate::modules::events::{SDCoreEvent, SDGlobalEvent};
+use crate::socket::{requesting_client, send_event_to_socket};
+
+/// Revision counters of a device
+#[derive(Debug, Default)]
+pub struct Revisions {
+    revision: u64,
+    /// Revision of last change to the stack, buttons are considered changed too
+    stack: u64,
+    /// Revisions of last changes to keys of current screen
+    buttons: HashMap<u8, u64>,
+}
+
+impl Revisions {
+    /// Latest revision of the device
+    pub fn current(&self) -> u64 {
+        self.revision
+    }
+
+    /// Revision of last change to the key
+    pub fn button(&self, key: u8) -> u64 {
+        self.buttons.get(&key).copied().unwrap_or(0).max(self.stack)
+    }
+
+    fn keys_changed(&mut self, keys: &[u8]) -> u64 {
+        self.revision += 1;
+
+        for key in keys {
+            self.buttons.insert(*key, self.revision);
+        }
+
+        self.revision
+    }
+
+    fn stack_changed(&mut self) -> u64 {
+        self.revision += 1;
+        self.stack = self.revision;
+        self.buttons.clear();
+        self.revision
+    }
+}
+
+/// Revisions of current screen that are sent to clients
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ScreenRevisions {
+    /// Latest revision of the device, changes with any edit
+    pub revision: u64,
+    /// Revisions of buttons on current screen
+    pub buttons: HashMap<u8, u64>,
+}
+
+/// Gets revisions of current screen and its buttons
+pub fn screen_revisions(core: &CoreHandle) -> ScreenRevisions {
+    let revisions = core.core.revisions.lock().unwrap();
+
+    ScreenRevisions {
+        revision: revisions.current(),
+        buttons: (0..core.core.key_count).map(|key| (key, revisions.button(key))).collect()
+    }
+}
+
+/// Latest revision of the device
+pub fn screen_revision(core: &CoreHandle) -> u64 {
+    core.core.revisions.lock().unwrap().current()
+}
+
+/// Revision of last change to the button on current screen
+pub fn button_revision(core: &CoreHandle, key: u8) -> u64 {
+    core.core.revisions.lock().unwrap().button(key)
+}
+
+/// Increases revisions if event is an edit and announces the change to clients
+pub(crate) fn record_edit(core: &CoreHandle, event: &SDCoreEvent) {
+    let keys = match event {
+        SDCoreEvent::ButtonAdded { key, .. } | SDCoreEvent::ButtonUpdated { key, .. } | SDCoreEvent::ButtonDeleted { key, .. } => vec![*key],
+        SDCoreEvent::ButtonMoved { from, to, .. } => vec![*from, *to],
+
+        SDCoreEvent::PanelUpdated { .. } | SDCoreEvent::PanelPushed { .. } | SDCoreEvent::PanelPopped { .. }
+        | SDCoreEvent::PanelReplaced { .. } | SDCoreEvent::StackReset { .. } => vec![],
+
+        _ => return,
+    };
+
+    let revision = {
+        let mut revisions = core.core.revisions.lock().unwrap();
+
+        if keys.is_empty() {
+            revisions.stack_changed()
+        } else {
+            revisions.keys_changed(&keys)
+        }
+    };
+
+    send_event_to_socket(&core.core.socket_manager, SDGlobalEvent::ScreenChanged {
+        serial_number: core.core.serial_number.clone(),
+        revision,
+        keys,
+        client: requesting_client()
+    });
+}
@@ -0,0 +1,64 @@
+//! Persistence of opened folders across daemon restarts
+//!
+//! If daemon config has `persist_stack` enabled, folder IDs of the stack are kept in device config as the stack changes,
+//! and the folders are opened again once device connects
+
+use crate::core::methods::{CoreHandle, get_stack, push_screen};
+use crate::modules::events::{SDCoreEvent, SDGlobalEvent};
+use crate::modules::folders::{folder_panel, FolderStackData};
+use crate::socket::send_event_to_socket;
+
+/// Folder IDs of panels on the stack above root screen, stops at first panel that isn't a folder
+pub fn stack_path(core: &CoreHandle) -> Vec<String> {
+    get_stack(core).into_iter()
+        .skip(1)
+        .map_while(|panel| serde_json::from_value::<FolderStackData>(panel.read().unwrap().data.clone()).ok())
+        .map(|x| x.folder_id)
+        .collect()
+}
+
+/// Keeps stack path in device config up to date with stack changes
+pub(crate) fn remember_stack(core: &CoreHandle, event: &SDCoreEvent) {
+    if !matches!(event, SDCoreEvent::PanelPushed { .. } | SDCoreEvent::PanelPopped { .. } | SDCoreEvent::PanelReplaced { .. } | SDCoreEvent::StackReset { .. }) {
+        return;
+    }
+
+    if !core.core.config.persist_stack() {
+        return;
+    }
+
+    let path = stack_path(core);
+
+    {
+        let mut device_config = core.core.device_config.write().unwrap();
+
+        if device_config.stack_path == path {
+            return;
+        }
+
+        device_config.stack_path = path.clone();
+    }
+
+    send_event_to_socket(&core.core.socket_manager, SDGlobalEvent::StackPathChanged {
+        serial_number: core.core.serial_number.clone(),
+        path
+    });
+}
+
+/// Opens folders of the path on top of current stack, stops at first folder that no longer exists.
+/// Does nothing unless daemon config has `persist_stack` enabled
+pub fn restore_stack(core: &CoreHandle, path: &[String]) {
+    if !core.core.config.persist_stack() {
+        return;
+    }
+
+    for folder_id in path {
+        match folder_panel(core, folder_id) {
+            Some(panel) => push_screen(core, panel),
+            None => {
+                log::warn!("Folder {} of saved stack no longer exists on {}", folder_id, core.core.serial_number);
+                break;
+            }
+        }
+    }
+}
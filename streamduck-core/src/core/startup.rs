@@ -0,0 +1,55 @@
+//! Folder that gets opened and actions that get triggered once device connects
+//!
+//! Startup actions are buttons that aren't on any key, modules receive them as if they were pressed on current screen.
+//! Device thread runs them after the first frame is rendered, so actions like setting lights happen when device is ready
+
+use crate::core::button::Button;
+use crate::core::methods::{CoreHandle, get_current_screen, push_screen};
+use crate::core::saved_stack::restore_stack;
+use crate::modules::events::SDCoreEvent;
+use crate::modules::folders::folder_panel;
+use crate::modules::send_core_event_to_modules;
+use crate::util::make_button_unique;
+
+/// Key that startup actions are reported on, since they aren't on any key
+pub const STARTUP_ACTION_KEY: u8 = u8::MAX;
+
+/// Opens saved stack if there's one to restore, startup folder of device config otherwise
+pub fn open_startup_panels(core: &CoreHandle, stack_path: &[String]) {
+    if core.core.config.persist_stack() && !stack_path.is_empty() {
+        restore_stack(core, stack_path);
+        return;
+    }
+
+    let startup_folder = core.core.device_config.read().unwrap().startup_folder.clone();
+
+    if let Some(folder_id) = startup_folder {
+        match folder_panel(core, &folder_id) {
+            Some(panel) => push_screen(core, panel),
+            None => log::warn!("Startup folder {} doesn't exist on {}", folder_id, core.core.serial_number)
+        }
+    }
+}
+
+/// Triggers startup actions of device config
+pub fn run_startup_actions(core: &CoreHandle) {
+    let actions = core.core.device_config.read().unwrap().startup_actions.clone();
+
+    for action in actions {
+        trigger_action(core, action);
+    }
+}
+
+fn trigger_action(core: &CoreHandle, action: Button) {
+    if let Some(screen) = get_current_screen(core) {
+        let names = action.component_names();
+
+        let event = SDCoreEvent::ButtonAction {
+            key: STARTUP_ACTION_KEY,
+            panel: screen,
+            pressed_button: make_button_unique(action)
+        };
+
+        send_core_event_to_modules(core, event, core.module_manager().get_modules_for_components(names.as_slice()).into_iter());
+    }
+}
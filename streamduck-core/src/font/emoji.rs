@@ -0,0 +1,90 @@
+//! Fallback font for emoji and other characters that regular fonts don't have
+//!
+//! Color emoji are read from bitmap tables (CBDT, sbix) of the emoji font, emoji fonts without bitmaps are drawn as outlines.
+//! Emoji sequences joined with zero width joiner are drawn as separate emoji
+
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Arc, RwLock};
+use image::imageops::FilterType;
+use image::RgbaImage;
+use rusttype::Font;
+use crate::font::font_file;
+
+/// Font families that are used for emoji, in order of preference
+pub const EMOJI_FONT_NAMES: &[&str] = &["Noto Color Emoji", "Apple Color Emoji", "Segoe UI Emoji", "Twemoji Mozilla", "Noto Emoji"];
+
+static EMOJI_FONT: RwLock<Option<Arc<EmojiFont>>> = RwLock::new(None);
+
+/// Font used for characters that are missing in fonts of text objects
+pub struct EmojiFont {
+    data: Vec<u8>,
+    index: u32,
+    outline: Option<Font<'static>>,
+    bitmaps: RwLock<HashMap<(char, u32), Option<RgbaImage>>>,
+}
+
+impl EmojiFont {
+    /// Color bitmap of the character scaled to provided height, if font has one
+    pub fn bitmap(&self, character: char, height: u32) -> Option<RgbaImage> {
+        if let Some(bitmap) = self.bitmaps.read().unwrap().get(&(character, height)) {
+            return bitmap.clone();
+        }
+
+        let bitmap = self.read_bitmap(character, height);
+        self.bitmaps.write().unwrap().insert((character, height), bitmap.clone());
+
+        bitmap
+    }
+
+    fn read_bitmap(&self, character: char, height: u32) -> Option<RgbaImage> {
+        let face = ttf_parser::Face::from_slice(&self.data, self.index).ok()?;
+        let glyph = face.glyph_index(character)?;
+        let raster = face.glyph_raster_image(glyph, height.min(u16::MAX as u32) as u16)?;
+
+        let image = image::load_from_memory(raster.data).ok()?;
+        let width = (image.width() * height / image.height().max(1)).max(1);
+
+        Some(image.resize_exact(width, height.max(1), FilterType::Triangle).to_rgba8())
+    }
+
+    /// Outline font, for emoji fonts that don't have bitmaps
+    pub fn outline(&self) -> Option<&Font<'static>> {
+        self.outline.as_ref()
+    }
+}
+
+/// Picks emoji font from fonts that were found, should be called after fonts are loaded
+pub fn load_emoji_font() {
+    for name in EMOJI_FONT_NAMES {
+        if let Some((path, index)) = font_file(name) {
+            match fs::read(&path) {
+                Ok(data) => {
+                    log::info!("Using {} for emoji", name);
+
+                    *EMOJI_FONT.write().unwrap() = Some(Arc::new(EmojiFont {
+                        outline: Font::try_from_vec_and_index(data.clone(), index),
+                        data,
+                        index,
+                        bitmaps: Default::default(),
+                    }));
+
+                    return;
+                }
+                Err(err) => log::error!("Failed to load {:?}: {}", path, err)
+            }
+        }
+    }
+
+    log::info!("No emoji font found, emoji won't be displayed");
+}
+
+/// Gets emoji font, if one was found
+pub fn get_emoji_font() -> Option<Arc<EmojiFont>> {
+    EMOJI_FONT.read().unwrap().clone()
+}
+
+/// Characters that only change how emoji look, they aren't drawn on their own
+pub fn is_emoji_modifier(character: char) -> bool {
+    matches!(character, '\u{200D}' | '\u{FE0E}' | '\u{FE0F}' | '\u{1F3FB}'..='\u{1F3FF}')
+}
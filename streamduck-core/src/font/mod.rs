@@ -1,14 +1,53 @@
+/// Fallback font for emoji
+pub mod emoji;
+
 use std::fs;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 use rusttype::Font;
 
-static mut LOADED_FONTS: Vec<(String, Arc<Font<'static>>)> = vec![];
+/// Font in global collection, fonts that were found in folders are only read once they get used
+struct FontEntry {
+    path: Option<PathBuf>,
+    index: u32,
+    font: Option<Arc<Font<'static>>>,
+}
+
+/// Global font collection, font can be referred to by multiple names
+struct FontCollection {
+    entries: Vec<FontEntry>,
+    names: Vec<(String, usize)>,
+}
+
+impl FontCollection {
+    /// Adds name for font entry, names that are already taken are ignored
+    fn add_name(&mut self, name: String, entry: usize) -> bool {
+        if self.names.iter().any(|(n, _)| *n == name) {
+            false
+        } else {
+            self.names.push((name, entry));
+            true
+        }
+    }
+}
+
+static LOADED_FONTS: RwLock<FontCollection> = RwLock::new(FontCollection {
+    entries: Vec::new(),
+    names: Vec::new(),
+});
 
 /// Adds font to global collection
 pub fn add_font_to_collection(name: String, font: Font<'static>) {
-    unsafe {
-        LOADED_FONTS.push((name, Arc::new(font)));
-    }
+    let mut collection = LOADED_FONTS.write().unwrap();
+
+    collection.entries.push(FontEntry {
+        path: None,
+        index: 0,
+        font: Some(Arc::new(font)),
+    });
+
+    let entry = collection.entries.len() - 1;
+    collection.add_name(name, entry);
 }
 
 /// Loads default font for everything
@@ -19,56 +58,115 @@ pub fn load_default_font() {
     }
 }
 
-/// Loads fonts into global collection from fonts folder
-pub fn load_fonts_from_resources() {
+/// Finds fonts in fonts folder and optionally fonts installed in the system, adding them to global collection.
+/// Fonts can be referred to by family name, PostScript name, or file name for fonts in fonts folder
+pub fn load_fonts_from_resources(path: &Path, system_fonts: bool) {
+    let mut database = fontdb::Database::new();
+
+    if path.is_dir() {
+        database.load_fonts_dir(path);
+    } else {
+        log::info!("No fonts folder found at {:?}", path);
+    }
+
+    let user_fonts = database.faces().len();
+
+    if system_fonts {
+        database.load_system_fonts();
+    }
+
+    let mut collection = LOADED_FONTS.write().unwrap();
     let mut counter = 0;
 
-    match fs::read_dir("fonts") {
-        Ok(directory) => {
-            for entry in directory {
-                if let Ok(entry) = entry {
-                    if entry.path().is_file() {
-                        match fs::read(entry.path()) {
-                            Ok(bytes) => {
-                                if let Some(font) = Font::try_from_vec(bytes) {
-                                    add_font_to_collection(entry.file_name().to_string_lossy().to_string(), font);
-                                    counter += 1;
-                                } else {
-                                    log::error!("Failed to load {:?}: Not a font file", entry.file_name())
-                                }
-                            }
-                            Err(err) => {
-                                log::error!("Failed to load {:?}: {}", entry.file_name(), err)
-                            }
-                        }
-                    }
-                }
-            }
+    for (index, face) in database.faces().iter().enumerate() {
+        let file = if let fontdb::Source::File(file) = &face.source {
+            file
+        } else {
+            continue;
+        };
+
+        collection.entries.push(FontEntry {
+            path: Some(file.clone()),
+            index: face.index,
+            font: None,
+        });
+
+        let entry = collection.entries.len() - 1;
+        let mut named = false;
 
-            log::info!("Loaded {} fonts", counter);
+        // Family name refers to regular style of the family, if there is one
+        if face.style == fontdb::Style::Normal && face.weight == fontdb::Weight::NORMAL {
+            named |= collection.add_name(face.family.clone(), entry);
         }
-        Err(e) => {
-            if let std::io::ErrorKind::NotFound = e.kind() {
-                log::info!("Loaded no external fonts, missing fonts folder");
-            } else {
-                log::error!("Failed to locate fonts folder: {:?}", e);
+
+        named |= collection.add_name(face.post_script_name.clone(), entry);
+
+        // Fonts used to be referred to by file name
+        if index < user_fonts {
+            if let Some(file_name) = file.file_name() {
+                named |= collection.add_name(file_name.to_string_lossy().to_string(), entry);
             }
         }
+
+        if named {
+            counter += 1;
+        }
     }
+
+    log::info!("Found {} fonts", counter);
 }
 
-/// Gets font reference from global collection
+/// Gets font reference from global collection, reading the font if it wasn't used before
 pub fn get_font_from_collection(name: &str) -> Option<Arc<Font<'static>>> {
-    for (font_name, font) in unsafe { &LOADED_FONTS } {
-        if *font_name == name {
-            return Some(font.clone())
+    let collection = LOADED_FONTS.read().unwrap();
+    let entry = collection.names.iter().find(|(n, _)| n == name)?.1;
+
+    if let Some(font) = &collection.entries[entry].font {
+        return Some(font.clone());
+    }
+
+    let path = collection.entries[entry].path.clone()?;
+    let index = collection.entries[entry].index;
+    drop(collection);
+
+    let font = match fs::read(&path) {
+        Ok(bytes) => {
+            let font = Font::try_from_vec_and_index(bytes, index).map(Arc::new);
+
+            if font.is_none() {
+                log::error!("Failed to load {:?}: Not a font file", path);
+            }
+
+            font
+        }
+        Err(err) => {
+            log::error!("Failed to load {:?}: {}", path, err);
+            None
         }
+    };
+
+    let mut collection = LOADED_FONTS.write().unwrap();
+    let entry = &mut collection.entries[entry];
+
+    // Not trying to read fonts that failed to load again
+    if font.is_none() {
+        entry.path = None;
     }
 
-    None
+    entry.font = font.clone();
+
+    font
+}
+
+/// Returns file and face index of font that was found in a folder
+pub(crate) fn font_file(name: &str) -> Option<(PathBuf, u32)> {
+    let collection = LOADED_FONTS.read().unwrap();
+    let entry = collection.names.iter().find(|(n, _)| n == name)?.1;
+
+    collection.entries[entry].path.clone().map(|path| (path, collection.entries[entry].index))
 }
 
 /// Returns names of fonts in global collection
 pub fn get_font_names() -> Vec<String> {
-    unsafe { &LOADED_FONTS }.iter().map(|(n, ..)| n.to_string()).collect()
-}
\ No newline at end of file
+    LOADED_FONTS.read().unwrap().names.iter().map(|(n, ..)| n.to_string()).collect()
+}
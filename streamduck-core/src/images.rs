@@ -1,14 +1,14 @@
 use std::hash::{Hash, Hasher};
 use std::io::Cursor;
 use std::time::Duration;
-use image::{AnimationDecoder, DynamicImage, Frame, ImageFormat};
+use image::{AnimationDecoder, DynamicImage, Frame, GenericImageView, ImageFormat};
 use image::codecs::gif::GifDecoder;
 use image::codecs::png::PngDecoder;
 use image::io::Reader;
 use itertools::Itertools;
 use serde::{Serialize, Deserialize};
 use streamdeck::{DeviceImage, ImageMode, Kind};
-use crate::thread::util::resize_for_streamdeck;
+use crate::thread::util::{fit_image, ImageFit, resize_for_streamdeck, strip_alpha};
 
 /// Enum that represents various types of images Streamduck will use
 #[derive(Clone)]
@@ -20,9 +20,42 @@ pub enum SDImage {
     AnimatedImage(Vec<AnimationFrame>)
 }
 
+/// How images are converted when they're added into image collection
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct ImageConversion {
+    /// How images with aspect ratio different from buttons are fitted, cropped by default
+    #[serde(default)]
+    pub fit: ImageFit,
+    /// If transparency should be removed, so images don't need to be blended when drawn
+    #[serde(default)]
+    pub strip_alpha: bool,
+}
+
+impl ImageConversion {
+    /// Converts image into specified size, images that already have the size are only stripped of alpha if needed
+    pub fn apply(&self, size: (usize, usize), image: DynamicImage) -> DynamicImage {
+        let image = match self.fit {
+            // Tiling doesn't make sense for images that are stored in button size
+            ImageFit::Cover | ImageFit::Tile => resize_for_streamdeck(size, image),
+            fit => fit_image(size, &image, fit),
+        };
+
+        if self.strip_alpha {
+            strip_alpha(&image)
+        } else {
+            image
+        }
+    }
+}
+
 impl SDImage {
     /// Attempts to decode base64 image to SDImage
     pub fn from_base64(image: &str, size: (usize, usize)) -> Result<SDImage, ImageDeserializationError> {
+        SDImage::from_base64_converted(image, size, ImageConversion::default())
+    }
+
+    /// Attempts to decode base64 image to SDImage, converting it with specified conversion
+    pub fn from_base64_converted(image: &str, size: (usize, usize), conversion: ImageConversion) -> Result<SDImage, ImageDeserializationError> {
         let bytes = base64::decode(image)?;
 
         let decoder = Reader::new(Cursor::new(bytes)).with_guessed_format()?;
@@ -36,19 +69,19 @@ impl SDImage {
                     if decoder.is_apng() {
                         let decoder = decoder.apng();
 
-                        Ok(SDImage::AnimatedImage(convert_frames(decoder.into_frames().collect_frames()?, size)))
+                        Ok(SDImage::AnimatedImage(convert_frames_with(decoder.into_frames().collect_frames()?, size, conversion)))
                     } else {
-                        Ok(SDImage::SingleImage(resize_for_streamdeck(size, DynamicImage::from_decoder(decoder)?)))
+                        Ok(SDImage::SingleImage(conversion.apply(size, DynamicImage::from_decoder(decoder)?)))
                     }
                 }
 
                 ImageFormat::Gif => {
                     let decoder = GifDecoder::new(decoder.into_inner())?;
-                    Ok(SDImage::AnimatedImage(convert_frames(decoder.into_frames().collect_frames()?, size)))
+                    Ok(SDImage::AnimatedImage(convert_frames_with(decoder.into_frames().collect_frames()?, size, conversion)))
                 }
 
                 _ => {
-                    Ok(SDImage::SingleImage(resize_for_streamdeck(size, decoder.decode()?)))
+                    Ok(SDImage::SingleImage(conversion.apply(size, decoder.decode()?)))
                 }
             }
         } else {
@@ -71,6 +104,29 @@ impl SDImage {
             SDImage::AnimatedImage(frames) => frames[0].image.clone()
         }
     }
+
+    /// Checks if image and all of its frames are of specified size
+    pub fn has_size(&self, size: (usize, usize)) -> bool {
+        let size = (size.0 as u32, size.1 as u32);
+
+        match self {
+            SDImage::SingleImage(img) => img.dimensions() == size,
+            SDImage::AnimatedImage(frames) => frames.iter().all(|x| x.image.dimensions() == size)
+        }
+    }
+
+    /// Converts image and all of its frames into specified size
+    pub fn converted(self, size: (usize, usize), conversion: ImageConversion) -> SDImage {
+        match self {
+            SDImage::SingleImage(img) => SDImage::SingleImage(conversion.apply(size, img)),
+            SDImage::AnimatedImage(frames) => SDImage::AnimatedImage(frames.into_iter()
+                .map(|x| AnimationFrame {
+                    image: conversion.apply(size, x.image),
+                    ..x
+                })
+                .collect())
+        }
+    }
 }
 
 /// Enum that represents serialized variant of [SDImage]
@@ -102,6 +158,24 @@ impl SDSerializedImage {
             SDSerializedImage::AnimatedImage(_) => true,
         }
     }
+
+    /// Gets dimensions of image or its first frame, only image header is read
+    pub fn dimensions(&self) -> Result<(u32, u32), ImageDeserializationError> {
+        let bytes = base64::decode(self.as_image_blob()?)?;
+        Ok(Reader::new(Cursor::new(bytes)).with_guessed_format()?.into_dimensions()?)
+    }
+
+    /// Size of encoded image data in bytes, all frames included
+    pub fn byte_size(&self) -> usize {
+        fn decoded_size(blob: &str) -> usize {
+            (blob.len() / 4 * 3).saturating_sub(blob.chars().rev().take_while(|x| *x == '=').count())
+        }
+
+        match self {
+            SDSerializedImage::SingleImage(blob) => decoded_size(blob),
+            SDSerializedImage::AnimatedImage(frames) => frames.iter().map(|x| decoded_size(&x.image)).sum(),
+        }
+    }
 }
 
 /// Frame of animated image
@@ -114,12 +188,17 @@ pub struct AnimationFrame {
 
 /// Converts [Frame] vector to [AnimationFrame]
 pub fn convert_frames(frames: Vec<Frame>, size: (usize, usize)) -> Vec<AnimationFrame> {
+    convert_frames_with(frames, size, ImageConversion::default())
+}
+
+/// Converts [Frame] vector to [AnimationFrame], converting every frame with specified conversion
+pub fn convert_frames_with(frames: Vec<Frame>, size: (usize, usize), conversion: ImageConversion) -> Vec<AnimationFrame> {
     frames.into_iter()
         .enumerate()
         .map(|(i, x)| {
             let delay = Duration::from(x.delay()).as_secs_f32();
             AnimationFrame {
-                image: resize_for_streamdeck(size, DynamicImage::from(x.into_buffer())),
+                image: conversion.apply(size, DynamicImage::from(x.into_buffer())),
                 index: i,
                 delay
             }
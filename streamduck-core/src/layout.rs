@@ -0,0 +1,486 @@
+//! Declarative layout definitions, for managing device layouts with hand-written YAML files
+//!
+//! Buttons are maps of component names to component values. Folders are defined inline with
+//! `folder: {name: ..., buttons: ...}` and linked with `folder_link: {name: ...}`, folder IDs are
+//! resolved by name, reusing IDs from current config so re-applying same file changes nothing
+//!
+//! Layout templates are layout definitions with `${VARIABLE}` placeholders in string values,
+//! variables can be declared with description and default value in top level `variables` map
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::config::DeviceConfig;
+use crate::core::button::{Button, Component, parse_button_to_component};
+use crate::core::{ButtonMap, RawButtonPanel};
+use crate::modules::folders::{BackButtonMode, FolderComponent, FolderLinkComponent};
+
+/// Key that folder module uses to keep folder contents in plugin data
+pub(crate) const FOLDERS_KEY: &str = "folders";
+
+/// Top level key of layout template that contains variable declarations
+const TEMPLATE_VARIABLES_KEY: &str = "variables";
+
+/// Map of folder IDs to folder contents, as stored by folder module
+pub(crate) type FolderMap = HashMap<String, RawButtonPanel>;
+
+/// Whole device layout
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LayoutDefinition {
+    /// Brightness of the device, current brightness is kept if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brightness: Option<u8>,
+    /// Display name of root panel, current name is kept if empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(default)]
+    pub buttons: BTreeMap<u8, ButtonDefinition>,
+}
+
+/// Button as map of component names to component values
+pub type ButtonDefinition = BTreeMap<String, Value>;
+
+/// Value of folder component in layout definition
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FolderDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub buttons: BTreeMap<u8, ButtonDefinition>,
+    #[serde(default)]
+    pub thumbnail: bool,
+    #[serde(default)]
+    pub back_button: BackButtonMode,
+}
+
+/// Value of folder link component in layout definition
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FolderLinkDefinition {
+    pub name: String,
+}
+
+/// Variable declared by layout template
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TemplateVariable {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    /// Value used if variable wasn't provided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+/// Layout definition with `${VARIABLE}` placeholders, that can be instantiated with different values
+#[derive(Debug, Clone)]
+pub struct LayoutTemplate {
+    /// Variables declared by the template, placeholders that weren't declared are added as required variables
+    pub variables: BTreeMap<String, TemplateVariable>,
+    content: serde_yaml::Value,
+}
+
+/// Layout definition converted into device config structures
+#[derive(Debug, Clone)]
+pub struct CompiledLayout {
+    pub brightness: Option<u8>,
+    pub layout: RawButtonPanel,
+    pub folders: FolderMap,
+}
+
+/// Single difference between current config and layout definition
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum LayoutChange {
+    BrightnessChanged { from: u8, to: u8 },
+    RootRenamed { name: String },
+    FolderAdded { folder: String },
+    FolderRemoved { folder: String },
+    /// Folder is [None] for root panel
+    ButtonAdded { folder: Option<String>, key: u8 },
+    ButtonChanged { folder: Option<String>, key: u8 },
+    ButtonRemoved { folder: Option<String>, key: u8 },
+}
+
+/// Errors that can occur while turning layout definition into config
+#[derive(Debug)]
+pub enum LayoutError {
+    ParseError(String),
+    /// Folder names are used as references, so they have to be unique
+    DuplicateFolder(String),
+    /// Folder link refers to folder that isn't defined
+    UnknownFolder(String),
+    /// Folder component doesn't follow [FolderDefinition]
+    InvalidFolder(u8, String),
+    /// Template variables that have no value and no default
+    MissingVariables(Vec<String>),
+}
+
+impl From<serde_yaml::Error> for LayoutError {
+    fn from(err: serde_yaml::Error) -> Self {
+        LayoutError::ParseError(err.to_string())
+    }
+}
+
+impl LayoutDefinition {
+    /// Parses layout definition from YAML
+    pub fn from_yaml(content: &str) -> Result<LayoutDefinition, LayoutError> {
+        Ok(serde_yaml::from_str(content)?)
+    }
+
+    /// Serializes layout definition to YAML
+    pub fn to_yaml(&self) -> Result<String, LayoutError> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+
+    /// Creates layout definition out of device config, folders are inlined by name
+    pub fn from_config(config: &DeviceConfig) -> LayoutDefinition {
+        let folders = folders_of(config);
+
+        let names: HashMap<String, String> = folders.iter()
+            .map(|(id, folder)| (id.clone(), folder.display_name.clone()))
+            .collect();
+
+        LayoutDefinition {
+            brightness: Some(config.brightness),
+            name: config.layout.display_name.clone(),
+            buttons: define_buttons(&config.layout.buttons, &folders, &names, &mut HashSet::new()),
+        }
+    }
+
+    /// Resolves folders and turns definition into config structures, IDs of folders that exist in current config are kept
+    pub fn compile(&self, current: &DeviceConfig) -> Result<CompiledLayout, LayoutError> {
+        let existing: HashMap<String, String> = folders_of(current).into_iter()
+            .map(|(id, folder)| (folder.display_name, id))
+            .collect();
+
+        // Assigning IDs to every folder first, so links can refer to folders defined anywhere
+        let mut ids = HashMap::new();
+        collect_folder_ids(&self.buttons, &existing, &mut ids)?;
+
+        let mut folders = FolderMap::new();
+        let buttons = compile_buttons(&self.buttons, &ids, &mut folders)?;
+
+        let display_name = if self.name.is_empty() { current.layout.display_name.clone() } else { self.name.clone() };
+
+        Ok(CompiledLayout {
+            brightness: self.brightness,
+            layout: RawButtonPanel {
+                display_name,
+                data: current.layout.data.clone(),
+                buttons
+            },
+            folders
+        })
+    }
+}
+
+impl LayoutTemplate {
+    /// Parses layout template from YAML
+    pub fn from_yaml(content: &str) -> Result<LayoutTemplate, LayoutError> {
+        let mut content: serde_yaml::Value = serde_yaml::from_str(content)?;
+
+        let mut variables: BTreeMap<String, TemplateVariable> = match content.as_mapping_mut().and_then(|x| x.remove(TEMPLATE_VARIABLES_KEY)) {
+            Some(value) => serde_yaml::from_value(value)?,
+            None => Default::default()
+        };
+
+        let mut used = BTreeSet::new();
+        collect_placeholders(&content, &mut used);
+
+        for name in used {
+            variables.entry(name).or_default();
+        }
+
+        Ok(LayoutTemplate {
+            variables,
+            content
+        })
+    }
+
+    /// Substitutes placeholders with provided values or defaults. If key count is provided, buttons that don't fit on the device are dropped
+    pub fn instantiate(&self, values: &HashMap<String, String>, key_count: Option<u8>) -> Result<LayoutDefinition, LayoutError> {
+        let mut resolved = HashMap::new();
+        let mut missing = vec![];
+
+        for (name, variable) in &self.variables {
+            match values.get(name).or(variable.default.as_ref()) {
+                Some(value) => { resolved.insert(name.clone(), value.clone()); }
+                None => missing.push(name.clone()),
+            }
+        }
+
+        if !missing.is_empty() {
+            return Err(LayoutError::MissingVariables(missing));
+        }
+
+        let mut content = self.content.clone();
+        substitute_placeholders(&mut content, &resolved);
+
+        let mut definition: LayoutDefinition = serde_yaml::from_value(content)?;
+
+        if let Some(key_count) = key_count {
+            trim_buttons(&mut definition.buttons, key_count);
+        }
+
+        Ok(definition)
+    }
+}
+
+impl CompiledLayout {
+    /// Lists changes that applying the layout would make to the config
+    pub fn diff(&self, current: &DeviceConfig) -> Vec<LayoutChange> {
+        let mut changes = vec![];
+
+        if let Some(brightness) = self.brightness {
+            if brightness != current.brightness {
+                changes.push(LayoutChange::BrightnessChanged { from: current.brightness, to: brightness });
+            }
+        }
+
+        if self.layout.display_name != current.layout.display_name {
+            changes.push(LayoutChange::RootRenamed { name: self.layout.display_name.clone() });
+        }
+
+        diff_buttons(None, &current.layout.buttons, &self.layout.buttons, &mut changes);
+
+        let current_folders = folders_of(current);
+
+        for (id, folder) in sorted(&current_folders) {
+            if !self.folders.contains_key(id) {
+                changes.push(LayoutChange::FolderRemoved { folder: folder.display_name.clone() });
+            }
+        }
+
+        for (id, folder) in sorted(&self.folders) {
+            if let Some(current_folder) = current_folders.get(id) {
+                diff_buttons(Some(&folder.display_name), &current_folder.buttons, &folder.buttons, &mut changes);
+            } else {
+                changes.push(LayoutChange::FolderAdded { folder: folder.display_name.clone() });
+            }
+        }
+
+        changes
+    }
+
+    /// Applies only the changes to the config, buttons that didn't change are left untouched. Returns list of applied changes
+    pub fn apply(&self, config: &mut DeviceConfig) -> Vec<LayoutChange> {
+        let changes = self.diff(config);
+
+        if changes.is_empty() {
+            return changes;
+        }
+
+        if let Some(brightness) = self.brightness {
+            config.brightness = brightness;
+        }
+
+        config.layout.display_name = self.layout.display_name.clone();
+        apply_buttons(&mut config.layout.buttons, &self.layout.buttons);
+
+        let mut folders = folders_of(config);
+        folders.retain(|id, _| self.folders.contains_key(id));
+
+        for (id, folder) in &self.folders {
+            if let Some(current) = folders.get_mut(id) {
+                current.display_name = folder.display_name.clone();
+                apply_buttons(&mut current.buttons, &folder.buttons);
+            } else {
+                folders.insert(id.clone(), folder.clone());
+            }
+        }
+
+        config.plugin_data.insert(FOLDERS_KEY.to_string(), serde_json::to_value(folders).unwrap());
+
+        changes
+    }
+}
+
+/// Finds names of all placeholders used in string values
+fn collect_placeholders(value: &serde_yaml::Value, names: &mut BTreeSet<String>) {
+    match value {
+        serde_yaml::Value::String(str) => {
+            let mut rest = str.as_str();
+
+            while let Some(start) = rest.find("${") {
+                rest = &rest[start + 2..];
+
+                if let Some(end) = rest.find('}') {
+                    names.insert(rest[..end].to_string());
+                    rest = &rest[end + 1..];
+                } else {
+                    break;
+                }
+            }
+        }
+
+        serde_yaml::Value::Sequence(sequence) => sequence.iter().for_each(|x| collect_placeholders(x, names)),
+        serde_yaml::Value::Mapping(mapping) => mapping.values().for_each(|x| collect_placeholders(x, names)),
+        serde_yaml::Value::Tagged(tagged) => collect_placeholders(&tagged.value, names),
+        _ => {}
+    }
+}
+
+/// Replaces placeholders in string values with variable values
+fn substitute_placeholders(value: &mut serde_yaml::Value, values: &HashMap<String, String>) {
+    match value {
+        serde_yaml::Value::String(str) => {
+            for (name, value) in values {
+                *str = str.replace(&format!("${{{}}}", name), value);
+            }
+        }
+
+        serde_yaml::Value::Sequence(sequence) => sequence.iter_mut().for_each(|x| substitute_placeholders(x, values)),
+        serde_yaml::Value::Mapping(mapping) => mapping.values_mut().for_each(|x| substitute_placeholders(x, values)),
+        serde_yaml::Value::Tagged(tagged) => substitute_placeholders(&mut tagged.value, values),
+        _ => {}
+    }
+}
+
+/// Drops buttons that are out of device's key range, including buttons inside of folders
+fn trim_buttons(buttons: &mut BTreeMap<u8, ButtonDefinition>, key_count: u8) {
+    buttons.retain(|key, _| *key < key_count);
+
+    for button in buttons.values_mut() {
+        if let Some(value) = button.get_mut(FolderComponent::NAME) {
+            if let Ok(mut folder) = serde_json::from_value::<FolderDefinition>(value.clone()) {
+                trim_buttons(&mut folder.buttons, key_count);
+                *value = serde_json::to_value(folder).unwrap();
+            }
+        }
+    }
+}
+
+/// Reads folders from plugin data of folder module
+pub(crate) fn folders_of(config: &DeviceConfig) -> FolderMap {
+    config.plugin_data.get(FOLDERS_KEY)
+        .and_then(|x| serde_json::from_value(x.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Sorts map by keys, so changes are listed in stable order
+fn sorted<V>(map: &HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut entries: Vec<(&String, &V)> = map.iter().collect();
+    entries.sort_by_key(|(key, _)| *key);
+    entries
+}
+
+fn define_buttons(buttons: &ButtonMap, folders: &FolderMap, names: &HashMap<String, String>, visited: &mut HashSet<String>) -> BTreeMap<u8, ButtonDefinition> {
+    buttons.iter()
+        .map(|(key, button)| {
+            let mut definition: ButtonDefinition = button.0.clone().into_iter().collect();
+
+            if let Ok(folder) = parse_button_to_component::<FolderComponent>(button) {
+                // Folder that contains itself is left as a reference to avoid infinite recursion
+                if visited.insert(folder.id.clone()) {
+                    let contents = folders.get(&folder.id).map(|x| define_buttons(&x.buttons, folders, names, visited)).unwrap_or_default();
+
+                    definition.insert(FolderComponent::NAME.to_string(), serde_json::to_value(FolderDefinition {
+                        name: folder.name,
+                        buttons: contents,
+                        thumbnail: folder.thumbnail,
+                        back_button: folder.back_button
+                    }).unwrap());
+                }
+            }
+
+            if let Ok(link) = parse_button_to_component::<FolderLinkComponent>(button) {
+                if let Some(name) = names.get(&link.id) {
+                    definition.insert(FolderLinkComponent::NAME.to_string(), serde_json::to_value(FolderLinkDefinition {
+                        name: name.clone()
+                    }).unwrap());
+                }
+            }
+
+            (*key, definition)
+        })
+        .collect()
+}
+
+fn collect_folder_ids(buttons: &BTreeMap<u8, ButtonDefinition>, existing: &HashMap<String, String>, ids: &mut HashMap<String, String>) -> Result<(), LayoutError> {
+    for (key, button) in buttons {
+        if let Some(value) = button.get(FolderComponent::NAME) {
+            let folder: FolderDefinition = serde_json::from_value(value.clone())
+                .map_err(|err| LayoutError::InvalidFolder(*key, err.to_string()))?;
+
+            if ids.contains_key(&folder.name) {
+                return Err(LayoutError::DuplicateFolder(folder.name));
+            }
+
+            let id = existing.get(&folder.name).cloned().unwrap_or_else(|| new_folder_id(existing, ids));
+            ids.insert(folder.name.clone(), id);
+
+            collect_folder_ids(&folder.buttons, existing, ids)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Generates random folder ID the same way folder module does, making sure it's not taken
+fn new_folder_id(existing: &HashMap<String, String>, ids: &HashMap<String, String>) -> String {
+    loop {
+        let id: String = rand::thread_rng().sample_iter(&Alphanumeric).take(16).map(char::from).collect();
+
+        if !existing.values().any(|x| *x == id) && !ids.values().any(|x| *x == id) {
+            return id;
+        }
+    }
+}
+
+fn compile_buttons(buttons: &BTreeMap<u8, ButtonDefinition>, ids: &HashMap<String, String>, folders: &mut FolderMap) -> Result<ButtonMap, LayoutError> {
+    let mut result = ButtonMap::new();
+
+    for (key, definition) in buttons {
+        let mut button = Button(definition.clone().into_iter().collect());
+
+        if let Some(value) = definition.get(FolderComponent::NAME) {
+            let folder: FolderDefinition = serde_json::from_value(value.clone())
+                .map_err(|err| LayoutError::InvalidFolder(*key, err.to_string()))?;
+            let id = ids[&folder.name].clone();
+
+            let contents = compile_buttons(&folder.buttons, ids, folders)?;
+            folders.insert(id.clone(), RawButtonPanel {
+                display_name: folder.name.clone(),
+                data: Default::default(),
+                buttons: contents
+            });
+
+            button.insert_component(FolderComponent { id, name: folder.name, thumbnail: folder.thumbnail, back_button: folder.back_button }).ok();
+        }
+
+        if let Some(value) = definition.get(FolderLinkComponent::NAME) {
+            if let Ok(link) = serde_json::from_value::<FolderLinkDefinition>(value.clone()) {
+                let id = ids.get(&link.name).cloned().ok_or(LayoutError::UnknownFolder(link.name))?;
+                button.insert_component(FolderLinkComponent { id }).ok();
+            }
+        }
+
+        result.insert(*key, button);
+    }
+
+    Ok(result)
+}
+
+fn diff_buttons(folder: Option<&String>, current: &ButtonMap, new: &ButtonMap, changes: &mut Vec<LayoutChange>) {
+    let mut keys: Vec<u8> = current.keys().chain(new.keys()).copied().collect::<HashSet<u8>>().into_iter().collect();
+    keys.sort();
+
+    for key in keys {
+        let folder = folder.cloned();
+
+        match (current.get(&key), new.get(&key)) {
+            (None, Some(_)) => changes.push(LayoutChange::ButtonAdded { folder, key }),
+            (Some(_), None) => changes.push(LayoutChange::ButtonRemoved { folder, key }),
+            (Some(old), Some(new)) if old.0 != new.0 => changes.push(LayoutChange::ButtonChanged { folder, key }),
+            _ => {}
+        }
+    }
+}
+
+fn apply_buttons(current: &mut ButtonMap, new: &ButtonMap) {
+    current.retain(|key, _| new.contains_key(key));
+
+    for (key, button) in new {
+        if current.get(key).map_or(true, |x| x.0 != button.0) {
+            current.insert(*key, button.clone());
+        }
+    }
+}
@@ -13,7 +13,21 @@ pub mod images;
 
 pub mod versions;
 pub mod config;
+/// Declarative layout definitions
+pub mod layout;
+/// Component value presets shared between buttons
+pub mod presets;
+/// Remapping of layouts between device kinds
+pub mod adapt;
+/// Buttons that can be moved between devices
+pub mod portable;
+/// Checks of device configs before they're applied
+pub mod review;
 pub mod socket;
+/// Log records tagged with device and module, capture of recent records
+pub mod logging;
+/// Performance counters exported by the daemon
+pub mod metrics;
 pub mod thread;
 
 pub use streamdeck;
@@ -0,0 +1,245 @@
+//! Log records tagged with device and module they came from, and capture of recent records for diagnostics
+//!
+//! Threads that do work for devices or modules set [LogContext], records are tagged with context of the thread they were logged on.
+//! Daemon wraps its logger with [CapturingLogger], so recent records can be requested or streamed by clients
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use serde::{Deserialize, Serialize};
+use crate::socket::{SocketPacket, SocketPool};
+
+/// Amount of recent records that are kept
+pub const LOG_BUFFER_SIZE: usize = 1000;
+
+/// Type of packets that streamed log records are sent in
+pub const LOG_PACKET_TYPE: &str = "log";
+
+thread_local! {
+    static CONTEXT: RefCell<LogContext> = RefCell::new(LogContext::default());
+}
+
+/// Device and module that current thread is doing work for
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct LogContext {
+    pub device: Option<String>,
+    pub module: Option<String>,
+}
+
+/// Gets log context of current thread
+pub fn log_context() -> LogContext {
+    CONTEXT.with(|x| x.borrow().clone())
+}
+
+/// Sets log context for rest of the thread, for threads that only work for a single device
+pub fn set_thread_log_context(device: Option<&str>, module: Option<&str>) {
+    CONTEXT.with(|x| {
+        *x.borrow_mut() = LogContext {
+            device: device.map(|x| x.to_string()),
+            module: module.map(|x| x.to_string())
+        }
+    });
+}
+
+/// Runs function with log context, fields that aren't provided keep values of outer context
+pub fn with_log_context<T>(device: Option<&str>, module: Option<&str>, func: impl FnOnce() -> T) -> T {
+    let previous = log_context();
+
+    CONTEXT.with(|x| {
+        let mut context = x.borrow_mut();
+
+        if let Some(device) = device {
+            context.device = Some(device.to_string());
+        }
+
+        if let Some(module) = module {
+            context.module = Some(module.to_string());
+        }
+    });
+
+    let result = func();
+
+    CONTEXT.with(|x| *x.borrow_mut() = previous);
+
+    result
+}
+
+/// Captured log record
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LogRecord {
+    /// Increasing number of the record, can be used to request only newer records
+    pub id: u64,
+    /// Unix timestamp in milliseconds
+    pub timestamp: u64,
+    pub level: String,
+    /// Rust module path that record was logged from
+    pub target: String,
+    /// Serial number of device that record is related to
+    pub device: Option<String>,
+    /// Name of streamduck module that record is related to
+    pub module: Option<String>,
+    pub message: String,
+}
+
+/// Filter for captured log records, all conditions have to match
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct LogFilter {
+    /// Only records with higher ID
+    #[serde(default)]
+    pub after: Option<u64>,
+    /// Most verbose level to include, eg. "warn" includes warnings and errors
+    #[serde(default)]
+    pub level: Option<String>,
+    /// Only records related to the device
+    #[serde(default)]
+    pub device: Option<String>,
+    /// Only records related to the module
+    #[serde(default)]
+    pub module: Option<String>,
+}
+
+impl LogFilter {
+    /// Checks if record passes the filter
+    pub fn matches(&self, record: &LogRecord) -> bool {
+        if let Some(after) = self.after {
+            if record.id <= after {
+                return false;
+            }
+        }
+
+        if let Some(level) = self.level.as_ref().and_then(|x| Level::from_str(x).ok()) {
+            if Level::from_str(&record.level).map_or(false, |x| x > level) {
+                return false;
+            }
+        }
+
+        if self.device.is_some() && self.device != record.device {
+            return false;
+        }
+
+        if self.module.is_some() && self.module != record.module {
+            return false;
+        }
+
+        true
+    }
+}
+
+struct LogCapture {
+    records: VecDeque<LogRecord>,
+    next_id: u64,
+    streams: Vec<(Arc<SocketPool>, LogFilter)>,
+}
+
+static CAPTURE: Mutex<LogCapture> = Mutex::new(LogCapture {
+    records: VecDeque::new(),
+    next_id: 1,
+    streams: Vec::new()
+});
+
+fn capture(record: &Record) {
+    let context = log_context();
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)
+        .map(|x| x.as_millis() as u64)
+        .unwrap_or_default();
+
+    let mut capture = CAPTURE.lock().unwrap();
+
+    let record = LogRecord {
+        id: capture.next_id,
+        timestamp,
+        level: record.level().to_string(),
+        target: record.target().to_string(),
+        device: context.device,
+        module: context.module,
+        message: record.args().to_string()
+    };
+
+    capture.next_id += 1;
+
+    capture.streams.retain(|(pool, _)| pool.is_open());
+
+    for (pool, filter) in &capture.streams {
+        if filter.matches(&record) {
+            pool.add_message(SocketPacket {
+                ty: LOG_PACKET_TYPE.to_string(),
+                requester: None,
+                data: Some(serde_json::to_value(&record).unwrap())
+            });
+        }
+    }
+
+    capture.records.push_back(record);
+
+    while capture.records.len() > LOG_BUFFER_SIZE {
+        capture.records.pop_front();
+    }
+}
+
+/// Gets up to limit of most recent captured records that pass the filter, oldest first
+pub fn recent_logs(filter: &LogFilter, limit: usize) -> Vec<LogRecord> {
+    let capture = CAPTURE.lock().unwrap();
+
+    let mut records: Vec<LogRecord> = capture.records.iter().rev()
+        .filter(|x| filter.matches(x))
+        .take(limit)
+        .cloned()
+        .collect();
+
+    records.reverse();
+    records
+}
+
+/// Starts sending records that pass the filter to connection that uses the pool, stops if filter is [None]
+pub fn stream_logs(pool: &Arc<SocketPool>, filter: Option<LogFilter>) {
+    let mut capture = CAPTURE.lock().unwrap();
+
+    capture.streams.retain(|(x, _)| !Arc::ptr_eq(x, pool));
+
+    if let Some(filter) = filter {
+        capture.streams.push((pool.clone(), filter));
+    }
+}
+
+/// Logger that captures records before passing them to inner logger
+pub struct CapturingLogger {
+    inner: Box<dyn Log>,
+}
+
+impl CapturingLogger {
+    pub fn new(inner: Box<dyn Log>) -> CapturingLogger {
+        CapturingLogger {
+            inner
+        }
+    }
+
+    /// Sets the logger as global logger
+    pub fn install(self, max_level: LevelFilter) -> Result<(), SetLoggerError> {
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(max_level);
+        Ok(())
+    }
+}
+
+impl Log for CapturingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        self.inner.log(record);
+        capture(record);
+    }
+
+    fn flush(&self) {
+        self.inner.flush()
+    }
+}
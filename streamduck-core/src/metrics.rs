@@ -0,0 +1,128 @@
+//! Performance counters of the daemon, exported in Prometheus text format
+//!
+//! Recording does nothing until metrics are enabled with [enable_metrics], so there's no cost when exporter isn't used
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static METRICS: Mutex<Metrics> = Mutex::new(Metrics {
+    render_times: BTreeMap::new(),
+    write_latencies: BTreeMap::new(),
+    key_events: BTreeMap::new(),
+    plugin_callbacks: BTreeMap::new()
+});
+
+/// Amount and total duration of measured operations
+#[derive(Debug, Clone, Copy, Default)]
+struct Timing {
+    count: u64,
+    total: f64,
+}
+
+impl Timing {
+    fn add(&mut self, duration: Duration) {
+        self.count += 1;
+        self.total += duration.as_secs_f64();
+    }
+}
+
+struct Metrics {
+    /// Frame render times by device serial
+    render_times: BTreeMap<String, Timing>,
+    /// USB image write latencies by device serial
+    write_latencies: BTreeMap<String, Timing>,
+    /// Key presses and releases by device serial
+    key_events: BTreeMap<String, u64>,
+    /// Plugin callback durations by plugin name and callback
+    plugin_callbacks: BTreeMap<(String, String), Timing>,
+}
+
+/// Starts recording metrics
+pub fn enable_metrics() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Checks if metrics are being recorded
+pub fn metrics_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Records time it took device thread to render a frame
+pub fn record_render_time(serial: &str, duration: Duration) {
+    if metrics_enabled() {
+        METRICS.lock().unwrap().render_times.entry(serial.to_string()).or_default().add(duration);
+    }
+}
+
+/// Records time it took to write an image to the device
+pub fn record_write_latency(serial: &str, duration: Duration) {
+    if metrics_enabled() {
+        METRICS.lock().unwrap().write_latencies.entry(serial.to_string()).or_default().add(duration);
+    }
+}
+
+/// Records key press or release on the device
+pub fn record_key_event(serial: &str) {
+    if metrics_enabled() {
+        *METRICS.lock().unwrap().key_events.entry(serial.to_string()).or_default() += 1;
+    }
+}
+
+/// Records time plugin spent in a callback
+pub fn record_plugin_callback(plugin: &str, callback: &str, duration: Duration) {
+    if metrics_enabled() {
+        METRICS.lock().unwrap().plugin_callbacks.entry((plugin.to_string(), callback.to_string())).or_default().add(duration);
+    }
+}
+
+/// Escapes label value for Prometheus text format
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn write_timings<'a>(output: &mut String, name: &str, help: &str, timings: impl Iterator<Item=(String, &'a Timing)>) {
+    writeln!(output, "# HELP {} {}", name, help).ok();
+    writeln!(output, "# TYPE {} summary", name).ok();
+
+    for (labels, timing) in timings {
+        writeln!(output, "{}_sum{{{}}} {}", name, labels, timing.total).ok();
+        writeln!(output, "{}_count{{{}}} {}", name, labels, timing.count).ok();
+    }
+}
+
+/// Renders recorded metrics along with provided gauges in Prometheus text format
+pub fn render_metrics(devices: usize, socket_clients: usize) -> String {
+    let metrics = METRICS.lock().unwrap();
+    let mut output = String::new();
+
+    writeln!(output, "# HELP streamduck_devices Devices that are currently added to the daemon").ok();
+    writeln!(output, "# TYPE streamduck_devices gauge").ok();
+    writeln!(output, "streamduck_devices {}", devices).ok();
+
+    writeln!(output, "# HELP streamduck_socket_clients Clients currently connected to the daemon").ok();
+    writeln!(output, "# TYPE streamduck_socket_clients gauge").ok();
+    writeln!(output, "streamduck_socket_clients {}", socket_clients).ok();
+
+    write_timings(&mut output, "streamduck_render_seconds", "Time spent rendering frames",
+        metrics.render_times.iter().map(|(serial, timing)| (format!("device=\"{}\"", escape_label(serial)), timing)));
+
+    write_timings(&mut output, "streamduck_usb_write_seconds", "Time spent writing images to devices",
+        metrics.write_latencies.iter().map(|(serial, timing)| (format!("device=\"{}\"", escape_label(serial)), timing)));
+
+    writeln!(output, "# HELP streamduck_key_events_total Key presses and releases").ok();
+    writeln!(output, "# TYPE streamduck_key_events_total counter").ok();
+
+    for (serial, count) in &metrics.key_events {
+        writeln!(output, "streamduck_key_events_total{{device=\"{}\"}} {}", escape_label(serial), count).ok();
+    }
+
+    write_timings(&mut output, "streamduck_plugin_callback_seconds", "Time spent in plugin callbacks",
+        metrics.plugin_callbacks.iter().map(|((plugin, callback), timing)| (format!("plugin=\"{}\",callback=\"{}\"", escape_label(plugin), escape_label(callback)), timing)));
+
+    output
+}
@@ -0,0 +1,300 @@
+//! Brightness module, provides component that steps or sets brightness of the device it's pressed on
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use image::DynamicImage;
+use rusttype::Scale;
+use serde::{Deserialize, Serialize};
+use crate::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
+use crate::core::methods::{CoreHandle, set_brightness};
+use crate::core::UniqueButton;
+use crate::font::get_font_from_collection;
+use crate::modules::components::{ComponentDefinition, FieldError, map_ui_values, UIFieldType, UIFieldValue, UIValue};
+use crate::modules::events::SDCoreEvent;
+use crate::modules::{PluginMetadata, SDModule};
+use crate::thread::rendering::{ButtonBackground, ButtonText, RendererComponentBuilder};
+use crate::thread::util::{render_aligned_text_on_image, TextAlignment};
+use crate::util::straight_copy;
+use crate::versions::{CORE, CORE_EVENTS, CORE_METHODS, RENDERING};
+
+const MODULE_NAME: &str = "core/brightness";
+
+/// What pressing brightness button does
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum BrightnessAction {
+    Up,
+    Down,
+    Set,
+}
+
+impl Default for BrightnessAction {
+    fn default() -> Self {
+        BrightnessAction::Up
+    }
+}
+
+impl BrightnessAction {
+    pub const VARIANTS: [&'static str; 3] = ["Up", "Down", "Set"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BrightnessAction::Up => "Up",
+            BrightnessAction::Down => "Down",
+            BrightnessAction::Set => "Set",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<BrightnessAction> {
+        match name {
+            "Up" => Some(BrightnessAction::Up),
+            "Down" => Some(BrightnessAction::Down),
+            "Set" => Some(BrightnessAction::Set),
+            _ => None
+        }
+    }
+
+    /// Brightness that device should have after the press
+    pub fn apply(&self, current: u8, step: u8, value: u8) -> u8 {
+        match self {
+            BrightnessAction::Up => current.saturating_add(step).min(100),
+            BrightnessAction::Down => current.saturating_sub(step),
+            BrightnessAction::Set => value.min(100),
+        }
+    }
+}
+
+/// Component that changes brightness of the device
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BrightnessComponent {
+    #[serde(default)]
+    pub action: BrightnessAction,
+    /// Percents that up and down actions change brightness by
+    #[serde(default = "default_step")]
+    pub step: u8,
+    /// Percents that set action sets brightness to
+    #[serde(default = "default_value")]
+    pub value: u8,
+}
+
+fn default_step() -> u8 { 10 }
+fn default_value() -> u8 { 50 }
+
+impl Default for BrightnessComponent {
+    fn default() -> Self {
+        BrightnessComponent {
+            action: Default::default(),
+            step: default_step(),
+            value: default_value()
+        }
+    }
+}
+
+impl Component for BrightnessComponent {
+    const NAME: &'static str = "brightness";
+}
+
+fn current_brightness(core: &CoreHandle) -> u8 {
+    core.core.device_config.read().unwrap().brightness
+}
+
+#[derive(Default)]
+pub struct BrightnessModule;
+
+impl SDModule for BrightnessModule {
+    fn name(&self) -> String {
+        MODULE_NAME.to_string()
+    }
+
+    fn components(&self) -> HashMap<String, ComponentDefinition> {
+        let mut map = HashMap::new();
+
+        map.insert(BrightnessComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Brightness".to_string(),
+            description: "Steps brightness of the device up or down, or sets it to a value. Shows current brightness".to_string(),
+            default_looks: RendererComponentBuilder::new()
+                .background(ButtonBackground::Solid((40, 40, 40, 255)))
+                .add_text(ButtonText {
+                    text: "☀".to_string(),
+                    font: "default".to_string(),
+                    scale: (28.0, 28.0),
+                    alignment: TextAlignment::TopCenter,
+                    padding: 8,
+                    offset: (0.0, 0.0),
+                    color: (255, 255, 255, 255),
+                    shadow: None,
+                    auto_fit: false,
+                    word_wrap: false,
+                    marquee_speed: 0.0
+                })
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map
+    }
+
+    fn add_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            BrightnessComponent::NAME => {
+                button.insert_component(BrightnessComponent::default()).ok();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn remove_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            BrightnessComponent::NAME => {
+                button.remove_component::<BrightnessComponent>();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn paste_component(&self, _: CoreHandle, reference_button: &Button, new_button: &mut Button) {
+        straight_copy(reference_button, new_button, BrightnessComponent::NAME);
+    }
+
+    fn component_values(&self, _: CoreHandle, button: &Button, name: &str) -> Vec<UIValue> {
+        match name {
+            BrightnessComponent::NAME => {
+                if let Ok(component) = parse_button_to_component::<BrightnessComponent>(button) {
+                    let mut fields = vec![
+                        UIValue {
+                            name: "action".to_string(),
+                            display_name: "Action".to_string(),
+                            description: "Step brightness up, down or set it to a value".to_string(),
+                            ty: UIFieldType::Choice(BrightnessAction::VARIANTS.iter().map(|x| x.to_string()).collect()),
+                            value: UIFieldValue::Choice(component.action.name().to_string())
+                        }
+                    ];
+
+                    if component.action == BrightnessAction::Set {
+                        fields.push(UIValue {
+                            name: "value".to_string(),
+                            display_name: "Brightness".to_string(),
+                            description: "Brightness in percents to set".to_string(),
+                            ty: UIFieldType::InputFieldUnsignedInteger,
+                            value: UIFieldValue::InputFieldUnsignedInteger(component.value as u32)
+                        });
+                    } else {
+                        fields.push(UIValue {
+                            name: "step".to_string(),
+                            display_name: "Step".to_string(),
+                            description: "Percents to change brightness by".to_string(),
+                            ty: UIFieldType::InputFieldUnsignedInteger,
+                            value: UIFieldValue::InputFieldUnsignedInteger(component.step as u32)
+                        });
+                    }
+
+                    fields
+                } else {
+                    vec![]
+                }
+            }
+
+            _ => vec![],
+        }
+    }
+
+    fn set_component_value(&self, _: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+        match name {
+            BrightnessComponent::NAME => {
+                if let Ok(mut component) = parse_button_to_component::<BrightnessComponent>(button) {
+                    let change_map = map_ui_values(value);
+
+                    if let Some(value) = change_map.get("action") {
+                        if let Ok(action) = value.value.try_into_string() {
+                            if let Some(action) = BrightnessAction::from_name(&action) {
+                                component.action = action;
+                            }
+                        }
+                    }
+
+                    for (name, field) in [
+                        ("step", &mut component.step),
+                        ("value", &mut component.value),
+                    ] {
+                        if let Some(value) = change_map.get(name) {
+                            if let Ok(percents) = value.value.try_into_u32() {
+                                if percents > 100 {
+                                    return Err(vec![FieldError::new(name, "Should be between 0 and 100")]);
+                                }
+
+                                *field = percents as u8;
+                            }
+                        }
+                    }
+
+                    button.insert_component(component).ok();
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn listening_for(&self) -> Vec<String> {
+        vec![
+            BrightnessComponent::NAME.to_string()
+        ]
+    }
+
+    fn event(&self, core: CoreHandle, event: SDCoreEvent) {
+        match event {
+            SDCoreEvent::ButtonAction { pressed_button, .. } => {
+                if let Ok(component) = parse_unique_button_to_component::<BrightnessComponent>(&pressed_button) {
+                    let current = current_brightness(&core);
+                    let brightness = component.action.apply(current, component.step, component.value);
+
+                    if brightness != current {
+                        set_brightness(&core, brightness);
+
+                        if let Err(err) = core.core.config.save_device_config(&core.core.serial_number) {
+                            log::error!("Failed to save brightness of {}: {:?}", core.core.serial_number, err);
+                        }
+
+                        core.core.mark_for_redraw();
+                    }
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn render(&self, core: CoreHandle, button: &UniqueButton, frame: &mut DynamicImage) {
+        if let Ok(_) = parse_unique_button_to_component::<BrightnessComponent>(button) {
+            if let Some(font) = get_font_from_collection("default") {
+                let text = format!("{}%", current_brightness(&core));
+                render_aligned_text_on_image(core.core.image_size, frame, font.as_ref(), &text, Scale::uniform(18.0), TextAlignment::BottomCenter, 8, (0.0, 0.0), (255, 255, 255, 255));
+            }
+        }
+    }
+
+    fn render_hash(&self, core: CoreHandle, button: &UniqueButton, hash: &mut Box<dyn Hasher>) {
+        if let Ok(_) = parse_unique_button_to_component::<BrightnessComponent>(button) {
+            current_brightness(&core).hash(hash);
+        }
+    }
+
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata::from_literals(
+            MODULE_NAME,
+            "TheJebForge",
+            "Brightness module, provides brightness component",
+            "0.1",
+            &[
+                CORE,
+                CORE_METHODS,
+                CORE_EVENTS,
+                RENDERING
+            ]
+        )
+    }
+}
@@ -0,0 +1,277 @@
+//! Carousel component that rotates through pages of content on a single button, on a timer or on press
+//!
+//! Pages override background and text of button's renderer, renderer settings like fonts are kept from the first text of the button
+
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
+use crate::core::UniqueButton;
+use crate::modules::components::{FieldError, map_ui_values, map_ui_values_ref, UIField, UIFieldType, UIFieldValue, UIValue};
+use crate::thread::rendering::{ButtonBackground, ButtonText, Color, RendererComponent};
+use crate::thread::util::TextAlignment;
+
+/// Choices of page background in UI
+const BACKGROUND_CHOICES: [&'static str; 3] = ["Keep", "Color", "Image"];
+
+/// Component that rotates content of the button
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CarouselComponent {
+    #[serde(default)]
+    pub pages: Vec<CarouselPage>,
+    /// Seconds between pages, 0 disables the timer
+    #[serde(default)]
+    pub interval: f32,
+    /// If pressing the button should move to next page
+    #[serde(default)]
+    pub advance_on_press: bool,
+    /// Offset of pages that is moved by presses
+    #[serde(default)]
+    pub current: usize,
+}
+
+impl Component for CarouselComponent {
+    const NAME: &'static str = "carousel";
+}
+
+/// Single page of carousel
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CarouselPage {
+    /// Text that replaces text of the button, empty text keeps button's text
+    #[serde(default)]
+    pub text: String,
+    /// Background that replaces background of the button
+    #[serde(default)]
+    pub background: Option<ButtonBackground>,
+}
+
+impl CarouselComponent {
+    /// Index of page that should be shown right now
+    pub fn page_index(&self) -> Option<usize> {
+        if self.pages.is_empty() {
+            return None;
+        }
+
+        let ticks = if self.interval > 0.0 {
+            let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+            (time / self.interval as f64) as usize
+        } else {
+            0
+        };
+
+        Some(self.current.wrapping_add(ticks) % self.pages.len())
+    }
+
+    /// Renderer component with current page applied
+    pub fn apply(&self, renderer: &RendererComponent) -> Option<RendererComponent> {
+        let page = &self.pages[self.page_index()?];
+        let mut renderer = renderer.clone();
+
+        if let Some(background) = &page.background {
+            renderer.background = background.clone();
+        }
+
+        if !page.text.is_empty() {
+            if let Some(text) = renderer.text.first_mut() {
+                text.text = page.text.clone();
+                renderer.text.truncate(1);
+            } else {
+                renderer.text.push(page_text(&page.text));
+            }
+        }
+
+        Some(renderer)
+    }
+}
+
+/// Text used for pages of buttons that don't have any text
+fn page_text(text: &str) -> ButtonText {
+    ButtonText {
+        text: text.to_string(),
+        font: "default".to_string(),
+        scale: (20.0, 20.0),
+        alignment: TextAlignment::Center,
+        padding: 0,
+        offset: (0.0, 0.0),
+        color: (255, 255, 255, 255),
+        shadow: None,
+        auto_fit: true,
+        word_wrap: true,
+        marquee_speed: 0.0
+    }
+}
+
+/// Gets renderer component with carousel page of the button applied, if button has a carousel
+pub fn carousel_looks(button: &UniqueButton, renderer: &RendererComponent) -> Option<RendererComponent> {
+    parse_unique_button_to_component::<CarouselComponent>(button).ok()?.apply(renderer)
+}
+
+/// Moves carousel of the button to next page if it advances on press, returns false if button wasn't changed
+pub fn advance_carousel(button: &UniqueButton) -> bool {
+    let mut handle = button.write().unwrap();
+
+    if let Ok(mut component) = parse_button_to_component::<CarouselComponent>(&handle) {
+        if component.advance_on_press && !component.pages.is_empty() {
+            component.current = (component.current + 1) % component.pages.len();
+            handle.insert_component(component).ok();
+            return true;
+        }
+    }
+
+    false
+}
+
+fn page_fields() -> Vec<UIField> {
+    vec![
+        UIField {
+            name: "text".to_string(),
+            display_name: "Text".to_string(),
+            description: "Text shown on this page, empty keeps text of the button".to_string(),
+            ty: UIFieldType::InputFieldString,
+            default_value: UIFieldValue::InputFieldString("".to_string())
+        },
+        UIField {
+            name: "background".to_string(),
+            display_name: "Background".to_string(),
+            description: "Background shown on this page".to_string(),
+            ty: UIFieldType::Choice(BACKGROUND_CHOICES.iter().map(|x| x.to_string()).collect()),
+            default_value: UIFieldValue::Choice(BACKGROUND_CHOICES[0].to_string())
+        },
+        UIField {
+            name: "color".to_string(),
+            display_name: "Color".to_string(),
+            description: "Background color, if background is set to color".to_string(),
+            ty: UIFieldType::Color,
+            default_value: UIFieldValue::Color(0, 0, 0, 255)
+        },
+        UIField {
+            name: "image".to_string(),
+            display_name: "Image".to_string(),
+            description: "Background image, if background is set to image".to_string(),
+            ty: UIFieldType::ExistingImage,
+            default_value: UIFieldValue::ExistingImage("".to_string())
+        },
+    ]
+}
+
+fn page_values(page: &CarouselPage) -> Vec<UIValue> {
+    let (choice, color, image): (&str, Color, String) = match &page.background {
+        Some(ButtonBackground::Solid(color)) => (BACKGROUND_CHOICES[1], *color, "".to_string()),
+        Some(ButtonBackground::ExistingImage(image)) => (BACKGROUND_CHOICES[2], (0, 0, 0, 255), image.clone()),
+        _ => (BACKGROUND_CHOICES[0], (0, 0, 0, 255), "".to_string()),
+    };
+
+    page_fields().into_iter()
+        .map(|field| {
+            let value = match field.name.as_str() {
+                "text" => UIFieldValue::InputFieldString(page.text.clone()),
+                "background" => UIFieldValue::Choice(choice.to_string()),
+                "color" => color.into(),
+                _ => UIFieldValue::ExistingImage(image.clone()),
+            };
+
+            UIValue {
+                name: field.name,
+                display_name: field.display_name,
+                description: field.description,
+                ty: field.ty,
+                value
+            }
+        })
+        .collect()
+}
+
+fn parse_page(values: &Vec<UIValue>) -> CarouselPage {
+    let map = map_ui_values_ref(values);
+
+    let text = map.get("text")
+        .and_then(|x| x.value.try_into_string().ok())
+        .unwrap_or_default();
+
+    let choice = map.get("background")
+        .and_then(|x| x.value.try_into_string().ok())
+        .unwrap_or_default();
+
+    let background = match choice.as_str() {
+        "Color" => map.get("color")
+            .and_then(|x| x.value.try_into_color().ok())
+            .map(ButtonBackground::Solid),
+        "Image" => map.get("image")
+            .and_then(|x| x.value.try_into_string().ok())
+            .filter(|x| !x.is_empty())
+            .map(ButtonBackground::ExistingImage),
+        _ => None
+    };
+
+    CarouselPage {
+        text,
+        background
+    }
+}
+
+/// Gets UI values of carousel component
+pub fn get_carousel_component_values(button: &Button) -> Vec<UIValue> {
+    if let Ok(component) = parse_button_to_component::<CarouselComponent>(button) {
+        vec![
+            UIValue {
+                name: "interval".to_string(),
+                display_name: "Interval".to_string(),
+                description: "Seconds between pages, 0 only changes pages on press".to_string(),
+                ty: UIFieldType::InputFieldFloat,
+                value: UIFieldValue::InputFieldFloat(component.interval)
+            },
+            UIValue {
+                name: "advance_on_press".to_string(),
+                display_name: "Next Page On Press".to_string(),
+                description: "Pressing the button moves to next page".to_string(),
+                ty: UIFieldType::Checkbox { disabled: false },
+                value: UIFieldValue::Checkbox(component.advance_on_press)
+            },
+            UIValue {
+                name: "pages".to_string(),
+                display_name: "Pages".to_string(),
+                description: "Content that button rotates through".to_string(),
+                ty: UIFieldType::Array(page_fields()),
+                value: UIFieldValue::Array(component.pages.iter().map(page_values).collect())
+            }
+        ]
+    } else {
+        vec![]
+    }
+}
+
+/// Sets UI values of carousel component
+pub fn set_carousel_component_values(button: &mut Button, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+    if let Ok(mut component) = parse_button_to_component::<CarouselComponent>(button) {
+        let change_map = map_ui_values(value);
+
+        if let Some(value) = change_map.get("interval") {
+            if let Ok(interval) = value.value.try_into_f32() {
+                if interval < 0.0 {
+                    return Err(vec![FieldError::new("interval", "Interval can't be negative")]);
+                }
+
+                component.interval = interval;
+            }
+        }
+
+        if let Some(value) = change_map.get("advance_on_press") {
+            if let UIFieldValue::Checkbox(state) = value.value {
+                component.advance_on_press = state;
+            }
+        }
+
+        if let Some(value) = change_map.get("pages") {
+            if let UIFieldValue::Array(items) = &value.value {
+                component.pages = items.iter().map(parse_page).collect();
+
+                if component.current >= component.pages.len() {
+                    component.current = 0;
+                }
+            }
+        }
+
+        button.insert_component(component).ok();
+    }
+
+    Ok(())
+}
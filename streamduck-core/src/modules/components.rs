@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::str::FromStr;
 use serde::{Deserialize, Serialize};
-use crate::thread::rendering::{Color, RendererComponent};
+use crate::thread::rendering::{Color, parse_color, RendererComponent};
 
 /// Component definition
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -14,7 +14,26 @@ pub struct ComponentDefinition {
     pub description: String,
 
     /// Default looks for a button, in case user doesn't want to setup one on their own
-    pub default_looks: RendererComponent
+    pub default_looks: RendererComponent,
+
+    /// Components that have to be on the button before this component can be added
+    #[serde(default)]
+    pub requires: Vec<String>,
+
+    /// Components that can't be on the same button with this component
+    #[serde(default)]
+    pub conflicts_with: Vec<String>
+}
+
+/// Reasons for component not being allowed on a button, see [ComponentDefinition::requires] and [ComponentDefinition::conflicts_with]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum ComponentCompatibilityError {
+    /// Component requires another component that button doesn't have
+    MissingRequirement(String),
+    /// Component can't be on the same button with another component that button has
+    ConflictsWith(String),
+    /// Component can't be removed, because another component on the button requires it
+    RequiredBy(String),
 }
 
 /// UI Field, will be represented in a list similar to Unity's inspector
@@ -48,6 +67,24 @@ pub struct UIPathValue {
     pub value: UIFieldValue<UIPathValue>,
 }
 
+/// Error of a single field, returned by modules when they reject values
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FieldError {
+    /// Path of the field, in same format as [UIPathValue::path]
+    pub path: String,
+    /// Message that UI can show next to the field
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(path: &str, message: &str) -> FieldError {
+        FieldError {
+            path: path.to_string(),
+            message: message.to_string()
+        }
+    }
+}
+
 /// UI Field Types, defines types that fields will have
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum UIFieldType {
@@ -73,6 +110,12 @@ pub enum UIFieldType {
     /// Text field that accepts only positive integer values
     InputFieldUnsignedInteger,
 
+    /// Multi-line text field, for scripts and longer texts
+    InputFieldMultiline {
+        /// Language of the text for syntax highlighting in UI, like "bash" or "python", [None] if it's plain text
+        language_hint: Option<String>
+    },
+
     // TODO: Add more types of inputs
 
     /// Float slider of specified bounds
@@ -135,6 +178,9 @@ pub enum UIFieldValue<V> {
     /// Text field that accepts only positive integer values
     InputFieldUnsignedInteger(u32),
 
+    /// Multi-line text field
+    InputFieldMultiline(String),
+
     /// Float slider of specified bounds
     ValueSliderFloat(f32),
     /// Integer slider of specified bounds
@@ -361,22 +407,19 @@ impl<V> TryInto<Color> for UIFieldValue<V> {
     type Error = String;
 
     fn try_into(self) -> Result<Color, Self::Error> {
-        if let UIFieldValue::Color(c1, c2, c3, c4) = self {
-            Ok((c1, c2, c3, c4))
-        } else {
-            Err("Incorrect value".to_string())
-        }
+        (&self).try_into()
     }
 }
 
+/// Also accepts strings in formats that [parse_color] understands, for clients without color picker
 impl<V> TryInto<Color> for &UIFieldValue<V> {
     type Error = String;
 
     fn try_into(self) -> Result<Color, Self::Error> {
-        if let UIFieldValue::Color(c1, c2, c3, c4) = self {
-            Ok((*c1, *c2, *c3, *c4))
-        } else {
-            Err("Incorrect value".to_string())
+        match self {
+            UIFieldValue::Color(c1, c2, c3, c4) => Ok((*c1, *c2, *c3, *c4)),
+            UIFieldValue::InputFieldString(str) => parse_color(str),
+            _ => Err("Incorrect value".to_string())
         }
     }
 }
@@ -385,7 +428,7 @@ impl<V> TryInto<String> for UIFieldValue<V> {
     type Error = String;
 
     fn try_into(self) -> Result<String, Self::Error> {
-        if let UIFieldValue::InputFieldString(str) | UIFieldValue::Choice(str) | UIFieldValue::ImageData(str) | UIFieldValue::ExistingImage(str) | UIFieldValue::Font(str) | UIFieldValue::Label(str) = self {
+        if let UIFieldValue::InputFieldString(str) | UIFieldValue::InputFieldMultiline(str) | UIFieldValue::Choice(str) | UIFieldValue::ImageData(str) | UIFieldValue::ExistingImage(str) | UIFieldValue::Font(str) | UIFieldValue::Label(str) = self {
             Ok(str)
         } else {
             Err("Incorrect value".to_string())
@@ -397,7 +440,7 @@ impl<V> TryInto<String> for &UIFieldValue<V> {
     type Error = String;
 
     fn try_into(self) -> Result<String, Self::Error> {
-        if let UIFieldValue::InputFieldString(str) | UIFieldValue::Choice(str) | UIFieldValue::ImageData(str) | UIFieldValue::ExistingImage(str) | UIFieldValue::Font(str) | UIFieldValue::Label(str) = self {
+        if let UIFieldValue::InputFieldString(str) | UIFieldValue::InputFieldMultiline(str) | UIFieldValue::Choice(str) | UIFieldValue::ImageData(str) | UIFieldValue::ExistingImage(str) | UIFieldValue::Font(str) | UIFieldValue::Label(str) = self {
             Ok(str.clone())
         } else {
             Err("Incorrect value".to_string())
@@ -448,6 +491,7 @@ impl From<UIFieldValue<UIValue>> for UIFieldValue<UIPathValue> {
             UIFieldValue::InputFieldFloat2(f1, f2) => UIFieldValue::InputFieldFloat2(f1, f2),
             UIFieldValue::InputFieldInteger2(i1, i2) => UIFieldValue::InputFieldInteger2(i1, i2),
             UIFieldValue::InputFieldUnsignedInteger(u) => UIFieldValue::InputFieldUnsignedInteger(u),
+            UIFieldValue::InputFieldMultiline(s) => UIFieldValue::InputFieldMultiline(s),
             UIFieldValue::ValueSliderFloat(f) => UIFieldValue::ValueSliderFloat(f),
             UIFieldValue::ValueSliderInteger(i) => UIFieldValue::ValueSliderInteger(i),
 
@@ -480,6 +524,7 @@ impl From<UIFieldValue<UIPathValue>> for UIFieldValue<UIValue> {
             UIFieldValue::InputFieldFloat2(f1, f2) => UIFieldValue::InputFieldFloat2(f1, f2),
             UIFieldValue::InputFieldInteger2(i1, i2) => UIFieldValue::InputFieldInteger2(i1, i2),
             UIFieldValue::InputFieldUnsignedInteger(u) => UIFieldValue::InputFieldUnsignedInteger(u),
+            UIFieldValue::InputFieldMultiline(s) => UIFieldValue::InputFieldMultiline(s),
             UIFieldValue::ValueSliderFloat(f) => UIFieldValue::ValueSliderFloat(f),
             UIFieldValue::ValueSliderInteger(i) => UIFieldValue::ValueSliderInteger(i),
 
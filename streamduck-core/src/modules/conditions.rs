@@ -0,0 +1,464 @@
+//! Condition component that hides or disables buttons depending on state that modules publish to [message bus](crate::modules::messages)
+//!
+//! Expressions compare state of topics to literals, eg. `obs.status#/recording == true && !obs.status#/paused`.
+//! References are topic names, optionally followed by `#` and JSON pointer into last data of the topic.
+//! Supported operators are `==`, `!=`, `>`, `<`, `>=`, `<=`, `!`, `&&`, `||` and parentheses.
+//! Literals are numbers, `true`, `false`, `null` and strings in double quotes. References used on their own are checked for being truthy
+//!
+//! Conditions are evaluated by renderer every frame, so buttons react to new state without needing a redraw
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+use strum::VariantNames;
+use strum_macros::{Display, EnumString, EnumVariantNames};
+use crate::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
+use crate::core::methods::CoreHandle;
+use crate::core::UniqueButton;
+use crate::modules::components::{FieldError, map_ui_values, UIFieldType, UIFieldValue, UIValue};
+use crate::modules::messages::MessageBus;
+
+/// Component that hides or disables button while its expression is false
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ConditionComponent {
+    #[serde(default)]
+    pub expression: String,
+    #[serde(default)]
+    pub action: ConditionAction,
+}
+
+impl Component for ConditionComponent {
+    const NAME: &'static str = "condition";
+}
+
+impl ConditionComponent {
+    /// State of button with this component, empty or invalid expressions keep button shown
+    pub fn state(&self, bus: &MessageBus) -> ConditionState {
+        match ConditionExpression::parse(&self.expression) {
+            Ok(expression) if !expression.evaluate(bus) => match self.action {
+                ConditionAction::Hide => ConditionState::Hidden,
+                ConditionAction::Disable => ConditionState::Disabled,
+            },
+
+            _ => ConditionState::Shown
+        }
+    }
+}
+
+/// What happens to button when condition isn't met
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, EnumVariantNames, EnumString, Display)]
+pub enum ConditionAction {
+    /// Button isn't drawn and can't be pressed
+    Hide,
+    /// Button is drawn grayed out and can't be pressed
+    Disable,
+}
+
+impl Default for ConditionAction {
+    fn default() -> Self {
+        ConditionAction::Hide
+    }
+}
+
+/// State of button according to its condition
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Hash)]
+pub enum ConditionState {
+    Shown,
+    Disabled,
+    Hidden,
+}
+
+/// Gets state of button according to its condition component, buttons without the component are always shown
+pub fn button_condition_state(core: &CoreHandle, button: &UniqueButton) -> ConditionState {
+    if let Ok(component) = parse_unique_button_to_component::<ConditionComponent>(button) {
+        component.state(core.core.module_manager.message_bus())
+    } else {
+        ConditionState::Shown
+    }
+}
+
+/// Looks of disabled buttons
+pub fn apply_disabled_looks(image: DynamicImage) -> DynamicImage {
+    DynamicImage::ImageRgba8(image.grayscale().brighten(-60).to_rgba8())
+}
+
+/// Comparison operators of expressions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareOperator {
+    Equal,
+    NotEqual,
+    Greater,
+    Less,
+    GreaterOrEqual,
+    LessOrEqual,
+}
+
+/// Parsed condition expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConditionExpression {
+    Literal(Value),
+    /// Topic and JSON pointer into data of the topic
+    Reference(String, String),
+    Not(Box<ConditionExpression>),
+    And(Box<ConditionExpression>, Box<ConditionExpression>),
+    Or(Box<ConditionExpression>, Box<ConditionExpression>),
+    Compare(Box<ConditionExpression>, CompareOperator, Box<ConditionExpression>),
+}
+
+/// Errors of parsing condition expressions
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConditionError {
+    /// Expression has nothing in it
+    Empty,
+    /// Expression has something unexpected in it, contains the unexpected part
+    UnexpectedToken(String),
+    /// Expression ended too early
+    UnexpectedEnd,
+    /// String literal is missing closing quote
+    UnclosedString,
+}
+
+impl Display for ConditionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConditionError::Empty => write!(f, "Expression is empty"),
+            ConditionError::UnexpectedToken(token) => write!(f, "Unexpected '{}'", token),
+            ConditionError::UnexpectedEnd => write!(f, "Expression ended unexpectedly"),
+            ConditionError::UnclosedString => write!(f, "String is missing closing quote"),
+        }
+    }
+}
+
+impl ConditionExpression {
+    /// Parses expression from text
+    pub fn parse(expression: &str) -> Result<ConditionExpression, ConditionError> {
+        let tokens = tokenize(expression)?;
+
+        if tokens.is_empty() {
+            return Err(ConditionError::Empty);
+        }
+
+        let mut parser = Parser { tokens, position: 0 };
+        let expression = parser.parse_or()?;
+
+        if let Some(token) = parser.tokens.get(parser.position) {
+            return Err(ConditionError::UnexpectedToken(token.to_string()));
+        }
+
+        Ok(expression)
+    }
+
+    /// Resolves value of the expression, references to topics that weren't published yet resolve to null
+    pub fn value(&self, bus: &MessageBus) -> Value {
+        match self {
+            ConditionExpression::Literal(value) => value.clone(),
+            ConditionExpression::Reference(topic, pointer) => bus.state(topic)
+                .and_then(|x| x.pointer(pointer).cloned())
+                .unwrap_or(Value::Null),
+            _ => Value::Bool(self.evaluate(bus))
+        }
+    }
+
+    /// Evaluates the expression against state of the message bus
+    pub fn evaluate(&self, bus: &MessageBus) -> bool {
+        match self {
+            ConditionExpression::Not(expression) => !expression.evaluate(bus),
+            ConditionExpression::And(left, right) => left.evaluate(bus) && right.evaluate(bus),
+            ConditionExpression::Or(left, right) => left.evaluate(bus) || right.evaluate(bus),
+            ConditionExpression::Compare(left, operator, right) => compare_values(&left.value(bus), *operator, &right.value(bus)),
+            _ => is_truthy(&self.value(bus))
+        }
+    }
+}
+
+/// Checks value for being truthy, null, false, zero and empty strings or arrays aren't
+pub fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(value) => *value,
+        Value::Number(number) => number.as_f64().map_or(false, |x| x != 0.0),
+        Value::String(string) => !string.is_empty(),
+        Value::Array(array) => !array.is_empty(),
+        Value::Object(_) => true,
+    }
+}
+
+/// Compares values, numbers and strings can be ordered, everything else can only be equal or not
+pub fn compare_values(left: &Value, operator: CompareOperator, right: &Value) -> bool {
+    let ordering = match (left, right) {
+        (Value::Number(left), Value::Number(right)) => left.as_f64().zip(right.as_f64()).and_then(|(left, right)| left.partial_cmp(&right)),
+        (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+        _ => if left == right { Some(Ordering::Equal) } else { None }
+    };
+
+    match operator {
+        CompareOperator::Equal => ordering == Some(Ordering::Equal),
+        CompareOperator::NotEqual => ordering != Some(Ordering::Equal),
+        CompareOperator::Greater => ordering == Some(Ordering::Greater),
+        CompareOperator::Less => ordering == Some(Ordering::Less),
+        CompareOperator::GreaterOrEqual => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        CompareOperator::LessOrEqual => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Not,
+    And,
+    Or,
+    Compare(CompareOperator),
+    Open,
+    Close,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "{}", word),
+            Token::Text(text) => write!(f, "\"{}\"", text),
+            Token::Not => write!(f, "!"),
+            Token::And => write!(f, "&&"),
+            Token::Or => write!(f, "||"),
+            Token::Compare(operator) => write!(f, "{}", match operator {
+                CompareOperator::Equal => "==",
+                CompareOperator::NotEqual => "!=",
+                CompareOperator::Greater => ">",
+                CompareOperator::Less => "<",
+                CompareOperator::GreaterOrEqual => ">=",
+                CompareOperator::LessOrEqual => "<=",
+            }),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+        }
+    }
+}
+
+/// Characters that end words
+const SPECIAL_CHARACTERS: &str = "()\"&|!=<>";
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, ConditionError> {
+    let mut tokens = vec![];
+    let mut chars = expression.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '"' => Token::Text(read_string(&mut chars)?),
+            '&' | '|' => {
+                if chars.next_if_eq(&c).is_none() {
+                    return Err(ConditionError::UnexpectedToken(c.to_string()));
+                }
+
+                if c == '&' { Token::And } else { Token::Or }
+            }
+            '=' => {
+                if chars.next_if_eq(&'=').is_none() {
+                    return Err(ConditionError::UnexpectedToken(c.to_string()));
+                }
+
+                Token::Compare(CompareOperator::Equal)
+            }
+            '!' => if chars.next_if_eq(&'=').is_some() { Token::Compare(CompareOperator::NotEqual) } else { Token::Not },
+            '>' => Token::Compare(if chars.next_if_eq(&'=').is_some() { CompareOperator::GreaterOrEqual } else { CompareOperator::Greater }),
+            '<' => Token::Compare(if chars.next_if_eq(&'=').is_some() { CompareOperator::LessOrEqual } else { CompareOperator::Less }),
+            _ => {
+                let mut word = c.to_string();
+
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace() && !SPECIAL_CHARACTERS.contains(*c)) {
+                    word.push(c);
+                }
+
+                Token::Word(word)
+            }
+        };
+
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// Reads string literal after opening quote, backslash escapes next character
+fn read_string(chars: &mut Peekable<Chars>) -> Result<String, ConditionError> {
+    let mut text = String::new();
+
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(text),
+            Some('\\') => text.push(chars.next().ok_or(ConditionError::UnclosedString)?),
+            Some(c) => text.push(c),
+            None => return Err(ConditionError::UnclosedString)
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.tokens.get(self.position) == Some(token) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<ConditionExpression, ConditionError> {
+        let mut left = self.parse_and()?;
+
+        while self.eat(&Token::Or) {
+            left = ConditionExpression::Or(Box::new(left), Box::new(self.parse_and()?));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<ConditionExpression, ConditionError> {
+        let mut left = self.parse_not()?;
+
+        while self.eat(&Token::And) {
+            left = ConditionExpression::And(Box::new(left), Box::new(self.parse_not()?));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> Result<ConditionExpression, ConditionError> {
+        if self.eat(&Token::Not) {
+            Ok(ConditionExpression::Not(Box::new(self.parse_not()?)))
+        } else {
+            self.parse_comparison()
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<ConditionExpression, ConditionError> {
+        let left = self.parse_primary()?;
+
+        if let Some(Token::Compare(operator)) = self.tokens.get(self.position).cloned() {
+            self.position += 1;
+            Ok(ConditionExpression::Compare(Box::new(left), operator, Box::new(self.parse_primary()?)))
+        } else {
+            Ok(left)
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<ConditionExpression, ConditionError> {
+        match self.next() {
+            Some(Token::Open) => {
+                let expression = self.parse_or()?;
+
+                match self.next() {
+                    Some(Token::Close) => Ok(expression),
+                    Some(token) => Err(ConditionError::UnexpectedToken(token.to_string())),
+                    None => Err(ConditionError::UnexpectedEnd)
+                }
+            }
+
+            Some(Token::Text(text)) => Ok(ConditionExpression::Literal(Value::String(text))),
+            Some(Token::Word(word)) => Ok(parse_word(word)),
+            Some(token) => Err(ConditionError::UnexpectedToken(token.to_string())),
+            None => Err(ConditionError::UnexpectedEnd)
+        }
+    }
+}
+
+/// Words are either literals or references to topics
+fn parse_word(word: String) -> ConditionExpression {
+    match word.as_str() {
+        "true" => ConditionExpression::Literal(Value::Bool(true)),
+        "false" => ConditionExpression::Literal(Value::Bool(false)),
+        "null" => ConditionExpression::Literal(Value::Null),
+        _ => {
+            if let Some(number) = word.parse::<f64>().ok().and_then(Number::from_f64) {
+                ConditionExpression::Literal(Value::Number(number))
+            } else if let Some((topic, pointer)) = word.split_once('#') {
+                ConditionExpression::Reference(topic.to_string(), pointer.to_string())
+            } else {
+                ConditionExpression::Reference(word, "".to_string())
+            }
+        }
+    }
+}
+
+/// Gets UI values of condition component
+pub fn get_condition_component_values(core: &CoreHandle, button: &Button) -> Vec<UIValue> {
+    if let Ok(component) = parse_button_to_component::<ConditionComponent>(button) {
+        let status = match ConditionExpression::parse(&component.expression) {
+            Ok(expression) => if expression.evaluate(core.core.module_manager.message_bus()) { "Condition is met" } else { "Condition isn't met" }.to_string(),
+            Err(ConditionError::Empty) => "No condition, button is always shown".to_string(),
+            Err(err) => format!("Invalid expression: {}", err),
+        };
+
+        vec![
+            UIValue {
+                name: "expression".to_string(),
+                display_name: "Expression".to_string(),
+                description: "Button is shown normally while expression is true, eg. obs.status#/recording == true".to_string(),
+                ty: UIFieldType::InputFieldString,
+                value: UIFieldValue::InputFieldString(component.expression)
+            },
+            UIValue {
+                name: "action".to_string(),
+                display_name: "Otherwise".to_string(),
+                description: "What happens to the button while expression is false".to_string(),
+                ty: UIFieldType::Choice(ConditionAction::VARIANTS.iter().map(|x| x.to_string()).collect()),
+                value: UIFieldValue::Choice(component.action.to_string())
+            },
+            UIValue {
+                name: "status".to_string(),
+                display_name: "Status".to_string(),
+                description: "Current result of the expression".to_string(),
+                ty: UIFieldType::Label,
+                value: UIFieldValue::Label(status)
+            }
+        ]
+    } else {
+        vec![]
+    }
+}
+
+/// Sets UI values of condition component, expressions that fail to parse are rejected
+pub fn set_condition_component_values(button: &mut Button, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+    if let Ok(mut component) = parse_button_to_component::<ConditionComponent>(button) {
+        let change_map = map_ui_values(value);
+
+        if let Some(value) = change_map.get("expression") {
+            if let Ok(expression) = value.value.try_into_string() {
+                match ConditionExpression::parse(&expression) {
+                    Ok(_) | Err(ConditionError::Empty) => component.expression = expression,
+                    Err(err) => return Err(vec![FieldError::new("expression", &err.to_string())])
+                }
+            }
+        }
+
+        if let Some(value) = change_map.get("action") {
+            if let Ok(choice) = value.value.try_into_string() {
+                if let Ok(action) = ConditionAction::from_str(&choice) {
+                    component.action = action;
+                }
+            }
+        }
+
+        button.insert_component(component).ok();
+    }
+
+    Ok(())
+}
@@ -1,23 +1,31 @@
 //! Core module
 
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 use serde::{Deserialize, Serialize};
+use strum::VariantNames;
 use crate::config::PluginConfig;
 use crate::core::button::{Button, Component};
 use crate::core::manager::CoreManager;
 use crate::core::methods::{check_feature_list_for_feature, CoreHandle};
-use crate::modules::components::{ComponentDefinition, map_ui_values, UIFieldType, UIFieldValue, UIValue};
+use crate::modules::conditions::{ConditionComponent, get_condition_component_values, set_condition_component_values};
+use crate::modules::components::{ComponentDefinition, FieldError, map_ui_values, UIFieldType, UIFieldValue, UIValue};
 use crate::modules::{PluginMetadata, SDModule};
+use crate::modules::carousel::{CarouselComponent, get_carousel_component_values, set_carousel_component_values};
+use crate::modules::radio::{get_radio_component_values, RadioComponent, set_radio_component_values};
+use crate::modules::states::{get_state_component_values, set_state_component_values, StateComponent};
 use crate::modules::events::{core_event_to_global, SDCoreEvent};
 use crate::socket::send_event_to_socket;
 use crate::SocketManager;
 use crate::thread::rendering::{RendererComponent, RendererSettings};
 use crate::thread::rendering::component_values::{get_renderer_component_values, set_renderer_component_values};
+use crate::thread::transition::PanelTransition;
+use crate::thread::util::ImageFit;
 use crate::util::straight_copy;
 use crate::versions::{CORE, MODULE_MANAGER};
 
-/// The core module, for exposing renderer component to requests and such
+/// The core module, for exposing core components to requests and such
 pub struct CoreModule {
     pub(crate) socket_manager: Arc<SocketManager>
 }
@@ -33,7 +41,41 @@ impl SDModule for CoreModule {
         map.insert("renderer".to_string(), ComponentDefinition {
             display_name: "Renderer".to_string(),
             description: "The only thing that makes a button render an image on streamdeck".to_string(),
-            default_looks: Default::default()
+            default_looks: Default::default(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map.insert(ConditionComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Condition".to_string(),
+            description: "Hides or disables the button depending on state that modules publish".to_string(),
+            default_looks: Default::default(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map.insert(StateComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "States".to_string(),
+            description: "Makes the button cycle through states on press, every state has its own looks and actions".to_string(),
+            default_looks: Default::default(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map.insert(RadioComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Radio Button".to_string(),
+            description: "Only one button of a group can be active, pressing the button activates it".to_string(),
+            default_looks: Default::default(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map.insert(CarouselComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Carousel".to_string(),
+            description: "Rotates through pages of texts, colors or images on a timer or on press".to_string(),
+            default_looks: Default::default(),
+            requires: vec![RendererComponent::NAME.to_string()],
+            conflicts_with: vec![]
         });
 
         map
@@ -44,6 +86,18 @@ impl SDModule for CoreModule {
             "renderer" => {
                 button.insert_component(RendererComponent::default()).ok();
             }
+            ConditionComponent::NAME => {
+                button.insert_component(ConditionComponent::default()).ok();
+            }
+            StateComponent::NAME => {
+                button.insert_component(StateComponent::default()).ok();
+            }
+            RadioComponent::NAME => {
+                button.insert_component(RadioComponent::default()).ok();
+            }
+            CarouselComponent::NAME => {
+                button.insert_component(CarouselComponent::default()).ok();
+            }
             _ => {}
         }
     }
@@ -53,12 +107,28 @@ impl SDModule for CoreModule {
             "renderer" => {
                 button.remove_component::<RendererComponent>();
             }
+            ConditionComponent::NAME => {
+                button.remove_component::<ConditionComponent>();
+            }
+            StateComponent::NAME => {
+                button.remove_component::<StateComponent>();
+            }
+            RadioComponent::NAME => {
+                button.remove_component::<RadioComponent>();
+            }
+            CarouselComponent::NAME => {
+                button.remove_component::<CarouselComponent>();
+            }
             _ => {}
         }
     }
 
     fn paste_component(&self, _: CoreHandle, reference_button: &Button, new_button: &mut Button) {
         straight_copy(reference_button, new_button, RendererComponent::NAME);
+        straight_copy(reference_button, new_button, ConditionComponent::NAME);
+        straight_copy(reference_button, new_button, StateComponent::NAME);
+        straight_copy(reference_button, new_button, RadioComponent::NAME);
+        straight_copy(reference_button, new_button, CarouselComponent::NAME);
     }
 
     fn component_values(&self, core: CoreHandle, button: &Button, name: &str) -> Vec<UIValue> {
@@ -67,18 +137,52 @@ impl SDModule for CoreModule {
                 get_renderer_component_values(&core, button)
             }
 
+            ConditionComponent::NAME => {
+                get_condition_component_values(&core, button)
+            }
+
+            StateComponent::NAME => {
+                get_state_component_values(button)
+            }
+
+            RadioComponent::NAME => {
+                get_radio_component_values(button)
+            }
+
+            CarouselComponent::NAME => {
+                get_carousel_component_values(button)
+            }
+
             _ => vec![],
         }
     }
 
-    fn set_component_value(&self, core: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) {
+    fn set_component_value(&self, core: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
         match name {
             "renderer" => {
-                set_renderer_component_values(&core, button, value)
+                return set_renderer_component_values(&core, button, value);
+            }
+
+            ConditionComponent::NAME => {
+                return set_condition_component_values(button, value);
+            }
+
+            StateComponent::NAME => {
+                return set_state_component_values(button, value);
+            }
+
+            RadioComponent::NAME => {
+                return set_radio_component_values(button, value);
+            }
+
+            CarouselComponent::NAME => {
+                return set_carousel_component_values(button, value);
             }
 
             _ => {}
         }
+
+        Ok(())
     }
 
     fn listening_for(&self) -> Vec<String> {
@@ -123,6 +227,36 @@ impl SDModule for CoreModule {
                         }
                     );
 
+                    fields.push(
+                        UIValue {
+                            name: "transition".to_string(),
+                            display_name: "Panel Transition".to_string(),
+                            description: "Animation played when folders are opened or closed, devices can override it".to_string(),
+                            ty: UIFieldType::Choice(PanelTransition::VARIANTS.iter().map(|x| x.to_string()).collect()),
+                            value: UIFieldValue::Choice(settings.renderer.transition.to_string())
+                        }
+                    );
+
+                    fields.push(
+                        UIValue {
+                            name: "image_fit".to_string(),
+                            display_name: "Image Fit".to_string(),
+                            description: "How added images that aren't square are fitted into buttons, cover crops them and contain pads them with black bars".to_string(),
+                            ty: UIFieldType::Choice(ImageFit::VARIANTS.iter().filter(|x| **x != "Tile").map(|x| x.to_string()).collect()),
+                            value: UIFieldValue::Choice(settings.renderer.image_conversion.fit.to_string())
+                        }
+                    );
+
+                    fields.push(
+                        UIValue {
+                            name: "strip_alpha".to_string(),
+                            display_name: "Remove Image Transparency".to_string(),
+                            description: "Places added images on black background".to_string(),
+                            ty: UIFieldType::Checkbox { disabled: false },
+                            value: UIFieldValue::Checkbox(settings.renderer.image_conversion.strip_alpha)
+                        }
+                    );
+
                     fields
                 })
             }
@@ -155,6 +289,28 @@ impl SDModule for CoreModule {
                         }
                     }
                 }
+
+                if let Some(value) = change_map.get("transition") {
+                    if let UIFieldValue::Choice(choice) = &value.value {
+                        if let Ok(transition) = PanelTransition::from_str(choice) {
+                            settings.renderer.transition = transition;
+                        }
+                    }
+                }
+
+                if let Some(value) = change_map.get("image_fit") {
+                    if let UIFieldValue::Choice(choice) = &value.value {
+                        if let Ok(fit) = ImageFit::from_str(choice) {
+                            settings.renderer.image_conversion.fit = fit;
+                        }
+                    }
+                }
+
+                if let Some(value) = change_map.get("strip_alpha") {
+                    if let UIFieldValue::Checkbox(state) = value.value {
+                        settings.renderer.image_conversion.strip_alpha = state;
+                    }
+                }
             }
         }
 
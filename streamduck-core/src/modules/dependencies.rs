@@ -0,0 +1,135 @@
+//! Dependencies between plugins, used to decide order in which plugins are loaded
+//!
+//! Dependencies are declared in [PluginMetadata] as plugin name and semver range, eg. `("obs", ">=0.2, <0.4")`.
+//! Plugin versions are allowed to omit minor and patch numbers, "0.1" is treated as "0.1.0"
+
+use std::collections::HashMap;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use crate::modules::PluginMetadata;
+
+/// Dependency of a plugin on another plugin or module
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PluginDependency {
+    /// Name of the plugin that is required
+    pub name: String,
+    /// Semver range of versions that are compatible, eg. "0.2" or ">=1.0, <2.0"
+    pub version: String,
+}
+
+/// Dependency of loaded plugin with version that it was resolved to
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ResolvedDependency {
+    pub name: String,
+    /// Version range that was requested
+    pub version: String,
+    /// Version of loaded plugin that satisfies the dependency, [None] if plugin that provided it was removed since
+    pub resolved_version: Option<String>,
+}
+
+/// Loaded plugin with its dependencies
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DependencyNode {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<ResolvedDependency>,
+}
+
+/// Reasons for plugin not being loaded because of its dependencies
+#[derive(Debug, Clone)]
+pub enum DependencyError {
+    /// Required plugin isn't available, contains name and version range of the dependency
+    Missing(String, String),
+    /// Required plugin is available, but its version doesn't match. Contains name, version range and available version
+    WrongVersion(String, String, String),
+    /// Plugin depends on itself through other plugins
+    Cycle,
+}
+
+/// Parses plugin version as semver, filling in missing minor and patch numbers
+pub fn parse_plugin_version(version: &str) -> Option<Version> {
+    let version = version.trim();
+    let core_end = version.find(|c| c == '-' || c == '+').unwrap_or(version.len());
+    let (core, suffix) = version.split_at(core_end);
+
+    let missing = 2usize.checked_sub(core.matches('.').count())?;
+
+    Version::parse(&format!("{}{}{}", core, ".0".repeat(missing), suffix)).ok()
+}
+
+/// Checks if version of a plugin satisfies version range of dependency
+pub fn version_matches(range: &str, version: &str) -> bool {
+    match (VersionReq::parse(range), parse_plugin_version(version)) {
+        (Ok(range), Some(version)) => range.matches(&version),
+        _ => false
+    }
+}
+
+/// Orders plugins so that every plugin comes after plugins it depends on.
+///
+/// Loaded is metadata of modules that are already available. Returns items in order they should be loaded in,
+/// and plugins that can't be loaded with the reason
+pub fn resolve_load_order<T>(loaded: &[PluginMetadata], candidates: Vec<(PluginMetadata, T)>) -> (Vec<(PluginMetadata, T)>, Vec<(String, DependencyError)>) {
+    let mut available: HashMap<String, String> = loaded.iter()
+        .map(|x| (x.name.clone(), x.version.clone()))
+        .collect();
+
+    let mut pending = candidates;
+    pending.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+
+    let mut order = vec![];
+    let mut failed = vec![];
+
+    loop {
+        let mut progress = false;
+        let mut waiting = vec![];
+
+        let pending_names: Vec<String> = pending.iter().map(|(x, _)| x.name.clone()).collect();
+
+        for (metadata, item) in pending {
+            let mut ready = true;
+            let mut error = None;
+
+            for dependency in &metadata.dependencies {
+                if let Some(version) = available.get(&dependency.name) {
+                    if !version_matches(&dependency.version, version) {
+                        error = Some(DependencyError::WrongVersion(dependency.name.clone(), dependency.version.clone(), version.clone()));
+                        break;
+                    }
+                } else if pending_names.contains(&dependency.name) {
+                    ready = false;
+                } else {
+                    error = Some(DependencyError::Missing(dependency.name.clone(), dependency.version.clone()));
+                    break;
+                }
+            }
+
+            if let Some(error) = error {
+                failed.push((metadata.name.clone(), error));
+                progress = true;
+            } else if ready {
+                available.insert(metadata.name.clone(), metadata.version.clone());
+                order.push((metadata, item));
+                progress = true;
+            } else {
+                waiting.push((metadata, item));
+            }
+        }
+
+        pending = waiting;
+
+        if pending.is_empty() {
+            break;
+        }
+
+        if !progress {
+            for (metadata, _) in pending {
+                failed.push((metadata.name, DependencyError::Cycle));
+            }
+
+            break;
+        }
+    }
+
+    (order, failed)
+}
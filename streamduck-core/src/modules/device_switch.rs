@@ -0,0 +1,272 @@
+//! Device switch module, provides component that opens folders on other devices, letting one device control others
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use crate::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
+use crate::core::methods::{CoreHandle, get_current_screen, get_root_screen, push_screen, reset_stack};
+use crate::modules::components::{ComponentDefinition, FieldError, map_ui_values, UIFieldType, UIFieldValue, UIValue};
+use crate::modules::events::SDCoreEvent;
+use crate::modules::folders::{folder_panel, FolderInfo, list_config_folders};
+use crate::modules::{PluginMetadata, SDModule};
+use crate::thread::rendering::{ButtonBackground, ButtonText, RendererComponentBuilder};
+use crate::thread::util::TextAlignment;
+use crate::util::straight_copy;
+use crate::versions::{CORE, CORE_EVENTS, CORE_MANAGER, CORE_METHODS};
+
+const MODULE_NAME: &str = "core/device_switch";
+
+/// Choice that stands for root screen of the target device
+const ROOT_CHOICE: &str = "Root";
+
+/// Component that opens folder on another device
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct DeviceSwitchComponent {
+    /// Serial number of the target device
+    #[serde(default)]
+    pub serial: String,
+    /// Folder to open on the target device, root screen if empty
+    #[serde(default)]
+    pub folder_id: String,
+}
+
+impl Component for DeviceSwitchComponent {
+    const NAME: &'static str = "device_switch";
+}
+
+fn folder_choice(folder: &FolderInfo) -> String {
+    format!("{} ({})", folder.name, folder.id)
+}
+
+/// Serial numbers of devices that can be targeted from the core
+fn target_serials(core: &CoreHandle) -> Vec<String> {
+    let mut serials: Vec<String> = core.core.module_manager.core_manager()
+        .map(|manager| manager.list_added_devices().into_keys().collect())
+        .unwrap_or_default();
+
+    serials.retain(|x| x != &core.core.serial_number);
+    serials.sort();
+    serials
+}
+
+/// Folders of target device, empty if device isn't connected
+fn target_folders(core: &CoreHandle, serial: &str) -> Vec<FolderInfo> {
+    core.device_core(serial)
+        .map(|target| list_config_folders(&target.core.device_config.read().unwrap()))
+        .unwrap_or_default()
+}
+
+/// Opens folder on the target device, or returns target device to its root screen if folder ID is empty
+pub fn switch_device(core: &CoreHandle, serial: &str, folder_id: &str) {
+    let target = if let Some(target) = core.device_core(serial) {
+        target
+    } else {
+        log::warn!("Device switch target {} is not connected", serial);
+        return;
+    };
+
+    if folder_id.is_empty() {
+        let root = get_root_screen(&target);
+        reset_stack(&target, root);
+        return;
+    }
+
+    if let Some(panel) = folder_panel(&target, folder_id) {
+        if let Some(current) = get_current_screen(&target) {
+            if Arc::ptr_eq(&current, &panel) {
+                return;
+            }
+        }
+
+        push_screen(&target, panel);
+    } else {
+        log::warn!("Folder {} doesn't exist on device {}", folder_id, serial);
+    }
+}
+
+#[derive(Default)]
+pub struct DeviceSwitchModule;
+
+impl SDModule for DeviceSwitchModule {
+    fn name(&self) -> String {
+        MODULE_NAME.to_string()
+    }
+
+    fn components(&self) -> HashMap<String, ComponentDefinition> {
+        let mut map = HashMap::new();
+
+        map.insert(DeviceSwitchComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Device Switch".to_string(),
+            description: "Opens folder or root screen on another device".to_string(),
+            default_looks: RendererComponentBuilder::new()
+                .background(ButtonBackground::Solid((30, 60, 80, 255)))
+                .add_text(ButtonText {
+                    text: "⇄".to_string(),
+                    font: "default".to_string(),
+                    scale: (30.0, 30.0),
+                    alignment: TextAlignment::Center,
+                    padding: 0,
+                    offset: (0.0, 0.0),
+                    color: (255, 255, 255, 255),
+                    shadow: None,
+                    auto_fit: false,
+                    word_wrap: false,
+                    marquee_speed: 0.0
+                })
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map
+    }
+
+    fn add_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            DeviceSwitchComponent::NAME => {
+                button.insert_component(DeviceSwitchComponent::default()).ok();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn remove_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            DeviceSwitchComponent::NAME => {
+                button.remove_component::<DeviceSwitchComponent>();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn paste_component(&self, _: CoreHandle, reference_button: &Button, new_button: &mut Button) {
+        straight_copy(reference_button, new_button, DeviceSwitchComponent::NAME);
+    }
+
+    fn component_values(&self, core: CoreHandle, button: &Button, name: &str) -> Vec<UIValue> {
+        match name {
+            DeviceSwitchComponent::NAME => {
+                if let Ok(component) = parse_button_to_component::<DeviceSwitchComponent>(button) {
+                    let mut serials = target_serials(&core);
+
+                    // Keeping disconnected target in the list so it doesn't look unset
+                    if !component.serial.is_empty() && !serials.contains(&component.serial) {
+                        serials.push(component.serial.clone());
+                    }
+
+                    let mut fields = vec![
+                        UIValue {
+                            name: "serial".to_string(),
+                            display_name: "Device".to_string(),
+                            description: "Serial number of the device to open folder on".to_string(),
+                            ty: UIFieldType::Choice(serials),
+                            value: UIFieldValue::Choice(component.serial.clone())
+                        }
+                    ];
+
+                    if !component.serial.is_empty() {
+                        let folders = target_folders(&core, &component.serial);
+
+                        let current = folders.iter()
+                            .find(|x| x.id == component.folder_id)
+                            .map(folder_choice)
+                            .unwrap_or_else(|| if component.folder_id.is_empty() { ROOT_CHOICE.to_string() } else { component.folder_id.clone() });
+
+                        let mut choices = vec![ROOT_CHOICE.to_string()];
+                        choices.extend(folders.iter().map(folder_choice));
+
+                        fields.push(UIValue {
+                            name: "folder".to_string(),
+                            display_name: "Folder".to_string(),
+                            description: "Folder to open on the device, root returns device to its root screen".to_string(),
+                            ty: UIFieldType::Choice(choices),
+                            value: UIFieldValue::Choice(current)
+                        });
+                    }
+
+                    fields
+                } else {
+                    vec![]
+                }
+            }
+
+            _ => vec![],
+        }
+    }
+
+    fn set_component_value(&self, core: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+        match name {
+            DeviceSwitchComponent::NAME => {
+                if let Ok(mut component) = parse_button_to_component::<DeviceSwitchComponent>(button) {
+                    let change_map = map_ui_values(value);
+
+                    if let Some(value) = change_map.get("serial") {
+                        if let Ok(serial) = value.value.try_into_string() {
+                            if serial == core.core.serial_number {
+                                return Err(vec![FieldError::new("serial", "Target should be another device")]);
+                            }
+
+                            if serial != component.serial {
+                                component.serial = serial;
+                                component.folder_id = "".to_string();
+                            }
+                        }
+                    }
+
+                    if let Some(value) = change_map.get("folder") {
+                        if let Ok(choice) = value.value.try_into_string() {
+                            if choice == ROOT_CHOICE {
+                                component.folder_id = "".to_string();
+                            } else if let Some(folder) = target_folders(&core, &component.serial).iter().find(|x| folder_choice(x) == choice) {
+                                component.folder_id = folder.id.clone();
+                            }
+                        }
+                    }
+
+                    button.insert_component(component).ok();
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn listening_for(&self) -> Vec<String> {
+        vec![
+            DeviceSwitchComponent::NAME.to_string()
+        ]
+    }
+
+    fn event(&self, core: CoreHandle, event: SDCoreEvent) {
+        match event {
+            SDCoreEvent::ButtonAction { pressed_button, .. } => {
+                if let Ok(component) = parse_unique_button_to_component::<DeviceSwitchComponent>(&pressed_button) {
+                    if !component.serial.is_empty() {
+                        switch_device(&core, &component.serial, &component.folder_id);
+                    }
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn metadata(&self) -> PluginMetadata {
+        PluginMetadata::from_literals(
+            MODULE_NAME,
+            "TheJebForge",
+            "Device switch module, provides component for opening folders on other devices",
+            "0.1",
+            &[
+                CORE,
+                CORE_METHODS,
+                CORE_EVENTS,
+                CORE_MANAGER
+            ]
+        )
+    }
+}
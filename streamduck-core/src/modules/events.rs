@@ -1,5 +1,6 @@
-use crate::core::{ButtonPanel, RawButtonPanel, UniqueButton};
+use crate::core::{ButtonPanel, RawButtonPanel, UniqueButton, UniqueButtonMap};
 use serde::{Serialize, Deserialize};
+use serde_json::Value;
 use crate::core::button::Button;
 use crate::util::{button_to_raw, panel_to_raw};
 
@@ -12,10 +13,17 @@ pub enum SDCoreEvent {
     ButtonUpdated {key: u8, panel: ButtonPanel, new_button: UniqueButton, old_button: UniqueButton},
     /// Called when a button gets deleted
     ButtonDeleted {key: u8, panel: ButtonPanel, deleted_button: UniqueButton},
+    /// Called when a button gets moved to another position, swapped button is the one that was moved the other way
+    ButtonMoved {from: u8, to: u8, panel: ButtonPanel, moved_button: UniqueButton, swapped_button: Option<UniqueButton>},
+    /// Called when all buttons of a panel get replaced at once
+    PanelUpdated {panel: ButtonPanel, old_buttons: UniqueButtonMap},
 
     /// Called when a valid button was pressed on
     ButtonAction {key: u8, panel: ButtonPanel, pressed_button: UniqueButton},
 
+    /// Called when a radio button was selected, see [radio](crate::modules::radio)
+    RadioSelected {key: u8, panel: ButtonPanel, group: String, value: String},
+
     /// Called when a button is pressed down
     ButtonDown {key: u8},
     /// Called when a button is released
@@ -40,10 +48,17 @@ pub enum SDGlobalEvent {
     ButtonUpdated {serial_number: String, key: u8, panel: RawButtonPanel, new_button: Button, old_button: Button},
     /// Called when a button gets deleted
     ButtonDeleted {serial_number: String, key: u8, panel: RawButtonPanel, deleted_button: Button},
+    /// Called when a button gets moved to another position, swapped button is the one that was moved the other way
+    ButtonMoved {serial_number: String, from: u8, to: u8, panel: RawButtonPanel, moved_button: Button, swapped_button: Option<Button>},
+    /// Called when all buttons of a panel get replaced at once
+    PanelUpdated {serial_number: String, panel: RawButtonPanel},
 
     /// Called when a valid button was pressed on
     ButtonAction {serial_number: String, key: u8, panel: RawButtonPanel, pressed_button: Button},
 
+    /// Called when a radio button was selected
+    RadioSelected {serial_number: String, key: u8, panel: RawButtonPanel, group: String, value: String},
+
     /// Called when a button is pressed down
     ButtonDown {serial_number: String, key: u8},
     /// Called when a button is released
@@ -63,6 +78,31 @@ pub enum SDGlobalEvent {
 
     /// Called when device has disconnected
     DeviceDisconnected {serial_number: String},
+
+    /// Called when device config was reloaded after being changed outside of the daemon
+    DeviceConfigReloaded {serial_number: String},
+
+    /// Called when global plugin settings were changed by a client
+    GlobalPluginSettingsChanged {name: String},
+
+    /// Called when button edit was undone or redone by a client, contains panel after the change
+    EditHistoryApplied {serial_number: String, undo: bool, key: u8, panel: RawButtonPanel},
+
+    /// Called when plugin has panicked, plugin gets disabled after too many panics in a row
+    PluginFailed {name: String, message: String, disabled: bool},
+
+    /// Custom event sent by a module, eg. phase change of a pomodoro timer, for scripts and other modules to react to
+    ModuleEvent {serial_number: String, module: String, name: String, data: Value},
+
+    /// Called when daemon is about to shut down by client request, restart is true if daemon will start again
+    DaemonShuttingDown {restart: bool},
+
+    /// Called after buttons or stack of a device were changed, keys are empty if whole screen changed.
+    /// Client is ID of the connection that made the change, [None] if it wasn't made by a client
+    ScreenChanged {serial_number: String, revision: u64, keys: Vec<u8>, client: Option<u64>},
+
+    /// Called when folders opened on a device changed and daemon config has `persist_stack` enabled, path contains folder IDs
+    StackPathChanged {serial_number: String, path: Vec<String>},
 }
 
 pub fn core_event_to_global(event: SDCoreEvent, serial: &str) -> SDGlobalEvent {
@@ -90,6 +130,20 @@ pub fn core_event_to_global(event: SDCoreEvent, serial: &str) -> SDGlobalEvent {
             deleted_button: button_to_raw(&deleted_button)
         },
 
+        SDCoreEvent::ButtonMoved { from, to, panel, moved_button, swapped_button } => SDGlobalEvent::ButtonMoved {
+            serial_number,
+            from,
+            to,
+            panel: panel_to_raw(&panel),
+            moved_button: button_to_raw(&moved_button),
+            swapped_button: swapped_button.map(|x| button_to_raw(&x))
+        },
+
+        SDCoreEvent::PanelUpdated { panel, .. } => SDGlobalEvent::PanelUpdated {
+            serial_number,
+            panel: panel_to_raw(&panel)
+        },
+
         SDCoreEvent::ButtonAction { key, panel, pressed_button } => SDGlobalEvent::ButtonAction {
             serial_number,
             key,
@@ -97,6 +151,14 @@ pub fn core_event_to_global(event: SDCoreEvent, serial: &str) -> SDGlobalEvent {
             pressed_button: button_to_raw(&pressed_button)
         },
 
+        SDCoreEvent::RadioSelected { key, panel, group, value } => SDGlobalEvent::RadioSelected {
+            serial_number,
+            key,
+            panel: panel_to_raw(&panel),
+            group,
+            value
+        },
+
         SDCoreEvent::ButtonDown { key } => SDGlobalEvent::ButtonDown {
             serial_number,
             key
@@ -1,30 +1,48 @@
-use std::collections::{HashMap, HashSet};
-use std::sync::RwLock;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+use image::DynamicImage;
+use image::imageops::{FilterType, overlay};
 use rand::distributions::Alphanumeric;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use crate::config::{DeviceConfig, PluginConfig};
 use crate::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
-use crate::core::{ButtonPanel, RawButtonPanel};
-use crate::core::methods::{CoreHandle, get_stack, pop_screen, push_screen};
-use crate::modules::components::{ComponentDefinition, map_ui_values, UIFieldType, UIFieldValue, UIValue};
+use crate::core::{ButtonMap, ButtonPanel, RawButtonPanel, UniqueButton, UniqueButtonMap};
+use crate::core::manager::CoreManager;
+use crate::core::methods::{CoreHandle, get_current_screen, get_stack, pop_screen, push_overlay, push_screen, remove_overlay};
+use crate::modules::components::{ComponentDefinition, FieldError, map_ui_values, UIFieldType, UIFieldValue, UIValue};
 use crate::modules::events::SDCoreEvent;
-use crate::modules::{PluginMetadata, SDModule};
-use crate::thread::rendering::{ButtonBackground, ButtonText, RendererComponentBuilder};
-use crate::util::{button_to_raw, make_panel_unique, straight_copy};
+use crate::images::SDImage;
+use crate::layout::{FOLDERS_KEY, FolderMap, folders_of};
+use crate::modules::{PluginMetadata, SDModule, UniqueSDModule};
+use crate::thread::rendering::{ButtonBackground, ButtonText, draw_background, draw_foreground, draw_missing_texture, RendererComponent, RendererComponentBuilder};
+use crate::thread::util::image_from_solid;
+use crate::thread::orientation::visible_columns;
+use crate::util::{button_to_raw, make_button_unique, make_panel_unique, straight_copy};
 use crate::thread::util::TextAlignment;
-use crate::versions::{CORE, CORE_METHODS, CORE_EVENTS, MODULE_MANAGER};
+use crate::versions::{CORE, CORE_METHODS, CORE_EVENTS, MODULE_MANAGER, RENDERING};
 
 const MODULE_NAME: &str = "core/folder";
 
+/// ID of overlay that shows automatic back button
+const BACK_BUTTON_OVERLAY: &str = "core/folder/back";
+
+/// Amount of buttons that folder thumbnail shows, in 2x2 grid
+const THUMBNAIL_BUTTONS: usize = 4;
+
 #[derive(Debug)]
 pub struct FolderModule {
     folder_references: RwLock<HashMap<String, ButtonPanel>>,
+    /// Incremented every time folder contents change, so thumbnails get redrawn
+    folder_versions: RwLock<HashMap<String, u64>>,
 }
 
 impl Default for FolderModule {
     fn default() -> Self {
         Self {
             folder_references: Default::default(),
+            folder_versions: Default::default(),
         }
     }
 }
@@ -50,9 +68,14 @@ impl SDModule for FolderModule {
                     padding: 0,
                     offset: (0.0, 0.0),
                     color: (255, 255, 255, 255),
-                    shadow: None
+                    shadow: None,
+                    auto_fit: false,
+                    word_wrap: false,
+                    marquee_speed: 0.0
                 })
-                .build()
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![FolderLinkComponent::NAME.to_string()]
         });
 
         map.insert(FolderLinkComponent::NAME.to_string(), ComponentDefinition {
@@ -68,27 +91,22 @@ impl SDModule for FolderModule {
                                 padding: 7,
                                 offset: (0.0, 0.0),
                                 color: (255, 255, 255, 255),
-                                shadow: None
+                                shadow: None,
+                                auto_fit: false,
+                                word_wrap: false,
+                                marquee_speed: 0.0
                 })
-                .build()
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![FolderComponent::NAME.to_string()]
         });
 
         map.insert(FolderUpComponent::NAME.to_string(), ComponentDefinition {
             display_name: "Folder Up".to_string(),
             description: "Back button for folders".to_string(),
-            default_looks: RendererComponentBuilder::new()
-                .background(ButtonBackground::Solid((50, 50, 50, 255)))
-                .add_text(ButtonText {
-                    text: "Back".to_string(),
-                    font: "default".to_string(),
-                    scale: (22.0, 22.0),
-                    alignment: TextAlignment::Center,
-                    padding: 0,
-                    offset: (0.0, 0.0),
-                    color: (255, 255, 255, 255),
-                    shadow: None
-                })
-                .build()
+            default_looks: back_button_looks(),
+            requires: vec![],
+            conflicts_with: vec![]
         });
 
         map
@@ -97,26 +115,24 @@ impl SDModule for FolderModule {
     fn add_component(&self, core: CoreHandle, button: &mut Button, name: &str) {
         match name {
             FolderComponent::NAME => {
-                if !button.contains(FolderLinkComponent::NAME) {
-                    let folder_id = self.new_folder(&core);
+                let folder_id = self.new_folder(&core);
 
-                    button.insert_component(
-                        FolderComponent {
-                            id: folder_id,
-                            name: "Folder".to_string()
-                        }
-                    ).ok();
-                }
+                button.insert_component(
+                    FolderComponent {
+                        id: folder_id,
+                        name: "Folder".to_string(),
+                        thumbnail: false,
+                        back_button: Default::default()
+                    }
+                ).ok();
             }
 
             FolderLinkComponent::NAME => {
-                if !button.contains(FolderComponent::NAME) {
-                    button.insert_component(
-                        FolderLinkComponent {
-                            id: "".to_string()
-                        }
-                    ).ok();
-                }
+                button.insert_component(
+                    FolderLinkComponent {
+                        id: "".to_string()
+                    }
+                ).ok();
             }
 
             FolderUpComponent::NAME => {
@@ -168,7 +184,9 @@ impl SDModule for FolderModule {
 
             new_button.insert_component(FolderComponent {
                 id: new_name,
-                name: component.name
+                name: component.name,
+                thumbnail: component.thumbnail,
+                back_button: component.back_button
             }).ok();
         }
     }
@@ -191,6 +209,20 @@ impl SDModule for FolderModule {
                             description: "Name that will appear in breadcrumbs of the stack".to_string(),
                             ty: UIFieldType::InputFieldString,
                             value: UIFieldValue::InputFieldString(component.name)
+                        },
+                        UIValue {
+                            name: "thumbnail".to_string(),
+                            display_name: "Show Contents".to_string(),
+                            description: "Shows first buttons of the folder as background".to_string(),
+                            ty: UIFieldType::Checkbox { disabled: false },
+                            value: UIFieldValue::Checkbox(component.thumbnail)
+                        },
+                        UIValue {
+                            name: "back_button".to_string(),
+                            display_name: "Back Button".to_string(),
+                            description: "Shows back button in the corner when folder is opened, if the key is free. Default follows folder module settings".to_string(),
+                            ty: UIFieldType::Choice(BackButtonMode::CHOICES.iter().map(|x| x.to_string()).collect()),
+                            value: UIFieldValue::Choice(component.back_button.as_choice().to_string())
                         }
                     ];
                 }
@@ -227,7 +259,7 @@ impl SDModule for FolderModule {
         vec![]
     }
 
-    fn set_component_value(&self, core: CoreHandle, button: &mut Button, component: &str, values: Vec<UIValue>) {
+    fn set_component_value(&self, core: CoreHandle, button: &mut Button, component: &str, values: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
         match component {
             FolderComponent::NAME => {
                 if let Ok(mut component) = parse_button_to_component::<FolderComponent>(button) {
@@ -250,6 +282,29 @@ impl SDModule for FolderModule {
                         }
                     }
 
+                    if let Some(value) = change_map.get("thumbnail") {
+                        if let UIFieldValue::Checkbox(state) = value.value {
+                            component.thumbnail = state;
+                        }
+                    }
+
+                    if let Some(value) = change_map.get("back_button") {
+                        if let Ok(str) = value.value.try_into_string() {
+                            if let Some(mode) = BackButtonMode::from_choice(&str) {
+                                component.back_button = mode;
+
+                                let handle = self.folder_references.read().unwrap();
+                                if let Some(folder) = handle.get(&component.id).cloned() {
+                                    let mut folder_handle = folder.write().unwrap();
+                                    folder_handle.data = serde_json::to_value(FolderStackData {
+                                        folder_id: component.id.clone(),
+                                        back_button: mode
+                                    }).unwrap();
+                                }
+                            }
+                        }
+                    }
+
                     button.insert_component(component).ok();
                 }
             }
@@ -279,6 +334,8 @@ impl SDModule for FolderModule {
 
             _ => {}
         }
+
+        Ok(())
     }
 
     fn listening_for(&self) -> Vec<String> {
@@ -318,11 +375,66 @@ impl SDModule for FolderModule {
                 }
             }
 
+            SDCoreEvent::ButtonMoved { from, to, panel, moved_button, swapped_button } => {
+                let panel = panel.read().unwrap();
+
+                if let Ok(stack_data) = serde_json::from_value::<FolderStackData>(panel.data.clone()) {
+                    if let Some(mut contents) = self.get_folder(&core, &stack_data.folder_id) {
+                        contents.buttons.remove(&from);
+                        contents.buttons.insert(to, button_to_raw(&moved_button));
+
+                        if let Some(swapped_button) = swapped_button {
+                            contents.buttons.insert(from, button_to_raw(&swapped_button));
+                        }
+
+                        self.update_folder(&core, stack_data.folder_id, contents);
+                    }
+                }
+            }
+
+            SDCoreEvent::PanelUpdated { panel, old_buttons } => {
+                let (buttons, data) = {
+                    let handle = panel.read().unwrap();
+                    (handle.buttons.clone(), handle.data.clone())
+                };
+
+                if let Ok(stack_data) = serde_json::from_value::<FolderStackData>(data) {
+                    if let Some(mut contents) = self.get_folder(&core, &stack_data.folder_id) {
+                        contents.buttons = buttons.iter().map(|(key, button)| (*key, button_to_raw(button))).collect();
+                        self.update_folder(&core, stack_data.folder_id.clone(), contents);
+                    }
+
+                    // Updated panel might not be the one that gets opened next time
+                    if let Some(cached) = self.folder_references.read().unwrap().get(&stack_data.folder_id) {
+                        if !Arc::ptr_eq(cached, &panel) {
+                            cached.write().unwrap().buttons = buttons.clone();
+                        }
+                    }
+                }
+
+                let remaining = buttons.values()
+                    .filter_map(|x| parse_unique_button_to_component::<FolderComponent>(x).ok())
+                    .map(|x| x.id)
+                    .collect::<HashSet<String>>();
+
+                for button in old_buttons.values() {
+                    if let Ok(component) = parse_unique_button_to_component::<FolderComponent>(button) {
+                        if !remaining.contains(&component.id) {
+                            self.delete_folder_recursively(&core, &component.id, &mut HashSet::new());
+                        }
+                    }
+                }
+
+                self.update_back_button(&core);
+            }
+
             SDCoreEvent::ButtonAction { pressed_button, .. } => {
                 if let Ok(_) = parse_unique_button_to_component::<FolderUpComponent>(&pressed_button) {
                     if get_stack(&core).len() > 1 {
                         pop_screen(&core);
                     }
+
+                    self.update_back_button(&core);
                 } else if let Ok(folder) = parse_unique_button_to_component::<FolderComponent>(&pressed_button) {
                     let mut folder_ref_handle = self.folder_references.write().unwrap();
 
@@ -332,7 +444,8 @@ impl SDModule for FolderModule {
                         if let Some(mut contents) = self.get_folder(&core, &folder.id) {
                             contents.display_name = folder.name;
                             contents.data = serde_json::to_value(FolderStackData {
-                                folder_id: folder.id.to_string()
+                                folder_id: folder.id.to_string(),
+                                back_button: folder.back_button
                             }).unwrap();
 
                             let panel = make_panel_unique(contents);
@@ -341,6 +454,8 @@ impl SDModule for FolderModule {
                         }
                     }
 
+                    drop(folder_ref_handle);
+                    self.update_back_button(&core);
 
                 } else if let Ok(folder_link) = parse_unique_button_to_component::<FolderLinkComponent>(&pressed_button) {
                     let mut folder_ref_handle = self.folder_references.write().unwrap();
@@ -350,7 +465,8 @@ impl SDModule for FolderModule {
                     } else {
                         if let Some(mut contents) = self.get_folder(&core, &folder_link.id) {
                             contents.data = serde_json::to_value(FolderStackData {
-                                folder_id: folder_link.id.to_string()
+                                folder_id: folder_link.id.to_string(),
+                                back_button: Default::default()
                             }).unwrap();
 
                             let panel = make_panel_unique(contents);
@@ -358,13 +474,92 @@ impl SDModule for FolderModule {
                             folder_ref_handle.insert(folder_link.id, panel);
                         }
                     }
+
+                    drop(folder_ref_handle);
+                    self.update_back_button(&core);
                 }
             }
 
+            SDCoreEvent::StackReset { .. } => {
+                // Stack is usually reset after config was changed, folders might have changed too
+                self.folder_references.write().unwrap().clear();
+                self.update_back_button(&core);
+            }
+
+            SDCoreEvent::PanelPushed { .. } |
+            SDCoreEvent::PanelPopped { .. } |
+            SDCoreEvent::PanelReplaced { .. } => self.update_back_button(&core),
+
             _ => {}
         }
     }
 
+    fn render(&self, core: CoreHandle, button: &UniqueButton, frame: &mut DynamicImage) {
+        if let Ok(folder) = parse_unique_button_to_component::<FolderComponent>(button) {
+            if folder.thumbnail {
+                if let Some(thumbnail) = self.folder_thumbnail(&core, &folder.id) {
+                    *frame = thumbnail;
+                }
+            }
+        }
+    }
+
+    fn render_hash(&self, _: CoreHandle, button: &UniqueButton, hash: &mut Box<dyn Hasher>) {
+        if let Ok(folder) = parse_unique_button_to_component::<FolderComponent>(button) {
+            if folder.thumbnail {
+                folder.id.hash(hash);
+                self.folder_versions.read().unwrap().get(&folder.id).hash(hash);
+            }
+        }
+    }
+
+    fn settings(&self, core_manager: Arc<CoreManager>) -> Vec<UIValue> {
+        let settings: FolderSettings = core_manager.config.get_plugin_settings().unwrap_or_default();
+
+        vec![
+            UIValue {
+                name: "auto_back".to_string(),
+                display_name: "Automatic Back Button".to_string(),
+                description: "Shows back button when folder is opened, unless folder already has one".to_string(),
+                ty: UIFieldType::Checkbox { disabled: false },
+                value: UIFieldValue::Checkbox(settings.auto_back)
+            },
+            UIValue {
+                name: "back_corner".to_string(),
+                display_name: "Back Button Corner".to_string(),
+                description: "Corner where automatic back button is shown".to_string(),
+                ty: UIFieldType::Choice(BackButtonCorner::CHOICES.iter().map(|x| x.to_string()).collect()),
+                value: UIFieldValue::Choice(settings.back_corner.as_choice().to_string())
+            }
+        ]
+    }
+
+    fn set_setting(&self, core_manager: Arc<CoreManager>, value: Vec<UIValue>) {
+        let mut settings: FolderSettings = core_manager.config.get_plugin_settings().unwrap_or_default();
+
+        let change_map = map_ui_values(value);
+
+        if let Some(value) = change_map.get("auto_back") {
+            if let UIFieldValue::Checkbox(state) = value.value {
+                settings.auto_back = state;
+            }
+        }
+
+        if let Some(value) = change_map.get("back_corner") {
+            if let Ok(str) = value.value.try_into_string() {
+                if let Some(corner) = BackButtonCorner::from_choice(&str) {
+                    settings.back_corner = corner;
+                }
+            }
+        }
+
+        core_manager.config.set_plugin_settings(settings);
+
+        for device in core_manager.list_added_devices().into_values() {
+            self.update_back_button(&CoreHandle::wrap(device.core));
+        }
+    }
+
     fn metadata(&self) -> PluginMetadata {
         PluginMetadata::from_literals(
             MODULE_NAME,
@@ -375,7 +570,8 @@ impl SDModule for FolderModule {
                 CORE,
                 CORE_METHODS,
                 MODULE_MANAGER,
-                CORE_EVENTS
+                CORE_EVENTS,
+                RENDERING
             ]
         )
     }
@@ -479,6 +675,10 @@ impl FolderModule {
 
         folders.insert(folder_id.clone(), folder_content);
         config_handle.plugin_data.insert("folders".to_string(), serde_json::to_value(folders).unwrap());
+        drop(config_handle);
+
+        core.image_collection.write().unwrap().remove(&thumbnail_identifier(&folder_id));
+        *self.folder_versions.write().unwrap().entry(folder_id).or_default() += 1;
     }
 
     /// Deletes folder from plugin data
@@ -500,6 +700,87 @@ impl FolderModule {
         config_handle.plugin_data.insert("folders".to_string(), serde_json::to_value(folders).unwrap());
     }
 
+    /// Gets thumbnail of folder contents from image collection, drawing it if it's not there
+    fn folder_thumbnail(&self, core: &CoreHandle, folder_id: &str) -> Option<DynamicImage> {
+        let identifier = thumbnail_identifier(folder_id);
+
+        if let Some(SDImage::SingleImage(image)) = core.core().image_collection.read().unwrap().get(&identifier) {
+            return Some(image.clone());
+        }
+
+        let contents = self.get_folder(core, folder_id)?;
+        let size = core.core().image_size;
+        let (cell_width, cell_height) = (size.0 as u32 / 2, size.1 as u32 / 2);
+
+        let mut keys = contents.buttons.keys().cloned().collect::<Vec<u8>>();
+        keys.sort();
+
+        let missing = draw_missing_texture(size);
+        let mut thumbnail = image_from_solid(size, image::Rgba([0, 0, 0, 255]));
+
+        let buttons = keys.into_iter()
+            .filter_map(|key| {
+                let button = contents.buttons.get(&key)?;
+                parse_button_to_component::<RendererComponent>(button).ok().map(|renderer| (renderer, make_button_unique(button.clone())))
+            })
+            .take(THUMBNAIL_BUTTONS);
+
+        for (index, (renderer, button)) in buttons.enumerate() {
+            // Leaving this module out, so folders inside of the folder don't draw their thumbnails too
+            let modules = core.module_manager().get_modules_for_rendering(&button.read().unwrap().component_names())
+                .into_values()
+                .filter(|x| x.name() != MODULE_NAME && !renderer.plugin_blacklist.contains(&x.name()))
+                .collect::<Vec<UniqueSDModule>>();
+
+            let image = draw_foreground(&renderer, &button, &modules, draw_background(&renderer, core, &missing), core)
+                .resize_exact(cell_width, cell_height, FilterType::Triangle);
+
+            overlay(&mut thumbnail, &image, ((index as u32 % 2) * cell_width) as i64, ((index as u32 / 2) * cell_height) as i64);
+        }
+
+        core.core().image_collection.write().unwrap().insert(identifier, SDImage::SingleImage(thumbnail.clone()));
+
+        Some(thumbnail)
+    }
+
+    /// Shows or hides automatic back button depending on what's on the screen
+    fn update_back_button(&self, core: &CoreHandle) {
+        let settings: FolderSettings = core.core().config.get_plugin_settings().unwrap_or_default();
+        let key = settings.back_corner.key(core);
+
+        let show = get_current_screen(core).map_or(false, |screen| {
+            let screen = screen.read().unwrap();
+
+            if let Ok(stack_data) = serde_json::from_value::<FolderStackData>(screen.data.clone()) {
+                let enabled = match stack_data.back_button {
+                    BackButtonMode::Default => settings.auto_back,
+                    BackButtonMode::Always => true,
+                    BackButtonMode::Never => false,
+                };
+
+                let has_back_button = screen.buttons.values()
+                    .any(|x| x.read().unwrap().contains(FolderUpComponent::NAME));
+
+                enabled && !has_back_button && !screen.buttons.contains_key(&key)
+            } else {
+                false
+            }
+        });
+
+        if show {
+            let mut button = Button::new();
+            button.insert_component(FolderUpComponent {}).ok();
+            button.insert_component(back_button_looks()).ok();
+
+            let mut buttons = UniqueButtonMap::new();
+            buttons.insert(key, make_button_unique(button));
+
+            push_overlay(core, BACK_BUTTON_OVERLAY, buttons);
+        } else {
+            remove_overlay(core, BACK_BUTTON_OVERLAY);
+        }
+    }
+
     /// Deletes folder with all folders that are linked from the folder recursively
     fn delete_folder_recursively(&self, core: &CoreHandle, folder_id: &str, ids: &mut HashSet<String>) {
         if let Some(folder) = self.get_folder(core, folder_id) {
@@ -523,6 +804,11 @@ pub struct FolderComponent {
     #[serde(default)]
     pub id: String,
     pub name: String,
+    /// Shows first buttons of the folder as background
+    #[serde(default)]
+    pub thumbnail: bool,
+    #[serde(default)]
+    pub back_button: BackButtonMode,
 }
 
 impl Component for FolderComponent {
@@ -546,7 +832,462 @@ impl Component for FolderUpComponent {
     const NAME: &'static str = "folder_up";
 }
 
+/// Data that folder module puts into panels of opened folders
 #[derive(Serialize, Deserialize)]
 pub struct FolderStackData {
-    folder_id: String,
-}
\ No newline at end of file
+    pub folder_id: String,
+    #[serde(default)]
+    pub back_button: BackButtonMode,
+}
+
+/// Whether opened folder shows automatic back button
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum BackButtonMode {
+    /// Follows folder module settings
+    Default,
+    Always,
+    Never,
+}
+
+impl Default for BackButtonMode {
+    fn default() -> Self {
+        BackButtonMode::Default
+    }
+}
+
+impl BackButtonMode {
+    const CHOICES: [&'static str; 3] = ["Default", "Always", "Never"];
+
+    fn as_choice(&self) -> &'static str {
+        match self {
+            BackButtonMode::Default => Self::CHOICES[0],
+            BackButtonMode::Always => Self::CHOICES[1],
+            BackButtonMode::Never => Self::CHOICES[2],
+        }
+    }
+
+    fn from_choice(choice: &str) -> Option<BackButtonMode> {
+        match Self::CHOICES.iter().position(|x| *x == choice)? {
+            0 => Some(BackButtonMode::Default),
+            1 => Some(BackButtonMode::Always),
+            _ => Some(BackButtonMode::Never),
+        }
+    }
+}
+
+/// Corner of the device
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum BackButtonCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Default for BackButtonCorner {
+    fn default() -> Self {
+        BackButtonCorner::TopLeft
+    }
+}
+
+impl BackButtonCorner {
+    const CHOICES: [&'static str; 4] = ["Top Left", "Top Right", "Bottom Left", "Bottom Right"];
+
+    fn as_choice(&self) -> &'static str {
+        match self {
+            BackButtonCorner::TopLeft => Self::CHOICES[0],
+            BackButtonCorner::TopRight => Self::CHOICES[1],
+            BackButtonCorner::BottomLeft => Self::CHOICES[2],
+            BackButtonCorner::BottomRight => Self::CHOICES[3],
+        }
+    }
+
+    fn from_choice(choice: &str) -> Option<BackButtonCorner> {
+        match Self::CHOICES.iter().position(|x| *x == choice)? {
+            0 => Some(BackButtonCorner::TopLeft),
+            1 => Some(BackButtonCorner::TopRight),
+            2 => Some(BackButtonCorner::BottomLeft),
+            _ => Some(BackButtonCorner::BottomRight),
+        }
+    }
+
+    /// Key index of the corner on the device
+    fn key(&self, core: &CoreHandle) -> u8 {
+        let core = core.core();
+        let columns = visible_columns(&core);
+        let last = core.key_count.saturating_sub(1);
+
+        match self {
+            BackButtonCorner::TopLeft => 0,
+            BackButtonCorner::TopRight => columns.saturating_sub(1),
+            BackButtonCorner::BottomLeft => last - last % columns,
+            BackButtonCorner::BottomRight => last,
+        }
+    }
+}
+
+/// Settings of folder module
+#[derive(Serialize, Deserialize, Default)]
+pub struct FolderSettings {
+    /// Shows back button in opened folders that don't have one
+    #[serde(default)]
+    pub auto_back: bool,
+    #[serde(default)]
+    pub back_corner: BackButtonCorner,
+}
+
+impl PluginConfig for FolderSettings {
+    const NAME: &'static str = MODULE_NAME;
+}
+
+/// Default looks of back button
+fn back_button_looks() -> RendererComponent {
+    RendererComponentBuilder::new()
+        .background(ButtonBackground::Solid((50, 50, 50, 255)))
+        .add_text(ButtonText {
+            text: "Back".to_string(),
+            font: "default".to_string(),
+            scale: (22.0, 22.0),
+            alignment: TextAlignment::Center,
+            padding: 0,
+            offset: (0.0, 0.0),
+            color: (255, 255, 255, 255),
+            shadow: None,
+            auto_fit: false,
+            word_wrap: false,
+            marquee_speed: 0.0
+        })
+        .build()
+}
+
+/// Identifier that folder thumbnail is kept under in image collection
+fn thumbnail_identifier(folder_id: &str) -> String {
+    format!("folder_thumbnail:{}", folder_id)
+}
+
+/// Returns panel of the folder, either the one that's opened on the stack or a new one made from folder contents
+pub fn folder_panel(core: &CoreHandle, folder_id: &str) -> Option<ButtonPanel> {
+    let opened = get_stack(core).into_iter().rev().find(|panel| {
+        serde_json::from_value::<FolderStackData>(panel.read().unwrap().data.clone())
+            .map_or(false, |x| x.folder_id == folder_id)
+    });
+
+    if opened.is_some() {
+        return opened;
+    }
+
+    let mut contents = folders_of(&core.core().device_config.read().unwrap()).remove(folder_id)?;
+    contents.data = serde_json::to_value(FolderStackData {
+        folder_id: folder_id.to_string(),
+        back_button: Default::default()
+    }).unwrap();
+
+    Some(make_panel_unique(contents))
+}
+
+/// Information about folder of a device
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FolderInfo {
+    pub id: String,
+    pub name: String,
+    pub button_count: usize,
+    /// Folder can't be reached from root screen through folder buttons
+    pub orphaned: bool,
+}
+
+/// Lists folders kept in device config
+pub fn list_config_folders(config: &DeviceConfig) -> Vec<FolderInfo> {
+    let orphaned = orphaned_config_folders(config);
+
+    let mut folders = folders_of(config).into_iter()
+        .map(|(id, folder)| FolderInfo {
+            orphaned: orphaned.contains(&id),
+            id,
+            name: folder.display_name,
+            button_count: folder.buttons.len()
+        })
+        .collect::<Vec<FolderInfo>>();
+
+    folders.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+    folders
+}
+
+/// Renames folder in device config together with buttons that open it, returns false if folder wasn't found
+pub fn rename_config_folder(config: &mut DeviceConfig, folder_id: &str, name: &str) -> bool {
+    let mut folders = folders_of(config);
+
+    if let Some(folder) = folders.get_mut(folder_id) {
+        folder.display_name = name.to_string();
+    } else {
+        return false;
+    }
+
+    let rename = |buttons: &mut ButtonMap| {
+        for button in buttons.values_mut() {
+            if let Ok(mut component) = parse_button_to_component::<FolderComponent>(button) {
+                if component.id == folder_id {
+                    component.name = name.to_string();
+                    button.insert_component(component).ok();
+                }
+            }
+        }
+    };
+
+    rename(&mut config.layout.buttons);
+    folders.values_mut().for_each(|x| rename(&mut x.buttons));
+
+    config.plugin_data.insert(FOLDERS_KEY.to_string(), serde_json::to_value(folders).unwrap());
+    true
+}
+
+/// Deletes folder from device config with all folders inside of it, buttons that open the folder are removed too.
+/// Returns false if folder wasn't found
+pub fn delete_config_folder(config: &mut DeviceConfig, folder_id: &str) -> bool {
+    let mut folders = folders_of(config);
+
+    if !folders.contains_key(folder_id) {
+        return false;
+    }
+
+    let mut deleted = HashSet::new();
+    collect_contained_folders(&folders, folder_id, &mut deleted);
+
+    for id in &deleted {
+        folders.remove(id);
+    }
+
+    let remove_buttons = |buttons: &mut ButtonMap| {
+        buttons.retain(|_, button| folder_target(button).map_or(true, |x| !deleted.contains(&x)));
+    };
+
+    remove_buttons(&mut config.layout.buttons);
+    folders.values_mut().for_each(|x| remove_buttons(&mut x.buttons));
+
+    config.plugin_data.insert(FOLDERS_KEY.to_string(), serde_json::to_value(folders).unwrap());
+    true
+}
+
+/// Finds folders that can't be reached from root screen through folder or folder link buttons
+pub fn orphaned_config_folders(config: &DeviceConfig) -> Vec<String> {
+    let folders = folders_of(config);
+
+    let mut reachable = HashSet::new();
+    for id in config.layout.buttons.values().filter_map(folder_target) {
+        collect_contained_folders(&folders, &id, &mut reachable);
+    }
+
+    let mut orphaned = folders.into_keys()
+        .filter(|x| !reachable.contains(x))
+        .collect::<Vec<String>>();
+
+    orphaned.sort();
+    orphaned
+}
+
+/// Deletes orphaned folders from device config, returns IDs of deleted folders
+pub fn remove_orphaned_config_folders(config: &mut DeviceConfig) -> Vec<String> {
+    let orphaned = orphaned_config_folders(config);
+
+    if !orphaned.is_empty() {
+        let mut folders = folders_of(config);
+        orphaned.iter().for_each(|x| { folders.remove(x); });
+        config.plugin_data.insert(FOLDERS_KEY.to_string(), serde_json::to_value(folders).unwrap());
+    }
+
+    orphaned
+}
+
+/// Folder on the way to a button found with [find_config_buttons]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FolderStep {
+    pub id: String,
+    pub name: String,
+}
+
+/// Button found in device config
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FoundButton {
+    /// Folders to open from root screen to get to the button, empty for buttons on root screen.
+    /// Paths of buttons in orphaned folders start with the orphaned folder
+    pub path: Vec<FolderStep>,
+    pub key: u8,
+    pub button: Button,
+}
+
+/// Finds buttons on root screen and in all folders of device config that have the component and contain the text
+/// in any of their component values, case-insensitively. Buttons are listed by shortest path, then by key
+pub fn find_config_buttons(config: &DeviceConfig, component_name: Option<&str>, query: Option<&str>) -> Vec<FoundButton> {
+    let folders = folders_of(config);
+    let query = query.map(|x| x.to_lowercase());
+
+    let matches = |button: &Button| {
+        component_name.map_or(true, |x| button.contains(x))
+            && query.as_ref().map_or(true, |x| button.0.values().any(|value| value_contains(value, x)))
+    };
+
+    let mut folder_ids: Vec<&String> = folders.keys().collect();
+    folder_ids.sort();
+
+    let mut found = vec![];
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([(vec![], &config.layout.buttons)]);
+
+    loop {
+        while let Some((path, buttons)) = queue.pop_front() {
+            let mut keys: Vec<&u8> = buttons.keys().collect();
+            keys.sort();
+
+            for key in keys {
+                let button = &buttons[key];
+
+                if matches(button) {
+                    found.push(FoundButton {
+                        path: path.clone(),
+                        key: *key,
+                        button: button.clone()
+                    });
+                }
+
+                if let Some((id, folder)) = folder_target(button).and_then(|id| folders.get_key_value(&id)) {
+                    if visited.insert(id) {
+                        let mut path = path.clone();
+                        path.push(FolderStep { id: id.clone(), name: folder.display_name.clone() });
+                        queue.push_back((path, &folder.buttons));
+                    }
+                }
+            }
+        }
+
+        // Continuing with orphaned folders that weren't reached from root screen
+        match folder_ids.iter().find(|x| !visited.contains(**x)) {
+            Some(id) => {
+                visited.insert(*id);
+
+                let folder = &folders[*id];
+                queue.push_back((vec![FolderStep { id: (*id).clone(), name: folder.display_name.clone() }], &folder.buttons));
+            }
+            None => break
+        }
+    }
+
+    found
+}
+
+/// Folder hierarchy of a device
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FolderTree {
+    pub root: FolderNode,
+    /// Folders that can't be reached from root screen, with folders inside of them
+    pub orphaned: Vec<FolderNode>,
+}
+
+/// Panel in folder tree
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FolderNode {
+    /// Folder ID, [None] for root screen
+    pub id: Option<String>,
+    pub name: String,
+    /// Key of the button that opens the folder in parent panel
+    pub key: Option<u8>,
+    /// Folder is already listed earlier in the tree, for folder links and folders that contain themselves, so children aren't listed here
+    pub reference: bool,
+    pub button_count: usize,
+    /// Buttons of the panel, only included if requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buttons: Option<ButtonMap>,
+    /// Folders that buttons of the panel open, ordered by key. Folder is listed with its children where it's reached first
+    pub children: Vec<FolderNode>,
+}
+
+/// Builds folder hierarchy of device config, starting at root screen
+pub fn config_folder_tree(config: &DeviceConfig, include_buttons: bool) -> FolderTree {
+    let folders = folders_of(config);
+    let mut visited = HashSet::new();
+
+    let root = folder_node(None, &config.layout, None, &folders, include_buttons, &mut visited);
+
+    let mut folder_ids: Vec<&String> = folders.keys().collect();
+    folder_ids.sort();
+
+    let mut orphaned = vec![];
+    for id in folder_ids {
+        if visited.insert(id.clone()) {
+            orphaned.push(folder_node(Some(id), &folders[id], None, &folders, include_buttons, &mut visited));
+        }
+    }
+
+    FolderTree {
+        root,
+        orphaned
+    }
+}
+
+fn folder_node(id: Option<&String>, panel: &RawButtonPanel, key: Option<u8>, folders: &FolderMap, include_buttons: bool, visited: &mut HashSet<String>) -> FolderNode {
+    let mut keys: Vec<&u8> = panel.buttons.keys().collect();
+    keys.sort();
+
+    let mut children = vec![];
+    for key in keys {
+        let button = &panel.buttons[key];
+
+        if let Some((child_id, child)) = folder_target(button).and_then(|id| folders.get_key_value(&id)) {
+            if visited.insert(child_id.clone()) {
+                children.push(folder_node(Some(child_id), child, Some(*key), folders, include_buttons, visited));
+            } else {
+                children.push(FolderNode {
+                    id: Some(child_id.clone()),
+                    name: child.display_name.clone(),
+                    key: Some(*key),
+                    reference: true,
+                    button_count: child.buttons.len(),
+                    buttons: None,
+                    children: vec![]
+                });
+            }
+        }
+    }
+
+    FolderNode {
+        id: id.cloned(),
+        name: panel.display_name.clone(),
+        key,
+        reference: false,
+        button_count: panel.buttons.len(),
+        buttons: if include_buttons { Some(panel.buttons.clone()) } else { None },
+        children
+    }
+}
+
+/// Checks if any string in the value contains lowercase query
+fn value_contains(value: &serde_json::Value, query: &str) -> bool {
+    match value {
+        serde_json::Value::String(str) => str.to_lowercase().contains(query),
+        serde_json::Value::Array(array) => array.iter().any(|x| value_contains(x, query)),
+        serde_json::Value::Object(object) => object.values().any(|x| value_contains(x, query)),
+        _ => false
+    }
+}
+
+/// Collects folder with IDs of all folders that are inside of it through folder or folder link buttons
+fn collect_contained_folders(folders: &FolderMap, folder_id: &str, ids: &mut HashSet<String>) {
+    if !ids.insert(folder_id.to_string()) {
+        return;
+    }
+
+    if let Some(folder) = folders.get(folder_id) {
+        for id in folder.buttons.values().filter_map(folder_target) {
+            collect_contained_folders(folders, &id, ids);
+        }
+    }
+}
+
+/// Returns ID of folder that button opens, if any
+fn folder_target(button: &Button) -> Option<String> {
+    if let Ok(component) = parse_button_to_component::<FolderComponent>(button) {
+        Some(component.id)
+    } else if let Ok(link) = parse_button_to_component::<FolderLinkComponent>(button) {
+        Some(link.id)
+    } else {
+        None
+    }
+}
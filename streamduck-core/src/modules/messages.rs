@@ -0,0 +1,150 @@
+//! Publish/subscribe message bus that lets modules exchange JSON messages, eg. module that tracks state of some app
+//! can publish it for modules that render it on buttons
+//!
+//! Topics are plain strings, subscription to "name.*" receives all topics starting with "name.", subscription to "*" receives everything.
+//! Bus remembers last data of every topic, so state published by modules can be looked up later, eg. by [conditions](crate::modules::conditions)
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread::spawn;
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use crate::core::methods::CoreHandle;
+
+/// Name that is used as sender for messages that came from socket clients
+pub const SOCKET_MESSAGE_SENDER: &'static str = "-socket-";
+
+/// Identifier of subscription
+pub type SubscriptionId = u64;
+
+/// Callback that receives messages
+pub type MessageCallback = Arc<dyn Fn(ModuleMessage) + Send + Sync>;
+
+/// Message that is sent between modules
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModuleMessage {
+    pub topic: String,
+    /// Name of module that sent the message
+    pub sender: String,
+    /// Serial number of device the message is related to, if any
+    pub serial_number: Option<String>,
+    pub data: Value,
+}
+
+impl ModuleMessage {
+    /// Creates message with data serialized from a type
+    pub fn new<T: Serialize>(topic: &str, sender: &str, serial_number: Option<String>, data: &T) -> Result<ModuleMessage, serde_json::Error> {
+        Ok(ModuleMessage {
+            topic: topic.to_string(),
+            sender: sender.to_string(),
+            serial_number,
+            data: serde_json::to_value(data)?
+        })
+    }
+
+    /// Parses data of the message into a type
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_value(self.data.clone())
+    }
+}
+
+struct Subscription {
+    id: SubscriptionId,
+    module: String,
+    topic: String,
+    callback: MessageCallback,
+}
+
+/// Message bus, kept in [ModuleManager](crate::modules::ModuleManager)
+#[derive(Default)]
+pub struct MessageBus {
+    subscriptions: RwLock<Vec<Subscription>>,
+    /// Last data that was published to each topic
+    retained: RwLock<HashMap<String, Value>>,
+    counter: AtomicU64,
+}
+
+impl MessageBus {
+    /// Subscribes module to a topic, callback is called on a separate thread for every message sent to the topic
+    pub fn subscribe(&self, module_name: &str, topic: &str, callback: impl Fn(ModuleMessage) + Send + Sync + 'static) -> SubscriptionId {
+        let id = self.counter.fetch_add(1, Ordering::SeqCst);
+
+        self.subscriptions.write().unwrap().push(Subscription {
+            id,
+            module: module_name.to_string(),
+            topic: topic.to_string(),
+            callback: Arc::new(callback)
+        });
+
+        id
+    }
+
+    /// Removes subscription, returns false if it didn't exist
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut subscriptions = self.subscriptions.write().unwrap();
+        let count = subscriptions.len();
+        subscriptions.retain(|x| x.id != id);
+        count != subscriptions.len()
+    }
+
+    /// Removes all subscriptions of a module
+    pub fn unsubscribe_module(&self, module_name: &str) {
+        self.subscriptions.write().unwrap().retain(|x| x.module != module_name);
+    }
+
+    /// Topics that modules are subscribed to
+    pub fn topics(&self) -> Vec<String> {
+        let mut topics: Vec<String> = self.subscriptions.read().unwrap().iter().map(|x| x.topic.clone()).collect();
+        topics.sort();
+        topics.dedup();
+        topics
+    }
+
+    /// Last data that was published to the topic
+    pub fn state(&self, topic: &str) -> Option<Value> {
+        self.retained.read().unwrap().get(topic).cloned()
+    }
+
+    /// Sends message to every subscriber of its topic except the sender, returns amount of subscriptions that will receive the message
+    pub fn publish(&self, message: ModuleMessage) -> usize {
+        self.retained.write().unwrap().insert(message.topic.clone(), message.data.clone());
+
+        let callbacks: Vec<MessageCallback> = self.subscriptions.read().unwrap().iter()
+            .filter(|x| x.module != message.sender && topic_matches(&x.topic, &message.topic))
+            .map(|x| x.callback.clone())
+            .collect();
+
+        let count = callbacks.len();
+
+        if count > 0 {
+            spawn(move || {
+                for callback in callbacks {
+                    callback(message.clone());
+                }
+            });
+        }
+
+        count
+    }
+}
+
+/// Checks if subscription topic covers topic of a message
+pub fn topic_matches(subscription: &str, topic: &str) -> bool {
+    if subscription == "*" {
+        true
+    } else if let Some(prefix) = subscription.strip_suffix('*') {
+        topic.starts_with(prefix)
+    } else {
+        subscription == topic
+    }
+}
+
+/// Publishes message on behalf of module, with device of the core attached. Returns amount of subscriptions that will receive the message
+pub fn publish_message<T: Serialize>(core: &CoreHandle, topic: &str, data: &T) -> Result<usize, serde_json::Error> {
+    core.required_feature("messages");
+
+    let message = ModuleMessage::new(topic, &core.module_name, Some(core.core.serial_number()), data)?;
+    Ok(core.core.module_manager.message_bus().publish(message))
+}
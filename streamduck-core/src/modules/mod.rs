@@ -1,4 +1,4 @@
-mod folders;
+pub mod folders;
 
 /// Definitions for UI controls for components
 pub mod components;
@@ -6,17 +6,46 @@ pub mod components;
 pub mod events;
 pub mod plugins;
 pub mod core_module;
+/// Background task scheduler for modules
+pub mod scheduler;
+/// Message bus for communication between modules
+pub mod messages;
+/// Dependencies between plugins and load ordering
+pub mod dependencies;
+/// Conditions that hide or disable buttons
+pub mod conditions;
+/// Buttons that cycle through several states
+pub mod states;
+/// Groups of buttons where only one is active
+pub mod radio;
+/// Buttons that rotate through pages of content
+pub mod carousel;
+/// Actions that run on cron-like schedules
+pub mod schedules;
+/// Buttons that change device brightness
+pub mod brightness;
+/// Buttons that open folders on other devices
+pub mod device_switch;
 
 use std::collections::HashMap;
 use std::hash::Hasher;
-use std::sync::{Arc, RwLock, RwLockReadGuard};
+use std::sync::{Arc, RwLock, RwLockReadGuard, Weak};
 use std::thread::spawn;
 
 use crate::core::button::{Button};
 use crate::core::methods::{check_feature_list_for_feature, CoreHandle};
-use crate::modules::components::{ComponentDefinition, UIPathValue, UIValue};
+use crate::core::revisions::record_edit;
+use crate::core::saved_stack::remember_stack;
+use crate::modules::components::{ComponentCompatibilityError, ComponentDefinition, FieldError, UIPathValue, UIValue};
+use crate::modules::dependencies::{DependencyError, DependencyNode, PluginDependency, resolve_load_order, ResolvedDependency};
+use crate::logging::with_log_context;
 use crate::modules::events::{SDCoreEvent, SDGlobalEvent};
 use crate::modules::folders::FolderModule;
+use crate::modules::brightness::BrightnessModule;
+use crate::modules::device_switch::DeviceSwitchModule;
+use crate::modules::messages::MessageBus;
+use crate::modules::plugins::PluginHealth;
+use crate::modules::scheduler::TaskScheduler;
 
 use serde::{Deserialize, Serialize};
 
@@ -39,6 +68,24 @@ pub struct ModuleManager {
 
     /// Separate list of modules that can render things
     rendering_modules: RwLock<HashMap<String, HashMap<String, UniqueSDModule>>>,
+
+    /// Background tasks of modules
+    scheduler: TaskScheduler,
+
+    /// Messages between modules
+    message_bus: MessageBus,
+
+    /// Names of modules in order they were added
+    load_order: RwLock<Vec<String>>,
+
+    /// Crash state of loaded plugins
+    plugin_health: RwLock<HashMap<String, Arc<PluginHealth>>>,
+
+    /// Reasons why plugins failed to load, by plugin name or file name
+    plugin_failures: RwLock<HashMap<String, String>>,
+
+    /// Core manager that uses this module manager, for reaching cores of other devices
+    core_manager: RwLock<Weak<CoreManager>>,
 }
 
 impl ModuleManager {
@@ -56,6 +103,11 @@ impl ModuleManager {
         module_map.insert(module_name.clone(), module.clone());
         drop(module_map);
 
+        let mut load_order = self.load_order.write().unwrap();
+        load_order.retain(|x| x != &module_name);
+        load_order.push(module_name.clone());
+        drop(load_order);
+
         // Adding to module component map
         let mut module_component_map = self.module_component_map.write().unwrap();
         for (component, definition) in module.components() {
@@ -109,6 +161,117 @@ impl ModuleManager {
         drop(rendering_modules);
     }
 
+    /// Removes module from module manager, cancelling all of its background tasks and message subscriptions
+    pub fn remove_module(&self, name: &str) -> Option<UniqueSDModule> {
+        let module = self.module_map.write().unwrap().remove(name)?;
+        self.load_order.write().unwrap().retain(|x| x != name);
+        self.plugin_health.write().unwrap().remove(name);
+
+        self.module_component_map.write().unwrap().remove(name);
+        self.component_map.write().unwrap().retain(|_, (_, x)| x.name() != name);
+
+        let mut component_listener_map = self.component_listener_map.write().unwrap();
+        for listeners in component_listener_map.values_mut() {
+            listeners.retain(|x| x.name() != name);
+        }
+        component_listener_map.retain(|_, x| !x.is_empty());
+        drop(component_listener_map);
+
+        let mut rendering_modules = self.rendering_modules.write().unwrap();
+        for map in rendering_modules.values_mut() {
+            map.remove(name);
+        }
+        rendering_modules.retain(|_, x| !x.is_empty());
+        drop(rendering_modules);
+
+        self.scheduler.cancel_module(name);
+        self.message_bus.unsubscribe_module(name);
+
+        Some(module)
+    }
+
+    /// Scheduler of background tasks, see [scheduler] module for functions that modules should use
+    pub fn scheduler(&self) -> &TaskScheduler {
+        &self.scheduler
+    }
+
+    /// Message bus for sending messages between modules, see [messages] module for details
+    pub fn message_bus(&self) -> &MessageBus {
+        &self.message_bus
+    }
+
+    /// Links core manager to module manager, done by core manager on creation
+    pub(crate) fn set_core_manager(&self, core_manager: &Arc<CoreManager>) {
+        *self.core_manager.write().unwrap() = Arc::downgrade(core_manager);
+    }
+
+    /// Core manager that uses this module manager, [None] if there's no core manager
+    pub fn core_manager(&self) -> Option<Arc<CoreManager>> {
+        self.core_manager.read().unwrap().upgrade()
+    }
+
+    /// Sets crash state of a plugin, done by plugin loader
+    pub fn set_plugin_health(&self, name: &str, health: Arc<PluginHealth>) {
+        self.plugin_health.write().unwrap().insert(name.to_string(), health);
+    }
+
+    /// Gets crash state of a plugin, [None] if module isn't a plugin
+    pub fn plugin_health(&self, name: &str) -> Option<Arc<PluginHealth>> {
+        self.plugin_health.read().unwrap().get(name).cloned()
+    }
+
+    /// Remembers that plugin failed to load, done by plugin loader
+    pub(crate) fn add_plugin_failure(&self, name: &str, reason: &str) {
+        self.plugin_failures.write().unwrap().insert(name.to_string(), reason.to_string());
+    }
+
+    /// Plugins that failed to load with reasons why, by plugin name or file name if plugin couldn't be opened
+    pub fn plugin_failures(&self) -> HashMap<String, String> {
+        self.plugin_failures.read().unwrap().clone()
+    }
+
+    /// Orders plugins so they're loaded after their dependencies, already added modules count as available dependencies.
+    /// Returns plugins in order they should be added, and plugins that can't be loaded
+    pub fn resolve_load_order<T>(&self, candidates: Vec<(PluginMetadata, T)>) -> (Vec<(PluginMetadata, T)>, Vec<(String, DependencyError)>) {
+        let loaded: Vec<PluginMetadata> = self.get_module_list().iter().map(|x| x.metadata()).collect();
+        resolve_load_order(&loaded, candidates)
+    }
+
+    /// Checks if dependencies of a plugin are satisfied by modules that were already added
+    pub fn check_dependencies(&self, metadata: &PluginMetadata) -> Result<(), DependencyError> {
+        let (_, mut failed) = self.resolve_load_order(vec![(metadata.clone(), ())]);
+
+        if let Some((_, error)) = failed.pop() {
+            Err(error)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Modules in order they were loaded, with dependencies resolved to versions of currently loaded modules
+    pub fn dependency_graph(&self) -> Vec<DependencyNode> {
+        let modules = self.get_modules();
+
+        self.load_order.read().unwrap().iter()
+            .filter_map(|name| modules.get(name))
+            .map(|module| {
+                let metadata = module.metadata();
+
+                DependencyNode {
+                    name: module.name(),
+                    version: metadata.version,
+                    dependencies: metadata.dependencies.into_iter()
+                        .map(|dependency| ResolvedDependency {
+                            resolved_version: modules.get(&dependency.name).map(|x| x.metadata().version),
+                            name: dependency.name,
+                            version: dependency.version
+                        })
+                        .collect()
+                }
+            })
+            .collect()
+    }
+
     /// Attempts to get module with specified name
     pub fn get_module(&self, name: &str) -> Option<UniqueSDModule> {
         self.get_modules().get(name).cloned()
@@ -233,6 +396,47 @@ impl ModuleManager {
         self.component_map.read().unwrap().get(component_name).cloned()
     }
 
+    /// Checks if component can be added onto a button that has specified components
+    pub fn check_component_addition(&self, existing: &[String], component_name: &str) -> Result<(), ComponentCompatibilityError> {
+        let components = self.component_map.read().unwrap();
+
+        if let Some((definition, _)) = components.get(component_name) {
+            if let Some(missing) = definition.requires.iter().find(|x| !existing.contains(x)) {
+                return Err(ComponentCompatibilityError::MissingRequirement(missing.clone()));
+            }
+
+            if let Some(conflict) = definition.conflicts_with.iter().find(|x| existing.contains(x)) {
+                return Err(ComponentCompatibilityError::ConflictsWith(conflict.clone()));
+            }
+        }
+
+        // Conflicts declared by components that are already on the button
+        for name in existing {
+            if let Some((definition, _)) = components.get(name) {
+                if definition.conflicts_with.iter().any(|x| x == component_name) {
+                    return Err(ComponentCompatibilityError::ConflictsWith(name.clone()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks if component can be removed from a button that has specified components
+    pub fn check_component_removal(&self, existing: &[String], component_name: &str) -> Result<(), ComponentCompatibilityError> {
+        let components = self.component_map.read().unwrap();
+
+        for name in existing.iter().filter(|x| *x != component_name) {
+            if let Some((definition, _)) = components.get(name) {
+                if definition.requires.iter().any(|x| x == component_name) {
+                    return Err(ComponentCompatibilityError::RequiredBy(name.clone()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Returns module map read lock
     pub fn read_module_map(&self) -> RwLockReadGuard<HashMap<String, UniqueSDModule>> {
         self.module_map.read().unwrap()
@@ -263,6 +467,8 @@ impl ModuleManager {
 pub fn load_base_modules(module_manager: Arc<ModuleManager>, socket_manager: Arc<SocketManager>) {
     module_manager.add_module(Arc::new(Box::new(CoreModule { socket_manager })));
     module_manager.add_module(Arc::new(Box::new(FolderModule::default())));
+    module_manager.add_module(Arc::new(Box::new(BrightnessModule::default())));
+    module_manager.add_module(Arc::new(Box::new(DeviceSwitchModule::default())));
 }
 
 /// Reference counted module object
@@ -297,8 +503,9 @@ pub trait SDModule: Send + Sync {
     /// Method for letting core know what values component currently has
     fn component_values(&self, core: CoreHandle, button: &Button, name: &str) -> Vec<UIValue>;
 
-    /// Method for setting values on components
-    fn set_component_value(&self, core: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>);
+    /// Method for setting values on components, errors should be returned for values that were rejected.
+    /// Button is reverted to its previous state if errors are returned
+    fn set_component_value(&self, core: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>>;
 
     /// Specifies which components the module will be receiving events for
     fn listening_for(&self) -> Vec<String>;
@@ -324,6 +531,11 @@ pub trait SDModule: Send + Sync {
     /// This method will also called very frequently, so keep code in here fast
     fn render_hash(&self, core: CoreHandle, button: &UniqueButton, hash: &mut Box<dyn Hasher>) {}
 
+    /// Frames per second that the button needs to be redrawn at, for anything that changes with time.
+    ///
+    /// None means that button only changes on events, device thread lowers its rate when nothing on the screen needs to be redrawn
+    fn refresh_rate(&self, core: CoreHandle, button: &UniqueButton) -> Option<f32> { None }
+
     /// Metadata of the module, auto-implemented for plugins from plugin metadata
     fn metadata(&self) -> PluginMetadata {
         let mut meta = PluginMetadata::default();
@@ -346,7 +558,10 @@ pub struct PluginMetadata {
     /// Version of the plugin
     pub version: String,
     /// Used features of the plugin, used to determine if plugin is compatible with different software versions, see [crate::versions]
-    pub used_features: Vec<(String, String)>
+    pub used_features: Vec<(String, String)>,
+    /// Plugins that have to be loaded before this plugin, see [dependencies]
+    #[serde(default)]
+    pub dependencies: Vec<PluginDependency>
 }
 
 impl PluginMetadata {
@@ -357,9 +572,20 @@ impl PluginMetadata {
             author: author.to_string(),
             description: description.to_string(),
             version: version.to_string(),
-            used_features: features_to_vec(used_features)
+            used_features: features_to_vec(used_features),
+            dependencies: vec![]
         }
     }
+
+    /// Adds dependencies on other plugins, as pairs of plugin name and semver range of compatible versions
+    pub fn with_dependencies(mut self, dependencies: &[(&str, &str)]) -> PluginMetadata {
+        self.dependencies.extend(dependencies.iter().map(|(name, version)| PluginDependency {
+            name: name.to_string(),
+            version: version.to_string()
+        }));
+
+        self
+    }
 }
 
 /// Retrieves module settings in array of UIPathValue
@@ -420,6 +646,9 @@ pub fn set_module_setting(core_manager: Arc<CoreManager>, module: &UniqueSDModul
 
 /// Sends core event to all modules, spawns a separate thread to do it, so doesn't block current thread
 pub fn send_core_event_to_modules<T: Iterator<Item=UniqueSDModule> + Send + 'static>(core: &CoreHandle, event: SDCoreEvent, modules: T) {
+    record_edit(core, &event);
+    remember_stack(core, &event);
+
     let core = core.clone();
     spawn(move || {
         for module in modules {
@@ -427,7 +656,9 @@ pub fn send_core_event_to_modules<T: Iterator<Item=UniqueSDModule> + Send + 'sta
                 continue;
             }
 
-            module.event(core.clone_for(&module), event.clone())
+            with_log_context(Some(&core.core.serial_number), Some(&module.name()), || {
+                module.event(core.clone_for(&module), event.clone())
+            });
         }
     });
 }
@@ -435,7 +666,9 @@ pub fn send_core_event_to_modules<T: Iterator<Item=UniqueSDModule> + Send + 'sta
 /// Sends global event to all modules, spawns a separate thread to do it, so doesn't block current thread
 pub fn send_global_event_to_modules<T: Iterator<Item=UniqueSDModule> + Send + 'static>(event: SDGlobalEvent, modules: T) {
     spawn(move || {
-        modules.for_each(|x| x.global_event(event.clone()));
+        modules.for_each(|x| {
+            with_log_context(None, Some(&x.name()), || x.global_event(event.clone()))
+        });
     });
 }
 
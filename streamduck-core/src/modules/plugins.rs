@@ -1,11 +1,15 @@
 //! Plugin API for loading dynamic library files
 
+use std::any::Any;
 use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::fs;
 use std::hash::Hasher;
+use std::panic::{AssertUnwindSafe, catch_unwind};
 use std::path::Path;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Instant;
 use dlopen::Error;
 use crate::modules::{BoxedSDModule, ModuleManager, PluginMetadata, SDModule, SDModulePointer};
 use dlopen::wrapper::{Container, WrapperApi};
@@ -14,25 +18,174 @@ use image::DynamicImage;
 use crate::core::button::Button;
 use crate::core::manager::CoreManager;
 use crate::core::methods::{check_feature_list_for_feature, CoreHandle, warn_for_feature};
-use crate::core::UniqueButton;
-use crate::modules::components::{ComponentDefinition, UIValue};
+use crate::core::{ButtonPanel, UniqueButton};
+use crate::modules::components::{ComponentDefinition, FieldError, UIValue};
+use crate::modules::dependencies::DependencyError;
+use crate::metrics::record_plugin_callback;
 use crate::modules::events::{SDCoreEvent, SDGlobalEvent};
 use crate::RenderingManager;
-use crate::socket::SocketManager;
+use crate::socket::{send_event_to_socket, SocketManager};
 use crate::versions::SUPPORTED_FEATURES;
 
+/// Entry points of a plugin, they use Rust ABI like plugins declare them, so panics can unwind into [catch_unwind]
 #[derive(WrapperApi)]
 struct PluginApi {
-    get_metadata: extern fn() -> PluginMetadata,
-    get_module: extern fn() -> SDModulePointer,
-    register: extern fn(socket_manager: Arc<SocketManager>, render_manager: Arc<RenderingManager>, module_manager: Arc<ModuleManager>),
+    get_metadata: fn() -> PluginMetadata,
+    get_module: fn() -> SDModulePointer,
+    register: fn(socket_manager: Arc<SocketManager>, render_manager: Arc<RenderingManager>, module_manager: Arc<ModuleManager>),
+}
+
+/// Amount of panics in a row after which plugin gets disabled
+pub const PLUGIN_PANIC_LIMIT: usize = 3;
+
+/// Crash state of a plugin, shared between plugin proxy and [ModuleManager]
+#[derive(Default)]
+pub struct PluginHealth {
+    consecutive_panics: AtomicUsize,
+    disabled: AtomicBool,
+}
+
+impl PluginHealth {
+    /// If plugin was disabled after panicking too many times, disabled plugins don't receive any callbacks
+    pub fn is_disabled(&self) -> bool {
+        self.disabled.load(Ordering::Relaxed)
+    }
+
+    /// Enables plugin again and resets its panic counter
+    pub fn reenable(&self) {
+        self.consecutive_panics.store(0, Ordering::Relaxed);
+        self.disabled.store(false, Ordering::Relaxed);
+    }
+
+    fn succeeded(&self) {
+        self.consecutive_panics.store(0, Ordering::Relaxed);
+    }
+
+    /// Counts the panic, returns true if plugin got disabled because of it
+    fn panicked(&self) -> bool {
+        let panics = self.consecutive_panics.fetch_add(1, Ordering::Relaxed) + 1;
+
+        if panics >= PLUGIN_PANIC_LIMIT {
+            !self.disabled.swap(true, Ordering::Relaxed)
+        } else {
+            false
+        }
+    }
+}
+
+/// Gets message out of panic payload
+fn panic_message(payload: &Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Clears poison that a panicking plugin left on a button, so rest of the program can keep using it
+fn recover_button(button: &UniqueButton) {
+    button.clear_poison();
+}
+
+/// Clears poison that a panicking plugin left on a panel or its buttons
+fn recover_panel(panel: &ButtonPanel) {
+    panel.clear_poison();
+
+    for button in panel.read().unwrap().buttons.values() {
+        recover_button(button);
+    }
+}
+
+/// Clears poison that a panicking plugin left on anything the event refers to
+fn recover_event(event: &SDCoreEvent) {
+    match event {
+        SDCoreEvent::ButtonAdded { panel, added_button: button, .. } |
+        SDCoreEvent::ButtonDeleted { panel, deleted_button: button, .. } |
+        SDCoreEvent::ButtonAction { panel, pressed_button: button, .. } => {
+            recover_panel(panel);
+            recover_button(button);
+        }
+
+        SDCoreEvent::ButtonUpdated { panel, new_button, old_button, .. } => {
+            recover_panel(panel);
+            recover_button(new_button);
+            recover_button(old_button);
+        }
+
+        SDCoreEvent::ButtonMoved { panel, moved_button, swapped_button, .. } => {
+            recover_panel(panel);
+            recover_button(moved_button);
+            swapped_button.iter().for_each(recover_button);
+        }
+
+        SDCoreEvent::PanelUpdated { panel, old_buttons } => {
+            recover_panel(panel);
+            old_buttons.values().for_each(recover_button);
+        }
+
+        SDCoreEvent::RadioSelected { panel, .. } |
+        SDCoreEvent::PanelPushed { new_panel: panel } |
+        SDCoreEvent::PanelPopped { popped_panel: panel } |
+        SDCoreEvent::StackReset { new_panel: panel } => recover_panel(panel),
+
+        SDCoreEvent::PanelReplaced { old_panel, new_panel } => {
+            old_panel.iter().for_each(recover_panel);
+            recover_panel(new_panel);
+        }
+
+        SDCoreEvent::ButtonDown { .. } | SDCoreEvent::ButtonUp { .. } => {}
+    }
 }
 
 #[allow(dead_code)]
 struct PluginProxy {
     pub wrapper: Container<PluginApi>,
     pub metadata: PluginMetadata,
-    pub plugin: BoxedSDModule
+    pub plugin: BoxedSDModule,
+    pub health: Arc<PluginHealth>,
+    pub socket_manager: Arc<SocketManager>
+}
+
+impl PluginProxy {
+    /// Runs plugin callback, catching panics and measuring its duration. Returns fallback if plugin is disabled or has panicked.
+    /// Locks that plugin poisoned by panicking have to be recovered by the caller
+    fn guard<R>(&self, name: &str, fallback: R, callback: impl FnOnce() -> R) -> R {
+        if self.health.is_disabled() {
+            return fallback;
+        }
+
+        let start = Instant::now();
+        let result = catch_unwind(AssertUnwindSafe(callback));
+        record_plugin_callback(&self.metadata.name, name, start.elapsed());
+
+        match result {
+            Ok(result) => {
+                self.health.succeeded();
+                result
+            }
+
+            Err(payload) => {
+                let message = panic_message(&payload);
+                let disabled = self.health.panicked();
+
+                if disabled {
+                    log::error!("Plugin '{}' has panicked {} times in a row and was disabled: {}", self.metadata.name, PLUGIN_PANIC_LIMIT, message);
+                } else {
+                    log::error!("Plugin '{}' has panicked: {}", self.metadata.name, message);
+                }
+
+                send_event_to_socket(&self.socket_manager, SDGlobalEvent::PluginFailed {
+                    name: self.metadata.name.clone(),
+                    message,
+                    disabled: self.health.is_disabled()
+                });
+
+                fallback
+            }
+        }
+    }
 }
 
 impl SDModule for PluginProxy {
@@ -41,62 +194,76 @@ impl SDModule for PluginProxy {
     }
 
     fn components(&self) -> HashMap<String, ComponentDefinition> {
-        self.plugin.components()
+        self.guard("components", HashMap::new(), || self.plugin.components())
     }
 
     fn add_component(&self, core: CoreHandle, button: &mut Button, name: &str) {
-        self.plugin.add_component(core, button, name)
+        self.guard("add_component", (), || self.plugin.add_component(core, button, name))
     }
 
     fn remove_component(&self, core: CoreHandle, button: &mut Button, name: &str) {
-        self.plugin.remove_component(core, button, name)
+        self.guard("remove_component", (), || self.plugin.remove_component(core, button, name))
     }
 
     fn paste_component(&self, core: CoreHandle, reference_button: &Button, new_button: &mut Button) {
-        self.plugin.paste_component(core, reference_button, new_button)
+        self.guard("paste_component", (), || self.plugin.paste_component(core, reference_button, new_button))
     }
 
     fn component_values(&self, core: CoreHandle, button: &Button, name: &str) -> Vec<UIValue> {
-        self.plugin.component_values(core, button, name)
+        self.guard("component_values", vec![], || self.plugin.component_values(core, button, name))
     }
 
-    fn set_component_value(&self, core: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) {
-        self.plugin.set_component_value(core, button, name, value)
+    fn set_component_value(&self, core: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+        self.guard("set_component_value", Err(vec![]), || self.plugin.set_component_value(core, button, name, value))
     }
 
     fn listening_for(&self) -> Vec<String> {
-        self.plugin.listening_for()
+        self.guard("listening_for", vec![], || self.plugin.listening_for())
     }
 
     fn settings(&self, core: Arc<CoreManager>) -> Vec<UIValue> {
-        self.plugin.settings(core)
+        self.guard("settings", vec![], || self.plugin.settings(core))
     }
 
     fn set_setting(&self, core: Arc<CoreManager>, value: Vec<UIValue>) {
-        self.plugin.set_setting(core, value)
+        self.guard("set_setting", (), || self.plugin.set_setting(core, value))
     }
 
     fn global_event(&self, event: SDGlobalEvent) {
         if check_feature_list_for_feature(&self.metadata.used_features, "global_events") {
-            self.plugin.global_event(event)
+            self.guard("global_event", (), || self.plugin.global_event(event))
         }
     }
 
     fn event(&self, core: CoreHandle, event: SDCoreEvent) {
         if core.check_for_feature("core_events") {
-            self.plugin.event(core, event)
+            let locks = event.clone();
+            self.guard("event", (), || self.plugin.event(core, event));
+            recover_event(&locks);
         }
     }
 
     fn render(&self, core: CoreHandle, button: &UniqueButton, frame: &mut DynamicImage) {
         if core.check_for_feature("rendering") {
-            self.plugin.render(core, button, frame)
+            self.guard("render", (), || self.plugin.render(core, button, frame));
+            recover_button(button);
         }
     }
 
     fn render_hash(&self, core: CoreHandle, button: &UniqueButton, hash: &mut Box<dyn Hasher>) {
         if core.check_for_feature("rendering") {
-            self.plugin.render_hash(core, button, hash)
+            self.guard("render_hash", (), || self.plugin.render_hash(core, button, hash));
+            recover_button(button);
+        }
+    }
+
+    fn refresh_rate(&self, core: CoreHandle, button: &UniqueButton) -> Option<f32> {
+        if core.check_for_feature("rendering") {
+            let rate = self.guard("refresh_rate", None, || self.plugin.refresh_rate(core, button));
+            recover_button(button);
+            rate
+        } else {
+            None
         }
     }
 
@@ -133,20 +300,28 @@ fn warn_about_essential_features(module: &PluginProxy) {
     warn_for_feature(name, &features, "sdmodule_trait");
 }
 
-/// Loads a plugin into module manager
-pub fn load_plugin<T: AsRef<OsStr>>(module_manager: Arc<ModuleManager>, socket_manager: Arc<SocketManager>, render_manager: Arc<RenderingManager>, path: T) -> Result<(), PluginError> {
+/// Loads plugin library and checks if plugin is compatible with the software
+fn open_plugin<T: AsRef<OsStr>>(path: T) -> Result<(PluginMetadata, Container<PluginApi>), PluginError> {
     // Loading file as a library, error if cannot load
-    let wrapper: Container<PluginApi> = unsafe { Container::load(path) }?;
+    let wrapper: Container<PluginApi> = unsafe { Container::load(&path) }?;
 
     // Retrieving metadata and comparing versions
-    let metadata = wrapper.get_metadata();
+    let metadata = catch_unwind(AssertUnwindSafe(|| wrapper.get_metadata()))
+        .map_err(|payload| PluginError::Panicked(path.as_ref().to_string_lossy().to_string(), panic_message(&payload)))?;
     compare_plugin_versions(&metadata.used_features)?;
 
+    Ok((metadata, wrapper))
+}
+
+/// Adds opened plugin into module manager
+fn add_plugin(module_manager: Arc<ModuleManager>, socket_manager: Arc<SocketManager>, render_manager: Arc<RenderingManager>, metadata: PluginMetadata, wrapper: Container<PluginApi>) -> Result<(), PluginError> {
     // Attempting to get module from the plugin
-    let module: BoxedSDModule = unsafe { Box::from_raw(wrapper.get_module()) };
+    let module = catch_unwind(AssertUnwindSafe(|| wrapper.get_module()))
+        .map_err(|payload| PluginError::Panicked(metadata.name.clone(), panic_message(&payload)))?;
+    let module: BoxedSDModule = unsafe { Box::from_raw(module) };
 
     // Wrapping plugin's module into a wrapper that contains loaded library
-    let proxy = PluginProxy { wrapper, metadata, plugin: module };
+    let proxy = PluginProxy { wrapper, metadata, plugin: module, health: Default::default(), socket_manager: socket_manager.clone() };
 
     // Warn plugin if metadata doesn't contain essential plugins
     warn_about_essential_features(&proxy);
@@ -160,8 +335,13 @@ pub fn load_plugin<T: AsRef<OsStr>>(module_manager: Arc<ModuleManager>, socket_m
         }
 
         // Calling register after all checks were done
-        proxy.wrapper.register(socket_manager, render_manager, module_manager.clone());
+        let register = catch_unwind(AssertUnwindSafe(|| proxy.wrapper.register(socket_manager, render_manager, module_manager.clone())));
+
+        if let Err(payload) = register {
+            return Err(PluginError::Panicked(proxy.name(), panic_message(&payload)));
+        }
 
+        module_manager.set_plugin_health(&proxy.name(), proxy.health.clone());
         module_manager.add_module(Arc::new(Box::new(proxy)));
         Ok(())
     } else {
@@ -169,9 +349,22 @@ pub fn load_plugin<T: AsRef<OsStr>>(module_manager: Arc<ModuleManager>, socket_m
     }
 }
 
-/// Loads plugins into module manager from path
+/// Loads a plugin into module manager, dependencies of the plugin have to be loaded already
+pub fn load_plugin<T: AsRef<OsStr>>(module_manager: Arc<ModuleManager>, socket_manager: Arc<SocketManager>, render_manager: Arc<RenderingManager>, path: T) -> Result<(), PluginError> {
+    let (metadata, wrapper) = open_plugin(path)?;
+
+    if let Err(err) = module_manager.check_dependencies(&metadata) {
+        return Err(PluginError::DependencyError(metadata.name, err));
+    }
+
+    add_plugin(module_manager, socket_manager, render_manager, metadata, wrapper)
+}
+
+/// Loads plugins into module manager from path, plugins are loaded after plugins they depend on
 pub fn load_plugins_from_folder<T: AsRef<OsStr>>(module_manager: Arc<ModuleManager>, socket_manager: Arc<SocketManager>, render_manager: Arc<RenderingManager>, path: T) {
     let path = Path::new(&path);
+    let mut opened = vec![];
+
     match fs::read_dir(path) {
         Ok(read_dir) => {
             for item in read_dir {
@@ -179,16 +372,10 @@ pub fn load_plugins_from_folder<T: AsRef<OsStr>>(module_manager: Arc<ModuleManag
                     Ok(entry) => {
                         if entry.path().is_file() {
                             if let Some(file_name) = entry.path().file_name() {
-                                log::info!("Loading plugin {:?}", file_name);
-                                match load_plugin(module_manager.clone(), socket_manager.clone(), render_manager.clone(), entry.path()) {
-                                    Err(err) => match err {
-                                        PluginError::LoadError(err) => log::error!("Failed to load plugin: {}", err),
-                                        PluginError::WrongVersion(plugin, software) => log::error!("Failed to load plugin: Plugin is using unsupported version of '{}', software's using '{}'", plugin, software),
-                                        PluginError::TooNew(version) => log::error!("Failed to load plugin: Software doesn't support '{}', try updating the software", version),
-                                        PluginError::AlreadyExists(name) => log::error!("Failed to load plugin: Module '{}' was already defined", name),
-                                        PluginError::ComponentConflict(name, component_name) => log::error!("Failed to load plugin: Module '{}' is declaring '{}' component, but it was already previously declared by other module", name, component_name),
-                                    },
-                                    _ => {}
+                                log::info!("Opening plugin {:?}", file_name);
+                                match open_plugin(entry.path()) {
+                                    Ok(plugin) => opened.push(plugin),
+                                    Err(err) => report_plugin_error(&module_manager, &file_name.to_string_lossy(), err),
                                 }
                             }
                         }
@@ -205,6 +392,46 @@ pub fn load_plugins_from_folder<T: AsRef<OsStr>>(module_manager: Arc<ModuleManag
             }
         }
     }
+
+    let (order, failed) = module_manager.resolve_load_order(opened);
+
+    for (name, err) in failed {
+        report_plugin_error(&module_manager, &name, PluginError::DependencyError(name.clone(), err));
+    }
+
+    for (metadata, wrapper) in order {
+        log::info!("Loading plugin '{}'", metadata.name);
+
+        let name = metadata.name.clone();
+
+        if let Err(err) = add_plugin(module_manager.clone(), socket_manager.clone(), render_manager.clone(), metadata, wrapper) {
+            report_plugin_error(&module_manager, &name, err);
+        }
+    }
+}
+
+/// Logs reason for plugin not being loaded and remembers it as a plugin failure
+fn report_plugin_error(module_manager: &ModuleManager, name: &str, err: PluginError) {
+    let message = plugin_error_message(err);
+    log::error!("Failed to load plugin: {}", message);
+    module_manager.add_plugin_failure(name, &message);
+}
+
+/// Describes reason for plugin not being loaded
+fn plugin_error_message(err: PluginError) -> String {
+    match err {
+        PluginError::LoadError(err) => err.to_string(),
+        PluginError::WrongVersion(plugin, software) => format!("Plugin is using unsupported version of '{}', software's using '{}'", plugin, software),
+        PluginError::TooNew(version) => format!("Software doesn't support '{}', try updating the software", version),
+        PluginError::AlreadyExists(name) => format!("Module '{}' was already defined", name),
+        PluginError::ComponentConflict(name, component_name) => format!("Module '{}' is declaring '{}' component, but it was already previously declared by other module", name, component_name),
+        PluginError::Panicked(name, message) => format!("Plugin '{}' has panicked while loading: {}", name, message),
+        PluginError::DependencyError(name, err) => match err {
+            DependencyError::Missing(dependency, version) => format!("Plugin '{}' depends on '{} {}', but it isn't available", name, dependency, version),
+            DependencyError::WrongVersion(dependency, version, available) => format!("Plugin '{}' depends on '{} {}', but version {} is available", name, dependency, version, available),
+            DependencyError::Cycle => format!("Plugin '{}' has circular dependencies", name),
+        },
+    }
 }
 
 /// Enum for anything wrong that might happen during plugin loading
@@ -215,6 +442,10 @@ pub enum PluginError {
     TooNew(String),
     AlreadyExists(String),
     ComponentConflict(String, String),
+    /// Dependencies of the plugin aren't satisfied, contains name of the plugin
+    DependencyError(String, DependencyError),
+    /// Plugin has panicked while loading, contains name of the plugin, or its path if metadata couldn't be retrieved, and panic message
+    Panicked(String, String),
 }
 
 impl From<dlopen::Error> for PluginError {
@@ -0,0 +1,210 @@
+//! Radio component that groups buttons so only one of them is active at a time, eg. for scene selectors or tabs
+//!
+//! Pressing a radio button activates it and deactivates buttons of the same group that are on the screen.
+//! Selection is sent to modules and clients as [RadioSelected](crate::modules::events::SDCoreEvent::RadioSelected) event,
+//! and published to message bus as "radio.<group>" topic, so [conditions](crate::modules::conditions) can depend on it
+
+use std::sync::Arc;
+use image::{DynamicImage, Rgba};
+use serde::{Deserialize, Serialize};
+use crate::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
+use crate::core::methods::{CoreHandle, get_current_screen, get_visible_buttons};
+use crate::core::UniqueButton;
+use crate::modules::components::{FieldError, map_ui_values, UIFieldType, UIFieldValue, UIValue};
+use crate::modules::events::{core_event_to_global, SDCoreEvent};
+use crate::modules::messages::ModuleMessage;
+use crate::modules::send_core_event_to_modules;
+use crate::socket::send_event_to_socket;
+use crate::thread::rendering::Color;
+
+/// Width of highlight border around active buttons
+pub const RADIO_HIGHLIGHT_WIDTH: u32 = 6;
+
+/// Component that makes button part of a radio group
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RadioComponent {
+    #[serde(default)]
+    pub group: String,
+    /// Value that gets selected by pressing the button
+    #[serde(default)]
+    pub value: String,
+    #[serde(default)]
+    pub active: bool,
+    /// Color of border drawn around the button while it's active
+    #[serde(default = "default_highlight")]
+    pub highlight: Color,
+}
+
+impl Default for RadioComponent {
+    fn default() -> Self {
+        RadioComponent {
+            group: "".to_string(),
+            value: "".to_string(),
+            active: false,
+            highlight: default_highlight()
+        }
+    }
+}
+
+fn default_highlight() -> Color {
+    (0, 170, 255, 255)
+}
+
+impl Component for RadioComponent {
+    const NAME: &'static str = "radio";
+}
+
+/// Topic of message bus that selections of the group are published to
+pub fn radio_topic(group: &str) -> String {
+    format!("radio.{}", group)
+}
+
+/// Gets highlight color of the button if it's an active radio button
+pub fn radio_highlight(button: &UniqueButton) -> Option<Color> {
+    parse_unique_button_to_component::<RadioComponent>(button).ok()
+        .filter(|x| x.active)
+        .map(|x| x.highlight)
+}
+
+/// Draws highlight border around the image
+pub fn apply_radio_highlight(image: DynamicImage, color: Color) -> DynamicImage {
+    let mut image = image.to_rgba8();
+    let (width, height) = image.dimensions();
+
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        if x < RADIO_HIGHLIGHT_WIDTH || y < RADIO_HIGHLIGHT_WIDTH || x >= width.saturating_sub(RADIO_HIGHLIGHT_WIDTH) || y >= height.saturating_sub(RADIO_HIGHLIGHT_WIDTH) {
+            *pixel = Rgba([color.0, color.1, color.2, color.3]);
+        }
+    }
+
+    DynamicImage::ImageRgba8(image)
+}
+
+/// Activates radio button and deactivates other buttons of its group that are on the screen, returns false if button isn't a radio button
+pub fn select_radio_button(core: &CoreHandle, key: u8, button: &UniqueButton) -> bool {
+    let selected = match parse_unique_button_to_component::<RadioComponent>(button) {
+        Ok(component) => component,
+        Err(_) => return false
+    };
+
+    if let Some(buttons) = get_visible_buttons(core) {
+        for other in buttons.values() {
+            set_radio_active(other, &selected.group, Arc::ptr_eq(other, button));
+        }
+    }
+
+    if let Some(panel) = get_current_screen(core) {
+        let event = SDCoreEvent::RadioSelected {
+            key,
+            panel,
+            group: selected.group.clone(),
+            value: selected.value.clone()
+        };
+
+        send_core_event_to_modules(core, event.clone(), core.module_manager().get_module_list().into_iter());
+        send_event_to_socket(&core.core.socket_manager, core_event_to_global(event, &core.core.serial_number));
+    }
+
+    if let Ok(message) = ModuleMessage::new(&radio_topic(&selected.group), "core", Some(core.core.serial_number()), &selected.value) {
+        core.module_manager().message_bus().publish(message);
+    }
+
+    core.core.mark_for_redraw();
+
+    true
+}
+
+/// Selects value of a group, for modules that learn about selection changing elsewhere. Returns false if no button on the screen has the value
+pub fn select_radio_value(core: &CoreHandle, group: &str, value: &str) -> bool {
+    core.required_feature("core_methods");
+
+    let buttons = match get_visible_buttons(core) {
+        Some(buttons) => buttons,
+        None => return false
+    };
+
+    let found = buttons.into_iter()
+        .find(|(_, button)| parse_unique_button_to_component::<RadioComponent>(button)
+            .map_or(false, |x| x.group == group && x.value == value));
+
+    if let Some((key, button)) = found {
+        select_radio_button(core, key, &button)
+    } else {
+        false
+    }
+}
+
+fn set_radio_active(button: &UniqueButton, group: &str, active: bool) {
+    let mut handle = button.write().unwrap();
+
+    if let Ok(mut component) = parse_button_to_component::<RadioComponent>(&handle) {
+        if component.group == group && component.active != active {
+            component.active = active;
+            handle.insert_component(component).ok();
+        }
+    }
+}
+
+/// Gets UI values of radio component
+pub fn get_radio_component_values(button: &Button) -> Vec<UIValue> {
+    if let Ok(component) = parse_button_to_component::<RadioComponent>(button) {
+        vec![
+            UIValue {
+                name: "group".to_string(),
+                display_name: "Group".to_string(),
+                description: "Buttons with same group name are deactivated when this button is pressed".to_string(),
+                ty: UIFieldType::InputFieldString,
+                value: UIFieldValue::InputFieldString(component.group)
+            },
+            UIValue {
+                name: "value".to_string(),
+                display_name: "Value".to_string(),
+                description: "Value that gets selected by pressing the button".to_string(),
+                ty: UIFieldType::InputFieldString,
+                value: UIFieldValue::InputFieldString(component.value)
+            },
+            UIValue {
+                name: "highlight".to_string(),
+                display_name: "Highlight Color".to_string(),
+                description: "Color of border around the button while it's active".to_string(),
+                ty: UIFieldType::Color,
+                value: component.highlight.into()
+            }
+        ]
+    } else {
+        vec![]
+    }
+}
+
+/// Sets UI values of radio component
+pub fn set_radio_component_values(button: &mut Button, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+    if let Ok(mut component) = parse_button_to_component::<RadioComponent>(button) {
+        let change_map = map_ui_values(value);
+
+        if let Some(value) = change_map.get("group") {
+            if let Ok(group) = value.value.try_into_string() {
+                if group.trim().is_empty() {
+                    return Err(vec![FieldError::new("group", "Group name can't be empty")]);
+                }
+
+                component.group = group.trim().to_string();
+            }
+        }
+
+        if let Some(value) = change_map.get("value") {
+            if let Ok(str) = value.value.try_into_string() {
+                component.value = str;
+            }
+        }
+
+        if let Some(value) = change_map.get("highlight") {
+            if let Ok(color) = value.value.try_into_color() {
+                component.highlight = color;
+            }
+        }
+
+        button.insert_component(component).ok();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+//! Background tasks that modules can schedule for periodic or delayed work, like updating clocks or polling services
+//!
+//! Tasks are executed on a shared pool of worker threads. Tasks of a module are cancelled when module is removed from module manager,
+//! tasks that were scheduled with a core are cancelled once the core closes
+
+use std::collections::HashSet;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread::spawn;
+use std::time::{Duration, Instant};
+use crate::core::methods::CoreHandle;
+use crate::core::SDCore;
+
+/// Amount of worker threads that execute tasks
+pub const SCHEDULER_WORKERS: usize = 4;
+
+/// Identifier of scheduled task
+pub type TaskId = u64;
+
+/// Function that task runs
+type TaskFunction = Box<dyn FnMut(CoreHandle) + Send>;
+
+/// Task that waits for its time to run
+struct ScheduledTask {
+    id: TaskId,
+    core: CoreHandle,
+    next_run: Instant,
+    interval: Option<Duration>,
+    function: TaskFunction,
+}
+
+#[derive(Default)]
+struct SchedulerState {
+    /// Tasks that wait to be run
+    tasks: Vec<ScheduledTask>,
+    /// Tasks that are being run by workers right now, with name of the module and address of the core they belong to
+    running: Vec<(TaskId, String, usize)>,
+    /// Running tasks that were cancelled, they won't be scheduled again after they finish
+    cancelled: HashSet<TaskId>,
+    workers_started: bool,
+}
+
+/// Shared scheduler of background tasks, kept in [ModuleManager](crate::modules::ModuleManager)
+#[derive(Default)]
+pub struct TaskScheduler {
+    state: Arc<(Mutex<SchedulerState>, Condvar)>,
+    counter: AtomicU64,
+}
+
+impl TaskScheduler {
+    /// Schedules function to run after delay, and then every interval if interval is specified
+    pub fn schedule(&self, core: &CoreHandle, delay: Duration, interval: Option<Duration>, function: TaskFunction) -> TaskId {
+        let id = self.counter.fetch_add(1, Ordering::SeqCst);
+
+        let (state, condvar) = self.state.as_ref();
+        let mut handle = state.lock().unwrap();
+
+        if !handle.workers_started {
+            handle.workers_started = true;
+
+            for _ in 0..SCHEDULER_WORKERS {
+                let state = self.state.clone();
+                spawn(move || worker(state));
+            }
+        }
+
+        handle.tasks.push(ScheduledTask {
+            id,
+            core: core.clone(),
+            next_run: Instant::now() + delay,
+            interval,
+            function
+        });
+
+        condvar.notify_all();
+
+        id
+    }
+
+    /// Cancels task, returns false if task doesn't exist anymore
+    pub fn cancel(&self, id: TaskId) -> bool {
+        self.cancel_where(|task_id, _, _| task_id == id)
+    }
+
+    /// Cancels all tasks of a module
+    pub fn cancel_module(&self, module_name: &str) {
+        self.cancel_where(|_, module, _| module == module_name);
+    }
+
+    /// Cancels all tasks that were scheduled with the core
+    pub fn cancel_core(&self, core: &SDCore) {
+        self.cancel_where(|_, _, task_core| task_core == core as *const SDCore as usize);
+    }
+
+    /// Amount of tasks that are waiting or running
+    pub fn task_count(&self) -> usize {
+        let handle = self.state.0.lock().unwrap();
+        (handle.tasks.len() + handle.running.len()).saturating_sub(handle.cancelled.len())
+    }
+
+    fn cancel_where(&self, predicate: impl Fn(TaskId, &str, usize) -> bool) -> bool {
+        let (state, condvar) = self.state.as_ref();
+        let mut handle = state.lock().unwrap();
+
+        let count = handle.tasks.len();
+        handle.tasks.retain(|task| !predicate(task.id, &task.core.module_name, core_address(&task.core)));
+        let mut found = count != handle.tasks.len();
+
+        let running: Vec<TaskId> = handle.running.iter()
+            .filter(|(id, module, core)| predicate(*id, module, *core))
+            .map(|(id, ..)| *id)
+            .collect();
+
+        for id in running {
+            found |= handle.cancelled.insert(id);
+        }
+
+        condvar.notify_all();
+
+        found
+    }
+}
+
+/// Address of the core, used to find tasks of the core
+fn core_address(core: &CoreHandle) -> usize {
+    Arc::as_ptr(&core.core) as usize
+}
+
+/// Loop of worker thread, takes tasks that are due and runs them
+fn worker(state: Arc<(Mutex<SchedulerState>, Condvar)>) {
+    let (state, condvar) = state.as_ref();
+    let mut handle = state.lock().unwrap();
+
+    loop {
+        let next = handle.tasks.iter().enumerate().min_by_key(|(_, task)| task.next_run).map(|(index, task)| (index, task.next_run));
+
+        let index = match next {
+            None => {
+                handle = condvar.wait(handle).unwrap();
+                continue;
+            }
+
+            Some((index, next_run)) => {
+                let now = Instant::now();
+
+                if next_run > now {
+                    handle = condvar.wait_timeout(handle, next_run - now).unwrap().0;
+                    continue;
+                }
+
+                index
+            }
+        };
+
+        let mut task = handle.tasks.swap_remove(index);
+
+        // Tasks of closed cores are dropped
+        if task.core.core.is_closed() {
+            continue;
+        }
+
+        handle.running.push((task.id, task.core.module_name.clone(), core_address(&task.core)));
+        drop(handle);
+
+        let started = Instant::now();
+        let core = task.core.clone();
+        let function = &mut task.function;
+
+        if catch_unwind(AssertUnwindSafe(|| function(core))).is_err() {
+            log::error!("Task of module '{}' has panicked", task.core.module_name);
+        }
+
+        handle = state.lock().unwrap();
+        handle.running.retain(|(id, ..)| *id != task.id);
+
+        let cancelled = handle.cancelled.remove(&task.id);
+
+        if let (Some(interval), false) = (task.interval, cancelled) {
+            // Tasks that took longer than their interval run again right away instead of catching up
+            task.next_run = (started + interval).max(Instant::now());
+            handle.tasks.push(task);
+            condvar.notify_all();
+        }
+    }
+}
+
+/// Runs task once after delay, returns ID that can be used to cancel the task
+pub fn schedule_task(core: &CoreHandle, delay: Duration, task: impl FnOnce(CoreHandle) + Send + 'static) -> TaskId {
+    core.required_feature("scheduler");
+
+    let mut task = Some(task);
+    core.core.module_manager.scheduler().schedule(core, delay, None, Box::new(move |core| {
+        if let Some(task) = task.take() {
+            task(core)
+        }
+    }))
+}
+
+/// Runs task every interval, first run happens after one interval. Returns ID that can be used to cancel the task
+pub fn schedule_recurring_task(core: &CoreHandle, interval: Duration, task: impl FnMut(CoreHandle) + Send + 'static) -> TaskId {
+    core.required_feature("scheduler");
+    core.core.module_manager.scheduler().schedule(core, interval, Some(interval), Box::new(task))
+}
+
+/// Cancels task that was scheduled before, returns false if task has already finished or was cancelled
+pub fn cancel_task(core: &CoreHandle, id: TaskId) -> bool {
+    core.required_feature("scheduler");
+    core.core.module_manager.scheduler().cancel(id)
+}
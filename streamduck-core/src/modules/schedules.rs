@@ -0,0 +1,207 @@
+//! Actions that run on a cron-like schedule, kept in device config, eg. opening "meeting" folder every weekday at 9:00
+//!
+//! Schedules are stored under "schedules" key of device's plugin data. Expressions have 5 fields: minute, hour, day of month, month and day of week,
+//! every field accepts `*`, numbers, ranges like `1-5`, lists like `1,15` and steps like `*/10`. Sunday is either 0 or 7 in day of week field
+
+use std::time::Duration;
+use chrono::{Datelike, Local, Timelike};
+use serde::{Deserialize, Serialize};
+use crate::config::DeviceConfig;
+use crate::core::methods::{button_action, CoreHandle, push_screen};
+use crate::modules::folders::folder_panel;
+use crate::modules::scheduler::schedule_task;
+
+/// Key of device config's plugin data that schedules are stored under
+pub const SCHEDULES_KEY: &'static str = "schedules";
+
+/// Action that runs on schedule
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Schedule {
+    /// Cron expression, eg. "0 9 * * 1-5"
+    pub cron: String,
+    #[serde(flatten)]
+    pub action: ScheduledAction,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// What happens when schedule is due
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ScheduledAction {
+    /// Opens folder on top of current screen
+    PushFolder {
+        folder_id: String
+    },
+
+    /// Triggers button of current screen as if it was pressed
+    PressButton {
+        key: u8
+    },
+}
+
+/// Parsed cron expression
+#[derive(Clone, Debug, PartialEq)]
+pub struct CronExpression {
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    days: Vec<u32>,
+    months: Vec<u32>,
+    weekdays: Vec<u32>,
+    /// Day fields that weren't "*", cron runs if either of them matches when both are restricted
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl CronExpression {
+    /// Parses expression of 5 fields
+    pub fn parse(expression: &str) -> Result<CronExpression, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+
+        if fields.len() != 5 {
+            return Err(format!("Expected 5 fields, found {}", fields.len()));
+        }
+
+        let mut weekdays = parse_field(fields[4], 0, 7)?;
+        for day in weekdays.iter_mut() {
+            if *day == 7 {
+                *day = 0;
+            }
+        }
+
+        Ok(CronExpression {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            weekdays,
+            days_restricted: fields[2] != "*",
+            weekdays_restricted: fields[4] != "*"
+        })
+    }
+
+    /// Checks if expression matches the time, weekday starts with Sunday as 0
+    pub fn matches(&self, minute: u32, hour: u32, day: u32, month: u32, weekday: u32) -> bool {
+        let day_matches = match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => self.days.contains(&day) || self.weekdays.contains(&weekday),
+            _ => self.days.contains(&day) && self.weekdays.contains(&weekday)
+        };
+
+        self.minutes.contains(&minute)
+            && self.hours.contains(&hour)
+            && self.months.contains(&month)
+            && day_matches
+    }
+}
+
+fn parse_number(value: &str, min: u32, max: u32) -> Result<u32, String> {
+    let number = value.parse::<u32>().map_err(|_| format!("'{}' is not a number", value))?;
+
+    if number < min || number > max {
+        return Err(format!("{} is out of range {}-{}", number, min, max));
+    }
+
+    Ok(number)
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<Vec<u32>, String> {
+    let mut values = vec![];
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, parse_number(step, 1, max.max(1))?),
+            None => (part, 1)
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (parse_number(start, min, max)?, parse_number(end, min, max)?)
+        } else {
+            let value = parse_number(range, min, max)?;
+
+            // "5/15" means starting at 5 with steps of 15
+            if part.contains('/') { (value, max) } else { (value, value) }
+        };
+
+        if start > end {
+            return Err(format!("Range {}-{} is reversed", start, end));
+        }
+
+        values.extend((start..=end).step_by(step as usize));
+    }
+
+    values.sort();
+    values.dedup();
+
+    Ok(values)
+}
+
+/// Reads schedules from device config
+pub fn get_schedules(config: &DeviceConfig) -> Vec<Schedule> {
+    config.plugin_data.get(SCHEDULES_KEY)
+        .and_then(|x| serde_json::from_value(x.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Writes schedules into device config
+pub fn set_schedules(config: &mut DeviceConfig, schedules: Vec<Schedule>) {
+    if schedules.is_empty() {
+        config.plugin_data.remove(SCHEDULES_KEY);
+    } else {
+        config.plugin_data.insert(SCHEDULES_KEY.to_string(), serde_json::to_value(schedules).unwrap());
+    }
+}
+
+/// Starts checking schedules of the device every minute, stops once core closes
+pub fn start_schedules(core: &CoreHandle) {
+    core.required_feature("scheduler");
+
+    // Waking up a bit after minute starts, so clock drift doesn't land the check at the end of previous minute
+    let now = Local::now();
+    let until_next_minute = Duration::from_secs(60 - now.second() as u64) - Duration::from_nanos(now.nanosecond().min(999_999_999) as u64)
+        + Duration::from_millis(500);
+
+    schedule_task(core, until_next_minute, |core| {
+        run_due_schedules(&core);
+        start_schedules(&core);
+    });
+}
+
+/// Runs schedules of the device that are due this minute
+fn run_due_schedules(core: &CoreHandle) {
+    let now = Local::now();
+    let schedules = get_schedules(&core.core.device_config.read().unwrap());
+
+    for schedule in schedules.into_iter().filter(|x| x.enabled) {
+        let expression = match CronExpression::parse(&schedule.cron) {
+            Ok(expression) => expression,
+            Err(err) => {
+                log::debug!("Invalid schedule '{}' on {}: {}", schedule.cron, core.core.serial_number, err);
+                continue;
+            }
+        };
+
+        if !expression.matches(now.minute(), now.hour(), now.day(), now.month(), now.weekday().num_days_from_sunday()) {
+            continue;
+        }
+
+        log::info!("Running schedule '{}' on {}", schedule.cron, core.core.serial_number);
+
+        match schedule.action {
+            ScheduledAction::PushFolder { folder_id } => {
+                if let Some(panel) = folder_panel(core, &folder_id) {
+                    push_screen(core, panel);
+                } else {
+                    log::warn!("Schedule '{}' refers to folder {} that doesn't exist", schedule.cron, folder_id);
+                }
+            }
+
+            ScheduledAction::PressButton { key } => button_action(core, key),
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! State component that turns button into a toggle or a cycle of several states
+//!
+//! Every state keeps its own copy of components the button tracks, like renderer for looks and action components for what gets done.
+//! Switching state stores current values of tracked components into the state that is left, and applies values of the state that is entered.
+//! States that don't have values for a component yet take whatever the button had at the moment of switching.
+//!
+//! Pressing the button triggers actions of current state, and then moves the button to next state
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::core::button::{Button, Component, parse_button_to_component};
+use crate::core::methods::CoreHandle;
+use crate::core::UniqueButton;
+use crate::modules::components::{FieldError, map_ui_values, map_ui_values_ref, UIField, UIFieldType, UIFieldValue, UIValue};
+use crate::thread::rendering::RendererComponent;
+
+/// Component that holds states of the button
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StateComponent {
+    #[serde(default)]
+    pub states: Vec<ButtonState>,
+    /// Index of current state
+    #[serde(default)]
+    pub current: usize,
+    /// Names of components that every state has its own values of
+    #[serde(default = "default_tracked")]
+    pub tracked: Vec<String>,
+}
+
+impl Default for StateComponent {
+    fn default() -> Self {
+        StateComponent {
+            states: vec![
+                ButtonState::new("Off"),
+                ButtonState::new("On"),
+            ],
+            current: 0,
+            tracked: default_tracked()
+        }
+    }
+}
+
+fn default_tracked() -> Vec<String> {
+    vec![RendererComponent::NAME.to_string()]
+}
+
+impl Component for StateComponent {
+    const NAME: &'static str = "state";
+}
+
+/// Single state of the button
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ButtonState {
+    pub name: String,
+    /// Values of tracked components while button is in this state
+    #[serde(default)]
+    pub components: HashMap<String, Value>,
+}
+
+impl ButtonState {
+    /// Creates state that will take values of the button when it's entered for the first time
+    pub fn new(name: &str) -> ButtonState {
+        ButtonState {
+            name: name.to_string(),
+            components: Default::default()
+        }
+    }
+}
+
+impl StateComponent {
+    /// Name of current state, if there are any states
+    pub fn current_name(&self) -> Option<&str> {
+        self.states.get(self.current).map(|x| x.name.as_str())
+    }
+
+    /// Moves button into a state, returns false if there's no state with the index
+    pub fn switch(&mut self, button: &mut Button, index: usize) -> bool {
+        if index >= self.states.len() {
+            return false;
+        }
+
+        if let Some(state) = self.states.get_mut(self.current) {
+            for name in &self.tracked {
+                if let Some(value) = button.0.get(name) {
+                    state.components.insert(name.clone(), value.clone());
+                } else {
+                    state.components.remove(name);
+                }
+            }
+        }
+
+        let state = &mut self.states[index];
+        for name in &self.tracked {
+            if let Some(value) = state.components.get(name) {
+                button.0.insert(name.clone(), value.clone());
+            } else if let Some(value) = button.0.get(name) {
+                state.components.insert(name.clone(), value.clone());
+            }
+        }
+
+        self.current = index;
+
+        true
+    }
+}
+
+/// Gets index and name of current state of the button
+pub fn get_button_state(button: &UniqueButton) -> Option<(usize, String)> {
+    let component = parse_button_to_component::<StateComponent>(&button.read().unwrap()).ok()?;
+    let name = component.current_name()?.to_string();
+    Some((component.current, name))
+}
+
+/// Moves button into state with the index, returns false if button doesn't have states or the state doesn't exist
+pub fn set_button_state(core: &CoreHandle, button: &UniqueButton, index: usize) -> bool {
+    core.required_feature("core_methods");
+
+    let changed = update_state(button, |component| Some(index).filter(|x| *x < component.states.len()));
+
+    if changed {
+        core.core.mark_for_redraw();
+    }
+
+    changed
+}
+
+/// Moves button into state with the name, returns false if button doesn't have states or the state doesn't exist
+pub fn set_button_state_by_name(core: &CoreHandle, button: &UniqueButton, name: &str) -> bool {
+    core.required_feature("core_methods");
+
+    let changed = update_state(button, |component| component.states.iter().position(|x| x.name == name));
+
+    if changed {
+        core.core.mark_for_redraw();
+    }
+
+    changed
+}
+
+/// Moves button into next state, wrapping around after the last one. Used when button is pressed
+pub fn advance_button_state(button: &UniqueButton) -> bool {
+    update_state(button, |component| {
+        if component.states.is_empty() {
+            None
+        } else {
+            Some((component.current + 1) % component.states.len())
+        }
+    })
+}
+
+fn update_state(button: &UniqueButton, target: impl FnOnce(&StateComponent) -> Option<usize>) -> bool {
+    let mut button = button.write().unwrap();
+
+    if let Ok(mut component) = parse_button_to_component::<StateComponent>(&button) {
+        if let Some(index) = target(&component) {
+            if component.switch(&mut button, index) {
+                button.insert_component(component).ok();
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// Gets UI values of state component
+pub fn get_state_component_values(button: &Button) -> Vec<UIValue> {
+    if let Ok(component) = parse_button_to_component::<StateComponent>(button) {
+        let name_field = UIField {
+            name: "name".to_string(),
+            display_name: "Name".to_string(),
+            description: "Name of the state".to_string(),
+            ty: UIFieldType::InputFieldString,
+            default_value: UIFieldValue::InputFieldString("".to_string())
+        };
+
+        vec![
+            UIValue {
+                name: "current".to_string(),
+                display_name: "Current State".to_string(),
+                description: "State the button is in, switch to a state to edit its looks and actions".to_string(),
+                ty: UIFieldType::Choice(component.states.iter().map(|x| x.name.clone()).collect()),
+                value: UIFieldValue::Choice(component.current_name().unwrap_or_default().to_string())
+            },
+            UIValue {
+                name: "tracked".to_string(),
+                display_name: "State Components".to_string(),
+                description: "Comma separated names of components that are different for every state".to_string(),
+                ty: UIFieldType::InputFieldString,
+                value: UIFieldValue::InputFieldString(component.tracked.join(", "))
+            },
+            UIValue {
+                name: "states".to_string(),
+                display_name: "States".to_string(),
+                description: "States that button cycles through when pressed".to_string(),
+                ty: UIFieldType::Array(vec![name_field.clone()]),
+                value: UIFieldValue::Array(
+                    component.states.iter()
+                        .map(|x| vec![UIValue {
+                            name: name_field.name.clone(),
+                            display_name: name_field.display_name.clone(),
+                            description: name_field.description.clone(),
+                            ty: name_field.ty.clone(),
+                            value: UIFieldValue::InputFieldString(x.name.clone())
+                        }])
+                        .collect()
+                )
+            }
+        ]
+    } else {
+        vec![]
+    }
+}
+
+/// Sets UI values of state component
+pub fn set_state_component_values(button: &mut Button, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+    if let Ok(mut component) = parse_button_to_component::<StateComponent>(button) {
+        let change_map = map_ui_values(value);
+
+        if let Some(value) = change_map.get("tracked") {
+            if let Ok(tracked) = value.value.try_into_string() {
+                let tracked: Vec<String> = tracked.split(',')
+                    .map(|x| x.trim().to_string())
+                    .filter(|x| !x.is_empty())
+                    .collect();
+
+                if tracked.iter().any(|x| x == StateComponent::NAME) {
+                    return Err(vec![FieldError::new("tracked", "State component can't be different for every state")]);
+                }
+
+                component.tracked = tracked;
+            }
+        }
+
+        if let Some(value) = change_map.get("states") {
+            if let UIFieldValue::Array(items) = &value.value {
+                let names: Vec<String> = items.iter()
+                    .map(|item| map_ui_values_ref(item).get("name")
+                        .and_then(|x| x.value.try_into_string().ok())
+                        .unwrap_or_default())
+                    .collect();
+
+                component.states.truncate(names.len());
+
+                for (index, name) in names.into_iter().enumerate() {
+                    if let Some(state) = component.states.get_mut(index) {
+                        state.name = name;
+                    } else {
+                        component.states.push(ButtonState::new(&name));
+                    }
+                }
+
+                if component.current >= component.states.len() {
+                    component.current = 0;
+
+                    if let Some(state) = component.states.first() {
+                        for (name, value) in &state.components {
+                            button.0.insert(name.clone(), value.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(value) = change_map.get("current") {
+            if let Ok(name) = value.value.try_into_string() {
+                if let Some(index) = component.states.iter().position(|x| x.name == name) {
+                    if index != component.current {
+                        component.switch(button, index);
+                    }
+                }
+            }
+        }
+
+        button.insert_component(component).ok();
+    }
+
+    Ok(())
+}
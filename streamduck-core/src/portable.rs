@@ -0,0 +1,133 @@
+//! Portable buttons, that carry folder contents and images they use along with them
+//!
+//! Portable button can be pasted onto any device, folders get new IDs on paste so pasting same
+//! button multiple times doesn't make folders shared between buttons
+
+use std::collections::{HashMap, HashSet};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::config::DeviceConfig;
+use crate::core::button::{Button, parse_button_to_component};
+use crate::core::RawButtonPanel;
+use crate::images::SDSerializedImage;
+use crate::layout::{FOLDERS_KEY, FolderMap, folders_of};
+use crate::modules::folders::{FolderComponent, FolderLinkComponent};
+use crate::thread::rendering::RendererComponent;
+
+/// Button with everything it depends on
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PortableButton {
+    pub button: Button,
+    /// Contents of folders contained in the button, by folder ID
+    #[serde(default)]
+    pub folders: HashMap<String, RawButtonPanel>,
+    /// Images used by the button or buttons in its folders, by identifier
+    #[serde(default)]
+    pub images: HashMap<String, SDSerializedImage>,
+}
+
+impl PortableButton {
+    /// Captures button together with its folders and images from device config
+    pub fn capture(config: &DeviceConfig, button: &Button) -> PortableButton {
+        let all_folders = folders_of(config);
+
+        let mut folders = FolderMap::new();
+        collect_folders(button, &all_folders, &mut folders);
+
+        let mut identifiers = HashSet::new();
+        collect_image(button, &mut identifiers);
+        for folder in folders.values() {
+            for button in folder.buttons.values() {
+                collect_image(button, &mut identifiers);
+            }
+        }
+
+        let images = identifiers.into_iter()
+            .filter_map(|x| config.images.get(&x).map(|image| (x, image.clone())))
+            .collect();
+
+        PortableButton {
+            button: button.clone(),
+            folders,
+            images
+        }
+    }
+
+    /// Adds folders of the button to device config under new IDs and returns button that refers to them,
+    /// images are returned separately since they need to be added to image collection of the device
+    pub fn unpack(self, config: &mut DeviceConfig) -> (Button, HashMap<String, SDSerializedImage>) {
+        let mut existing = folders_of(config);
+
+        let mut ids = HashMap::new();
+        for id in self.folders.keys() {
+            let new_id = loop {
+                let id: String = rand::thread_rng().sample_iter(&Alphanumeric).take(16).map(char::from).collect();
+
+                if !existing.contains_key(&id) && !ids.values().any(|x| *x == id) {
+                    break id;
+                }
+            };
+
+            ids.insert(id.clone(), new_id);
+        }
+
+        for (id, mut folder) in self.folders {
+            for button in folder.buttons.values_mut() {
+                remap_folders(button, &ids);
+            }
+
+            existing.insert(ids[&id].clone(), folder);
+        }
+
+        config.plugin_data.insert(FOLDERS_KEY.to_string(), serde_json::to_value(existing).unwrap());
+
+        let mut button = self.button;
+        remap_folders(&mut button, &ids);
+
+        (button, self.images)
+    }
+}
+
+/// Collects folders contained in the button recursively
+fn collect_folders(button: &Button, all_folders: &FolderMap, folders: &mut FolderMap) {
+    if let Ok(folder) = parse_button_to_component::<FolderComponent>(button) {
+        if folders.contains_key(&folder.id) {
+            return;
+        }
+
+        if let Some(contents) = all_folders.get(&folder.id) {
+            folders.insert(folder.id, contents.clone());
+
+            for button in contents.buttons.values() {
+                collect_folders(button, all_folders, folders);
+            }
+        }
+    }
+}
+
+/// Collects identifier of existing image the button uses as background
+fn collect_image(button: &Button, identifiers: &mut HashSet<String>) {
+    if let Ok(renderer) = parse_button_to_component::<RendererComponent>(button) {
+        if let Some(identifier) = renderer.background.image_identifier() {
+            identifiers.insert(identifier.clone());
+        }
+    }
+}
+
+/// Changes folder and folder link IDs of the button according to provided map
+fn remap_folders(button: &mut Button, ids: &HashMap<String, String>) {
+    if let Ok(mut folder) = parse_button_to_component::<FolderComponent>(button) {
+        if let Some(id) = ids.get(&folder.id) {
+            folder.id = id.clone();
+            button.insert_component(folder).ok();
+        }
+    }
+
+    if let Ok(mut link) = parse_button_to_component::<FolderLinkComponent>(button) {
+        if let Some(id) = ids.get(&link.id) {
+            link.id = id.clone();
+            button.insert_component(link).ok();
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Named presets of component values, that can be applied onto any button with the same component
+//!
+//! Preset is a set of top level fields of component's value, applying it replaces only those fields.
+//! When preset is updated with propagation, buttons that still match old preset receive new values
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::config::{DeviceConfig, PluginConfig};
+use crate::core::button::Button;
+use crate::core::ButtonMap;
+use crate::layout::{FOLDERS_KEY, folders_of};
+
+/// Preset of component value fields
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ComponentPreset {
+    /// Name of component the preset is for
+    pub component: String,
+    /// Fields of component value that preset sets
+    pub fields: Map<String, Value>,
+}
+
+/// All presets by their names, kept in global config
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ComponentPresets(pub HashMap<String, ComponentPreset>);
+
+impl PluginConfig for ComponentPresets {
+    const NAME: &'static str = "component_presets";
+}
+
+impl ComponentPreset {
+    /// Captures preset from component on a button, takes all fields of component if field list is empty
+    pub fn from_button(button: &Button, component: &str, fields: &[String]) -> Option<ComponentPreset> {
+        let value = button.0.get(component)?.as_object()?;
+
+        let fields = value.iter()
+            .filter(|(name, _)| fields.is_empty() || fields.contains(name))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+
+        Some(ComponentPreset {
+            component: component.to_string(),
+            fields
+        })
+    }
+
+    /// Checks if button has component that preset can be applied to
+    pub fn is_compatible(&self, button: &Button) -> bool {
+        button.0.get(&self.component).map_or(false, |x| x.is_object())
+    }
+
+    /// Checks if all fields of the preset have same values on the button
+    pub fn matches(&self, button: &Button) -> bool {
+        if let Some(value) = button.0.get(&self.component).and_then(|x| x.as_object()) {
+            self.fields.iter().all(|(name, field)| value.get(name) == Some(field))
+        } else {
+            false
+        }
+    }
+
+    /// Sets preset's fields on the button, returns false if button doesn't have the component
+    pub fn apply(&self, button: &mut Button) -> bool {
+        if let Some(value) = button.0.get_mut(&self.component).and_then(|x| x.as_object_mut()) {
+            for (name, field) in &self.fields {
+                value.insert(name.clone(), field.clone());
+            }
+
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Applies new preset to buttons that matched old preset, returns amount of updated buttons
+pub fn propagate_preset(buttons: &mut ButtonMap, old: &ComponentPreset, new: &ComponentPreset) -> usize {
+    let mut count = 0;
+
+    for button in buttons.values_mut() {
+        if old.matches(button) && !new.matches(button) {
+            // Fields that were removed from the preset are left as they were
+            new.apply(button);
+            count += 1;
+        }
+    }
+
+    count
+}
+
+/// Propagates preset change through root layout and folders of the device config, returns amount of updated buttons
+pub fn propagate_preset_in_config(config: &mut DeviceConfig, old: &ComponentPreset, new: &ComponentPreset) -> usize {
+    let mut count = propagate_preset(&mut config.layout.buttons, old, new);
+
+    let mut folders = folders_of(config);
+    let mut folder_count = 0;
+
+    for folder in folders.values_mut() {
+        folder_count += propagate_preset(&mut folder.buttons, old, new);
+    }
+
+    if folder_count > 0 {
+        config.plugin_data.insert(FOLDERS_KEY.to_string(), serde_json::to_value(folders).unwrap());
+        count += folder_count;
+    }
+
+    count
+}
@@ -0,0 +1,212 @@
+//! Review of device configs before they're applied
+//!
+//! Imported configs can come from other machines, so they might use components of modules that aren't loaded here,
+//! or images that were left out of the export. Review lists such problems along with changes to current config.
+//! [ConfigDiff] describes changes down to component values, for GUIs to show before config is applied
+
+use std::collections::{BTreeSet, HashMap};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::config::DeviceConfig;
+use crate::core::ButtonMap;
+use crate::core::button::{Button, parse_button_to_component};
+use crate::layout::{CompiledLayout, folders_of, LayoutChange};
+use crate::modules::ModuleManager;
+use crate::thread::rendering::RendererComponent;
+
+/// Problems found in device config and changes it would make to current config
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ConfigReview {
+    /// Components of buttons that aren't provided by any loaded module
+    pub unknown_components: Vec<String>,
+    /// Modules that config refers to by name, but aren't loaded
+    pub unknown_modules: Vec<String>,
+    /// Images that buttons use, but aren't in image collection of the config
+    pub missing_images: Vec<String>,
+    /// Changes to current config, empty if device doesn't have a config yet
+    pub changes: Vec<LayoutChange>,
+}
+
+impl ConfigReview {
+    /// Checks if config can be applied without losing anything
+    pub fn is_clean(&self) -> bool {
+        self.unknown_components.is_empty() && self.unknown_modules.is_empty() && self.missing_images.is_empty()
+    }
+}
+
+/// Reviews new config against loaded modules and current config of the device
+pub fn review_config(new: &DeviceConfig, current: Option<&DeviceConfig>, module_manager: &ModuleManager) -> ConfigReview {
+    let mut unknown_components = BTreeSet::new();
+    let mut unknown_modules = BTreeSet::new();
+    let mut missing_images = BTreeSet::new();
+
+    let components = module_manager.read_component_map();
+    let modules = module_manager.get_modules();
+
+    let folders = folders_of(new);
+    let buttons = new.layout.buttons.values()
+        .chain(folders.values().flat_map(|x| x.buttons.values()));
+
+    for button in buttons {
+        for name in button.0.keys() {
+            if !components.contains_key(name) {
+                unknown_components.insert(name.clone());
+            }
+        }
+
+        check_renderer(button, new, &modules, &mut unknown_modules, &mut missing_images);
+    }
+
+    ConfigReview {
+        unknown_components: unknown_components.into_iter().collect(),
+        unknown_modules: unknown_modules.into_iter().collect(),
+        missing_images: missing_images.into_iter().collect(),
+        changes: current.map(|current| diff_configs(current, new)).unwrap_or_default()
+    }
+}
+
+/// Lists changes that replacing current config with new config would make
+pub fn diff_configs(current: &DeviceConfig, new: &DeviceConfig) -> Vec<LayoutChange> {
+    CompiledLayout {
+        brightness: Some(new.brightness),
+        layout: new.layout.clone(),
+        folders: folders_of(new)
+    }.diff(current)
+}
+
+fn check_renderer<M>(button: &Button, config: &DeviceConfig, modules: &HashMap<String, M>, unknown_modules: &mut BTreeSet<String>, missing_images: &mut BTreeSet<String>) {
+    if let Ok(renderer) = parse_button_to_component::<RendererComponent>(button) {
+        for module in renderer.plugin_blacklist {
+            if !modules.contains_key(&module) {
+                unknown_modules.insert(module);
+            }
+        }
+
+        if let Some(identifier) = renderer.background.image_identifier() {
+            if !identifier.is_empty() && !config.images.contains_key(identifier) {
+                missing_images.insert(identifier.clone());
+            }
+        }
+    }
+}
+
+/// Changes that replacing current config with new config would make
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ConfigDiff {
+    /// Current and new brightness, if brightness changes
+    pub brightness: Option<(u8, u8)>,
+    /// New display name of root panel, if it changes
+    pub root_name: Option<String>,
+    pub folders_added: Vec<FolderRef>,
+    pub folders_removed: Vec<FolderRef>,
+    pub buttons: Vec<ButtonDiff>,
+}
+
+/// Folder that diff refers to
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FolderRef {
+    pub id: String,
+    pub name: String,
+}
+
+/// Change of a single button
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ButtonDiff {
+    /// Folder ID that button is in, [None] for root panel
+    pub folder: Option<String>,
+    pub key: u8,
+    pub change: ButtonChange,
+}
+
+/// How button changes
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ButtonChange {
+    /// Button appears on empty key, contains new button
+    Added(Button),
+    /// Button is removed from the key, contains current button
+    Removed(Button),
+    /// Button stays on the key, but its components change
+    Changed(Vec<ComponentChange>),
+}
+
+/// Change of a single component of a button
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ComponentChange {
+    Added { component: String, value: Value },
+    Removed { component: String, value: Value },
+    Changed { component: String, from: Value, to: Value },
+}
+
+impl ConfigDiff {
+    /// Compares current config with new config
+    pub fn between(current: &DeviceConfig, new: &DeviceConfig) -> ConfigDiff {
+        let mut diff = ConfigDiff::default();
+
+        if current.brightness != new.brightness {
+            diff.brightness = Some((current.brightness, new.brightness));
+        }
+
+        if current.layout.display_name != new.layout.display_name {
+            diff.root_name = Some(new.layout.display_name.clone());
+        }
+
+        diff_button_maps(None, &current.layout.buttons, &new.layout.buttons, &mut diff.buttons);
+
+        let current_folders = folders_of(current);
+        let new_folders = folders_of(new);
+
+        let ids: BTreeSet<&String> = current_folders.keys().chain(new_folders.keys()).collect();
+
+        for id in ids {
+            match (current_folders.get(id), new_folders.get(id)) {
+                (Some(folder), None) => diff.folders_removed.push(FolderRef { id: id.clone(), name: folder.display_name.clone() }),
+                (None, Some(folder)) => diff.folders_added.push(FolderRef { id: id.clone(), name: folder.display_name.clone() }),
+                (Some(current), Some(new)) => diff_button_maps(Some(id), &current.buttons, &new.buttons, &mut diff.buttons),
+                (None, None) => {}
+            }
+        }
+
+        diff
+    }
+
+    /// Checks if configs are the same
+    pub fn is_empty(&self) -> bool {
+        self == &ConfigDiff::default()
+    }
+}
+
+fn diff_button_maps(folder: Option<&String>, current: &ButtonMap, new: &ButtonMap, diffs: &mut Vec<ButtonDiff>) {
+    let keys: BTreeSet<u8> = current.keys().chain(new.keys()).copied().collect();
+
+    for key in keys {
+        let change = match (current.get(&key), new.get(&key)) {
+            (None, Some(button)) => ButtonChange::Added(button.clone()),
+            (Some(button), None) => ButtonChange::Removed(button.clone()),
+            (Some(old), Some(new)) if old.0 != new.0 => ButtonChange::Changed(diff_components(old, new)),
+            _ => continue
+        };
+
+        diffs.push(ButtonDiff {
+            folder: folder.cloned(),
+            key,
+            change
+        });
+    }
+}
+
+fn diff_components(current: &Button, new: &Button) -> Vec<ComponentChange> {
+    let names: BTreeSet<&String> = current.0.keys().chain(new.0.keys()).collect();
+
+    names.into_iter()
+        .filter_map(|name| {
+            let component = name.clone();
+
+            match (current.0.get(name), new.0.get(name)) {
+                (None, Some(value)) => Some(ComponentChange::Added { component, value: value.clone() }),
+                (Some(value), None) => Some(ComponentChange::Removed { component, value: value.clone() }),
+                (Some(from), Some(to)) if from != to => Some(ComponentChange::Changed { component, from: from.clone(), to: to.clone() }),
+                _ => None
+            }
+        })
+        .collect()
+}
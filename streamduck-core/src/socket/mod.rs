@@ -1,20 +1,56 @@
 //! Socket related definitions
 
-use std::io::Write;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, Write};
 use std::ops::Deref;
 use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::thread::spawn;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use serde::de::{DeserializeOwned, Error};
 use serde_json::Value;
 use crate::modules::events::SDGlobalEvent;
 
+thread_local! {
+    static REQUESTING_CLIENT: Cell<Option<RequestOrigin>> = Cell::new(None);
+}
+
+/// Client whose request is being processed on a thread, client is [None] for requests that didn't come from socket clients
+#[derive(Clone, Copy)]
+struct RequestOrigin {
+    client: Option<u64>,
+    read_only: bool,
+}
+
+/// ID of the client whose request is being processed on current thread
+pub fn requesting_client() -> Option<u64> {
+    REQUESTING_CLIENT.with(|x| x.get()).and_then(|x| x.client)
+}
+
+/// Response to requests that read-only clients aren't allowed to make
+#[derive(Serialize, Deserialize)]
+pub struct PermissionDenied {
+    /// Type of the request that was rejected
+    pub request: String,
+}
+
+impl SocketData for PermissionDenied {
+    const NAME: &'static str = "permission_denied";
+}
+
 /// Type for listener's socket handles
 pub type SocketHandle<'a> = &'a mut dyn Write;
 
 /// Boxed socket listener
 pub type BoxedSocketListener = Box<dyn SocketListener + Send + Sync>;
 
+/// Handler of request that was registered by plugin
+pub type PluginRequestHandler = Arc<dyn Fn(SocketHandle, &SocketPacket) + Send + Sync>;
+
 /// Socket packet
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SocketPacket {
@@ -26,6 +62,24 @@ pub struct SocketPacket {
     pub data: Option<Value>
 }
 
+/// Byte that terminates every packet
+pub const PACKET_TERMINATOR: u8 = 0x4;
+
+/// Maximum size of a single packet, terminator excluded
+pub const MAX_PACKET_SIZE: usize = 16 * 1024 * 1024;
+
+/// Text that replaces secrets in packets sent to read-only clients
+pub const REDACTED: &str = "<redacted>";
+
+/// Endings of field names that hold credentials
+const SECRET_FIELD_SUFFIXES: &[&str] = &["token", "secret", "password", "api_key"];
+
+/// Time during which disconnected session can be resumed
+pub const SESSION_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+/// Maximum amount of events buffered for a disconnected session, oldest events are dropped first
+pub const SESSION_BUFFER_LIMIT: usize = 1000;
+
 /// Socket listener, something that can listen in to socket connections
 pub trait SocketListener {
     /// Called when message is received, handle can be used to send back a response
@@ -103,6 +157,51 @@ pub fn send_no_data_packet_with_requester<T: SocketData>(handle: SocketHandle, r
     Ok(())
 }
 
+/// Checks if field with the name holds credentials, like tokens, passwords or API keys
+pub fn is_secret_field(name: &str) -> bool {
+    let name = name.to_lowercase();
+    SECRET_FIELD_SUFFIXES.iter().any(|x| name.ends_with(x))
+}
+
+/// Replaces strings of secret fields with [REDACTED], including UI values that are named as secret fields
+pub fn redact_secrets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            let named_secret = map.get("name").and_then(|x| x.as_str()).map_or(false, is_secret_field);
+
+            for (key, field) in map.iter_mut() {
+                if is_secret_field(key) || (named_secret && key == "value") {
+                    redact_strings(field);
+                } else {
+                    redact_secrets(field);
+                }
+            }
+        }
+
+        Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+
+        _ => {}
+    }
+}
+
+fn redact_strings(value: &mut Value) {
+    match value {
+        Value::String(string) if !string.is_empty() => *string = REDACTED.to_string(),
+        Value::Object(map) => map.values_mut().for_each(redact_strings),
+        Value::Array(items) => items.iter_mut().for_each(redact_strings),
+        _ => {}
+    }
+}
+
+/// Redacts secrets in data of the packet, for packets that are sent to read-only clients
+pub fn redact_packet(mut packet: SocketPacket) -> SocketPacket {
+    if let Some(data) = &mut packet.data {
+        redact_secrets(data);
+    }
+
+    packet
+}
+
 /// Sends a packet as is
 pub fn send_packet_as_is(handle: SocketHandle, data: SocketPacket) -> Result<(), SocketError> {
     write_in_chunks(handle, format!("{}\u{0004}", serde_json::to_string(&data)?))?;
@@ -129,10 +228,250 @@ impl From<std::io::Error> for SocketError {
     }
 }
 
+/// Enumeration of errors that can occur while reading incoming packets
+#[derive(Debug)]
+pub enum PacketError {
+    /// Stream ended cleanly between packets
+    Closed,
+    /// Stream ended in the middle of a packet
+    Truncated,
+    /// Packet is bigger than [MAX_PACKET_SIZE], contains size that was read before giving up
+    TooLarge(usize),
+    InvalidUtf8(std::str::Utf8Error),
+    InvalidJson(serde_json::Error),
+    ReadError(std::io::Error),
+}
+
+impl From<std::str::Utf8Error> for PacketError {
+    fn from(err: std::str::Utf8Error) -> Self {
+        PacketError::InvalidUtf8(err)
+    }
+}
+
+impl From<serde_json::Error> for PacketError {
+    fn from(err: serde_json::Error) -> Self {
+        PacketError::InvalidJson(err)
+    }
+}
+
+impl From<std::io::Error> for PacketError {
+    fn from(err: std::io::Error) -> Self {
+        PacketError::ReadError(err)
+    }
+}
+
+/// Parses single frame into a packet, trailing terminator is optional. Doesn't panic on any input
+pub fn parse_packet(frame: &[u8]) -> Result<SocketPacket, PacketError> {
+    let frame = frame.strip_suffix(&[PACKET_TERMINATOR]).unwrap_or(frame);
+
+    if frame.len() > MAX_PACKET_SIZE {
+        return Err(PacketError::TooLarge(frame.len()));
+    }
+
+    Ok(serde_json::from_str(std::str::from_utf8(frame)?.trim())?)
+}
+
+/// Reads and parses next packet from the stream, never buffering more than [MAX_PACKET_SIZE].
+/// Stream stays usable after any error except [PacketError::Closed], [PacketError::Truncated] and [PacketError::ReadError]
+pub fn read_packet(reader: &mut dyn BufRead) -> Result<SocketPacket, PacketError> {
+    let mut frame = vec![];
+
+    loop {
+        let available = reader.fill_buf()?;
+
+        if available.is_empty() {
+            return Err(if frame.is_empty() { PacketError::Closed } else { PacketError::Truncated });
+        }
+
+        let (chunk, terminated) = match available.iter().position(|x| *x == PACKET_TERMINATOR) {
+            Some(index) => (&available[..index], true),
+            None => (available, false),
+        };
+
+        if frame.len() + chunk.len() > MAX_PACKET_SIZE {
+            let size = frame.len() + chunk.len();
+            let consumed = chunk.len() + terminated as usize;
+            reader.consume(consumed);
+
+            if !terminated {
+                skip_frame(reader)?;
+            }
+
+            return Err(PacketError::TooLarge(size));
+        }
+
+        frame.extend_from_slice(chunk);
+        let consumed = chunk.len() + terminated as usize;
+        reader.consume(consumed);
+
+        if terminated {
+            return parse_packet(&frame);
+        }
+    }
+}
+
+/// Discards everything up to and including next terminator, so reading can continue from the next packet
+fn skip_frame(reader: &mut dyn BufRead) -> Result<(), PacketError> {
+    loop {
+        let available = reader.fill_buf()?;
+
+        if available.is_empty() {
+            return Err(PacketError::Truncated);
+        }
+
+        match available.iter().position(|x| *x == PACKET_TERMINATOR) {
+            Some(index) => {
+                reader.consume(index + 1);
+                return Ok(());
+            }
+
+            None => {
+                let length = available.len();
+                reader.consume(length);
+            }
+        }
+    }
+}
+
 /// Manager of socket listeners
 pub struct SocketManager {
     listeners: RwLock<Vec<BoxedSocketListener>>,
-    pools: RwLock<Vec<Arc<SocketPool>>>
+    plugin_requests: RwLock<HashMap<String, PluginRequestHandler>>,
+    pools: RwLock<Vec<Arc<SocketPool>>>,
+    sessions: RwLock<HashMap<String, Session>>,
+    clients: RwLock<HashMap<u64, Arc<SocketClient>>>,
+    next_client_id: AtomicU64,
+    /// If new clients have to authenticate before making requests
+    auth_required: AtomicBool,
+    /// Requests that read-only clients are allowed to make
+    read_only_requests: RwLock<HashSet<String>>
+}
+
+/// Event subscription that outlives connection for [SESSION_GRACE_PERIOD]
+struct Session {
+    pool: Arc<SocketPool>,
+    /// Access of the connection that started the session
+    access: ClientAccess,
+    /// When connection of the session was lost, [None] if session is currently connected
+    detached: Option<Instant>
+}
+
+/// Authentication state of a client, kept in sessions so resumed connections get the same access
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ClientAccess {
+    pub authenticated: bool,
+    pub read_only: bool,
+}
+
+/// Information about connected client
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClientInfo {
+    pub id: u64,
+    /// Kind of connection client is using, like "unix" or "windows-requests"
+    pub transport: String,
+    /// Name that client reported about itself
+    pub name: Option<String>,
+    /// Version that client reported about itself
+    pub version: Option<String>,
+    /// Unix timestamp of when client connected
+    pub connected_since: u64,
+    /// Amount of requests client has sent
+    pub request_count: u64,
+    /// If client receives events
+    pub receives_events: bool,
+    /// If client has a session that can be resumed after reconnecting
+    pub has_session: bool,
+    /// If client can only make requests that don't change anything
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// Connection that is registered in socket manager, created by socket implementations
+pub struct SocketClient {
+    id: u64,
+    transport: String,
+    connected_since: u64,
+    identity: RwLock<(Option<String>, Option<String>)>,
+    request_count: AtomicU64,
+    pool: Option<Arc<SocketPool>>,
+    disconnected: AtomicBool,
+    authenticated: AtomicBool,
+    read_only: AtomicBool,
+    closer: Mutex<Option<Box<dyn FnOnce() + Send>>>
+}
+
+impl SocketClient {
+    /// ID of the client
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Sets name and version that client reported about itself
+    pub fn identify(&self, name: String, version: String) {
+        *self.identity.write().unwrap() = (Some(name), Some(version));
+    }
+
+    /// Should be called by socket implementations for every packet received from the client
+    pub fn count_request(&self) {
+        self.request_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// If client was disconnected with [SocketManager::disconnect_client], socket implementations should stop serving it
+    pub fn is_disconnected(&self) -> bool {
+        self.disconnected.load(Ordering::Relaxed)
+    }
+
+    /// Marks client as authenticated, done after client has provided valid token
+    pub fn authenticate(&self) {
+        self.authenticated.store(true, Ordering::Relaxed);
+    }
+
+    /// If client is allowed to make requests and receive events, always true if authentication isn't required
+    pub fn is_authenticated(&self) -> bool {
+        self.authenticated.load(Ordering::Relaxed)
+    }
+
+    /// Restricts client to requests that don't change anything, can't be undone for the connection
+    pub fn make_read_only(&self) {
+        self.read_only.store(true, Ordering::Relaxed);
+    }
+
+    /// If client can only make requests that don't change anything
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::Relaxed)
+    }
+
+    /// Gets authentication state of the client
+    pub fn access(&self) -> ClientAccess {
+        ClientAccess {
+            authenticated: self.is_authenticated(),
+            read_only: self.is_read_only()
+        }
+    }
+
+    /// Gives client access of a resumed session, read-only restriction of the client is kept
+    pub fn restore_access(&self, access: ClientAccess) {
+        if access.authenticated {
+            self.authenticate();
+        }
+
+        if access.read_only {
+            self.make_read_only();
+        }
+    }
+
+    /// Marks client as disconnected, closes its event pool and connection if closer was provided
+    fn disconnect(&self) {
+        self.disconnected.store(true, Ordering::Relaxed);
+
+        if let Some(pool) = &self.pool {
+            pool.close();
+        }
+
+        if let Some(closer) = self.closer.lock().unwrap().take() {
+            closer();
+        }
+    }
 }
 
 impl SocketManager {
@@ -140,7 +479,13 @@ impl SocketManager {
     pub fn new() -> Arc<SocketManager> {
         Arc::new(SocketManager {
             listeners: Default::default(),
-            pools: Default::default()
+            plugin_requests: Default::default(),
+            pools: Default::default(),
+            sessions: Default::default(),
+            clients: Default::default(),
+            next_client_id: AtomicU64::new(1),
+            auth_required: Default::default(),
+            read_only_requests: Default::default()
         })
     }
 
@@ -151,9 +496,108 @@ impl SocketManager {
 
     /// Sends a message to all listeners, for socket implementation to trigger all listeners when message is received
     pub fn received_message(&self, handle: SocketHandle, packet: SocketPacket) {
+        let read_only = REQUESTING_CLIENT.with(|x| x.get()).map_or(false, |x| x.read_only);
+
+        if read_only {
+            if !self.is_read_only_request(&packet.ty) {
+                send_packet(handle, &packet, &PermissionDenied {
+                    request: packet.ty.clone()
+                }).ok();
+                return;
+            }
+
+            // Responses are captured, so secrets can be redacted before they reach the client
+            let mut buffer: Vec<u8> = vec![];
+            self.dispatch_message(&mut buffer, packet);
+
+            for response in buffer.split(|x| *x == PACKET_TERMINATOR).filter_map(|x| parse_packet(x).ok()) {
+                send_packet_as_is(handle, redact_packet(response)).ok();
+            }
+        } else {
+            self.dispatch_message(handle, packet);
+        }
+    }
+
+    fn dispatch_message(&self, handle: SocketHandle, packet: SocketPacket) {
         for listener in self.listeners.read().unwrap().deref() {
             listener.message(handle, packet.clone());
         }
+
+        let handler = self.plugin_requests.read().unwrap().get(&packet.ty).cloned();
+
+        if let Some(handler) = handler {
+            handler(handle, &packet);
+        }
+    }
+
+    /// Same as [SocketManager::received_message], but changes made by listeners are attributed to the client, see [requesting_client].
+    /// Requests of read-only clients that aren't allowed with [SocketManager::allow_for_read_only] are rejected with [PermissionDenied]
+    pub fn received_message_from(&self, client: &SocketClient, handle: SocketHandle, packet: SocketPacket) {
+        self.received_message_with_origin(RequestOrigin {
+            client: Some(client.id()),
+            read_only: client.is_read_only()
+        }, handle, packet);
+    }
+
+    /// Same as [SocketManager::received_message], but only requests allowed with [SocketManager::allow_for_read_only] are processed,
+    /// for transports that don't have socket clients
+    pub fn received_read_only_message(&self, handle: SocketHandle, packet: SocketPacket) {
+        self.received_message_with_origin(RequestOrigin {
+            client: None,
+            read_only: true
+        }, handle, packet);
+    }
+
+    fn received_message_with_origin(&self, origin: RequestOrigin, handle: SocketHandle, packet: SocketPacket) {
+        let previous = REQUESTING_CLIENT.with(|x| x.replace(Some(origin)));
+        self.received_message(handle, packet);
+        REQUESTING_CLIENT.with(|x| x.set(previous));
+    }
+
+    /// Allows read-only clients to make the requests, should only be used for requests that don't change anything
+    pub fn allow_for_read_only(&self, names: &[&str]) {
+        self.read_only_requests.write().unwrap().extend(names.iter().map(|x| x.to_string()));
+    }
+
+    /// Checks if read-only clients are allowed to make the request
+    pub fn is_read_only_request(&self, name: &str) -> bool {
+        self.read_only_requests.read().unwrap().contains(name)
+    }
+
+    /// Registers handler for request of a plugin, [SocketData::NAME] of the request must be namespaced as "plugin_name/request_name".
+    /// Returns false if name isn't namespaced with the plugin or request with the name is already registered
+    pub fn add_plugin_request<T: SocketData>(&self, plugin_name: &str, handler: impl Fn(SocketHandle, &SocketPacket) + Send + Sync + 'static) -> bool {
+        let valid_name = T::NAME.strip_prefix(plugin_name)
+            .and_then(|x| x.strip_prefix('/'))
+            .map_or(false, |x| !x.is_empty() && !x.contains('/'));
+
+        if plugin_name.is_empty() || !valid_name {
+            log::warn!("Plugin '{}' tried to register request '{}' that isn't namespaced as '{}/request_name'", plugin_name, T::NAME, plugin_name);
+            return false;
+        }
+
+        let mut requests = self.plugin_requests.write().unwrap();
+
+        if requests.contains_key(T::NAME) {
+            log::warn!("Request '{}' is already registered", T::NAME);
+            return false;
+        }
+
+        requests.insert(T::NAME.to_string(), Arc::new(handler));
+        true
+    }
+
+    /// Removes all requests that were registered by a plugin
+    pub fn remove_plugin_requests(&self, plugin_name: &str) {
+        let prefix = format!("{}/", plugin_name);
+        self.plugin_requests.write().unwrap().retain(|name, _| !name.starts_with(&prefix));
+    }
+
+    /// Names of requests that were registered by plugins
+    pub fn list_plugin_requests(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.plugin_requests.read().unwrap().keys().cloned().collect();
+        names.sort();
+        names
     }
 
     /// Creates a new message pool
@@ -163,7 +607,8 @@ impl SocketManager {
         let new_pool = Arc::new(SocketPool {
             messages: Mutex::new(vec![]),
             condvar: Default::default(),
-            is_open: RwLock::new(true)
+            is_open: RwLock::new(true),
+            released: RwLock::new(false)
         });
 
         pools.push(new_pool.clone());
@@ -180,6 +625,177 @@ impl SocketManager {
         for pool in pools.iter() {
             pool.add_message(packet.clone())
         }
+
+        drop(pools);
+        self.maintain_sessions();
+    }
+
+    /// Starts a session for connection that uses the pool, returns token that can be used to resume the session.
+    /// Access of the client is kept in the session and restored when session is resumed
+    pub fn start_session(&self, pool: &Arc<SocketPool>, access: ClientAccess) -> String {
+        let token: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(32)
+            .map(char::from)
+            .collect();
+
+        let mut sessions = self.sessions.write().unwrap();
+        sessions.retain(|_, session| !Arc::ptr_eq(&session.pool, pool));
+        sessions.insert(token.clone(), Session {
+            pool: pool.clone(),
+            access,
+            detached: None
+        });
+
+        token
+    }
+
+    /// Checks if session exists and hasn't expired yet
+    pub fn session_exists(&self, token: &str) -> bool {
+        self.maintain_sessions();
+        self.sessions.read().unwrap().contains_key(token)
+    }
+
+    /// Sends message only to connection of the session, messages are buffered while session is disconnected.
+    /// Returns false if session doesn't exist or has expired
+    pub fn send_to_session(&self, token: &str, packet: SocketPacket) -> bool {
+        self.maintain_sessions();
+
+        if let Some(session) = self.sessions.read().unwrap().get(token) {
+            session.pool.add_message(packet);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Attaches session to connection that uses the pool, moving over all events that were buffered while session was disconnected.
+    /// Returns amount of moved events and access that session was started with, or [None] if session doesn't exist or has expired
+    pub fn resume_session(&self, token: &str, pool: &Arc<SocketPool>) -> Option<(usize, ClientAccess)> {
+        self.maintain_sessions();
+
+        let mut sessions = self.sessions.write().unwrap();
+        sessions.retain(|key, session| key == token || !Arc::ptr_eq(&session.pool, pool));
+
+        let session = sessions.get_mut(token)?;
+
+        if Arc::ptr_eq(&session.pool, pool) {
+            return Some((0, session.access));
+        }
+
+        let missed = session.pool.drain_into(pool);
+        session.pool.close();
+        session.pool = pool.clone();
+        session.detached = None;
+
+        Some((missed, session.access))
+    }
+
+    /// Called by socket implementations when connection is lost.
+    /// Pool keeps collecting events if connection had a session, otherwise it gets closed
+    pub fn release_pool(&self, pool: &Arc<SocketPool>) {
+        let mut sessions = self.sessions.write().unwrap();
+
+        if let Some(session) = sessions.values_mut().find(|x| Arc::ptr_eq(&x.pool, pool)) {
+            session.detached = Some(Instant::now());
+            pool.wake();
+        } else {
+            pool.close();
+        }
+    }
+
+    /// Registers connection for client listing, transport is a short name of the connection kind.
+    /// Pool should be the one connection receives events from, closer should close the connection if that's possible from another thread
+    pub fn register_client(&self, transport: &str, pool: Option<Arc<SocketPool>>, closer: Option<Box<dyn FnOnce() + Send>>) -> Arc<SocketClient> {
+        let client = Arc::new(SocketClient {
+            id: self.next_client_id.fetch_add(1, Ordering::Relaxed),
+            transport: transport.to_string(),
+            connected_since: SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_secs()).unwrap_or(0),
+            identity: Default::default(),
+            request_count: Default::default(),
+            pool,
+            disconnected: Default::default(),
+            authenticated: AtomicBool::new(!self.auth_required.load(Ordering::Relaxed)),
+            read_only: Default::default(),
+            closer: Mutex::new(closer)
+        });
+
+        self.clients.write().unwrap().insert(client.id, client.clone());
+
+        client
+    }
+
+    /// Sets if clients that connect from now on have to authenticate before making requests
+    pub fn set_auth_required(&self, required: bool) {
+        self.auth_required.store(required, Ordering::Relaxed);
+    }
+
+    /// Called by socket implementations when registered connection is lost
+    pub fn unregister_client(&self, client: &Arc<SocketClient>) {
+        self.clients.write().unwrap().remove(&client.id);
+    }
+
+    /// Lists currently connected clients, sorted by ID
+    pub fn list_clients(&self) -> Vec<ClientInfo> {
+        let sessions = self.sessions.read().unwrap();
+
+        let mut clients: Vec<ClientInfo> = self.clients.read().unwrap().values()
+            .map(|client| {
+                let (name, version) = client.identity.read().unwrap().clone();
+
+                ClientInfo {
+                    id: client.id,
+                    transport: client.transport.clone(),
+                    name,
+                    version,
+                    connected_since: client.connected_since,
+                    request_count: client.request_count.load(Ordering::Relaxed),
+                    receives_events: client.pool.is_some(),
+                    has_session: client.pool.as_ref()
+                        .map_or(false, |pool| sessions.values().any(|x| Arc::ptr_eq(&x.pool, pool))),
+                    read_only: client.is_read_only()
+                }
+            })
+            .collect();
+
+        clients.sort_by_key(|x| x.id);
+
+        clients
+    }
+
+    /// Disconnects client, returns false if client wasn't found
+    pub fn disconnect_client(&self, id: u64) -> bool {
+        let client = self.clients.write().unwrap().remove(&id);
+
+        if let Some(client) = client {
+            // Session of the client shouldn't be resumable after forced disconnect
+            if let Some(pool) = &client.pool {
+                self.sessions.write().unwrap().retain(|_, session| !Arc::ptr_eq(&session.pool, pool));
+            }
+
+            client.disconnect();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes expired sessions and trims buffers of disconnected ones
+    fn maintain_sessions(&self) {
+        let mut sessions = self.sessions.write().unwrap();
+
+        sessions.retain(|_, session| {
+            if let Some(detached) = session.detached {
+                if detached.elapsed() > SESSION_GRACE_PERIOD {
+                    session.pool.close();
+                    return false;
+                }
+
+                session.pool.trim(SESSION_BUFFER_LIMIT);
+            }
+
+            true
+        });
     }
 }
 
@@ -203,7 +819,8 @@ pub fn send_event_to_socket(socket_manager: &Arc<SocketManager>, event: SDGlobal
 pub struct SocketPool {
     messages: Mutex<Vec<SocketPacket>>,
     condvar: Condvar,
-    is_open: RwLock<bool>
+    is_open: RwLock<bool>,
+    released: RwLock<bool>
 }
 
 impl SocketPool {
@@ -226,11 +843,72 @@ impl SocketPool {
         }
     }
 
+    /// Retrieves a message, waiting up to specified amount of time if pool is currently empty
+    pub fn take_message_timeout(&self, timeout: Duration) -> Option<SocketPacket> {
+        let (mut guard, _) = self.condvar.wait_timeout_while(self.messages.lock().unwrap(), timeout, |x| x.len() <= 0).unwrap();
+        guard.pop()
+    }
+
+    /// Retrieves a message for a connection, blocks while pool is empty.
+    /// Returns [None] if pool was closed or released by connection, see [SocketManager::release_pool]
+    pub fn take_message_for_connection(&self) -> Option<SocketPacket> {
+        let mut guard = self.messages.lock().unwrap();
+
+        loop {
+            if !self.is_open() || self.is_released() {
+                return None;
+            }
+
+            if let Some(packet) = guard.pop() {
+                return Some(packet);
+            }
+
+            guard = self.condvar.wait(guard).unwrap();
+        }
+    }
+
+    /// Puts message back as the oldest one, for when it couldn't be delivered
+    pub fn return_message(&self, message: SocketPacket) {
+        self.messages.lock().unwrap().push(message);
+    }
+
+    /// Moves all messages into another pool, keeping their order, returns amount of moved messages
+    fn drain_into(&self, other: &SocketPool) -> usize {
+        let messages: Vec<SocketPacket> = self.messages.lock().unwrap().drain(..).collect();
+        let count = messages.len();
+
+        let mut other_messages = other.messages.lock().unwrap();
+        // Both are stored newest first, moved messages are older than anything in other pool
+        other_messages.extend(messages);
+        other.condvar.notify_all();
+
+        count
+    }
+
+    /// Drops oldest messages until pool has no more than specified amount of messages
+    fn trim(&self, limit: usize) {
+        self.messages.lock().unwrap().truncate(limit);
+    }
+
+    /// Marks pool as no longer used by a connection, waking up anything waiting for messages
+    fn wake(&self) {
+        // Holding messages lock, so waiting threads can't miss the notification
+        let _messages = self.messages.lock().unwrap();
+        *self.released.write().unwrap() = true;
+        self.condvar.notify_all();
+    }
+
+    fn is_released(&self) -> bool {
+        *self.released.read().unwrap()
+    }
+
     pub fn is_open(&self) -> bool {
         *self.is_open.read().unwrap()
     }
 
     pub fn close(&self) {
+        let _messages = self.messages.lock().unwrap();
         *self.is_open.write().unwrap() = false;
+        self.condvar.notify_all();
     }
 }
\ No newline at end of file
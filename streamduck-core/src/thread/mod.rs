@@ -11,17 +11,32 @@ use std::thread::{sleep, spawn};
 use std::time::{Duration, Instant};
 use image::{DynamicImage, ImageFormat};
 use streamdeck::{Colour, DeviceImage, ImageMode, StreamDeck};
-use rendering::RendererComponent;
+use rendering::{RendererComponent, write_button_image};
 use crate::core::SDCore;
 use crate::core::button::{Component, parse_unique_button_to_component};
-use crate::core::methods::{CoreHandle, get_current_screen};
+use crate::core::methods::{CoreHandle, get_button_images, get_current_screen, get_visible_buttons};
+use crate::core::composite::composite_visible_buttons;
+use crate::core::mirror::mirrored_visible_buttons;
+use crate::core::startup::run_startup_actions;
 use crate::images::SDImage;
+use crate::logging::set_thread_log_context;
+use crate::metrics::record_render_time;
 use crate::modules::core_module::CoreSettings;
 use crate::modules::UniqueSDModule;
+use crate::modules::scheduler::schedule_task;
+use crate::thread::orientation::key_mapping;
+use crate::thread::transition::{PanelTransition, TransitionAnimation, TransitionDirection};
 
 /// Rendering utilities
 pub mod util;
 pub mod rendering;
+/// Transition animations between panels
+pub mod transition;
+/// Orientation of devices that are mounted sideways or upside down
+pub mod orientation;
+
+/// How long device thread keeps running at full rate after key presses or commands
+const ACTIVITY_TIMEOUT: Duration = Duration::from_secs(1);
 
 /// Collection of images
 pub type ImageCollection = Arc<RwLock<HashMap<String, SDImage>>>;
@@ -55,6 +70,9 @@ pub enum DeviceThreadCommunication {
 
     /// Clears button and sets it to black color
     ClearButtonImage(u8),
+
+    /// Plays transition from provided images of previous screen to the screen that will be shown on next refresh
+    StartTransition(PanelTransition, TransitionDirection, HashMap<u8, DynamicImage>),
 }
 
 /// Spawns device thread from a core reference
@@ -62,6 +80,8 @@ pub fn spawn_device_thread(core: Arc<SDCore>, streamdeck: StreamDeck, key_tx: Se
     let (tx, rx) = channel::<Vec<DeviceThreadCommunication>>();
 
     spawn(move || {
+        set_thread_log_context(Some(&core.serial_number), None);
+
         let core = CoreHandle::wrap(core.clone());
         let mut streamdeck = streamdeck;
         let mut last_buttons = Vec::new();
@@ -71,29 +91,52 @@ pub fn spawn_device_thread(core: Arc<SDCore>, streamdeck: StreamDeck, key_tx: Se
         let missing = rendering::draw_missing_texture(core.core.image_size);
 
         let mut animation_counters = HashMap::new();
-        let mut last_iter = Instant::now();
         let mut renderer_map = HashMap::new();
         let mut animation_cache: HashMap<u64, Arc<DeviceImage>> = HashMap::new();
         let mut previous_state: HashMap<u8, u64> = HashMap::new();
+        let mut pending_transition = None;
+        let mut transition: Option<TransitionAnimation> = None;
+        let mut last_activity = Instant::now();
+        let mut wait = None;
+        let mut mapping = key_mapping(&core.core);
+        let kind = core.core.kind;
+        let mut started = false;
         loop {
             if core.core.is_closed() {
                 break;
             }
 
-            // Reading buttons
-            match streamdeck.read_buttons(None) {
+            // Reading buttons, waiting for key presses instead of sleeping so presses are handled right away
+            let read_start = Instant::now();
+            let result = streamdeck.read_buttons(wait);
+
+            if let (Err(streamdeck::Error::NoData), Some(wait)) = (&result, wait) {
+                let remaining = wait.saturating_sub(read_start.elapsed());
+
+                if !remaining.is_zero() {
+                    sleep(remaining);
+                }
+            }
+
+            let last_iter = Instant::now();
+
+            match result {
                 Ok(buttons) => {
+                    if buttons != last_buttons {
+                        last_activity = Instant::now();
+                    }
+
                     for (key, value) in buttons.iter().enumerate() {
                         if let Some(last_value) = last_buttons.get(key) {
                             if last_value != value {
-                                if key_tx.send((key as u8, *last_value == 0)).is_err() {
+                                if key_tx.send((mapping.visible_key(kind, key as u8), *last_value == 0)).is_err() {
                                     log::error!("Key Handler thread crashed, killing connection...");
                                     core.core.close();
                                 }
                             }
                         } else {
                             if *value > 0 {
-                                if key_tx.send((key as u8, true)).is_err() {
+                                if key_tx.send((mapping.visible_key(kind, key as u8), true)).is_err() {
                                     log::error!("Key Handler thread crashed, killing connection...");
                                     core.core.close();
                                 }
@@ -119,6 +162,8 @@ pub fn spawn_device_thread(core: Arc<SDCore>, streamdeck: StreamDeck, key_tx: Se
             // Reading commands
             match rx.try_recv() {
                 Ok(com) => {
+                    last_activity = Instant::now();
+
                     for com in com {
                         match com {
                             DeviceThreadCommunication::SetBrightness(brightness) => {
@@ -128,37 +173,49 @@ pub fn spawn_device_thread(core: Arc<SDCore>, streamdeck: StreamDeck, key_tx: Se
                             DeviceThreadCommunication::SetButtonImage(key, image) => {
                                 let mut buffer = vec![];
 
-                                image.write_to(&mut Cursor::new(&mut buffer), match streamdeck.kind().image_mode() {
+                                mapping.apply(image).write_to(&mut Cursor::new(&mut buffer), match streamdeck.kind().image_mode() {
                                     ImageMode::Bmp => ImageFormat::Bmp,
                                     ImageMode::Jpeg => ImageFormat::Jpeg,
                                 }).ok();
 
-                                streamdeck.write_button_image(key, &DeviceImage::from(buffer)).ok();
+                                write_button_image(&mut streamdeck, &core.core.serial_number, mapping.device_key(kind, key), &DeviceImage::from(buffer));
                             }
 
                             DeviceThreadCommunication::SetButtonImageRaw(key, image) => {
-                                streamdeck.write_button_image(key, image.deref()).ok();
+                                write_button_image(&mut streamdeck, &core.core.serial_number, mapping.device_key(kind, key), image.deref());
                             }
 
                             DeviceThreadCommunication::ClearButtonImage(key) => {
-                                streamdeck.set_button_rgb(key, &Colour {
+                                streamdeck.set_button_rgb(mapping.device_key(kind, key), &Colour {
                                     r: 0,
                                     g: 0,
                                     b: 0
                                 }).ok();
                             }
 
+                            DeviceThreadCommunication::StartTransition(kind, direction, from) => {
+                                pending_transition = Some((kind, direction, from));
+                            }
+
                             DeviceThreadCommunication::RefreshScreen => {
-                                let current_screen = get_current_screen(&core);
+                                // Images in cache are rotated for previous key mapping
+                                let new_mapping = key_mapping(&core.core);
+
+                                if new_mapping != mapping {
+                                    mapping = new_mapping;
+                                    animation_cache.clear();
+                                    previous_state.clear();
+                                }
+
+                                let current_screen = composite_visible_buttons(&core)
+                                    .or_else(|| mirrored_visible_buttons(&core))
+                                    .or_else(|| get_visible_buttons(&core));
 
                                 if current_screen.is_none() {
                                     return;
                                 }
 
                                 let current_screen = current_screen.unwrap();
-                                let screen_handle = current_screen.read().unwrap();
-                                let current_screen = screen_handle.buttons.clone();
-                                drop(screen_handle);
 
                                 let core_settings: CoreSettings = core.config().get_plugin_settings().unwrap_or_default();
 
@@ -181,6 +238,12 @@ pub fn spawn_device_thread(core: Arc<SDCore>, streamdeck: StreamDeck, key_tx: Se
 
                                 core.core.render_manager.read_renderers().iter()
                                     .for_each(|(_, renderer)| renderer.refresh(&core));
+
+                                if let Some((kind, direction, from)) = pending_transition.take() {
+                                    if let Some(to) = get_button_images(&core) {
+                                        transition = Some(TransitionAnimation::new(&core, kind, direction, from, to));
+                                    }
+                                }
                             }
                         }
                     }
@@ -193,18 +256,50 @@ pub fn spawn_device_thread(core: Arc<SDCore>, streamdeck: StreamDeck, key_tx: Se
                 }
             }
 
-            rendering::process_frame(&core, &mut streamdeck, &mut animation_cache, &mut animation_counters, &mut renderer_map, &mut previous_state, &missing);
+            if let Some(animation) = &transition {
+                if animation.is_finished() {
+                    transition = None;
 
-            // Rate limiter
-            let rate = 1.0 / core.core.pool_rate as f32;
-            let time_since_last = last_iter.elapsed().as_secs_f32();
+                    // Making sure everything gets drawn again over the last frame of transition
+                    previous_state.clear();
+                    core.core.render_manager.read_renderers().iter()
+                        .for_each(|(_, renderer)| renderer.refresh(&core));
+                } else {
+                    animation.render(&mut streamdeck, core.core.key_count, mapping);
+                }
+            }
 
-            let to_wait = rate - time_since_last;
-            if to_wait > 0.0 {
-                sleep(Duration::from_secs_f32(to_wait));
+            if transition.is_none() {
+                let pressed = last_buttons.iter()
+                    .enumerate()
+                    .filter(|(_, value)| **value > 0)
+                    .map(|(key, _)| mapping.visible_key(kind, key as u8))
+                    .collect();
+
+                let render_start = Instant::now();
+                rendering::process_frame(&core, &mut streamdeck, &mut animation_cache, &mut animation_counters, &mut renderer_map, &mut previous_state, &missing, &pressed, mapping);
+                record_render_time(&core.core.serial_number, render_start.elapsed());
+
+                // Startup actions run on scheduler, so slow modules don't hold up rendering
+                if !started && get_current_screen(&core).is_some() {
+                    started = true;
+                    schedule_task(&core, Duration::ZERO, |core| run_startup_actions(&core));
+                }
             }
 
-            last_iter = Instant::now();
+            // Rate limiter, full rate is kept for a bit after any activity, otherwise running only as fast as buttons on the screen need
+            let frame_rate = if transition.is_some() || last_activity.elapsed() < ACTIVITY_TIMEOUT {
+                core.core.pool_rate as f32
+            } else {
+                rendering::required_frame_rate(&core, &animation_counters, &renderer_map)
+            };
+
+            let to_wait = 1.0 / frame_rate - last_iter.elapsed().as_secs_f32();
+            wait = if to_wait > 0.0 {
+                Some(Duration::from_secs_f32(to_wait))
+            } else {
+                None
+            };
         }
 
         log::trace!("rendering closed");
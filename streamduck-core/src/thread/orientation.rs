@@ -0,0 +1,176 @@
+//! Orientation of devices that are mounted sideways or upside down, and mirrored key layouts for left-handed use
+//!
+//! Layouts are made for the grid that user sees, device thread converts keys between that grid and keys of the device
+//! and rotates rendered images, so buttons appear upright
+
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+use streamdeck::Kind;
+use crate::adapt::key_columns;
+use crate::core::SDCore;
+
+/// Clockwise rotation of the device from its normal position
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeviceOrientation {
+    Normal,
+    Rotated90,
+    Rotated180,
+    Rotated270,
+}
+
+impl Default for DeviceOrientation {
+    fn default() -> Self {
+        DeviceOrientation::Normal
+    }
+}
+
+impl DeviceOrientation {
+    /// Gets orientation from rotation in degrees, only multiples of 90 are valid
+    pub fn from_degrees(degrees: u16) -> Option<DeviceOrientation> {
+        match degrees % 360 {
+            0 => Some(DeviceOrientation::Normal),
+            90 => Some(DeviceOrientation::Rotated90),
+            180 => Some(DeviceOrientation::Rotated180),
+            270 => Some(DeviceOrientation::Rotated270),
+            _ => None
+        }
+    }
+
+    /// Rotation in degrees
+    pub fn degrees(&self) -> u16 {
+        match self {
+            DeviceOrientation::Normal => 0,
+            DeviceOrientation::Rotated90 => 90,
+            DeviceOrientation::Rotated180 => 180,
+            DeviceOrientation::Rotated270 => 270,
+        }
+    }
+
+    /// Checks if device is turned on its side, so rows and columns are swapped
+    pub fn is_sideways(&self) -> bool {
+        matches!(self, DeviceOrientation::Rotated90 | DeviceOrientation::Rotated270)
+    }
+
+    /// Amount of key columns that user sees
+    pub fn columns(&self, kind: Kind) -> u8 {
+        if self.is_sideways() {
+            kind.keys() / key_columns(kind)
+        } else {
+            key_columns(kind)
+        }
+    }
+
+    /// Converts key that user sees into key of the device
+    pub fn device_key(&self, kind: Kind, key: u8) -> u8 {
+        let columns = key_columns(kind);
+        let rows = kind.keys() / columns;
+
+        let visible_columns = self.columns(kind);
+        let (row, column) = (key / visible_columns, key % visible_columns);
+
+        let (device_row, device_column) = match self {
+            DeviceOrientation::Normal => (row, column),
+            DeviceOrientation::Rotated90 => (rows - 1 - column, row),
+            DeviceOrientation::Rotated180 => (rows - 1 - row, columns - 1 - column),
+            DeviceOrientation::Rotated270 => (column, columns - 1 - row),
+        };
+
+        device_row * columns + device_column
+    }
+
+    /// Converts key of the device into key that user sees
+    pub fn visible_key(&self, kind: Kind, key: u8) -> u8 {
+        let columns = key_columns(kind);
+        let rows = kind.keys() / columns;
+        let (device_row, device_column) = (key / columns, key % columns);
+
+        let (row, column) = match self {
+            DeviceOrientation::Normal => (device_row, device_column),
+            DeviceOrientation::Rotated90 => (device_column, rows - 1 - device_row),
+            DeviceOrientation::Rotated180 => (rows - 1 - device_row, columns - 1 - device_column),
+            DeviceOrientation::Rotated270 => (columns - 1 - device_column, device_row),
+        };
+
+        row * self.columns(kind) + column
+    }
+
+    /// Rotates image, so it appears upright on the rotated device
+    pub fn apply(&self, image: DynamicImage) -> DynamicImage {
+        match self {
+            DeviceOrientation::Normal => image,
+            DeviceOrientation::Rotated90 => image.rotate270(),
+            DeviceOrientation::Rotated180 => image.rotate180(),
+            DeviceOrientation::Rotated270 => image.rotate90(),
+        }
+    }
+}
+
+/// Conversion between keys that user sees and keys of the device, made from orientation and mirroring settings of the device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KeyMapping {
+    pub orientation: DeviceOrientation,
+    /// Keys are mirrored horizontally
+    pub mirrored: bool,
+    /// Images are flipped horizontally too
+    pub flip_images: bool,
+}
+
+impl KeyMapping {
+    fn mirror(&self, kind: Kind, key: u8) -> u8 {
+        if !self.mirrored {
+            return key;
+        }
+
+        let columns = self.orientation.columns(kind);
+        let (row, column) = (key / columns, key % columns);
+
+        row * columns + (columns - 1 - column)
+    }
+
+    /// Converts key that user sees into key of the device
+    pub fn device_key(&self, kind: Kind, key: u8) -> u8 {
+        self.orientation.device_key(kind, self.mirror(kind, key))
+    }
+
+    /// Converts key of the device into key that user sees
+    pub fn visible_key(&self, kind: Kind, key: u8) -> u8 {
+        self.mirror(kind, self.orientation.visible_key(kind, key))
+    }
+
+    /// Flips and rotates image for the device
+    pub fn apply(&self, image: DynamicImage) -> DynamicImage {
+        // Flipping before rotation, so image is flipped as user sees it
+        let image = if self.mirrored && self.flip_images {
+            image.fliph()
+        } else {
+            image
+        };
+
+        self.orientation.apply(image)
+    }
+}
+
+/// Orientation of the device from its device config
+pub fn device_orientation(core: &SDCore) -> DeviceOrientation {
+    core.device_config.read().unwrap().orientation
+}
+
+/// Key mapping of the device from its device config
+pub fn key_mapping(core: &SDCore) -> KeyMapping {
+    let config = core.device_config.read().unwrap();
+
+    KeyMapping {
+        orientation: config.orientation,
+        mirrored: config.mirrored,
+        flip_images: config.flip_images
+    }
+}
+
+/// Amount of key columns that user sees on the device, uses grid of the core, so composite devices get their own grid
+pub fn visible_columns(core: &SDCore) -> u8 {
+    if device_orientation(core).is_sideways() {
+        core.key_count / core.columns
+    } else {
+        core.columns
+    }
+}
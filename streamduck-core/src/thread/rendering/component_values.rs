@@ -5,12 +5,36 @@ use strum::VariantNames;
 use std::str::FromStr;
 use crate::core::button::{Button, parse_button_to_component};
 use crate::core::methods::CoreHandle;
-use crate::modules::components::{map_ui_values, map_ui_values_ref, UIField, UIFieldType, UIFieldValue, UIValue};
-use crate::thread::rendering::{ButtonBackground, ButtonText, ButtonTextShadow, RendererComponent};
-use crate::thread::util::{resize_for_streamdeck, TextAlignment};
+use crate::modules::components::{FieldError, map_ui_values, map_ui_values_ref, UIField, UIFieldType, UIFieldValue, UIScalar, UIValue};
+use crate::thread::rendering::{ButtonBackground, ButtonText, ButtonTextShadow, PressedStyle, RendererComponent};
+use crate::thread::util::{ImageFit, TextAlignment};
 use crate::images::SDImage;
 use crate::util::hash_str;
 
+/// Limits of text padding slider, larger values can still be typed in
+const PADDING_SLIDER: UIScalar<i32> = UIScalar {
+    default_value: 0,
+    max_value: 36,
+    min_value: 0,
+    step: 1,
+    allow_out_of_bounds: true
+};
+
+/// Limits of gradient angle slider
+const ANGLE_SLIDER: UIScalar<i32> = UIScalar {
+    default_value: 0,
+    max_value: 360,
+    min_value: 0,
+    step: 15,
+    allow_out_of_bounds: true
+};
+
+/// Reads padding from slider value, also accepting unsigned integers that padding used to be
+fn padding_from_value(value: &UIFieldValue<UIValue>) -> Option<u32> {
+    value.try_into_u32().ok()
+        .or_else(|| value.try_into_i32().ok().map(|x| x.max(0) as u32))
+}
+
 pub fn get_renderer_component_values(core: &CoreHandle, button: &Button) -> Vec<UIValue> {
     if let Ok(component) = parse_button_to_component::<RendererComponent>(button) {
         let mut fields = vec![];
@@ -52,7 +76,7 @@ pub fn get_renderer_component_values(core: &CoreHandle, button: &Button) -> Vec<
                                 name: "background".to_string(),
                                 display_name: "Background Type".to_string(),
                                 description: "Type of the background to use".to_string(),
-                                ty: UIFieldType::Choice(vec!["Solid Color".to_string(), "Horizontal Gradient".to_string(), "Vertical Gradient".to_string(), "Existing Image".to_string(), "New Image".to_string()]),
+                                ty: UIFieldType::Choice(vec!["Solid Color".to_string(), "Horizontal Gradient".to_string(), "Vertical Gradient".to_string(), "Linear Gradient".to_string(), "Radial Gradient".to_string(), "Existing Image".to_string(), "Image Fill".to_string(), "New Image".to_string()]),
                                 value: UIFieldValue::Choice(
                                     match &component.background {
                                         ButtonBackground::Solid(_) => "Solid Color",
@@ -60,6 +84,9 @@ pub fn get_renderer_component_values(core: &CoreHandle, button: &Button) -> Vec<
                                         ButtonBackground::VerticalGradient(_, _) => "Vertical Gradient",
                                         ButtonBackground::ExistingImage(_) => "Existing Image",
                                         ButtonBackground::NewImage(_) => "New Image",
+                                        ButtonBackground::LinearGradient(..) => "Linear Gradient",
+                                        ButtonBackground::RadialGradient(..) => "Radial Gradient",
+                                        ButtonBackground::ImageFill(..) => "Image Fill",
                                     }.to_string()
                                 )
                             }
@@ -143,6 +170,81 @@ pub fn get_renderer_component_values(core: &CoreHandle, button: &Button) -> Vec<
                                     }
                                 );
                             }
+                            ButtonBackground::LinearGradient(start_color, end_color, angle) => {
+                                fields.push(
+                                    UIValue {
+                                        name: "start_color".to_string(),
+                                        display_name: "Gradient Start Color".to_string(),
+                                        description: "Color that gradient starts with".to_string(),
+                                        ty: UIFieldType::Color,
+                                        value: start_color.into()
+                                    }
+                                );
+
+                                fields.push(
+                                    UIValue {
+                                        name: "end_color".to_string(),
+                                        display_name: "Gradient End Color".to_string(),
+                                        description: "Color that gradient ends with".to_string(),
+                                        ty: UIFieldType::Color,
+                                        value: end_color.into()
+                                    }
+                                );
+
+                                fields.push(
+                                    UIValue {
+                                        name: "angle".to_string(),
+                                        display_name: "Gradient Angle".to_string(),
+                                        description: "Direction of the gradient in degrees, 0 goes from left to right, 90 from top to bottom".to_string(),
+                                        ty: UIFieldType::ValueSliderInteger(ANGLE_SLIDER),
+                                        value: UIFieldValue::ValueSliderInteger(*angle)
+                                    }
+                                );
+                            }
+                            ButtonBackground::RadialGradient(center_color, edge_color) => {
+                                fields.push(
+                                    UIValue {
+                                        name: "start_color".to_string(),
+                                        display_name: "Gradient Center Color".to_string(),
+                                        description: "Color that will be in the center of the gradient".to_string(),
+                                        ty: UIFieldType::Color,
+                                        value: center_color.into()
+                                    }
+                                );
+
+                                fields.push(
+                                    UIValue {
+                                        name: "end_color".to_string(),
+                                        display_name: "Gradient Edge Color".to_string(),
+                                        description: "Color that will be in corners of the gradient".to_string(),
+                                        ty: UIFieldType::Color,
+                                        value: edge_color.into()
+                                    }
+                                );
+                            }
+                            ButtonBackground::ImageFill(identifier, fit) => {
+                                fields.push(
+                                    UIValue {
+                                        name: "image".to_string(),
+                                        display_name: "Image".to_string(),
+                                        description: "Image to use as background of the button".to_string(),
+                                        ty: UIFieldType::ExistingImage,
+                                        value: UIFieldValue::ExistingImage(identifier.to_string())
+                                    }
+                                );
+
+                                fields.push(
+                                    UIValue {
+                                        name: "fit".to_string(),
+                                        display_name: "Image Fit".to_string(),
+                                        description: "How image is fitted into the button".to_string(),
+                                        ty: UIFieldType::Choice(
+                                            ImageFit::VARIANTS.iter().map(|x| x.to_string()).collect()
+                                        ),
+                                        value: UIFieldValue::Choice(fit.to_string())
+                                    }
+                                );
+                            }
                         }
 
                         fields
@@ -201,8 +303,8 @@ pub fn get_renderer_component_values(core: &CoreHandle, button: &Button) -> Vec<
                                             name: "padding".to_string(),
                                             display_name: "Padding".to_string(),
                                             description: "Gap to have from alignment/anchor point".to_string(),
-                                            ty: UIFieldType::InputFieldUnsignedInteger,
-                                            default_value: UIFieldValue::InputFieldUnsignedInteger(0)
+                                            ty: UIFieldType::ValueSliderInteger(PADDING_SLIDER),
+                                            default_value: UIFieldValue::ValueSliderInteger(0)
                                         },
                                         UIField {
                                             name: "offset".to_string(),
@@ -218,6 +320,31 @@ pub fn get_renderer_component_values(core: &CoreHandle, button: &Button) -> Vec<
                                             ty: UIFieldType::Color,
                                             default_value: UIFieldValue::Color(0, 0, 0, 255)
                                         },
+                                        UIField {
+                                            name: "auto_fit".to_string(),
+                                            display_name: "Fit Text".to_string(),
+                                            description: "Shrinks text until it fits the button".to_string(),
+                                            ty: UIFieldType::Checkbox {
+                                                disabled: false
+                                            },
+                                            default_value: UIFieldValue::Checkbox(false)
+                                        },
+                                        UIField {
+                                            name: "word_wrap".to_string(),
+                                            display_name: "Wrap Text".to_string(),
+                                            description: "Breaks text into multiple lines if it's wider than the button".to_string(),
+                                            ty: UIFieldType::Checkbox {
+                                                disabled: false
+                                            },
+                                            default_value: UIFieldValue::Checkbox(false)
+                                        },
+                                        UIField {
+                                            name: "marquee_speed".to_string(),
+                                            display_name: "Scroll Speed".to_string(),
+                                            description: "Pixels per second that text wider than the button scrolls at, 0 disables scrolling".to_string(),
+                                            ty: UIFieldType::InputFieldFloat,
+                                            default_value: UIFieldValue::InputFieldFloat(0.0)
+                                        },
                                         UIField {
                                             name: "shadow_enabled".to_string(),
                                             display_name: "Text Shadow".to_string(),
@@ -273,8 +400,8 @@ pub fn get_renderer_component_values(core: &CoreHandle, button: &Button) -> Vec<
                                             name: "padding".to_string(),
                                             display_name: "Padding".to_string(),
                                             description: "Gap to have from alignment/anchor point".to_string(),
-                                            ty: UIFieldType::InputFieldUnsignedInteger,
-                                            value: UIFieldValue::InputFieldUnsignedInteger(text.padding)
+                                            ty: UIFieldType::ValueSliderInteger(PADDING_SLIDER),
+                                            value: UIFieldValue::ValueSliderInteger(text.padding as i32)
                                         });
 
                                         values.push(UIValue {
@@ -293,6 +420,34 @@ pub fn get_renderer_component_values(core: &CoreHandle, button: &Button) -> Vec<
                                             value: text.color.into()
                                         });
 
+                                        values.push(UIValue {
+                                            name: "auto_fit".to_string(),
+                                            display_name: "Fit Text".to_string(),
+                                            description: "Shrinks text until it fits the button".to_string(),
+                                            ty: UIFieldType::Checkbox {
+                                                disabled: false
+                                            },
+                                            value: UIFieldValue::Checkbox(text.auto_fit)
+                                        });
+
+                                        values.push(UIValue {
+                                            name: "word_wrap".to_string(),
+                                            display_name: "Wrap Text".to_string(),
+                                            description: "Breaks text into multiple lines if it's wider than the button".to_string(),
+                                            ty: UIFieldType::Checkbox {
+                                                disabled: false
+                                            },
+                                            value: UIFieldValue::Checkbox(text.word_wrap)
+                                        });
+
+                                        values.push(UIValue {
+                                            name: "marquee_speed".to_string(),
+                                            display_name: "Scroll Speed".to_string(),
+                                            description: "Pixels per second that text wider than the button scrolls at, 0 disables scrolling".to_string(),
+                                            ty: UIFieldType::InputFieldFloat,
+                                            value: UIFieldValue::InputFieldFloat(text.marquee_speed)
+                                        });
+
                                         if let Some(shadow) = &text.shadow {
                                             values.push(
                                                 UIValue {
@@ -348,6 +503,59 @@ pub fn get_renderer_component_values(core: &CoreHandle, button: &Button) -> Vec<
                 }
             );
 
+            fields.push(
+                UIValue {
+                    name: "pressed_params".to_string(),
+                    display_name: "Pressed Parameters".to_string(),
+                    description: "Looks of the button while its key is held".to_string(),
+                    ty: UIFieldType::Collapsable,
+                    value: UIFieldValue::Collapsable({
+                        let mut fields = vec![];
+
+                        fields.push(
+                            UIValue {
+                                name: "pressed_style".to_string(),
+                                display_name: "Pressed Style".to_string(),
+                                description: "How button changes while its key is held".to_string(),
+                                ty: UIFieldType::Choice(vec!["None".to_string(), "Darken".to_string(), "Invert".to_string(), "Alternate".to_string()]),
+                                value: UIFieldValue::Choice(
+                                    match &component.pressed_style {
+                                        PressedStyle::None => "None",
+                                        PressedStyle::Darken => "Darken",
+                                        PressedStyle::Invert => "Invert",
+                                        PressedStyle::Alternate(..) => "Alternate",
+                                    }.to_string()
+                                )
+                            }
+                        );
+
+                        if let PressedStyle::Alternate(color, text) = &component.pressed_style {
+                            fields.push(
+                                UIValue {
+                                    name: "pressed_color".to_string(),
+                                    display_name: "Pressed Background Color".to_string(),
+                                    description: "Background color of the button while its key is held".to_string(),
+                                    ty: UIFieldType::Color,
+                                    value: color.into()
+                                }
+                            );
+
+                            fields.push(
+                                UIValue {
+                                    name: "pressed_text".to_string(),
+                                    display_name: "Pressed Text".to_string(),
+                                    description: "Text shown while key is held, text stays the same if empty".to_string(),
+                                    ty: UIFieldType::InputFieldString,
+                                    value: UIFieldValue::InputFieldString(text.clone())
+                                }
+                            );
+                        }
+
+                        fields
+                    })
+                }
+            );
+
             // Ignore plugin thread menu
             fields.push(
                 UIValue {
@@ -393,18 +601,33 @@ pub fn get_renderer_component_values(core: &CoreHandle, button: &Button) -> Vec<
     }
 }
 
-pub fn set_renderer_component_values(core: &CoreHandle, button: &mut Button, value: Vec<UIValue>) {
+/// Sets values of renderer component, unknown choices and references to images that aren't in the collection are rejected.
+/// Values that button already had are accepted, so other fields can still be changed if referenced image was removed
+pub fn set_renderer_component_values(core: &CoreHandle, button: &mut Button, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
     if let Ok(mut component) = parse_button_to_component::<RendererComponent>(button) {
         let change_map = map_ui_values(value);
+        let mut errors = vec![];
+
+        let previous_image = match &component.background {
+            ButtonBackground::ExistingImage(identifier) | ButtonBackground::ImageFill(identifier, _) => Some(identifier.clone()),
+            _ => None
+        };
+
+        // Images that can be referenced, unchanged references are accepted even if image is gone
+        let is_known_image = |identifier: &String| {
+            identifier.is_empty()
+                || previous_image.as_ref() == Some(identifier)
+                || core.core.image_collection.read().unwrap().contains_key(identifier)
+        };
 
         if let Some(value) = change_map.get("renderer") {
             if let Ok(value) = value.value.try_into_string() {
                 if value == "default" {
                     component.renderer = "".to_string();
-                } else {
-                    if let Some(_) = core.core.render_manager.read_renderers().get(&value) {
-                        component.renderer = value;
-                    }
+                } else if core.core.render_manager.read_renderers().get(&value).is_some() {
+                    component.renderer = value;
+                } else if value != component.renderer {
+                    errors.push(FieldError::new("renderer", "Unknown renderer"));
                 }
             }
         }
@@ -427,8 +650,11 @@ pub fn set_renderer_component_values(core: &CoreHandle, button: &mut Button, val
                                 "Vertical Gradient" => component.background = ButtonBackground::VerticalGradient((0, 0, 0, 255), (0, 0, 0, 255)),
                                 "Existing Image" => component.background = ButtonBackground::ExistingImage("".to_string()),
                                 "New Image" => component.background = ButtonBackground::NewImage("".to_string()),
+                                "Linear Gradient" => component.background = ButtonBackground::LinearGradient((0, 0, 0, 255), (0, 0, 0, 255), 0),
+                                "Radial Gradient" => component.background = ButtonBackground::RadialGradient((0, 0, 0, 255), (0, 0, 0, 255)),
+                                "Image Fill" => component.background = ButtonBackground::ImageFill("".to_string(), ImageFit::default()),
 
-                                _ => {}
+                                _ => errors.push(FieldError::new("background_params.background", "Unknown background type")),
                             }
                         }
                     }
@@ -443,29 +669,46 @@ pub fn set_renderer_component_values(core: &CoreHandle, button: &mut Button, val
                     }
 
                     if let Some(value) = change_map.get("start_color") {
-                        if let ButtonBackground::HorizontalGradient(_, end) = component.background {
-                            if let Ok(color) = (&value.value).try_into() {
-                                component.background = ButtonBackground::HorizontalGradient(color, end);
+                        if let Ok(color) = (&value.value).try_into() {
+                            match &mut component.background {
+                                ButtonBackground::HorizontalGradient(start, _) |
+                                ButtonBackground::VerticalGradient(start, _) |
+                                ButtonBackground::LinearGradient(start, _, _) |
+                                ButtonBackground::RadialGradient(start, _) => *start = color,
+
+                                _ => {}
                             }
                         }
+                    }
 
-                        if let ButtonBackground::VerticalGradient(_, end) = component.background {
-                            if let Ok(color) = (&value.value).try_into() {
-                                component.background = ButtonBackground::VerticalGradient(color, end);
+                    if let Some(value) = change_map.get("end_color") {
+                        if let Ok(color) = (&value.value).try_into() {
+                            match &mut component.background {
+                                ButtonBackground::HorizontalGradient(_, end) |
+                                ButtonBackground::VerticalGradient(_, end) |
+                                ButtonBackground::LinearGradient(_, end, _) |
+                                ButtonBackground::RadialGradient(_, end) => *end = color,
+
+                                _ => {}
                             }
                         }
                     }
 
-                    if let Some(value) = change_map.get("end_color") {
-                        if let ButtonBackground::HorizontalGradient(start, _) = component.background {
-                            if let Ok(color) = (&value.value).try_into() {
-                                component.background = ButtonBackground::HorizontalGradient(start, color);
+                    if let Some(value) = change_map.get("angle") {
+                        if let ButtonBackground::LinearGradient(_, _, angle) = &mut component.background {
+                            if let Ok(value) = value.value.try_into_i32() {
+                                *angle = value.rem_euclid(360);
                             }
                         }
+                    }
 
-                        if let ButtonBackground::VerticalGradient(start, _) = component.background {
-                            if let Ok(color) = (&value.value).try_into() {
-                                component.background = ButtonBackground::VerticalGradient(start, color);
+                    if let Some(value) = change_map.get("fit") {
+                        if let ButtonBackground::ImageFill(_, fit) = &mut component.background {
+                            if let Ok(value) = value.value.try_into_string() {
+                                match ImageFit::from_str(&value) {
+                                    Ok(value) => *fit = value,
+                                    Err(_) => errors.push(FieldError::new("background_params.fit", "Unknown image fit")),
+                                }
                             }
                         }
                     }
@@ -473,8 +716,23 @@ pub fn set_renderer_component_values(core: &CoreHandle, button: &mut Button, val
                     if let Some(value) = change_map.get("image") {
                         match &component.background {
                             ButtonBackground::ExistingImage(_) => {
-                                if let Ok(identifier) = (&value.value).try_into() {
-                                    component.background = ButtonBackground::ExistingImage(identifier);
+                                if let Ok(identifier) = (&value.value).try_into_string() {
+                                    // Only accepting references to images that are actually in the collection
+                                    if is_known_image(&identifier) {
+                                        component.background = ButtonBackground::ExistingImage(identifier);
+                                    } else {
+                                        errors.push(FieldError::new("background_params.image", "Image isn't in the image collection"));
+                                    }
+                                }
+                            }
+
+                            ButtonBackground::ImageFill(_, fit) => {
+                                if let Ok(identifier) = (&value.value).try_into_string() {
+                                    if is_known_image(&identifier) {
+                                        component.background = ButtonBackground::ImageFill(identifier, *fit);
+                                    } else {
+                                        errors.push(FieldError::new("background_params.image", "Image isn't in the image collection"));
+                                    }
                                 }
                             }
 
@@ -499,7 +757,7 @@ pub fn set_renderer_component_values(core: &CoreHandle, button: &mut Button, val
                                         component.background = ButtonBackground::ExistingImage(identifier.clone());
 
                                         let mut handle = core.core.image_collection.write().unwrap();
-                                        handle.insert(identifier, SDImage::SingleImage(resize_for_streamdeck(core.core.image_size, image)));
+                                        handle.insert(identifier, SDImage::SingleImage(core.core.config.image_conversion().apply(core.core.image_size, image)));
                                     } else {
                                         component.background = ButtonBackground::NewImage(blob);
                                     }
@@ -528,9 +786,12 @@ pub fn set_renderer_component_values(core: &CoreHandle, button: &mut Button, val
                                     font: (&map.get("font")?.value).try_into().ok()?,
                                     scale: (&map.get("scale")?.value).try_into().ok()?,
                                     alignment: TextAlignment::from_str(&map.get("alignment")?.value.try_into_string().ok()?).ok()?,
-                                    padding: (&map.get("padding")?.value).try_into().ok()?,
+                                    padding: padding_from_value(&map.get("padding")?.value)?,
                                     offset: (&map.get("offset")?.value).try_into_f32_f32().ok()?,
                                     color: (&map.get("color")?.value).try_into().ok()?,
+                                    auto_fit: map.get("auto_fit").and_then(|x| x.value.try_into_bool().ok()).unwrap_or(false),
+                                    word_wrap: map.get("word_wrap").and_then(|x| x.value.try_into_bool().ok()).unwrap_or(false),
+                                    marquee_speed: map.get("marquee_speed").and_then(|x| x.value.try_into_f32().ok()).unwrap_or(0.0).max(0.0),
                                     shadow: if let Some(bool) = map.get("shadow_enabled")?.value.try_into_bool().ok() {
                                         let get_shadow = || {
                                             Some(ButtonTextShadow {
@@ -563,6 +824,41 @@ pub fn set_renderer_component_values(core: &CoreHandle, button: &mut Button, val
                 }
             }
 
+            if let Some(value) = change_map.get("pressed_params") {
+                if let UIFieldValue::Collapsable(value) = &value.value {
+                    let change_map = map_ui_values(value.clone());
+
+                    if let Some(value) = change_map.get("pressed_style") {
+                        if let Ok(choice) = value.value.try_into_string() {
+                            match choice.as_str() {
+                                "None" => component.pressed_style = PressedStyle::None,
+                                "Darken" => component.pressed_style = PressedStyle::Darken,
+                                "Invert" => component.pressed_style = PressedStyle::Invert,
+                                "Alternate" => if !matches!(component.pressed_style, PressedStyle::Alternate(..)) {
+                                    component.pressed_style = PressedStyle::Alternate((0, 0, 0, 255), "".to_string());
+                                },
+
+                                _ => errors.push(FieldError::new("pressed_params.pressed_style", "Unknown pressed style")),
+                            }
+                        }
+                    }
+
+                    if let PressedStyle::Alternate(color, text) = &mut component.pressed_style {
+                        if let Some(value) = change_map.get("pressed_color") {
+                            if let Ok(value) = (&value.value).try_into() {
+                                *color = value;
+                            }
+                        }
+
+                        if let Some(value) = change_map.get("pressed_text") {
+                            if let Ok(value) = value.value.try_into_string() {
+                                *text = value;
+                            }
+                        }
+                    }
+                }
+            }
+
             if let Some(value) = change_map.get("plugin_blacklist") {
                 if let UIFieldValue::Collapsable(value) = &value.value {
                     let change_map = map_ui_values(value.clone());
@@ -586,9 +882,15 @@ pub fn set_renderer_component_values(core: &CoreHandle, button: &mut Button, val
             }
         }
 
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
         // Apply changes to button
         button.insert_component(component).ok();
 
         core.core.mark_for_redraw();
     }
+
+    Ok(())
 }
\ No newline at end of file
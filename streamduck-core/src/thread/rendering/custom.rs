@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::hash::Hasher;
 use std::sync::{Arc, RwLock, RwLockReadGuard};
 use image::DynamicImage;
 use streamdeck::{DeviceImage, StreamDeck};
@@ -56,10 +57,48 @@ pub trait CustomRenderer: Send + Sync {
 /// Reference counted renderer object
 pub type UniqueRenderer = Arc<Box<dyn CustomRenderer>>;
 
+/// Where draw layer is placed relative to text of the button
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LayerPosition {
+    /// Drawn on top of background, before text
+    BelowText,
+    /// Drawn on top of text
+    AboveText,
+}
+
+/// Additional draw layer for buttons that contain components of the layer
+#[allow(unused_variables)]
+pub trait RenderLayer: Send + Sync {
+    /// Name of the layer
+    fn name(&self) -> String;
+
+    /// Components that buttons should have for layer to be drawn on them
+    fn components(&self) -> Vec<String>;
+
+    /// Position of the layer relative to text
+    fn position(&self) -> LayerPosition { LayerPosition::BelowText }
+
+    /// Order of the layer among layers with same position, layers with higher order are drawn on top
+    fn z_order(&self) -> i32 { 0 }
+
+    /// Called when button is being drawn, frame already contains everything drawn below the layer
+    fn draw(&self, button: &UniqueButton, core_handle: &CoreHandle, frame: &mut DynamicImage);
+
+    /// Called to determine if button should be redrawn, should hash any state that the layer depends on
+    fn render_hash(&self, button: &UniqueButton, core_handle: &CoreHandle, hash: &mut Box<dyn Hasher>) {}
+
+    /// Frames per second that the layer needs to be redrawn at, None if layer only changes on events
+    fn refresh_rate(&self, button: &UniqueButton, core_handle: &CoreHandle) -> Option<f32> { None }
+}
+
+/// Reference counted draw layer object
+pub type UniqueRenderLayer = Arc<Box<dyn RenderLayer>>;
+
 /// Manager that keeps a bunch of related things to rendering thread
 #[derive(Default)]
 pub struct RenderingManager {
-    renderers: RwLock<HashMap<String, UniqueRenderer>>
+    renderers: RwLock<HashMap<String, UniqueRenderer>>,
+    layers: RwLock<Vec<UniqueRenderLayer>>,
 }
 
 impl RenderingManager {
@@ -83,4 +122,35 @@ impl RenderingManager {
     pub fn read_renderers(&self) -> RwLockReadGuard<HashMap<String, UniqueRenderer>> {
         self.renderers.read().unwrap()
     }
-}
\ No newline at end of file
+
+    /// Adds draw layer to the manager, layer with same name gets replaced
+    pub fn add_render_layer(&self, layer: UniqueRenderLayer) {
+        let mut lock = self.layers.write().unwrap();
+        let name = layer.name();
+
+        lock.retain(|x| x.name() != name);
+        lock.push(layer);
+        lock.sort_by_key(|x| (x.position(), x.z_order()));
+    }
+
+    /// Returns all draw layers managed by the manager, sorted in drawing order
+    pub fn get_render_layers(&self) -> Vec<UniqueRenderLayer> {
+        self.layers.read().unwrap().clone()
+    }
+
+    /// Returns draw layers that should be drawn on the button, sorted in drawing order
+    pub fn get_layers_for_button(&self, button: &UniqueButton) -> Vec<UniqueRenderLayer> {
+        let lock = self.layers.read().unwrap();
+
+        if lock.is_empty() {
+            return vec![];
+        }
+
+        let button = button.read().unwrap();
+
+        lock.iter()
+            .filter(|layer| layer.components().iter().any(|component| button.contains(component)))
+            .cloned()
+            .collect()
+    }
+}
@@ -6,13 +6,13 @@ pub mod component_values;
 use std::hash::{Hash, Hasher};
 use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
 use rusttype::Scale;
-use image::imageops::{FilterType, tile};
+use image::imageops::tile;
 use streamdeck::{DeviceImage, ImageMode, StreamDeck};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::collections::hash_map::DefaultHasher;
 use std::io::Cursor;
-use std::time::Instant;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use std::ops::Deref;
 use serde::{Serialize, Deserialize};
 use serde_json::Value;
@@ -20,10 +20,16 @@ use crate::core::button::Component;
 use crate::core::methods::CoreHandle;
 use crate::core::UniqueButton;
 use crate::font::get_font_from_collection;
-use crate::images::{AnimationFrame, SDImage};
+use crate::images::{AnimationFrame, ImageConversion, SDImage};
+use crate::metrics::record_write_latency;
+use crate::modules::carousel::carousel_looks;
+use crate::modules::conditions::{apply_disabled_looks, button_condition_state, ConditionState};
+use crate::modules::radio::{apply_radio_highlight, radio_highlight};
 use crate::modules::UniqueSDModule;
-use crate::thread::rendering::custom::DeviceReference;
-use crate::thread::util::{image_from_horiz_gradient, image_from_solid, image_from_vert_gradient, render_aligned_shadowed_text_on_image, render_aligned_text_on_image, TextAlignment};
+use crate::thread::rendering::custom::{DeviceReference, LayerPosition};
+use crate::thread::orientation::KeyMapping;
+use crate::thread::transition::PanelTransition;
+use crate::thread::util::{calculate_bounds_for_text, fit_image, image_from_horiz_gradient, image_from_linear_gradient, image_from_radial_gradient, image_from_solid, image_from_vert_gradient, ImageFit, layout_text, render_aligned_lines_on_image, render_marquee_text_on_image, TextAlignment};
 use crate::util::hash_value;
 
 /// Animation counter that counts frames for animated images
@@ -80,6 +86,13 @@ impl AnimationCounter {
     }
 }
 
+/// Writes image to a key of the device, recording how long the write took
+pub(crate) fn write_button_image(streamdeck: &mut StreamDeck, serial: &str, key: u8, image: &DeviceImage) {
+    let start = Instant::now();
+    streamdeck.write_button_image(key, image).ok();
+    record_write_latency(serial, start.elapsed());
+}
+
 /// Rendering code that's being called every loop
 pub fn process_frame(
     core: &CoreHandle,
@@ -88,26 +101,62 @@ pub fn process_frame(
     counters: &mut HashMap<String, AnimationCounter>,
     renderer_map: &mut HashMap<u8, (RendererComponent, UniqueButton, Vec<UniqueSDModule>)>,
     previous_state: &mut HashMap<u8, u64>,
-    missing: &DynamicImage
+    missing: &DynamicImage,
+    pressed: &HashSet<u8>,
+    mapping: KeyMapping
 ) {
 
+    let animations_enabled = !core.core.config.safe_mode();
+
     for key in 0..core.core.key_count {
-        if let Some((component, button, modules)) = renderer_map.get(&key) {
+        let device_key = mapping.device_key(core.core.kind, key);
+
+        let condition = renderer_map.get(&key)
+            .map(|(_, button, _)| button_condition_state(core, button))
+            .unwrap_or(ConditionState::Shown);
+
+        // Hidden buttons are drawn same as keys without a button
+        if let Some((component, button, modules)) = renderer_map.get(&key).filter(|_| condition != ConditionState::Hidden) {
+            let is_disabled = condition == ConditionState::Disabled;
+            let is_pressed = !is_disabled && component.pressed_style != PressedStyle::None && pressed.contains(&key);
+            let highlight = radio_highlight(button);
+
+            let carousel_component;
+            let component = if let Some(looks) = carousel_looks(button, component) {
+                carousel_component = looks;
+                &carousel_component
+            } else {
+                component
+            };
+
+            let pressed_component;
+            let component = if is_pressed {
+                pressed_component = component.pressed_looks();
+                &pressed_component
+            } else {
+                component
+            };
+
+            // Every position of scrolling text would end up in the cache
+            let to_cache = component.to_cache && !component.text.iter().any(|x| x.marquee_speed > 0.0);
+
             if !component.renderer.is_empty() {
                 // Custom renderer detected
                 let lock = core.core.render_manager.read_renderers();
 
                 if let Some(renderer) = lock.get(&component.renderer) {
                     // Stopping any further process if custom renderer is found
-                    renderer.render(key, button, core, &mut DeviceReference::new(streamdeck, key));
+                    renderer.render(key, button, core, &mut DeviceReference::new(streamdeck, device_key));
                     previous_state.insert(key, 1);
                     continue;
                 }
             }
 
 
-            if let ButtonBackground::ExistingImage(identifier) = &component.background {
-                let counter = if let Some(counter) = counters.get_mut(identifier) {
+            if let Some(identifier) = component.background.image_identifier() {
+                let counter = if !animations_enabled {
+                    None
+                } else if let Some(counter) = counters.get_mut(identifier) {
                     Some(counter)
                 } else {
                     if let Some(SDImage::AnimatedImage(frames)) = core.core.image_collection.read().unwrap().get(identifier).cloned() {
@@ -125,37 +174,56 @@ pub fn process_frame(
                     let mut hasher: Box<dyn Hasher> = Box::new(DefaultHasher::new());
 
                     component.hash(&mut hasher);
+                    is_pressed.hash(&mut hasher);
+                    is_disabled.hash(&mut hasher);
+                    highlight.hash(&mut hasher);
+                    marquee_positions(component).hash(&mut hasher);
                     frame.index.hash(&mut hasher);
 
                     for module in modules {
                         module.render_hash(core.clone_for(module), &button, &mut hasher);
                     }
 
+                    for layer in core.core.render_manager.get_layers_for_button(button) {
+                        layer.render_hash(button, core, &mut hasher);
+                    }
+
                     let hash = hasher.finish();
 
                     if counter.new_frame || (hash != *previous_state.get(&key).unwrap_or(&1)) {
                         let variant = cache.get(&hash);
 
-                        if component.to_cache && variant.is_some() {
+                        if to_cache && variant.is_some() {
                             let previous = previous_state.get(&key).unwrap_or(&1);
                             if hash != *previous {
-                                streamdeck.write_button_image(key, variant.unwrap().deref()).ok();
+                                write_button_image(streamdeck, &core.core.serial_number, device_key, variant.unwrap().deref());
                             }
                         } else {
                             let mut buffer = vec![];
 
-                            draw_foreground(&component, &button, modules,frame.image.clone(), core).rotate180().write_to(&mut Cursor::new(&mut buffer), match core.core.kind.image_mode() {
+                            let background = if let ButtonBackground::ImageFill(_, fit) = &component.background {
+                                fit_image(core.core.image_size, &frame.image, *fit)
+                            } else {
+                                frame.image.clone()
+                            };
+
+                            let image = draw_foreground(&component, &button, modules, background, core);
+                            let image = if is_pressed { component.pressed_style.apply(image) } else { image };
+                            let image = if let Some(color) = highlight { apply_radio_highlight(image, color) } else { image };
+                            let image = if is_disabled { apply_disabled_looks(image) } else { image };
+
+                            mapping.apply(image).rotate180().write_to(&mut Cursor::new(&mut buffer), match core.core.kind.image_mode() {
                                 ImageMode::Bmp => ImageFormat::Bmp,
                                 ImageMode::Jpeg => ImageFormat::Jpeg,
                             }).ok();
 
                             let arc = Arc::new(DeviceImage::from(buffer));
 
-                            if component.to_cache {
+                            if to_cache {
                                 cache.insert(hash, arc.clone());
                             }
 
-                            streamdeck.write_button_image(key, arc.deref()).ok();
+                            write_button_image(streamdeck, &core.core.serial_number, device_key, arc.deref());
                         }
 
                         previous_state.insert(key, hash);
@@ -170,34 +238,48 @@ pub fn process_frame(
             let mut hasher: Box<dyn Hasher> = Box::new(DefaultHasher::new());
 
             component.hash(&mut hasher);
+            is_pressed.hash(&mut hasher);
+            is_disabled.hash(&mut hasher);
+            highlight.hash(&mut hasher);
+            marquee_positions(component).hash(&mut hasher);
+
             for module in modules {
                 module.render_hash(core.clone_for(module), &button, &mut hasher);
             }
 
+            for layer in core.core.render_manager.get_layers_for_button(button) {
+                layer.render_hash(button, core, &mut hasher);
+            }
+
             let hash = hasher.finish();
 
             let variant = cache.get(&hash);
 
-            if component.to_cache && variant.is_some() {
+            if to_cache && variant.is_some() {
                 let previous = previous_state.get(&key).unwrap_or(&1);
                 if hash != *previous {
-                    streamdeck.write_button_image(key, variant.unwrap().deref()).ok();
+                    write_button_image(streamdeck, &core.core.serial_number, device_key, variant.unwrap().deref());
                 }
             } else {
                 let mut buffer = vec![];
 
-                draw_foreground(&component, &button, modules, draw_background(component, core, missing), core).rotate180().write_to(&mut Cursor::new(&mut buffer), match core.core.kind.image_mode() {
+                let image = draw_foreground(&component, &button, modules, draw_background(component, core, missing), core);
+                let image = if is_pressed { component.pressed_style.apply(image) } else { image };
+                let image = if let Some(color) = highlight { apply_radio_highlight(image, color) } else { image };
+                let image = if is_disabled { apply_disabled_looks(image) } else { image };
+
+                mapping.apply(image).rotate180().write_to(&mut Cursor::new(&mut buffer), match core.core.kind.image_mode() {
                     ImageMode::Bmp => ImageFormat::Bmp,
                     ImageMode::Jpeg => ImageFormat::Jpeg,
                 }).ok();
 
                 let arc = Arc::new(DeviceImage::from(buffer));
 
-                if component.to_cache {
+                if to_cache {
                     cache.insert(hash, arc.clone());
                 }
 
-                streamdeck.write_button_image(key, arc.deref()).ok();
+                write_button_image(streamdeck, &core.core.serial_number, device_key, arc.deref());
             }
 
             previous_state.insert(key, hash);
@@ -206,7 +288,7 @@ pub fn process_frame(
 
             if *previous != 0 {
                 previous_state.insert(key, 0);
-                streamdeck.set_button_rgb(key, &streamdeck::Colour {
+                streamdeck.set_button_rgb(device_key, &streamdeck::Colour {
                     r: 0,
                     g: 0,
                     b: 0
@@ -221,6 +303,51 @@ pub fn process_frame(
     };
 }
 
+/// Positions of scrolling texts of the button, so buttons get redrawn as their text scrolls
+fn marquee_positions(component: &RendererComponent) -> Vec<u64> {
+    component.text.iter().filter_map(|x| x.marquee_position()).collect()
+}
+
+/// Frame rate that device thread drops to when nothing on the screen changes with time
+pub const IDLE_FRAME_RATE: f32 = 4.0;
+
+/// Calculates frame rate that currently shown buttons need, animations and custom renderers need full rate of the device
+pub fn required_frame_rate(core: &CoreHandle, counters: &HashMap<String, AnimationCounter>, renderer_map: &HashMap<u8, (RendererComponent, UniqueButton, Vec<UniqueSDModule>)>) -> f32 {
+    let full_rate = core.core.pool_rate as f32;
+    let mut rate = IDLE_FRAME_RATE;
+
+    for (component, button, modules) in renderer_map.values() {
+        if !component.renderer.is_empty() {
+            return full_rate;
+        }
+
+        if let Some(identifier) = component.background.image_identifier() {
+            if counters.contains_key(identifier) {
+                return full_rate;
+            }
+        }
+
+        // Scrolling text moves a pixel every frame
+        for text in &component.text {
+            rate = rate.max(text.marquee_speed);
+        }
+
+        for module in modules {
+            if let Some(module_rate) = module.refresh_rate(core.clone_for(module), button) {
+                rate = rate.max(module_rate);
+            }
+        }
+
+        for layer in core.core.render_manager.get_layers_for_button(button) {
+            if let Some(layer_rate) = layer.refresh_rate(button, core) {
+                rate = rate.max(layer_rate);
+            }
+        }
+    }
+
+    rate.min(full_rate)
+}
+
 /// Draws background for static images
 pub fn draw_background(renderer: &RendererComponent, core: &CoreHandle, missing: &DynamicImage) -> DynamicImage {
     match &renderer.background {
@@ -236,16 +363,30 @@ pub fn draw_background(renderer: &RendererComponent, core: &CoreHandle, missing:
             image_from_vert_gradient(core.core.image_size, Rgba([start.0, start.1, start.2, 255]), Rgba([end.0, end.1, end.2, 255]))
         }
 
-        ButtonBackground::ExistingImage(identifier) => {
+        ButtonBackground::LinearGradient(start, end, angle) => {
+            image_from_linear_gradient(core.core.image_size, Rgba([start.0, start.1, start.2, 255]), Rgba([end.0, end.1, end.2, 255]), *angle as f32)
+        }
+
+        ButtonBackground::RadialGradient(center, edge) => {
+            image_from_radial_gradient(core.core.image_size, Rgba([center.0, center.1, center.2, 255]), Rgba([edge.0, edge.1, edge.2, 255]))
+        }
+
+        ButtonBackground::ImageFill(identifier, fit) => {
             if let Some(image) = core.core.image_collection.read().unwrap().get(identifier) {
                 match image {
-                    SDImage::SingleImage(image) => {
-                        image.resize_to_fill(core.core.image_size.0 as u32, core.core.image_size.1 as u32, FilterType::Triangle)
-                    }
+                    SDImage::SingleImage(image) => fit_image(core.core.image_size, image, *fit),
+                    SDImage::AnimatedImage(frames) => fit_image(core.core.image_size, &frames[0].image, *fit),
+                }
+            } else {
+                missing.clone()
+            }
+        }
 
-                    SDImage::AnimatedImage(frames) => {
-                        frames[0].image.clone().resize_to_fill(core.core.image_size.0 as u32, core.core.image_size.1 as u32, FilterType::Triangle)
-                    }
+        ButtonBackground::ExistingImage(identifier) => {
+            if let Some(image) = core.core.image_collection.read().unwrap().get(identifier) {
+                match image {
+                    SDImage::SingleImage(image) => fit_image(core.core.image_size, image, ImageFit::Cover),
+                    SDImage::AnimatedImage(frames) => fit_image(core.core.image_size, &frames[0].image, ImageFit::Cover),
                 }
             } else {
                 missing.clone()
@@ -269,6 +410,11 @@ pub fn draw_foreground(renderer: &RendererComponent, button: &UniqueButton, modu
         module.render(core.clone_for(module), button, &mut background);
     }
 
+    let layers = core.core.render_manager.get_layers_for_button(button);
+
+    for layer in layers.iter().filter(|x| x.position() == LayerPosition::BelowText) {
+        layer.draw(button, core, &mut background);
+    }
 
     for button_text in &renderer.text {
         let text = button_text.text.as_str();
@@ -279,36 +425,60 @@ pub fn draw_foreground(renderer: &RendererComponent, button: &UniqueButton, modu
         let color = button_text.color.clone();
 
         if let Some(font) = get_font_from_collection(&button_text.font) {
-            if let Some(shadow) = &button_text.shadow {
-                render_aligned_shadowed_text_on_image(
-                    core.core.image_size,
-                    &mut background,
-                    font.as_ref(),
-                    text,
-                    scale,
-                    align,
-                    padding,
-                    offset,
-                    color,
-                    shadow.offset.clone(),
-                    shadow.color.clone(),
-                )
-            } else {
-                render_aligned_text_on_image(
-                    core.core.image_size,
-                    &mut background,
-                    font.as_ref(),
-                    text,
-                    scale,
-                    align,
-                    padding,
-                    offset,
-                    color,
-                )
+            let marquee = button_text.marquee_position();
+
+            let (lines, scale) = layout_text(
+                core.core.image_size,
+                font.as_ref(),
+                text,
+                scale,
+                padding,
+                button_text.auto_fit && marquee.is_none(),
+                button_text.word_wrap && marquee.is_none()
+            );
+
+            // Scrolling only text that doesn't fit
+            if let (Some(position), [line]) = (marquee, lines.as_slice()) {
+                let width = (core.core.image_size.0 as u32).saturating_sub(padding * 2);
+
+                if calculate_bounds_for_text(font.as_ref(), line, scale).0 > width {
+                    render_marquee_text_on_image(
+                        core.core.image_size,
+                        &mut background,
+                        font.as_ref(),
+                        line,
+                        scale,
+                        align,
+                        padding,
+                        offset,
+                        color,
+                        button_text.shadow.as_ref().map(|x| (x.offset, x.color)),
+                        position
+                    );
+
+                    continue;
+                }
             }
+
+            render_aligned_lines_on_image(
+                core.core.image_size,
+                &mut background,
+                font.as_ref(),
+                &lines,
+                scale,
+                align,
+                padding,
+                offset,
+                color,
+                button_text.shadow.as_ref().map(|x| (x.offset, x.color))
+            )
         }
     }
 
+    for layer in layers.iter().filter(|x| x.position() == LayerPosition::AboveText) {
+        layer.draw(button, core, &mut background);
+    }
+
     background
 }
 
@@ -379,6 +549,36 @@ pub fn draw_missing_texture(size: (usize, usize)) -> DynamicImage {
 /// Definition for color format
 pub type Color = (u8, u8, u8, u8);
 
+/// Parses color from `#RRGGBB`, `#RRGGBBAA` or `r,g,b[,a]`, alpha defaults to 255
+pub fn parse_color(str: &str) -> Result<Color, String> {
+    let str = str.trim();
+
+    let components: Vec<u8> = if let Some(hex) = str.strip_prefix('#') {
+        if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+            return Err(format!("Hex color should have 6 or 8 digits, got '{}'", str));
+        }
+
+        (0..hex.len()).step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| format!("Invalid hex color '{}'", str)))
+            .collect::<Result<_, _>>()?
+    } else {
+        str.split(',')
+            .map(|x| x.trim().parse::<u8>().map_err(|_| format!("Color components should be numbers from 0 to 255, got '{}'", x.trim())))
+            .collect::<Result<_, _>>()?
+    };
+
+    match components[..] {
+        [r, g, b] => Ok((r, g, b, 255)),
+        [r, g, b, a] => Ok((r, g, b, a)),
+        _ => Err(format!("Color should have 3 or 4 components, got '{}'", str)),
+    }
+}
+
+/// Formats color as `#RRGGBBAA`
+pub fn color_to_hex(color: &Color) -> String {
+    format!("#{:02x}{:02x}{:02x}{:02x}", color.0, color.1, color.2, color.3)
+}
+
 /// Button Background definition for button renderer
 #[derive(Serialize, Deserialize, Debug, Clone, Hash)]
 pub enum ButtonBackground {
@@ -387,6 +587,22 @@ pub enum ButtonBackground {
     VerticalGradient(Color, Color),
     ExistingImage(String),
     NewImage(String),
+    /// Gradient between two colors going at angle in degrees, 0 goes from left to right, 90 from top to bottom
+    LinearGradient(Color, Color, i32),
+    /// Gradient from center color to edge color
+    RadialGradient(Color, Color),
+    /// Image from image collection fitted into the button in specified way
+    ImageFill(String, ImageFit),
+}
+
+impl ButtonBackground {
+    /// Identifier of image from image collection that background uses, if any
+    pub fn image_identifier(&self) -> Option<&String> {
+        match self {
+            ButtonBackground::ExistingImage(identifier) | ButtonBackground::ImageFill(identifier, _) => Some(identifier),
+            _ => None
+        }
+    }
 }
 
 impl Default for ButtonBackground {
@@ -406,6 +622,28 @@ pub struct ButtonText {
     pub offset: (f32, f32),
     pub color: Color,
     pub shadow: Option<ButtonTextShadow>,
+    /// Shrinks text until it fits the button
+    #[serde(default)]
+    pub auto_fit: bool,
+    /// Breaks text into lines at spaces if it's wider than the button
+    #[serde(default)]
+    pub word_wrap: bool,
+    /// Pixels per second that text scrolls at if it's wider than the button, 0 disables scrolling.
+    /// Scrolling text is kept on a single line and isn't shrunk to fit
+    #[serde(default)]
+    pub marquee_speed: f32,
+}
+
+impl ButtonText {
+    /// Distance in pixels that scrolling text has travelled, [None] if text doesn't scroll
+    pub fn marquee_position(&self) -> Option<u64> {
+        if self.marquee_speed > 0.0 {
+            let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+            Some((time * self.marquee_speed as f64) as u64)
+        } else {
+            None
+        }
+    }
 }
 
 impl Hash for ButtonText {
@@ -420,6 +658,9 @@ impl Hash for ButtonText {
         ((self.offset.1 * 100.0) as i32).hash(state);
         self.color.hash(state);
         self.shadow.hash(state);
+        self.auto_fit.hash(state);
+        self.word_wrap.hash(state);
+        ((self.marquee_speed * 100.0) as i32).hash(state);
     }
 }
 
@@ -430,6 +671,39 @@ pub struct ButtonTextShadow {
     pub color: Color,
 }
 
+/// Looks of the button while its key is held
+#[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq)]
+pub enum PressedStyle {
+    /// Button looks the same
+    None,
+    /// Button gets darker
+    Darken,
+    /// Colors of the button get inverted
+    Invert,
+    /// Button gets solid background of provided color, and text replaced with provided text if it's not empty
+    Alternate(Color, String),
+}
+
+impl Default for PressedStyle {
+    fn default() -> Self {
+        PressedStyle::None
+    }
+}
+
+impl PressedStyle {
+    /// Applies darken or invert effect to already drawn button
+    pub fn apply(&self, mut image: DynamicImage) -> DynamicImage {
+        match self {
+            PressedStyle::Darken => image.brighten(-80),
+            PressedStyle::Invert => {
+                image.invert();
+                image
+            }
+            _ => image
+        }
+    }
+}
+
 /// Renderer component that contains button background and array of text structs
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct RendererComponent {
@@ -447,6 +721,9 @@ pub struct RendererComponent {
     /// Anything that custom renderers might want to remember
     #[serde(default)]
     pub custom_data: Value,
+    /// Looks of the button while its key is held
+    #[serde(default)]
+    pub pressed_style: PressedStyle,
 }
 
 fn make_true() -> bool { true }
@@ -459,11 +736,31 @@ impl Default for RendererComponent {
             text: vec![],
             plugin_blacklist: vec![],
             to_cache: true,
-            custom_data: Default::default()
+            custom_data: Default::default(),
+            pressed_style: Default::default()
         }
     }
 }
 
+impl RendererComponent {
+    /// Component with alternate looks of pressed style applied
+    pub fn pressed_looks(&self) -> RendererComponent {
+        let mut component = self.clone();
+
+        if let PressedStyle::Alternate(color, text) = &self.pressed_style {
+            component.background = ButtonBackground::Solid(*color);
+
+            if !text.is_empty() {
+                for button_text in &mut component.text {
+                    button_text.text = text.clone();
+                }
+            }
+        }
+
+        component
+    }
+}
+
 impl Hash for RendererComponent {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.renderer.hash(state);
@@ -472,6 +769,7 @@ impl Hash for RendererComponent {
         self.to_cache.hash(state);
         self.background.hash(state);
         hash_value(&self.custom_data, state);
+        self.pressed_style.hash(state);
     }
 }
 
@@ -516,6 +814,11 @@ impl RendererComponentBuilder {
         self.component.to_cache = cache; self
     }
 
+    /// Sets looks of the button while its key is held
+    pub fn pressed_style(mut self, style: PressedStyle) -> Self {
+        self.component.pressed_style = style; self
+    }
+
     /// Builds the component
     pub fn build(self) -> RendererComponent {
         self.into()
@@ -532,7 +835,13 @@ impl From<RendererComponentBuilder> for RendererComponent {
 #[derive(Serialize, Deserialize, Default)]
 pub struct RendererSettings {
     /// Blacklist of plugins that aren't allowed to render
-    pub plugin_blacklist: Vec<String>
+    pub plugin_blacklist: Vec<String>,
+    /// Transition played when panels are pushed or popped, devices can override it
+    #[serde(default)]
+    pub transition: PanelTransition,
+    /// How images are converted when they're added to image collection
+    #[serde(default)]
+    pub image_conversion: ImageConversion,
 }
 
 #[allow(dead_code)]
@@ -0,0 +1,173 @@
+//! Transition animations that are played when panels are pushed or popped
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::time::Instant;
+use image::{DynamicImage, GenericImageView, ImageFormat, Rgba, RgbaImage};
+use image::imageops::overlay;
+use serde::{Deserialize, Serialize};
+use streamdeck::{DeviceImage, ImageMode, StreamDeck};
+use strum_macros::{Display, EnumString, EnumVariantNames};
+use crate::thread::orientation::{KeyMapping, visible_columns};
+use crate::core::methods::CoreHandle;
+use crate::modules::core_module::CoreSettings;
+
+/// Duration of transition animations in seconds
+pub const TRANSITION_DURATION: f32 = 0.15;
+
+/// Animation that is played when panel changes
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, Deserialize, EnumVariantNames, EnumString, Display)]
+#[strum(serialize_all = "title_case")]
+pub enum PanelTransition {
+    /// Screen changes instantly
+    None,
+    /// New panel slides in, pushing old panel out of the screen
+    Slide,
+    /// Old panel fades into the new one
+    Fade,
+}
+
+impl Default for PanelTransition {
+    fn default() -> Self {
+        PanelTransition::None
+    }
+}
+
+/// Direction of the transition, slide goes left for pushed panels and right for popped panels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionDirection {
+    /// Panel was pushed into the stack
+    Forward,
+    /// Panel was popped from the stack
+    Backward,
+}
+
+/// Gets transition that should be used for the device, device setting takes priority over global setting
+pub fn get_panel_transition(core: &CoreHandle) -> PanelTransition {
+    if let Some(transition) = core.core.device_config.read().unwrap().transition {
+        return transition;
+    }
+
+    let settings: CoreSettings = core.core.config.get_plugin_settings().unwrap_or_default();
+    settings.renderer.transition
+}
+
+/// Transition that is currently being played on device thread
+pub(crate) struct TransitionAnimation {
+    transition: PanelTransition,
+    direction: TransitionDirection,
+    from: HashMap<u8, DynamicImage>,
+    to: HashMap<u8, DynamicImage>,
+    columns: u8,
+    image_size: (usize, usize),
+    start: Instant,
+}
+
+impl TransitionAnimation {
+    pub(crate) fn new(core: &CoreHandle, transition: PanelTransition, direction: TransitionDirection, from: HashMap<u8, DynamicImage>, to: HashMap<u8, DynamicImage>) -> Self {
+        Self {
+            transition,
+            direction,
+            from,
+            to,
+            columns: visible_columns(&core.core),
+            image_size: core.core.image_size,
+            start: Instant::now(),
+        }
+    }
+
+    /// Progress of the animation from 0.0 to 1.0
+    fn progress(&self) -> f32 {
+        (self.start.elapsed().as_secs_f32() / TRANSITION_DURATION).min(1.0)
+    }
+
+    /// Checks if animation has played out
+    pub(crate) fn is_finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    /// Writes current frame of the animation to every key of the device
+    pub(crate) fn render(&self, streamdeck: &mut StreamDeck, key_count: u8, mapping: KeyMapping) {
+        // Smoothstep, so animation starts and ends softly
+        let progress = self.progress();
+        let progress = progress * progress * (3.0 - 2.0 * progress);
+
+        for key in 0..key_count {
+            let frame = match self.transition {
+                PanelTransition::None => return,
+                PanelTransition::Slide => self.slide_frame(key, progress),
+                PanelTransition::Fade => self.fade_frame(key, progress),
+            };
+
+            let mut buffer = vec![];
+
+            mapping.apply(frame).rotate180().write_to(&mut Cursor::new(&mut buffer), match streamdeck.kind().image_mode() {
+                ImageMode::Bmp => ImageFormat::Bmp,
+                ImageMode::Jpeg => ImageFormat::Jpeg,
+            }).ok();
+
+            streamdeck.write_button_image(mapping.device_key(streamdeck.kind(), key), &DeviceImage::from(buffer)).ok();
+        }
+    }
+
+    fn blank(&self) -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_pixel(self.image_size.0 as u32, self.image_size.1 as u32, Rgba([0, 0, 0, 255])))
+    }
+
+    /// Blends old and new image of the key
+    fn fade_frame(&self, key: u8, progress: f32) -> DynamicImage {
+        let blank = self.blank();
+        let from = self.from.get(&key).unwrap_or(&blank);
+        let to = self.to.get(&key).unwrap_or(&blank);
+
+        let mut frame = RgbaImage::new(self.image_size.0 as u32, self.image_size.1 as u32);
+
+        for (x, y, pixel) in frame.enumerate_pixels_mut() {
+            let a = if from.in_bounds(x, y) { from.get_pixel(x, y) } else { Rgba([0, 0, 0, 255]) };
+            let b = if to.in_bounds(x, y) { to.get_pixel(x, y) } else { Rgba([0, 0, 0, 255]) };
+
+            let channel = |i: usize| (a.0[i] as f32 + (b.0[i] as f32 - a.0[i] as f32) * progress).round() as u8;
+            *pixel = Rgba([channel(0), channel(1), channel(2), 255]);
+        }
+
+        DynamicImage::ImageRgba8(frame)
+    }
+
+    /// Cuts out part of the key from strip of both screens placed next to each other, strip moves as animation progresses
+    fn slide_frame(&self, key: u8, progress: f32) -> DynamicImage {
+        let width = self.image_size.0 as i64;
+        let columns = self.columns.max(1);
+        let screen_width = columns as i64 * width;
+
+        let row = key / columns;
+        let column = key % columns;
+
+        // Forward places new screen to the right of the old one, backward places it to the left
+        let (left, right, offset) = match self.direction {
+            TransitionDirection::Forward => (&self.from, &self.to, progress),
+            TransitionDirection::Backward => (&self.to, &self.from, 1.0 - progress),
+        };
+
+        let start = column as i64 * width + (offset * screen_width as f32).round() as i64;
+        let mut frame = self.blank();
+
+        let first = start / width;
+        for strip_column in first..=(first + 1) {
+            if strip_column >= columns as i64 * 2 {
+                break;
+            }
+
+            let (screen, screen_column) = if strip_column < columns as i64 {
+                (left, strip_column)
+            } else {
+                (right, strip_column - columns as i64)
+            };
+
+            if let Some(image) = screen.get(&(row * columns + screen_column as u8)) {
+                overlay(&mut frame, image, strip_column * width - start, 0);
+            }
+        }
+
+        frame
+    }
+}
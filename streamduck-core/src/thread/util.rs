@@ -1,10 +1,11 @@
 use serde::{Serialize, Deserialize};
 use strum_macros::{EnumVariantNames, EnumString, Display};
 use std::path::Path;
-use image::{DynamicImage, GenericImage, GenericImageView, Rgba};
-use image::imageops::{FilterType, horizontal_gradient, vertical_gradient};
+use image::{DynamicImage, GenericImage, GenericImageView, Rgb, Rgba, RgbaImage, RgbImage};
+use image::imageops::{FilterType, horizontal_gradient, overlay, vertical_gradient};
 use image::io::Reader;
-use rusttype::{Font, Point, point, Scale};
+use rusttype::{Font, Point, point, PositionedGlyph, Scale};
+use crate::font::emoji::{get_emoji_font, is_emoji_modifier};
 
 /// Resizes image to specified size
 pub fn resize_for_streamdeck(size: (usize, usize), image: DynamicImage) -> DynamicImage {
@@ -42,6 +43,121 @@ pub fn image_from_vert_gradient(size: (usize, usize), start: Rgba<u8>, end: Rgba
     image
 }
 
+/// Generates gradient image of specified size going along provided angle in degrees, 0 goes from left to right, 90 from top to bottom
+pub fn image_from_linear_gradient(size: (usize, usize), start: Rgba<u8>, end: Rgba<u8>, angle: f32) -> DynamicImage {
+    let (sx, sy) = size;
+    let (sin, cos) = angle.to_radians().sin_cos();
+
+    // Distance from center to the furthest corner along gradient direction
+    let extent = ((sx as f32 / 2.0) * cos).abs() + ((sy as f32 / 2.0) * sin).abs();
+
+    let image = RgbaImage::from_fn(sx as u32, sy as u32, |x, y| {
+        let projection = (x as f32 + 0.5 - sx as f32 / 2.0) * cos + (y as f32 + 0.5 - sy as f32 / 2.0) * sin;
+        lerp_color(start, end, (projection / extent.max(1.0) + 1.0) / 2.0)
+    });
+
+    DynamicImage::ImageRgba8(image)
+}
+
+/// Generates radial gradient image of specified size, going from center to corners
+pub fn image_from_radial_gradient(size: (usize, usize), center: Rgba<u8>, edge: Rgba<u8>) -> DynamicImage {
+    let (sx, sy) = size;
+    let (cx, cy) = (sx as f32 / 2.0, sy as f32 / 2.0);
+    let radius = (cx * cx + cy * cy).sqrt();
+
+    let image = RgbaImage::from_fn(sx as u32, sy as u32, |x, y| {
+        let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+        lerp_color(center, edge, (dx * dx + dy * dy).sqrt() / radius.max(1.0))
+    });
+
+    DynamicImage::ImageRgba8(image)
+}
+
+/// Interpolates between two colors, amount is clamped between 0 and 1
+fn lerp_color(start: Rgba<u8>, end: Rgba<u8>, amount: f32) -> Rgba<u8> {
+    let amount = amount.clamp(0.0, 1.0);
+    let channel = |i: usize| (start.0[i] as f32 + (end.0[i] as f32 - start.0[i] as f32) * amount).round() as u8;
+
+    Rgba([channel(0), channel(1), channel(2), channel(3)])
+}
+
+/// How image is fitted into a button
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Serialize, Deserialize, EnumVariantNames, EnumString, Display)]
+#[strum(serialize_all = "title_case")]
+pub enum ImageFit {
+    /// Fills whole button, cutting off parts of image that don't fit
+    Cover,
+    /// Fits whole image into the button, leaving black bars
+    Contain,
+    /// Stretches image to size of the button
+    Stretch,
+    /// Repeats image in its original size
+    Tile,
+}
+
+impl Default for ImageFit {
+    fn default() -> Self {
+        ImageFit::Cover
+    }
+}
+
+/// Fits image into specified size
+pub fn fit_image(size: (usize, usize), image: &DynamicImage, fit: ImageFit) -> DynamicImage {
+    let (sx, sy) = (size.0 as u32, size.1 as u32);
+
+    // Images that were already prepared for the device don't need to be resized again
+    if fit != ImageFit::Tile && image.dimensions() == (sx, sy) {
+        return image.clone();
+    }
+
+    match fit {
+        ImageFit::Cover => image.resize_to_fill(sx, sy, FilterType::Triangle),
+
+        ImageFit::Stretch => image.resize_exact(sx, sy, FilterType::Triangle),
+
+        ImageFit::Contain => {
+            let resized = image.resize(sx, sy, FilterType::Triangle);
+            let mut result = image_from_solid(size, Rgba([0, 0, 0, 255]));
+
+            overlay(&mut result, &resized, ((sx - resized.width()) / 2) as i64, ((sy - resized.height()) / 2) as i64);
+
+            result
+        }
+
+        ImageFit::Tile => {
+            let (width, height) = (image.width().max(1), image.height().max(1));
+            let mut result = image_from_solid(size, Rgba([0, 0, 0, 255]));
+
+            for x in (0..sx).step_by(width as usize) {
+                for y in (0..sy).step_by(height as usize) {
+                    overlay(&mut result, image, x as i64, y as i64);
+                }
+            }
+
+            result
+        }
+    }
+}
+
+/// Removes transparency from image by placing it on black background
+pub fn strip_alpha(image: &DynamicImage) -> DynamicImage {
+    if !image.color().has_alpha() {
+        return image.clone();
+    }
+
+    let source = image.to_rgba8();
+    let mut result = RgbImage::new(source.width(), source.height());
+
+    for (x, y, pixel) in source.enumerate_pixels() {
+        let alpha = pixel.0[3] as u16;
+        let channel = |i: usize| (pixel.0[i] as u16 * alpha / 255) as u8;
+
+        result.put_pixel(x, y, Rgb([channel(0), channel(1), channel(2)]));
+    }
+
+    DynamicImage::ImageRgb8(result)
+}
+
 /// Loads image from provided path and resizes it to specified size
 pub fn load_image<P: AsRef<Path>>(size: (usize, usize), path: P) -> Option<DynamicImage> {
     if let Ok(image) = Reader::open(path) {
@@ -55,69 +171,158 @@ pub fn load_image<P: AsRef<Path>>(size: (usize, usize), path: P) -> Option<Dynam
     }
 }
 
+/// Glyph of laid out text
+enum LaidOutGlyph<'a> {
+    /// Glyph drawn from font outline
+    Outline(PositionedGlyph<'a>),
+    /// Color bitmap from emoji font, with position of its top left corner
+    Bitmap(RgbaImage, i32, i32),
+}
+
+/// Lays out text on a line starting at provided point, characters that font doesn't have are taken from emoji font
+fn layout_glyphs<'a>(font: &Font<'a>, text: &str, scale: Scale, start: Point<f32>) -> Vec<LaidOutGlyph<'a>> {
+    let emoji_font = get_emoji_font();
+    let metrics = font.v_metrics(scale);
+
+    let mut glyphs = vec![];
+    let mut caret = start.x;
+    let mut last_glyph = None;
+
+    for character in text.chars() {
+        let glyph = font.glyph(character);
+
+        if glyph.id().0 == 0 {
+            if is_emoji_modifier(character) {
+                continue;
+            }
+
+            if let Some(emoji_font) = &emoji_font {
+                let height = (metrics.ascent - metrics.descent).round().max(1.0) as u32;
+
+                if let Some(bitmap) = emoji_font.bitmap(character, height) {
+                    let width = bitmap.width();
+                    glyphs.push(LaidOutGlyph::Bitmap(bitmap, caret.round() as i32, (start.y - metrics.ascent).round() as i32));
+
+                    caret += width as f32;
+                    last_glyph = None;
+                    continue;
+                }
+
+                if let Some(outline) = emoji_font.outline() {
+                    let glyph = outline.glyph(character);
+
+                    if glyph.id().0 != 0 {
+                        let glyph = glyph.scaled(scale);
+                        let advance = glyph.h_metrics().advance_width;
+
+                        glyphs.push(LaidOutGlyph::Outline(glyph.positioned(point(caret, start.y))));
+
+                        caret += advance;
+                        last_glyph = None;
+                        continue;
+                    }
+                }
+            }
+        }
+
+        let glyph = glyph.scaled(scale);
+
+        if let Some(last_glyph) = last_glyph {
+            caret += font.pair_kerning(scale, last_glyph, glyph.id());
+        }
+
+        let advance = glyph.h_metrics().advance_width;
+        last_glyph = Some(glyph.id());
+
+        glyphs.push(LaidOutGlyph::Outline(glyph.positioned(point(caret, start.y))));
+        caret += advance;
+    }
+
+    glyphs
+}
+
+/// Blends pixel of provided color with coverage onto image
+fn blend_pixel(image: &mut DynamicImage, x: i32, y: i32, color: (u8, u8, u8, u8), coverage: f32) {
+    let (size_x, size_y) = image.dimensions();
+
+    if x < 0 || y < 0 || (x as u32) >= size_x || (y as u32) >= size_y {
+        return;
+    }
+
+    let (x, y) = (x as u32, y as u32);
+    let pixel = image.get_pixel(x, y);
+    let color_mul = (coverage * (color.3 as f32 / 255.0)).clamp(0.0, 1.0);
+
+    image.put_pixel(
+        x,
+        y,
+        // Turn the coverage into an alpha value
+        Rgba([(pixel.0[0] as f32 * (1.0 - color_mul) + color.0 as f32 * color_mul) as u8, (pixel.0[1] as f32 * (1.0 - color_mul) + color.1 as f32 * color_mul) as u8, (pixel.0[2] as f32 * (1.0 - color_mul) + color.2 as f32 * color_mul) as u8, 255])
+    )
+}
+
+/// Draws color bitmap onto image, alpha of text color is applied to the bitmap
+fn draw_bitmap(image: &mut DynamicImage, bitmap: &RgbaImage, left: i32, top: i32, alpha: u8) {
+    for (x, y, pixel) in bitmap.enumerate_pixels() {
+        let coverage = pixel.0[3] as f32 / 255.0;
+
+        if coverage > 0.0 {
+            blend_pixel(image, left + x as i32, top + y as i32, (pixel.0[0], pixel.0[1], pixel.0[2], alpha), coverage);
+        }
+    }
+}
+
+/// Draws bitmap as a silhouette of provided color onto image
+fn draw_bitmap_silhouette(image: &mut DynamicImage, bitmap: &RgbaImage, left: i32, top: i32, color: (u8, u8, u8, u8)) {
+    for (x, y, pixel) in bitmap.enumerate_pixels() {
+        let coverage = pixel.0[3] as f32 / 255.0;
+
+        if coverage > 0.01 {
+            blend_pixel(image, left + x as i32, top + y as i32, color, coverage);
+        }
+    }
+}
+
 /// Renders text from font and parameters onto provided image
 pub fn render_text_on_image(image: &mut DynamicImage, font: &Font, text: &str, scale: Scale, point: Point<f32>, color: (u8, u8, u8, u8)) {
-    let (size_x, size_y) = image.dimensions();
-    for glyph in font.layout(text, scale, point) {
-        if let Some(bounding_box) = glyph.pixel_bounding_box() {
-            glyph.draw(|x, y, v| {
-                let bound_x = (x as i32 + bounding_box.min.x) as u32;
-                let bound_y = (y as i32 + bounding_box.min.y) as u32;
-
-                if (bound_x < size_x) && (bound_y < size_y) {
-                    let pixel = image.get_pixel(bound_x, bound_y);
-                    let color_mul = (v * (color.3 as f32 / 255.0)).clamp(0.0, 1.0);
-
-                    image.put_pixel(
-                        bound_x,
-                        bound_y,
-                        // Turn the coverage into an alpha value
-                        Rgba([(pixel.0[0] as f32 * (1.0 - color_mul) + color.0 as f32 * color_mul) as u8, (pixel.0[1] as f32 * (1.0 - color_mul) + color.1 as f32 * color_mul) as u8, (pixel.0[2] as f32 * (1.0 - color_mul) + color.2 as f32 * color_mul) as u8, 255])
-                    )
+    for glyph in layout_glyphs(font, text, scale, point) {
+        match glyph {
+            LaidOutGlyph::Outline(glyph) => {
+                if let Some(bounding_box) = glyph.pixel_bounding_box() {
+                    glyph.draw(|x, y, v| {
+                        blend_pixel(image, x as i32 + bounding_box.min.x, y as i32 + bounding_box.min.y, color, v);
+                    })
                 }
-            })
+            }
+
+            LaidOutGlyph::Bitmap(bitmap, left, top) => draw_bitmap(image, &bitmap, left, top, color.3)
         }
     }
 }
 
 /// Renders text with shadows from font and parameters onto provided image
 pub fn render_shadowed_text_on_image(image: &mut DynamicImage, font: &Font, text: &str, scale: Scale, point: Point<f32>, color: (u8, u8, u8, u8), shadow_offset: (i32, i32), shadow_color: (u8, u8, u8, u8)) {
-    let (size_x, size_y) = image.dimensions();
-    for glyph in font.layout(text, scale, point) {
-        if let Some(bounding_box) = glyph.pixel_bounding_box() {
-            glyph.draw(|x, y, v| {
-                let bound_x = (x as i32 + bounding_box.min.x) as u32;
-                let bound_y = (y as i32 + bounding_box.min.y) as u32;
-
-                if (bound_x < size_x) && (bound_y < size_y) {
-                    let pixel = image.get_pixel(bound_x, bound_y);
-                    let color_mul = (v * (color.3 as f32 / 255.0)).clamp(0.0, 1.0);
-
-                    image.put_pixel(
-                        bound_x,
-                        bound_y,
-                        // Turn the coverage into an alpha value
-                        Rgba([(pixel.0[0] as f32 * (1.0 - color_mul) + color.0 as f32 * color_mul) as u8, (pixel.0[1] as f32 * (1.0 - color_mul) + color.1 as f32 * color_mul) as u8, (pixel.0[2] as f32 * (1.0 - color_mul) + color.2 as f32 * color_mul) as u8, 255])
-                    );
-
-                    let shadow_x = (bound_x as i32 + shadow_offset.0) as u32;
-                    let shadow_y = (bound_y as i32 + shadow_offset.1) as u32;
-
-                    if (shadow_x < size_x) && (shadow_y < size_y) {
-                        let pixel = image.get_pixel(shadow_x, shadow_y);
-                        let shadow_mul = (v * (shadow_color.3 as f32 / 255.0)).clamp(0.0, 1.0);
-
-                        if shadow_mul > 0.01 {
-                            image.put_pixel(
-                                shadow_x,
-                                shadow_y,
-                                // Turn the coverage into an alpha value
-                                Rgba([(pixel.0[0] as f32 * (1.0 - shadow_mul) + shadow_color.0 as f32 * shadow_mul) as u8, (pixel.0[1] as f32 * (1.0 - shadow_mul) + shadow_color.1 as f32 * shadow_mul) as u8, (pixel.0[2] as f32 * (1.0 - shadow_mul) + shadow_color.2 as f32 * shadow_mul) as u8, 255])
-                            );
+    for glyph in layout_glyphs(font, text, scale, point) {
+        match glyph {
+            LaidOutGlyph::Outline(glyph) => {
+                if let Some(bounding_box) = glyph.pixel_bounding_box() {
+                    glyph.draw(|x, y, v| {
+                        let bound_x = x as i32 + bounding_box.min.x;
+                        let bound_y = y as i32 + bounding_box.min.y;
+
+                        blend_pixel(image, bound_x, bound_y, color, v);
+
+                        if v * (shadow_color.3 as f32 / 255.0) > 0.01 {
+                            blend_pixel(image, bound_x + shadow_offset.0, bound_y + shadow_offset.1, shadow_color, v);
                         }
-                    }
+                    })
                 }
-            })
+            }
+
+            LaidOutGlyph::Bitmap(bitmap, left, top) => {
+                draw_bitmap_silhouette(image, &bitmap, left + shadow_offset.0, top + shadow_offset.1, shadow_color);
+                draw_bitmap(image, &bitmap, left, top, color.3);
+            }
         }
     }
 }
@@ -141,15 +346,99 @@ pub fn render_box_on_image(image: &mut DynamicImage, scale: Scale, point: Point<
     }
 }
 
+/// Renders filled rectangle onto provided image, alpha of the color is respected
+pub fn render_filled_rect_on_image(image: &mut DynamicImage, position: (i32, i32), size: (u32, u32), color: (u8, u8, u8, u8)) {
+    for x in 0..size.0 as i32 {
+        for y in 0..size.1 as i32 {
+            blend_pixel(image, position.0 + x, position.1 + y, color, 1.0);
+        }
+    }
+}
+
+/// Renders anti-aliased filled circle onto provided image
+pub fn render_circle_on_image(image: &mut DynamicImage, center: (f32, f32), radius: f32, color: (u8, u8, u8, u8)) {
+    render_arc_on_image(image, center, radius, radius, 0.0, 360.0, color);
+}
+
+/// Renders anti-aliased arc of a ring onto provided image.
+/// Angles are in degrees, 0 points up and angles go clockwise, ring is drawn inwards from radius with provided thickness
+pub fn render_arc_on_image(image: &mut DynamicImage, center: (f32, f32), radius: f32, thickness: f32, start_angle: f32, sweep: f32, color: (u8, u8, u8, u8)) {
+    if radius <= 0.0 || thickness <= 0.0 || sweep <= 0.0 {
+        return;
+    }
+
+    let inner_radius = (radius - thickness).max(0.0);
+    let full_circle = sweep >= 360.0;
+    let start_angle = start_angle.rem_euclid(360.0);
+
+    let min_x = (center.0 - radius - 1.0).floor() as i32;
+    let max_x = (center.0 + radius + 1.0).ceil() as i32;
+    let min_y = (center.1 - radius - 1.0).floor() as i32;
+    let max_y = (center.1 + radius + 1.0).ceil() as i32;
+
+    for x in min_x..=max_x {
+        for y in min_y..=max_y {
+            let dx = x as f32 + 0.5 - center.0;
+            let dy = y as f32 + 0.5 - center.1;
+            let distance = (dx * dx + dy * dy).sqrt();
+
+            let mut coverage = (radius - distance + 0.5).clamp(0.0, 1.0);
+
+            if inner_radius > 0.0 {
+                coverage = coverage.min((distance - inner_radius + 0.5).clamp(0.0, 1.0));
+            }
+
+            if coverage <= 0.0 {
+                continue;
+            }
+
+            if !full_circle {
+                let angle = dx.atan2(-dy).to_degrees().rem_euclid(360.0);
+
+                if (angle - start_angle).rem_euclid(360.0) > sweep {
+                    continue;
+                }
+            }
+
+            blend_pixel(image, x, y, color, coverage);
+        }
+    }
+}
+
+/// Renders horizontal progress bar onto provided image, progress is clamped to 0.0 - 1.0 range
+pub fn render_progress_bar_on_image(image: &mut DynamicImage, position: (i32, i32), size: (u32, u32), progress: f32, color: (u8, u8, u8, u8), background_color: (u8, u8, u8, u8)) {
+    let filled = (size.0 as f32 * progress.clamp(0.0, 1.0)).round() as u32;
+
+    render_filled_rect_on_image(image, (position.0 + filled as i32, position.1), (size.0 - filled, size.1), background_color);
+    render_filled_rect_on_image(image, position, (filled, size.1), color);
+}
+
+/// Renders progress ring onto provided image that fills clockwise starting from the top, progress is clamped to 0.0 - 1.0 range
+pub fn render_progress_ring_on_image(image: &mut DynamicImage, center: (f32, f32), radius: f32, thickness: f32, progress: f32, color: (u8, u8, u8, u8), background_color: (u8, u8, u8, u8)) {
+    let sweep = 360.0 * progress.clamp(0.0, 1.0);
+
+    render_arc_on_image(image, center, radius, thickness, sweep, 360.0 - sweep, background_color);
+    render_arc_on_image(image, center, radius, thickness, 0.0, sweep, color);
+}
+
 /// Calculates bounds for text with provided font and parameters
 pub fn calculate_bounds_for_text(font: &Font, text: &str, scale: Scale) -> (u32, u32) {
     let mut w: u32 = 0;
     let mut h: u32 = 0;
 
-    for glyph in font.layout(text, scale, point(0.0, 0.0)) {
-        if let Some(bounding) = glyph.pixel_bounding_box() {
-            h = h.max(bounding.height() as u32);
-            w = w.max(bounding.max.x as u32);
+    for glyph in layout_glyphs(font, text, scale, point(0.0, 0.0)) {
+        match glyph {
+            LaidOutGlyph::Outline(glyph) => {
+                if let Some(bounding) = glyph.pixel_bounding_box() {
+                    h = h.max(bounding.height() as u32);
+                    w = w.max(bounding.max.x as u32);
+                }
+            }
+
+            LaidOutGlyph::Bitmap(bitmap, left, _) => {
+                h = h.max(bitmap.height());
+                w = w.max((left + bitmap.width() as i32).max(0) as u32);
+            }
         }
     }
 
@@ -211,4 +500,149 @@ pub fn render_aligned_text_on_image(size: (usize, usize), image: &mut DynamicIma
 pub fn render_aligned_shadowed_text_on_image(size: (usize, usize), image: &mut DynamicImage, font: &Font, text: &str, scale: Scale, align: TextAlignment, padding: u32, offset: (f32, f32), color: (u8, u8, u8, u8), shadow_offset: (i32, i32), shadow_color: (u8, u8, u8, u8)) {
     let point = get_alignment_position_for_text(size, font, text, scale.clone(), align, padding, offset);
     render_shadowed_text_on_image(image, &font, text, scale, point, color, shadow_offset, shadow_color);
-}
\ No newline at end of file
+}
+
+/// Gap in pixels between end of scrolling text and its next repetition
+pub const MARQUEE_GAP: u32 = 24;
+
+/// Renders single line of text scrolled to the left by position in pixels, text repeats after a gap so scrolling loops seamlessly.
+/// Vertical alignment is respected, text always starts at the left edge
+pub fn render_marquee_text_on_image(size: (usize, usize), image: &mut DynamicImage, font: &Font, text: &str, scale: Scale, align: TextAlignment, padding: u32, offset: (f32, f32), color: (u8, u8, u8, u8), shadow: Option<((i32, i32), (u8, u8, u8, u8))>, position: u64) {
+    let cycle = calculate_bounds_for_text(font, text, scale).0 + MARQUEE_GAP;
+    let y = get_alignment_position_for_text(size, font, text, scale, align, padding, offset).y;
+    let start = padding as f32 + offset.0 - (position % cycle as u64) as f32;
+
+    for x in [start, start + cycle as f32] {
+        if let Some((shadow_offset, shadow_color)) = shadow {
+            render_shadowed_text_on_image(image, font, text, scale, point(x, y), color, shadow_offset, shadow_color);
+        } else {
+            render_text_on_image(image, font, text, scale, point(x, y), color);
+        }
+    }
+}
+
+/// Smallest scale that auto-fit will shrink text to
+const MIN_FIT_SCALE: f32 = 4.0;
+
+/// Breaks text into lines that fit into provided width, splitting at spaces and line breaks. Words that don't fit on their own are kept whole
+pub fn wrap_text(font: &Font, text: &str, scale: Scale, width: u32) -> Vec<String> {
+    let mut lines = vec![];
+
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+
+        for word in paragraph.split_whitespace() {
+            if line.is_empty() {
+                line = word.to_string();
+                continue;
+            }
+
+            let candidate = format!("{} {}", line, word);
+
+            if calculate_bounds_for_text(font, &candidate, scale).0 <= width {
+                line = candidate;
+            } else {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            }
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Height of a single line of text, including gap between lines
+pub fn line_height(font: &Font, scale: Scale) -> u32 {
+    let metrics = font.v_metrics(scale);
+    (metrics.ascent - metrics.descent + metrics.line_gap).ceil() as u32
+}
+
+/// Calculates bounds for lines of text rendered below each other
+pub fn calculate_bounds_for_lines(font: &Font, lines: &[String], scale: Scale) -> (u32, u32) {
+    if let [line] = lines {
+        return calculate_bounds_for_text(font, line, scale);
+    }
+
+    let width = lines.iter()
+        .map(|x| calculate_bounds_for_text(font, x, scale).0)
+        .max()
+        .unwrap_or(0);
+
+    (width, line_height(font, scale) * lines.len() as u32)
+}
+
+/// Splits text into lines and picks scale for it, so it fits into button of provided size.
+/// Word wrap breaks text into lines, auto-fit shrinks the scale until text fits
+pub fn layout_text(size: (usize, usize), font: &Font, text: &str, scale: Scale, padding: u32, auto_fit: bool, word_wrap: bool) -> (Vec<String>, Scale) {
+    let width = (size.0 as u32).saturating_sub(padding * 2);
+    let height = (size.1 as u32).saturating_sub(padding * 2);
+
+    let mut scale = scale;
+
+    loop {
+        let lines = if word_wrap {
+            wrap_text(font, text, scale, width)
+        } else {
+            vec![text.to_string()]
+        };
+
+        if !auto_fit || scale.x.min(scale.y) <= MIN_FIT_SCALE {
+            return (lines, scale);
+        }
+
+        let (text_width, text_height) = calculate_bounds_for_lines(font, &lines, scale);
+
+        if text_width <= width && text_height <= height {
+            return (lines, scale);
+        }
+
+        scale = Scale {
+            x: scale.x * 0.9,
+            y: scale.y * 0.9
+        };
+    }
+}
+
+/// Renders aligned lines of text onto provided image with specified parameters, shadow is offset and color of text shadow
+pub fn render_aligned_lines_on_image(size: (usize, usize), image: &mut DynamicImage, font: &Font, lines: &[String], scale: Scale, align: TextAlignment, padding: u32, offset: (f32, f32), color: (u8, u8, u8, u8), shadow: Option<((i32, i32), (u8, u8, u8, u8))>) {
+    if let [line] = lines {
+        if let Some((shadow_offset, shadow_color)) = shadow {
+            render_aligned_shadowed_text_on_image(size, image, font, line, scale, align, padding, offset, color, shadow_offset, shadow_color);
+        } else {
+            render_aligned_text_on_image(size, image, font, line, scale, align, padding, offset, color);
+        }
+
+        return;
+    }
+
+    let line_height = line_height(font, scale);
+    let block_height = line_height * lines.len() as u32;
+
+    let top = match align {
+        TextAlignment::TopLeft | TextAlignment::TopCenter | TextAlignment::TopRight => {
+            padding as i32
+        }
+
+        TextAlignment::MiddleLeft | TextAlignment::Center | TextAlignment::MiddleRight => {
+            size.1 as i32 / 2 - block_height as i32 / 2
+        }
+
+        TextAlignment::BottomLeft | TextAlignment::BottomCenter | TextAlignment::BottomRight => {
+            size.1 as i32 - padding as i32 - block_height as i32
+        }
+    };
+
+    let ascent = font.v_metrics(scale).ascent;
+
+    for (index, line) in lines.iter().enumerate() {
+        let x = get_alignment_position_for_text(size, font, line, scale, align.clone(), padding, offset).x;
+        let y = top as f32 + ascent + (line_height * index as u32) as f32 + offset.1;
+
+        if let Some((shadow_offset, shadow_color)) = shadow {
+            render_shadowed_text_on_image(image, font, line, scale, point(x, y), color, shadow_offset, shadow_color);
+        } else {
+            render_text_on_image(image, font, line, scale, point(x, y), color);
+        }
+    }
+}
@@ -322,6 +322,15 @@ pub fn set_value_function(value: UIPathValue) -> Box<dyn Fn(&mut UIValue) -> boo
                 }
             }
 
+            UIFieldType::InputFieldMultiline { .. } => {
+                if let Ok(s) = value.value.try_into_string() {
+                    x.value = UIFieldValue::InputFieldMultiline(s);
+                    true
+                } else {
+                    false
+                }
+            }
+
             UIFieldType::InputFieldFloat2 => {
                 if let Ok((f1, f2)) = value.value.try_into_f32_f32() {
                     x.value = UIFieldValue::InputFieldFloat2(f1, f2);
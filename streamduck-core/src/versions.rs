@@ -8,13 +8,13 @@
 //! are not using unsupported API.
 
 /// API related to plugin definition and initialization, will be updated very rarely if ever
-pub const PLUGIN_API: (&str, &str) = ("plugin_api", "0.1");
+pub const PLUGIN_API: (&str, &str) = ("plugin_api", "0.2");
 /// SDModule trait version, will be updated everytime there's a change to the module trait
-pub const SDMODULE_TRAIT: (&str, &str) = ("sdmodule_trait", "0.1");
+pub const SDMODULE_TRAIT: (&str, &str) = ("sdmodule_trait", "0.4");
 /// Core version, will be updated everytime there's change to core struct, probably never
 pub const CORE: (&str, &str) = ("core", "0.1");
 /// Core methods version, will be updated everytime there's changes to existing functions or functions get deleted
-pub const CORE_METHODS: (&str, &str) = ("core_methods", "0.1");
+pub const CORE_METHODS: (&str, &str) = ("core_methods", "0.2");
 /// Config, will be updated everytime there's changes to existing functions or functions get deleted
 pub const CONFIG: (&str, &str) = ("config", "0.1");
 /// Module manager, will be updated everytime there's changes to existing functions or functions get deleted
@@ -25,6 +25,12 @@ pub const CORE_EVENTS: (&str, &str) = ("core_events", "0.1");
 pub const SOCKET_API: (&str, &str) = ("socket_api", "0.1");
 /// Rendering version, will be updated everytime there's changes to existing rendering API for plugins
 pub const RENDERING: (&str, &str) = ("rendering", "0.1");
+/// Background task scheduler, will be updated everytime there's changes to existing scheduler functions
+pub const SCHEDULER: (&str, &str) = ("scheduler", "0.1");
+/// Message bus between modules, will be updated everytime there's changes to existing message bus functions or message format
+pub const MESSAGES: (&str, &str) = ("messages", "0.1");
+/// Access to other devices through core manager, will be updated everytime there's changes to existing cross-device functions
+pub const CORE_MANAGER: (&str, &str) = ("core_manager", "0.1");
 
 /// Constant array of currently supported features, can also be used for plugin to specify using all of the features
 pub const SUPPORTED_FEATURES: &[(&str, &str)] = &[
@@ -36,5 +42,8 @@ pub const SUPPORTED_FEATURES: &[(&str, &str)] = &[
     MODULE_MANAGER,
     CORE_EVENTS,
     RENDERING,
-    SOCKET_API
+    SOCKET_API,
+    SCHEDULER,
+    MESSAGES,
+    CORE_MANAGER
 ];
\ No newline at end of file
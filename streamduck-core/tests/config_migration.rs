@@ -0,0 +1,73 @@
+use serde_json::{json, Value};
+use streamduck_core::config::{ConfigError, DEVICE_CONFIG_VERSION, DeviceConfigFormat, migrate_device_config};
+
+fn load(content: &str) -> Result<streamduck_core::config::DeviceConfig, ConfigError> {
+    migrate_device_config(serde_json::from_str::<Value>(content).unwrap())
+}
+
+#[test]
+fn loads_v0_config() {
+    let config = load(include_str!("fixtures/device_config_v0.json")).unwrap();
+
+    assert_eq!(config.version, DEVICE_CONFIG_VERSION);
+    assert_eq!(config.serial, "AL00A000000");
+    assert_eq!(config.brightness, 50);
+    assert!(config.layout.buttons.contains_key(&0));
+}
+
+#[test]
+fn loads_v0_config_without_images_and_plugin_data() {
+    let config = load(include_str!("fixtures/device_config_v0_minimal.json")).unwrap();
+
+    assert_eq!(config.version, DEVICE_CONFIG_VERSION);
+    assert!(config.images.is_empty());
+    assert!(config.plugin_data.is_empty());
+}
+
+#[test]
+fn loads_v1_config() {
+    let config = load(include_str!("fixtures/device_config_v1.json")).unwrap();
+
+    assert_eq!(config.version, DEVICE_CONFIG_VERSION);
+    assert_eq!(config.plugin_data["example"], json!({ "enabled": true }));
+}
+
+#[test]
+fn migrates_v1_config_with_inline_images() {
+    let config = load(include_str!("fixtures/device_config_v1_images.json")).unwrap();
+
+    assert_eq!(config.version, DEVICE_CONFIG_VERSION);
+    assert!(config.images.contains_key("logo"));
+    assert!(config.stored_images.is_empty());
+}
+
+#[test]
+fn loads_current_config() {
+    let config = load(include_str!("fixtures/device_config_v2.json")).unwrap();
+
+    assert_eq!(config.version, DEVICE_CONFIG_VERSION);
+    assert!(config.images.is_empty());
+    assert_eq!(config.stored_images["logo"], "4164383916342342158");
+    assert_eq!(config.plugin_data["example"], json!({ "enabled": true }));
+}
+
+#[test]
+fn current_config_round_trips() {
+    let config = load(include_str!("fixtures/device_config_v2.json")).unwrap();
+
+    let content = DeviceConfigFormat::Json.serialize(&config).unwrap();
+    let reloaded = DeviceConfigFormat::Json.deserialize(&content).unwrap();
+
+    assert_eq!(reloaded.version, DEVICE_CONFIG_VERSION);
+    assert_eq!(reloaded.serial, config.serial);
+    assert_eq!(reloaded.stored_images, config.stored_images);
+    assert_eq!(reloaded.plugin_data, config.plugin_data);
+    assert_eq!(serde_json::from_str::<Value>(&content).unwrap(), serde_json::from_str::<Value>(&DeviceConfigFormat::Json.serialize(&reloaded).unwrap()).unwrap());
+}
+
+#[test]
+fn rejects_newer_config() {
+    let result = migrate_device_config(json!({ "version": DEVICE_CONFIG_VERSION + 1 }));
+
+    assert!(matches!(result, Err(ConfigError::UnsupportedVersion(_))));
+}
@@ -0,0 +1,122 @@
+//! Debounced saving of device configs that were changed by requests
+
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+use streamduck_core::config::Config;
+use streamduck_core::modules::events::SDGlobalEvent;
+use streamduck_core::socket::{SocketManager, SocketPacket};
+
+/// Keeps track of changed device configs and saves them once they weren't changed for the debounce interval
+pub struct ConfigAutosave {
+    config: Arc<Config>,
+    /// Serials of dirty configs and time of their last change
+    dirty: Mutex<HashMap<String, Instant>>,
+    condvar: Condvar,
+}
+
+impl ConfigAutosave {
+    /// Creates autosave for provided config
+    pub fn new(config: Arc<Config>) -> Arc<ConfigAutosave> {
+        Arc::new(ConfigAutosave {
+            config,
+            dirty: Default::default(),
+            condvar: Default::default()
+        })
+    }
+
+    /// Marks device config as changed, does nothing if autosave is disabled
+    pub fn mark_dirty(&self, serial: &str) {
+        if self.config.autosave_interval().is_none() {
+            return;
+        }
+
+        self.dirty.lock().unwrap().insert(serial.to_string(), Instant::now());
+        self.condvar.notify_all();
+    }
+
+    /// Marks device config of `serial_number` in request data as changed, for requests that change device configs without saving them
+    pub fn process_packet(&self, packet: &SocketPacket) {
+        if let Some(serial) = packet.data.as_ref().and_then(|x| x["serial_number"].as_str()) {
+            self.mark_dirty(serial);
+        }
+    }
+
+    /// Marks device configs as changed when events report changes that weren't made by requests, blocks forever
+    pub fn watch_events(&self, socket_manager: &Arc<SocketManager>) {
+        let pool = socket_manager.get_pool();
+
+        loop {
+            let packet = pool.take_message();
+
+            if packet.ty != "event" {
+                continue;
+            }
+
+            if let Some(Ok(SDGlobalEvent::StackPathChanged { serial_number, .. })) = packet.data.map(serde_json::from_value) {
+                self.mark_dirty(&serial_number);
+            }
+        }
+    }
+
+    /// Saves all dirty configs right away
+    pub fn flush(&self) {
+        let serials: Vec<String> = self.dirty.lock().unwrap().drain().map(|(serial, _)| serial).collect();
+
+        for serial in serials {
+            self.save(&serial);
+        }
+    }
+
+    fn save(&self, serial: &str) {
+        match self.config.save_device_config(serial) {
+            Ok(_) => log::debug!("Autosaved device config for {}", serial),
+            Err(err) => log::error!("Failed to autosave device config for {}: {:?}", serial, err),
+        }
+    }
+
+    /// Saves configs as they settle, blocks forever
+    pub fn run(&self) {
+        let mut dirty = self.dirty.lock().unwrap();
+
+        loop {
+            let interval = if let Some(interval) = self.config.autosave_interval() {
+                interval
+            } else {
+                dirty = self.condvar.wait(dirty).unwrap();
+                continue;
+            };
+
+            let now = Instant::now();
+            let ready: Vec<String> = dirty.iter()
+                .filter(|(_, changed)| now.duration_since(**changed) >= interval)
+                .map(|(serial, _)| serial.clone())
+                .collect();
+
+            if !ready.is_empty() {
+                for serial in &ready {
+                    dirty.remove(serial);
+                }
+
+                drop(dirty);
+
+                for serial in ready {
+                    self.save(&serial);
+                }
+
+                dirty = self.dirty.lock().unwrap();
+                continue;
+            }
+
+            let next = dirty.values()
+                .map(|changed| (*changed + interval).saturating_duration_since(now))
+                .min();
+
+            dirty = if let Some(wait) = next {
+                self.condvar.wait_timeout(dirty, wait.max(Duration::from_millis(10))).unwrap().0
+            } else {
+                self.condvar.wait(dirty).unwrap()
+            };
+        }
+    }
+}
@@ -1,9 +1,12 @@
 //! Requests related to images and fonts
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
+use streamduck_core::config::{ConfigError, ImageCleanup};
+use streamduck_core::core::methods::{commit_changes, CoreHandle};
 use streamduck_core::font::get_font_names;
 use streamduck_core::socket::{check_packet_for_data, parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket};
 use crate::daemon_data::{DaemonListener, DaemonRequest};
+use crate::transfer::{get_chunk, TransferError};
 
 /// Request for getting all images currently saved on device
 #[derive(Serialize, Deserialize)]
@@ -15,7 +18,16 @@ pub struct ListImages {
 #[derive(Serialize, Deserialize)]
 pub struct SocketImage {
     pub image_blob: String,
-    pub animated: bool
+    pub animated: bool,
+    /// Width of the image in pixels
+    #[serde(default)]
+    pub width: u32,
+    /// Height of the image in pixels
+    #[serde(default)]
+    pub height: u32,
+    /// Size of encoded image data in bytes
+    #[serde(default)]
+    pub size: usize,
 }
 
 /// Response for [ListImages] request
@@ -42,10 +54,17 @@ impl DaemonRequest for ListImages {
             if let Some(images) = listener.config.get_images(&request.serial_number) {
                 send_packet(handle, packet, &ListImagesResult::Images(
                     images.into_iter()
-                        .map(|(id, image)| (id, SocketImage {
-                            image_blob: image.as_image_blob().unwrap_or("failed".to_string()),
-                            animated: image.is_animated()
-                        }))
+                        .map(|(id, image)| {
+                            let (width, height) = image.dimensions().unwrap_or((0, 0));
+
+                            (id, SocketImage {
+                                image_blob: image.as_image_blob().unwrap_or("failed".to_string()),
+                                animated: image.is_animated(),
+                                width,
+                                height,
+                                size: image.byte_size()
+                            })
+                        })
                         .collect()
                 )).ok();
             } else {
@@ -84,6 +103,8 @@ impl SocketData for AddImageResult {
 }
 
 impl DaemonRequest for AddImage {
+    const CHANGES_DEVICE_CONFIG: bool = true;
+
     fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
         if let Ok(request) = parse_packet_to_data::<AddImage>(packet) {
             if let Some(_) = listener.core_manager.get_device(&request.serial_number) {
@@ -99,6 +120,207 @@ impl DaemonRequest for AddImage {
     }
 }
 
+/// Request for getting contents of an image from image collection, image data is sent in chunks of [TRANSFER_CHUNK_SIZE](crate::transfer::TRANSFER_CHUNK_SIZE) bytes.
+/// Animated images are sent as their first frame
+#[derive(Serialize, Deserialize)]
+pub struct GetImage {
+    pub serial_number: String,
+    pub image_identifier: String,
+    /// Offset of the chunk in bytes
+    #[serde(default)]
+    pub offset: usize,
+}
+
+/// Response of [GetImage] request
+#[derive(Serialize, Deserialize)]
+pub enum GetImageResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if image wasn't found
+    NotFound,
+
+    /// Sent if image was found, contains base64 encoded chunk of image file, offset of the chunk and size of the whole file
+    Chunk {
+        data: String,
+        offset: usize,
+        total_size: usize,
+    },
+}
+
+impl SocketData for GetImage {
+    const NAME: &'static str = "get_image";
+}
+
+impl SocketData for GetImageResult {
+    const NAME: &'static str = "get_image";
+}
+
+impl DaemonRequest for GetImage {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<GetImage>(packet) {
+            let result = if let Some(images) = listener.config.get_images(&request.serial_number) {
+                let bytes = images.get(&request.image_identifier)
+                    .and_then(|image| image.as_image_blob().ok())
+                    .and_then(|blob| base64::decode(blob).ok());
+
+                if let Some(bytes) = bytes {
+                    GetImageResult::Chunk {
+                        data: base64::encode(get_chunk(&bytes, request.offset)),
+                        offset: request.offset,
+                        total_size: bytes.len()
+                    }
+                } else {
+                    GetImageResult::NotFound
+                }
+            } else {
+                GetImageResult::DeviceNotFound
+            };
+
+            send_packet(handle, packet, &result).ok();
+        }
+    }
+}
+
+/// Request for starting chunked upload of an image, for images that are too big to be sent with [AddImage]
+#[derive(Serialize, Deserialize)]
+pub struct StartImageUpload {
+    pub serial_number: String,
+}
+
+/// Response of [StartImageUpload] request
+#[derive(Serialize, Deserialize)]
+pub enum StartImageUploadResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if upload was started, contains ID of the upload
+    Started(String),
+}
+
+impl SocketData for StartImageUpload {
+    const NAME: &'static str = "start_image_upload";
+}
+
+impl SocketData for StartImageUploadResult {
+    const NAME: &'static str = "start_image_upload";
+}
+
+impl DaemonRequest for StartImageUpload {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<StartImageUpload>(packet) {
+            if listener.config.get_device_config(&request.serial_number).is_some() {
+                send_packet(handle, packet, &StartImageUploadResult::Started(listener.transfers.start(&request.serial_number))).ok();
+            } else {
+                send_packet(handle, packet, &StartImageUploadResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
+
+/// Request for sending next chunk of an image upload
+#[derive(Serialize, Deserialize)]
+pub struct UploadImageChunk {
+    pub upload_id: String,
+    /// Base64 encoded chunk of image file
+    pub data: String,
+}
+
+/// Response of [UploadImageChunk] request
+#[derive(Serialize, Deserialize)]
+pub enum UploadImageChunkResult {
+    /// Sent if upload doesn't exist or has timed out
+    UploadNotFound,
+
+    /// Sent if chunk isn't valid base64
+    InvalidData,
+
+    /// Sent if upload got bigger than [MAX_UPLOAD_SIZE](crate::transfer::MAX_UPLOAD_SIZE), upload is cancelled
+    TooLarge,
+
+    /// Sent if chunk was received, contains amount of bytes received so far
+    Received(usize),
+}
+
+impl SocketData for UploadImageChunk {
+    const NAME: &'static str = "upload_image_chunk";
+}
+
+impl SocketData for UploadImageChunkResult {
+    const NAME: &'static str = "upload_image_chunk";
+}
+
+impl DaemonRequest for UploadImageChunk {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<UploadImageChunk>(packet) {
+            let result = if let Ok(chunk) = base64::decode(&request.data) {
+                match listener.transfers.append(&request.upload_id, &chunk) {
+                    Ok(received) => UploadImageChunkResult::Received(received),
+                    Err(TransferError::NotFound) => UploadImageChunkResult::UploadNotFound,
+                    Err(TransferError::TooLarge) => UploadImageChunkResult::TooLarge,
+                }
+            } else {
+                UploadImageChunkResult::InvalidData
+            };
+
+            send_packet(handle, packet, &result).ok();
+        }
+    }
+}
+
+/// Request for finishing image upload and adding uploaded image into image collection
+#[derive(Serialize, Deserialize)]
+pub struct FinishImageUpload {
+    pub upload_id: String,
+}
+
+/// Response of [FinishImageUpload] request
+#[derive(Serialize, Deserialize)]
+pub enum FinishImageUploadResult {
+    /// Sent if upload doesn't exist or has timed out
+    UploadNotFound,
+
+    /// Sent if device was removed during upload
+    DeviceNotFound,
+
+    /// Sent if uploaded data isn't a supported image
+    InvalidData,
+
+    /// Sent if successfully added image, contains identifier for the image
+    Added(String),
+}
+
+impl SocketData for FinishImageUpload {
+    const NAME: &'static str = "finish_image_upload";
+}
+
+impl SocketData for FinishImageUploadResult {
+    const NAME: &'static str = "finish_image_upload";
+}
+
+impl DaemonRequest for FinishImageUpload {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<FinishImageUpload>(packet) {
+            let result = match listener.transfers.finish(&request.upload_id) {
+                Ok((serial, data)) => {
+                    if listener.config.get_device_config(&serial).is_none() {
+                        FinishImageUploadResult::DeviceNotFound
+                    } else if let Some(identifier) = listener.config.add_image(&serial, base64::encode(data)) {
+                        // Serial isn't in request data, so autosave is requested here
+                        listener.autosave.mark_dirty(&serial);
+                        FinishImageUploadResult::Added(identifier)
+                    } else {
+                        FinishImageUploadResult::InvalidData
+                    }
+                }
+                Err(_) => FinishImageUploadResult::UploadNotFound,
+            };
+
+            send_packet(handle, packet, &result).ok();
+        }
+    }
+}
+
 /// Request for removing an image from image collection
 #[derive(Serialize, Deserialize)]
 pub struct RemoveImage {
@@ -125,6 +347,8 @@ impl SocketData for RemoveImageResult {
 }
 
 impl DaemonRequest for RemoveImage {
+    const CHANGES_DEVICE_CONFIG: bool = true;
+
     fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
         if let Ok(request) = parse_packet_to_data::<RemoveImage>(packet) {
             // Decoding image to make sure the data is correct
@@ -137,7 +361,7 @@ impl DaemonRequest for RemoveImage {
     }
 }
 
-/// Request for getting fonts loaded by daemon
+/// Request for getting names of fonts available to daemon, including fonts from fonts folder and system fonts if enabled
 #[derive(Serialize, Deserialize)]
 pub struct ListFonts {
     pub font_names: Vec<String>
@@ -155,4 +379,53 @@ impl DaemonRequest for ListFonts {
             }).ok();
         }
     }
-}
\ No newline at end of file
+}
+
+/// Request for removing images that aren't used anywhere, from specified device or from all devices if serial isn't specified
+#[derive(Serialize, Deserialize)]
+pub struct CleanupUnusedImages {
+    #[serde(default)]
+    pub serial_number: Option<String>,
+}
+
+/// Response of [CleanupUnusedImages] request
+#[derive(Serialize, Deserialize)]
+pub enum CleanupUnusedImagesResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if cleanup failed to save configs or access image store
+    FailedToCleanup(String),
+
+    /// Sent if cleanup was done, contains identifiers of removed images and amount of bytes freed
+    Cleaned(ImageCleanup),
+}
+
+impl SocketData for CleanupUnusedImages {
+    const NAME: &'static str = "cleanup_unused_images";
+}
+
+impl SocketData for CleanupUnusedImagesResult {
+    const NAME: &'static str = "cleanup_unused_images";
+}
+
+impl DaemonRequest for CleanupUnusedImages {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<CleanupUnusedImages>(packet) {
+            // Images could be referenced by changes that weren't committed yet
+            for device in listener.core_manager.list_added_devices().into_values() {
+                if !device.core.is_closed() && request.serial_number.as_ref().map_or(true, |x| *x == device.serial) {
+                    commit_changes(&CoreHandle::wrap(device.core));
+                }
+            }
+
+            let result = match listener.config.cleanup_unused_images(request.serial_number.as_deref()) {
+                Ok(cleanup) => CleanupUnusedImagesResult::Cleaned(cleanup),
+                Err(ConfigError::DeviceNotFound) => CleanupUnusedImagesResult::DeviceNotFound,
+                Err(err) => CleanupUnusedImagesResult::FailedToCleanup(format!("{:?}", err)),
+            };
+
+            send_packet(handle, packet, &result).ok();
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Authentication of connections with token from daemon config
+//!
+//! If daemon config has a socket token, connections can only request socket API version and authenticate
+//! until they send [Authenticate] with the token, or resume a session of an authenticated connection.
+//! Authenticating with read-only token makes the connection read-only.
+//! Socket implementations process packets with [process_auth_request] before anything else
+use serde::{Deserialize, Serialize};
+use streamduck_core::config::Config;
+use streamduck_core::socket::{check_packet_for_data, parse_packet_to_data, send_packet, SocketClient, SocketData, SocketHandle, SocketPacket};
+use crate::daemon_data::SocketAPIVersion;
+use crate::daemon_data::sessions::ResumeSession;
+
+/// Request for authenticating current connection
+#[derive(Serialize, Deserialize)]
+pub struct Authenticate {
+    pub token: String,
+}
+
+/// Response of [Authenticate] request
+#[derive(Serialize, Deserialize)]
+pub enum AuthenticateResult {
+    /// Sent if daemon doesn't require authentication
+    NotRequired,
+
+    /// Sent if token doesn't match
+    InvalidToken,
+
+    /// Sent if connection is now authenticated
+    Authenticated,
+
+    /// Sent if connection is now authenticated, but can only make requests that don't change anything
+    AuthenticatedReadOnly,
+}
+
+impl SocketData for Authenticate {
+    const NAME: &'static str = "authenticate";
+}
+
+impl SocketData for AuthenticateResult {
+    const NAME: &'static str = "authenticate";
+}
+
+/// Response to any request that was made before authenticating
+#[derive(Serialize, Deserialize)]
+pub struct Unauthorized {
+    /// Type of the request that was rejected
+    pub request: String,
+}
+
+impl SocketData for Unauthorized {
+    const NAME: &'static str = "unauthorized";
+}
+
+/// Tokens from daemon config that clients can authenticate with
+#[derive(Clone, Default)]
+pub struct SocketTokens {
+    /// Token that gives full access
+    pub token: Option<String>,
+    /// Token that only gives read-only access
+    pub read_only_token: Option<String>,
+}
+
+impl SocketTokens {
+    /// Gets tokens from daemon config
+    pub fn from_config(config: &Config) -> SocketTokens {
+        SocketTokens {
+            token: config.socket_token().map(|x| x.to_string()),
+            read_only_token: config.read_only_token().map(|x| x.to_string())
+        }
+    }
+
+    /// Checks provided token, returns [None] if it doesn't match any of the tokens, or if access is read-only otherwise
+    pub fn check(&self, provided: &str) -> Option<bool> {
+        if self.token.as_deref().map_or(false, |x| tokens_match(x, provided)) {
+            Some(false)
+        } else if self.read_only_token.as_deref().map_or(false, |x| tokens_match(x, provided)) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+}
+
+/// Compares tokens without returning early, so token can't be guessed from response times
+pub fn tokens_match(expected: &str, provided: &str) -> bool {
+    let expected = expected.as_bytes();
+    let provided = provided.as_bytes();
+
+    let difference = expected.iter().zip(provided.iter())
+        .fold(0, |acc, (a, b)| acc | (a ^ b));
+
+    expected.len() == provided.len() && difference == 0
+}
+
+/// Processes authentication requests and rejects requests of unauthenticated clients.
+/// Returns true if packet was handled and shouldn't be processed any further
+pub fn process_auth_request(tokens: &SocketTokens, client: &SocketClient, handle: SocketHandle, packet: &SocketPacket) -> bool {
+    if check_packet_for_data::<Authenticate>(packet) {
+        if let Ok(request) = parse_packet_to_data::<Authenticate>(packet) {
+            let response = match tokens.check(&request.token) {
+                _ if tokens.token.is_none() && tokens.read_only_token.is_none() => AuthenticateResult::NotRequired,
+
+                Some(false) => {
+                    client.authenticate();
+                    AuthenticateResult::Authenticated
+                }
+
+                Some(true) => {
+                    client.make_read_only();
+                    client.authenticate();
+                    AuthenticateResult::AuthenticatedReadOnly
+                }
+
+                None => {
+                    log::warn!("Client {} has provided invalid token", client.id());
+                    AuthenticateResult::InvalidToken
+                }
+            };
+
+            send_packet(handle, packet, &response).ok();
+        }
+
+        return true;
+    }
+
+    // Resumed sessions get authentication of the connection that started them
+    if client.is_authenticated() || check_packet_for_data::<SocketAPIVersion>(packet) || check_packet_for_data::<ResumeSession>(packet) {
+        return false;
+    }
+
+    send_packet(handle, packet, &Unauthorized {
+        request: packet.ty.clone()
+    }).ok();
+
+    true
+}
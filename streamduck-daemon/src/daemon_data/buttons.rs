@@ -2,11 +2,13 @@
 use std::ops::Deref;
 use serde::{Deserialize, Serialize};
 use streamduck_core::core::button::Button;
-use streamduck_core::core::methods::{add_element_component_value, add_component, clear_button, CoreHandle, get_button, get_component_values_with_paths, remove_component, set_button, set_component_value_by_path, remove_element_component_value, paste_button};
-use streamduck_core::modules::components::UIPathValue;
+use streamduck_core::core::methods::{add_element_component_value, add_component, clear_button, ComponentValueError, CoreHandle, get_button, get_component_values_with_paths, remove_component, set_button, set_component_value_by_path, remove_element_component_value, paste_button};
+use streamduck_core::modules::components::{ComponentCompatibilityError, FieldError, UIPathValue};
+use streamduck_core::portable::PortableButton;
 use streamduck_core::socket::{check_packet_for_data, parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket};
 use streamduck_core::util::{button_to_raw, make_button_unique};
 use crate::daemon_data::{DaemonListener, DaemonRequest};
+use crate::daemon_data::revisions::edit_at_revision;
 
 /// Request for getting a button from current screen on a device
 #[derive(Serialize, Deserialize)]
@@ -59,7 +61,10 @@ impl DaemonRequest for GetButton {
 pub struct SetButton {
     pub serial_number: String,
     pub key: u8,
-    pub button: Button
+    pub button: Button,
+    /// Revision of the button that client has last seen, edit is rejected if button was changed since then
+    #[serde(default)]
+    pub revision: Option<u64>,
 }
 
 /// Response of [SetButton] request
@@ -72,7 +77,10 @@ pub enum SetButtonResult {
     DeviceNotFound,
 
     /// Sent if successfully set the button
-    Set
+    Set,
+
+    /// Sent if button was changed since provided revision, contains current revision
+    Conflict(u64),
 }
 
 impl SocketData for SetButton {
@@ -89,11 +97,11 @@ impl DaemonRequest for SetButton {
             if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
                 let wrapped_core = CoreHandle::wrap(device.core);
 
-                if set_button(&wrapped_core, request.key, make_button_unique(request.button)) {
-                    send_packet(handle, packet, &SetButtonResult::Set).ok();
-                } else {
-                    send_packet(handle, packet, &SetButtonResult::NoScreen).ok();
-                }
+                match edit_at_revision(&wrapped_core, &[request.key], request.revision, || listener.history.track(&request.serial_number, &wrapped_core, request.key, || set_button(&wrapped_core, request.key, make_button_unique(request.button)))) {
+                    Ok(true) => send_packet(handle, packet, &SetButtonResult::Set).ok(),
+                    Ok(false) => send_packet(handle, packet, &SetButtonResult::NoScreen).ok(),
+                    Err(revision) => send_packet(handle, packet, &SetButtonResult::Conflict(revision)).ok(),
+                };
             } else {
                 send_packet(handle, packet, &SetButtonResult::DeviceNotFound).ok();
             }
@@ -106,6 +114,9 @@ impl DaemonRequest for SetButton {
 pub struct ClearButton {
     pub serial_number: String,
     pub key: u8,
+    /// Revision of the button that client has last seen, edit is rejected if button was changed since then
+    #[serde(default)]
+    pub revision: Option<u64>,
 }
 
 /// Response of [ClearButton] request
@@ -118,7 +129,10 @@ pub enum ClearButtonResult {
     FailedToClear,
 
     /// Sent if successfully set the button
-    Cleared
+    Cleared,
+
+    /// Sent if button was changed since provided revision, contains current revision
+    Conflict(u64),
 }
 
 impl SocketData for ClearButton {
@@ -135,11 +149,11 @@ impl DaemonRequest for ClearButton {
             if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
                 let wrapped_core = CoreHandle::wrap(device.core);
 
-                if clear_button(&wrapped_core, request.key) {
-                    send_packet(handle, packet, &ClearButtonResult::Cleared).ok();
-                } else {
-                    send_packet(handle, packet, &ClearButtonResult::FailedToClear).ok();
-                }
+                match edit_at_revision(&wrapped_core, &[request.key], request.revision, || listener.history.track(&request.serial_number, &wrapped_core, request.key, || clear_button(&wrapped_core, request.key))) {
+                    Ok(true) => send_packet(handle, packet, &ClearButtonResult::Cleared).ok(),
+                    Ok(false) => send_packet(handle, packet, &ClearButtonResult::FailedToClear).ok(),
+                    Err(revision) => send_packet(handle, packet, &ClearButtonResult::Conflict(revision)).ok(),
+                };
             } else {
                 send_packet(handle, packet, &ClearButtonResult::DeviceNotFound).ok();
             }
@@ -147,11 +161,78 @@ impl DaemonRequest for ClearButton {
     }
 }
 
+/// Request for moving a button to another position on current screen
+#[derive(Serialize, Deserialize)]
+pub struct MoveButton {
+    pub serial_number: String,
+    pub from: u8,
+    pub to: u8,
+    /// Moves button on destination to source position, moving fails if destination is taken and this is false
+    #[serde(default)]
+    pub swap: bool,
+    /// Revision of the buttons that client has last seen, edit is rejected if either of buttons was changed since then
+    #[serde(default)]
+    pub revision: Option<u64>,
+}
+
+/// Response of [MoveButton] request
+#[derive(Serialize, Deserialize)]
+pub enum MoveButtonResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if there's no button to move, or destination is taken without swap
+    FailedToMove,
+
+    /// Sent if button was successfully moved
+    Moved,
+
+    /// Sent if button was changed since provided revision, contains current revision
+    Conflict(u64),
+}
+
+impl SocketData for MoveButton {
+    const NAME: &'static str = "move_button";
+}
+
+impl SocketData for MoveButtonResult {
+    const NAME: &'static str = "move_button";
+}
+
+impl DaemonRequest for MoveButton {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<MoveButton>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
+                let wrapped_core = CoreHandle::wrap(device.core);
+
+                match edit_at_revision(&wrapped_core, &[request.from, request.to], request.revision, || listener.history.track_move(&request.serial_number, &wrapped_core, request.from, request.to, request.swap)) {
+                    Ok(true) => {
+                        send_packet(handle, packet, &MoveButtonResult::Moved).ok();
+                    }
+
+                    Ok(false) => {
+                        send_packet(handle, packet, &MoveButtonResult::FailedToMove).ok();
+                    }
+
+                    Err(revision) => {
+                        send_packet(handle, packet, &MoveButtonResult::Conflict(revision)).ok();
+                    }
+                }
+            } else {
+                send_packet(handle, packet, &MoveButtonResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
+
 /// Request for adding a new empty button
 #[derive(Serialize, Deserialize)]
 pub struct NewButton {
     pub serial_number: String,
     pub key: u8,
+    /// Revision of the button that client has last seen, edit is rejected if button was changed since then
+    #[serde(default)]
+    pub revision: Option<u64>,
 }
 
 /// Response of [NewButton] request
@@ -165,6 +246,9 @@ pub enum NewButtonResult {
 
     /// Sent if button was successfully created
     Created,
+
+    /// Sent if button was changed since provided revision, contains current revision
+    Conflict(u64),
 }
 
 impl SocketData for NewButton {
@@ -181,11 +265,11 @@ impl DaemonRequest for NewButton {
             if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
                 let wrapped_core = CoreHandle::wrap(device.core);
 
-                if set_button(&wrapped_core, request.key, make_button_unique(Button::new())) {
-                    send_packet(handle, packet, &NewButtonResult::Created).ok();
-                } else {
-                    send_packet(handle, packet, &NewButtonResult::FailedToCreate).ok();
-                }
+                match edit_at_revision(&wrapped_core, &[request.key], request.revision, || listener.history.track(&request.serial_number, &wrapped_core, request.key, || set_button(&wrapped_core, request.key, make_button_unique(Button::new())))) {
+                    Ok(true) => send_packet(handle, packet, &NewButtonResult::Created).ok(),
+                    Ok(false) => send_packet(handle, packet, &NewButtonResult::FailedToCreate).ok(),
+                    Err(revision) => send_packet(handle, packet, &NewButtonResult::Conflict(revision)).ok(),
+                };
             } else {
                 send_packet(handle, packet, &NewButtonResult::DeviceNotFound).ok();
             }
@@ -199,6 +283,9 @@ pub struct NewButtonFromComponent {
     pub serial_number: String,
     pub key: u8,
     pub component_name: String,
+    /// Revision of the button that client has last seen, edit is rejected if button was changed since then
+    #[serde(default)]
+    pub revision: Option<u64>,
 }
 
 /// Response of [NewButtonFromComponent] request
@@ -210,11 +297,17 @@ pub enum NewButtonFromComponentResult {
     /// Sent if component wasn't found
     ComponentNotFound,
 
+    /// Sent if component requires other components, so it can't be the only component on a button
+    Incompatible(ComponentCompatibilityError),
+
     /// Sent if button failed to be created on specified spot
     FailedToCreate,
 
     /// Sent if button was successfully created
     Created,
+
+    /// Sent if button was changed since provided revision, contains current revision
+    Conflict(u64),
 }
 
 impl SocketData for NewButtonFromComponent {
@@ -239,13 +332,18 @@ impl DaemonRequest for NewButtonFromComponent {
                     let mut button = Button::new();
                     button.insert_component(definition.default_looks).ok();
 
+                    if let Err(err) = listener.module_manager.check_component_addition(&button.component_names(), &request.component_name) {
+                        send_packet(handle, packet, &NewButtonFromComponentResult::Incompatible(err)).ok();
+                        return;
+                    }
+
                     module.add_component(wrapped_core.clone_for(&module), &mut button, &request.component_name);
 
-                    if set_button(&wrapped_core, request.key, make_button_unique(button)) {
-                        send_packet(handle, packet, &NewButtonFromComponentResult::Created).ok();
-                    } else {
-                        send_packet(handle, packet, &NewButtonFromComponentResult::FailedToCreate).ok();
-                    }
+                    match edit_at_revision(&wrapped_core, &[request.key], request.revision, || listener.history.track(&request.serial_number, &wrapped_core, request.key, || set_button(&wrapped_core, request.key, make_button_unique(button)))) {
+                        Ok(true) => send_packet(handle, packet, &NewButtonFromComponentResult::Created).ok(),
+                        Ok(false) => send_packet(handle, packet, &NewButtonFromComponentResult::FailedToCreate).ok(),
+                        Err(revision) => send_packet(handle, packet, &NewButtonFromComponentResult::Conflict(revision)).ok(),
+                    };
 
                     return;
                 }
@@ -265,6 +363,9 @@ pub struct AddComponent {
     pub serial_number: String,
     pub key: u8,
     pub component_name: String,
+    /// Revision of the button that client has last seen, edit is rejected if button was changed since then
+    #[serde(default)]
+    pub revision: Option<u64>,
 }
 
 /// Response of [AddComponent] request
@@ -276,8 +377,14 @@ pub enum AddComponentResult {
     /// Sent if failed to add component
     FailedToAdd,
 
+    /// Sent if component can't be on the same button with components the button has
+    Incompatible(ComponentCompatibilityError),
+
     /// Sent if component was successfully added
     Added,
+
+    /// Sent if button was changed since provided revision, contains current revision
+    Conflict(u64),
 }
 
 impl SocketData for AddComponent {
@@ -294,11 +401,20 @@ impl DaemonRequest for AddComponent {
             if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
                 let wrapped_core = CoreHandle::wrap(device.core);
 
-                if add_component(&wrapped_core, request.key, &request.component_name) {
-                    send_packet(handle, packet, &AddComponentResult::Added).ok();
-                } else {
-                    send_packet(handle, packet, &AddComponentResult::FailedToAdd).ok();
+                if let Some(button) = get_button(&wrapped_core, request.key) {
+                    let component_names = button.read().unwrap().component_names();
+
+                    if let Err(err) = listener.module_manager.check_component_addition(&component_names, &request.component_name) {
+                        send_packet(handle, packet, &AddComponentResult::Incompatible(err)).ok();
+                        return;
+                    }
                 }
+
+                match edit_at_revision(&wrapped_core, &[request.key], request.revision, || listener.history.track(&request.serial_number, &wrapped_core, request.key, || add_component(&wrapped_core, request.key, &request.component_name))) {
+                    Ok(true) => send_packet(handle, packet, &AddComponentResult::Added).ok(),
+                    Ok(false) => send_packet(handle, packet, &AddComponentResult::FailedToAdd).ok(),
+                    Err(revision) => send_packet(handle, packet, &AddComponentResult::Conflict(revision)).ok(),
+                };
             } else {
                 send_packet(handle, packet, &AddComponentResult::DeviceNotFound).ok();
             }
@@ -362,6 +478,9 @@ pub struct AddComponentValue {
     pub key: u8,
     pub component_name: String,
     pub path: String,
+    /// Revision of the button that client has last seen, edit is rejected if button was changed since then
+    #[serde(default)]
+    pub revision: Option<u64>,
 }
 
 /// Response of [AddComponentValue] request
@@ -375,6 +494,9 @@ pub enum AddComponentValueResult {
 
     /// Sent if component value was successfully added
     Added,
+
+    /// Sent if button was changed since provided revision, contains current revision
+    Conflict(u64),
 }
 
 impl SocketData for AddComponentValue {
@@ -391,11 +513,19 @@ impl DaemonRequest for AddComponentValue {
             if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
                 let wrapped_core = CoreHandle::wrap(device.core);
 
-                if add_element_component_value(&wrapped_core, request.key, &request.component_name, &request.path) {
-                    listener.config.sync_images(&request.serial_number);
-                    send_packet(handle, packet, &AddComponentValueResult::Added).ok();
-                } else {
-                    send_packet(handle, packet, &AddComponentValueResult::FailedToAdd).ok();
+                match edit_at_revision(&wrapped_core, &[request.key], request.revision, || listener.history.track(&request.serial_number, &wrapped_core, request.key, || add_element_component_value(&wrapped_core, request.key, &request.component_name, &request.path))) {
+                    Ok(Ok(_)) => {
+                        listener.config.sync_images(&request.serial_number);
+                        send_packet(handle, packet, &AddComponentValueResult::Added).ok();
+                    }
+
+                    Ok(Err(_)) => {
+                        send_packet(handle, packet, &AddComponentValueResult::FailedToAdd).ok();
+                    }
+
+                    Err(revision) => {
+                        send_packet(handle, packet, &AddComponentValueResult::Conflict(revision)).ok();
+                    }
                 }
             } else {
                 send_packet(handle, packet, &AddComponentValueResult::DeviceNotFound).ok();
@@ -412,6 +542,9 @@ pub struct RemoveComponentValue {
     pub component_name: String,
     pub path: String,
     pub index: usize,
+    /// Revision of the button that client has last seen, edit is rejected if button was changed since then
+    #[serde(default)]
+    pub revision: Option<u64>,
 }
 
 /// Response of [RemoveComponentValue] request
@@ -425,6 +558,9 @@ pub enum RemoveComponentValueResult {
 
     /// Sent if component value was successfully removed
     Removed,
+
+    /// Sent if button was changed since provided revision, contains current revision
+    Conflict(u64),
 }
 
 impl SocketData for RemoveComponentValue {
@@ -441,11 +577,19 @@ impl DaemonRequest for RemoveComponentValue {
             if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
                 let wrapped_core = CoreHandle::wrap(device.core);
 
-                if remove_element_component_value(&wrapped_core, request.key, &request.component_name, &request.path, request.index) {
-                    listener.config.sync_images(&request.serial_number);
-                    send_packet(handle, packet, &RemoveComponentValueResult::Removed).ok();
-                } else {
-                    send_packet(handle, packet, &RemoveComponentValueResult::FailedToRemove).ok();
+                match edit_at_revision(&wrapped_core, &[request.key], request.revision, || listener.history.track(&request.serial_number, &wrapped_core, request.key, || remove_element_component_value(&wrapped_core, request.key, &request.component_name, &request.path, request.index))) {
+                    Ok(Ok(_)) => {
+                        listener.config.sync_images(&request.serial_number);
+                        send_packet(handle, packet, &RemoveComponentValueResult::Removed).ok();
+                    }
+
+                    Ok(Err(_)) => {
+                        send_packet(handle, packet, &RemoveComponentValueResult::FailedToRemove).ok();
+                    }
+
+                    Err(revision) => {
+                        send_packet(handle, packet, &RemoveComponentValueResult::Conflict(revision)).ok();
+                    }
                 }
             } else {
                 send_packet(handle, packet, &RemoveComponentValueResult::DeviceNotFound).ok();
@@ -461,6 +605,9 @@ pub struct SetComponentValue {
     pub key: u8,
     pub component_name: String,
     pub value: UIPathValue,
+    /// Revision of the button that client has last seen, edit is rejected if button was changed since then
+    #[serde(default)]
+    pub revision: Option<u64>,
 }
 
 /// Response of [SetComponentValue] request
@@ -472,8 +619,14 @@ pub enum SetComponentValueResult {
     /// Sent if failed to set component parameter
     FailedToSet,
 
+    /// Sent if component rejected the value, contains errors for each offending field
+    InvalidValues(Vec<FieldError>),
+
     /// Sent if component value was successfully set
     Set,
+
+    /// Sent if button was changed since provided revision, contains current revision
+    Conflict(u64),
 }
 
 impl SocketData for SetComponentValue {
@@ -490,11 +643,23 @@ impl DaemonRequest for SetComponentValue {
             if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
                 let wrapped_core = CoreHandle::wrap(device.core);
 
-                if set_component_value_by_path(&wrapped_core, request.key, &request.component_name, request.value) {
-                    listener.config.sync_images(&request.serial_number);
-                    send_packet(handle, packet, &SetComponentValueResult::Set).ok();
-                } else {
-                    send_packet(handle, packet, &SetComponentValueResult::FailedToSet).ok();
+                match edit_at_revision(&wrapped_core, &[request.key], request.revision, || listener.history.track(&request.serial_number, &wrapped_core, request.key, || set_component_value_by_path(&wrapped_core, request.key, &request.component_name, request.value))) {
+                    Ok(Ok(_)) => {
+                        listener.config.sync_images(&request.serial_number);
+                        send_packet(handle, packet, &SetComponentValueResult::Set).ok();
+                    }
+
+                    Ok(Err(ComponentValueError::InvalidValues(errors))) => {
+                        send_packet(handle, packet, &SetComponentValueResult::InvalidValues(errors)).ok();
+                    }
+
+                    Ok(Err(ComponentValueError::Failed)) => {
+                        send_packet(handle, packet, &SetComponentValueResult::FailedToSet).ok();
+                    }
+
+                    Err(revision) => {
+                        send_packet(handle, packet, &SetComponentValueResult::Conflict(revision)).ok();
+                    }
                 }
             } else {
                 send_packet(handle, packet, &SetComponentValueResult::DeviceNotFound).ok();
@@ -509,6 +674,9 @@ pub struct RemoveComponent {
     pub serial_number: String,
     pub key: u8,
     pub component_name: String,
+    /// Revision of the button that client has last seen, edit is rejected if button was changed since then
+    #[serde(default)]
+    pub revision: Option<u64>,
 }
 
 /// Response of [RemoveComponent] request
@@ -520,8 +688,14 @@ pub enum RemoveComponentResult {
     /// Sent if failed to remove component
     FailedToRemove,
 
+    /// Sent if other components of the button require the component
+    Incompatible(ComponentCompatibilityError),
+
     /// Sent if component value was successfully set
     Removed,
+
+    /// Sent if button was changed since provided revision, contains current revision
+    Conflict(u64),
 }
 
 impl SocketData for RemoveComponent {
@@ -538,11 +712,20 @@ impl DaemonRequest for RemoveComponent {
             if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
                 let wrapped_core = CoreHandle::wrap(device.core);
 
-                if remove_component(&wrapped_core, request.key, &request.component_name) {
-                    send_packet(handle, packet, &RemoveComponentResult::Removed).ok();
-                } else {
-                    send_packet(handle, packet, &RemoveComponentResult::FailedToRemove).ok();
+                if let Some(button) = get_button(&wrapped_core, request.key) {
+                    let component_names = button.read().unwrap().component_names();
+
+                    if let Err(err) = listener.module_manager.check_component_removal(&component_names, &request.component_name) {
+                        send_packet(handle, packet, &RemoveComponentResult::Incompatible(err)).ok();
+                        return;
+                    }
                 }
+
+                match edit_at_revision(&wrapped_core, &[request.key], request.revision, || listener.history.track(&request.serial_number, &wrapped_core, request.key, || remove_component(&wrapped_core, request.key, &request.component_name))) {
+                    Ok(true) => send_packet(handle, packet, &RemoveComponentResult::Removed).ok(),
+                    Ok(false) => send_packet(handle, packet, &RemoveComponentResult::FailedToRemove).ok(),
+                    Err(revision) => send_packet(handle, packet, &RemoveComponentResult::Conflict(revision)).ok(),
+                };
             } else {
                 send_packet(handle, packet, &RemoveComponentResult::DeviceNotFound).ok();
             }
@@ -591,8 +774,16 @@ pub enum CopyButtonResult {
     /// Sent if there's no button to copy
     NoButton,
 
-    /// Sent if successfully copied a button
-    Copied
+    /// Sent if successfully copied a button, contains the button with its folders and images,
+    /// which can be pasted onto any device with [PasteButton]
+    Copied(PortableButton)
+}
+
+/// Button that is in clipboard
+pub struct CopiedButton {
+    /// Device that the button was copied from
+    pub serial_number: String,
+    pub button: PortableButton,
 }
 
 impl SocketData for CopyButton {
@@ -610,9 +801,15 @@ impl DaemonRequest for CopyButton {
                 let wrapped_core = CoreHandle::wrap(device.core);
 
                 if let Some(button) = get_button(&wrapped_core, request.key) {
+                    let portable = PortableButton::capture(&device.core.device_config.read().unwrap(), button.read().unwrap().deref());
+
                     let mut lock = listener.clipboard.lock().unwrap();
-                    *lock = Some(button.read().unwrap().deref().clone());
-                    send_packet(handle, packet, &CopyButtonResult::Copied).ok();
+                    *lock = Some(CopiedButton {
+                        serial_number: request.serial_number.clone(),
+                        button: portable.clone()
+                    });
+
+                    send_packet(handle, packet, &CopyButtonResult::Copied(portable)).ok();
                 } else {
                     send_packet(handle, packet, &CopyButtonResult::NoButton).ok();
                 }
@@ -623,11 +820,17 @@ impl DaemonRequest for CopyButton {
     }
 }
 
-/// Request for pasting button
+/// Request for pasting button, pastes button from clipboard if data isn't provided
 #[derive(Serialize, Deserialize)]
 pub struct PasteButton {
     pub serial_number: String,
     pub key: u8,
+    /// Button previously received from [CopyButton], for pasting buttons copied elsewhere or saved as snippets
+    #[serde(default)]
+    pub data: Option<PortableButton>,
+    /// Revision of the button that client has last seen, edit is rejected if button was changed since then
+    #[serde(default)]
+    pub revision: Option<u64>,
 }
 
 /// Response of [PasteButton] request
@@ -640,7 +843,10 @@ pub enum PasteButtonResult {
     FailedToPaste,
 
     /// Sent if successfully pasted button
-    Pasted
+    Pasted,
+
+    /// Sent if button was changed since provided revision, contains current revision
+    Conflict(u64),
 }
 
 impl SocketData for PasteButton {
@@ -652,24 +858,51 @@ impl SocketData for PasteButtonResult {
 }
 
 impl DaemonRequest for PasteButton {
+    const CHANGES_DEVICE_CONFIG: bool = true;
+
     fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
         if let Ok(request) = parse_packet_to_data::<PasteButton>(packet) {
             if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
                 let wrapped_core = CoreHandle::wrap(device.core);
 
-                let clipboard = listener.clipboard.lock().unwrap();
+                let pasted = edit_at_revision(&wrapped_core, &[request.key], request.revision, || if let Some(data) = request.data {
+                    listener.history.track(&request.serial_number, &wrapped_core, request.key, || paste_portable_button(listener, &wrapped_core, &request.serial_number, request.key, data))
+                } else {
+                    let clipboard = listener.clipboard.lock().unwrap();
 
-                if clipboard.is_some() {
-                    if paste_button(&wrapped_core, request.key, clipboard.as_ref().unwrap()) {
-                        send_packet(handle, packet, &PasteButtonResult::Pasted).ok();
-                        return;
+                    match clipboard.as_ref() {
+                        // Letting modules copy their components if button comes from the same device
+                        Some(copied) if copied.serial_number == request.serial_number => {
+                            listener.history.track(&request.serial_number, &wrapped_core, request.key, || paste_button(&wrapped_core, request.key, &copied.button.button))
+                        }
+
+                        Some(copied) => {
+                            let data = copied.button.clone();
+                            drop(clipboard);
+
+                            listener.history.track(&request.serial_number, &wrapped_core, request.key, || paste_portable_button(listener, &wrapped_core, &request.serial_number, request.key, data))
+                        }
+
+                        None => false,
                     }
-                }
+                });
 
-                send_packet(handle, packet, &PasteButtonResult::FailedToPaste).ok();
+                match pasted {
+                    Ok(true) => send_packet(handle, packet, &PasteButtonResult::Pasted).ok(),
+                    Ok(false) => send_packet(handle, packet, &PasteButtonResult::FailedToPaste).ok(),
+                    Err(revision) => send_packet(handle, packet, &PasteButtonResult::Conflict(revision)).ok(),
+                };
             } else {
                 send_packet(handle, packet, &PasteButtonResult::DeviceNotFound).ok();
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Adds folders and images of portable button to the device and sets the button
+fn paste_portable_button(listener: &DaemonListener, core: &CoreHandle, serial_number: &str, key: u8, data: PortableButton) -> bool {
+    let (button, images) = data.unpack(&mut core.core.device_config.write().unwrap());
+    listener.config.add_serialized_images(serial_number, images);
+
+    set_button(core, key, make_button_unique(button))
+}
@@ -0,0 +1,114 @@
+//! Requests related to connected clients
+//!
+//! These requests depend on connection they came from or need socket manager, so socket implementations process them
+//! with [process_client_request] before handing packets over to listeners
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use streamduck_core::socket::{check_packet_for_data, ClientInfo, parse_packet_to_data, PermissionDenied, send_packet, SocketClient, SocketData, SocketHandle, SocketManager, SocketPacket};
+
+/// Request for reporting name and version of the client for current connection
+#[derive(Serialize, Deserialize)]
+pub struct IdentifyClient {
+    pub name: String,
+    pub version: String,
+    /// Restricts connection to requests that don't change anything, for dashboards and stream overlays
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// Response of [IdentifyClient] request
+#[derive(Serialize, Deserialize)]
+pub enum IdentifyClientResult {
+    /// Sent if client was identified, contains ID of current connection
+    Identified(u64),
+}
+
+impl SocketData for IdentifyClient {
+    const NAME: &'static str = "identify_client";
+}
+
+impl SocketData for IdentifyClientResult {
+    const NAME: &'static str = "identify_client";
+}
+
+/// Request for listing connected clients
+#[derive(Serialize, Deserialize)]
+pub struct ListClients {
+    pub clients: Vec<ClientInfo>,
+}
+
+impl SocketData for ListClients {
+    const NAME: &'static str = "list_clients";
+}
+
+/// Request for disconnecting a client
+#[derive(Serialize, Deserialize)]
+pub struct DisconnectClient {
+    pub id: u64,
+}
+
+/// Response of [DisconnectClient] request
+#[derive(Serialize, Deserialize)]
+pub enum DisconnectClientResult {
+    /// Sent if client wasn't found
+    ClientNotFound,
+
+    /// Sent if client was disconnected
+    Disconnected,
+}
+
+impl SocketData for DisconnectClient {
+    const NAME: &'static str = "disconnect_client";
+}
+
+impl SocketData for DisconnectClientResult {
+    const NAME: &'static str = "disconnect_client";
+}
+
+/// Processes client requests, client should be the one packet came from.
+/// Returns true if packet was a client request
+pub fn process_client_request(socket_manager: &SocketManager, client: &Arc<SocketClient>, handle: SocketHandle, packet: &SocketPacket) -> bool {
+    if check_packet_for_data::<IdentifyClient>(packet) {
+        if let Ok(request) = parse_packet_to_data::<IdentifyClient>(packet) {
+            client.identify(request.name, request.version);
+
+            if request.read_only {
+                client.make_read_only();
+            }
+
+            send_packet(handle, packet, &IdentifyClientResult::Identified(client.id())).ok();
+        }
+
+        return true;
+    }
+
+    if check_packet_for_data::<ListClients>(packet) {
+        send_packet(handle, packet, &ListClients {
+            clients: socket_manager.list_clients()
+        }).ok();
+
+        return true;
+    }
+
+    if check_packet_for_data::<DisconnectClient>(packet) {
+        if let Ok(request) = parse_packet_to_data::<DisconnectClient>(packet) {
+            // Responding first, in case client is disconnecting itself
+            if request.id == client.id() {
+                send_packet(handle, packet, &DisconnectClientResult::Disconnected).ok();
+                socket_manager.disconnect_client(request.id);
+            } else if client.is_read_only() {
+                send_packet(handle, packet, &PermissionDenied {
+                    request: packet.ty.clone()
+                }).ok();
+            } else if socket_manager.disconnect_client(request.id) {
+                send_packet(handle, packet, &DisconnectClientResult::Disconnected).ok();
+            } else {
+                send_packet(handle, packet, &DisconnectClientResult::ClientNotFound).ok();
+            }
+        }
+
+        return true;
+    }
+
+    false
+}
@@ -1,12 +1,14 @@
 //! Requests related to configs
 use std::io::Read;
-use std::ops::Deref;
 use flate2::Compression;
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
 use serde::{Deserialize, Serialize};
-use streamduck_core::config::{ConfigError, DeviceConfig};
+use streamduck_core::adapt::adapt_config;
+use streamduck_core::config::{ConfigError, ConfigMilestone, DeviceConfig, migrate_device_config};
 use streamduck_core::core::methods::{CoreHandle, reset_stack, set_brightness};
+use streamduck_core::core::manager::DeviceData;
+use streamduck_core::review::{ConfigDiff, ConfigReview, review_config};
 use streamduck_core::socket::{check_packet_for_data, parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket};
 use streamduck_core::util::make_panel_unique;
 use crate::daemon_data::{DaemonListener, DaemonRequest};
@@ -229,6 +231,9 @@ impl DaemonRequest for GetDeviceConfig {
 #[derive(Serialize, Deserialize)]
 pub struct ExportDeviceConfig {
     pub serial_number: String,
+    /// If data of images used by the layout should be embedded, so config can be imported on another machine
+    #[serde(default)]
+    pub include_images: bool,
 }
 
 /// Response of [ExportDeviceConfig] request
@@ -256,8 +261,18 @@ impl DaemonRequest for ExportDeviceConfig {
     fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
         if let Ok(request) = parse_packet_to_data::<ExportDeviceConfig>(packet) {
             if let Some(config) = listener.config.get_device_config(&request.serial_number) {
-                let config_handle = config.read().unwrap();
-                let config = serde_json::to_string(config_handle.deref()).unwrap();
+                listener.config.sync_images(&request.serial_number);
+
+                let mut config = config.read().unwrap().clone();
+
+                if request.include_images {
+                    let referenced = config.referenced_images();
+                    config.images.retain(|identifier, _| referenced.contains(identifier));
+                } else {
+                    config.images.clear();
+                }
+
+                let config = serde_json::to_string(&config).unwrap();
 
                 // Compressing data
                 let mut encoder = GzEncoder::new(vec![], Compression::default());
@@ -280,6 +295,9 @@ impl DaemonRequest for ExportDeviceConfig {
 pub struct ImportDeviceConfig {
     pub serial_number: String,
     pub config: String,
+    /// Only reviews the config without applying it
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 /// Response of [ImportDeviceConfig] request
@@ -296,6 +314,9 @@ pub enum ImportDeviceConfigResult {
 
     /// Sent if successfully imported
     Imported,
+
+    /// Sent if dry run was requested, contains problems of the config and changes it would make
+    Reviewed(ConfigReview),
 }
 
 impl SocketData for ImportDeviceConfig {
@@ -309,53 +330,446 @@ impl SocketData for ImportDeviceConfigResult {
 impl DaemonRequest for ImportDeviceConfig {
     fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
         if let Ok(request) = parse_packet_to_data::<ImportDeviceConfig>(packet) {
-            if let Ok(byte_array) = base64::decode(&request.config) {
-                let mut decoder = GzDecoder::new(&byte_array[..]);
-                let mut config = String::new();
+            let mut config = match decode_device_config(&request.config) {
+                Some(config) => config,
+                None => {
+                    send_packet(handle, packet, &ImportDeviceConfigResult::InvalidConfig).ok();
+                    return;
+                }
+            };
+
+            let device = match listener.core_manager.get_device(&request.serial_number) {
+                Some(device) => device,
+                None => {
+                    send_packet(handle, packet, &ImportDeviceConfigResult::DeviceNotFound).ok();
+                    return;
+                }
+            };
+
+            prepare_imported_config(listener, &device, &mut config);
+
+            if request.dry_run {
+                let current = listener.config.get_device_config(&request.serial_number);
+                let current = current.as_ref().map(|x| x.read().unwrap());
+                let review = review_config(&config, current.as_deref(), &listener.module_manager);
+
+                send_packet(handle, packet, &ImportDeviceConfigResult::Reviewed(review)).ok();
+                return;
+            }
+
+            listener.config.set_device_config(&request.serial_number, config.clone());
+            listener.config.sync_images(&request.serial_number);
+
+            match listener.config.save_device_config(&request.serial_number) {
+                Ok(_) => {
+                    let wrapped_core = CoreHandle::wrap(device.core);
+
+                    reset_stack(&wrapped_core, make_panel_unique(config.layout));
+                    set_brightness(&wrapped_core, config.brightness);
+
+                    send_packet(handle, packet, &ImportDeviceConfigResult::Imported).ok();
+                }
+
+                Err(err) => {
+                    match err {
+                        ConfigError::DeviceNotFound => {
+                            send_packet(handle, packet, &ImportDeviceConfigResult::DeviceNotFound).ok();
+                        }
+
+                        _ => {
+                            send_packet(handle, packet, &ImportDeviceConfigResult::FailedToSave).ok();
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Decodes device config that was exported with [ExportDeviceConfig]
+fn decode_device_config(encoded: &str) -> Option<DeviceConfig> {
+    let byte_array = base64::decode(encoded).ok()?;
+
+    let mut decoder = GzDecoder::new(&byte_array[..]);
+    let mut config = String::new();
+    decoder.read_to_string(&mut config).ok()?;
+
+    serde_json::from_str(&config).map_err(ConfigError::from).and_then(migrate_device_config).ok()
+}
+
+/// Makes imported config fit the device it's imported to
+fn prepare_imported_config(listener: &DaemonListener, device: &DeviceData, config: &mut DeviceConfig) {
+    let exported_kind = config.kind();
+
+    config.serial = device.serial.clone();
+    config.vid = device.vid;
+    config.pid = device.pid;
+
+    // Config could've been exported from different kind of device
+    let device_kind = config.kind();
+    let report = adapt_config(config, exported_kind, device_kind);
+
+    if !report.is_empty() {
+        log::info!("Adapted imported config for {}: {} buttons moved, {} moved into {} overflow folders", device.serial, report.moved, report.overflowed, report.overflow_folders);
+    }
+
+    // Keeping local images, so configs exported without images still work on the same machine
+    for (identifier, image) in listener.config.get_images(&device.serial).unwrap_or_default() {
+        config.images.entry(identifier).or_insert(image);
+    }
+}
+
+/// Request for comparing device config with current config of the device, without applying it
+#[derive(Serialize, Deserialize)]
+pub struct DiffDeviceConfig {
+    pub serial_number: String,
+    /// Config in the same format as [ImportDeviceConfig] takes
+    pub config: String,
+}
+
+/// Response of [DiffDeviceConfig] request
+#[derive(Serialize, Deserialize)]
+pub enum DiffDeviceConfigResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if config was invalid
+    InvalidConfig,
+
+    /// Sent with changes that importing the config would make
+    Diff(ConfigDiff),
+}
+
+impl SocketData for DiffDeviceConfig {
+    const NAME: &'static str = "diff_device_config";
+}
+
+impl SocketData for DiffDeviceConfigResult {
+    const NAME: &'static str = "diff_device_config";
+}
+
+impl DaemonRequest for DiffDeviceConfig {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<DiffDeviceConfig>(packet) {
+            let mut config = match decode_device_config(&request.config) {
+                Some(config) => config,
+                None => {
+                    send_packet(handle, packet, &DiffDeviceConfigResult::InvalidConfig).ok();
+                    return;
+                }
+            };
+
+            let (device, current) = match (listener.core_manager.get_device(&request.serial_number), listener.config.get_device_config(&request.serial_number)) {
+                (Some(device), Some(current)) => (device, current),
+                _ => {
+                    send_packet(handle, packet, &DiffDeviceConfigResult::DeviceNotFound).ok();
+                    return;
+                }
+            };
+
+            prepare_imported_config(listener, &device, &mut config);
+
+            let diff = ConfigDiff::between(&current.read().unwrap(), &config);
+            send_packet(handle, packet, &DiffDeviceConfigResult::Diff(diff)).ok();
+        }
+    }
+}
+
+/// Request for restoring device config from one of rotated backups
+#[derive(Serialize, Deserialize)]
+pub struct RestoreDeviceConfigBackup {
+    pub serial_number: String,
+    /// Number of the backup, 1 is the most recent one
+    pub backup: usize,
+}
+
+/// Response of [RestoreDeviceConfigBackup] request
+#[derive(Serialize, Deserialize)]
+pub enum RestoreDeviceConfigBackupResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if backup with such number doesn't exist
+    BackupNotFound,
+
+    /// Sent if backup failed to load or save
+    FailedToRestore,
+
+    /// Sent if config was successfully restored
+    Restored,
+}
+
+impl SocketData for RestoreDeviceConfigBackup {
+    const NAME: &'static str = "restore_device_config_backup";
+}
+
+impl SocketData for RestoreDeviceConfigBackupResult {
+    const NAME: &'static str = "restore_device_config_backup";
+}
+
+impl DaemonRequest for RestoreDeviceConfigBackup {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<RestoreDeviceConfigBackup>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
+                match listener.config.restore_device_config_backup(&request.serial_number, request.backup) {
+                    Ok(_) => {
+                        if !device.core.is_closed() {
+                            if let Some(dvc_cfg) = listener.config.get_device_config(&request.serial_number) {
+                                let config = dvc_cfg.read().unwrap();
+                                let wrapped_core = CoreHandle::wrap(device.core);
+
+                                reset_stack(&wrapped_core, make_panel_unique(config.layout.clone()));
+                                set_brightness(&wrapped_core, config.brightness);
+                            }
+                        }
+
+                        send_packet(handle, packet, &RestoreDeviceConfigBackupResult::Restored).ok();
+                    }
+
+                    Err(ConfigError::BackupNotFound) => {
+                        send_packet(handle, packet, &RestoreDeviceConfigBackupResult::BackupNotFound).ok();
+                    }
+
+                    Err(err) => {
+                        log::error!("Error encountered while restoring config backup for {}: {:?}", request.serial_number, err);
+                        send_packet(handle, packet, &RestoreDeviceConfigBackupResult::FailedToRestore).ok();
+                    }
+                }
+            } else {
+                send_packet(handle, packet, &RestoreDeviceConfigBackupResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
+
+/// Request for saving snapshot of all device configs and plugin settings under a name
+#[derive(Serialize, Deserialize)]
+pub struct CreateConfigMilestone {
+    pub name: String,
+}
+
+/// Response of [CreateConfigMilestone] request
+#[derive(Serialize, Deserialize)]
+pub enum CreateConfigMilestoneResult {
+    /// Sent if name is empty or can't be used as a file name
+    InvalidName,
+
+    /// Sent if milestone failed to save
+    FailedToSave,
+
+    /// Sent if milestone was created
+    Created,
+}
+
+impl SocketData for CreateConfigMilestone {
+    const NAME: &'static str = "create_config_milestone";
+}
+
+impl SocketData for CreateConfigMilestoneResult {
+    const NAME: &'static str = "create_config_milestone";
+}
+
+impl DaemonRequest for CreateConfigMilestone {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<CreateConfigMilestone>(packet) {
+            match listener.config.create_config_milestone(&request.name) {
+                Ok(_) => {
+                    send_packet(handle, packet, &CreateConfigMilestoneResult::Created).ok();
+                }
+
+                Err(ConfigError::InvalidMilestoneName) => {
+                    send_packet(handle, packet, &CreateConfigMilestoneResult::InvalidName).ok();
+                }
+
+                Err(err) => {
+                    log::error!("Error encountered while creating milestone '{}': {:?}", request.name, err);
+                    send_packet(handle, packet, &CreateConfigMilestoneResult::FailedToSave).ok();
+                }
+            }
+        }
+    }
+}
 
-                if let Ok(_) = decoder.read_to_string(&mut config) {
-                    if let Ok(mut config) = serde_json::from_str::<DeviceConfig>(&config) {
-                        if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
-                            config.serial = device.serial.clone();
-                            config.vid = device.vid;
-                            config.pid = device.pid;
+/// Request for listing config milestones
+#[derive(Serialize, Deserialize)]
+pub struct ListConfigMilestones {
+    pub milestones: Vec<ConfigMilestone>
+}
+
+impl SocketData for ListConfigMilestones {
+    const NAME: &'static str = "list_config_milestones";
+}
+
+impl DaemonRequest for ListConfigMilestones {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if check_packet_for_data::<ListConfigMilestones>(packet) {
+            send_packet(handle, packet, &ListConfigMilestones {
+                milestones: listener.config.list_config_milestones()
+            }).ok();
+        }
+    }
+}
+
+/// Request for restoring all device configs and plugin settings from a milestone
+#[derive(Serialize, Deserialize)]
+pub struct RestoreConfigMilestone {
+    pub name: String,
+}
+
+/// Response of [RestoreConfigMilestone] request
+#[derive(Serialize, Deserialize)]
+pub enum RestoreConfigMilestoneResult {
+    /// Sent if milestone with such name doesn't exist
+    MilestoneNotFound,
 
-                            listener.config.set_device_config(&request.serial_number, config.clone());
+    /// Sent if milestone failed to load or configs failed to save
+    FailedToRestore,
 
-                            match listener.config.save_device_config(&request.serial_number) {
-                                Ok(_) => {
+    /// Sent if milestone was restored
+    Restored,
+}
+
+impl SocketData for RestoreConfigMilestone {
+    const NAME: &'static str = "restore_config_milestone";
+}
+
+impl SocketData for RestoreConfigMilestoneResult {
+    const NAME: &'static str = "restore_config_milestone";
+}
+
+impl DaemonRequest for RestoreConfigMilestone {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<RestoreConfigMilestone>(packet) {
+            match listener.config.restore_config_milestone(&request.name) {
+                Ok(serials) => {
+                    for serial in serials {
+                        if let Some(device) = listener.core_manager.get_device(&serial) {
+                            if !device.core.is_closed() {
+                                if let Some(dvc_cfg) = listener.config.get_device_config(&serial) {
+                                    let config = dvc_cfg.read().unwrap();
                                     let wrapped_core = CoreHandle::wrap(device.core);
 
-                                    reset_stack(&wrapped_core, make_panel_unique(config.layout));
+                                    reset_stack(&wrapped_core, make_panel_unique(config.layout.clone()));
                                     set_brightness(&wrapped_core, config.brightness);
-
-                                    send_packet(handle, packet, &ImportDeviceConfigResult::Imported).ok();
                                 }
+                            }
+                        }
+                    }
+
+                    send_packet(handle, packet, &RestoreConfigMilestoneResult::Restored).ok();
+                }
+
+                Err(ConfigError::MilestoneNotFound) | Err(ConfigError::InvalidMilestoneName) => {
+                    send_packet(handle, packet, &RestoreConfigMilestoneResult::MilestoneNotFound).ok();
+                }
+
+                Err(err) => {
+                    log::error!("Error encountered while restoring milestone '{}': {:?}", request.name, err);
+                    send_packet(handle, packet, &RestoreConfigMilestoneResult::FailedToRestore).ok();
+                }
+            }
+        }
+    }
+}
+
+/// Request for exporting every device config, daemon settings, plugin settings and images as a single archive
+#[derive(Serialize, Deserialize)]
+pub enum ExportAllConfigsResult {
+    /// Sent if error happened while packing configs
+    FailedToExport,
+
+    /// Sent if successfully exported, contains base64 encoded gzipped tar archive
+    Exported(String),
+}
+
+impl SocketData for ExportAllConfigsResult {
+    const NAME: &'static str = "export_all_configs";
+}
+
+impl DaemonRequest for ExportAllConfigsResult {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if check_packet_for_data::<ExportAllConfigsResult>(packet) {
+            match listener.config.export_bundle() {
+                Ok(bundle) => {
+                    send_packet(handle, packet, &ExportAllConfigsResult::Exported(base64::encode(bundle))).ok();
+                }
+
+                Err(err) => {
+                    log::error!("Error encountered while exporting configs: {:?}", err);
+                    send_packet(handle, packet, &ExportAllConfigsResult::FailedToExport).ok();
+                }
+            }
+        }
+    }
+}
+
+/// Request for importing archive made by [ExportAllConfigsResult]
+#[derive(Serialize, Deserialize)]
+pub struct ImportAllConfigs {
+    /// Base64 encoded archive
+    pub bundle: String,
+}
 
-                                Err(err) => {
-                                    match err {
-                                        ConfigError::IoError(_) | ConfigError::ParseError(_) => {
-                                            send_packet(handle, packet, &ImportDeviceConfigResult::FailedToSave).ok();
-                                        }
+/// Response of [ImportAllConfigs] request
+#[derive(Serialize, Deserialize)]
+pub enum ImportAllConfigsResult {
+    /// Sent if archive is corrupted or contains invalid configs
+    InvalidBundle,
+
+    /// Sent if configs failed to save
+    FailedToSave,
+
+    /// Sent if successfully imported, contains serial numbers of imported devices
+    Imported(Vec<String>),
+}
+
+impl SocketData for ImportAllConfigs {
+    const NAME: &'static str = "import_all_configs";
+}
+
+impl SocketData for ImportAllConfigsResult {
+    const NAME: &'static str = "import_all_configs";
+}
+
+impl DaemonRequest for ImportAllConfigs {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<ImportAllConfigs>(packet) {
+            let bundle = if let Ok(bundle) = base64::decode(&request.bundle) {
+                bundle
+            } else {
+                send_packet(handle, packet, &ImportAllConfigsResult::InvalidBundle).ok();
+                return;
+            };
+
+            match listener.config.import_bundle(&bundle) {
+                Ok(serials) => {
+                    for serial in &serials {
+                        if let Some(device) = listener.core_manager.get_device(serial) {
+                            if !device.core.is_closed() {
+                                if let Some(dvc_cfg) = listener.config.get_device_config(serial) {
+                                    let config = dvc_cfg.read().unwrap();
+                                    let wrapped_core = CoreHandle::wrap(device.core);
 
-                                        ConfigError::DeviceNotFound => {
-                                            send_packet(handle, packet, &ImportDeviceConfigResult::DeviceNotFound).ok();
-                                        }
-                                    }
+                                    reset_stack(&wrapped_core, make_panel_unique(config.layout.clone()));
+                                    set_brightness(&wrapped_core, config.brightness);
                                 }
                             }
-                        } else {
-                            send_packet(handle, packet, &ImportDeviceConfigResult::DeviceNotFound).ok();
                         }
-                    } else {
-                        send_packet(handle, packet, &ImportDeviceConfigResult::InvalidConfig).ok();
                     }
-                } else {
-                    send_packet(handle, packet, &ImportDeviceConfigResult::InvalidConfig).ok();
+
+                    send_packet(handle, packet, &ImportAllConfigsResult::Imported(serials)).ok();
+                }
+
+                Err(err @ ConfigError::IoError(_)) => {
+                    log::error!("Error encountered while importing configs: {:?}", err);
+                    send_packet(handle, packet, &ImportAllConfigsResult::FailedToSave).ok();
+                }
+
+                Err(err) => {
+                    log::warn!("Rejected config bundle: {:?}", err);
+                    send_packet(handle, packet, &ImportAllConfigsResult::InvalidBundle).ok();
                 }
-            } else {
-                send_packet(handle, packet, &ImportDeviceConfigResult::InvalidConfig).ok();
             }
         }
     }
-}
\ No newline at end of file
+}
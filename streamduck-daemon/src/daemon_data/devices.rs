@@ -3,8 +3,13 @@ use serde::{Deserialize, Serialize};
 use strum_macros::Display;
 use streamduck_core::socket::{check_packet_for_data, parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket};
 use crate::daemon_data::{DaemonListener, DaemonRequest};
-use streamduck_core::core::methods::{CoreHandle, set_brightness};
+use streamduck_core::core::methods::{commit_changes, CoreHandle, set_brightness};
+use streamduck_core::core::button::Button;
+use streamduck_core::core::mirror::MirrorError;
+use streamduck_core::modules::folders::list_config_folders;
 use streamduck_core::streamdeck;
+use streamduck_core::thread::orientation::DeviceOrientation;
+use streamduck_core::thread::transition::PanelTransition;
 
 /// Request for getting device list
 #[derive(Serialize, Deserialize)]
@@ -240,6 +245,8 @@ impl SocketData for SetBrightnessResult {
 }
 
 impl DaemonRequest for SetBrightness {
+    const CHANGES_DEVICE_CONFIG: bool = true;
+
     fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
         if let Ok(request) = parse_packet_to_data::<SetBrightness>(packet) {
             if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
@@ -253,4 +260,265 @@ impl DaemonRequest for SetBrightness {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Request for setting rotation of the device, for devices that are mounted sideways or upside down
+#[derive(Serialize, Deserialize)]
+pub struct SetDeviceOrientation {
+    pub serial_number: String,
+    pub orientation: DeviceOrientation,
+}
+
+/// Response of [SetDeviceOrientation] request
+#[derive(Serialize, Deserialize)]
+pub enum SetDeviceOrientationResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if orientation was successfully set
+    Set,
+}
+
+impl SocketData for SetDeviceOrientation {
+    const NAME: &'static str = "set_device_orientation";
+}
+
+impl SocketData for SetDeviceOrientationResult {
+    const NAME: &'static str = "set_device_orientation";
+}
+
+impl DaemonRequest for SetDeviceOrientation {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<SetDeviceOrientation>(packet) {
+            if let Some(config) = listener.config.get_device_config(&request.serial_number) {
+                config.write().unwrap().orientation = request.orientation;
+
+                if let Err(err) = listener.config.save_device_config(&request.serial_number) {
+                    log::error!("Error encountered while saving orientation for {}: {:?}", request.serial_number, err);
+                }
+
+                if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
+                    device.core.mark_for_redraw();
+                }
+
+                send_packet(handle, packet, &SetDeviceOrientationResult::Set).ok();
+            } else {
+                send_packet(handle, packet, &SetDeviceOrientationResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
+
+/// Request for mirroring key layout of the device horizontally, for left-handed use
+#[derive(Serialize, Deserialize)]
+pub struct SetMirroredLayout {
+    pub serial_number: String,
+    pub mirrored: bool,
+    /// If images should be flipped horizontally too
+    #[serde(default)]
+    pub flip_images: bool,
+}
+
+/// Response of [SetMirroredLayout] request
+#[derive(Serialize, Deserialize)]
+pub enum SetMirroredLayoutResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if layout mirroring was successfully set
+    Set,
+}
+
+impl SocketData for SetMirroredLayout {
+    const NAME: &'static str = "set_mirrored_layout";
+}
+
+impl SocketData for SetMirroredLayoutResult {
+    const NAME: &'static str = "set_mirrored_layout";
+}
+
+impl DaemonRequest for SetMirroredLayout {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<SetMirroredLayout>(packet) {
+            if let Some(config) = listener.config.get_device_config(&request.serial_number) {
+                let mut config_handle = config.write().unwrap();
+                config_handle.mirrored = request.mirrored;
+                config_handle.flip_images = request.flip_images;
+                drop(config_handle);
+
+                if let Err(err) = listener.config.save_device_config(&request.serial_number) {
+                    log::error!("Error encountered while saving mirrored layout for {}: {:?}", request.serial_number, err);
+                }
+
+                if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
+                    device.core.mark_for_redraw();
+                }
+
+                send_packet(handle, packet, &SetMirroredLayoutResult::Set).ok();
+            } else {
+                send_packet(handle, packet, &SetMirroredLayoutResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
+
+/// Request for setting folder that gets opened and buttons that get triggered once device connects
+#[derive(Serialize, Deserialize)]
+pub struct SetDeviceStartup {
+    pub serial_number: String,
+    /// Folder ID of startup folder, root panel is shown if not provided
+    #[serde(default)]
+    pub folder_id: Option<String>,
+    #[serde(default)]
+    pub actions: Vec<Button>,
+}
+
+/// Response of [SetDeviceStartup] request
+#[derive(Serialize, Deserialize)]
+pub enum SetDeviceStartupResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if startup folder doesn't exist in device config
+    FolderNotFound,
+
+    /// Sent if startup folder and actions were successfully set
+    Set,
+}
+
+impl SocketData for SetDeviceStartup {
+    const NAME: &'static str = "set_device_startup";
+}
+
+impl SocketData for SetDeviceStartupResult {
+    const NAME: &'static str = "set_device_startup";
+}
+
+impl DaemonRequest for SetDeviceStartup {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<SetDeviceStartup>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
+                // Making sure folders that weren't committed yet can be used
+                if !device.core.is_closed() {
+                    commit_changes(&CoreHandle::wrap(device.core));
+                }
+            }
+
+            if let Some(config) = listener.config.get_device_config(&request.serial_number) {
+                let mut config_handle = config.write().unwrap();
+
+                if let Some(folder_id) = &request.folder_id {
+                    if !list_config_folders(&config_handle).iter().any(|x| &x.id == folder_id) {
+                        send_packet(handle, packet, &SetDeviceStartupResult::FolderNotFound).ok();
+                        return;
+                    }
+                }
+
+                config_handle.startup_folder = request.folder_id;
+                config_handle.startup_actions = request.actions;
+                drop(config_handle);
+
+                if let Err(err) = listener.config.save_device_config(&request.serial_number) {
+                    log::error!("Error encountered while saving startup settings for {}: {:?}", request.serial_number, err);
+                }
+
+                send_packet(handle, packet, &SetDeviceStartupResult::Set).ok();
+            } else {
+                send_packet(handle, packet, &SetDeviceStartupResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
+
+/// Request for making device mirror current screen of another device, stops mirroring if source isn't provided
+#[derive(Serialize, Deserialize)]
+pub struct SetDeviceMirror {
+    pub serial_number: String,
+    pub source: Option<String>,
+}
+
+/// Response of [SetDeviceMirror] request
+#[derive(Serialize, Deserialize)]
+pub enum SetDeviceMirrorResult {
+    /// Sent if either of devices wasn't found
+    DeviceNotFound,
+
+    /// Sent if device was asked to mirror itself
+    SameDevice,
+
+    /// Sent if source device is mirroring another device itself
+    SourceIsMirroring,
+
+    /// Sent if device is mirrored by other devices
+    DeviceIsMirrored,
+
+    /// Sent if mirror was successfully set
+    Set,
+}
+
+impl SocketData for SetDeviceMirror {
+    const NAME: &'static str = "set_device_mirror";
+}
+
+impl SocketData for SetDeviceMirrorResult {
+    const NAME: &'static str = "set_device_mirror";
+}
+
+impl DaemonRequest for SetDeviceMirror {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<SetDeviceMirror>(packet) {
+            let result = match listener.core_manager.set_mirror(&request.serial_number, request.source.as_deref()) {
+                Ok(_) => SetDeviceMirrorResult::Set,
+                Err(MirrorError::DeviceNotFound) => SetDeviceMirrorResult::DeviceNotFound,
+                Err(MirrorError::SameDevice) => SetDeviceMirrorResult::SameDevice,
+                Err(MirrorError::SourceIsMirroring) => SetDeviceMirrorResult::SourceIsMirroring,
+                Err(MirrorError::DeviceIsMirrored) => SetDeviceMirrorResult::DeviceIsMirrored,
+            };
+
+            send_packet(handle, packet, &result).ok();
+        }
+    }
+}
+
+/// Request for setting transition of device's panels, global transition is used if transition isn't provided
+#[derive(Serialize, Deserialize)]
+pub struct SetPanelTransition {
+    pub serial_number: String,
+    pub transition: Option<PanelTransition>,
+}
+
+/// Response of [SetPanelTransition] request
+#[derive(Serialize, Deserialize)]
+pub enum SetPanelTransitionResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if transition was successfully set
+    Set,
+}
+
+impl SocketData for SetPanelTransition {
+    const NAME: &'static str = "set_panel_transition";
+}
+
+impl SocketData for SetPanelTransitionResult {
+    const NAME: &'static str = "set_panel_transition";
+}
+
+impl DaemonRequest for SetPanelTransition {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<SetPanelTransition>(packet) {
+            if let Some(config) = listener.config.get_device_config(&request.serial_number) {
+                config.write().unwrap().transition = request.transition;
+
+                if let Err(err) = listener.config.save_device_config(&request.serial_number) {
+                    log::error!("Error encountered while saving transition for {}: {:?}", request.serial_number, err);
+                }
+
+                send_packet(handle, packet, &SetPanelTransitionResult::Set).ok();
+            } else {
+                send_packet(handle, packet, &SetPanelTransitionResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
@@ -0,0 +1,293 @@
+//! Requests related to folders kept in device configs
+use serde::{Deserialize, Serialize};
+use streamduck_core::config::DeviceConfig;
+use streamduck_core::core::methods::{commit_changes, CoreHandle, reset_stack, uncommitted_config};
+use streamduck_core::modules::folders::{delete_config_folder, config_folder_tree, find_config_buttons, FolderInfo, FolderTree, FoundButton, list_config_folders, remove_orphaned_config_folders, rename_config_folder};
+use streamduck_core::socket::{parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket};
+use streamduck_core::util::make_panel_unique;
+use crate::daemon_data::{DaemonListener, DaemonRequest};
+
+/// Gets device config with changes that weren't committed yet, without committing them
+fn current_config(listener: &DaemonListener, serial: &str) -> Option<DeviceConfig> {
+    if let Some(device) = listener.core_manager.get_device(serial) {
+        if !device.core.is_closed() {
+            return Some(uncommitted_config(&CoreHandle::wrap(device.core)));
+        }
+    }
+
+    Some(listener.config.get_device_config(serial)?.read().unwrap().clone())
+}
+
+/// Request for listing all folders of a device
+#[derive(Serialize, Deserialize)]
+pub struct ListFolders {
+    pub serial_number: String,
+}
+
+/// Response of [ListFolders] request
+#[derive(Serialize, Deserialize)]
+pub enum ListFoldersResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if device was found, contains folders sorted by name
+    Folders(Vec<FolderInfo>),
+}
+
+impl SocketData for ListFolders {
+    const NAME: &'static str = "list_folders";
+}
+
+impl SocketData for ListFoldersResult {
+    const NAME: &'static str = "list_folders";
+}
+
+impl DaemonRequest for ListFolders {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<ListFolders>(packet) {
+            if let Some(config) = current_config(listener, &request.serial_number) {
+                let folders = list_config_folders(&config);
+                send_packet(handle, packet, &ListFoldersResult::Folders(folders)).ok();
+            } else {
+                send_packet(handle, packet, &ListFoldersResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
+
+/// Request for getting whole folder hierarchy of a device as a tree
+#[derive(Serialize, Deserialize)]
+pub struct GetFolderTree {
+    pub serial_number: String,
+    /// Includes buttons of every panel in the tree
+    #[serde(default)]
+    pub include_buttons: bool,
+}
+
+/// Response of [GetFolderTree] request
+#[derive(Serialize, Deserialize)]
+pub enum GetFolderTreeResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if device was found, contains tree starting at root screen
+    Tree(FolderTree),
+}
+
+impl SocketData for GetFolderTree {
+    const NAME: &'static str = "get_folder_tree";
+}
+
+impl SocketData for GetFolderTreeResult {
+    const NAME: &'static str = "get_folder_tree";
+}
+
+impl DaemonRequest for GetFolderTree {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<GetFolderTree>(packet) {
+            if let Some(config) = current_config(listener, &request.serial_number) {
+                let tree = config_folder_tree(&config, request.include_buttons);
+                send_packet(handle, packet, &GetFolderTreeResult::Tree(tree)).ok();
+            } else {
+                send_packet(handle, packet, &GetFolderTreeResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
+
+/// Request for finding buttons anywhere on a device, by component they have or text in their component values
+#[derive(Serialize, Deserialize)]
+pub struct FindButtons {
+    pub serial_number: String,
+    /// Only buttons that have the component
+    #[serde(default)]
+    pub component_name: Option<String>,
+    /// Only buttons that contain the text in component values, case-insensitive
+    #[serde(default)]
+    pub query: Option<String>,
+}
+
+/// Response of [FindButtons] request
+#[derive(Serialize, Deserialize)]
+pub enum FindButtonsResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if device was found, contains buttons with folders leading to them
+    Found(Vec<FoundButton>),
+}
+
+impl SocketData for FindButtons {
+    const NAME: &'static str = "find_buttons";
+}
+
+impl SocketData for FindButtonsResult {
+    const NAME: &'static str = "find_buttons";
+}
+
+impl DaemonRequest for FindButtons {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<FindButtons>(packet) {
+            if let Some(config) = current_config(listener, &request.serial_number) {
+                let buttons = find_config_buttons(&config, request.component_name.as_deref(), request.query.as_deref());
+                send_packet(handle, packet, &FindButtonsResult::Found(buttons)).ok();
+            } else {
+                send_packet(handle, packet, &FindButtonsResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
+
+/// Request for renaming a folder, buttons that open the folder are renamed too
+#[derive(Serialize, Deserialize)]
+pub struct RenameFolder {
+    pub serial_number: String,
+    pub id: String,
+    pub name: String,
+}
+
+/// Response of [RenameFolder] request
+#[derive(Serialize, Deserialize)]
+pub enum RenameFolderResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if folder wasn't found
+    FolderNotFound,
+
+    /// Sent if folder was renamed
+    Renamed,
+}
+
+impl SocketData for RenameFolder {
+    const NAME: &'static str = "rename_folder";
+}
+
+impl SocketData for RenameFolderResult {
+    const NAME: &'static str = "rename_folder";
+}
+
+impl DaemonRequest for RenameFolder {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<RenameFolder>(packet) {
+            let result = match edit_folders(listener, &request.serial_number, |config| rename_config_folder(config, &request.id, &request.name)) {
+                None => RenameFolderResult::DeviceNotFound,
+                Some(false) => RenameFolderResult::FolderNotFound,
+                Some(true) => RenameFolderResult::Renamed,
+            };
+
+            send_packet(handle, packet, &result).ok();
+        }
+    }
+}
+
+/// Request for deleting a folder together with folders inside of it, buttons that open the folder are removed
+#[derive(Serialize, Deserialize)]
+pub struct DeleteFolder {
+    pub serial_number: String,
+    pub id: String,
+}
+
+/// Response of [DeleteFolder] request
+#[derive(Serialize, Deserialize)]
+pub enum DeleteFolderResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if folder wasn't found
+    FolderNotFound,
+
+    /// Sent if folder was deleted
+    Deleted,
+}
+
+impl SocketData for DeleteFolder {
+    const NAME: &'static str = "delete_folder";
+}
+
+impl SocketData for DeleteFolderResult {
+    const NAME: &'static str = "delete_folder";
+}
+
+impl DaemonRequest for DeleteFolder {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<DeleteFolder>(packet) {
+            let result = match edit_folders(listener, &request.serial_number, |config| delete_config_folder(config, &request.id)) {
+                None => DeleteFolderResult::DeviceNotFound,
+                Some(false) => DeleteFolderResult::FolderNotFound,
+                Some(true) => DeleteFolderResult::Deleted,
+            };
+
+            send_packet(handle, packet, &result).ok();
+        }
+    }
+}
+
+/// Request for deleting folders that can no longer be reached from root screen of a device
+#[derive(Serialize, Deserialize)]
+pub struct CleanupFolders {
+    pub serial_number: String,
+}
+
+/// Response of [CleanupFolders] request
+#[derive(Serialize, Deserialize)]
+pub enum CleanupFoldersResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if cleanup was done, contains IDs of deleted folders
+    Removed(Vec<String>),
+}
+
+impl SocketData for CleanupFolders {
+    const NAME: &'static str = "cleanup_folders";
+}
+
+impl SocketData for CleanupFoldersResult {
+    const NAME: &'static str = "cleanup_folders";
+}
+
+impl DaemonRequest for CleanupFolders {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<CleanupFolders>(packet) {
+            let result = match edit_folders(listener, &request.serial_number, remove_orphaned_config_folders) {
+                None => CleanupFoldersResult::DeviceNotFound,
+                Some(removed) => CleanupFoldersResult::Removed(removed),
+            };
+
+            send_packet(handle, packet, &result).ok();
+        }
+    }
+}
+
+/// Edits device config and resets stack of the device if it's connected, returns None if device wasn't found
+fn edit_folders<T>(listener: &DaemonListener, serial_number: &str, edit: impl FnOnce(&mut DeviceConfig) -> T) -> Option<T> {
+    let config = listener.config.get_device_config(serial_number)?;
+
+    let core = listener.core_manager.get_device(serial_number)
+        .filter(|device| !device.core.is_closed())
+        .map(|device| CoreHandle::wrap(device.core));
+
+    // Edits to root screen that weren't committed yet would be lost on stack reset otherwise
+    if let Some(core) = &core {
+        commit_changes(core);
+    }
+
+    let mut config_handle = config.write().unwrap();
+    let result = edit(&mut config_handle);
+    let layout = config_handle.layout.clone();
+    drop(config_handle);
+
+    if let Some(core) = &core {
+        reset_stack(core, make_panel_unique(layout));
+    }
+
+    if let Err(err) = listener.config.save_device_config(serial_number) {
+        log::error!("Error encountered while saving folder changes for {}: {:?}", serial_number, err);
+    }
+
+    // Buttons that history refers to might be gone now
+    listener.history.clear(serial_number);
+
+    Some(result)
+}
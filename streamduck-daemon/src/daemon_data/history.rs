@@ -0,0 +1,134 @@
+//! Requests related to undoing and redoing button edits
+use serde::{Deserialize, Serialize};
+use streamduck_core::core::methods::{CoreHandle, get_current_screen};
+use streamduck_core::modules::events::SDGlobalEvent;
+use streamduck_core::socket::{parse_packet_to_data, send_event_to_socket, send_packet, SocketData, SocketHandle, SocketPacket};
+use streamduck_core::util::panel_to_raw;
+use crate::daemon_data::{DaemonListener, DaemonRequest};
+use crate::history::HistoryError;
+
+/// Request for undoing last button edit on a device
+#[derive(Serialize, Deserialize)]
+pub struct Undo {
+    pub serial_number: String,
+}
+
+/// Response of [Undo] request
+#[derive(Serialize, Deserialize)]
+pub enum UndoResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if there's no edits to undo
+    NothingToUndo,
+
+    /// Sent if edited panel isn't currently on screen
+    DifferentScreen,
+
+    /// Sent if button couldn't be restored
+    FailedToApply,
+
+    /// Sent if edit was undone, contains key of the button that was changed, or key that button was moved to for moves
+    Undone(u8),
+}
+
+impl SocketData for Undo {
+    const NAME: &'static str = "undo";
+}
+
+impl SocketData for UndoResult {
+    const NAME: &'static str = "undo";
+}
+
+impl DaemonRequest for Undo {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<Undo>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
+                let wrapped_core = CoreHandle::wrap(device.core);
+
+                let response = match listener.history.undo(&request.serial_number, &wrapped_core) {
+                    Ok(key) => {
+                        notify_applied(listener, &wrapped_core, &request.serial_number, true, key);
+                        UndoResult::Undone(key)
+                    }
+                    Err(HistoryError::Empty) => UndoResult::NothingToUndo,
+                    Err(HistoryError::DifferentScreen) => UndoResult::DifferentScreen,
+                    Err(HistoryError::Failed) => UndoResult::FailedToApply,
+                };
+
+                send_packet(handle, packet, &response).ok();
+            } else {
+                send_packet(handle, packet, &UndoResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
+
+/// Request for reapplying last undone button edit on a device
+#[derive(Serialize, Deserialize)]
+pub struct Redo {
+    pub serial_number: String,
+}
+
+/// Response of [Redo] request
+#[derive(Serialize, Deserialize)]
+pub enum RedoResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if there's no undone edits to redo
+    NothingToRedo,
+
+    /// Sent if edited panel isn't currently on screen
+    DifferentScreen,
+
+    /// Sent if button couldn't be changed
+    FailedToApply,
+
+    /// Sent if edit was redone, contains key of the button that was changed, or key that button was moved to for moves
+    Redone(u8),
+}
+
+impl SocketData for Redo {
+    const NAME: &'static str = "redo";
+}
+
+impl SocketData for RedoResult {
+    const NAME: &'static str = "redo";
+}
+
+impl DaemonRequest for Redo {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<Redo>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
+                let wrapped_core = CoreHandle::wrap(device.core);
+
+                let response = match listener.history.redo(&request.serial_number, &wrapped_core) {
+                    Ok(key) => {
+                        notify_applied(listener, &wrapped_core, &request.serial_number, false, key);
+                        RedoResult::Redone(key)
+                    }
+                    Err(HistoryError::Empty) => RedoResult::NothingToRedo,
+                    Err(HistoryError::DifferentScreen) => RedoResult::DifferentScreen,
+                    Err(HistoryError::Failed) => RedoResult::FailedToApply,
+                };
+
+                send_packet(handle, packet, &response).ok();
+            } else {
+                send_packet(handle, packet, &RedoResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
+
+/// Sends panel state after undo or redo to clients
+fn notify_applied(listener: &DaemonListener, core: &CoreHandle, serial_number: &str, undo: bool, key: u8) {
+    if let Some(screen) = get_current_screen(core) {
+        send_event_to_socket(&listener.core_manager.socket_manager, SDGlobalEvent::EditHistoryApplied {
+            serial_number: serial_number.to_string(),
+            undo,
+            key,
+            panel: panel_to_raw(&screen)
+        });
+    }
+}
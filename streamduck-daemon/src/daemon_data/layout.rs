@@ -0,0 +1,228 @@
+//! Requests related to declarative layout definitions and templates
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use streamduck_core::core::methods::{CoreHandle, reset_stack, set_brightness};
+use streamduck_core::layout::{LayoutChange, LayoutDefinition, LayoutError, LayoutTemplate};
+use streamduck_core::socket::{parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket};
+use streamduck_core::util::make_panel_unique;
+use crate::daemon_data::{DaemonListener, DaemonRequest};
+
+/// Request for applying YAML layout definition to a device, only differences to current config are applied
+#[derive(Serialize, Deserialize)]
+pub struct ApplyLayout {
+    pub serial_number: String,
+    /// Layout definition in YAML
+    pub layout: String,
+    /// If true, changes are only listed without being applied
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Response of [ApplyLayout] request
+#[derive(Serialize, Deserialize)]
+pub enum ApplyLayoutResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if layout failed to parse or refers to unknown folders, contains reason
+    InvalidLayout(String),
+
+    /// Sent if changes were applied, but config failed to save
+    FailedToSave,
+
+    /// Sent if layout was applied or checked with dry run, contains list of changes
+    Applied(Vec<LayoutChange>),
+}
+
+impl SocketData for ApplyLayout {
+    const NAME: &'static str = "apply_layout";
+}
+
+impl SocketData for ApplyLayoutResult {
+    const NAME: &'static str = "apply_layout";
+}
+
+impl DaemonRequest for ApplyLayout {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<ApplyLayout>(packet) {
+            let definition = match LayoutDefinition::from_yaml(&request.layout) {
+                Ok(definition) => definition,
+                Err(err) => {
+                    send_packet(handle, packet, &ApplyLayoutResult::InvalidLayout(format!("{:?}", err))).ok();
+                    return;
+                }
+            };
+
+            let result = match apply_definition(listener, &request.serial_number, &definition, request.dry_run) {
+                Ok(changes) => ApplyLayoutResult::Applied(changes),
+                Err(ApplyError::DeviceNotFound) => ApplyLayoutResult::DeviceNotFound,
+                Err(ApplyError::InvalidLayout(err)) => ApplyLayoutResult::InvalidLayout(format!("{:?}", err)),
+                Err(ApplyError::FailedToSave) => ApplyLayoutResult::FailedToSave,
+            };
+
+            send_packet(handle, packet, &result).ok();
+        }
+    }
+}
+
+/// Request for instantiating YAML layout template with provided variable values and applying it to a device
+#[derive(Serialize, Deserialize)]
+pub struct InstantiateTemplate {
+    pub serial_number: String,
+    /// Layout template in YAML
+    pub template: String,
+    /// Values for template variables, variables that aren't provided use their defaults
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    /// If true, changes are only listed without being applied
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Response of [InstantiateTemplate] request
+#[derive(Serialize, Deserialize)]
+pub enum InstantiateTemplateResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if template failed to parse or resulting layout is invalid, contains reason
+    InvalidTemplate(String),
+
+    /// Sent if some variables didn't have values or defaults, contains their names
+    MissingVariables(Vec<String>),
+
+    /// Sent if changes were applied, but config failed to save
+    FailedToSave,
+
+    /// Sent if template was applied or checked with dry run, contains list of changes
+    Applied(Vec<LayoutChange>),
+}
+
+impl SocketData for InstantiateTemplate {
+    const NAME: &'static str = "instantiate_template";
+}
+
+impl SocketData for InstantiateTemplateResult {
+    const NAME: &'static str = "instantiate_template";
+}
+
+impl DaemonRequest for InstantiateTemplate {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<InstantiateTemplate>(packet) {
+            // Buttons that don't fit on the device are dropped, so same template can be used on devices of any size
+            let key_count = listener.core_manager.get_device(&request.serial_number).map(|x| x.core.key_count);
+
+            let definition = match LayoutTemplate::from_yaml(&request.template).and_then(|x| x.instantiate(&request.variables, key_count)) {
+                Ok(definition) => definition,
+                Err(LayoutError::MissingVariables(names)) => {
+                    send_packet(handle, packet, &InstantiateTemplateResult::MissingVariables(names)).ok();
+                    return;
+                }
+                Err(err) => {
+                    send_packet(handle, packet, &InstantiateTemplateResult::InvalidTemplate(format!("{:?}", err))).ok();
+                    return;
+                }
+            };
+
+            let result = match apply_definition(listener, &request.serial_number, &definition, request.dry_run) {
+                Ok(changes) => InstantiateTemplateResult::Applied(changes),
+                Err(ApplyError::DeviceNotFound) => InstantiateTemplateResult::DeviceNotFound,
+                Err(ApplyError::InvalidLayout(err)) => InstantiateTemplateResult::InvalidTemplate(format!("{:?}", err)),
+                Err(ApplyError::FailedToSave) => InstantiateTemplateResult::FailedToSave,
+            };
+
+            send_packet(handle, packet, &result).ok();
+        }
+    }
+}
+
+/// Reasons layout definition couldn't be applied
+enum ApplyError {
+    DeviceNotFound,
+    InvalidLayout(LayoutError),
+    FailedToSave,
+}
+
+/// Applies layout definition to device config, resetting stack of the device and saving config if anything changed
+fn apply_definition(listener: &DaemonListener, serial_number: &str, definition: &LayoutDefinition, dry_run: bool) -> Result<Vec<LayoutChange>, ApplyError> {
+    let config = listener.config.get_device_config(serial_number).ok_or(ApplyError::DeviceNotFound)?;
+
+    let compiled = definition.compile(&config.read().unwrap()).map_err(ApplyError::InvalidLayout)?;
+
+    if dry_run {
+        return Ok(compiled.diff(&config.read().unwrap()));
+    }
+
+    let mut config_handle = config.write().unwrap();
+    let changes = compiled.apply(&mut config_handle);
+    let (layout, brightness) = (config_handle.layout.clone(), config_handle.brightness);
+    drop(config_handle);
+
+    if changes.is_empty() {
+        return Ok(changes);
+    }
+
+    if let Some(device) = listener.core_manager.get_device(serial_number) {
+        if !device.core.is_closed() {
+            let wrapped_core = CoreHandle::wrap(device.core);
+
+            reset_stack(&wrapped_core, make_panel_unique(layout));
+            set_brightness(&wrapped_core, brightness);
+        }
+    }
+
+    if let Err(err) = listener.config.save_device_config(serial_number) {
+        log::error!("Error encountered while saving applied layout for {}: {:?}", serial_number, err);
+        return Err(ApplyError::FailedToSave);
+    }
+
+    Ok(changes)
+}
+
+/// Request for exporting current layout of a device as YAML layout definition
+#[derive(Serialize, Deserialize)]
+pub struct ExportLayout {
+    pub serial_number: String,
+}
+
+/// Response of [ExportLayout] request
+#[derive(Serialize, Deserialize)]
+pub enum ExportLayoutResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if layout couldn't be represented in YAML
+    FailedToExport,
+
+    /// Sent if successfully exported, contains YAML
+    Exported(String),
+}
+
+impl SocketData for ExportLayout {
+    const NAME: &'static str = "export_layout";
+}
+
+impl SocketData for ExportLayoutResult {
+    const NAME: &'static str = "export_layout";
+}
+
+impl DaemonRequest for ExportLayout {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<ExportLayout>(packet) {
+            if let Some(config) = listener.config.get_device_config(&request.serial_number) {
+                match LayoutDefinition::from_config(&config.read().unwrap()).to_yaml() {
+                    Ok(yaml) => {
+                        send_packet(handle, packet, &ExportLayoutResult::Exported(yaml)).ok();
+                    }
+
+                    Err(err) => {
+                        log::error!("Failed to export layout for {}: {:?}", request.serial_number, err);
+                        send_packet(handle, packet, &ExportLayoutResult::FailedToExport).ok();
+                    }
+                }
+            } else {
+                send_packet(handle, packet, &ExportLayoutResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Requests related to captured log records
+//!
+//! [StreamLogs] depends on connection it came from, so socket implementations process it
+//! with [process_log_request] before handing packets over to listeners
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use streamduck_core::logging::{LogFilter, LogRecord, recent_logs, stream_logs};
+use streamduck_core::socket::{check_packet_for_data, parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket, SocketPool};
+use crate::daemon_data::{DaemonListener, DaemonRequest};
+
+fn default_limit() -> usize {
+    100
+}
+
+/// Request for getting recent log records that daemon captured
+#[derive(Serialize, Deserialize)]
+pub struct GetRecentLogs {
+    /// Maximum amount of records to return, most recent records are returned
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+    #[serde(flatten)]
+    pub filter: LogFilter,
+}
+
+/// Response of [GetRecentLogs] request
+#[derive(Serialize, Deserialize)]
+pub struct GetRecentLogsResult {
+    /// Records ordered from oldest to newest
+    pub logs: Vec<LogRecord>,
+}
+
+impl SocketData for GetRecentLogs {
+    const NAME: &'static str = "get_recent_logs";
+}
+
+impl SocketData for GetRecentLogsResult {
+    const NAME: &'static str = "get_recent_logs";
+}
+
+impl DaemonRequest for GetRecentLogs {
+    fn process(_listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<GetRecentLogs>(packet) {
+            send_packet(handle, packet, &GetRecentLogsResult {
+                logs: recent_logs(&request.filter, request.limit)
+            }).ok();
+        }
+    }
+}
+
+/// Request for starting or stopping streaming of new log records to current connection, records are sent as "log" packets
+#[derive(Serialize, Deserialize)]
+pub struct StreamLogs {
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub filter: LogFilter,
+}
+
+/// Response of [StreamLogs] request
+#[derive(Serialize, Deserialize)]
+pub enum StreamLogsResult {
+    /// Sent if connection can't receive events, like request pipe on Windows
+    Unsupported,
+
+    /// Sent if new records will be sent to the connection
+    Streaming,
+
+    /// Sent if streaming was stopped
+    Stopped,
+}
+
+impl SocketData for StreamLogs {
+    const NAME: &'static str = "stream_logs";
+}
+
+impl SocketData for StreamLogsResult {
+    const NAME: &'static str = "stream_logs";
+}
+
+/// Processes log streaming requests, pool should be the one connection receives events from.
+/// Returns true if packet was a log streaming request
+pub fn process_log_request(pool: Option<&Arc<SocketPool>>, handle: SocketHandle, packet: &SocketPacket) -> bool {
+    if !check_packet_for_data::<StreamLogs>(packet) {
+        return false;
+    }
+
+    if let Ok(request) = parse_packet_to_data::<StreamLogs>(packet) {
+        let response = if let Some(pool) = pool {
+            if request.enabled {
+                stream_logs(pool, Some(request.filter));
+                StreamLogsResult::Streaming
+            } else {
+                stream_logs(pool, None);
+                StreamLogsResult::Stopped
+            }
+        } else {
+            StreamLogsResult::Unsupported
+        };
+
+        send_packet(handle, packet, &response).ok();
+    }
+
+    true
+}
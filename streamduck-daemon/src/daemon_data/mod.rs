@@ -6,29 +6,100 @@ pub mod modules;
 pub mod panels;
 pub mod buttons;
 pub mod ops;
+pub mod sessions;
+pub mod clients;
+pub mod layout;
+pub mod presets;
+pub mod history;
+pub mod folders;
+pub mod remote;
+pub mod notifications;
+pub mod logs;
+pub mod status;
+pub mod auth;
+pub mod revisions;
 
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use serde::{Deserialize, Serialize};
 use streamduck_core::versions::SOCKET_API;
 use streamduck_core::core::manager::CoreManager;
 use streamduck_core::socket::{check_packet_for_data, send_packet, SocketData, SocketHandle, SocketListener, SocketPacket};
 use streamduck_core::modules::ModuleManager;
 use streamduck_core::config::Config;
-use streamduck_core::core::button::Button;
-use crate::daemon_data::assets::{AddImage, ListFonts, ListImages, RemoveImage};
-use crate::daemon_data::buttons::{AddComponent, AddComponentValue, ClearButton, ClipboardStatusResult, CopyButton, GetButton, GetComponentValues, NewButton, NewButtonFromComponent, PasteButton, RemoveComponent, RemoveComponentValue, SetButton, SetComponentValue};
-use crate::daemon_data::config::{ExportDeviceConfig, GetDeviceConfig, ImportDeviceConfig, ReloadDeviceConfig, ReloadDeviceConfigsResult, SaveDeviceConfig, SaveDeviceConfigsResult};
-use crate::daemon_data::devices::{AddDevice, GetDevice, ListDevices, RemoveDevice, SetBrightness};
-use crate::daemon_data::modules::{AddModuleValue, GetModuleValues, ListComponents, ListModules, RemoveModuleValue, SetModuleValue};
-use crate::daemon_data::ops::{CommitChangesToConfig, DoButtonAction};
-use crate::daemon_data::panels::{DropStackToRoot, ForciblyPopScreen, GetButtonImage, GetButtonImages, GetCurrentScreen, GetStack, GetStackNames, PopScreen, PushScreen, ReplaceScreen, ResetStack};
+use crate::autosave::ConfigAutosave;
+use crate::history::EditHistory;
+use crate::transfer::TransferManager;
+use crate::remote::RemoteModuleHost;
+use crate::daemon_data::assets::{AddImage, CleanupUnusedImages, FinishImageUpload, GetImage, ListFonts, ListImages, RemoveImage, StartImageUpload, UploadImageChunk};
+use crate::daemon_data::buttons::{AddComponent, AddComponentValue, ClearButton, ClipboardStatusResult, CopiedButton, CopyButton, GetButton, GetComponentValues, MoveButton, NewButton, NewButtonFromComponent, PasteButton, RemoveComponent, RemoveComponentValue, SetButton, SetComponentValue};
+use crate::daemon_data::config::{CreateConfigMilestone, DiffDeviceConfig, ExportAllConfigsResult, ExportDeviceConfig, GetDeviceConfig, ImportAllConfigs, ImportDeviceConfig, ListConfigMilestones, ReloadDeviceConfig, ReloadDeviceConfigsResult, RestoreConfigMilestone, RestoreDeviceConfigBackup, SaveDeviceConfig, SaveDeviceConfigsResult};
+use crate::daemon_data::history::{Redo, Undo};
+use crate::daemon_data::folders::{CleanupFolders, DeleteFolder, FindButtons, GetFolderTree, ListFolders, RenameFolder};
+use crate::daemon_data::layout::{ApplyLayout, ExportLayout, InstantiateTemplate};
+use crate::daemon_data::devices::{AddDevice, GetDevice, ListDevices, RemoveDevice, SetBrightness, SetDeviceMirror, SetDeviceOrientation, SetDeviceStartup, SetMirroredLayout, SetPanelTransition};
+use crate::daemon_data::modules::{AddModuleValue, GetGlobalPluginSettings, GetModuleValues, GetPluginDependencies, ListComponents, ListModules, ListModuleSettings, ListPluginRequests, ReenablePlugin, RemoveModuleValue, SendModuleMessage, SetGlobalPluginSettings, SetModuleValue};
+use crate::daemon_data::ops::{Batch, CommitChangesToConfig, DoButtonAction, GetSafeModeStatus, RestartDaemonResult, SetSafeModeOnNextStart, ShutdownDaemonResult};
+use crate::daemon_data::panels::{DropStackToRoot, ForciblyPopScreen, GetButtonImage, GetButtonImages, GetCurrentScreen, GetStack, GetStackBreadcrumbs, GetStackNames, PopScreen, PopToScreen, PushScreen, RenderButtonPreview, ReplaceScreen, ResetStack, SetPanel};
+use crate::daemon_data::logs::GetRecentLogs;
+use crate::daemon_data::notifications::{DismissNotification, ShowNotification};
+use crate::daemon_data::remote::{RegisterRemoteModule, RemoteModuleResponse, UnregisterRemoteModule};
+use crate::daemon_data::status::GetDaemonStatus;
+use crate::daemon_data::revisions::GetScreenRevisions;
+use crate::daemon_data::presets::{ApplyPreset, ListPresets, RemovePreset, SavePreset};
+
+/// Requests that read-only clients are allowed to make, none of them change anything.
+/// Requests that return plugin settings or whole configs are left out, since plugin settings can hold credentials.
+/// Secrets in buttons are redacted from responses, see [redact_secrets](streamduck_core::socket::redact_secrets)
+pub const READ_ONLY_REQUESTS: &[&str] = &[
+    SocketAPIVersion::NAME,
+    ListDevices::NAME,
+    GetDevice::NAME,
+    GetDeviceConfig::NAME,
+    ExportDeviceConfig::NAME,
+    DiffDeviceConfig::NAME,
+    ListConfigMilestones::NAME,
+    ExportLayout::NAME,
+    ListFolders::NAME,
+    GetFolderTree::NAME,
+    FindButtons::NAME,
+    ListPresets::NAME,
+    ListImages::NAME,
+    GetImage::NAME,
+    ListFonts::NAME,
+    ListModules::NAME,
+    ListComponents::NAME,
+    ListPluginRequests::NAME,
+    GetPluginDependencies::NAME,
+    GetStack::NAME,
+    GetStackNames::NAME,
+    GetStackBreadcrumbs::NAME,
+    GetCurrentScreen::NAME,
+    GetButtonImage::NAME,
+    GetButtonImages::NAME,
+    RenderButtonPreview::NAME,
+    GetScreenRevisions::NAME,
+    GetButton::NAME,
+    GetComponentValues::NAME,
+    ClipboardStatusResult::NAME,
+    Batch::NAME,
+    GetSafeModeStatus::NAME,
+    GetRecentLogs::NAME,
+    GetDaemonStatus::NAME,
+];
 
 /// Listener for daemon types
 pub struct DaemonListener {
     pub core_manager: Arc<CoreManager>,
     pub module_manager: Arc<ModuleManager>,
     pub config: Arc<Config>,
-    pub clipboard: Mutex<Option<Button>>,
+    pub clipboard: Mutex<Option<CopiedButton>>,
+    pub autosave: Arc<ConfigAutosave>,
+    pub history: Arc<EditHistory>,
+    pub transfers: Arc<TransferManager>,
+    pub remote_modules: Arc<RemoteModuleHost>,
+    /// When daemon has started
+    pub started: Instant,
 }
 
 impl SocketListener for DaemonListener {
@@ -51,35 +122,84 @@ impl SocketListener for DaemonListener {
         process_for_type::<GetDeviceConfig>(self, socket, &packet);
 
         process_for_type::<ImportDeviceConfig>(self, socket, &packet);
+        process_for_type::<DiffDeviceConfig>(self, socket, &packet);
+        process_for_type::<RestoreDeviceConfigBackup>(self, socket, &packet);
         process_for_type::<ExportDeviceConfig>(self, socket, &packet);
+        process_for_type::<ExportAllConfigsResult>(self, socket, &packet);
+        process_for_type::<ImportAllConfigs>(self, socket, &packet);
+
+        process_for_type::<CreateConfigMilestone>(self, socket, &packet);
+        process_for_type::<ListConfigMilestones>(self, socket, &packet);
+        process_for_type::<RestoreConfigMilestone>(self, socket, &packet);
+
+        process_for_type::<ApplyLayout>(self, socket, &packet);
+        process_for_type::<ExportLayout>(self, socket, &packet);
+        process_for_type::<InstantiateTemplate>(self, socket, &packet);
+
+        process_for_type::<ListFolders>(self, socket, &packet);
+        process_for_type::<GetFolderTree>(self, socket, &packet);
+        process_for_type::<FindButtons>(self, socket, &packet);
+        process_for_type::<RenameFolder>(self, socket, &packet);
+        process_for_type::<DeleteFolder>(self, socket, &packet);
+        process_for_type::<CleanupFolders>(self, socket, &packet);
+
+        process_for_type::<ListPresets>(self, socket, &packet);
+        process_for_type::<SavePreset>(self, socket, &packet);
+        process_for_type::<RemovePreset>(self, socket, &packet);
+        process_for_type::<ApplyPreset>(self, socket, &packet);
 
         process_for_type::<SetBrightness>(self, socket, &packet);
+        process_for_type::<SetPanelTransition>(self, socket, &packet);
+        process_for_type::<SetDeviceMirror>(self, socket, &packet);
+        process_for_type::<SetDeviceOrientation>(self, socket, &packet);
+        process_for_type::<SetMirroredLayout>(self, socket, &packet);
+        process_for_type::<SetDeviceStartup>(self, socket, &packet);
 
         process_for_type::<ListImages>(self, socket, &packet);
         process_for_type::<AddImage>(self, socket, &packet);
         process_for_type::<RemoveImage>(self, socket, &packet);
+        process_for_type::<CleanupUnusedImages>(self, socket, &packet);
+        process_for_type::<GetImage>(self, socket, &packet);
+        process_for_type::<StartImageUpload>(self, socket, &packet);
+        process_for_type::<UploadImageChunk>(self, socket, &packet);
+        process_for_type::<FinishImageUpload>(self, socket, &packet);
 
         process_for_type::<ListFonts>(self,socket, &packet);
 
         // Module management
         process_for_type::<ListModules>(self,socket, &packet);
         process_for_type::<ListComponents>(self,socket, &packet);
+        process_for_type::<ListPluginRequests>(self,socket, &packet);
+        process_for_type::<GetPluginDependencies>(self,socket, &packet);
+        process_for_type::<ReenablePlugin>(self,socket, &packet);
+
+        process_for_type::<RegisterRemoteModule>(self,socket, &packet);
+        process_for_type::<UnregisterRemoteModule>(self,socket, &packet);
+        process_for_type::<RemoteModuleResponse>(self,socket, &packet);
 
         process_for_type::<GetModuleValues>(self,socket, &packet);
+        process_for_type::<ListModuleSettings>(self, socket, &packet);
         process_for_type::<AddModuleValue>(self,socket, &packet);
         process_for_type::<RemoveModuleValue>(self,socket, &packet);
         process_for_type::<SetModuleValue>(self,socket, &packet);
+        process_for_type::<GetGlobalPluginSettings>(self, socket, &packet);
+        process_for_type::<SetGlobalPluginSettings>(self, socket, &packet);
+        process_for_type::<SendModuleMessage>(self, socket, &packet);
 
         // Panel management
         process_for_type::<GetStack>(self, socket, &packet);
         process_for_type::<GetStackNames>(self, socket, &packet);
+        process_for_type::<GetStackBreadcrumbs>(self, socket, &packet);
         process_for_type::<GetCurrentScreen>(self, socket, &packet);
         process_for_type::<GetButtonImage>(self, socket, &packet);
+        process_for_type::<RenderButtonPreview>(self, socket, &packet);
         process_for_type::<GetButtonImages>(self, socket, &packet);
 
+        process_for_type::<GetScreenRevisions>(self, socket, &packet);
         process_for_type::<GetButton>(self, socket, &packet);
         process_for_type::<SetButton>(self, socket, &packet);
         process_for_type::<ClearButton>(self, socket, &packet);
+        process_for_type::<MoveButton>(self, socket, &packet);
 
         process_for_type::<ClipboardStatusResult>(self, socket, &packet);
         process_for_type::<CopyButton>(self, socket, &packet);
@@ -97,26 +217,51 @@ impl SocketListener for DaemonListener {
 
         process_for_type::<RemoveComponent>(self, socket, &packet);
 
+        process_for_type::<Undo>(self, socket, &packet);
+        process_for_type::<Redo>(self, socket, &packet);
+
         process_for_type::<PushScreen>(self, socket, &packet);
         process_for_type::<PopScreen>(self, socket, &packet);
         process_for_type::<ForciblyPopScreen>(self, socket, &packet);
         process_for_type::<ReplaceScreen>(self, socket, &packet);
+        process_for_type::<SetPanel>(self, socket, &packet);
         process_for_type::<ResetStack>(self, socket, &packet);
         process_for_type::<DropStackToRoot>(self, socket, &packet);
+        process_for_type::<PopToScreen>(self, socket, &packet);
 
         process_for_type::<CommitChangesToConfig>(self, socket, &packet);
 
         process_for_type::<DoButtonAction>(self, socket, &packet);
+
+        process_for_type::<ShowNotification>(self, socket, &packet);
+        process_for_type::<DismissNotification>(self, socket, &packet);
+
+        process_for_type::<Batch>(self, socket, &packet);
+
+        process_for_type::<GetSafeModeStatus>(self, socket, &packet);
+        process_for_type::<SetSafeModeOnNextStart>(self, socket, &packet);
+        process_for_type::<ShutdownDaemonResult>(self, socket, &packet);
+        process_for_type::<RestartDaemonResult>(self, socket, &packet);
+
+        process_for_type::<GetRecentLogs>(self, socket, &packet);
+        process_for_type::<GetDaemonStatus>(self, socket, &packet);
     }
 }
 
 trait DaemonRequest {
+    /// If request changes device config of `serial_number` in request data without saving it, so autosave should save it
+    const CHANGES_DEVICE_CONFIG: bool = false;
+
     fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket);
 }
 
 fn process_for_type<T: DaemonRequest + SocketData>(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
     if packet.ty == T::NAME {
         T::process(listener, handle, packet);
+
+        if T::CHANGES_DEVICE_CONFIG {
+            listener.autosave.process_packet(packet);
+        }
     }
 }
 
@@ -1,9 +1,13 @@
 //! Requests related to modules
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use streamduck_core::modules::components::{ComponentDefinition, UIPathValue};
-use streamduck_core::modules::{add_element_module_setting, PluginMetadata, remove_element_module_setting, set_module_setting};
-use streamduck_core::socket::{check_packet_for_data, parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket};
+use streamduck_core::modules::dependencies::DependencyNode;
+use streamduck_core::modules::events::SDGlobalEvent;
+use streamduck_core::modules::messages::{ModuleMessage, SOCKET_MESSAGE_SENDER};
+use streamduck_core::modules::{add_element_module_setting, get_module_settings, PluginMetadata, remove_element_module_setting, send_global_event_to_modules, set_module_setting};
+use streamduck_core::socket::{check_packet_for_data, parse_packet_to_data, send_event_to_socket, send_packet, SocketData, SocketHandle, SocketPacket};
 use streamduck_core::util::convert_value_to_path;
 use crate::daemon_data::{DaemonListener, DaemonRequest};
 
@@ -32,6 +36,46 @@ impl DaemonRequest for ListModules {
     }
 }
 
+/// Request for getting loaded modules in order they were loaded, with their dependencies
+#[derive(Serialize, Deserialize)]
+pub struct GetPluginDependencies {
+    pub modules: Vec<DependencyNode>
+}
+
+impl SocketData for GetPluginDependencies {
+    const NAME: &'static str = "get_plugin_dependencies";
+}
+
+impl DaemonRequest for GetPluginDependencies {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if check_packet_for_data::<GetPluginDependencies>(&packet) {
+            send_packet(handle, packet, &GetPluginDependencies {
+                modules: listener.module_manager.dependency_graph()
+            }).ok();
+        }
+    }
+}
+
+/// Request for getting names of requests that plugins have registered
+#[derive(Serialize, Deserialize)]
+pub struct ListPluginRequests {
+    pub requests: Vec<String>
+}
+
+impl SocketData for ListPluginRequests {
+    const NAME: &'static str = "list_plugin_requests";
+}
+
+impl DaemonRequest for ListPluginRequests {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if check_packet_for_data::<ListPluginRequests>(&packet) {
+            send_packet(handle, packet, &ListPluginRequests {
+                requests: listener.core_manager.socket_manager.list_plugin_requests()
+            }).ok();
+        }
+    }
+}
+
 /// Request for getting all components defined by all modules
 #[derive(Serialize, Deserialize)]
 pub struct ListComponents {
@@ -102,6 +146,31 @@ impl DaemonRequest for GetModuleValues {
     }
 }
 
+/// Request for getting settings of every module that has any, so UI can make a settings page for each
+#[derive(Serialize, Deserialize)]
+pub struct ListModuleSettings {
+    pub settings: HashMap<String, Vec<UIPathValue>>,
+}
+
+impl SocketData for ListModuleSettings {
+    const NAME: &'static str = "list_module_settings";
+}
+
+impl DaemonRequest for ListModuleSettings {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if check_packet_for_data::<ListModuleSettings>(packet) {
+            let settings = listener.module_manager.get_module_list().into_iter()
+                .map(|module| (module.name(), get_module_settings(listener.core_manager.clone(), &module)))
+                .filter(|(_, values)| !values.is_empty())
+                .collect();
+
+            send_packet(handle, packet, &ListModuleSettings {
+                settings
+            }).ok();
+        }
+    }
+}
+
 /// Request for adding element into array of module's setting
 #[derive(Serialize, Deserialize)]
 pub struct AddModuleValue {
@@ -245,4 +314,175 @@ impl DaemonRequest for SetModuleValue {
             send_packet(handle, packet, &SetModuleValueResult::ModuleNotFound).ok();
         }
     }
-}
\ No newline at end of file
+}
+/// Request for getting global plugin settings, that are shared between all devices
+#[derive(Serialize, Deserialize)]
+pub struct GetGlobalPluginSettings {
+    pub name: String,
+}
+
+/// Response of [GetGlobalPluginSettings] request
+#[derive(Serialize, Deserialize)]
+pub enum GetGlobalPluginSettingsResult {
+    /// Sent if there's no settings with such name
+    NotFound,
+
+    /// Sent if settings were found
+    Settings(Value),
+}
+
+impl SocketData for GetGlobalPluginSettings {
+    const NAME: &'static str = "get_global_plugin_settings";
+}
+
+impl SocketData for GetGlobalPluginSettingsResult {
+    const NAME: &'static str = "get_global_plugin_settings";
+}
+
+impl DaemonRequest for GetGlobalPluginSettings {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<GetGlobalPluginSettings>(packet) {
+            if let Some(settings) = listener.config.get_plugin_settings_value(&request.name) {
+                send_packet(handle, packet, &GetGlobalPluginSettingsResult::Settings(settings)).ok();
+            } else {
+                send_packet(handle, packet, &GetGlobalPluginSettingsResult::NotFound).ok();
+            }
+        }
+    }
+}
+
+/// Request for setting global plugin settings, modules are notified with [SDGlobalEvent::GlobalPluginSettingsChanged]
+#[derive(Serialize, Deserialize)]
+pub struct SetGlobalPluginSettings {
+    pub name: String,
+    pub settings: Value,
+}
+
+/// Response of [SetGlobalPluginSettings] request
+#[derive(Serialize, Deserialize)]
+pub enum SetGlobalPluginSettingsResult {
+    /// Sent if settings were set and saved
+    Set,
+}
+
+impl SocketData for SetGlobalPluginSettings {
+    const NAME: &'static str = "set_global_plugin_settings";
+}
+
+impl SocketData for SetGlobalPluginSettingsResult {
+    const NAME: &'static str = "set_global_plugin_settings";
+}
+
+impl DaemonRequest for SetGlobalPluginSettings {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<SetGlobalPluginSettings>(packet) {
+            listener.config.set_plugin_settings_value(&request.name, request.settings);
+
+            let event = SDGlobalEvent::GlobalPluginSettingsChanged {
+                name: request.name
+            };
+
+            send_global_event_to_modules(event.clone(), listener.module_manager.get_module_list().into_iter());
+            send_event_to_socket(&listener.core_manager.socket_manager, event);
+
+            send_packet(handle, packet, &SetGlobalPluginSettingsResult::Set).ok();
+        }
+    }
+}
+
+/// Request for sending a message to modules that are subscribed to the topic
+#[derive(Serialize, Deserialize)]
+pub struct SendModuleMessage {
+    pub topic: String,
+    pub data: Value,
+    /// Device the message is related to, if any
+    #[serde(default)]
+    pub serial_number: Option<String>,
+}
+
+/// Response of [SendModuleMessage] request
+#[derive(Serialize, Deserialize)]
+pub enum SendModuleMessageResult {
+    /// Sent if specified device wasn't found
+    DeviceNotFound,
+
+    /// Sent if message was sent, contains amount of subscriptions that received it
+    Sent(usize),
+}
+
+impl SocketData for SendModuleMessage {
+    const NAME: &'static str = "send_module_message";
+}
+
+impl SocketData for SendModuleMessageResult {
+    const NAME: &'static str = "send_module_message";
+}
+
+impl DaemonRequest for SendModuleMessage {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<SendModuleMessage>(packet) {
+            if let Some(serial) = &request.serial_number {
+                if listener.config.get_device_config(serial).is_none() {
+                    send_packet(handle, packet, &SendModuleMessageResult::DeviceNotFound).ok();
+                    return;
+                }
+            }
+
+            let count = listener.module_manager.message_bus().publish(ModuleMessage {
+                topic: request.topic,
+                sender: SOCKET_MESSAGE_SENDER.to_string(),
+                serial_number: request.serial_number,
+                data: request.data
+            });
+
+            send_packet(handle, packet, &SendModuleMessageResult::Sent(count)).ok();
+        }
+    }
+}
+
+/// Request for enabling plugin that was disabled after panicking too many times
+#[derive(Serialize, Deserialize)]
+pub struct ReenablePlugin {
+    pub name: String,
+}
+
+/// Response of [ReenablePlugin] request
+#[derive(Serialize, Deserialize)]
+pub enum ReenablePluginResult {
+    /// Sent if plugin with the name isn't loaded
+    PluginNotFound,
+
+    /// Sent if plugin wasn't disabled
+    NotDisabled,
+
+    /// Sent if plugin was enabled again
+    Reenabled,
+}
+
+impl SocketData for ReenablePlugin {
+    const NAME: &'static str = "reenable_plugin";
+}
+
+impl SocketData for ReenablePluginResult {
+    const NAME: &'static str = "reenable_plugin";
+}
+
+impl DaemonRequest for ReenablePlugin {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<ReenablePlugin>(packet) {
+            let result = if let Some(health) = listener.module_manager.plugin_health(&request.name) {
+                if health.is_disabled() {
+                    health.reenable();
+                    log::info!("Plugin '{}' was enabled again", request.name);
+                    ReenablePluginResult::Reenabled
+                } else {
+                    ReenablePluginResult::NotDisabled
+                }
+            } else {
+                ReenablePluginResult::PluginNotFound
+            };
+
+            send_packet(handle, packet, &result).ok();
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Requests related to notifications
+use serde::{Deserialize, Serialize};
+use streamduck_core::core::methods::CoreHandle;
+use streamduck_core::core::notifications::{dismiss_notification, Notification, show_notification};
+use streamduck_core::socket::{parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket};
+use crate::daemon_data::{DaemonListener, DaemonRequest};
+
+/// Request for showing notification over current panel of a device
+#[derive(Serialize, Deserialize)]
+pub struct ShowNotification {
+    pub serial_number: String,
+    pub text: String,
+    /// Identifier of image from image collection or base64 encoded image
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Seconds before notification disappears, 0 keeps it until it's dismissed
+    #[serde(default)]
+    pub duration: f32,
+    /// Keys to cover, all keys if empty
+    #[serde(default)]
+    pub keys: Vec<u8>,
+}
+
+/// Response of [ShowNotification] request
+#[derive(Serialize, Deserialize)]
+pub enum ShowNotificationResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if none of the keys exist on the device
+    InvalidKeys,
+
+    /// Sent if notification is shown, contains id of the notification
+    Shown(String),
+}
+
+impl SocketData for ShowNotification {
+    const NAME: &'static str = "show_notification";
+}
+
+impl SocketData for ShowNotificationResult {
+    const NAME: &'static str = "show_notification";
+}
+
+impl DaemonRequest for ShowNotification {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<ShowNotification>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
+                let wrapped_core = CoreHandle::wrap(device.core);
+
+                if !request.keys.is_empty() && request.keys.iter().all(|x| *x >= wrapped_core.core().key_count) {
+                    send_packet(handle, packet, &ShowNotificationResult::InvalidKeys).ok();
+                    return;
+                }
+
+                let id = show_notification(&wrapped_core, Notification {
+                    text: request.text,
+                    icon: request.icon,
+                    duration: request.duration,
+                    keys: request.keys
+                });
+
+                send_packet(handle, packet, &ShowNotificationResult::Shown(id)).ok();
+            } else {
+                send_packet(handle, packet, &ShowNotificationResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
+
+/// Request for removing notification before it disappears on its own
+#[derive(Serialize, Deserialize)]
+pub struct DismissNotification {
+    pub serial_number: String,
+    pub id: String,
+}
+
+/// Response of [DismissNotification] request
+#[derive(Serialize, Deserialize)]
+pub enum DismissNotificationResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if notification has already disappeared or never existed
+    NotificationNotFound,
+
+    /// Sent if notification was removed
+    Dismissed,
+}
+
+impl SocketData for DismissNotification {
+    const NAME: &'static str = "dismiss_notification";
+}
+
+impl SocketData for DismissNotificationResult {
+    const NAME: &'static str = "dismiss_notification";
+}
+
+impl DaemonRequest for DismissNotification {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<DismissNotification>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
+                let wrapped_core = CoreHandle::wrap(device.core);
+
+                let result = if dismiss_notification(&wrapped_core, &request.id) {
+                    DismissNotificationResult::Dismissed
+                } else {
+                    DismissNotificationResult::NotificationNotFound
+                };
+
+                send_packet(handle, packet, &result).ok();
+            } else {
+                send_packet(handle, packet, &DismissNotificationResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
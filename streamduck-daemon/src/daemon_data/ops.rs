@@ -1,8 +1,10 @@
 //! Requests for various operations
 use serde::{Deserialize, Serialize};
 use streamduck_core::core::methods::{button_action, commit_changes, CoreHandle};
-use streamduck_core::socket::{parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket};
+use streamduck_core::socket::{check_packet_for_data, parse_packet, parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket, PACKET_TERMINATOR};
 use crate::daemon_data::{DaemonListener, DaemonRequest};
+use crate::safe_mode::{is_safe_mode_on_next_start, set_safe_mode_on_next_start};
+use crate::shutdown::schedule_shutdown;
 
 /// Request for committing all changes of the stack to device config
 #[derive(Serialize, Deserialize)]
@@ -29,6 +31,8 @@ impl SocketData for CommitChangesToConfigResult {
 }
 
 impl DaemonRequest for CommitChangesToConfig {
+    const CHANGES_DEVICE_CONFIG: bool = true;
+
     fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
         if let Ok(request) = parse_packet_to_data::<CommitChangesToConfig>(packet) {
             if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
@@ -81,4 +85,173 @@ impl DaemonRequest for DoButtonAction {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Request for executing multiple requests in order with a single round trip
+#[derive(Serialize, Deserialize)]
+pub struct Batch {
+    pub requests: Vec<SocketPacket>
+}
+
+/// Response of [Batch] request
+#[derive(Serialize, Deserialize)]
+pub struct BatchResult {
+    /// Responses in the same order as requests, [None] if request didn't produce a response
+    pub responses: Vec<Option<SocketPacket>>
+}
+
+impl SocketData for Batch {
+    const NAME: &'static str = "batch";
+}
+
+impl SocketData for BatchResult {
+    const NAME: &'static str = "batch";
+}
+
+impl DaemonRequest for Batch {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<Batch>(packet) {
+            let mut responses = vec![];
+
+            for (index, mut inner_packet) in request.requests.into_iter().enumerate() {
+                let requester = inner_packet.requester.clone().unwrap_or_else(|| format!("batch_{}", index));
+                inner_packet.requester = Some(requester.clone());
+
+                // Capturing anything listeners write in response to the request
+                let mut buffer: Vec<u8> = vec![];
+                listener.core_manager.socket_manager.received_message(&mut buffer, inner_packet);
+
+                let response = buffer.split(|x| *x == PACKET_TERMINATOR)
+                    .filter_map(|x| parse_packet(x).ok())
+                    .find(|x| x.requester.as_ref() == Some(&requester));
+
+                responses.push(response);
+            }
+
+            send_packet(handle, packet, &BatchResult {
+                responses
+            }).ok();
+        }
+    }
+}
+
+/// Request for safe mode status
+#[derive(Serialize, Deserialize)]
+pub struct GetSafeModeStatus {
+    /// If daemon is currently running in safe mode
+    pub active: bool,
+    /// If daemon will start in safe mode next time
+    pub next_start: bool,
+}
+
+impl SocketData for GetSafeModeStatus {
+    const NAME: &'static str = "get_safe_mode_status";
+}
+
+impl DaemonRequest for GetSafeModeStatus {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if check_packet_for_data::<GetSafeModeStatus>(packet) {
+            send_packet(handle, packet, &GetSafeModeStatus {
+                active: listener.config.safe_mode(),
+                next_start: is_safe_mode_on_next_start()
+            }).ok();
+        }
+    }
+}
+
+/// Request for starting daemon in safe mode next time it starts
+#[derive(Serialize, Deserialize)]
+pub struct SetSafeModeOnNextStart {
+    pub enabled: bool,
+}
+
+/// Response of [SetSafeModeOnNextStart] request
+#[derive(Serialize, Deserialize)]
+pub enum SetSafeModeOnNextStartResult {
+    /// Sent if request couldn't be saved
+    FailedToSet,
+
+    /// Sent if successfully set
+    Set,
+}
+
+impl SocketData for SetSafeModeOnNextStart {
+    const NAME: &'static str = "set_safe_mode_on_next_start";
+}
+
+impl SocketData for SetSafeModeOnNextStartResult {
+    const NAME: &'static str = "set_safe_mode_on_next_start";
+}
+
+impl DaemonRequest for SetSafeModeOnNextStart {
+    fn process(_listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<SetSafeModeOnNextStart>(packet) {
+            match set_safe_mode_on_next_start(request.enabled) {
+                Ok(_) => {
+                    send_packet(handle, packet, &SetSafeModeOnNextStartResult::Set).ok();
+                }
+
+                Err(err) => {
+                    log::error!("Failed to set safe mode for next start: {}", err);
+                    send_packet(handle, packet, &SetSafeModeOnNextStartResult::FailedToSet).ok();
+                }
+            }
+        }
+    }
+}
+
+/// Request for saving all configs and shutting down the daemon
+#[derive(Serialize, Deserialize)]
+pub enum ShutdownDaemonResult {
+    /// Sent if shutdown requests aren't allowed in daemon config
+    NotAllowed,
+
+    /// Sent if daemon is shutting down, [streamduck_core::modules::events::SDGlobalEvent::DaemonShuttingDown] event will follow
+    ShuttingDown,
+}
+
+impl SocketData for ShutdownDaemonResult {
+    const NAME: &'static str = "shutdown_daemon";
+}
+
+impl DaemonRequest for ShutdownDaemonResult {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if check_packet_for_data::<ShutdownDaemonResult>(packet) {
+            if !listener.config.allow_shutdown_requests() {
+                send_packet(handle, packet, &ShutdownDaemonResult::NotAllowed).ok();
+                return;
+            }
+
+            send_packet(handle, packet, &ShutdownDaemonResult::ShuttingDown).ok();
+            schedule_shutdown(listener.core_manager.clone(), listener.autosave.clone(), false);
+        }
+    }
+}
+
+/// Request for saving all configs and restarting the daemon with same arguments, eg. after installing plugins
+#[derive(Serialize, Deserialize)]
+pub enum RestartDaemonResult {
+    /// Sent if shutdown requests aren't allowed in daemon config
+    NotAllowed,
+
+    /// Sent if daemon is restarting, [streamduck_core::modules::events::SDGlobalEvent::DaemonShuttingDown] event will follow
+    Restarting,
+}
+
+impl SocketData for RestartDaemonResult {
+    const NAME: &'static str = "restart_daemon";
+}
+
+impl DaemonRequest for RestartDaemonResult {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if check_packet_for_data::<RestartDaemonResult>(packet) {
+            if !listener.config.allow_shutdown_requests() {
+                send_packet(handle, packet, &RestartDaemonResult::NotAllowed).ok();
+                return;
+            }
+
+            send_packet(handle, packet, &RestartDaemonResult::Restarting).ok();
+            schedule_shutdown(listener.core_manager.clone(), listener.autosave.clone(), true);
+        }
+    }
+}
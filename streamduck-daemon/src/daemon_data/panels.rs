@@ -2,12 +2,15 @@
 use std::collections::HashMap;
 use std::io::Cursor;
 use serde::{Deserialize, Serialize};
-use streamduck_core::core::methods::{CoreHandle, get_button_image, get_button_images, get_current_screen, get_root_screen, get_stack, pop_screen, push_screen, replace_screen, reset_stack};
+use streamduck_core::core::methods::{CoreHandle, get_button_image, get_button_images, get_current_screen, get_root_screen, get_stack, pop_screen, push_screen, render_button_preview, replace_screen, reset_stack, set_panel_buttons};
+use streamduck_core::core::button::Button;
 use streamduck_core::core::RawButtonPanel;
 use streamduck_core::image::ImageOutputFormat;
+use streamduck_core::modules::folders::{folder_panel, FolderStackData};
 use streamduck_core::socket::{parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket};
-use streamduck_core::util::{make_panel_unique, panel_to_raw};
+use streamduck_core::util::{make_button_unique, make_panel_unique, panel_to_raw};
 use crate::daemon_data::{DaemonListener, DaemonRequest};
+use crate::daemon_data::revisions::edit_at_revision;
 
 /// Request for getting current stack on a device
 #[derive(Serialize, Deserialize)]
@@ -99,6 +102,63 @@ impl DaemonRequest for GetStackNames {
     }
 }
 
+/// Request for getting breadcrumbs of current stack on a device, for navigating back through opened folders
+#[derive(Serialize, Deserialize)]
+pub struct GetStackBreadcrumbs {
+    pub serial_number: String
+}
+
+/// Panel on the stack
+#[derive(Serialize, Deserialize)]
+pub struct Breadcrumb {
+    pub display_name: String,
+    /// ID of folder that the panel shows, if panel is a folder
+    pub folder_id: Option<String>,
+}
+
+/// Response of [GetStackBreadcrumbs] request
+#[derive(Serialize, Deserialize)]
+pub enum GetStackBreadcrumbsResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if successfully got stack, starting with root screen
+    Breadcrumbs(Vec<Breadcrumb>)
+}
+
+impl SocketData for GetStackBreadcrumbs {
+    const NAME: &'static str = "get_stack_breadcrumbs";
+}
+
+impl SocketData for GetStackBreadcrumbsResult {
+    const NAME: &'static str = "get_stack_breadcrumbs";
+}
+
+impl DaemonRequest for GetStackBreadcrumbs {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<GetStackBreadcrumbs>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
+                let wrapped_core = CoreHandle::wrap(device.core);
+
+                let breadcrumbs = get_stack(&wrapped_core).into_iter()
+                    .map(|panel| {
+                        let panel = panel.read().unwrap();
+
+                        Breadcrumb {
+                            display_name: panel.display_name.clone(),
+                            folder_id: serde_json::from_value::<FolderStackData>(panel.data.clone()).ok().map(|x| x.folder_id)
+                        }
+                    })
+                    .collect();
+
+                send_packet(handle, packet, &GetStackBreadcrumbsResult::Breadcrumbs(breadcrumbs)).ok();
+            } else {
+                send_packet(handle, packet, &GetStackBreadcrumbsResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
+
 /// Request for getting current screen on a device
 #[derive(Serialize, Deserialize)]
 pub struct GetCurrentScreen {
@@ -245,6 +305,62 @@ impl DaemonRequest for GetButtonImage {
     }
 }
 
+/// Request for rendering a button that isn't on the screen, for previewing changes while editing
+#[derive(Serialize, Deserialize)]
+pub struct RenderButtonPreview {
+    pub serial_number: String,
+    /// Key that button is going to be on, custom renderers might use it
+    pub key: u8,
+    pub button: Button,
+    /// Size of the image, resolution of the device is used if width or height is 0
+    #[serde(default)]
+    pub width: u32,
+    #[serde(default)]
+    pub height: u32,
+}
+
+/// Response of [RenderButtonPreview] request
+#[derive(Serialize, Deserialize)]
+pub enum RenderButtonPreviewResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if button doesn't have renderer component
+    NoRenderer,
+
+    /// Sent if successfully rendered, contains base64 encoded PNG image
+    Image(String)
+}
+
+impl SocketData for RenderButtonPreview {
+    const NAME: &'static str = "render_button_preview";
+}
+
+impl SocketData for RenderButtonPreviewResult {
+    const NAME: &'static str = "render_button_preview";
+}
+
+impl DaemonRequest for RenderButtonPreview {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<RenderButtonPreview>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
+                let wrapped_core = CoreHandle::wrap(device.core);
+
+                if let Some(image) = render_button_preview(&wrapped_core, request.key, &request.button, (request.width, request.height)) {
+                    let mut buffer: Vec<u8> = vec![];
+                    image.write_to(&mut Cursor::new(&mut buffer), ImageOutputFormat::Png).ok();
+
+                    send_packet(handle, packet, &RenderButtonPreviewResult::Image(base64::encode(buffer))).ok();
+                } else {
+                    send_packet(handle, packet, &RenderButtonPreviewResult::NoRenderer).ok();
+                }
+            } else {
+                send_packet(handle, packet, &RenderButtonPreviewResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
+
 /// Request for pushing a new screen on a device
 #[derive(Serialize, Deserialize)]
 pub struct PushScreen {
@@ -415,6 +531,89 @@ impl DaemonRequest for ReplaceScreen {
     }
 }
 
+/// Request for replacing all buttons of current screen or a folder at once, only buttons of the panel are used.
+/// Device gets redrawn once and a single event is sent, instead of doing that for every button
+#[derive(Serialize, Deserialize)]
+pub struct SetPanel {
+    pub serial_number: String,
+    pub panel: RawButtonPanel,
+    /// Folder to set buttons of, current screen is used if not specified
+    #[serde(default)]
+    pub folder_id: Option<String>,
+    /// Revision of the screen that client has last seen, edit is rejected if anything on the device was changed since then
+    #[serde(default)]
+    pub revision: Option<u64>,
+}
+
+/// Response of [SetPanel] request
+#[derive(Serialize, Deserialize)]
+pub enum SetPanelResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if there's no screen
+    NoScreen,
+
+    /// Sent if folder wasn't found
+    FolderNotFound,
+
+    /// Sent if successfully set the panel
+    Set,
+
+    /// Sent if device was changed since provided revision, contains current revision
+    Conflict(u64),
+}
+
+impl SocketData for SetPanel {
+    const NAME: &'static str = "set_panel";
+}
+
+impl SocketData for SetPanelResult {
+    const NAME: &'static str = "set_panel";
+}
+
+impl DaemonRequest for SetPanel {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<SetPanel>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
+                let wrapped_core = CoreHandle::wrap(device.core);
+
+                let panel = if let Some(folder_id) = &request.folder_id {
+                    if let Some(panel) = folder_panel(&wrapped_core, folder_id) {
+                        panel
+                    } else {
+                        send_packet(handle, packet, &SetPanelResult::FolderNotFound).ok();
+                        return;
+                    }
+                } else {
+                    if let Some(panel) = get_current_screen(&wrapped_core) {
+                        panel
+                    } else {
+                        send_packet(handle, packet, &SetPanelResult::NoScreen).ok();
+                        return;
+                    }
+                };
+
+                let buttons = request.panel.buttons.into_iter()
+                    .map(|(key, button)| (key, make_button_unique(button)))
+                    .collect();
+
+                if let Err(revision) = edit_at_revision(&wrapped_core, &[], request.revision, || set_panel_buttons(&wrapped_core, &panel, buttons)) {
+                    send_packet(handle, packet, &SetPanelResult::Conflict(revision)).ok();
+                    return;
+                }
+
+                // Edits that history refers to no longer apply to the panel
+                listener.history.clear(&request.serial_number);
+
+                send_packet(handle, packet, &SetPanelResult::Set).ok();
+            } else {
+                send_packet(handle, packet, &SetPanelResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
+
 /// Request for resetting stack with provided screen
 #[derive(Serialize, Deserialize)]
 pub struct ResetStack {
@@ -493,4 +692,57 @@ impl DaemonRequest for DropStackToRoot {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Request for popping screens until screen at provided stack index is on top, root screen has index 0
+#[derive(Serialize, Deserialize)]
+pub struct PopToScreen {
+    pub serial_number: String,
+    pub index: usize,
+}
+
+/// Response of [PopToScreen] request
+#[derive(Serialize, Deserialize)]
+pub enum PopToScreenResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if there's no screen at the index
+    InvalidIndex,
+
+    /// Sent if successfully popped screens
+    Popped
+}
+
+impl SocketData for PopToScreen {
+    const NAME: &'static str = "pop_to_screen";
+}
+
+impl SocketData for PopToScreenResult {
+    const NAME: &'static str = "pop_to_screen";
+}
+
+impl DaemonRequest for PopToScreen {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<PopToScreen>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
+                let wrapped_core = CoreHandle::wrap(device.core);
+
+                let count = get_stack(&wrapped_core).len();
+
+                if request.index < count {
+                    // Popping one by one, so folders save their contents as they're closed
+                    for _ in request.index + 1..count {
+                        pop_screen(&wrapped_core);
+                    }
+
+                    send_packet(handle, packet, &PopToScreenResult::Popped).ok();
+                } else {
+                    send_packet(handle, packet, &PopToScreenResult::InvalidIndex).ok();
+                }
+            } else {
+                send_packet(handle, packet, &PopToScreenResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
@@ -0,0 +1,223 @@
+//! Requests related to component value presets
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use streamduck_core::core::methods::{CoreHandle, commit_changes, get_button, reset_stack, set_button};
+use streamduck_core::presets::{ComponentPreset, ComponentPresets, propagate_preset_in_config};
+use streamduck_core::socket::{check_packet_for_data, parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket};
+use streamduck_core::util::{button_to_raw, make_button_unique, make_panel_unique};
+use crate::daemon_data::{DaemonListener, DaemonRequest};
+
+/// Request for listing all saved presets
+#[derive(Serialize, Deserialize)]
+pub struct ListPresets {
+    pub presets: HashMap<String, ComponentPreset>
+}
+
+impl SocketData for ListPresets {
+    const NAME: &'static str = "list_presets";
+}
+
+impl DaemonRequest for ListPresets {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if check_packet_for_data::<ListPresets>(packet) {
+            send_packet(handle, packet, &ListPresets {
+                presets: listener.config.get_plugin_settings::<ComponentPresets>().unwrap_or_default().0
+            }).ok();
+        }
+    }
+}
+
+/// Request for creating or updating a preset
+#[derive(Serialize, Deserialize)]
+pub struct SavePreset {
+    pub name: String,
+    pub preset: ComponentPreset,
+    /// If true, buttons on all devices that matched previous version of the preset are updated
+    #[serde(default)]
+    pub propagate: bool,
+}
+
+/// Response of [SavePreset] request
+#[derive(Serialize, Deserialize)]
+pub enum SavePresetResult {
+    /// Sent if preset was saved, contains amount of buttons that were updated by propagation
+    Saved(usize),
+}
+
+impl SocketData for SavePreset {
+    const NAME: &'static str = "save_preset";
+}
+
+impl SocketData for SavePresetResult {
+    const NAME: &'static str = "save_preset";
+}
+
+impl DaemonRequest for SavePreset {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<SavePreset>(packet) {
+            let mut presets = listener.config.get_plugin_settings::<ComponentPresets>().unwrap_or_default();
+            let previous = presets.0.insert(request.name, request.preset.clone());
+            listener.config.set_plugin_settings(presets);
+
+            let updated = match previous {
+                Some(previous) if request.propagate && previous != request.preset => propagate(listener, &previous, &request.preset),
+                _ => 0
+            };
+
+            send_packet(handle, packet, &SavePresetResult::Saved(updated)).ok();
+        }
+    }
+}
+
+/// Updates buttons on all devices that match old preset, devices that had changes get their stack reset and config saved
+fn propagate(listener: &DaemonListener, old: &ComponentPreset, new: &ComponentPreset) -> usize {
+    let mut total = 0;
+
+    for config in listener.config.get_all_device_configs() {
+        let serial = config.read().unwrap().serial.clone();
+
+        let core = listener.core_manager.get_device(&serial)
+            .filter(|x| !x.core.is_closed())
+            .map(|x| CoreHandle::wrap(x.core));
+
+        // Making sure buttons that weren't committed yet are also considered
+        if let Some(core) = &core {
+            commit_changes(core);
+        }
+
+        let mut config_handle = config.write().unwrap();
+        let count = propagate_preset_in_config(&mut config_handle, old, new);
+        let layout = config_handle.layout.clone();
+        drop(config_handle);
+
+        if count == 0 {
+            continue;
+        }
+
+        total += count;
+
+        if let Some(core) = &core {
+            reset_stack(core, make_panel_unique(layout));
+        }
+
+        listener.config.sync_images(&serial);
+
+        if let Err(err) = listener.config.save_device_config(&serial) {
+            log::error!("Error encountered while saving config of {} after propagating preset: {:?}", serial, err);
+        }
+    }
+
+    total
+}
+
+/// Request for removing a preset, buttons that used it are left as is
+#[derive(Serialize, Deserialize)]
+pub struct RemovePreset {
+    pub name: String,
+}
+
+/// Response of [RemovePreset] request
+#[derive(Serialize, Deserialize)]
+pub enum RemovePresetResult {
+    /// Sent if preset wasn't found
+    PresetNotFound,
+
+    /// Sent if preset was removed
+    Removed,
+}
+
+impl SocketData for RemovePreset {
+    const NAME: &'static str = "remove_preset";
+}
+
+impl SocketData for RemovePresetResult {
+    const NAME: &'static str = "remove_preset";
+}
+
+impl DaemonRequest for RemovePreset {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<RemovePreset>(packet) {
+            let mut presets = listener.config.get_plugin_settings::<ComponentPresets>().unwrap_or_default();
+
+            if presets.0.remove(&request.name).is_some() {
+                listener.config.set_plugin_settings(presets);
+                send_packet(handle, packet, &RemovePresetResult::Removed).ok();
+            } else {
+                send_packet(handle, packet, &RemovePresetResult::PresetNotFound).ok();
+            }
+        }
+    }
+}
+
+/// Request for applying a preset onto a button on current screen
+#[derive(Serialize, Deserialize)]
+pub struct ApplyPreset {
+    pub serial_number: String,
+    pub key: u8,
+    pub name: String,
+}
+
+/// Response of [ApplyPreset] request
+#[derive(Serialize, Deserialize)]
+pub enum ApplyPresetResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if preset wasn't found
+    PresetNotFound,
+
+    /// Sent if there's no button on specified key
+    NoButton,
+
+    /// Sent if button doesn't have component that preset is for
+    Incompatible,
+
+    /// Sent if preset was applied
+    Applied,
+}
+
+impl SocketData for ApplyPreset {
+    const NAME: &'static str = "apply_preset";
+}
+
+impl SocketData for ApplyPresetResult {
+    const NAME: &'static str = "apply_preset";
+}
+
+impl DaemonRequest for ApplyPreset {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<ApplyPreset>(packet) {
+            let device = if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
+                device
+            } else {
+                send_packet(handle, packet, &ApplyPresetResult::DeviceNotFound).ok();
+                return;
+            };
+
+            let presets = listener.config.get_plugin_settings::<ComponentPresets>().unwrap_or_default();
+
+            let preset = if let Some(preset) = presets.0.get(&request.name) {
+                preset
+            } else {
+                send_packet(handle, packet, &ApplyPresetResult::PresetNotFound).ok();
+                return;
+            };
+
+            let wrapped_core = CoreHandle::wrap(device.core);
+
+            if let Some(button) = get_button(&wrapped_core, request.key) {
+                let mut button = button_to_raw(&button);
+
+                if preset.apply(&mut button) {
+                    set_button(&wrapped_core, request.key, make_button_unique(button));
+                    listener.config.sync_images(&request.serial_number);
+                    send_packet(handle, packet, &ApplyPresetResult::Applied).ok();
+                } else {
+                    send_packet(handle, packet, &ApplyPresetResult::Incompatible).ok();
+                }
+            } else {
+                send_packet(handle, packet, &ApplyPresetResult::NoButton).ok();
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Requests related to remote modules, see [remote](crate::remote) for how remote modules work
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use streamduck_core::modules::components::ComponentDefinition;
+use streamduck_core::modules::plugins::compare_plugin_versions;
+use streamduck_core::modules::PluginMetadata;
+use streamduck_core::socket::{parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket};
+use crate::daemon_data::{DaemonListener, DaemonRequest};
+use crate::remote::RemoteModuleError;
+
+/// Request for registering module that is implemented by the client
+#[derive(Serialize, Deserialize)]
+pub struct RegisterRemoteModule {
+    /// Token of session that will receive calls, see [StartSessionResult](crate::daemon_data::sessions::StartSessionResult)
+    pub session_token: String,
+    pub metadata: PluginMetadata,
+    #[serde(default)]
+    pub components: HashMap<String, ComponentDefinition>,
+    /// Components module wants to receive events for
+    #[serde(default)]
+    pub listening_for: Vec<String>,
+}
+
+/// Response of [RegisterRemoteModule] request
+#[derive(Serialize, Deserialize)]
+pub enum RegisterRemoteModuleResult {
+    /// Sent if session doesn't exist or has expired
+    SessionNotFound,
+
+    /// Sent if module is using features that daemon doesn't support, contains description of the problem
+    Incompatible(String),
+
+    /// Sent if module with the name already exists
+    AlreadyExists,
+
+    /// Sent if component with the name is already declared by other module
+    ComponentConflict(String),
+
+    /// Sent if module was registered
+    Registered,
+}
+
+impl SocketData for RegisterRemoteModule {
+    const NAME: &'static str = "register_remote_module";
+}
+
+impl SocketData for RegisterRemoteModuleResult {
+    const NAME: &'static str = "register_remote_module";
+}
+
+impl DaemonRequest for RegisterRemoteModule {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<RegisterRemoteModule>(packet) {
+            if let Err(err) = compare_plugin_versions(&request.metadata.used_features) {
+                send_packet(handle, packet, &RegisterRemoteModuleResult::Incompatible(format!("{:?}", err))).ok();
+                return;
+            }
+
+            let result = match listener.remote_modules.register(&request.session_token, request.metadata, request.components, request.listening_for) {
+                Ok(_) => RegisterRemoteModuleResult::Registered,
+                Err(RemoteModuleError::SessionNotFound) => RegisterRemoteModuleResult::SessionNotFound,
+                Err(RemoteModuleError::AlreadyExists) => RegisterRemoteModuleResult::AlreadyExists,
+                Err(RemoteModuleError::ComponentConflict(component)) => RegisterRemoteModuleResult::ComponentConflict(component),
+            };
+
+            send_packet(handle, packet, &result).ok();
+        }
+    }
+}
+
+/// Request for removing remote module
+#[derive(Serialize, Deserialize)]
+pub struct UnregisterRemoteModule {
+    pub name: String,
+}
+
+/// Response of [UnregisterRemoteModule] request
+#[derive(Serialize, Deserialize)]
+pub enum UnregisterRemoteModuleResult {
+    /// Sent if there's no remote module with the name
+    ModuleNotFound,
+
+    /// Sent if module was removed
+    Unregistered,
+}
+
+impl SocketData for UnregisterRemoteModule {
+    const NAME: &'static str = "unregister_remote_module";
+}
+
+impl SocketData for UnregisterRemoteModuleResult {
+    const NAME: &'static str = "unregister_remote_module";
+}
+
+impl DaemonRequest for UnregisterRemoteModule {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<UnregisterRemoteModule>(packet) {
+            let result = if listener.remote_modules.unregister(&request.name) {
+                UnregisterRemoteModuleResult::Unregistered
+            } else {
+                UnregisterRemoteModuleResult::ModuleNotFound
+            };
+
+            send_packet(handle, packet, &result).ok();
+        }
+    }
+}
+
+/// Request for answering a [RemoteModuleCall](crate::remote::RemoteModuleCall)
+#[derive(Serialize, Deserialize)]
+pub struct RemoteModuleResponse {
+    pub module: String,
+    pub call_id: u64,
+    pub data: Value,
+}
+
+/// Response of [RemoteModuleResponse] request
+#[derive(Serialize, Deserialize)]
+pub enum RemoteModuleResponseResult {
+    /// Sent if call isn't waiting for response anymore, usually because it timed out
+    CallNotFound,
+
+    /// Sent if response was handed over to the call
+    Accepted,
+}
+
+impl SocketData for RemoteModuleResponse {
+    const NAME: &'static str = "remote_module_response";
+}
+
+impl SocketData for RemoteModuleResponseResult {
+    const NAME: &'static str = "remote_module_response";
+}
+
+impl DaemonRequest for RemoteModuleResponse {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<RemoteModuleResponse>(packet) {
+            let result = if listener.remote_modules.respond(&request.module, request.call_id, request.data) {
+                RemoteModuleResponseResult::Accepted
+            } else {
+                RemoteModuleResponseResult::CallNotFound
+            };
+
+            send_packet(handle, packet, &result).ok();
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Revisions of current screen, for clients that edit buttons at the same time
+//!
+//! Editing requests accept revision that client has last seen, edits of buttons that were changed since then are rejected
+//! with `Conflict` response containing current revision. Changes are announced with
+//! [SDGlobalEvent::ScreenChanged](streamduck_core::modules::events::SDGlobalEvent::ScreenChanged)
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use streamduck_core::core::methods::CoreHandle;
+use streamduck_core::core::revisions::{button_revision, screen_revision, screen_revisions, ScreenRevisions};
+use streamduck_core::socket::{parse_packet_to_data, send_packet, SocketData, SocketHandle, SocketPacket};
+use crate::daemon_data::{DaemonListener, DaemonRequest};
+
+/// Makes revision check and edit happen at once, so two clients can't both pass the check with same revision
+static EDIT_LOCK: Mutex<()> = Mutex::new(());
+
+/// Runs the edit if none of the keys were changed since provided revision, otherwise returns current revision.
+/// Whole screen is checked if keys are empty, edit is always made if revision isn't provided
+pub(crate) fn edit_at_revision<T>(core: &CoreHandle, keys: &[u8], revision: Option<u64>, edit: impl FnOnce() -> T) -> Result<T, u64> {
+    let _lock = EDIT_LOCK.lock().unwrap();
+
+    if let Some(revision) = revision {
+        let latest = if keys.is_empty() {
+            screen_revision(core)
+        } else {
+            keys.iter().map(|key| button_revision(core, *key)).max().unwrap_or_default()
+        };
+
+        if latest > revision {
+            return Err(screen_revision(core));
+        }
+    }
+
+    Ok(edit())
+}
+
+/// Request for getting revisions of current screen on a device
+#[derive(Serialize, Deserialize)]
+pub struct GetScreenRevisions {
+    pub serial_number: String,
+}
+
+/// Response of [GetScreenRevisions] request
+#[derive(Serialize, Deserialize)]
+pub enum GetScreenRevisionsResult {
+    /// Sent if device wasn't found
+    DeviceNotFound,
+
+    /// Sent if successfully got revisions, should be requested before reading buttons to not miss changes made in between
+    Revisions(ScreenRevisions),
+}
+
+impl SocketData for GetScreenRevisions {
+    const NAME: &'static str = "get_screen_revisions";
+}
+
+impl SocketData for GetScreenRevisionsResult {
+    const NAME: &'static str = "get_screen_revisions";
+}
+
+impl DaemonRequest for GetScreenRevisions {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if let Ok(request) = parse_packet_to_data::<GetScreenRevisions>(packet) {
+            if let Some(device) = listener.core_manager.get_device(&request.serial_number) {
+                let revisions = screen_revisions(&CoreHandle::wrap(device.core));
+                send_packet(handle, packet, &GetScreenRevisionsResult::Revisions(revisions)).ok();
+            } else {
+                send_packet(handle, packet, &GetScreenRevisionsResult::DeviceNotFound).ok();
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! Requests related to persistent client sessions
+//!
+//! These requests depend on connection they came from, so socket implementations process them
+//! with [process_session_request] before handing packets over to listeners.
+//! Resuming a session restores authentication of the connection that started it, so it can be done before authenticating
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use streamduck_core::socket::{check_packet_for_data, parse_packet_to_data, send_packet, SESSION_GRACE_PERIOD, SocketClient, SocketData, SocketHandle, SocketManager, SocketPacket, SocketPool};
+
+/// Request for starting a session for current connection, so events can be resumed after reconnecting
+#[derive(Serialize, Deserialize)]
+pub enum StartSessionResult {
+    /// Sent if connection can't receive events, like request pipe on Windows
+    Unsupported,
+
+    /// Sent if session was started
+    Started {
+        token: String,
+        /// Seconds during which session can be resumed after disconnecting
+        grace_period: u64,
+    },
+}
+
+impl SocketData for StartSessionResult {
+    const NAME: &'static str = "start_session";
+}
+
+/// Request for resuming previous session on current connection
+#[derive(Serialize, Deserialize)]
+pub struct ResumeSession {
+    pub token: String,
+}
+
+/// Response of [ResumeSession] request
+#[derive(Serialize, Deserialize)]
+pub enum ResumeSessionResult {
+    /// Sent if connection can't receive events, like request pipe on Windows
+    Unsupported,
+
+    /// Sent if session doesn't exist or has expired
+    SessionNotFound,
+
+    /// Sent if session was resumed, events missed while disconnected will follow
+    Resumed {
+        missed_events: usize,
+    },
+}
+
+impl SocketData for ResumeSession {
+    const NAME: &'static str = "resume_session";
+}
+
+impl SocketData for ResumeSessionResult {
+    const NAME: &'static str = "resume_session";
+}
+
+/// Processes session requests, pool should be the one connection receives events from.
+/// Returns true if packet was a session request
+pub fn process_session_request(socket_manager: &SocketManager, client: &SocketClient, pool: Option<&Arc<SocketPool>>, handle: SocketHandle, packet: &SocketPacket) -> bool {
+    if check_packet_for_data::<StartSessionResult>(packet) {
+        let response = if let Some(pool) = pool {
+            StartSessionResult::Started {
+                token: socket_manager.start_session(pool, client.access()),
+                grace_period: SESSION_GRACE_PERIOD.as_secs()
+            }
+        } else {
+            StartSessionResult::Unsupported
+        };
+
+        send_packet(handle, packet, &response).ok();
+        return true;
+    }
+
+    if check_packet_for_data::<ResumeSession>(packet) {
+        if let Ok(request) = parse_packet_to_data::<ResumeSession>(packet) {
+            let response = if let Some(pool) = pool {
+                match socket_manager.resume_session(&request.token, pool) {
+                    Some((missed_events, access)) => {
+                        client.restore_access(access);
+                        ResumeSessionResult::Resumed { missed_events }
+                    }
+                    None => ResumeSessionResult::SessionNotFound,
+                }
+            } else {
+                ResumeSessionResult::Unsupported
+            };
+
+            send_packet(handle, packet, &response).ok();
+        }
+
+        return true;
+    }
+
+    false
+}
@@ -0,0 +1,143 @@
+//! Requests related to status of the daemon
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use streamduck_core::config::CONFIG_FILE_PATH;
+use streamduck_core::socket::{check_packet_for_data, send_packet, SocketData, SocketHandle, SocketPacket};
+use streamduck_core::versions::SOCKET_API;
+use crate::daemon_data::{DaemonListener, DaemonRequest};
+use crate::daemon_data::devices::{Device, DeviceType};
+
+/// Request for overall status of the daemon, for status pages and health checks
+#[derive(Serialize, Deserialize)]
+pub struct GetDaemonStatus {
+    /// Version of the daemon
+    pub version: String,
+    /// Version of socket API
+    pub socket_api: String,
+    /// Seconds since daemon has started
+    pub uptime: u64,
+    /// If daemon is running in safe mode
+    pub safe_mode: bool,
+    /// Loaded modules, including built-in modules
+    pub modules: Vec<ModuleStatus>,
+    /// Devices that are added to the daemon
+    pub devices: Vec<Device>,
+    pub paths: DaemonPaths,
+    /// Components that don't work as they should, empty if everything is fine
+    pub degraded: Vec<DegradedComponent>,
+}
+
+/// Loaded module and its state
+#[derive(Serialize, Deserialize)]
+pub struct ModuleStatus {
+    pub name: String,
+    pub version: String,
+    /// If module was loaded from a plugin
+    pub plugin: bool,
+    /// If plugin was disabled after panicking too many times
+    pub disabled: bool,
+}
+
+/// Paths that daemon uses for configuration
+#[derive(Serialize, Deserialize)]
+pub struct DaemonPaths {
+    pub config_file: PathBuf,
+    pub device_configs: PathBuf,
+    pub plugins: PathBuf,
+    pub plugin_settings: PathBuf,
+    pub fonts: PathBuf,
+}
+
+/// Component of the daemon that doesn't work as it should
+#[derive(Serialize, Deserialize)]
+pub enum DegradedComponent {
+    /// Daemon is running in safe mode, plugins and animations are disabled
+    SafeMode,
+    /// Plugin was disabled after panicking too many times
+    PluginDisabled(String),
+    /// Plugin couldn't be loaded
+    PluginFailed {
+        name: String,
+        reason: String,
+    },
+    /// Device that was added to the daemon is disconnected
+    DeviceOffline(String),
+}
+
+impl SocketData for GetDaemonStatus {
+    const NAME: &'static str = "get_daemon_status";
+}
+
+impl DaemonRequest for GetDaemonStatus {
+    fn process(listener: &DaemonListener, handle: SocketHandle, packet: &SocketPacket) {
+        if check_packet_for_data::<GetDaemonStatus>(packet) {
+            let module_manager = &listener.module_manager;
+            let mut degraded = vec![];
+
+            if listener.config.safe_mode() {
+                degraded.push(DegradedComponent::SafeMode);
+            }
+
+            let mut modules: Vec<ModuleStatus> = module_manager.get_module_list().iter()
+                .map(|module| {
+                    let metadata = module.metadata();
+                    let health = module_manager.plugin_health(&metadata.name);
+
+                    ModuleStatus {
+                        plugin: health.is_some(),
+                        disabled: health.map_or(false, |x| x.is_disabled()),
+                        name: metadata.name,
+                        version: metadata.version
+                    }
+                })
+                .collect();
+
+            modules.sort_by(|a, b| a.name.cmp(&b.name));
+
+            for module in modules.iter().filter(|x| x.disabled) {
+                degraded.push(DegradedComponent::PluginDisabled(module.name.clone()));
+            }
+
+            let mut failures: Vec<(String, String)> = module_manager.plugin_failures().into_iter().collect();
+            failures.sort();
+
+            for (name, reason) in failures {
+                degraded.push(DegradedComponent::PluginFailed { name, reason });
+            }
+
+            let mut devices: Vec<Device> = listener.core_manager.list_added_devices().values()
+                .map(|device| Device {
+                    device_type: DeviceType::from_pid(device.pid),
+                    serial_number: device.serial.clone(),
+                    managed: true,
+                    online: !device.core.is_closed()
+                })
+                .collect();
+
+            devices.sort_by(|a, b| a.serial_number.cmp(&b.serial_number));
+
+            for device in devices.iter().filter(|x| !x.online) {
+                degraded.push(DegradedComponent::DeviceOffline(device.serial_number.clone()));
+            }
+
+            let config = &listener.config;
+
+            send_packet(handle, packet, &GetDaemonStatus {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                socket_api: SOCKET_API.1.to_string(),
+                uptime: listener.started.elapsed().as_secs(),
+                safe_mode: config.safe_mode(),
+                modules,
+                devices,
+                paths: DaemonPaths {
+                    config_file: PathBuf::from(CONFIG_FILE_PATH),
+                    device_configs: config.device_config_path(),
+                    plugins: config.plugin_path(),
+                    plugin_settings: config.plugin_settings_path(),
+                    fonts: config.font_path()
+                },
+                degraded
+            }).ok();
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! Undo and redo of button edits that were made by requests
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use streamduck_core::core::button::{Button, Component};
+use streamduck_core::core::ButtonPanel;
+use streamduck_core::core::methods::{clear_button, CoreHandle, get_button, get_current_screen, move_button, set_button};
+use streamduck_core::modules::folders::FolderComponent;
+use streamduck_core::util::make_button_unique;
+
+/// Maximum amount of edits that are remembered per device
+const HISTORY_LIMIT: usize = 100;
+
+/// Single edit of one or more buttons
+struct Edit {
+    /// Panel that was edited, edit can only be undone or redone while this panel is on screen
+    panel: ButtonPanel,
+    /// Key that edit is reported with
+    key: u8,
+    change: Change,
+}
+
+/// What the edit did to the panel
+enum Change {
+    /// Buttons were replaced, added or removed
+    Buttons(Vec<KeyChange>),
+    /// Button was moved, moves are replayed as moves so modules see them as such
+    Move {
+        from: u8,
+        to: u8,
+        swap: bool,
+    },
+}
+
+/// State of a button before and after the edit
+struct KeyChange {
+    key: u8,
+    before: Option<Button>,
+    after: Option<Button>,
+}
+
+#[derive(Default)]
+struct DeviceHistory {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+/// Reasons for undo or redo not being applied
+#[derive(Debug)]
+pub enum HistoryError {
+    /// There's nothing to undo or redo
+    Empty,
+    /// Panel that was edited isn't on screen
+    DifferentScreen,
+    /// Button couldn't be changed
+    Failed,
+}
+
+/// Keeps track of button edits for each device
+pub struct EditHistory {
+    devices: Mutex<HashMap<String, DeviceHistory>>,
+}
+
+impl EditHistory {
+    /// Creates empty history
+    pub fn new() -> Arc<EditHistory> {
+        Arc::new(EditHistory {
+            devices: Default::default()
+        })
+    }
+
+    /// Runs edit of a button on current screen, recording it if the button was changed.
+    /// Edits of folder buttons aren't recorded and make history forget everything before them,
+    /// since replaying them could delete the folder's contents or restore a button of a deleted folder
+    pub fn track<T>(&self, serial: &str, core: &CoreHandle, key: u8, edit: impl FnOnce() -> T) -> T {
+        let panel = get_current_screen(core);
+        let before = button_state(core, key);
+
+        let result = edit();
+
+        let after = button_state(core, key);
+
+        if is_folder(&before) || is_folder(&after) {
+            self.clear(serial);
+            return result;
+        }
+
+        if let Some(panel) = panel {
+            if before.as_ref().map(|x| &x.0) != after.as_ref().map(|x| &x.0) {
+                self.record(serial, Edit {
+                    panel,
+                    key,
+                    change: Change::Buttons(vec![KeyChange { key, before, after }])
+                });
+            }
+        }
+
+        result
+    }
+
+    /// Moves a button on current screen, recording the move if it succeeded. Edit is reported with the key the button was moved to
+    pub fn track_move(&self, serial: &str, core: &CoreHandle, from: u8, to: u8, swap: bool) -> bool {
+        let panel = get_current_screen(core);
+
+        let moved = move_button(core, from, to, swap);
+
+        if let Some(panel) = panel {
+            if moved {
+                self.record(serial, Edit {
+                    panel,
+                    key: to,
+                    change: Change::Move { from, to, swap }
+                });
+            }
+        }
+
+        moved
+    }
+
+    fn record(&self, serial: &str, edit: Edit) {
+        let mut devices = self.devices.lock().unwrap();
+        let history = devices.entry(serial.to_string()).or_default();
+
+        history.undo.push(edit);
+
+        if history.undo.len() > HISTORY_LIMIT {
+            history.undo.remove(0);
+        }
+
+        history.redo.clear();
+    }
+
+    /// Reverts last edit, returns key of the button that was changed
+    pub fn undo(&self, serial: &str, core: &CoreHandle) -> Result<u8, HistoryError> {
+        self.step(serial, core, true)
+    }
+
+    /// Reapplies last undone edit, returns key of the button that was changed
+    pub fn redo(&self, serial: &str, core: &CoreHandle) -> Result<u8, HistoryError> {
+        self.step(serial, core, false)
+    }
+
+    /// Forgets all edits of a device
+    pub fn clear(&self, serial: &str) {
+        self.devices.lock().unwrap().remove(serial);
+    }
+
+    fn step(&self, serial: &str, core: &CoreHandle, undo: bool) -> Result<u8, HistoryError> {
+        let mut devices = self.devices.lock().unwrap();
+        let DeviceHistory { undo: undo_stack, redo: redo_stack } = devices.get_mut(serial).ok_or(HistoryError::Empty)?;
+
+        let (from, to) = if undo {
+            (undo_stack, redo_stack)
+        } else {
+            (redo_stack, undo_stack)
+        };
+
+        let edit = from.last().ok_or(HistoryError::Empty)?;
+
+        match get_current_screen(core) {
+            Some(screen) if Arc::ptr_eq(&screen, &edit.panel) => {}
+            _ => return Err(HistoryError::DifferentScreen),
+        }
+
+        let edit = from.pop().unwrap();
+
+        let applied = match &edit.change {
+            Change::Buttons(changes) => {
+                let mut applied = true;
+
+                for change in changes {
+                    let state = if undo { &change.before } else { &change.after };
+
+                    applied &= match state {
+                        Some(button) => set_button(core, change.key, make_button_unique(button.clone())),
+                        None => clear_button(core, change.key),
+                    };
+                }
+
+                applied
+            }
+
+            Change::Move { from, to, swap } => {
+                if undo {
+                    move_button(core, *to, *from, *swap)
+                } else {
+                    move_button(core, *from, *to, *swap)
+                }
+            }
+        };
+
+        if !applied {
+            from.push(edit);
+            return Err(HistoryError::Failed);
+        }
+
+        let key = edit.key;
+        to.push(edit);
+
+        Ok(key)
+    }
+}
+
+/// Copy of a button on current screen
+fn button_state(core: &CoreHandle, key: u8) -> Option<Button> {
+    get_button(core, key).map(|x| x.read().unwrap().clone())
+}
+
+/// Checks if button state is a folder button
+fn is_folder(button: &Option<Button>) -> bool {
+    button.as_ref().map_or(false, |x| x.contains(FolderComponent::NAME))
+}
@@ -0,0 +1,100 @@
+//! Global keyboard shortcuts that open folders or trigger buttons, configured in daemon config
+//!
+//! Listening for shortcuts is only built in if feature for the OS is enabled: `hotkeys-windows`, `hotkeys-linux` or `hotkeys-macos`
+
+use std::sync::Arc;
+use streamduck_core::config::{Config, HotkeyAction, HotkeyBinding};
+use streamduck_core::core::manager::CoreManager;
+use streamduck_core::core::methods::{button_action, CoreHandle, pop_screen, push_screen};
+use streamduck_core::modules::folders::folder_panel;
+
+/// Performs action of the shortcut on its device
+pub fn run_hotkey_action(core_manager: &Arc<CoreManager>, binding: &HotkeyBinding) {
+    let device = if let Some(device) = core_manager.get_device(&binding.serial) {
+        device
+    } else {
+        log::warn!("Hotkey '{}' refers to device {} that isn't added", binding.keys, binding.serial);
+        return;
+    };
+
+    let stack_size = device.core.current_stack.lock().unwrap().len();
+    let wrapped_core = CoreHandle::wrap(device.core);
+
+    match &binding.action {
+        HotkeyAction::PushFolder { folder_id } => {
+            if let Some(panel) = folder_panel(&wrapped_core, folder_id) {
+                push_screen(&wrapped_core, panel);
+            } else {
+                log::warn!("Hotkey '{}' refers to folder {} that doesn't exist", binding.keys, folder_id);
+            }
+        }
+
+        HotkeyAction::PressButton { key } => button_action(&wrapped_core, *key),
+
+        HotkeyAction::PopScreen => {
+            if stack_size > 1 {
+                pop_screen(&wrapped_core);
+            }
+        }
+    }
+}
+
+#[cfg(any(
+    all(feature = "hotkeys-windows", target_os = "windows"),
+    all(feature = "hotkeys-linux", target_os = "linux"),
+    all(feature = "hotkeys-macos", target_os = "macos")
+))]
+/// Registers shortcuts from the config and keeps listening for them, blocks forever
+pub fn listen_for_hotkeys(config: Arc<Config>, core_manager: Arc<CoreManager>) {
+    use livesplit_hotkey::{Hook, Hotkey};
+
+    let bindings = config.hotkeys();
+
+    if bindings.is_empty() {
+        return;
+    }
+
+    let hook = match Hook::new() {
+        Ok(hook) => hook,
+        Err(err) => {
+            log::error!("Failed to start listening for hotkeys: {:?}", err);
+            return;
+        }
+    };
+
+    for binding in bindings {
+        let hotkey = match binding.keys.parse::<Hotkey>() {
+            Ok(hotkey) => hotkey,
+            Err(_) => {
+                log::error!("Invalid hotkey '{}'", binding.keys);
+                continue;
+            }
+        };
+
+        let core_manager = core_manager.clone();
+        let keys = binding.keys.clone();
+
+        if let Err(err) = hook.register(hotkey, move || run_hotkey_action(&core_manager, &binding)) {
+            log::error!("Failed to register hotkey '{}': {:?}", keys, err);
+        } else {
+            log::info!("Registered hotkey '{}'", keys);
+        }
+    }
+
+    // Hotkeys are unregistered once hook is dropped
+    loop {
+        std::thread::park();
+    }
+}
+
+#[cfg(not(any(
+    all(feature = "hotkeys-windows", target_os = "windows"),
+    all(feature = "hotkeys-linux", target_os = "linux"),
+    all(feature = "hotkeys-macos", target_os = "macos")
+)))]
+/// Registers shortcuts from the config and keeps listening for them, blocks forever
+pub fn listen_for_hotkeys(config: Arc<Config>, _core_manager: Arc<CoreManager>) {
+    if !config.hotkeys().is_empty() {
+        log::warn!("Hotkeys are configured, but daemon was built without hotkey support for this OS");
+    }
+}
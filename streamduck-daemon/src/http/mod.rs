@@ -0,0 +1,224 @@
+//! HTTP bridge to daemon requests, with optional built-in web UI for headless machines
+//!
+//! `POST /api/<request name>` with request data as JSON body is processed the same way as socket
+//! requests and responds with response data as JSON. Requests without data are sent with empty body.
+//! API requests need `Content-Type: application/json` header, and requests made by web pages are only accepted from the daemon's own origin,
+//! so other sites can't send requests to the daemon through user's browser. Host of API requests has to be an IP address, localhost or the address
+//! daemon is bound to, so other sites can't reach the daemon through DNS rebinding.
+//! If daemon config has a socket token, API requests need `Authorization: Bearer <token>` header.
+//! HTTP API refuses to start on addresses other than loopback if there's no token.
+//! Requests authorized with read-only token, or all requests if HTTP API is set to be read-only, can only query the daemon
+use std::io::{Cursor, Read};
+use std::net::{IpAddr, ToSocketAddrs};
+use std::sync::Arc;
+use std::thread::spawn;
+use serde_json::Value;
+use crate::daemon_data::auth::SocketTokens;
+use streamduck_core::socket::{MAX_PACKET_SIZE, PACKET_TERMINATOR, parse_packet, SocketManager, SocketPacket};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Requester ID that HTTP requests are sent to listeners with
+const HTTP_REQUESTER: &str = "http";
+
+/// Path prefix of daemon requests
+const API_PREFIX: &str = "/api/";
+
+/// Amount of threads that handle HTTP requests
+const WORKER_COUNT: usize = 4;
+
+const INDEX_HTML: &str = include_str!("ui/index.html");
+const APP_JS: &str = include_str!("ui/app.js");
+const STYLE_CSS: &str = include_str!("ui/style.css");
+
+/// Serves HTTP API on provided address, blocks until server fails
+pub fn serve_http(address: &str, socket_manager: Arc<SocketManager>, ui: bool, read_only: bool, tokens: SocketTokens) {
+    if !is_loopback(address) && tokens.token.is_none() && tokens.read_only_token.is_none() {
+        log::error!("Refusing to serve HTTP API on {} without a socket token, set a token or use a loopback address", address);
+        return;
+    }
+
+    let server = match Server::http(address) {
+        Ok(server) => server,
+        Err(err) => {
+            log::error!("Failed to start HTTP API on {}: {}", address, err);
+            return;
+        }
+    };
+
+    log::info!("Serving HTTP API on {}", address);
+
+    let server = Arc::new(server);
+    let bind_host = host_name(address).to_string();
+
+    let workers: Vec<_> = (0..WORKER_COUNT).map(|_| {
+        let server = server.clone();
+        let socket_manager = socket_manager.clone();
+        let tokens = tokens.clone();
+        let bind_host = bind_host.clone();
+
+        spawn(move || {
+            for request in server.incoming_requests() {
+                handle_request(request, &socket_manager, ui, read_only, &tokens, &bind_host);
+            }
+        })
+    }).collect();
+
+    for worker in workers {
+        worker.join().ok();
+    }
+}
+
+fn handle_request(mut request: Request, socket_manager: &Arc<SocketManager>, ui: bool, read_only: bool, tokens: &SocketTokens, bind_host: &str) {
+    let url = request.url().to_string();
+    let access = authorize(&request, tokens);
+
+    let response = match (request.method(), url.as_str(), access) {
+        (Method::Post, path, _) if path.starts_with(API_PREFIX) && !is_allowed_host(&request, bind_host) => text_response(403, "Unknown host"),
+        (Method::Post, path, _) if path.starts_with(API_PREFIX) && !is_same_origin(&request) => text_response(403, "Cross-origin requests are not allowed"),
+        (Method::Post, path, _) if path.starts_with(API_PREFIX) && !is_json(&request) => text_response(415, "Content-Type should be application/json"),
+        (Method::Post, path, None) if path.starts_with(API_PREFIX) => text_response(401, "Unauthorized"),
+        (Method::Post, path, Some(token_read_only)) if path.starts_with(API_PREFIX) => {
+            let name = &path[API_PREFIX.len()..];
+
+            if (read_only || token_read_only) && !socket_manager.is_read_only_request(name) {
+                text_response(403, "Permission denied")
+            } else {
+                api_request(&mut request, name, socket_manager, read_only || token_read_only)
+            }
+        }
+
+        (Method::Get, "/", _) if ui => asset(INDEX_HTML, "text/html; charset=utf-8"),
+        (Method::Get, "/app.js", _) if ui => asset(APP_JS, "application/javascript; charset=utf-8"),
+        (Method::Get, "/style.css", _) if ui => asset(STYLE_CSS, "text/css; charset=utf-8"),
+
+        _ => text_response(404, "Not found"),
+    };
+
+    if let Err(err) = request.respond(response) {
+        log::warn!("Failed to respond to HTTP request: {}", err);
+    }
+}
+
+/// Checks bearer token of the request, returns [None] if request isn't authorized or if access is read-only otherwise.
+/// Every request has full access if there are no tokens
+fn authorize(request: &Request, tokens: &SocketTokens) -> Option<bool> {
+    if tokens.token.is_none() && tokens.read_only_token.is_none() {
+        return Some(false);
+    }
+
+    header(request, "Authorization")
+        .and_then(|x| x.strip_prefix("Bearer "))
+        .and_then(|x| tokens.check(x.trim()))
+}
+
+fn header<'a>(request: &'a Request, name: &str) -> Option<&'a str> {
+    request.headers().iter()
+        .find(|x| x.field.equiv(name))
+        .map(|x| x.value.as_str())
+}
+
+/// Checks that request made by a web page came from the page served by the daemon, requests without Origin don't come from browsers
+fn is_same_origin(request: &Request) -> bool {
+    match header(request, "Origin") {
+        Some(origin) => {
+            let origin_host = origin.split_once("://").map_or(origin, |(_, host)| host).trim_end_matches('/');
+            header(request, "Host").map_or(false, |host| host.eq_ignore_ascii_case(origin_host))
+        }
+        None => true
+    }
+}
+
+/// Checks if address only accepts connections from the same machine
+fn is_loopback(address: &str) -> bool {
+    address.to_socket_addrs()
+        .map(|x| x.collect::<Vec<_>>())
+        .map_or(false, |x| !x.is_empty() && x.iter().all(|x| x.ip().is_loopback()))
+}
+
+/// Strips port from host, IPv6 addresses keep their brackets
+fn host_name(host: &str) -> &str {
+    match host.find(']') {
+        Some(end) => &host[..=end],
+        None => host.split(':').next().unwrap_or(host),
+    }
+}
+
+/// Checks that request was sent to an IP address, localhost or host daemon is bound to,
+/// names that could be pointed at the daemon by other sites aren't accepted
+fn is_allowed_host(request: &Request, bind_host: &str) -> bool {
+    match header(request, "Host").map(host_name) {
+        Some(host) => host.eq_ignore_ascii_case("localhost")
+            || host.eq_ignore_ascii_case(bind_host)
+            || host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>().is_ok(),
+        None => false
+    }
+}
+
+/// Checks that request has JSON content type, browsers can't send such requests to other sites without a CORS preflight
+fn is_json(request: &Request) -> bool {
+    header(request, "Content-Type")
+        .map_or(false, |x| x.split(';').next().unwrap_or_default().trim().eq_ignore_ascii_case("application/json"))
+}
+
+/// Passes request to daemon listeners and responds with response data
+fn api_request(request: &mut Request, name: &str, socket_manager: &Arc<SocketManager>, read_only: bool) -> Response<Cursor<Vec<u8>>> {
+    let mut body = String::new();
+
+    if request.as_reader().take(MAX_PACKET_SIZE as u64 + 1).read_to_string(&mut body).is_err() {
+        return text_response(400, "Body should be UTF-8 JSON");
+    }
+
+    if body.len() > MAX_PACKET_SIZE {
+        return text_response(413, "Request is too large");
+    }
+
+    let data = if body.trim().is_empty() {
+        None
+    } else {
+        match serde_json::from_str::<Value>(&body) {
+            Ok(data) => Some(data),
+            Err(err) => return text_response(400, &format!("Invalid JSON: {}", err)),
+        }
+    };
+
+    let mut buffer: Vec<u8> = vec![];
+
+    let packet = SocketPacket {
+        ty: name.to_string(),
+        requester: Some(HTTP_REQUESTER.to_string()),
+        data
+    };
+
+    if read_only {
+        socket_manager.received_read_only_message(&mut buffer, packet);
+    } else {
+        socket_manager.received_message(&mut buffer, packet);
+    }
+
+    let response = buffer.split(|x| *x == PACKET_TERMINATOR)
+        .filter_map(|x| parse_packet(x).ok())
+        .find(|x| x.ty == name);
+
+    match response {
+        Some(packet) => {
+            let content = serde_json::to_vec(&packet.data.unwrap_or(Value::Null)).unwrap();
+            Response::from_data(content).with_header(content_type("application/json"))
+        }
+
+        None => text_response(404, "Unknown request or invalid request data"),
+    }
+}
+
+fn asset(content: &str, mime: &str) -> Response<Cursor<Vec<u8>>> {
+    Response::from_string(content).with_header(content_type(mime))
+}
+
+fn text_response(status: u16, message: &str) -> Response<Cursor<Vec<u8>>> {
+    Response::from_string(message)
+        .with_status_code(status)
+        .with_header(content_type("text/plain; charset=utf-8"))
+}
+
+fn content_type(mime: &str) -> Header {
+    Header::from_bytes(&b"Content-Type"[..], mime.as_bytes()).unwrap()
+}
@@ -0,0 +1,148 @@
+// Minimal web UI over daemon's HTTP API
+const COLUMNS = { Mini: 3, Original: 5, OriginalV2: 5, MK2: 5, XL: 8 };
+const REFRESH_INTERVAL = 2000;
+
+let devices = [];
+let selected = undefined;
+
+async function request(name, data) {
+    const headers = { "Content-Type": "application/json" };
+    const token = localStorage.getItem("streamduck-token");
+
+    if (token) {
+        headers["Authorization"] = "Bearer " + token;
+    }
+
+    const response = await fetch("api/" + name, {
+        method: "POST",
+        headers,
+        body: data === undefined ? "" : JSON.stringify(data)
+    });
+
+    if (response.status === 401) {
+        const entered = prompt("Daemon requires a token");
+
+        if (entered) {
+            localStorage.setItem("streamduck-token", entered);
+            return request(name, data);
+        }
+    }
+
+    if (!response.ok) {
+        throw new Error(await response.text());
+    }
+
+    return response.json();
+}
+
+function showMessage(text) {
+    document.getElementById("message").textContent = text;
+}
+
+// Result enums are serialized as either a string or an object with single key
+function variant(result) {
+    return typeof result === "string" ? [result, null] : Object.entries(result)[0];
+}
+
+async function loadDevices() {
+    devices = (await request("list_devices")).devices.filter(device => device.managed);
+
+    const select = document.getElementById("device");
+    select.replaceChildren(...devices.map(device => {
+        const option = document.createElement("option");
+        option.value = device.serial_number;
+        option.textContent = device.serial_number + " (" + device.device_type + (device.online ? "" : ", offline") + ")";
+        return option;
+    }));
+
+    const previous = selected;
+
+    if (!devices.some(device => device.serial_number === selected)) {
+        selected = devices.length > 0 ? devices[0].serial_number : null;
+    }
+
+    select.value = selected;
+
+    if (previous !== selected) {
+        await loadDevice();
+    } else if (selected !== null) {
+        await loadButtons(devices.find(device => device.serial_number === selected));
+    }
+}
+
+async function loadDevice() {
+    const details = document.getElementById("details");
+
+    if (selected === null) {
+        details.hidden = true;
+        showMessage("No managed devices");
+        return;
+    }
+
+    const device = devices.find(device => device.serial_number === selected);
+    details.hidden = false;
+    document.getElementById("status").textContent = device.online ? "Online" : "Offline";
+
+    const [configResult, config] = variant(await request("get_device_config", { serial_number: selected }));
+    if (configResult === "Config") {
+        document.getElementById("brightness").value = config.brightness;
+    }
+
+    await loadButtons(device);
+    showMessage("");
+}
+
+async function loadButtons(device) {
+    const [stackResult, names] = variant(await request("get_stack_names", { serial_number: selected }));
+    document.getElementById("stack").textContent = stackResult === "Stack" ? names.join(" / ") : "";
+
+    const [imagesResult, images] = variant(await request("get_button_images", { serial_number: selected }));
+    const container = document.getElementById("buttons");
+
+    if (imagesResult !== "Images") {
+        container.replaceChildren();
+        return;
+    }
+
+    const columns = COLUMNS[device.device_type] || 5;
+    const keys = Object.keys(images).map(Number);
+    const count = Math.max(columns * Math.ceil((Math.max(-1, ...keys) + 1) / columns), columns);
+
+    container.style.gridTemplateColumns = "repeat(" + columns + ", 72px)";
+    container.replaceChildren(...Array.from({ length: count }, (_, key) => {
+        const button = document.createElement("button");
+        button.title = "Key " + key;
+
+        if (images[key] !== undefined) {
+            const image = document.createElement("img");
+            image.src = "data:image/png;base64," + images[key];
+            image.alt = button.title;
+            button.appendChild(image);
+        }
+
+        button.addEventListener("click", () => press(key));
+        return button;
+    }));
+}
+
+async function press(key) {
+    try {
+        await request("do_button_action", { serial_number: selected, key: key });
+        await loadButtons(devices.find(device => device.serial_number === selected));
+    } catch (err) {
+        showMessage(err.message);
+    }
+}
+
+document.getElementById("device").addEventListener("change", event => {
+    selected = event.target.value;
+    loadDevice().catch(err => showMessage(err.message));
+});
+
+document.getElementById("brightness").addEventListener("change", event => {
+    request("set_brightness", { serial_number: selected, brightness: Number(event.target.value) })
+        .catch(err => showMessage(err.message));
+});
+
+loadDevices().catch(err => showMessage(err.message));
+setInterval(() => loadDevices().catch(err => showMessage(err.message)), REFRESH_INTERVAL);
@@ -1,5 +1,18 @@
 //! Library that provides definitions for daemon related features in streamduck
 pub mod daemon_data;
+pub mod autosave;
+pub mod history;
+pub mod transfer;
+pub mod remote;
+pub mod watcher;
+pub mod safe_mode;
+pub mod shutdown;
+pub mod http;
+pub mod metrics;
+pub mod hotkeys;
+
+use std::path::PathBuf;
+use streamduck_core::config::Config;
 
 /// Name that is used for request pipe on Windows
 pub const WINDOWS_REQUEST_PIPE_NAME: &'static str = "\\\\.\\pipe\\streamduck_requests";
@@ -7,4 +20,11 @@ pub const WINDOWS_REQUEST_PIPE_NAME: &'static str = "\\\\.\\pipe\\streamduck_req
 pub const WINDOWS_EVENT_PIPE_NAME: &'static str = "\\\\.\\pipe\\streamduck_events";
 
 /// Path to unix domain socket on Unix
-pub const UNIX_SOCKET_PATH: &'static str = "/tmp/streamduck.sock";
\ No newline at end of file
+pub const UNIX_SOCKET_PATH: &'static str = "/tmp/streamduck.sock";
+
+/// Path to unix domain socket from daemon config, or [UNIX_SOCKET_PATH] if it's not set
+pub fn unix_socket_path(config: &Config) -> PathBuf {
+    config.socket_path()
+        .map(|x| x.to_path_buf())
+        .unwrap_or_else(|| PathBuf::from(UNIX_SOCKET_PATH))
+}
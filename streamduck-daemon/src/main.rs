@@ -5,16 +5,29 @@ mod windows;
 
 use std::sync::{Arc, Mutex};
 use std::thread::spawn;
+use std::time::Instant;
 use flexi_logger::{DeferredNow, Logger, LogSpecification, style, TS_DASHES_BLANK_COLONS_DOT_BLANK};
 use log::{LevelFilter, Record};
 use streamduck_core::font::{load_default_font, load_fonts_from_resources};
+use streamduck_core::font::emoji::load_emoji_font;
 use streamduck_core::modules::{load_base_modules, ModuleManager};
 use streamduck_core::config::Config;
 use streamduck_core::core::manager::CoreManager;
 use streamduck_core::socket::SocketManager;
+use streamduck_core::logging::CapturingLogger;
 use streamduck_core::modules::plugins::load_plugins_from_folder;
 use streamduck_core::thread::rendering::custom::RenderingManager;
-use streamduck_daemon::daemon_data::DaemonListener;
+use streamduck_daemon::autosave::ConfigAutosave;
+use streamduck_daemon::daemon_data::{DaemonListener, READ_ONLY_REQUESTS};
+use streamduck_daemon::daemon_data::auth::SocketTokens;
+use streamduck_daemon::history::EditHistory;
+use streamduck_daemon::http::serve_http;
+use streamduck_daemon::metrics::serve_metrics;
+use streamduck_daemon::hotkeys::listen_for_hotkeys;
+use streamduck_daemon::safe_mode::{begin_boot, clear_boot_marker, should_start_in_safe_mode};
+use streamduck_daemon::watcher::watch_device_configs;
+use streamduck_daemon::transfer::TransferManager;
+use streamduck_daemon::remote::RemoteModuleHost;
 
 fn logging_format(
     w: &mut dyn std::io::Write,
@@ -32,14 +45,19 @@ fn logging_format(
 }
 
 fn main() {
+    let started = Instant::now();
+
     // Initializing logger
     let mut builder = LogSpecification::builder();
     builder.default(LevelFilter::Debug)
         .module("streamdeck", LevelFilter::Off);
 
-    Logger::with(builder.build())
+    // Records are captured before being written, so clients can view recent logs
+    let (logger, _logger_handle) = Logger::with(builder.build())
         .format(logging_format)
-        .start().unwrap();
+        .build().unwrap();
+
+    CapturingLogger::new(logger).install(LevelFilter::Debug).unwrap();
 
     log::info!("Streamduck Daemon");
 
@@ -52,13 +70,26 @@ fn main() {
     // Reading config
     let config = Arc::new(Config::get());
 
+    // Checking for safe mode
+    let safe_mode = should_start_in_safe_mode();
+    config.set_safe_mode(safe_mode);
+
+    if safe_mode {
+        log::warn!("Starting in safe mode, plugins and animations are disabled, known good device configs will be used");
+    }
+
+    begin_boot(config.clone());
+
     // Initializing socket manager
     let socket_manager = SocketManager::new();
+    socket_manager.set_auth_required(config.socket_token().is_some() || config.read_only_token().is_some());
+    socket_manager.allow_for_read_only(READ_ONLY_REQUESTS);
 
     // Initializing core stuff
     load_base_modules(module_manager.clone(), socket_manager.clone());
     load_default_font();
-    load_fonts_from_resources();
+    load_fonts_from_resources(&config.font_path(), config.system_fonts());
+    load_emoji_font();
 
     // Initializing built-in modules
     streamduck_actions::init_module(&module_manager);
@@ -66,16 +97,26 @@ fn main() {
     // Initializing core manager
     let core_manager = CoreManager::new(module_manager.clone(), render_manager.clone(), socket_manager.clone(), config.clone());
 
+    // Initializing config autosave
+    let autosave = ConfigAutosave::new(config.clone());
+
     // Adding daemon listener
     socket_manager.add_listener(Box::new(DaemonListener {
         core_manager: core_manager.clone(),
         module_manager: module_manager.clone(),
         config: config.clone(),
-        clipboard: Mutex::new(None)
+        clipboard: Mutex::new(None),
+        autosave: autosave.clone(),
+        history: EditHistory::new(),
+        transfers: TransferManager::new(),
+        remote_modules: RemoteModuleHost::new(module_manager.clone(), socket_manager.clone()),
+        started
     }));
 
     // Loading plugins
-    load_plugins_from_folder(module_manager.clone(), socket_manager.clone(), render_manager.clone(), config.plugin_path());
+    if !safe_mode {
+        load_plugins_from_folder(module_manager.clone(), socket_manager.clone(), render_manager.clone(), config.plugin_path());
+    }
 
     // Announcing loaded modules
     for (module_name, _) in module_manager.get_modules() {
@@ -85,6 +126,12 @@ fn main() {
     // Loading device configs
     config.reload_device_configs().ok();
 
+    if safe_mode {
+        if let Err(err) = config.reload_known_good_device_configs() {
+            log::error!("Failed to load known good device configs: {:?}", err);
+        }
+    }
+
     // Adding devices from config
     core_manager.add_devices_from_config();
 
@@ -94,31 +141,83 @@ fn main() {
         spawn(move || manager.reconnect_routine());
     }
 
+    // Spawning device config watcher
+    if config.watch_device_configs() {
+        let config = config.clone();
+        let manager = core_manager.clone();
+        let socket_manager = socket_manager.clone();
+        spawn(move || watch_device_configs(config, manager, socket_manager));
+    }
+
+    // Spawning HTTP API
+    if let Some(address) = config.http_address() {
+        let address = address.to_string();
+        let socket_manager = socket_manager.clone();
+        let ui = config.http_ui();
+        let read_only = config.http_read_only();
+        let tokens = SocketTokens::from_config(&config);
+        spawn(move || serve_http(&address, socket_manager, ui, read_only, tokens));
+    }
+
+    // Spawning metrics exporter
+    if let Some(address) = config.metrics_address() {
+        let address = address.to_string();
+        let manager = core_manager.clone();
+        let socket_manager = socket_manager.clone();
+        spawn(move || serve_metrics(&address, manager, socket_manager));
+    }
+
+    // Spawning global hotkey listener
+    {
+        let config = config.clone();
+        let manager = core_manager.clone();
+        spawn(move || listen_for_hotkeys(config, manager));
+    }
+
+    // Spawning autosave routine
+    {
+        let autosave = autosave.clone();
+        spawn(move || autosave.run());
+    }
+
+    // Saving stack paths as devices navigate folders
+    if config.persist_stack() {
+        let autosave = autosave.clone();
+        let socket_manager = socket_manager.clone();
+        spawn(move || autosave.watch_events(&socket_manager));
+    }
+
     // Registering interrupt handle
-    ctrlc::set_handler(move || {
-        clean_socket();
-        std::process::exit(0);
-    }).expect("Error setting Ctrl-C handler");
+    {
+        let config = config.clone();
+
+        ctrlc::set_handler(move || {
+            autosave.flush();
+            clear_boot_marker();
+            clean_socket(&config);
+            std::process::exit(0);
+        }).expect("Error setting Ctrl-C handler");
+    }
 
-    run_socket(socket_manager.clone());
+    run_socket(socket_manager.clone(), config);
 }
 
 #[cfg(target_family = "windows")]
-fn run_socket(socket_manager: Arc<SocketManager>) {
-    windows::open_socket(socket_manager)
+fn run_socket(socket_manager: Arc<SocketManager>, config: Arc<Config>) {
+    windows::open_socket(socket_manager, SocketTokens::from_config(&config))
 }
 
 #[cfg(target_family = "windows")]
-fn clean_socket() {
+fn clean_socket(_config: &Config) {
     // cleanup not needed
 }
 
 #[cfg(target_family = "unix")]
-fn run_socket(socket_manager: Arc<SocketManager>) {
-    unix::open_socket(socket_manager)
+fn run_socket(socket_manager: Arc<SocketManager>, config: Arc<Config>) {
+    unix::open_socket(socket_manager, config)
 }
 
 #[cfg(target_family = "unix")]
-fn clean_socket() {
-    unix::remove_socket()
+fn clean_socket(config: &Config) {
+    unix::remove_socket(config)
 }
\ No newline at end of file
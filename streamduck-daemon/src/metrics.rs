@@ -0,0 +1,41 @@
+//! Prometheus metrics exporter
+//!
+//! `GET /metrics` responds with render times, USB write latencies, key event counts,
+//! plugin callback durations, device and client counts in Prometheus text format
+use std::sync::Arc;
+use streamduck_core::core::manager::CoreManager;
+use streamduck_core::metrics::{enable_metrics, render_metrics};
+use streamduck_core::socket::SocketManager;
+use tiny_http::{Header, Method, Response, Server};
+
+/// Serves metrics on provided address, blocks until server fails
+pub fn serve_metrics(address: &str, core_manager: Arc<CoreManager>, socket_manager: Arc<SocketManager>) {
+    let server = match Server::http(address) {
+        Ok(server) => server,
+        Err(err) => {
+            log::error!("Failed to start metrics exporter on {}: {}", address, err);
+            return;
+        }
+    };
+
+    enable_metrics();
+
+    log::info!("Serving metrics on {}", address);
+
+    for request in server.incoming_requests() {
+        let response = match (request.method(), request.url()) {
+            (Method::Get, "/metrics") => {
+                let content = render_metrics(core_manager.list_added_devices().len(), socket_manager.list_clients().len());
+
+                Response::from_string(content)
+                    .with_header(Header::from_bytes(&b"Content-Type"[..], &b"text/plain; version=0.0.4"[..]).unwrap())
+            }
+
+            _ => Response::from_string("Not found").with_status_code(404),
+        };
+
+        if let Err(err) = request.respond(response) {
+            log::warn!("Failed to respond to metrics request: {}", err);
+        }
+    }
+}
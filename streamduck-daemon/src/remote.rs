@@ -0,0 +1,392 @@
+//! Modules that live in separate processes and talk to daemon over the socket protocol
+//!
+//! Remote process starts a session on a connection that receives events, then registers module with the session token.
+//! Daemon sends [RemoteModuleCall] packets to the session, calls that expect a result have to be answered with
+//! [RemoteModuleResponse](crate::daemon_data::remote::RemoteModuleResponse) request within [REMOTE_CALL_TIMEOUT].
+//! Module is removed once its session expires. Remote modules can't draw on buttons
+
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex, RwLock, Weak};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread::spawn;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use streamduck_core::core::button::Button;
+use streamduck_core::core::manager::CoreManager;
+use streamduck_core::core::methods::CoreHandle;
+use streamduck_core::modules::components::{ComponentDefinition, FieldError, UIValue};
+use streamduck_core::modules::events::{core_event_to_global, SDCoreEvent, SDGlobalEvent};
+use streamduck_core::modules::{ModuleManager, PluginMetadata, SDModule};
+use streamduck_core::socket::{SocketManager, SocketPacket};
+
+/// Time daemon waits for remote module to answer a call
+pub const REMOTE_CALL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Type of packets that daemon sends to remote modules
+pub const REMOTE_CALL_PACKET: &'static str = "remote_module_call";
+
+/// Packet that is sent to remote module
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteModuleCall {
+    /// Name of module the call is meant for
+    pub module: String,
+    /// ID that should be used in response, [None] if call doesn't expect a response
+    pub call_id: Option<u64>,
+    pub call: RemoteCall,
+}
+
+/// Module callbacks that are forwarded to remote module, comments describe expected response data
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RemoteCall {
+    /// Button with component added
+    AddComponent { serial_number: String, button: Button, name: String },
+    /// Button with component removed
+    RemoveComponent { serial_number: String, button: Button, name: String },
+    /// New button with components of this module copied from reference button
+    PasteComponent { serial_number: String, reference_button: Button, new_button: Button },
+    /// Array of UI values
+    ComponentValues { serial_number: String, button: Button, name: String },
+    /// Button with values applied, or array of field errors if values were rejected
+    SetComponentValue { serial_number: String, button: Button, name: String, value: Vec<UIValue> },
+    /// Array of UI values
+    Settings,
+    /// No response
+    SetSetting { value: Vec<UIValue> },
+    /// No response
+    GlobalEvent { event: SDGlobalEvent },
+    /// No response
+    Event { serial_number: String, event: SDGlobalEvent },
+}
+
+/// Reasons for remote module not being registered
+#[derive(Debug)]
+pub enum RemoteModuleError {
+    SessionNotFound,
+    AlreadyExists,
+    /// Contains name of conflicting component
+    ComponentConflict(String),
+}
+
+/// Connection to remote module
+struct RemoteConnection {
+    session_token: String,
+    socket_manager: Arc<SocketManager>,
+    responses: Mutex<HashMap<u64, Option<Value>>>,
+    condvar: Condvar,
+    counter: AtomicU64,
+    connected: AtomicBool,
+}
+
+impl RemoteConnection {
+    /// Sends call to remote module, returns false if session of the module has expired
+    fn send(&self, module: &str, call_id: Option<u64>, call: RemoteCall) -> bool {
+        let call = RemoteModuleCall {
+            module: module.to_string(),
+            call_id,
+            call
+        };
+
+        let sent = self.socket_manager.send_to_session(&self.session_token, SocketPacket {
+            ty: REMOTE_CALL_PACKET.to_string(),
+            requester: None,
+            data: Some(serde_json::to_value(call).unwrap())
+        });
+
+        if !sent {
+            self.connected.store(false, Ordering::Relaxed);
+        }
+
+        sent
+    }
+
+    /// Sends call and waits for response, returns [None] if remote module didn't answer in time
+    fn call<T: DeserializeOwned>(&self, module: &str, call: RemoteCall) -> Option<T> {
+        let id = self.counter.fetch_add(1, Ordering::SeqCst);
+        self.responses.lock().unwrap().insert(id, None);
+
+        if !self.send(module, Some(id), call) {
+            self.responses.lock().unwrap().remove(&id);
+            return None;
+        }
+
+        let deadline = Instant::now() + REMOTE_CALL_TIMEOUT;
+        let mut responses = self.responses.lock().unwrap();
+
+        let response = loop {
+            if let Some(Some(_)) = responses.get(&id) {
+                break responses.remove(&id).flatten();
+            }
+
+            let now = Instant::now();
+
+            if now >= deadline {
+                responses.remove(&id);
+                log::warn!("Remote module '{}' didn't respond in time", module);
+                break None;
+            }
+
+            responses = self.condvar.wait_timeout(responses, deadline - now).unwrap().0;
+        };
+
+        drop(responses);
+
+        match serde_json::from_value(response?) {
+            Ok(data) => Some(data),
+            Err(err) => {
+                log::warn!("Remote module '{}' sent invalid response: {}", module, err);
+                None
+            }
+        }
+    }
+
+    /// Puts response for call that's waiting for it, returns false if call isn't waiting anymore
+    fn respond(&self, call_id: u64, data: Value) -> bool {
+        let mut responses = self.responses.lock().unwrap();
+
+        if let Some(response) = responses.get_mut(&call_id) {
+            *response = Some(data);
+            self.condvar.notify_all();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Module that forwards its callbacks to remote process
+struct RemoteModule {
+    metadata: PluginMetadata,
+    components: HashMap<String, ComponentDefinition>,
+    listening_for: Vec<String>,
+    connection: Arc<RemoteConnection>,
+    module_manager: Weak<ModuleManager>,
+}
+
+impl RemoteModule {
+    fn notify(&self, call: RemoteCall) {
+        if !self.connection.send(&self.metadata.name, None, call) {
+            self.disconnected();
+        }
+    }
+
+    fn call<T: DeserializeOwned>(&self, call: RemoteCall) -> Option<T> {
+        let result = self.connection.call(&self.metadata.name, call);
+
+        if !self.connection.connected.load(Ordering::Relaxed) {
+            self.disconnected();
+        }
+
+        result
+    }
+
+    /// Removes module from module manager on another thread, since module manager might be locked by caller
+    fn disconnected(&self) {
+        if let Some(module_manager) = self.module_manager.upgrade() {
+            let name = self.metadata.name.clone();
+
+            spawn(move || {
+                if module_manager.remove_module(&name).is_some() {
+                    log::info!("Remote module '{}' has disconnected", name);
+                }
+            });
+        }
+    }
+
+    /// Replaces button with button that remote module returned
+    fn call_for_button(&self, button: &mut Button, call: RemoteCall) {
+        if let Some(new_button) = self.call::<Button>(call) {
+            *button = new_button;
+        }
+    }
+}
+
+impl SDModule for RemoteModule {
+    fn name(&self) -> String {
+        self.metadata.name.clone()
+    }
+
+    fn components(&self) -> HashMap<String, ComponentDefinition> {
+        self.components.clone()
+    }
+
+    fn add_component(&self, core: CoreHandle, button: &mut Button, name: &str) {
+        self.call_for_button(button, RemoteCall::AddComponent {
+            serial_number: core.core.serial_number(),
+            button: button.clone(),
+            name: name.to_string()
+        });
+    }
+
+    fn remove_component(&self, core: CoreHandle, button: &mut Button, name: &str) {
+        self.call_for_button(button, RemoteCall::RemoveComponent {
+            serial_number: core.core.serial_number(),
+            button: button.clone(),
+            name: name.to_string()
+        });
+    }
+
+    fn paste_component(&self, core: CoreHandle, reference_button: &Button, new_button: &mut Button) {
+        self.call_for_button(new_button, RemoteCall::PasteComponent {
+            serial_number: core.core.serial_number(),
+            reference_button: reference_button.clone(),
+            new_button: new_button.clone()
+        });
+    }
+
+    fn component_values(&self, core: CoreHandle, button: &Button, name: &str) -> Vec<UIValue> {
+        self.call(RemoteCall::ComponentValues {
+            serial_number: core.core.serial_number(),
+            button: button.clone(),
+            name: name.to_string()
+        }).unwrap_or_default()
+    }
+
+    fn set_component_value(&self, core: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum SetValueResponse {
+            Button(Button),
+            Errors(Vec<FieldError>),
+        }
+
+        let response = self.call(RemoteCall::SetComponentValue {
+            serial_number: core.core.serial_number(),
+            button: button.clone(),
+            name: name.to_string(),
+            value
+        });
+
+        match response {
+            Some(SetValueResponse::Button(new_button)) => {
+                *button = new_button;
+                Ok(())
+            }
+
+            Some(SetValueResponse::Errors(errors)) => Err(errors),
+
+            None => Err(vec![])
+        }
+    }
+
+    fn listening_for(&self) -> Vec<String> {
+        self.listening_for.clone()
+    }
+
+    fn settings(&self, _: Arc<CoreManager>) -> Vec<UIValue> {
+        self.call(RemoteCall::Settings).unwrap_or_default()
+    }
+
+    fn set_setting(&self, _: Arc<CoreManager>, value: Vec<UIValue>) {
+        self.notify(RemoteCall::SetSetting { value });
+    }
+
+    fn global_event(&self, event: SDGlobalEvent) {
+        self.notify(RemoteCall::GlobalEvent { event });
+    }
+
+    fn event(&self, core: CoreHandle, event: SDCoreEvent) {
+        let serial_number = core.core.serial_number();
+
+        self.notify(RemoteCall::Event {
+            event: core_event_to_global(event, &serial_number),
+            serial_number
+        });
+    }
+
+    fn metadata(&self) -> PluginMetadata {
+        self.metadata.clone()
+    }
+}
+
+/// Keeps connections of remote modules
+pub struct RemoteModuleHost {
+    module_manager: Arc<ModuleManager>,
+    socket_manager: Arc<SocketManager>,
+    connections: RwLock<HashMap<String, Arc<RemoteConnection>>>,
+}
+
+impl RemoteModuleHost {
+    /// Creates host without any remote modules
+    pub fn new(module_manager: Arc<ModuleManager>, socket_manager: Arc<SocketManager>) -> Arc<RemoteModuleHost> {
+        Arc::new(RemoteModuleHost {
+            module_manager,
+            socket_manager,
+            connections: Default::default()
+        })
+    }
+
+    /// Registers remote module that receives calls through the session
+    pub fn register(&self, session_token: &str, metadata: PluginMetadata, components: HashMap<String, ComponentDefinition>, listening_for: Vec<String>) -> Result<(), RemoteModuleError> {
+        self.cleanup();
+
+        if self.module_manager.get_module(&metadata.name).is_some() {
+            return Err(RemoteModuleError::AlreadyExists);
+        }
+
+        if let Some(component) = components.keys().find(|x| self.module_manager.get_component(x).is_some()) {
+            return Err(RemoteModuleError::ComponentConflict(component.clone()));
+        }
+
+        if !self.socket_manager.session_exists(session_token) {
+            return Err(RemoteModuleError::SessionNotFound);
+        }
+
+        let connection = Arc::new(RemoteConnection {
+            session_token: session_token.to_string(),
+            socket_manager: self.socket_manager.clone(),
+            responses: Default::default(),
+            condvar: Default::default(),
+            counter: AtomicU64::new(0),
+            connected: AtomicBool::new(true)
+        });
+
+        let name = metadata.name.clone();
+
+        self.connections.write().unwrap().insert(name.clone(), connection.clone());
+        self.module_manager.add_module(Arc::new(Box::new(RemoteModule {
+            metadata,
+            components,
+            listening_for,
+            connection,
+            module_manager: Arc::downgrade(&self.module_manager)
+        })));
+
+        log::info!("Remote module '{}' has registered", name);
+
+        Ok(())
+    }
+
+    /// Removes remote module, returns false if there's no remote module with the name
+    pub fn unregister(&self, name: &str) -> bool {
+        if self.connections.write().unwrap().remove(name).is_some() {
+            self.module_manager.remove_module(name);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Hands response over to call that's waiting for it, returns false if module or call wasn't found
+    pub fn respond(&self, module: &str, call_id: u64, data: Value) -> bool {
+        let connection = self.connections.read().unwrap().get(module).cloned();
+        connection.map_or(false, |x| x.respond(call_id, data))
+    }
+
+    /// Names of registered remote modules
+    pub fn module_names(&self) -> Vec<String> {
+        self.cleanup();
+
+        let mut names: Vec<String> = self.connections.read().unwrap().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Forgets connections of modules that were removed from module manager
+    fn cleanup(&self) {
+        self.connections.write().unwrap().retain(|name, connection| {
+            connection.connected.load(Ordering::Relaxed) && self.module_manager.get_module(name).is_some()
+        });
+    }
+}
@@ -0,0 +1,75 @@
+//! Safe mode for recovering from plugins or configs that crash the daemon
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread::{sleep, spawn};
+use std::time::Duration;
+use streamduck_core::config::Config;
+
+/// Command line flag that starts daemon in safe mode
+pub const SAFE_MODE_FLAG: &'static str = "--safe-mode";
+
+/// File that requests safe mode on next start
+pub const SAFE_MODE_MARKER: &'static str = "safe_mode";
+
+/// File that exists while daemon is starting up, if it's still there on next start, previous start has crashed
+pub const BOOT_MARKER: &'static str = "booting";
+
+/// How long daemon should run before current configs are considered good
+const BOOT_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// Decides if daemon should start in safe mode, either due to flag, request or a crash during previous start
+pub fn should_start_in_safe_mode() -> bool {
+    let flag = std::env::args().any(|x| x == SAFE_MODE_FLAG);
+    let requested = Path::new(SAFE_MODE_MARKER).exists();
+    let crashed = Path::new(BOOT_MARKER).exists();
+
+    if requested {
+        fs::remove_file(SAFE_MODE_MARKER).ok();
+    }
+
+    if crashed {
+        log::warn!("Daemon didn't finish starting up last time");
+    }
+
+    flag || requested || crashed
+}
+
+/// Sets or clears request for safe mode on next start
+pub fn set_safe_mode_on_next_start(enabled: bool) -> std::io::Result<()> {
+    if enabled {
+        fs::write(SAFE_MODE_MARKER, "")
+    } else if Path::new(SAFE_MODE_MARKER).exists() {
+        fs::remove_file(SAFE_MODE_MARKER)
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks if safe mode was requested for next start
+pub fn is_safe_mode_on_next_start() -> bool {
+    Path::new(SAFE_MODE_MARKER).exists()
+}
+
+/// Marks start of the daemon, after grace period the marker is removed and if not in safe mode, device configs are marked as known good
+pub fn begin_boot(config: Arc<Config>) {
+    fs::write(BOOT_MARKER, "").ok();
+
+    spawn(move || {
+        sleep(BOOT_GRACE_PERIOD);
+
+        clear_boot_marker();
+
+        if !config.safe_mode() {
+            if let Err(err) = config.mark_device_configs_good() {
+                log::warn!("Failed to mark device configs as known good: {:?}", err);
+            }
+        }
+    });
+}
+
+/// Removes boot marker, for when daemon is being shut down normally
+pub fn clear_boot_marker() {
+    fs::remove_file(BOOT_MARKER).ok();
+}
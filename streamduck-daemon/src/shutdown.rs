@@ -0,0 +1,81 @@
+//! Graceful shutdown and restart of the daemon
+
+use std::process::{Command, exit};
+use std::sync::Arc;
+use std::thread::{sleep, spawn};
+use std::time::Duration;
+use streamduck_core::core::manager::CoreManager;
+use streamduck_core::modules::events::SDGlobalEvent;
+use streamduck_core::socket::send_event_to_socket;
+use crate::autosave::ConfigAutosave;
+use crate::safe_mode::clear_boot_marker;
+
+/// How long to wait after announcing shutdown, so clients receive the response and the event
+const SHUTDOWN_DELAY: Duration = Duration::from_millis(500);
+
+/// Saves all device configs, announces shutdown to clients and closes devices
+pub fn prepare_shutdown(core_manager: &CoreManager, autosave: &ConfigAutosave, restart: bool) {
+    autosave.flush();
+
+    if let Err(err) = core_manager.config.save_device_configs() {
+        log::error!("Failed to save device configs before shutting down: {:?}", err);
+    }
+
+    send_event_to_socket(&core_manager.socket_manager, SDGlobalEvent::DaemonShuttingDown {
+        restart
+    });
+
+    for device in core_manager.list_added_devices().values() {
+        device.core.close();
+    }
+
+    clear_boot_marker();
+}
+
+/// Shuts down or restarts the daemon on a separate thread, so current request can still be responded to
+pub fn schedule_shutdown(core_manager: Arc<CoreManager>, autosave: Arc<ConfigAutosave>, restart: bool) {
+    spawn(move || {
+        log::info!("{} daemon by client request", if restart { "Restarting" } else { "Shutting down" });
+
+        prepare_shutdown(&core_manager, &autosave, restart);
+        sleep(SHUTDOWN_DELAY);
+
+        #[cfg(target_family = "unix")]
+        std::fs::remove_file(crate::unix_socket_path(&core_manager.config)).ok();
+
+        if restart {
+            restart_process();
+        }
+
+        exit(0);
+    });
+}
+
+/// Replaces current process with a new instance of the daemon using same arguments, returns only if that failed
+#[cfg(target_family = "unix")]
+fn restart_process() {
+    use std::os::unix::process::CommandExt;
+
+    match std::env::current_exe() {
+        Ok(exe) => {
+            let err = Command::new(exe).args(std::env::args().skip(1)).exec();
+            log::error!("Failed to restart daemon: {}", err);
+        }
+
+        Err(err) => log::error!("Failed to find daemon executable: {}", err),
+    }
+}
+
+/// Starts a new instance of the daemon using same arguments
+#[cfg(target_family = "windows")]
+fn restart_process() {
+    match std::env::current_exe() {
+        Ok(exe) => {
+            if let Err(err) = Command::new(exe).args(std::env::args().skip(1)).spawn() {
+                log::error!("Failed to restart daemon: {}", err);
+            }
+        }
+
+        Err(err) => log::error!("Failed to find daemon executable: {}", err),
+    }
+}
@@ -0,0 +1,91 @@
+//! Chunked transfers of files that are too big to be comfortably sent in a single packet
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Maximum amount of bytes daemon sends in a single chunk
+pub const TRANSFER_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Maximum size of a file that can be uploaded
+pub const MAX_UPLOAD_SIZE: usize = 64 * 1024 * 1024;
+
+/// Time without new chunks after which unfinished uploads are dropped
+pub const UPLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Upload that is still receiving chunks
+struct Upload {
+    serial: String,
+    data: Vec<u8>,
+    last_chunk: Instant,
+}
+
+/// Reasons for upload chunk not being accepted
+#[derive(Debug)]
+pub enum TransferError {
+    /// Upload doesn't exist, was already finished or has timed out
+    NotFound,
+    /// Upload would exceed [MAX_UPLOAD_SIZE], upload is dropped
+    TooLarge,
+}
+
+/// Keeps uploads that are in progress
+pub struct TransferManager {
+    uploads: Mutex<HashMap<String, Upload>>,
+    counter: AtomicU64,
+}
+
+impl TransferManager {
+    /// Creates transfer manager without any uploads
+    pub fn new() -> Arc<TransferManager> {
+        Arc::new(TransferManager {
+            uploads: Default::default(),
+            counter: AtomicU64::new(0)
+        })
+    }
+
+    /// Starts new upload for device, returns ID of the upload
+    pub fn start(&self, serial: &str) -> String {
+        let mut uploads = self.uploads.lock().unwrap();
+        uploads.retain(|_, upload| upload.last_chunk.elapsed() < UPLOAD_TIMEOUT);
+
+        let id = format!("upload-{}", self.counter.fetch_add(1, Ordering::SeqCst));
+
+        uploads.insert(id.clone(), Upload {
+            serial: serial.to_string(),
+            data: vec![],
+            last_chunk: Instant::now()
+        });
+
+        id
+    }
+
+    /// Appends chunk to upload, returns amount of bytes received so far
+    pub fn append(&self, id: &str, chunk: &[u8]) -> Result<usize, TransferError> {
+        let mut uploads = self.uploads.lock().unwrap();
+        let upload = uploads.get_mut(id).ok_or(TransferError::NotFound)?;
+
+        if upload.data.len() + chunk.len() > MAX_UPLOAD_SIZE {
+            uploads.remove(id);
+            return Err(TransferError::TooLarge);
+        }
+
+        upload.data.extend_from_slice(chunk);
+        upload.last_chunk = Instant::now();
+
+        Ok(upload.data.len())
+    }
+
+    /// Finishes upload, returns serial of the device upload was meant for and received data
+    pub fn finish(&self, id: &str) -> Result<(String, Vec<u8>), TransferError> {
+        let upload = self.uploads.lock().unwrap().remove(id).ok_or(TransferError::NotFound)?;
+        Ok((upload.serial, upload.data))
+    }
+}
+
+/// Cuts chunk of data that starts at offset, returns empty slice if offset is past the end
+pub fn get_chunk(data: &[u8], offset: usize) -> &[u8] {
+    let start = offset.min(data.len());
+    &data[start..(start + TRANSFER_CHUNK_SIZE).min(data.len())]
+}
@@ -1,23 +1,56 @@
-use std::io::{BufRead, BufReader};
+use std::io::BufReader;
+use std::net::Shutdown;
+use std::os::unix::fs::{chown, PermissionsExt};
 use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
 use std::{fs, thread};
 use std::sync::Arc;
-use streamduck_core::socket::{send_packet_as_is, SocketManager};
-use streamduck_daemon::UNIX_SOCKET_PATH;
+use streamduck_core::config::Config;
+use streamduck_core::socket::{PacketError, read_packet, redact_packet, send_packet_as_is, SocketManager};
+use streamduck_daemon::daemon_data::auth::{process_auth_request, SocketTokens};
+use streamduck_daemon::daemon_data::clients::process_client_request;
+use streamduck_daemon::daemon_data::logs::process_log_request;
+use streamduck_daemon::daemon_data::sessions::process_session_request;
+use streamduck_daemon::unix_socket_path;
 
-pub fn remove_socket() {
-    fs::remove_file(UNIX_SOCKET_PATH).ok();
+pub fn remove_socket(config: &Config) {
+    fs::remove_file(unix_socket_path(config)).ok();
 }
 
-pub fn open_socket(socket_manager: Arc<SocketManager>) {
-    remove_socket();
-    let listener = UnixListener::bind(UNIX_SOCKET_PATH).unwrap();
+/// Applies ownership and permission bits from config to the socket
+fn restrict_socket(path: &Path, config: &Config) {
+    let (owner, group) = config.socket_owner();
+
+    if owner.is_some() || group.is_some() {
+        if let Err(err) = chown(path, owner, group) {
+            log::error!("Failed to change owner of unix socket: {}", err);
+        }
+    }
+
+    if let Some(mode) = config.socket_permissions() {
+        if let Err(err) = fs::set_permissions(path, fs::Permissions::from_mode(mode)) {
+            log::error!("Failed to change permissions of unix socket: {}", err);
+        }
+    }
+}
+
+pub fn open_socket(socket_manager: Arc<SocketManager>, config: Arc<Config>) {
+    remove_socket(&config);
+
+    let path = unix_socket_path(&config);
+    let listener = UnixListener::bind(&path).unwrap();
+    restrict_socket(&path, &config);
+
+    log::info!("Listening on unix socket {}", path.display());
+
+    let tokens = SocketTokens::from_config(&config);
 
     for stream in listener.incoming() {
         match stream {
             Ok(stream) => {
                 let manager = socket_manager.clone();
-                thread::spawn(move || handle_client(stream, manager));
+                let tokens = tokens.clone();
+                thread::spawn(move || handle_client(stream, manager, tokens));
             }
             Err(err) => {
                 log::error!("Unix socket error: {}", err);
@@ -27,45 +60,77 @@ pub fn open_socket(socket_manager: Arc<SocketManager>) {
     }
 }
 
-fn handle_client(stream: UnixStream, socket_manager: Arc<SocketManager>) {
+fn handle_client(stream: UnixStream, socket_manager: Arc<SocketManager>, tokens: SocketTokens) {
     log::info!("Unix Socket client connected");
 
-    if let Ok(write_stream) = stream.try_clone() {
+    if let (Ok(write_stream), Ok(close_stream)) = (stream.try_clone(), stream.try_clone()) {
         let pool = socket_manager.get_pool();
+        let client = socket_manager.register_client("unix", Some(pool.clone()), Some(Box::new(move || {
+            close_stream.shutdown(Shutdown::Both).ok();
+        })));
 
         // Write thread
-        thread::spawn(move || {
-            let mut stream = write_stream;
-            let pool = pool;
+        {
+            let pool = pool.clone();
+            let socket_manager = socket_manager.clone();
+            let client = client.clone();
 
-            loop {
-                let message = pool.take_message();
-                if send_packet_as_is(&mut stream, message).is_err() {
-                    break;
-                }
-            }
+            thread::spawn(move || {
+                let mut stream = write_stream;
 
-            pool.close();
-        });
+                while let Some(message) = pool.take_message_for_connection() {
+                    // Events aren't sent to clients that haven't authenticated yet
+                    if !client.is_authenticated() {
+                        continue;
+                    }
+
+                    let outgoing = if client.is_read_only() { redact_packet(message.clone()) } else { message.clone() };
+
+                    if send_packet_as_is(&mut stream, outgoing).is_err() {
+                        // Keeping message for the session in case client resumes it
+                        pool.return_message(message);
+                        stream.shutdown(Shutdown::Both).ok();
+                        socket_manager.release_pool(&pool);
+                        break;
+                    }
+                }
+            });
+        }
 
         // Read thread
         let mut stream = BufReader::new(stream);
 
-        let mut message = vec![];
-        while let Ok(size) = stream.read_until(0x4, &mut message) {
-            if size <= 0 {
-                break;
-            }
+        loop {
+            match read_packet(&mut stream) {
+                Ok(packet) => {
+                    client.count_request();
+
+                    if !process_auth_request(&tokens, &client, stream.get_mut(), &packet)
+                        && !process_client_request(&socket_manager, &client, stream.get_mut(), &packet)
+                        && !process_session_request(&socket_manager, &client, Some(&pool), stream.get_mut(), &packet)
+                        && !process_log_request(Some(&pool), stream.get_mut(), &packet) {
+                        socket_manager.received_message_from(&client, stream.get_mut(), packet)
+                    }
 
-            if let Ok(message) = String::from_utf8(message.clone()) {
-                match serde_json::from_str(&message.replace("\u{0004}", "")) {
-                    Ok(packet) => socket_manager.received_message(stream.get_mut(), packet),
-                    Err(e) => log::warn!("Invalid message in sockets: {}", e)
+                    if client.is_disconnected() {
+                        break;
+                    }
                 }
+                Err(PacketError::Closed) => break,
+                Err(PacketError::Truncated) => {
+                    log::warn!("Unix Socket client disconnected in the middle of a message");
+                    break;
+                }
+                Err(PacketError::ReadError(e)) => {
+                    log::warn!("Failed to read from Unix Socket: {}", e);
+                    break;
+                }
+                Err(e) => log::warn!("Invalid message in sockets: {:?}", e)
             }
-
-            message.clear();
         }
+
+        socket_manager.unregister_client(&client);
+        socket_manager.release_pool(&pool);
     }
 
     log::info!("Unix Socket client disconnected");
@@ -0,0 +1,106 @@
+//! Hot reload of device configs that were changed outside of the daemon
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::Duration;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use streamduck_core::config::{Config, DeviceConfigFormat};
+use streamduck_core::core::manager::CoreManager;
+use streamduck_core::core::methods::{CoreHandle, reset_stack};
+use streamduck_core::modules::events::SDGlobalEvent;
+use streamduck_core::socket::{send_event_to_socket, SocketManager};
+use streamduck_core::util::make_panel_unique;
+
+/// Time to wait for more changes before reloading, editors tend to write files in several steps
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Watches device config folder and reloads configs that were changed externally, blocks forever
+pub fn watch_device_configs(config: Arc<Config>, core_manager: Arc<CoreManager>, socket_manager: Arc<SocketManager>) {
+    let (tx, rx) = channel();
+
+    let mut watcher: RecommendedWatcher = match notify::recommended_watcher(move |event: notify::Result<Event>| {
+        if let Ok(event) = event {
+            if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                for path in event.paths {
+                    tx.send(path).ok();
+                }
+            }
+        }
+    }) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            log::error!("Failed to create device config watcher: {}", err);
+            return;
+        }
+    };
+
+    let path = config.device_config_path();
+    std::fs::create_dir_all(&path).ok();
+
+    if let Err(err) = watcher.watch(&path, RecursiveMode::NonRecursive) {
+        log::error!("Failed to watch device config folder: {}", err);
+        return;
+    }
+
+    log::info!("Watching {:?} for device config changes", path);
+
+    let mut changed: HashSet<PathBuf> = HashSet::new();
+
+    loop {
+        match rx.recv_timeout(WATCH_DEBOUNCE) {
+            Ok(path) => {
+                changed.insert(path);
+            }
+
+            Err(RecvTimeoutError::Timeout) => {
+                for path in changed.drain() {
+                    reload_if_changed(&config, &core_manager, &socket_manager, path);
+                }
+            }
+
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+/// Gets serial number of device config file that was changed outside of the daemon, daemon's own saves are ignored
+pub fn externally_changed_config(config: &Config, path: &Path) -> Option<String> {
+    path.extension().and_then(|x| x.to_str()).and_then(DeviceConfigFormat::from_extension)?;
+
+    let serial = path.file_stem().and_then(|x| x.to_str())?.to_string();
+    let content = std::fs::read_to_string(path).ok()?;
+
+    if config.is_own_save(&serial, &content) {
+        None
+    } else {
+        Some(serial)
+    }
+}
+
+/// Reloads device config if file was changed outside of the daemon
+fn reload_if_changed(config: &Arc<Config>, core_manager: &Arc<CoreManager>, socket_manager: &Arc<SocketManager>, path: PathBuf) {
+    let serial = if let Some(serial) = externally_changed_config(config, &path) { serial } else { return; };
+
+    match config.reload_device_config(&serial) {
+        Ok(_) => {
+            log::info!("Device config for {} was changed externally, reloaded", serial);
+
+            if let Some(device) = core_manager.get_device(&serial) {
+                if !device.core.is_closed() {
+                    if let Some(dvc_cfg) = config.get_device_config(&serial) {
+                        let layout = dvc_cfg.read().unwrap().layout.clone();
+                        reset_stack(&CoreHandle::wrap(device.core), make_panel_unique(layout));
+                    }
+                }
+            }
+
+            send_event_to_socket(socket_manager, SDGlobalEvent::DeviceConfigReloaded {
+                serial_number: serial
+            });
+        }
+
+        Err(err) => log::warn!("Failed to reload externally changed device config for {}: {:?}", serial, err),
+    }
+}
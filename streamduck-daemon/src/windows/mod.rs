@@ -1,22 +1,28 @@
-use std::io::{BufRead, BufReader};
+use std::io::BufReader;
 use std::sync::Arc;
 use std::thread::spawn;
 use named_pipe::{PipeOptions, PipeServer};
-use streamduck_core::socket::{send_packet_as_is, SocketManager};
+use streamduck_core::socket::{PacketError, read_packet, redact_packet, send_packet_as_is, SocketManager};
+use streamduck_daemon::daemon_data::auth::{process_auth_request, SocketTokens};
+use streamduck_daemon::daemon_data::clients::process_client_request;
+use streamduck_daemon::daemon_data::logs::process_log_request;
+use streamduck_daemon::daemon_data::sessions::process_session_request;
 use streamduck_daemon::{WINDOWS_EVENT_PIPE_NAME, WINDOWS_REQUEST_PIPE_NAME};
 
-pub fn open_socket(socket_manager: Arc<SocketManager>) {
+/// Named pipes are created with default security of the daemon's user, use socket token to restrict access on multi-user machines
+pub fn open_socket(socket_manager: Arc<SocketManager>, tokens: SocketTokens) {
     // Events socket
     {
         let socket = socket_manager.clone();
-        spawn(move || open_event_socket(socket));
+        let tokens = tokens.clone();
+        spawn(move || open_event_socket(socket, tokens));
     }
 
     // Requests socket
-    open_request_socket(socket_manager);
+    open_request_socket(socket_manager, tokens);
 }
 
-fn open_request_socket(socket_manager: Arc<SocketManager>) {
+fn open_request_socket(socket_manager: Arc<SocketManager>, tokens: SocketTokens) {
     loop {
         let instance = PipeOptions::new(WINDOWS_REQUEST_PIPE_NAME)
             .first(false)
@@ -24,12 +30,13 @@ fn open_request_socket(socket_manager: Arc<SocketManager>) {
 
         if let Ok(client) = instance.wait() {
             let manager = socket_manager.clone();
-            spawn(move || handle_request_client(client, manager));
+            let tokens = tokens.clone();
+            spawn(move || handle_request_client(client, manager, tokens));
         }
     }
 }
 
-fn open_event_socket(socket_manager: Arc<SocketManager>) {
+fn open_event_socket(socket_manager: Arc<SocketManager>, tokens: SocketTokens) {
     loop {
         let instance = PipeOptions::new(WINDOWS_EVENT_PIPE_NAME)
             .first(false)
@@ -37,48 +44,87 @@ fn open_event_socket(socket_manager: Arc<SocketManager>) {
 
         if let Ok(client) = instance.wait() {
             let manager = socket_manager.clone();
-            spawn(move || handle_event_client(client, manager));
+            let tokens = tokens.clone();
+            spawn(move || handle_event_client(client, manager, tokens));
         }
     }
 }
 
-fn handle_request_client(client: PipeServer, manager: Arc<SocketManager>) {
+fn handle_request_client(client: PipeServer, manager: Arc<SocketManager>, tokens: SocketTokens) {
     log::info!("Windows pipe request client connected");
 
+    // Pipe can't be closed from another thread, disconnected client is dropped after its next request
+    let registered = manager.register_client("windows-requests", None, None);
     let mut stream = BufReader::new(client);
 
-    let mut message = vec![];
-    while let Ok(size) = stream.read_until(0x4, &mut message) {
-        if size <= 0 {
-            break;
-        }
-
-        if let Ok(message) = String::from_utf8(message.clone()) {
-            log::info!("message {}", message.replace("\u{0004}", ""));
-            match serde_json::from_str(&message.replace("\u{0004}", "")) {
-                Ok(packet) => manager.received_message(stream.get_mut(), packet),
-                Err(e) => log::warn!("Invalid message in sockets: {}", e)
+    loop {
+        match read_packet(&mut stream) {
+            Ok(packet) => {
+                if registered.is_disconnected() {
+                    break;
+                }
+
+                registered.count_request();
+
+                // Request pipe doesn't receive events, so sessions can't be used with it
+                if !process_auth_request(&tokens, &registered, stream.get_mut(), &packet)
+                    && !process_client_request(&manager, &registered, stream.get_mut(), &packet)
+                    && !process_session_request(&manager, &registered, None, stream.get_mut(), &packet)
+                    && !process_log_request(None, stream.get_mut(), &packet) {
+                    manager.received_message_from(&registered, stream.get_mut(), packet)
+                }
+            }
+            Err(PacketError::Closed) => break,
+            Err(PacketError::Truncated) => {
+                log::warn!("Windows pipe request client disconnected in the middle of a message");
+                break;
             }
+            Err(PacketError::ReadError(e)) => {
+                log::warn!("Failed to read from Windows pipe: {}", e);
+                break;
+            }
+            Err(e) => log::warn!("Invalid message in sockets: {:?}", e)
         }
-
-        message.clear();
     }
 
+    manager.unregister_client(&registered);
+
     log::info!("Windows pipe request client disconnected");
 }
 
-fn handle_event_client(mut client: PipeServer, manager: Arc<SocketManager>) {
+fn handle_event_client(client: PipeServer, manager: Arc<SocketManager>, tokens: SocketTokens) {
     log::info!("Windows pipe event client connected");
 
     let pool = manager.get_pool();
+    let registered = manager.register_client("windows-events", Some(pool.clone()), None);
+    let mut stream = BufReader::new(client);
 
-    loop {
-        let message = pool.take_message();
-        if send_packet_as_is(&mut client, message).is_err() {
-            break;
+    // Events are only sent after client authenticates on the event pipe
+    while !registered.is_authenticated() {
+        match read_packet(&mut stream) {
+            Ok(packet) => {
+                process_auth_request(&tokens, &registered, stream.get_mut(), &packet);
+            }
+            Err(PacketError::Closed) | Err(PacketError::Truncated) | Err(PacketError::ReadError(_)) => break,
+            Err(e) => log::warn!("Invalid message in sockets: {:?}", e)
+        }
+    }
+
+    // Pool gets closed if client is disconnected
+    while registered.is_authenticated() {
+        match pool.take_message_for_connection() {
+            Some(message) => {
+                let message = if registered.is_read_only() { redact_packet(message) } else { message };
+
+                if send_packet_as_is(stream.get_mut(), message).is_err() {
+                    break;
+                }
+            }
+            None => break,
         }
     }
 
+    manager.unregister_client(&registered);
     pool.close();
 
     log::info!("Windows pipe event client disconnected");
@@ -7,18 +7,18 @@ use streamduck_core::versions::{CORE_EVENTS, PLUGIN_API, RENDERING, SDMODULE_TRA
 use serde::{Deserialize, Serialize};
 use serde_json::{Number, Value};
 use streamduck_core::core::methods::CoreHandle;
-use streamduck_core::modules::components::{ComponentDefinition, map_ui_values, UIFieldType, UIFieldValue, UIScalar, UIValue};
+use streamduck_core::modules::components::{ComponentDefinition, FieldError, map_ui_values, UIFieldType, UIFieldValue, UIScalar, UIValue};
 use streamduck_core::modules::events::SDCoreEvent;
 use streamduck_core::core::UniqueButton;
 use streamduck_core::image::{DynamicImage, Rgba};
 use streamduck_core::images::convert_image;
 use streamduck_core_derive::component;
 use streamduck_core_derive::plugin_config;
-use streamduck_core::socket::{SocketHandle, SocketListener, SocketManager, SocketPacket};
+use streamduck_core::socket::{check_packet_for_data, send_packet, SocketData, SocketHandle, SocketListener, SocketManager, SocketPacket};
 use streamduck_core::streamdeck::{DeviceImage, Kind};
 use streamduck_core::thread::rendering::{ButtonBackground, RendererComponent, RendererComponentBuilder};
-use streamduck_core::thread::rendering::custom::{CustomRenderer, DeviceReference, RenderingManager};
-use streamduck_core::thread::util::{image_from_horiz_gradient, image_from_solid, render_box_on_image};
+use streamduck_core::thread::rendering::custom::{CustomRenderer, DeviceReference, LayerPosition, RenderingManager, RenderLayer};
+use streamduck_core::thread::util::{image_from_horiz_gradient, image_from_solid, render_box_on_image, render_circle_on_image};
 use streamduck_core::util::rusttype::{Point, Scale};
 use streamduck_core::util::straight_copy;
 
@@ -46,7 +46,25 @@ pub fn get_module() -> SDModulePointer {
 #[no_mangle]
 pub fn register(socket_manager: Arc<SocketManager>, render_manager: Arc<RenderingManager>, _module_manager: Arc<ModuleManager>) {
     socket_manager.add_listener(Box::new(ExampleListener));
+    socket_manager.add_plugin_request::<ExamplePing>("example", |handle, packet| {
+        if check_packet_for_data::<ExamplePing>(packet) {
+            send_packet(handle, packet, &ExamplePing {
+                message: "pong".to_string()
+            }).ok();
+        }
+    });
     render_manager.add_custom_renderer(Arc::new(Box::new(ExampleRenderer::new())));
+    render_manager.add_render_layer(Arc::new(Box::new(ExampleLayer)));
+}
+
+/// Request that example plugin exposes to clients
+#[derive(Serialize, Deserialize)]
+pub struct ExamplePing {
+    pub message: String
+}
+
+impl SocketData for ExamplePing {
+    const NAME: &'static str = "example/ping";
 }
 
 pub struct ExampleListener;
@@ -75,7 +93,9 @@ impl SDModule for ExampleModule {
             description: "Example component".to_string(),
             default_looks: RendererComponentBuilder::new()
                 .background(ButtonBackground::Solid((255, 0, 255, 255)))
-                .build()
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
         });
 
         map
@@ -166,6 +186,15 @@ impl SDModule for ExampleModule {
                 ty: UIFieldType::InputFieldUnsignedInteger,
                 value: UIFieldValue::InputFieldUnsignedInteger(232)
             },
+            UIValue {
+                name: "multiline".to_string(),
+                display_name: "Multiline String".to_string(),
+                description: "".to_string(),
+                ty: UIFieldType::InputFieldMultiline {
+                    language_hint: Some("bash".to_string())
+                },
+                value: UIFieldValue::InputFieldMultiline("echo first\necho second".to_string())
+            },
             UIValue {
                 name: "float_slider".to_string(),
                 display_name: "Float Slider".to_string(),
@@ -195,8 +224,9 @@ impl SDModule for ExampleModule {
         ]
     }
 
-    fn set_component_value(&self, _: CoreHandle, _: &mut Button, _: &str, values: Vec<UIValue>) {
+    fn set_component_value(&self, _: CoreHandle, _: &mut Button, _: &str, values: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
         println!("{:?}", values);
+        Ok(())
     }
 
     fn listening_for(&self) -> Vec<String> {
@@ -244,6 +274,26 @@ pub struct ExampleSettings {
     test: i64
 }
 
+pub struct ExampleLayer;
+
+impl RenderLayer for ExampleLayer {
+    fn name(&self) -> String {
+        "example".to_string()
+    }
+
+    fn components(&self) -> Vec<String> {
+        vec!["example".to_string()]
+    }
+
+    fn position(&self) -> LayerPosition {
+        LayerPosition::AboveText
+    }
+
+    fn draw(&self, _: &UniqueButton, _: &CoreHandle, frame: &mut DynamicImage) {
+        render_circle_on_image(frame, (60.0, 12.0), 6.0, (0, 255, 0, 255));
+    }
+}
+
 pub struct ExampleRenderer {
     tex: DeviceImage,
     already_rendered: Mutex<HashSet<u8>>,
@@ -0,0 +1,457 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use streamduck_core::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
+use streamduck_core::core::methods::{CoreHandle, get_current_screen, pop_screen, push_screen, send_module_event};
+use streamduck_core::core::{ButtonPanel, UniqueButton};
+use streamduck_core::font::get_font_from_collection;
+use streamduck_core::image::{DynamicImage, GenericImageView};
+use streamduck_core::modules::components::{ComponentDefinition, FieldError, map_ui_values, UIFieldType, UIFieldValue, UIValue};
+use streamduck_core::modules::events::SDCoreEvent;
+use streamduck_core::modules::folders::{folder_panel, list_config_folders};
+use streamduck_core::modules::scheduler::{cancel_task, schedule_task, TaskId};
+use streamduck_core::modules::{ModuleManager, PluginMetadata, SDModule, SDModulePointer};
+use streamduck_core::socket::SocketManager;
+use streamduck_core::thread::rendering::{ButtonBackground, RendererComponentBuilder};
+use streamduck_core::thread::rendering::custom::RenderingManager;
+use streamduck_core::thread::util::{render_aligned_text_on_image, render_progress_ring_on_image, TextAlignment};
+use streamduck_core::util::rusttype::Scale;
+use streamduck_core::util::straight_copy;
+use streamduck_core::versions::{CORE, CORE_EVENTS, CORE_METHODS, PLUGIN_API, RENDERING, SCHEDULER, SDMODULE_TRAIT};
+use streamduck_core_derive::component;
+
+/// Choice of break folder that stands for not opening any folder
+const NO_FOLDER: &'static str = "None";
+
+#[no_mangle]
+pub fn get_metadata() -> PluginMetadata {
+    PluginMetadata::from_literals(
+        "pomodoro",
+        "TheJebForge",
+        "Pomodoro timer that cycles between work and breaks, with optional break folder",
+        "0.1",
+        &[
+            PLUGIN_API,
+            SDMODULE_TRAIT,
+            CORE,
+            CORE_METHODS,
+            CORE_EVENTS,
+            RENDERING,
+            SCHEDULER
+        ]
+    )
+}
+
+#[no_mangle]
+pub fn get_module() -> SDModulePointer {
+    Box::into_raw(Box::new(PomodoroModule::default()))
+}
+
+#[no_mangle]
+pub fn register(_: Arc<SocketManager>, _: Arc<RenderingManager>, _: Arc<ModuleManager>) {}
+
+/// Phase of pomodoro cycle
+#[derive(Debug, Clone, Copy, Hash, PartialEq)]
+pub enum Phase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl Phase {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Phase::Work => "work",
+            Phase::ShortBreak => "short_break",
+            Phase::LongBreak => "long_break",
+        }
+    }
+
+    fn color(&self) -> (u8, u8, u8, u8) {
+        match self {
+            Phase::Work => (220, 70, 60, 255),
+            Phase::ShortBreak | Phase::LongBreak => (70, 190, 90, 255),
+        }
+    }
+}
+
+/// Running timer of a device
+struct Session {
+    settings: PomodoroComponent,
+    phase: Phase,
+    /// Work phases finished so far
+    completed: u32,
+    started: Instant,
+    length: Duration,
+    task: TaskId,
+    /// Folder opened for the break, closed once break ends if it's still on top
+    break_panel: Option<ButtonPanel>,
+}
+
+impl Session {
+    fn remaining(&self) -> Duration {
+        self.length.saturating_sub(self.started.elapsed())
+    }
+}
+
+/// Timers by device serial number, there's one timer per device
+type Sessions = Arc<RwLock<HashMap<String, Session>>>;
+
+#[derive(Default)]
+pub struct PomodoroModule {
+    sessions: Sessions,
+}
+
+/// Closes break folder if it's still the current screen
+fn close_break_panel(core: &CoreHandle, panel: Option<ButtonPanel>) {
+    if let (Some(panel), Some(current)) = (panel, get_current_screen(core)) {
+        if Arc::ptr_eq(&panel, &current) {
+            pop_screen(core);
+        }
+    }
+}
+
+/// Starts a phase, replacing any phase that device had running
+fn start_phase(core: &CoreHandle, sessions: &Sessions, settings: PomodoroComponent, phase: Phase, completed: u32) {
+    let serial = core.core().serial_number.clone();
+
+    let minutes = match phase {
+        Phase::Work => settings.work_minutes,
+        Phase::ShortBreak => settings.break_minutes,
+        Phase::LongBreak => settings.long_break_minutes,
+    };
+    let length = Duration::from_secs(minutes.max(1) as u64 * 60);
+
+    let task_sessions = sessions.clone();
+    let task = schedule_task(core, length, move |core| finish_phase(&core, &task_sessions));
+
+    let previous_panel = sessions.write().unwrap().remove(&serial).and_then(|x| x.break_panel);
+
+    let break_panel = if phase != Phase::Work && !settings.break_folder.is_empty() {
+        let panel = folder_panel(core, &settings.break_folder);
+
+        if let Some(panel) = &panel {
+            push_screen(core, panel.clone());
+        } else {
+            log::warn!("Break folder {} doesn't exist", settings.break_folder);
+        }
+
+        panel
+    } else {
+        close_break_panel(core, previous_panel);
+        None
+    };
+
+    sessions.write().unwrap().insert(serial, Session {
+        settings,
+        phase,
+        completed,
+        started: Instant::now(),
+        length,
+        task,
+        break_panel
+    });
+
+    send_module_event(core, "phase_changed", json!({
+        "phase": phase.name(),
+        "completed": completed,
+        "minutes": minutes
+    }));
+
+    core.core().mark_for_redraw();
+}
+
+/// Moves device's timer to the next phase
+fn finish_phase(core: &CoreHandle, sessions: &Sessions) {
+    let session = sessions.read().unwrap().get(&core.core().serial_number)
+        .map(|x| (x.settings.clone(), x.phase, x.completed));
+
+    if let Some((settings, phase, completed)) = session {
+        match phase {
+            Phase::Work => {
+                let completed = completed + 1;
+
+                let next = if settings.long_break_every > 0 && completed % settings.long_break_every == 0 {
+                    Phase::LongBreak
+                } else {
+                    Phase::ShortBreak
+                };
+
+                start_phase(core, sessions, settings, next, completed);
+            }
+
+            Phase::ShortBreak | Phase::LongBreak => start_phase(core, sessions, settings, Phase::Work, completed),
+        }
+    }
+}
+
+/// Stops device's timer
+fn stop(core: &CoreHandle, sessions: &Sessions) {
+    if let Some(session) = sessions.write().unwrap().remove(&core.core().serial_number) {
+        cancel_task(core, session.task);
+        close_break_panel(core, session.break_panel);
+
+        send_module_event(core, "stopped", json!({
+            "phase": session.phase.name(),
+            "completed": session.completed
+        }));
+
+        core.core().mark_for_redraw();
+    }
+}
+
+impl PomodoroModule {
+    /// Current phase, progress of the phase and remaining time of device's timer
+    fn state(&self, core: &CoreHandle) -> Option<(Phase, f32, Duration)> {
+        let lock = self.sessions.read().unwrap();
+        let session = lock.get(&core.core().serial_number)?;
+
+        let remaining = session.remaining();
+        Some((session.phase, remaining.as_secs_f32() / session.length.as_secs_f32(), remaining))
+    }
+}
+
+fn format_time(time: Duration) -> String {
+    let seconds = (time.as_secs_f32().ceil()) as u64;
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+impl SDModule for PomodoroModule {
+    fn name(&self) -> String {
+        "pomodoro".to_string()
+    }
+
+    fn components(&self) -> HashMap<String, ComponentDefinition> {
+        let mut map = HashMap::new();
+
+        map.insert(PomodoroComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Pomodoro".to_string(),
+            description: "Starts work and break cycle when pressed, shows remaining time as a ring. Press again to stop".to_string(),
+            default_looks: RendererComponentBuilder::new()
+                .background(ButtonBackground::Solid((30, 30, 30, 255)))
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map
+    }
+
+    fn add_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            PomodoroComponent::NAME => {
+                button.insert_component(PomodoroComponent::default()).ok();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn remove_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            PomodoroComponent::NAME => {
+                button.remove_component::<PomodoroComponent>();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn paste_component(&self, _: CoreHandle, reference_button: &Button, new_button: &mut Button) {
+        straight_copy(reference_button, new_button, PomodoroComponent::NAME);
+    }
+
+    fn component_values(&self, core: CoreHandle, button: &Button, name: &str) -> Vec<UIValue> {
+        match name {
+            PomodoroComponent::NAME => {
+                if let Ok(component) = parse_button_to_component::<PomodoroComponent>(button) {
+                    let folders = list_config_folders(&core.core().device_config.read().unwrap());
+
+                    let mut choices = vec![NO_FOLDER.to_string()];
+                    choices.extend(folders.iter().map(|x| format!("{} ({})", x.name, x.id)));
+
+                    let choice = folders.iter()
+                        .find(|x| x.id == component.break_folder)
+                        .map_or(NO_FOLDER.to_string(), |x| format!("{} ({})", x.name, x.id));
+
+                    vec![
+                        minutes_field("work_minutes", "Work", "Length of work phase in minutes", component.work_minutes),
+                        minutes_field("break_minutes", "Short Break", "Length of short break in minutes", component.break_minutes),
+                        minutes_field("long_break_minutes", "Long Break", "Length of long break in minutes", component.long_break_minutes),
+                        UIValue {
+                            name: "long_break_every".to_string(),
+                            display_name: "Long Break Every".to_string(),
+                            description: "Amount of work phases before a long break, 0 for no long breaks".to_string(),
+                            ty: UIFieldType::InputFieldUnsignedInteger,
+                            value: UIFieldValue::InputFieldUnsignedInteger(component.long_break_every)
+                        },
+                        UIValue {
+                            name: "break_folder".to_string(),
+                            display_name: "Break Folder".to_string(),
+                            description: "Folder to open during breaks, closed when break ends".to_string(),
+                            ty: UIFieldType::Choice(choices),
+                            value: UIFieldValue::Choice(choice)
+                        },
+                    ]
+                } else {
+                    vec![]
+                }
+            }
+
+            _ => vec![],
+        }
+    }
+
+    fn set_component_value(&self, _: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+        match name {
+            PomodoroComponent::NAME => {
+                if let Ok(mut component) = parse_button_to_component::<PomodoroComponent>(button) {
+                    let change_map = map_ui_values(value);
+
+                    for (name, field) in [
+                        ("work_minutes", &mut component.work_minutes),
+                        ("break_minutes", &mut component.break_minutes),
+                        ("long_break_minutes", &mut component.long_break_minutes),
+                    ] {
+                        if let Some(value) = change_map.get(name) {
+                            if let Ok(minutes) = value.value.try_into_u32() {
+                                if minutes == 0 {
+                                    return Err(vec![FieldError::new(name, "Phase should be at least a minute long")]);
+                                }
+
+                                *field = minutes;
+                            }
+                        }
+                    }
+
+                    if let Some(value) = change_map.get("long_break_every") {
+                        if let Ok(count) = value.value.try_into_u32() {
+                            component.long_break_every = count;
+                        }
+                    }
+
+                    if let Some(value) = change_map.get("break_folder") {
+                        if let Ok(choice) = value.value.try_into_string() {
+                            component.break_folder = if choice == NO_FOLDER {
+                                "".to_string()
+                            } else {
+                                choice.rsplit_once('(')
+                                    .map(|(_, id)| id.trim_end_matches(')').to_string())
+                                    .unwrap_or(choice)
+                            };
+                        }
+                    }
+
+                    button.insert_component(component).ok();
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn listening_for(&self) -> Vec<String> {
+        vec![
+            PomodoroComponent::NAME.to_string()
+        ]
+    }
+
+    fn event(&self, core: CoreHandle, event: SDCoreEvent) {
+        match event {
+            SDCoreEvent::ButtonAction { pressed_button, .. } => {
+                if let Ok(component) = parse_unique_button_to_component::<PomodoroComponent>(&pressed_button) {
+                    if self.sessions.read().unwrap().contains_key(&core.core().serial_number) {
+                        stop(&core, &self.sessions);
+                    } else {
+                        start_phase(&core, &self.sessions, component, Phase::Work, 0);
+                    }
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn render(&self, core: CoreHandle, button: &UniqueButton, frame: &mut DynamicImage) {
+        if let Ok(component) = parse_unique_button_to_component::<PomodoroComponent>(button) {
+            let (width, height) = frame.dimensions();
+            let center = (width as f32 / 2.0, height as f32 / 2.0);
+            let radius = width.min(height) as f32 / 2.0 - 4.0;
+            let thickness = (width as f32 / 12.0).max(2.0);
+
+            let (color, progress, text) = match self.state(&core) {
+                Some((phase, progress, remaining)) => (phase.color(), progress, format_time(remaining)),
+                None => ((120, 120, 120, 255), 1.0, format_time(Duration::from_secs(component.work_minutes as u64 * 60))),
+            };
+
+            render_progress_ring_on_image(frame, center, radius, thickness, progress, color, (60, 60, 60, 255));
+
+            if let Some(font) = get_font_from_collection("default") {
+                render_aligned_text_on_image(core.core().image_size, frame, font.as_ref(), &text, Scale::uniform(18.0), TextAlignment::Center, 0, (0.0, 0.0), (255, 255, 255, 255));
+            }
+        }
+    }
+
+    fn render_hash(&self, core: CoreHandle, button: &UniqueButton, hash: &mut Box<dyn Hasher>) {
+        if let Ok(_) = parse_unique_button_to_component::<PomodoroComponent>(button) {
+            if let Some((phase, _, remaining)) = self.state(&core) {
+                phase.hash(hash);
+                remaining.as_secs().hash(hash);
+            }
+        }
+    }
+
+    fn metadata(&self) -> PluginMetadata {
+        get_metadata()
+    }
+}
+
+fn minutes_field(name: &str, display_name: &str, description: &str, value: u32) -> UIValue {
+    UIValue {
+        name: name.to_string(),
+        display_name: display_name.to_string(),
+        description: description.to_string(),
+        ty: UIFieldType::InputFieldUnsignedInteger,
+        value: UIFieldValue::InputFieldUnsignedInteger(value)
+    }
+}
+
+/// Component that runs pomodoro timer
+#[component("pomodoro")]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PomodoroComponent {
+    #[serde(default = "default_work")]
+    pub work_minutes: u32,
+    #[serde(default = "default_break")]
+    pub break_minutes: u32,
+    #[serde(default = "default_long_break")]
+    pub long_break_minutes: u32,
+    /// Amount of work phases before a long break, 0 disables long breaks
+    #[serde(default = "default_long_break_every")]
+    pub long_break_every: u32,
+    /// ID of folder that gets opened during breaks
+    #[serde(default)]
+    pub break_folder: String,
+}
+
+fn default_work() -> u32 { 25 }
+fn default_break() -> u32 { 5 }
+fn default_long_break() -> u32 { 15 }
+fn default_long_break_every() -> u32 { 4 }
+
+impl Default for PomodoroComponent {
+    fn default() -> Self {
+        Self {
+            work_minutes: default_work(),
+            break_minutes: default_break(),
+            long_break_minutes: default_long_break(),
+            long_break_every: default_long_break_every(),
+            break_folder: "".to_string()
+        }
+    }
+}
@@ -0,0 +1,249 @@
+//! Minimal clients for OctoPrint and Moonraker HTTP APIs
+
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Timeout for any request made to printer host
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Type of printer host software
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum PrinterKind {
+    OctoPrint,
+    Moonraker,
+}
+
+impl Default for PrinterKind {
+    fn default() -> Self {
+        PrinterKind::OctoPrint
+    }
+}
+
+impl PrinterKind {
+    pub const VARIANTS: [&'static str; 2] = ["OctoPrint", "Moonraker"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PrinterKind::OctoPrint => "OctoPrint",
+            PrinterKind::Moonraker => "Moonraker",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<PrinterKind> {
+        match name {
+            "OctoPrint" => Some(PrinterKind::OctoPrint),
+            "Moonraker" => Some(PrinterKind::Moonraker),
+            _ => None
+        }
+    }
+}
+
+/// Connection details of a printer host
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Hash, PartialEq, Eq)]
+pub struct PrinterConnection {
+    pub kind: PrinterKind,
+    /// Base URL of the host, eg. http://octopi.local
+    pub url: String,
+    /// API key, can be empty for Moonraker instances without authorization
+    pub api_key: String,
+}
+
+/// Command that can be sent to the printer
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum PrinterCommand {
+    Pause,
+    Resume,
+    Cancel,
+}
+
+impl Default for PrinterCommand {
+    fn default() -> Self {
+        PrinterCommand::Pause
+    }
+}
+
+impl PrinterCommand {
+    pub const VARIANTS: [&'static str; 3] = ["Pause", "Resume", "Cancel"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PrinterCommand::Pause => "Pause",
+            PrinterCommand::Resume => "Resume",
+            PrinterCommand::Cancel => "Cancel",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<PrinterCommand> {
+        match name {
+            "Pause" => Some(PrinterCommand::Pause),
+            "Resume" => Some(PrinterCommand::Resume),
+            "Cancel" => Some(PrinterCommand::Cancel),
+            _ => None
+        }
+    }
+}
+
+/// Temperature reading of a heater
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Temperature {
+    pub actual: f32,
+    pub target: f32,
+}
+
+/// Printer status snapshot
+#[derive(Debug, Clone, Default)]
+pub struct PrinterStatus {
+    /// State reported by host, eg. "Printing"
+    pub state: String,
+    /// Progress of current job, from 0 to 100
+    pub progress: f32,
+    pub nozzle: Temperature,
+    pub bed: Temperature,
+    /// Remaining time of current job in seconds, if known
+    pub remaining: Option<u64>,
+}
+
+/// Error that might occur while talking to printer host
+#[derive(Debug)]
+pub enum PrinterError {
+    RequestError(String),
+    MalformedResponse,
+}
+
+impl From<ureq::Error> for PrinterError {
+    fn from(err: ureq::Error) -> Self {
+        PrinterError::RequestError(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for PrinterError {
+    fn from(err: std::io::Error) -> Self {
+        PrinterError::RequestError(err.to_string())
+    }
+}
+
+impl PrinterConnection {
+    fn endpoint(&self, path: &str) -> String {
+        format!("{}{}", self.url.trim_end_matches('/'), path)
+    }
+
+    fn get(&self, path: &str) -> Result<Value, PrinterError> {
+        let mut request = ureq::get(&self.endpoint(path)).timeout(REQUEST_TIMEOUT);
+
+        if !self.api_key.is_empty() {
+            request = request.set("X-Api-Key", &self.api_key);
+        }
+
+        Ok(request.call()?.into_json()?)
+    }
+
+    fn post(&self, path: &str, body: Option<Value>) -> Result<(), PrinterError> {
+        let mut request = ureq::post(&self.endpoint(path)).timeout(REQUEST_TIMEOUT);
+
+        if !self.api_key.is_empty() {
+            request = request.set("X-Api-Key", &self.api_key);
+        }
+
+        if let Some(body) = body {
+            request.send_json(body)?;
+        } else {
+            request.call()?;
+        }
+
+        Ok(())
+    }
+
+    /// Retrieves current status of the printer
+    pub fn status(&self) -> Result<PrinterStatus, PrinterError> {
+        match self.kind {
+            PrinterKind::OctoPrint => {
+                let job = self.get("/api/job")?;
+                let printer = self.get("/api/printer")?;
+
+                Ok(PrinterStatus {
+                    state: job["state"].as_str().ok_or(PrinterError::MalformedResponse)?.to_string(),
+                    progress: job["progress"]["completion"].as_f64().unwrap_or(0.0) as f32,
+                    nozzle: read_temperature(&printer["temperature"]["tool0"], "actual", "target"),
+                    bed: read_temperature(&printer["temperature"]["bed"], "actual", "target"),
+                    remaining: job["progress"]["printTimeLeft"].as_u64()
+                })
+            }
+
+            PrinterKind::Moonraker => {
+                let response = self.get("/printer/objects/query?print_stats&virtual_sdcard&extruder&heater_bed")?;
+                let status = &response["result"]["status"];
+
+                let state = status["print_stats"]["state"].as_str().ok_or(PrinterError::MalformedResponse)?;
+                let progress = status["virtual_sdcard"]["progress"].as_f64().unwrap_or(0.0);
+                let duration = status["print_stats"]["print_duration"].as_f64().unwrap_or(0.0);
+
+                // Moonraker doesn't report remaining time, estimating it from progress
+                let remaining = if progress > 0.0 && duration > 0.0 {
+                    Some((duration / progress - duration).max(0.0) as u64)
+                } else {
+                    None
+                };
+
+                Ok(PrinterStatus {
+                    state: capitalize(state),
+                    progress: (progress * 100.0) as f32,
+                    nozzle: read_temperature(&status["extruder"], "temperature", "target"),
+                    bed: read_temperature(&status["heater_bed"], "temperature", "target"),
+                    remaining
+                })
+            }
+        }
+    }
+
+    /// Sends a job command to the printer
+    pub fn send_command(&self, command: PrinterCommand) -> Result<(), PrinterError> {
+        match self.kind {
+            PrinterKind::OctoPrint => {
+                let body = match command {
+                    PrinterCommand::Pause => json!({"command": "pause", "action": "pause"}),
+                    PrinterCommand::Resume => json!({"command": "pause", "action": "resume"}),
+                    PrinterCommand::Cancel => json!({"command": "cancel"}),
+                };
+
+                self.post("/api/job", Some(body))
+            }
+
+            PrinterKind::Moonraker => {
+                self.post(match command {
+                    PrinterCommand::Pause => "/printer/print/pause",
+                    PrinterCommand::Resume => "/printer/print/resume",
+                    PrinterCommand::Cancel => "/printer/print/cancel",
+                }, None)
+            }
+        }
+    }
+}
+
+fn read_temperature(value: &Value, actual: &str, target: &str) -> Temperature {
+    Temperature {
+        actual: value[actual].as_f64().unwrap_or(0.0) as f32,
+        target: value[target].as_f64().unwrap_or(0.0) as f32
+    }
+}
+
+fn capitalize(str: &str) -> String {
+    let mut chars = str.chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new()
+    }
+}
+
+/// Formats seconds into short human readable duration
+pub fn format_duration(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+
+    if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else {
+        format!("{}m {:02}s", minutes, seconds % 60)
+    }
+}
@@ -0,0 +1,427 @@
+mod api;
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{sleep, spawn};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use streamduck_core::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
+use streamduck_core::core::methods::CoreHandle;
+use streamduck_core::core::UniqueButton;
+use streamduck_core::font::get_font_from_collection;
+use streamduck_core::image::{DynamicImage, GenericImageView};
+use streamduck_core::modules::components::{ComponentDefinition, FieldError, map_ui_values, UIFieldType, UIFieldValue, UIValue};
+use streamduck_core::modules::events::SDCoreEvent;
+use streamduck_core::modules::{ModuleManager, PluginMetadata, SDModule, SDModulePointer};
+use streamduck_core::socket::SocketManager;
+use streamduck_core::thread::rendering::{ButtonBackground, ButtonText, RendererComponentBuilder};
+use streamduck_core::thread::rendering::custom::RenderingManager;
+use streamduck_core::thread::util::{render_aligned_text_on_image, render_progress_bar_on_image, TextAlignment};
+use streamduck_core::util::rusttype::Scale;
+use streamduck_core::util::straight_copy;
+use streamduck_core::versions::{CORE_EVENTS, PLUGIN_API, RENDERING, SDMODULE_TRAIT};
+use streamduck_core_derive::component;
+use crate::api::{format_duration, PrinterCommand, PrinterConnection, PrinterKind, PrinterStatus};
+
+/// How often printer hosts are polled for status
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[no_mangle]
+pub fn get_metadata() -> PluginMetadata {
+    PluginMetadata::from_literals(
+        "printer",
+        "TheJebForge",
+        "Controls and monitors 3D printers over OctoPrint and Moonraker",
+        "0.1",
+        &[
+            PLUGIN_API,
+            SDMODULE_TRAIT,
+            CORE_EVENTS,
+            RENDERING
+        ]
+    )
+}
+
+#[no_mangle]
+pub fn get_module() -> SDModulePointer {
+    Box::into_raw(Box::new(PrinterModule::new()))
+}
+
+#[no_mangle]
+pub fn register(_: Arc<SocketManager>, _: Arc<RenderingManager>, _: Arc<ModuleManager>) {}
+
+/// Printers that weren't displayed on any button for this many polls stop being polled
+const EVICT_AFTER_POLLS: u32 = 12;
+
+/// Last known status of a printer
+#[derive(Default)]
+struct WatchedPrinter {
+    status: Option<PrinterStatus>,
+    /// Polls since status was last requested by a button
+    unseen_polls: u32,
+}
+
+/// Status cache, keeps last known status of every printer that is displayed on any button
+type StatusMap = Arc<RwLock<HashMap<PrinterConnection, WatchedPrinter>>>;
+
+pub struct PrinterModule {
+    statuses: StatusMap,
+    /// Polling thread stops once this is false
+    running: Arc<AtomicBool>,
+}
+
+impl PrinterModule {
+    pub fn new() -> PrinterModule {
+        let statuses: StatusMap = Default::default();
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread_statuses = statuses.clone();
+        let thread_running = running.clone();
+        spawn(move || {
+            while thread_running.load(Ordering::Relaxed) {
+                let connections: Vec<PrinterConnection> = {
+                    let mut statuses = thread_statuses.write().unwrap();
+
+                    statuses.retain(|connection, printer| {
+                        printer.unseen_polls += 1;
+
+                        if printer.unseen_polls > EVICT_AFTER_POLLS {
+                            log::debug!("Printer {} is no longer displayed, stopped polling it", connection.url);
+                            false
+                        } else {
+                            true
+                        }
+                    });
+
+                    statuses.keys().cloned().collect()
+                };
+
+                for connection in connections {
+                    let status = match connection.status() {
+                        Ok(status) => Some(status),
+                        Err(err) => {
+                            log::debug!("Failed to retrieve status from {}: {:?}", connection.url, err);
+                            None
+                        }
+                    };
+
+                    if let Some(printer) = thread_statuses.write().unwrap().get_mut(&connection) {
+                        printer.status = status;
+                    }
+                }
+
+                sleep(POLL_INTERVAL);
+            }
+        });
+
+        PrinterModule {
+            statuses,
+            running
+        }
+    }
+
+    /// Gets last known status of the printer, starts watching the printer if it wasn't watched before
+    fn get_status(&self, connection: &PrinterConnection) -> Option<PrinterStatus> {
+        let mut statuses = self.statuses.write().unwrap();
+        let printer = statuses.entry(connection.clone()).or_default();
+
+        printer.unseen_polls = 0;
+        printer.status.clone()
+    }
+}
+
+impl Drop for PrinterModule {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+impl SDModule for PrinterModule {
+    fn name(&self) -> String {
+        "printer".to_string()
+    }
+
+    fn components(&self) -> HashMap<String, ComponentDefinition> {
+        let mut map = HashMap::new();
+
+        map.insert(PrinterStatusComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Printer Status".to_string(),
+            description: "Displays progress, temperatures and remaining time of current print".to_string(),
+            default_looks: RendererComponentBuilder::new()
+                .background(ButtonBackground::Solid((30, 30, 30, 255)))
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map.insert(PrinterControlComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Printer Control".to_string(),
+            description: "Pauses, resumes or cancels current print".to_string(),
+            default_looks: RendererComponentBuilder::new()
+                .background(ButtonBackground::Solid((50, 50, 50, 255)))
+                .add_text(ButtonText {
+                    text: "||".to_string(),
+                    font: "default".to_string(),
+                    scale: (30.0, 30.0),
+                    alignment: TextAlignment::Center,
+                    padding: 0,
+                    offset: (0.0, 0.0),
+                    color: (255, 255, 255, 255),
+                    shadow: None,
+                    auto_fit: false,
+                    word_wrap: false,
+                    marquee_speed: 0.0
+                })
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map
+    }
+
+    fn add_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            PrinterStatusComponent::NAME => {
+                button.insert_component(PrinterStatusComponent::default()).ok();
+            }
+
+            PrinterControlComponent::NAME => {
+                button.insert_component(PrinterControlComponent::default()).ok();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn remove_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            PrinterStatusComponent::NAME => {
+                button.remove_component::<PrinterStatusComponent>();
+            }
+
+            PrinterControlComponent::NAME => {
+                button.remove_component::<PrinterControlComponent>();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn paste_component(&self, _: CoreHandle, reference_button: &Button, new_button: &mut Button) {
+        straight_copy(reference_button, new_button, PrinterStatusComponent::NAME);
+        straight_copy(reference_button, new_button, PrinterControlComponent::NAME);
+    }
+
+    fn component_values(&self, _: CoreHandle, button: &Button, name: &str) -> Vec<UIValue> {
+        match name {
+            PrinterStatusComponent::NAME => {
+                if let Ok(component) = parse_button_to_component::<PrinterStatusComponent>(button) {
+                    connection_values(&component.connection)
+                } else {
+                    vec![]
+                }
+            }
+
+            PrinterControlComponent::NAME => {
+                if let Ok(component) = parse_button_to_component::<PrinterControlComponent>(button) {
+                    let mut fields = connection_values(&component.connection);
+
+                    fields.push(UIValue {
+                        name: "command".to_string(),
+                        display_name: "Command".to_string(),
+                        description: "Command to send to the printer when button is pressed".to_string(),
+                        ty: UIFieldType::Choice(PrinterCommand::VARIANTS.iter().map(|x| x.to_string()).collect()),
+                        value: UIFieldValue::Choice(component.command.name().to_string())
+                    });
+
+                    fields
+                } else {
+                    vec![]
+                }
+            }
+
+            _ => vec![],
+        }
+    }
+
+    fn set_component_value(&self, _: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+        match name {
+            PrinterStatusComponent::NAME => {
+                if let Ok(mut component) = parse_button_to_component::<PrinterStatusComponent>(button) {
+                    set_connection_values(&mut component.connection, &value)?;
+                    button.insert_component(component).ok();
+                }
+            }
+
+            PrinterControlComponent::NAME => {
+                if let Ok(mut component) = parse_button_to_component::<PrinterControlComponent>(button) {
+                    set_connection_values(&mut component.connection, &value)?;
+
+                    let change_map = map_ui_values(value);
+
+                    if let Some(value) = change_map.get("command") {
+                        if let Ok(command) = value.value.try_into_string() {
+                            if let Some(command) = PrinterCommand::from_name(&command) {
+                                component.command = command;
+                            }
+                        }
+                    }
+
+                    button.insert_component(component).ok();
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn listening_for(&self) -> Vec<String> {
+        vec![
+            PrinterStatusComponent::NAME.to_string(),
+            PrinterControlComponent::NAME.to_string()
+        ]
+    }
+
+    fn event(&self, _: CoreHandle, event: SDCoreEvent) {
+        match event {
+            SDCoreEvent::ButtonAction { pressed_button, .. } => {
+                if let Ok(component) = parse_unique_button_to_component::<PrinterControlComponent>(&pressed_button) {
+                    spawn(move || {
+                        if let Err(err) = component.connection.send_command(component.command) {
+                            log::warn!("Failed to send {} command to {}: {:?}", component.command.name(), component.connection.url, err);
+                        }
+                    });
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn render(&self, core: CoreHandle, button: &UniqueButton, frame: &mut DynamicImage) {
+        if let Ok(component) = parse_unique_button_to_component::<PrinterStatusComponent>(button) {
+            let size = core.core().image_size;
+            let font = if let Some(font) = get_font_from_collection("default") { font } else { return; };
+
+            if let Some(status) = self.get_status(&component.connection) {
+                // Progress overlay at the bottom of the button
+                let (width, height) = frame.dimensions();
+                let bar_height = (height / 8).max(1);
+
+                render_progress_bar_on_image(frame, (0, (height - bar_height) as i32), (width, bar_height), status.progress / 100.0, (80, 200, 80, 255), (60, 60, 60, 255));
+
+                render_aligned_text_on_image(size, frame, font.as_ref(), &format!("{:.0}%", status.progress), Scale::uniform(22.0), TextAlignment::TopCenter, 4, (0.0, 0.0), (255, 255, 255, 255));
+
+                let temps = format!("{:.0}°/{:.0}°", status.nozzle.actual, status.bed.actual);
+                render_aligned_text_on_image(size, frame, font.as_ref(), &temps, Scale::uniform(13.0), TextAlignment::Center, 0, (0.0, 2.0), (255, 180, 120, 255));
+
+                let bottom_line = if let Some(remaining) = status.remaining {
+                    format_duration(remaining)
+                } else {
+                    status.state.clone()
+                };
+                render_aligned_text_on_image(size, frame, font.as_ref(), &bottom_line, Scale::uniform(12.0), TextAlignment::BottomCenter, bar_height + 2, (0.0, 0.0), (200, 200, 200, 255));
+            } else {
+                render_aligned_text_on_image(size, frame, font.as_ref(), "Offline", Scale::uniform(14.0), TextAlignment::Center, 0, (0.0, 0.0), (200, 80, 80, 255));
+            }
+        }
+    }
+
+    fn render_hash(&self, _: CoreHandle, button: &UniqueButton, hash: &mut Box<dyn Hasher>) {
+        if let Ok(component) = parse_unique_button_to_component::<PrinterStatusComponent>(button) {
+            if let Some(status) = self.get_status(&component.connection) {
+                status.state.hash(hash);
+                (status.progress as i32).hash(hash);
+                (status.nozzle.actual as i32).hash(hash);
+                (status.bed.actual as i32).hash(hash);
+                status.remaining.map(|x| x / 60).hash(hash);
+            } else {
+                0.hash(hash);
+            }
+        }
+    }
+
+    fn metadata(&self) -> PluginMetadata {
+        get_metadata()
+    }
+}
+
+/// Status display component
+#[component("printer_status")]
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct PrinterStatusComponent {
+    #[serde(default)]
+    pub connection: PrinterConnection,
+}
+
+/// Print control component
+#[component("printer_control")]
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct PrinterControlComponent {
+    #[serde(default)]
+    pub connection: PrinterConnection,
+    #[serde(default)]
+    pub command: PrinterCommand,
+}
+
+fn connection_values(connection: &PrinterConnection) -> Vec<UIValue> {
+    vec![
+        UIValue {
+            name: "kind".to_string(),
+            display_name: "Host".to_string(),
+            description: "Software that runs on the printer host".to_string(),
+            ty: UIFieldType::Choice(PrinterKind::VARIANTS.iter().map(|x| x.to_string()).collect()),
+            value: UIFieldValue::Choice(connection.kind.name().to_string())
+        },
+        UIValue {
+            name: "url".to_string(),
+            display_name: "URL".to_string(),
+            description: "Address of the printer host, eg. http://octopi.local".to_string(),
+            ty: UIFieldType::InputFieldString,
+            value: UIFieldValue::InputFieldString(connection.url.clone())
+        },
+        UIValue {
+            name: "api_key".to_string(),
+            display_name: "API Key".to_string(),
+            description: "API key of the host, can be left empty if host doesn't require it".to_string(),
+            ty: UIFieldType::InputFieldString,
+            value: UIFieldValue::InputFieldString(connection.api_key.clone())
+        },
+    ]
+}
+
+fn set_connection_values(connection: &mut PrinterConnection, value: &Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+    let change_map = map_ui_values(value.clone());
+
+    if let Some(value) = change_map.get("kind") {
+        if let Ok(kind) = value.value.try_into_string() {
+            if let Some(kind) = PrinterKind::from_name(&kind) {
+                connection.kind = kind;
+            }
+        }
+    }
+
+    if let Some(value) = change_map.get("url") {
+        if let Ok(url) = value.value.try_into_string() {
+            if !url.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(vec![FieldError::new("url", "Invalid URL, should start with http:// or https://")]);
+            }
+
+            connection.url = url;
+        }
+    }
+
+    if let Some(value) = change_map.get("api_key") {
+        if let Ok(api_key) = value.value.try_into_string() {
+            connection.api_key = api_key;
+        }
+    }
+
+    Ok(())
+}
+
@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use streamduck_core::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
+use streamduck_core::core::methods::{CoreHandle, send_module_event};
+use streamduck_core::core::UniqueButton;
+use streamduck_core::font::get_font_from_collection;
+use streamduck_core::image::{DynamicImage, GenericImageView};
+use streamduck_core::modules::components::{ComponentDefinition, FieldError, map_ui_values, UIFieldType, UIFieldValue, UIValue};
+use streamduck_core::modules::events::SDCoreEvent;
+use streamduck_core::modules::{ModuleManager, PluginMetadata, SDModule, SDModulePointer};
+use streamduck_core::socket::SocketManager;
+use streamduck_core::thread::rendering::{ButtonBackground, ButtonText, RendererComponentBuilder};
+use streamduck_core::thread::rendering::custom::RenderingManager;
+use streamduck_core::thread::util::{render_aligned_text_on_image, render_filled_rect_on_image, TextAlignment};
+use streamduck_core::util::rusttype::Scale;
+use streamduck_core::util::straight_copy;
+use streamduck_core::versions::{CORE, CORE_EVENTS, CORE_METHODS, PLUGIN_API, RENDERING, SDMODULE_TRAIT};
+use streamduck_core_derive::component;
+
+/// Most dice that can be rolled at once
+const MAX_DICE: u32 = 100;
+
+#[no_mangle]
+pub fn get_metadata() -> PluginMetadata {
+    PluginMetadata::from_literals(
+        "random",
+        "TheJebForge",
+        "Picks random entries from a list or rolls dice on button press",
+        "0.1",
+        &[
+            PLUGIN_API,
+            SDMODULE_TRAIT,
+            CORE,
+            CORE_METHODS,
+            CORE_EVENTS,
+            RENDERING
+        ]
+    )
+}
+
+#[no_mangle]
+pub fn get_module() -> SDModulePointer {
+    Box::into_raw(Box::new(RandomModule))
+}
+
+#[no_mangle]
+pub fn register(_: Arc<SocketManager>, _: Arc<RenderingManager>, _: Arc<ModuleManager>) {}
+
+/// What random choice component picks from
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum RandomMode {
+    List,
+    Dice,
+}
+
+impl Default for RandomMode {
+    fn default() -> Self {
+        RandomMode::List
+    }
+}
+
+impl RandomMode {
+    pub const VARIANTS: [&'static str; 2] = ["List", "Dice"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RandomMode::List => "List",
+            RandomMode::Dice => "Dice",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<RandomMode> {
+        match name {
+            "List" => Some(RandomMode::List),
+            "Dice" => Some(RandomMode::Dice),
+            _ => None
+        }
+    }
+}
+
+/// Parses dice expression like "2d6" into amount of dice and amount of sides, "d20" is same as "1d20"
+pub fn parse_dice(expression: &str) -> Option<(u32, u32)> {
+    let (count, sides) = expression.trim().to_lowercase().split_once('d')
+        .map(|(count, sides)| (count.to_string(), sides.to_string()))?;
+
+    let count = if count.is_empty() { 1 } else { count.parse::<u32>().ok()? };
+    let sides = sides.parse::<u32>().ok()?;
+
+    if count == 0 || count > MAX_DICE || sides == 0 {
+        return None;
+    }
+
+    Some((count, sides))
+}
+
+/// Entries of the list, one per line, empty lines are ignored
+fn list_entries(list: &str) -> Vec<&str> {
+    list.lines()
+        .map(|x| x.trim())
+        .filter(|x| !x.is_empty())
+        .collect()
+}
+
+pub struct RandomModule;
+
+impl SDModule for RandomModule {
+    fn name(&self) -> String {
+        "random".to_string()
+    }
+
+    fn components(&self) -> HashMap<String, ComponentDefinition> {
+        let mut map = HashMap::new();
+
+        map.insert(RandomChoiceComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Random Choice".to_string(),
+            description: "Shows random entry of a list or result of a dice roll when pressed".to_string(),
+            default_looks: RendererComponentBuilder::new()
+                .background(ButtonBackground::Solid((60, 40, 90, 255)))
+                .add_text(ButtonText {
+                    text: "🎲".to_string(),
+                    font: "default".to_string(),
+                    scale: (30.0, 30.0),
+                    alignment: TextAlignment::Center,
+                    padding: 0,
+                    offset: (0.0, 0.0),
+                    color: (255, 255, 255, 255),
+                    shadow: None,
+                    auto_fit: false,
+                    word_wrap: false,
+                    marquee_speed: 0.0
+                })
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map
+    }
+
+    fn add_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            RandomChoiceComponent::NAME => {
+                button.insert_component(RandomChoiceComponent::default()).ok();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn remove_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            RandomChoiceComponent::NAME => {
+                button.remove_component::<RandomChoiceComponent>();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn paste_component(&self, _: CoreHandle, reference_button: &Button, new_button: &mut Button) {
+        straight_copy(reference_button, new_button, RandomChoiceComponent::NAME);
+    }
+
+    fn component_values(&self, _: CoreHandle, button: &Button, name: &str) -> Vec<UIValue> {
+        match name {
+            RandomChoiceComponent::NAME => {
+                if let Ok(component) = parse_button_to_component::<RandomChoiceComponent>(button) {
+                    let mut fields = vec![
+                        UIValue {
+                            name: "mode".to_string(),
+                            display_name: "Mode".to_string(),
+                            description: "Pick an entry from a list or roll dice".to_string(),
+                            ty: UIFieldType::Choice(RandomMode::VARIANTS.iter().map(|x| x.to_string()).collect()),
+                            value: UIFieldValue::Choice(component.mode.name().to_string())
+                        }
+                    ];
+
+                    match component.mode {
+                        RandomMode::List => fields.push(UIValue {
+                            name: "entries".to_string(),
+                            display_name: "Entries".to_string(),
+                            description: "Entries to pick from, one per line".to_string(),
+                            ty: UIFieldType::InputFieldMultiline { language_hint: None },
+                            value: UIFieldValue::InputFieldMultiline(component.entries)
+                        }),
+
+                        RandomMode::Dice => fields.push(UIValue {
+                            name: "dice".to_string(),
+                            display_name: "Dice".to_string(),
+                            description: "Dice to roll in NdM format, eg. 2d6 rolls two six-sided dice".to_string(),
+                            ty: UIFieldType::InputFieldString,
+                            value: UIFieldValue::InputFieldString(component.dice)
+                        }),
+                    }
+
+                    fields.push(UIValue {
+                        name: "emit_event".to_string(),
+                        display_name: "Emit Event".to_string(),
+                        description: "Sends result as \"result\" module event, so scripts can react to it".to_string(),
+                        ty: UIFieldType::Checkbox { disabled: false },
+                        value: UIFieldValue::Checkbox(component.emit_event)
+                    });
+
+                    fields
+                } else {
+                    vec![]
+                }
+            }
+
+            _ => vec![],
+        }
+    }
+
+    fn set_component_value(&self, _: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+        match name {
+            RandomChoiceComponent::NAME => {
+                if let Ok(mut component) = parse_button_to_component::<RandomChoiceComponent>(button) {
+                    let change_map = map_ui_values(value);
+
+                    if let Some(value) = change_map.get("mode") {
+                        if let Ok(mode) = value.value.try_into_string() {
+                            if let Some(mode) = RandomMode::from_name(&mode) {
+                                component.mode = mode;
+                            }
+                        }
+                    }
+
+                    if let Some(value) = change_map.get("entries") {
+                        if let Ok(entries) = value.value.try_into_string() {
+                            component.entries = entries;
+                        }
+                    }
+
+                    if let Some(value) = change_map.get("dice") {
+                        if let Ok(dice) = value.value.try_into_string() {
+                            if parse_dice(&dice).is_none() {
+                                return Err(vec![FieldError::new("dice", &format!("Expected NdM format with up to {} dice, eg. 2d6", MAX_DICE))]);
+                            }
+
+                            component.dice = dice.trim().to_string();
+                        }
+                    }
+
+                    if let Some(value) = change_map.get("emit_event") {
+                        if let Ok(state) = value.value.try_into_bool() {
+                            component.emit_event = state;
+                        }
+                    }
+
+                    // Result of previous settings shouldn't stay on the button
+                    component.result = "".to_string();
+
+                    button.insert_component(component).ok();
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn listening_for(&self) -> Vec<String> {
+        vec![
+            RandomChoiceComponent::NAME.to_string()
+        ]
+    }
+
+    fn event(&self, core: CoreHandle, event: SDCoreEvent) {
+        match event {
+            SDCoreEvent::ButtonAction { key, pressed_button, .. } => {
+                if let Ok(mut component) = parse_unique_button_to_component::<RandomChoiceComponent>(&pressed_button) {
+                    let mut rng = rand::thread_rng();
+
+                    let (result, data) = match component.mode {
+                        RandomMode::List => {
+                            let entries = list_entries(&component.entries);
+
+                            match entries.choose(&mut rng) {
+                                Some(entry) => (entry.to_string(), json!({ "mode": "list", "result": entry })),
+                                None => return
+                            }
+                        }
+
+                        RandomMode::Dice => {
+                            let (count, sides) = match parse_dice(&component.dice) {
+                                Some(dice) => dice,
+                                None => {
+                                    log::warn!("Invalid dice expression '{}'", component.dice);
+                                    return;
+                                }
+                            };
+
+                            let rolls: Vec<u32> = (0..count).map(|_| rng.gen_range(1..=sides)).collect();
+                            let total: u32 = rolls.iter().sum();
+
+                            (total.to_string(), json!({ "mode": "dice", "dice": component.dice, "rolls": rolls, "result": total }))
+                        }
+                    };
+
+                    if component.emit_event {
+                        send_module_event(&core, "result", json!({ "key": key, "data": data }));
+                    }
+
+                    component.result = result;
+                    pressed_button.write().unwrap().insert_component(component).ok();
+                    core.core().mark_for_redraw();
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn render(&self, core: CoreHandle, button: &UniqueButton, frame: &mut DynamicImage) {
+        if let Ok(component) = parse_unique_button_to_component::<RandomChoiceComponent>(button) {
+            if component.result.is_empty() {
+                return;
+            }
+
+            if let Some(font) = get_font_from_collection("default") {
+                // Dimming looks of the button so result stands out
+                let (width, height) = frame.dimensions();
+                render_filled_rect_on_image(frame, (0, 0), (width, height), (0, 0, 0, 170));
+
+                let scale = if component.result.chars().count() > 4 { 14.0 } else { 30.0 };
+                render_aligned_text_on_image(core.core().image_size, frame, font.as_ref(), &component.result, Scale::uniform(scale), TextAlignment::Center, 0, (0.0, 0.0), (255, 230, 120, 255));
+            }
+        }
+    }
+
+    fn render_hash(&self, _: CoreHandle, button: &UniqueButton, hash: &mut Box<dyn Hasher>) {
+        if let Ok(component) = parse_unique_button_to_component::<RandomChoiceComponent>(button) {
+            component.result.hash(hash);
+        }
+    }
+
+    fn metadata(&self) -> PluginMetadata {
+        get_metadata()
+    }
+}
+
+/// Component that shows random entry or dice roll on press
+#[component("random_choice")]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RandomChoiceComponent {
+    #[serde(default)]
+    pub mode: RandomMode,
+    /// Entries to pick from, one per line
+    #[serde(default)]
+    pub entries: String,
+    /// Dice in NdM format
+    #[serde(default = "default_dice")]
+    pub dice: String,
+    /// If result should be sent as module event
+    #[serde(default)]
+    pub emit_event: bool,
+    /// Last picked result that's displayed on the button
+    #[serde(default)]
+    pub result: String,
+}
+
+fn default_dice() -> String { "1d6".to_string() }
+
+impl Default for RandomChoiceComponent {
+    fn default() -> Self {
+        Self {
+            mode: Default::default(),
+            entries: "".to_string(),
+            dice: default_dice(),
+            emit_event: false,
+            result: "".to_string()
+        }
+    }
+}
@@ -0,0 +1,302 @@
+mod player;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::mpsc::SyncSender;
+use serde::{Deserialize, Serialize};
+use streamduck_core::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
+use streamduck_core::core::methods::CoreHandle;
+use streamduck_core::modules::components::{ComponentDefinition, FieldError, map_ui_values, UIFieldType, UIFieldValue, UIScalar, UIValue};
+use streamduck_core::modules::events::SDCoreEvent;
+use streamduck_core::modules::{ModuleManager, PluginMetadata, SDModule, SDModulePointer};
+use streamduck_core::socket::SocketManager;
+use streamduck_core::thread::rendering::{ButtonBackground, ButtonText, RendererComponent, RendererComponentBuilder};
+use streamduck_core::thread::rendering::custom::RenderingManager;
+use streamduck_core::thread::util::TextAlignment;
+use streamduck_core::util::straight_copy;
+use streamduck_core::versions::{CORE_EVENTS, PLUGIN_API, SDMODULE_TRAIT};
+use streamduck_core_derive::component;
+use crate::player::{output_devices, PlayerCommand, start_player};
+
+/// Name of output device choice that stands for default device
+const DEFAULT_DEVICE: &'static str = "Default";
+
+#[no_mangle]
+pub fn get_metadata() -> PluginMetadata {
+    PluginMetadata::from_literals(
+        "soundboard",
+        "TheJebForge",
+        "Plays sound files on button press",
+        "0.1",
+        &[
+            PLUGIN_API,
+            SDMODULE_TRAIT,
+            CORE_EVENTS
+        ]
+    )
+}
+
+#[no_mangle]
+pub fn get_module() -> SDModulePointer {
+    Box::into_raw(Box::new(SoundboardModule::new()))
+}
+
+#[no_mangle]
+pub fn register(_: Arc<SocketManager>, _: Arc<RenderingManager>, _: Arc<ModuleManager>) {}
+
+pub struct SoundboardModule {
+    player: SyncSender<PlayerCommand>,
+}
+
+impl SoundboardModule {
+    pub fn new() -> SoundboardModule {
+        SoundboardModule {
+            player: start_player()
+        }
+    }
+}
+
+fn sound_looks(text: &str) -> RendererComponent {
+    RendererComponentBuilder::new()
+        .background(ButtonBackground::Solid((50, 50, 50, 255)))
+        .add_text(ButtonText {
+            text: text.to_string(),
+            font: "default".to_string(),
+            scale: (30.0, 30.0),
+            alignment: TextAlignment::Center,
+            padding: 0,
+            offset: (0.0, 0.0),
+            color: (255, 255, 255, 255),
+            shadow: None,
+            auto_fit: false,
+            word_wrap: false,
+            marquee_speed: 0.0
+        })
+        .build()
+}
+
+impl SDModule for SoundboardModule {
+    fn name(&self) -> String {
+        "soundboard".to_string()
+    }
+
+    fn components(&self) -> HashMap<String, ComponentDefinition> {
+        let mut map = HashMap::new();
+
+        map.insert(PlaySoundComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Play Sound".to_string(),
+            description: "Plays wav, mp3 or ogg file when pressed".to_string(),
+            default_looks: sound_looks("♪"),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map.insert(StopSoundsComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Stop All Sounds".to_string(),
+            description: "Stops every sound that soundboard is playing".to_string(),
+            default_looks: sound_looks("■"),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map
+    }
+
+    fn add_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            PlaySoundComponent::NAME => {
+                button.insert_component(PlaySoundComponent::default()).ok();
+            }
+
+            StopSoundsComponent::NAME => {
+                button.insert_component(StopSoundsComponent::default()).ok();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn remove_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            PlaySoundComponent::NAME => {
+                button.remove_component::<PlaySoundComponent>();
+            }
+
+            StopSoundsComponent::NAME => {
+                button.remove_component::<StopSoundsComponent>();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn paste_component(&self, _: CoreHandle, reference_button: &Button, new_button: &mut Button) {
+        straight_copy(reference_button, new_button, PlaySoundComponent::NAME);
+        straight_copy(reference_button, new_button, StopSoundsComponent::NAME);
+    }
+
+    fn component_values(&self, _: CoreHandle, button: &Button, name: &str) -> Vec<UIValue> {
+        match name {
+            PlaySoundComponent::NAME => {
+                if let Ok(component) = parse_button_to_component::<PlaySoundComponent>(button) {
+                    let mut devices = vec![DEFAULT_DEVICE.to_string()];
+                    devices.extend(output_devices());
+
+                    vec![
+                        UIValue {
+                            name: "file".to_string(),
+                            display_name: "File".to_string(),
+                            description: "Path to wav, mp3 or ogg file".to_string(),
+                            ty: UIFieldType::InputFieldString,
+                            value: UIFieldValue::InputFieldString(component.file)
+                        },
+                        UIValue {
+                            name: "device".to_string(),
+                            display_name: "Output Device".to_string(),
+                            description: "Device to play the sound on".to_string(),
+                            ty: UIFieldType::Choice(devices),
+                            value: UIFieldValue::Choice(if component.device.is_empty() { DEFAULT_DEVICE.to_string() } else { component.device })
+                        },
+                        UIValue {
+                            name: "volume".to_string(),
+                            display_name: "Volume".to_string(),
+                            description: "Volume in percents".to_string(),
+                            ty: UIFieldType::ValueSliderFloat(UIScalar {
+                                default_value: 100.0,
+                                max_value: 100.0,
+                                min_value: 0.0,
+                                step: 1.0,
+                                allow_out_of_bounds: true
+                            }),
+                            value: UIFieldValue::ValueSliderFloat(component.volume)
+                        },
+                        UIValue {
+                            name: "stop_on_repress".to_string(),
+                            display_name: "Stop On Repress".to_string(),
+                            description: "Pressing the button while the sound is playing stops it, otherwise sounds overlap".to_string(),
+                            ty: UIFieldType::Checkbox { disabled: false },
+                            value: UIFieldValue::Checkbox(component.stop_on_repress)
+                        },
+                    ]
+                } else {
+                    vec![]
+                }
+            }
+
+            _ => vec![],
+        }
+    }
+
+    fn set_component_value(&self, _: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+        match name {
+            PlaySoundComponent::NAME => {
+                if let Ok(mut component) = parse_button_to_component::<PlaySoundComponent>(button) {
+                    let change_map = map_ui_values(value);
+
+                    if let Some(value) = change_map.get("file") {
+                        if let Ok(file) = value.value.try_into_string() {
+                            component.file = file;
+                        }
+                    }
+
+                    if let Some(value) = change_map.get("device") {
+                        if let Ok(device) = value.value.try_into_string() {
+                            component.device = if device == DEFAULT_DEVICE { "".to_string() } else { device };
+                        }
+                    }
+
+                    if let Some(value) = change_map.get("volume") {
+                        if let Ok(volume) = value.value.try_into_f32() {
+                            if volume < 0.0 {
+                                return Err(vec![FieldError::new("volume", "Volume can't be negative")]);
+                            }
+
+                            component.volume = volume;
+                        }
+                    }
+
+                    if let Some(value) = change_map.get("stop_on_repress") {
+                        if let Ok(state) = value.value.try_into_bool() {
+                            component.stop_on_repress = state;
+                        }
+                    }
+
+                    button.insert_component(component).ok();
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn listening_for(&self) -> Vec<String> {
+        vec![
+            PlaySoundComponent::NAME.to_string(),
+            StopSoundsComponent::NAME.to_string()
+        ]
+    }
+
+    fn event(&self, _: CoreHandle, event: SDCoreEvent) {
+        match event {
+            SDCoreEvent::ButtonAction { pressed_button, .. } => {
+                if let Ok(_) = parse_unique_button_to_component::<StopSoundsComponent>(&pressed_button) {
+                    self.player.send(PlayerCommand::StopAll).ok();
+                }
+
+                if let Ok(component) = parse_unique_button_to_component::<PlaySoundComponent>(&pressed_button) {
+                    if !component.file.is_empty() {
+                        self.player.send(PlayerCommand::Play {
+                            path: component.file,
+                            device: component.device,
+                            volume: component.volume / 100.0,
+                            stop_if_playing: component.stop_on_repress
+                        }).ok();
+                    }
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn metadata(&self) -> PluginMetadata {
+        get_metadata()
+    }
+}
+
+/// Component that plays a sound file on press
+#[component("play_sound")]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlaySoundComponent {
+    #[serde(default)]
+    pub file: String,
+    /// Name of output device, default device is used if empty
+    #[serde(default)]
+    pub device: String,
+    /// Volume in percents
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+    /// If pressing the button again should stop the sound instead of playing another copy
+    #[serde(default)]
+    pub stop_on_repress: bool,
+}
+
+fn default_volume() -> f32 { 100.0 }
+
+impl Default for PlaySoundComponent {
+    fn default() -> Self {
+        Self {
+            file: "".to_string(),
+            device: "".to_string(),
+            volume: default_volume(),
+            stop_on_repress: false
+        }
+    }
+}
+
+/// Component that stops all sounds on press
+#[component("stop_sounds")]
+#[derive(Serialize, Deserialize, Default)]
+pub struct StopSoundsComponent {}
@@ -0,0 +1,115 @@
+//! Audio thread that keeps output streams and currently playing sounds
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::mpsc::{sync_channel, SyncSender};
+use std::thread::spawn;
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use rodio::cpal::traits::{DeviceTrait, HostTrait};
+
+/// Command sent to audio thread
+pub enum PlayerCommand {
+    /// Plays a file
+    Play {
+        path: String,
+        /// Name of output device, default device is used if empty
+        device: String,
+        /// Volume from 0 to 1
+        volume: f32,
+        /// Stops the file instead if it's already playing
+        stop_if_playing: bool,
+    },
+
+    /// Stops every sound that is playing
+    StopAll,
+}
+
+/// Lists names of output devices
+pub fn output_devices() -> Vec<String> {
+    rodio::cpal::default_host().output_devices()
+        .map(|devices| devices.filter_map(|x| x.name().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Opens output stream for device with the name, or default device if name is empty
+fn open_stream(device: &str) -> Result<(OutputStream, OutputStreamHandle), String> {
+    if device.is_empty() {
+        return OutputStream::try_default().map_err(|err| err.to_string());
+    }
+
+    let found = rodio::cpal::default_host().output_devices()
+        .map_err(|err| err.to_string())?
+        .find(|x| x.name().map_or(false, |name| name == device))
+        .ok_or_else(|| format!("Output device '{}' not found", device))?;
+
+    OutputStream::try_from_device(&found).map_err(|err| err.to_string())
+}
+
+/// Starts audio thread, streams can't be moved between threads so everything is played from there
+pub fn start_player() -> SyncSender<PlayerCommand> {
+    let (tx, rx) = sync_channel::<PlayerCommand>(16);
+
+    spawn(move || {
+        let mut streams: HashMap<String, (OutputStream, OutputStreamHandle)> = HashMap::new();
+        let mut sinks: Vec<(String, Sink)> = vec![];
+
+        while let Ok(command) = rx.recv() {
+            sinks.retain(|(_, sink)| !sink.empty());
+
+            match command {
+                PlayerCommand::Play { path, device, volume, stop_if_playing } => {
+                    if stop_if_playing && sinks.iter().any(|(playing, _)| *playing == path) {
+                        sinks.retain(|(playing, _)| *playing != path);
+                        continue;
+                    }
+
+                    if !streams.contains_key(&device) {
+                        match open_stream(&device) {
+                            Ok(stream) => {
+                                streams.insert(device.clone(), stream);
+                            }
+
+                            Err(err) => {
+                                log::warn!("Failed to open audio output: {}", err);
+                                continue;
+                            }
+                        }
+                    }
+
+                    let (_, handle) = &streams[&device];
+
+                    let source = match File::open(&path).map_err(|err| err.to_string())
+                        .and_then(|file| Decoder::new(BufReader::new(file)).map_err(|err| err.to_string())) {
+                        Ok(source) => source,
+                        Err(err) => {
+                            log::warn!("Failed to read sound {}: {}", path, err);
+                            continue;
+                        }
+                    };
+
+                    match Sink::try_new(handle) {
+                        Ok(sink) => {
+                            sink.set_volume(volume);
+                            sink.append(source);
+                            sinks.push((path, sink));
+                        }
+
+                        Err(err) => {
+                            log::warn!("Failed to play sound {}: {}", path, err);
+
+                            // Device might have been disconnected, opening the stream again next time
+                            streams.remove(&device);
+                        }
+                    }
+                }
+
+                PlayerCommand::StopAll => {
+                    sinks.clear();
+                }
+            }
+        }
+    });
+
+    tx
+}
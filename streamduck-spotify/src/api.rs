@@ -0,0 +1,233 @@
+//! Minimal client for Spotify Web API
+
+use std::io::Read;
+use std::time::{Duration, Instant};
+use serde_json::{json, Value};
+use streamduck_core::image::DynamicImage;
+
+/// Timeout for any request made to Spotify
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Address of Spotify Web API
+const API_URL: &'static str = "https://api.spotify.com/v1";
+
+/// Address that access tokens are retrieved from
+const TOKEN_URL: &'static str = "https://accounts.spotify.com/api/token";
+
+/// Access tokens are refreshed this long before they actually expire
+const EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+/// Largest album art that will be downloaded
+const MAX_IMAGE_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Error that might occur while talking to Spotify
+#[derive(Debug)]
+pub enum SpotifyError {
+    /// Client ID, client secret or refresh token are missing or were rejected
+    NotAuthorized,
+    /// There's no device that playback could be controlled on
+    NoActiveDevice,
+    /// Spotify refused the request due to rate limiting, contains amount of time to wait before next request
+    RateLimited(Duration),
+    RequestError(String),
+    MalformedResponse,
+}
+
+impl From<ureq::Error> for SpotifyError {
+    fn from(err: ureq::Error) -> Self {
+        if let ureq::Error::Status(code, response) = &err {
+            match code {
+                401 => return SpotifyError::NotAuthorized,
+                404 => return SpotifyError::NoActiveDevice,
+                429 => {
+                    let wait = response.header("retry-after").and_then(|x| x.parse::<u64>().ok()).unwrap_or(5);
+                    return SpotifyError::RateLimited(Duration::from_secs(wait));
+                }
+                _ => {}
+            }
+        }
+
+        SpotifyError::RequestError(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for SpotifyError {
+    fn from(err: std::io::Error) -> Self {
+        SpotifyError::RequestError(err.to_string())
+    }
+}
+
+/// Access token that requests are authorized with
+#[derive(Debug, Clone)]
+pub struct AccessToken {
+    pub token: String,
+    pub expires: Instant,
+}
+
+impl AccessToken {
+    pub fn is_expired(&self) -> bool {
+        Instant::now() + EXPIRY_MARGIN >= self.expires
+    }
+}
+
+/// Retrieves new access token with refresh token, also returns new refresh token if Spotify rotated it
+pub fn refresh_access_token(client_id: &str, client_secret: &str, refresh_token: &str) -> Result<(AccessToken, Option<String>), SpotifyError> {
+    if client_id.is_empty() || refresh_token.is_empty() {
+        return Err(SpotifyError::NotAuthorized);
+    }
+
+    let response: Value = ureq::post(TOKEN_URL)
+        .timeout(REQUEST_TIMEOUT)
+        .set("Authorization", &format!("Basic {}", base64::encode(format!("{}:{}", client_id, client_secret))))
+        .send_form(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ])
+        .map_err(|err| match err {
+            ureq::Error::Status(400, _) => SpotifyError::NotAuthorized,
+            err => err.into()
+        })?
+        .into_json()?;
+
+    let token = response["access_token"].as_str().ok_or(SpotifyError::MalformedResponse)?;
+    let expires_in = response["expires_in"].as_u64().unwrap_or(3600);
+
+    Ok((
+        AccessToken {
+            token: token.to_string(),
+            expires: Instant::now() + Duration::from_secs(expires_in)
+        },
+        response["refresh_token"].as_str().map(|x| x.to_string())
+    ))
+}
+
+/// Track that is currently playing
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct NowPlaying {
+    pub track_id: String,
+    pub title: String,
+    pub artists: String,
+    /// URL of the smallest album art that is still big enough for a key
+    pub album_art: Option<String>,
+    pub is_playing: bool,
+}
+
+/// Device that Spotify can play music on
+#[derive(Debug, Clone)]
+pub struct PlaybackDevice {
+    pub id: String,
+    pub name: String,
+    pub is_active: bool,
+}
+
+fn request(method: &str, path: &str, token: &AccessToken) -> ureq::Request {
+    ureq::request(method, &format!("{}{}", API_URL, path))
+        .timeout(REQUEST_TIMEOUT)
+        .set("Authorization", &format!("Bearer {}", token.token))
+}
+
+/// Retrieves currently playing track, [None] if nothing is playing
+pub fn now_playing(token: &AccessToken) -> Result<Option<NowPlaying>, SpotifyError> {
+    let response = request("GET", "/me/player", token).call()?;
+
+    // Spotify responds with no content if there's no active playback
+    if response.status() == 204 {
+        return Ok(None);
+    }
+
+    let response: Value = response.into_json()?;
+    let item = &response["item"];
+
+    let track_id = if let Some(id) = item["id"].as_str() { id } else { return Ok(None) };
+
+    let artists = item["artists"].as_array()
+        .map(|x| x.iter().filter_map(|x| x["name"].as_str()).collect::<Vec<&str>>().join(", "))
+        .unwrap_or_default();
+
+    // Images are sorted from the largest, keys are rarely bigger than 100 pixels
+    let album_art = item["album"]["images"].as_array()
+        .and_then(|images| images.iter().rev()
+            .find(|x| x["width"].as_u64().unwrap_or(0) >= 100)
+            .or(images.first()))
+        .and_then(|x| x["url"].as_str())
+        .map(|x| x.to_string());
+
+    Ok(Some(NowPlaying {
+        track_id: track_id.to_string(),
+        title: item["name"].as_str().unwrap_or_default().to_string(),
+        artists,
+        album_art,
+        is_playing: response["is_playing"].as_bool().unwrap_or(false)
+    }))
+}
+
+/// Resumes or pauses playback
+pub fn set_playing(token: &AccessToken, playing: bool) -> Result<(), SpotifyError> {
+    let path = if playing { "/me/player/play" } else { "/me/player/pause" };
+    request("PUT", path, token).send_bytes(&[])?;
+    Ok(())
+}
+
+/// Skips to next or previous track
+pub fn skip(token: &AccessToken, forward: bool) -> Result<(), SpotifyError> {
+    let path = if forward { "/me/player/next" } else { "/me/player/previous" };
+    request("POST", path, token).send_bytes(&[])?;
+    Ok(())
+}
+
+/// Checks if track is saved in user's library
+pub fn is_saved(token: &AccessToken, track_id: &str) -> Result<bool, SpotifyError> {
+    let response: Value = request("GET", &format!("/me/tracks/contains?ids={}", track_id), token)
+        .call()?
+        .into_json()?;
+
+    response.as_array()
+        .and_then(|x| x.first())
+        .and_then(|x| x.as_bool())
+        .ok_or(SpotifyError::MalformedResponse)
+}
+
+/// Saves track to user's library or removes it from there
+pub fn set_saved(token: &AccessToken, track_id: &str, saved: bool) -> Result<(), SpotifyError> {
+    let method = if saved { "PUT" } else { "DELETE" };
+    request(method, &format!("/me/tracks?ids={}", track_id), token).send_bytes(&[])?;
+    Ok(())
+}
+
+/// Lists devices that are available for playback
+pub fn devices(token: &AccessToken) -> Result<Vec<PlaybackDevice>, SpotifyError> {
+    let response: Value = request("GET", "/me/player/devices", token)
+        .call()?
+        .into_json()?;
+
+    let devices = response["devices"].as_array().ok_or(SpotifyError::MalformedResponse)?;
+
+    Ok(devices.iter()
+        .filter_map(|x| Some(PlaybackDevice {
+            id: x["id"].as_str()?.to_string(),
+            name: x["name"].as_str().unwrap_or_default().to_string(),
+            is_active: x["is_active"].as_bool().unwrap_or(false)
+        }))
+        .collect())
+}
+
+/// Moves playback to another device
+pub fn transfer_playback(token: &AccessToken, device_id: &str) -> Result<(), SpotifyError> {
+    request("PUT", "/me/player", token)
+        .send_json(json!({ "device_ids": [device_id], "play": true }))?;
+    Ok(())
+}
+
+/// Downloads album art
+pub fn download_image(url: &str) -> Result<DynamicImage, SpotifyError> {
+    let mut bytes = vec![];
+
+    ureq::get(url)
+        .timeout(REQUEST_TIMEOUT)
+        .call()?
+        .into_reader()
+        .take(MAX_IMAGE_SIZE)
+        .read_to_end(&mut bytes)?;
+
+    streamduck_core::image::load_from_memory(&bytes).map_err(|_| SpotifyError::MalformedResponse)
+}
@@ -0,0 +1,669 @@
+mod api;
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{sleep, spawn};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use streamduck_core::config::Config;
+use streamduck_core::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
+use streamduck_core::core::manager::CoreManager;
+use streamduck_core::core::methods::CoreHandle;
+use streamduck_core::core::UniqueButton;
+use streamduck_core::font::get_font_from_collection;
+use streamduck_core::image::{DynamicImage, GenericImageView};
+use streamduck_core::image::imageops::FilterType;
+use streamduck_core::modules::components::{ComponentDefinition, FieldError, map_ui_values, UIFieldType, UIFieldValue, UIValue};
+use streamduck_core::modules::events::SDCoreEvent;
+use streamduck_core::modules::{ModuleManager, PluginMetadata, SDModule, SDModulePointer};
+use streamduck_core::socket::SocketManager;
+use streamduck_core::thread::rendering::{ButtonBackground, ButtonText, RendererComponentBuilder};
+use streamduck_core::thread::rendering::custom::RenderingManager;
+use streamduck_core::thread::util::{render_aligned_text_on_image, render_filled_rect_on_image, TextAlignment};
+use streamduck_core::util::rusttype::Scale;
+use streamduck_core::util::straight_copy;
+use streamduck_core::versions::{CONFIG, CORE, CORE_EVENTS, PLUGIN_API, RENDERING, SDMODULE_TRAIT};
+use streamduck_core_derive::{component, plugin_config};
+use crate::api::{AccessToken, NowPlaying, SpotifyError};
+
+/// Polling interval of playback state
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// Polling interval after errors
+const ERROR_POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Playback state is only polled if now playing button was rendered this recently
+const WATCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// Color of indicator on like buttons when current track is liked
+const LIKED_COLOR: (u8, u8, u8, u8) = (30, 215, 96, 255);
+
+#[no_mangle]
+pub fn get_metadata() -> PluginMetadata {
+    PluginMetadata::from_literals(
+        "spotify",
+        "TheJebForge",
+        "Controls Spotify playback and shows currently playing track",
+        "0.1",
+        &[
+            PLUGIN_API,
+            SDMODULE_TRAIT,
+            CORE,
+            CONFIG,
+            CORE_EVENTS,
+            RENDERING
+        ]
+    )
+}
+
+#[no_mangle]
+pub fn get_module() -> SDModulePointer {
+    Box::into_raw(Box::new(SpotifyModule::new()))
+}
+
+#[no_mangle]
+pub fn register(_: Arc<SocketManager>, _: Arc<RenderingManager>, _: Arc<ModuleManager>) {}
+
+/// Credentials of Spotify application, kept in global plugin settings so every device shares them
+#[plugin_config("spotify")]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SpotifySettings {
+    #[serde(default)]
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: String,
+    /// Refresh token of authorized user, replaced whenever Spotify rotates it
+    #[serde(default)]
+    pub refresh_token: String,
+}
+
+/// Playback state shared with polling thread
+#[derive(Default)]
+struct PlayerState {
+    /// Config to read credentials from, known after module is used for the first time
+    config: Option<Arc<Config>>,
+    token: Option<AccessToken>,
+    now_playing: Option<NowPlaying>,
+    liked: bool,
+    /// Album art of current track along with its URL
+    album_art: Option<(String, DynamicImage)>,
+    last_watched: Option<Instant>,
+    next_poll: Option<Instant>,
+}
+
+type SharedState = Arc<RwLock<PlayerState>>;
+
+/// Gets valid access token, refreshing it if it expired
+fn access_token(state: &SharedState) -> Result<AccessToken, SpotifyError> {
+    let config = {
+        let lock = state.read().unwrap();
+
+        if let Some(token) = &lock.token {
+            if !token.is_expired() {
+                return Ok(token.clone());
+            }
+        }
+
+        lock.config.clone().ok_or(SpotifyError::NotAuthorized)?
+    };
+
+    let mut settings: SpotifySettings = config.get_plugin_settings().unwrap_or_default();
+    let (token, refresh_token) = api::refresh_access_token(&settings.client_id, &settings.client_secret, &settings.refresh_token)?;
+
+    if let Some(refresh_token) = refresh_token {
+        settings.refresh_token = refresh_token;
+        config.set_plugin_settings(settings);
+    }
+
+    state.write().unwrap().token = Some(token.clone());
+    Ok(token)
+}
+
+/// Retrieves playback state, liked status and album art
+fn poll(state: &SharedState) -> Result<(), SpotifyError> {
+    let token = access_token(state)?;
+    let now_playing = api::now_playing(&token)?;
+
+    let (previous_track, previous_art) = {
+        let lock = state.read().unwrap();
+        (
+            lock.now_playing.as_ref().map(|x| x.track_id.clone()),
+            lock.album_art.as_ref().map(|(url, _)| url.clone())
+        )
+    };
+
+    let track_changed = previous_track != now_playing.as_ref().map(|x| x.track_id.clone());
+
+    let liked = match &now_playing {
+        Some(track) if track_changed => Some(api::is_saved(&token, &track.track_id)?),
+        _ => None
+    };
+
+    let art_url = now_playing.as_ref().and_then(|x| x.album_art.clone());
+    let album_art = if art_url != previous_art {
+        Some(art_url.and_then(|url| match api::download_image(&url) {
+            Ok(image) => Some((url, image)),
+            Err(err) => {
+                log::debug!("Failed to download album art: {:?}", err);
+                None
+            }
+        }))
+    } else {
+        None
+    };
+
+    let mut lock = state.write().unwrap();
+    lock.now_playing = now_playing;
+
+    if let Some(liked) = liked {
+        lock.liked = liked;
+    }
+
+    if let Some(album_art) = album_art {
+        lock.album_art = album_art;
+    }
+
+    Ok(())
+}
+
+pub struct SpotifyModule {
+    state: SharedState,
+    /// Polling thread stops once this is false
+    running: Arc<AtomicBool>,
+}
+
+impl SpotifyModule {
+    pub fn new() -> SpotifyModule {
+        let state: SharedState = Default::default();
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread_state = state.clone();
+        let thread_running = running.clone();
+        spawn(move || {
+            while thread_running.load(Ordering::Relaxed) {
+                let due = {
+                    let lock = thread_state.read().unwrap();
+
+                    let watched = lock.last_watched.map_or(false, |x| x.elapsed() < WATCH_TIMEOUT);
+                    let ready = lock.next_poll.map_or(true, |x| x <= Instant::now());
+
+                    watched && ready && lock.config.is_some()
+                };
+
+                if due {
+                    let next_poll = match poll(&thread_state) {
+                        Ok(_) => POLL_INTERVAL,
+
+                        Err(SpotifyError::RateLimited(wait)) => {
+                            log::warn!("Rate limited by Spotify, waiting {}s", wait.as_secs());
+                            wait
+                        }
+
+                        Err(SpotifyError::NotAuthorized) => {
+                            log::warn!("Spotify credentials are missing or invalid, check settings of spotify module");
+                            thread_state.write().unwrap().token = None;
+                            ERROR_POLL_INTERVAL
+                        }
+
+                        Err(err) => {
+                            log::debug!("Failed to retrieve Spotify playback state: {:?}", err);
+                            ERROR_POLL_INTERVAL
+                        }
+                    };
+
+                    thread_state.write().unwrap().next_poll = Some(Instant::now() + next_poll);
+                }
+
+                sleep(Duration::from_secs(1));
+            }
+        });
+
+        SpotifyModule {
+            state,
+            running
+        }
+    }
+
+    /// Remembers config to read credentials from
+    fn use_config(&self, config: Arc<Config>) {
+        let mut lock = self.state.write().unwrap();
+
+        if lock.config.is_none() {
+            lock.config = Some(config);
+        }
+    }
+
+    /// Makes polling thread check playback state on next iteration, used after changing playback
+    fn poll_soon(&self) {
+        self.state.write().unwrap().next_poll = None;
+    }
+
+    /// Performs action of control button
+    fn perform(&self, component: &SpotifyControlComponent) -> Result<(), SpotifyError> {
+        let token = access_token(&self.state)?;
+
+        match component.action {
+            SpotifyAction::PlayPause => {
+                let playing = self.state.read().unwrap().now_playing.as_ref().map(|x| x.is_playing);
+
+                let playing = match playing {
+                    Some(playing) => playing,
+                    None => api::now_playing(&token)?.map_or(false, |x| x.is_playing)
+                };
+
+                api::set_playing(&token, !playing)?;
+
+                if let Some(track) = self.state.write().unwrap().now_playing.as_mut() {
+                    track.is_playing = !playing;
+                }
+            }
+
+            SpotifyAction::Next => api::skip(&token, true)?,
+
+            SpotifyAction::Previous => api::skip(&token, false)?,
+
+            SpotifyAction::Like => {
+                let track = api::now_playing(&token)?.ok_or(SpotifyError::NoActiveDevice)?;
+                let liked = !api::is_saved(&token, &track.track_id)?;
+
+                api::set_saved(&token, &track.track_id, liked)?;
+                self.state.write().unwrap().liked = liked;
+            }
+
+            SpotifyAction::SwitchDevice => {
+                let devices = api::devices(&token)?;
+
+                let target = if component.device.is_empty() {
+                    // Cycling through devices, starting after the active one
+                    let active = devices.iter().position(|x| x.is_active);
+                    let next = active.map_or(0, |x| x + 1);
+                    devices.get(next).or(devices.first())
+                } else {
+                    devices.iter().find(|x| x.name.eq_ignore_ascii_case(&component.device))
+                };
+
+                let target = target.ok_or(SpotifyError::NoActiveDevice)?;
+                api::transfer_playback(&token, &target.id)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for SpotifyModule {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+impl SDModule for SpotifyModule {
+    fn name(&self) -> String {
+        "spotify".to_string()
+    }
+
+    fn components(&self) -> HashMap<String, ComponentDefinition> {
+        let mut map = HashMap::new();
+
+        map.insert(SpotifyControlComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Spotify Control".to_string(),
+            description: "Controls Spotify playback: play/pause, skip, like current track or switch playback device".to_string(),
+            default_looks: RendererComponentBuilder::new()
+                .background(ButtonBackground::Solid((25, 20, 20, 255)))
+                .add_text(ButtonText {
+                    text: "⏯".to_string(),
+                    font: "default".to_string(),
+                    scale: (30.0, 30.0),
+                    alignment: TextAlignment::Center,
+                    padding: 0,
+                    offset: (0.0, 0.0),
+                    color: (30, 215, 96, 255),
+                    shadow: None,
+                    auto_fit: false,
+                    word_wrap: false,
+                    marquee_speed: 0.0
+                })
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map.insert(SpotifyNowPlayingComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Spotify Now Playing".to_string(),
+            description: "Shows album art and title of currently playing track".to_string(),
+            default_looks: RendererComponentBuilder::new()
+                .background(ButtonBackground::Solid((25, 20, 20, 255)))
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map
+    }
+
+    fn add_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            SpotifyControlComponent::NAME => {
+                button.insert_component(SpotifyControlComponent::default()).ok();
+            }
+
+            SpotifyNowPlayingComponent::NAME => {
+                button.insert_component(SpotifyNowPlayingComponent::default()).ok();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn remove_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            SpotifyControlComponent::NAME => {
+                button.remove_component::<SpotifyControlComponent>();
+            }
+
+            SpotifyNowPlayingComponent::NAME => {
+                button.remove_component::<SpotifyNowPlayingComponent>();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn paste_component(&self, _: CoreHandle, reference_button: &Button, new_button: &mut Button) {
+        straight_copy(reference_button, new_button, SpotifyControlComponent::NAME);
+        straight_copy(reference_button, new_button, SpotifyNowPlayingComponent::NAME);
+    }
+
+    fn component_values(&self, _: CoreHandle, button: &Button, name: &str) -> Vec<UIValue> {
+        match name {
+            SpotifyControlComponent::NAME => {
+                if let Ok(component) = parse_button_to_component::<SpotifyControlComponent>(button) {
+                    let mut fields = vec![
+                        UIValue {
+                            name: "action".to_string(),
+                            display_name: "Action".to_string(),
+                            description: "What happens when button is pressed".to_string(),
+                            ty: UIFieldType::Choice(SpotifyAction::VARIANTS.iter().map(|x| x.to_string()).collect()),
+                            value: UIFieldValue::Choice(component.action.name().to_string())
+                        }
+                    ];
+
+                    if component.action == SpotifyAction::SwitchDevice {
+                        fields.push(UIValue {
+                            name: "device".to_string(),
+                            display_name: "Device".to_string(),
+                            description: "Name of device to play on, cycles through available devices if empty".to_string(),
+                            ty: UIFieldType::InputFieldString,
+                            value: UIFieldValue::InputFieldString(component.device)
+                        });
+                    }
+
+                    fields
+                } else {
+                    vec![]
+                }
+            }
+
+            SpotifyNowPlayingComponent::NAME => {
+                if let Ok(component) = parse_button_to_component::<SpotifyNowPlayingComponent>(button) {
+                    vec![
+                        UIValue {
+                            name: "show_title".to_string(),
+                            display_name: "Show Title".to_string(),
+                            description: "Shows title of the track over album art".to_string(),
+                            ty: UIFieldType::Checkbox { disabled: false },
+                            value: UIFieldValue::Checkbox(component.show_title)
+                        }
+                    ]
+                } else {
+                    vec![]
+                }
+            }
+
+            _ => vec![],
+        }
+    }
+
+    fn set_component_value(&self, _: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+        let change_map = map_ui_values(value);
+
+        match name {
+            SpotifyControlComponent::NAME => {
+                if let Ok(mut component) = parse_button_to_component::<SpotifyControlComponent>(button) {
+                    if let Some(value) = change_map.get("action") {
+                        if let Ok(action) = value.value.try_into_string() {
+                            if let Some(action) = SpotifyAction::from_name(&action) {
+                                component.action = action;
+                            }
+                        }
+                    }
+
+                    if let Some(value) = change_map.get("device") {
+                        if let Ok(device) = value.value.try_into_string() {
+                            component.device = device;
+                        }
+                    }
+
+                    button.insert_component(component).ok();
+                }
+            }
+
+            SpotifyNowPlayingComponent::NAME => {
+                if let Ok(mut component) = parse_button_to_component::<SpotifyNowPlayingComponent>(button) {
+                    if let Some(value) = change_map.get("show_title") {
+                        if let Ok(show_title) = value.value.try_into_bool() {
+                            component.show_title = show_title;
+                        }
+                    }
+
+                    button.insert_component(component).ok();
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn listening_for(&self) -> Vec<String> {
+        vec![
+            SpotifyControlComponent::NAME.to_string(),
+            SpotifyNowPlayingComponent::NAME.to_string()
+        ]
+    }
+
+    fn settings(&self, core_manager: Arc<CoreManager>) -> Vec<UIValue> {
+        let settings: SpotifySettings = core_manager.config.get_plugin_settings().unwrap_or_default();
+
+        vec![
+            UIValue {
+                name: "client_id".to_string(),
+                display_name: "Client ID".to_string(),
+                description: "Client ID of application registered on Spotify developer dashboard".to_string(),
+                ty: UIFieldType::InputFieldString,
+                value: UIFieldValue::InputFieldString(settings.client_id)
+            },
+            UIValue {
+                name: "client_secret".to_string(),
+                display_name: "Client Secret".to_string(),
+                description: "Client secret of the application".to_string(),
+                ty: UIFieldType::InputFieldString,
+                value: UIFieldValue::InputFieldString(settings.client_secret)
+            },
+            UIValue {
+                name: "refresh_token".to_string(),
+                display_name: "Refresh Token".to_string(),
+                description: "Refresh token of the user, authorized with user-read-playback-state, user-modify-playback-state, user-library-read and user-library-modify scopes".to_string(),
+                ty: UIFieldType::InputFieldString,
+                value: UIFieldValue::InputFieldString(settings.refresh_token)
+            },
+        ]
+    }
+
+    fn set_setting(&self, core_manager: Arc<CoreManager>, value: Vec<UIValue>) {
+        let mut settings: SpotifySettings = core_manager.config.get_plugin_settings().unwrap_or_default();
+        let change_map = map_ui_values(value);
+
+        for (name, field) in [
+            ("client_id", &mut settings.client_id),
+            ("client_secret", &mut settings.client_secret),
+            ("refresh_token", &mut settings.refresh_token),
+        ] {
+            if let Some(value) = change_map.get(name) {
+                if let Ok(str) = value.value.try_into_string() {
+                    *field = str.trim().to_string();
+                }
+            }
+        }
+
+        core_manager.config.set_plugin_settings(settings);
+        self.use_config(core_manager.config.clone());
+
+        // Credentials might have changed, so token has to be retrieved again
+        let mut lock = self.state.write().unwrap();
+        lock.token = None;
+        lock.next_poll = None;
+    }
+
+    fn event(&self, core: CoreHandle, event: SDCoreEvent) {
+        match event {
+            SDCoreEvent::ButtonAction { pressed_button, .. } => {
+                if let Ok(component) = parse_unique_button_to_component::<SpotifyControlComponent>(&pressed_button) {
+                    self.use_config(core.config());
+
+                    if let Err(err) = self.perform(&component) {
+                        log::warn!("Failed to perform Spotify action: {:?}", err);
+                    }
+
+                    self.poll_soon();
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn render(&self, core: CoreHandle, button: &UniqueButton, frame: &mut DynamicImage) {
+        let lock = self.state.read().unwrap();
+
+        if let Ok(component) = parse_unique_button_to_component::<SpotifyNowPlayingComponent>(button) {
+            let (width, height) = frame.dimensions();
+
+            if let Some((_, art)) = &lock.album_art {
+                *frame = DynamicImage::ImageRgba8(art.resize_to_fill(width, height, FilterType::Triangle).to_rgba8());
+            }
+
+            if let Some(track) = &lock.now_playing {
+                if !track.is_playing {
+                    render_filled_rect_on_image(frame, (0, 0), (width, height), (0, 0, 0, 140));
+                }
+
+                if component.show_title {
+                    if let Some(font) = get_font_from_collection("default") {
+                        let strip = height / 4;
+                        render_filled_rect_on_image(frame, (0, (height - strip) as i32), (width, strip), (0, 0, 0, 170));
+                        render_aligned_text_on_image(core.core().image_size, frame, font.as_ref(), &track.title, Scale::uniform(12.0), TextAlignment::BottomCenter, 4, (0.0, 0.0), (255, 255, 255, 255));
+                    }
+                }
+            }
+        }
+
+        if let Ok(component) = parse_unique_button_to_component::<SpotifyControlComponent>(button) {
+            if component.action == SpotifyAction::Like && lock.liked && lock.now_playing.is_some() {
+                let (width, height) = frame.dimensions();
+                let thickness = (width / 14).max(1);
+
+                render_filled_rect_on_image(frame, (0, (height - thickness) as i32), (width, thickness), LIKED_COLOR);
+            }
+        }
+    }
+
+    fn render_hash(&self, core: CoreHandle, button: &UniqueButton, hash: &mut Box<dyn Hasher>) {
+        let is_now_playing = parse_unique_button_to_component::<SpotifyNowPlayingComponent>(button).is_ok();
+
+        if is_now_playing {
+            self.use_config(core.config());
+            self.state.write().unwrap().last_watched = Some(Instant::now());
+        }
+
+        let lock = self.state.read().unwrap();
+
+        lock.now_playing.hash(hash);
+        lock.liked.hash(hash);
+        lock.album_art.as_ref().map(|(url, _)| url).hash(hash);
+    }
+
+    fn metadata(&self) -> PluginMetadata {
+        get_metadata()
+    }
+}
+
+/// Action that control button performs
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpotifyAction {
+    PlayPause,
+    Next,
+    Previous,
+    Like,
+    SwitchDevice,
+}
+
+impl Default for SpotifyAction {
+    fn default() -> Self {
+        SpotifyAction::PlayPause
+    }
+}
+
+impl SpotifyAction {
+    pub const VARIANTS: [&'static str; 5] = ["Play/Pause", "Next Track", "Previous Track", "Like", "Switch Device"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpotifyAction::PlayPause => "Play/Pause",
+            SpotifyAction::Next => "Next Track",
+            SpotifyAction::Previous => "Previous Track",
+            SpotifyAction::Like => "Like",
+            SpotifyAction::SwitchDevice => "Switch Device",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<SpotifyAction> {
+        match name {
+            "Play/Pause" => Some(SpotifyAction::PlayPause),
+            "Next Track" => Some(SpotifyAction::Next),
+            "Previous Track" => Some(SpotifyAction::Previous),
+            "Like" => Some(SpotifyAction::Like),
+            "Switch Device" => Some(SpotifyAction::SwitchDevice),
+            _ => None
+        }
+    }
+}
+
+/// Component that controls Spotify playback on press
+#[component("spotify_control")]
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct SpotifyControlComponent {
+    #[serde(default)]
+    pub action: SpotifyAction,
+    /// Name of device to switch playback to, cycles through devices if empty
+    #[serde(default)]
+    pub device: String,
+}
+
+/// Component that shows album art of currently playing track
+#[component("spotify_now_playing")]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SpotifyNowPlayingComponent {
+    #[serde(default = "default_show_title")]
+    pub show_title: bool,
+}
+
+fn default_show_title() -> bool { true }
+
+impl Default for SpotifyNowPlayingComponent {
+    fn default() -> Self {
+        Self {
+            show_title: default_show_title()
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Harness for end-to-end tests, boots daemon in-process with virtual devices and loopback socket
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::json;
+use streamduck_core::config::Config;
+use streamduck_core::core::manager::CoreManager;
+use streamduck_core::core::methods::{button_down, button_up, CoreHandle, get_button_image, get_stack};
+use streamduck_core::font::load_default_font;
+use streamduck_core::image::{DynamicImage, GenericImageView};
+use streamduck_core::modules::events::SDGlobalEvent;
+use streamduck_core::modules::{load_base_modules, ModuleManager};
+use streamduck_core::socket::{PACKET_TERMINATOR, parse_packet, SocketData, SocketManager, SocketPacket, SocketPool};
+use streamduck_core::streamdeck::Kind;
+use streamduck_core::thread::rendering::custom::RenderingManager;
+use streamduck_daemon::autosave::ConfigAutosave;
+use streamduck_daemon::daemon_data::{DaemonListener, READ_ONLY_REQUESTS};
+use streamduck_daemon::history::EditHistory;
+use streamduck_daemon::transfer::TransferManager;
+use streamduck_daemon::remote::RemoteModuleHost;
+
+pub use streamduck_core;
+pub use streamduck_daemon;
+
+/// Default time to wait for asynchronous things like events
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
+
+static INSTANCE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Daemon running in current process, with its own temporary config folder
+pub struct TestDaemon {
+    pub config: Arc<Config>,
+    pub module_manager: Arc<ModuleManager>,
+    pub render_manager: Arc<RenderingManager>,
+    pub socket_manager: Arc<SocketManager>,
+    pub core_manager: Arc<CoreManager>,
+    events: Arc<SocketPool>,
+    root: PathBuf,
+}
+
+impl TestDaemon {
+    /// Boots daemon with built-in modules and no devices
+    pub fn new() -> TestDaemon {
+        let root = std::env::temp_dir().join(format!(
+            "streamduck-test-{}-{}",
+            std::process::id(),
+            INSTANCE_COUNTER.fetch_add(1, Ordering::SeqCst)
+        ));
+        std::fs::create_dir_all(&root).unwrap();
+
+        let config: Config = serde_json::from_value(json!({
+            "device_config_path": root.join("devices"),
+            "plugin_path": root.join("plugins"),
+            "plugin_settings_path": root.join("global.json"),
+            "autosave_interval": 0.0
+        })).unwrap();
+        config.load_plugin_settings();
+        let config = Arc::new(config);
+
+        let module_manager = ModuleManager::new();
+        let render_manager = RenderingManager::new();
+        let socket_manager = SocketManager::new();
+
+        load_base_modules(module_manager.clone(), socket_manager.clone());
+        load_default_font();
+        streamduck_actions::init_module(&module_manager);
+
+        let core_manager = CoreManager::new(module_manager.clone(), render_manager.clone(), socket_manager.clone(), config.clone());
+
+        socket_manager.add_listener(Box::new(DaemonListener {
+            core_manager: core_manager.clone(),
+            module_manager: module_manager.clone(),
+            config: config.clone(),
+            clipboard: Mutex::new(None),
+            autosave: ConfigAutosave::new(config.clone()),
+            history: EditHistory::new(),
+            transfers: TransferManager::new(),
+            remote_modules: RemoteModuleHost::new(module_manager.clone(), socket_manager.clone()),
+            started: Instant::now()
+        }));
+
+        socket_manager.allow_for_read_only(READ_ONLY_REQUESTS);
+
+        let events = socket_manager.get_pool();
+
+        TestDaemon {
+            config,
+            module_manager,
+            render_manager,
+            socket_manager,
+            core_manager,
+            events,
+            root
+        }
+    }
+
+    /// Adds virtual device of specified kind and returns handle to its core
+    pub fn add_virtual_device(&self, kind: Kind, serial: &str) -> CoreHandle {
+        CoreHandle::wrap(self.core_manager.add_virtual_device(kind, serial).core)
+    }
+
+    /// Gets handle to core of a device
+    pub fn device(&self, serial: &str) -> CoreHandle {
+        CoreHandle::wrap(self.core_manager.get_device(serial).expect("device not found").core)
+    }
+
+    /// Sends packet through loopback socket and returns everything daemon wrote back
+    pub fn send_packet(&self, packet: SocketPacket) -> Vec<SocketPacket> {
+        let mut buffer: Vec<u8> = vec![];
+        self.socket_manager.received_message(&mut buffer, packet);
+
+        buffer.split(|x| *x == PACKET_TERMINATOR)
+            .filter_map(|x| parse_packet(x).ok())
+            .collect()
+    }
+
+    /// Same as [TestDaemon::send_packet], but packet is processed as if it came from read-only client
+    pub fn send_read_only_packet(&self, packet: SocketPacket) -> Vec<SocketPacket> {
+        let mut buffer: Vec<u8> = vec![];
+        self.socket_manager.received_read_only_message(&mut buffer, packet);
+
+        buffer.split(|x| *x == PACKET_TERMINATOR)
+            .filter_map(|x| parse_packet(x).ok())
+            .collect()
+    }
+
+    /// Sends request through loopback socket and parses response
+    pub fn request<Req: SocketData + Serialize, Res: SocketData + DeserializeOwned>(&self, request: &Req) -> Res {
+        self.parse_response(SocketPacket {
+            ty: Req::NAME.to_string(),
+            requester: Some("test".to_string()),
+            data: Some(serde_json::to_value(request).unwrap())
+        })
+    }
+
+    /// Sends request without any data through loopback socket and parses response
+    pub fn request_without_data<Res: SocketData + DeserializeOwned>(&self) -> Res {
+        self.parse_response(SocketPacket {
+            ty: Res::NAME.to_string(),
+            requester: Some("test".to_string()),
+            data: None
+        })
+    }
+
+    fn parse_response<Res: SocketData + DeserializeOwned>(&self, packet: SocketPacket) -> Res {
+        let response = self.send_packet(packet).into_iter()
+            .find(|x| x.ty == Res::NAME)
+            .unwrap_or_else(|| panic!("daemon didn't respond to '{}'", Res::NAME));
+
+        serde_json::from_value(response.data.expect("response has no data")).expect("failed to parse response")
+    }
+
+    /// Simulates press and release of a key on a device
+    pub fn press(&self, serial: &str, key: u8) {
+        let core = self.device(serial);
+        button_down(&core, key);
+        button_up(&core, key);
+    }
+
+    /// Renders image of a key as it would be shown on the device
+    pub fn key_image(&self, serial: &str, key: u8) -> Option<DynamicImage> {
+        get_button_image(&self.device(serial), key)
+    }
+
+    /// Asserts that pixel in the middle of the key has specified color
+    pub fn assert_key_color(&self, serial: &str, key: u8, color: (u8, u8, u8)) {
+        let image = self.key_image(serial, key).unwrap_or_else(|| panic!("key {} has no image", key));
+        let (width, height) = image.dimensions();
+        let pixel = image.get_pixel(width / 2, height / 2).0;
+
+        assert_eq!((pixel[0], pixel[1], pixel[2]), color, "unexpected color of key {}", key);
+    }
+
+    /// Gets display names of panels in the stack, from root to top
+    pub fn stack_names(&self, serial: &str) -> Vec<String> {
+        get_stack(&self.device(serial)).into_iter()
+            .map(|x| x.read().unwrap().display_name.clone())
+            .collect()
+    }
+
+    /// Waits for event that matches the predicate, skipping any other events
+    pub fn wait_for_event<F: Fn(&SDGlobalEvent) -> bool>(&self, predicate: F) -> Option<SDGlobalEvent> {
+        let deadline = Instant::now() + DEFAULT_TIMEOUT;
+
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            if let Some(packet) = self.events.take_message_timeout(remaining) {
+                if packet.ty != "event" {
+                    continue;
+                }
+
+                if let Some(event) = packet.data.and_then(|x| serde_json::from_value::<SDGlobalEvent>(x).ok()) {
+                    if predicate(&event) {
+                        return Some(event);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Drops all events received so far
+    pub fn clear_events(&self) {
+        while self.events.take_message_timeout(Duration::ZERO).is_some() {}
+    }
+}
+
+impl Drop for TestDaemon {
+    fn drop(&mut self) {
+        self.events.close();
+        std::fs::remove_dir_all(&self.root).ok();
+    }
+}
@@ -0,0 +1,351 @@
+mod speaker;
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use streamduck_core::config::Config;
+use streamduck_core::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
+use streamduck_core::core::manager::CoreManager;
+use streamduck_core::core::methods::CoreHandle;
+use streamduck_core::modules::components::{ComponentDefinition, FieldError, map_ui_values, UIFieldType, UIFieldValue, UIScalar, UIValue};
+use streamduck_core::modules::events::SDCoreEvent;
+use streamduck_core::modules::messages::ModuleMessage;
+use streamduck_core::modules::{ModuleManager, PluginMetadata, SDModule, SDModulePointer};
+use streamduck_core::socket::SocketManager;
+use streamduck_core::thread::rendering::{ButtonBackground, ButtonText, RendererComponentBuilder};
+use streamduck_core::thread::rendering::custom::RenderingManager;
+use streamduck_core::thread::util::TextAlignment;
+use streamduck_core::util::straight_copy;
+use streamduck_core::versions::{CONFIG, CORE, CORE_EVENTS, PLUGIN_API, SDMODULE_TRAIT};
+use streamduck_core_derive::{component, plugin_config};
+use crate::speaker::{speak, voices};
+
+/// Topic of message bus that other modules can send text to, to have it spoken
+pub const SPEAK_TOPIC: &'static str = "tts.speak";
+
+/// Name of voice choice that stands for default voice
+const DEFAULT_VOICE: &'static str = "Default";
+
+/// Settings last read from config, message bus callbacks don't have access to config
+static SETTINGS: RwLock<Option<TtsSettings>> = RwLock::new(None);
+
+#[no_mangle]
+pub fn get_metadata() -> PluginMetadata {
+    PluginMetadata::from_literals(
+        "tts",
+        "TheJebForge",
+        "Speaks text on button press or when other modules send text to \"tts.speak\" topic",
+        "0.1",
+        &[
+            PLUGIN_API,
+            SDMODULE_TRAIT,
+            CORE,
+            CONFIG,
+            CORE_EVENTS
+        ]
+    )
+}
+
+#[no_mangle]
+pub fn get_module() -> SDModulePointer {
+    Box::into_raw(Box::new(TtsModule))
+}
+
+#[no_mangle]
+pub fn register(_: Arc<SocketManager>, _: Arc<RenderingManager>, module_manager: Arc<ModuleManager>) {
+    module_manager.message_bus().subscribe("tts", SPEAK_TOPIC, |message: ModuleMessage| {
+        speak(&text_from_value(&message.data), cached_settings());
+    });
+}
+
+/// Voice settings shared by all speak buttons
+#[plugin_config("tts")]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TtsSettings {
+    /// Name of voice, default voice is used if empty
+    #[serde(default)]
+    pub voice: String,
+    /// Speech rate in percents of normal rate
+    #[serde(default = "default_percent")]
+    pub rate: f32,
+    /// Volume in percents
+    #[serde(default = "default_percent")]
+    pub volume: f32,
+    /// If new text should interrupt text that is being spoken
+    #[serde(default = "default_interrupt")]
+    pub interrupt: bool,
+}
+
+fn default_percent() -> f32 { 100.0 }
+fn default_interrupt() -> bool { true }
+
+impl Default for TtsSettings {
+    fn default() -> Self {
+        Self {
+            voice: "".to_string(),
+            rate: default_percent(),
+            volume: default_percent(),
+            interrupt: default_interrupt()
+        }
+    }
+}
+
+fn cached_settings() -> TtsSettings {
+    SETTINGS.read().unwrap().clone().unwrap_or_default()
+}
+
+fn load_settings(config: &Arc<Config>) -> TtsSettings {
+    let settings: TtsSettings = config.get_plugin_settings().unwrap_or_default();
+    *SETTINGS.write().unwrap() = Some(settings.clone());
+    settings
+}
+
+/// Turns message data into text, strings are taken as is, objects can have "text" field
+fn text_from_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => "".to_string(),
+        Value::Object(map) if map.contains_key("text") => text_from_value(&map["text"]),
+        value => value.to_string()
+    }
+}
+
+fn percent_slider() -> UIFieldType {
+    UIFieldType::ValueSliderFloat(UIScalar {
+        default_value: 100.0,
+        max_value: 200.0,
+        min_value: 0.0,
+        step: 5.0,
+        allow_out_of_bounds: false
+    })
+}
+
+pub struct TtsModule;
+
+impl SDModule for TtsModule {
+    fn name(&self) -> String {
+        "tts".to_string()
+    }
+
+    fn components(&self) -> HashMap<String, ComponentDefinition> {
+        let mut map = HashMap::new();
+
+        map.insert(SpeakTextComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Speak Text".to_string(),
+            description: "Speaks text or last message of a message bus topic when pressed".to_string(),
+            default_looks: RendererComponentBuilder::new()
+                .background(ButtonBackground::Solid((50, 50, 50, 255)))
+                .add_text(ButtonText {
+                    text: "TTS".to_string(),
+                    font: "default".to_string(),
+                    scale: (25.0, 25.0),
+                    alignment: TextAlignment::Center,
+                    padding: 0,
+                    offset: (0.0, 0.0),
+                    color: (255, 255, 255, 255),
+                    shadow: None,
+                    auto_fit: false,
+                    word_wrap: false,
+                    marquee_speed: 0.0
+                })
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map
+    }
+
+    fn add_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            SpeakTextComponent::NAME => {
+                button.insert_component(SpeakTextComponent::default()).ok();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn remove_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            SpeakTextComponent::NAME => {
+                button.remove_component::<SpeakTextComponent>();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn paste_component(&self, _: CoreHandle, reference_button: &Button, new_button: &mut Button) {
+        straight_copy(reference_button, new_button, SpeakTextComponent::NAME);
+    }
+
+    fn component_values(&self, _: CoreHandle, button: &Button, name: &str) -> Vec<UIValue> {
+        match name {
+            SpeakTextComponent::NAME => {
+                if let Ok(component) = parse_button_to_component::<SpeakTextComponent>(button) {
+                    vec![
+                        UIValue {
+                            name: "text".to_string(),
+                            display_name: "Text".to_string(),
+                            description: "Text to speak".to_string(),
+                            ty: UIFieldType::InputFieldMultiline { language_hint: None },
+                            value: UIFieldValue::InputFieldMultiline(component.text)
+                        },
+                        UIValue {
+                            name: "topic".to_string(),
+                            display_name: "Topic".to_string(),
+                            description: "Message bus topic to speak last message of instead, text is spoken if nothing was sent to the topic yet".to_string(),
+                            ty: UIFieldType::InputFieldString,
+                            value: UIFieldValue::InputFieldString(component.topic)
+                        },
+                    ]
+                } else {
+                    vec![]
+                }
+            }
+
+            _ => vec![],
+        }
+    }
+
+    fn set_component_value(&self, _: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+        match name {
+            SpeakTextComponent::NAME => {
+                if let Ok(mut component) = parse_button_to_component::<SpeakTextComponent>(button) {
+                    let change_map = map_ui_values(value);
+
+                    if let Some(value) = change_map.get("text") {
+                        if let Ok(text) = value.value.try_into_string() {
+                            component.text = text;
+                        }
+                    }
+
+                    if let Some(value) = change_map.get("topic") {
+                        if let Ok(topic) = value.value.try_into_string() {
+                            component.topic = topic.trim().to_string();
+                        }
+                    }
+
+                    button.insert_component(component).ok();
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn listening_for(&self) -> Vec<String> {
+        vec![
+            SpeakTextComponent::NAME.to_string()
+        ]
+    }
+
+    fn settings(&self, core_manager: Arc<CoreManager>) -> Vec<UIValue> {
+        let settings = load_settings(&core_manager.config);
+
+        let mut voice_choices = vec![DEFAULT_VOICE.to_string()];
+        voice_choices.extend(voices());
+
+        vec![
+            UIValue {
+                name: "voice".to_string(),
+                display_name: "Voice".to_string(),
+                description: "Voice of speech engine".to_string(),
+                ty: UIFieldType::Choice(voice_choices),
+                value: UIFieldValue::Choice(if settings.voice.is_empty() { DEFAULT_VOICE.to_string() } else { settings.voice })
+            },
+            UIValue {
+                name: "rate".to_string(),
+                display_name: "Rate".to_string(),
+                description: "Speech rate in percents of normal rate".to_string(),
+                ty: percent_slider(),
+                value: UIFieldValue::ValueSliderFloat(settings.rate)
+            },
+            UIValue {
+                name: "volume".to_string(),
+                display_name: "Volume".to_string(),
+                description: "Volume in percents".to_string(),
+                ty: percent_slider(),
+                value: UIFieldValue::ValueSliderFloat(settings.volume)
+            },
+            UIValue {
+                name: "interrupt".to_string(),
+                display_name: "Interrupt".to_string(),
+                description: "New text stops text that is being spoken, otherwise it waits for its turn".to_string(),
+                ty: UIFieldType::Checkbox { disabled: false },
+                value: UIFieldValue::Checkbox(settings.interrupt)
+            },
+        ]
+    }
+
+    fn set_setting(&self, core_manager: Arc<CoreManager>, value: Vec<UIValue>) {
+        let mut settings = load_settings(&core_manager.config);
+        let change_map = map_ui_values(value);
+
+        if let Some(value) = change_map.get("voice") {
+            if let Ok(voice) = value.value.try_into_string() {
+                settings.voice = if voice == DEFAULT_VOICE { "".to_string() } else { voice };
+            }
+        }
+
+        if let Some(value) = change_map.get("rate") {
+            if let Ok(rate) = value.value.try_into_f32() {
+                settings.rate = rate.max(0.0);
+            }
+        }
+
+        if let Some(value) = change_map.get("volume") {
+            if let Ok(volume) = value.value.try_into_f32() {
+                settings.volume = volume.max(0.0);
+            }
+        }
+
+        if let Some(value) = change_map.get("interrupt") {
+            if let Ok(interrupt) = value.value.try_into_bool() {
+                settings.interrupt = interrupt;
+            }
+        }
+
+        *SETTINGS.write().unwrap() = Some(settings.clone());
+        core_manager.config.set_plugin_settings(settings);
+    }
+
+    fn event(&self, core: CoreHandle, event: SDCoreEvent) {
+        match event {
+            SDCoreEvent::ButtonAction { pressed_button, .. } => {
+                if let Ok(component) = parse_unique_button_to_component::<SpeakTextComponent>(&pressed_button) {
+                    let piped = if component.topic.is_empty() {
+                        None
+                    } else {
+                        core.module_manager().message_bus().state(&component.topic)
+                    };
+
+                    let text = piped.map_or(component.text, |x| text_from_value(&x));
+
+                    speak(&text, load_settings(&core.config()));
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    fn metadata(&self) -> PluginMetadata {
+        get_metadata()
+    }
+}
+
+/// Component that speaks text on press
+#[component("speak_text")]
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct SpeakTextComponent {
+    #[serde(default)]
+    pub text: String,
+    /// Message bus topic which last message will be spoken instead of the text
+    #[serde(default)]
+    pub topic: String,
+}
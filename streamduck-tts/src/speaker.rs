@@ -0,0 +1,98 @@
+//! Speech thread, speech engines aren't thread safe on every platform so everything is spoken from one thread
+
+use std::sync::Mutex;
+use std::sync::mpsc::{sync_channel, SyncSender};
+use std::thread::spawn;
+use std::time::Duration;
+use tts::Tts;
+use crate::TtsSettings;
+
+/// Time to wait for speech thread to list voices
+const VOICE_LIST_TIMEOUT: Duration = Duration::from_secs(2);
+
+static SPEAKER: Mutex<Option<SyncSender<SpeakerCommand>>> = Mutex::new(None);
+
+enum SpeakerCommand {
+    Speak(String, TtsSettings),
+    ListVoices(SyncSender<Vec<String>>),
+}
+
+/// Gets sender of speech thread, starting the thread if it's not running yet
+fn speaker() -> SyncSender<SpeakerCommand> {
+    let mut lock = SPEAKER.lock().unwrap();
+
+    if let Some(sender) = lock.as_ref() {
+        return sender.clone();
+    }
+
+    let (tx, rx) = sync_channel::<SpeakerCommand>(16);
+
+    spawn(move || {
+        let mut tts = match Tts::default() {
+            Ok(tts) => tts,
+            Err(err) => {
+                log::error!("Failed to initialize text-to-speech: {}", err);
+                return;
+            }
+        };
+
+        while let Ok(command) = rx.recv() {
+            match command {
+                SpeakerCommand::Speak(text, settings) => {
+                    apply_settings(&mut tts, &settings);
+
+                    if let Err(err) = tts.speak(text, settings.interrupt) {
+                        log::warn!("Failed to speak: {}", err);
+                    }
+                }
+
+                SpeakerCommand::ListVoices(response) => {
+                    let voices = tts.voices()
+                        .map(|voices| voices.iter().map(|x| x.name()).collect())
+                        .unwrap_or_default();
+
+                    response.send(voices).ok();
+                }
+            }
+        }
+    });
+
+    *lock = Some(tx.clone());
+    tx
+}
+
+fn apply_settings(tts: &mut Tts, settings: &TtsSettings) {
+    if !settings.voice.is_empty() {
+        if let Ok(voices) = tts.voices() {
+            if let Some(voice) = voices.iter().find(|x| x.name() == settings.voice) {
+                tts.set_voice(voice).ok();
+            }
+        }
+    }
+
+    let rate = (tts.normal_rate() * settings.rate / 100.0).clamp(tts.min_rate(), tts.max_rate());
+    tts.set_rate(rate).ok();
+
+    let volume = (tts.max_volume() * settings.volume / 100.0).clamp(tts.min_volume(), tts.max_volume());
+    tts.set_volume(volume).ok();
+}
+
+/// Speaks text with provided settings
+pub fn speak(text: &str, settings: TtsSettings) {
+    if text.trim().is_empty() {
+        return;
+    }
+
+    speaker().send(SpeakerCommand::Speak(text.to_string(), settings)).ok();
+}
+
+/// Lists names of voices that speech engine has
+pub fn voices() -> Vec<String> {
+    let (tx, rx) = sync_channel(1);
+
+    if speaker().send(SpeakerCommand::ListVoices(tx)).is_err() {
+        return vec![];
+    }
+
+    rx.recv_timeout(VOICE_LIST_TIMEOUT).unwrap_or_default()
+}
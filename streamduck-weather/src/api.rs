@@ -0,0 +1,199 @@
+//! Minimal clients for Open-Meteo and OpenWeatherMap APIs
+
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Timeout for any request made to weather service
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+const OPEN_METEO_API: &'static str = "https://api.open-meteo.com/v1/forecast";
+const OPEN_WEATHER_MAP_API: &'static str = "https://api.openweathermap.org/data/2.5/weather";
+
+/// Service that provides the weather
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum WeatherProvider {
+    OpenMeteo,
+    OpenWeatherMap,
+}
+
+impl Default for WeatherProvider {
+    fn default() -> Self {
+        WeatherProvider::OpenMeteo
+    }
+}
+
+impl WeatherProvider {
+    pub const VARIANTS: [&'static str; 2] = ["Open-Meteo", "OpenWeatherMap"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WeatherProvider::OpenMeteo => "Open-Meteo",
+            WeatherProvider::OpenWeatherMap => "OpenWeatherMap",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<WeatherProvider> {
+        match name {
+            "Open-Meteo" => Some(WeatherProvider::OpenMeteo),
+            "OpenWeatherMap" => Some(WeatherProvider::OpenWeatherMap),
+            _ => None
+        }
+    }
+}
+
+/// Location and service to retrieve weather from, buttons with same query share responses
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Hash, PartialEq, Eq)]
+pub struct WeatherQuery {
+    pub provider: WeatherProvider,
+    #[serde(default)]
+    pub latitude: String,
+    #[serde(default)]
+    pub longitude: String,
+    /// API key, only needed for OpenWeatherMap
+    #[serde(default)]
+    pub api_key: String,
+}
+
+/// Rough weather condition, used to pick an icon
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum WeatherCondition {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Fog,
+    Drizzle,
+    Rain,
+    Snow,
+    Thunderstorm,
+}
+
+impl WeatherCondition {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            WeatherCondition::Clear => "☀",
+            WeatherCondition::PartlyCloudy => "⛅",
+            WeatherCondition::Cloudy => "☁",
+            WeatherCondition::Fog => "🌫",
+            WeatherCondition::Drizzle => "🌦",
+            WeatherCondition::Rain => "🌧",
+            WeatherCondition::Snow => "❄",
+            WeatherCondition::Thunderstorm => "⛈",
+        }
+    }
+
+    /// Condition from WMO weather code that Open-Meteo uses
+    fn from_wmo_code(code: u64) -> WeatherCondition {
+        match code {
+            0 => WeatherCondition::Clear,
+            1 | 2 => WeatherCondition::PartlyCloudy,
+            3 => WeatherCondition::Cloudy,
+            45 | 48 => WeatherCondition::Fog,
+            51..=57 => WeatherCondition::Drizzle,
+            71..=77 | 85 | 86 => WeatherCondition::Snow,
+            95..=99 => WeatherCondition::Thunderstorm,
+            _ => WeatherCondition::Rain
+        }
+    }
+
+    /// Condition from OpenWeatherMap condition ID
+    fn from_owm_id(id: u64) -> WeatherCondition {
+        match id {
+            200..=299 => WeatherCondition::Thunderstorm,
+            300..=399 => WeatherCondition::Drizzle,
+            500..=599 => WeatherCondition::Rain,
+            600..=699 => WeatherCondition::Snow,
+            700..=799 => WeatherCondition::Fog,
+            800 => WeatherCondition::Clear,
+            801 | 802 => WeatherCondition::PartlyCloudy,
+            _ => WeatherCondition::Cloudy
+        }
+    }
+}
+
+/// Current weather
+#[derive(Debug, Clone, Copy)]
+pub struct Weather {
+    /// Temperature in Celsius
+    pub temperature: f32,
+    pub condition: WeatherCondition,
+}
+
+/// Error that might occur while retrieving weather
+#[derive(Debug)]
+pub enum WeatherError {
+    /// Latitude, longitude or API key are missing or invalid
+    InvalidQuery,
+    /// Service refused the request due to rate limiting
+    RateLimited,
+    RequestError(String),
+    MalformedResponse,
+}
+
+impl From<ureq::Error> for WeatherError {
+    fn from(err: ureq::Error) -> Self {
+        match err {
+            ureq::Error::Status(401, _) | ureq::Error::Status(400, _) => WeatherError::InvalidQuery,
+            ureq::Error::Status(429, _) => WeatherError::RateLimited,
+            err => WeatherError::RequestError(err.to_string())
+        }
+    }
+}
+
+impl From<std::io::Error> for WeatherError {
+    fn from(err: std::io::Error) -> Self {
+        WeatherError::RequestError(err.to_string())
+    }
+}
+
+impl WeatherQuery {
+    fn coordinates(&self) -> Result<(f32, f32), WeatherError> {
+        let latitude = self.latitude.trim().parse::<f32>().map_err(|_| WeatherError::InvalidQuery)?;
+        let longitude = self.longitude.trim().parse::<f32>().map_err(|_| WeatherError::InvalidQuery)?;
+        Ok((latitude, longitude))
+    }
+
+    /// Retrieves current weather
+    pub fn current(&self) -> Result<Weather, WeatherError> {
+        let (latitude, longitude) = self.coordinates()?;
+
+        match self.provider {
+            WeatherProvider::OpenMeteo => {
+                let response: Value = ureq::get(OPEN_METEO_API)
+                    .timeout(REQUEST_TIMEOUT)
+                    .query("latitude", &latitude.to_string())
+                    .query("longitude", &longitude.to_string())
+                    .query("current_weather", "true")
+                    .call()?
+                    .into_json()?;
+
+                let current = &response["current_weather"];
+
+                Ok(Weather {
+                    temperature: current["temperature"].as_f64().ok_or(WeatherError::MalformedResponse)? as f32,
+                    condition: WeatherCondition::from_wmo_code(current["weathercode"].as_u64().unwrap_or(0))
+                })
+            }
+
+            WeatherProvider::OpenWeatherMap => {
+                if self.api_key.is_empty() {
+                    return Err(WeatherError::InvalidQuery);
+                }
+
+                let response: Value = ureq::get(OPEN_WEATHER_MAP_API)
+                    .timeout(REQUEST_TIMEOUT)
+                    .query("lat", &latitude.to_string())
+                    .query("lon", &longitude.to_string())
+                    .query("units", "metric")
+                    .query("appid", &self.api_key)
+                    .call()?
+                    .into_json()?;
+
+                Ok(Weather {
+                    temperature: response["main"]["temp"].as_f64().ok_or(WeatherError::MalformedResponse)? as f32,
+                    condition: WeatherCondition::from_owm_id(response["weather"][0]["id"].as_u64().unwrap_or(800))
+                })
+            }
+        }
+    }
+}
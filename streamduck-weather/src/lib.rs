@@ -0,0 +1,428 @@
+mod api;
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{sleep, spawn};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use streamduck_core::core::button::{Button, Component, parse_button_to_component, parse_unique_button_to_component};
+use streamduck_core::core::methods::CoreHandle;
+use streamduck_core::core::UniqueButton;
+use streamduck_core::font::get_font_from_collection;
+use streamduck_core::image::DynamicImage;
+use streamduck_core::modules::components::{ComponentDefinition, FieldError, map_ui_values, UIFieldType, UIFieldValue, UIValue};
+use streamduck_core::modules::{ModuleManager, PluginMetadata, SDModule, SDModulePointer};
+use streamduck_core::socket::SocketManager;
+use streamduck_core::thread::rendering::{ButtonBackground, RendererComponentBuilder};
+use streamduck_core::thread::rendering::custom::RenderingManager;
+use streamduck_core::thread::util::{render_aligned_text_on_image, TextAlignment};
+use streamduck_core::util::rusttype::Scale;
+use streamduck_core::util::straight_copy;
+use streamduck_core::versions::{PLUGIN_API, RENDERING, SDMODULE_TRAIT};
+use streamduck_core_derive::component;
+use crate::api::{Weather, WeatherError, WeatherProvider, WeatherQuery};
+
+/// Refresh interval in minutes that new buttons start with
+const DEFAULT_INTERVAL: u32 = 15;
+/// Lowest refresh interval in minutes, weather services don't update more often than that anyway
+const MIN_INTERVAL: u32 = 5;
+/// Wait before retrying after a failed request
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+/// Wait after service reported rate limiting
+const RATE_LIMIT_INTERVAL: Duration = Duration::from_secs(30 * 60);
+/// Locations that weren't rendered for this long are no longer refreshed
+const UNUSED_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+#[no_mangle]
+pub fn get_metadata() -> PluginMetadata {
+    PluginMetadata::from_literals(
+        "weather",
+        "TheJebForge",
+        "Displays current temperature and weather conditions from Open-Meteo or OpenWeatherMap",
+        "0.1",
+        &[
+            PLUGIN_API,
+            SDMODULE_TRAIT,
+            RENDERING
+        ]
+    )
+}
+
+#[no_mangle]
+pub fn get_module() -> SDModulePointer {
+    Box::into_raw(Box::new(WeatherModule::new()))
+}
+
+#[no_mangle]
+pub fn register(_: Arc<SocketManager>, _: Arc<RenderingManager>, _: Arc<ModuleManager>) {}
+
+/// Cached weather of a location
+struct LocationState {
+    weather: Option<Weather>,
+    next_fetch: Instant,
+    /// Shortest interval requested by buttons showing the location
+    interval: Duration,
+    last_used: Instant,
+}
+
+type StateMap = Arc<RwLock<HashMap<WeatherQuery, LocationState>>>;
+
+pub struct WeatherModule {
+    states: StateMap,
+    /// Polling thread stops once this is false
+    running: Arc<AtomicBool>,
+}
+
+impl WeatherModule {
+    pub fn new() -> WeatherModule {
+        let states: StateMap = Default::default();
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread_states = states.clone();
+        let thread_running = running.clone();
+        spawn(move || {
+            while thread_running.load(Ordering::Relaxed) {
+                thread_states.write().unwrap().retain(|_, state| state.last_used.elapsed() < UNUSED_TIMEOUT);
+
+                let due: Vec<WeatherQuery> = thread_states.read().unwrap().iter()
+                    .filter(|(_, state)| state.next_fetch <= Instant::now())
+                    .map(|(query, _)| query.clone())
+                    .collect();
+
+                for query in due {
+                    let result = query.current();
+
+                    let mut lock = thread_states.write().unwrap();
+                    let state = if let Some(state) = lock.get_mut(&query) { state } else { continue };
+
+                    match result {
+                        Ok(weather) => {
+                            state.weather = Some(weather);
+                            state.next_fetch = Instant::now() + state.interval;
+                        }
+
+                        Err(WeatherError::RateLimited) => {
+                            log::warn!("Rate limited by {}, waiting {} minutes", query.provider.name(), RATE_LIMIT_INTERVAL.as_secs() / 60);
+                            state.next_fetch = Instant::now() + RATE_LIMIT_INTERVAL.max(state.interval);
+                        }
+
+                        Err(err) => {
+                            log::debug!("Failed to retrieve weather for {}, {}: {:?}", query.latitude, query.longitude, err);
+                            state.next_fetch = Instant::now() + RETRY_INTERVAL.min(state.interval);
+                        }
+                    }
+                }
+
+                sleep(Duration::from_secs(1));
+            }
+        });
+
+        WeatherModule {
+            states,
+            running
+        }
+    }
+
+    /// Gets cached weather of the location, starts refreshing the location if it wasn't refreshed before
+    fn get_weather(&self, component: &WeatherComponent) -> Option<Weather> {
+        let interval = Duration::from_secs(component.interval.max(MIN_INTERVAL) as u64 * 60);
+
+        let mut lock = self.states.write().unwrap();
+        let state = lock.entry(component.query.clone()).or_insert_with(|| LocationState {
+            weather: None,
+            next_fetch: Instant::now(),
+            interval,
+            last_used: Instant::now()
+        });
+
+        state.last_used = Instant::now();
+
+        if interval < state.interval {
+            state.next_fetch = state.next_fetch.min(Instant::now() + interval);
+            state.interval = interval;
+        }
+
+        state.weather
+    }
+}
+
+impl Drop for WeatherModule {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+impl SDModule for WeatherModule {
+    fn name(&self) -> String {
+        "weather".to_string()
+    }
+
+    fn components(&self) -> HashMap<String, ComponentDefinition> {
+        let mut map = HashMap::new();
+
+        map.insert(WeatherComponent::NAME.to_string(), ComponentDefinition {
+            display_name: "Weather".to_string(),
+            description: "Displays current temperature and weather icon of a location".to_string(),
+            default_looks: RendererComponentBuilder::new()
+                .background(ButtonBackground::Solid((40, 60, 90, 255)))
+                .build(),
+            requires: vec![],
+            conflicts_with: vec![]
+        });
+
+        map
+    }
+
+    fn add_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            WeatherComponent::NAME => {
+                button.insert_component(WeatherComponent::default()).ok();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn remove_component(&self, _: CoreHandle, button: &mut Button, name: &str) {
+        match name {
+            WeatherComponent::NAME => {
+                button.remove_component::<WeatherComponent>();
+            }
+
+            _ => {}
+        }
+    }
+
+    fn paste_component(&self, _: CoreHandle, reference_button: &Button, new_button: &mut Button) {
+        straight_copy(reference_button, new_button, WeatherComponent::NAME);
+    }
+
+    fn component_values(&self, _: CoreHandle, button: &Button, name: &str) -> Vec<UIValue> {
+        match name {
+            WeatherComponent::NAME => {
+                if let Ok(component) = parse_button_to_component::<WeatherComponent>(button) {
+                    get_values(&component)
+                } else {
+                    vec![]
+                }
+            }
+
+            _ => vec![],
+        }
+    }
+
+    fn set_component_value(&self, _: CoreHandle, button: &mut Button, name: &str, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+        match name {
+            WeatherComponent::NAME => {
+                if let Ok(mut component) = parse_button_to_component::<WeatherComponent>(button) {
+                    set_values(&mut component, value)?;
+                    button.insert_component(component).ok();
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn listening_for(&self) -> Vec<String> {
+        vec![
+            WeatherComponent::NAME.to_string()
+        ]
+    }
+
+    fn render(&self, core: CoreHandle, button: &UniqueButton, frame: &mut DynamicImage) {
+        if let Ok(component) = parse_unique_button_to_component::<WeatherComponent>(button) {
+            if let Some(font) = get_font_from_collection("default") {
+                let size = core.core().image_size;
+                let color = (255, 255, 255, 255);
+
+                match self.get_weather(&component) {
+                    Some(weather) => {
+                        let temperature = format!("{}°", component.units.convert(weather.temperature).round() as i32);
+
+                        render_aligned_text_on_image(size, frame, font.as_ref(), weather.condition.icon(), Scale::uniform(32.0), TextAlignment::TopCenter, 6, (0.0, 0.0), color);
+                        render_aligned_text_on_image(size, frame, font.as_ref(), &temperature, Scale::uniform(22.0), TextAlignment::BottomCenter, 6, (0.0, 0.0), color);
+                    }
+
+                    None => {
+                        render_aligned_text_on_image(size, frame, font.as_ref(), "...", Scale::uniform(22.0), TextAlignment::Center, 0, (0.0, 0.0), color);
+                    }
+                }
+            }
+        }
+    }
+
+    fn render_hash(&self, _: CoreHandle, button: &UniqueButton, hash: &mut Box<dyn Hasher>) {
+        if let Ok(component) = parse_unique_button_to_component::<WeatherComponent>(button) {
+            if let Some(weather) = self.get_weather(&component) {
+                (component.units.convert(weather.temperature).round() as i32).hash(hash);
+                weather.condition.hash(hash);
+            }
+        }
+    }
+
+    fn metadata(&self) -> PluginMetadata {
+        get_metadata()
+    }
+}
+
+/// Unit that temperature is displayed in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum TemperatureUnits {
+    Celsius,
+    Fahrenheit,
+}
+
+impl Default for TemperatureUnits {
+    fn default() -> Self {
+        TemperatureUnits::Celsius
+    }
+}
+
+impl TemperatureUnits {
+    pub const VARIANTS: [&'static str; 2] = ["Celsius", "Fahrenheit"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TemperatureUnits::Celsius => "Celsius",
+            TemperatureUnits::Fahrenheit => "Fahrenheit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<TemperatureUnits> {
+        match name {
+            "Celsius" => Some(TemperatureUnits::Celsius),
+            "Fahrenheit" => Some(TemperatureUnits::Fahrenheit),
+            _ => None
+        }
+    }
+
+    /// Converts temperature from Celsius
+    pub fn convert(&self, celsius: f32) -> f32 {
+        match self {
+            TemperatureUnits::Celsius => celsius,
+            TemperatureUnits::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+}
+
+/// Component that displays current weather of a location
+#[component("weather")]
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WeatherComponent {
+    #[serde(default)]
+    pub query: WeatherQuery,
+    #[serde(default)]
+    pub units: TemperatureUnits,
+    /// Refresh interval in minutes
+    #[serde(default = "default_interval")]
+    pub interval: u32,
+}
+
+fn default_interval() -> u32 { DEFAULT_INTERVAL }
+
+impl Default for WeatherComponent {
+    fn default() -> Self {
+        Self {
+            query: Default::default(),
+            units: Default::default(),
+            interval: default_interval()
+        }
+    }
+}
+
+fn string_field(name: &str, display_name: &str, description: &str, value: &str) -> UIValue {
+    UIValue {
+        name: name.to_string(),
+        display_name: display_name.to_string(),
+        description: description.to_string(),
+        ty: UIFieldType::InputFieldString,
+        value: UIFieldValue::InputFieldString(value.to_string())
+    }
+}
+
+fn get_values(component: &WeatherComponent) -> Vec<UIValue> {
+    let query = &component.query;
+
+    let mut fields = vec![
+        UIValue {
+            name: "provider".to_string(),
+            display_name: "Provider".to_string(),
+            description: "Service to retrieve weather from".to_string(),
+            ty: UIFieldType::Choice(WeatherProvider::VARIANTS.iter().map(|x| x.to_string()).collect()),
+            value: UIFieldValue::Choice(query.provider.name().to_string())
+        },
+        string_field("latitude", "Latitude", "Latitude of the location, eg. 51.51", &query.latitude),
+        string_field("longitude", "Longitude", "Longitude of the location, eg. -0.13", &query.longitude),
+    ];
+
+    if query.provider == WeatherProvider::OpenWeatherMap {
+        fields.push(string_field("api_key", "API Key", "OpenWeatherMap API key", &query.api_key));
+    }
+
+    fields.push(UIValue {
+        name: "units".to_string(),
+        display_name: "Units".to_string(),
+        description: "Unit to display temperature in".to_string(),
+        ty: UIFieldType::Choice(TemperatureUnits::VARIANTS.iter().map(|x| x.to_string()).collect()),
+        value: UIFieldValue::Choice(component.units.name().to_string())
+    });
+
+    fields.push(UIValue {
+        name: "interval".to_string(),
+        display_name: "Refresh Interval".to_string(),
+        description: format!("Minutes between weather updates, at least {}. Buttons showing the same location share updates", MIN_INTERVAL),
+        ty: UIFieldType::InputFieldUnsignedInteger,
+        value: UIFieldValue::InputFieldUnsignedInteger(component.interval)
+    });
+
+    fields
+}
+
+fn set_values(component: &mut WeatherComponent, value: Vec<UIValue>) -> Result<(), Vec<FieldError>> {
+    let change_map = map_ui_values(value);
+
+    if let Some(value) = change_map.get("provider") {
+        if let Ok(provider) = value.value.try_into_string() {
+            if let Some(provider) = WeatherProvider::from_name(&provider) {
+                component.query.provider = provider;
+            }
+        }
+    }
+
+    if let Some(value) = change_map.get("units") {
+        if let Ok(units) = value.value.try_into_string() {
+            if let Some(units) = TemperatureUnits::from_name(&units) {
+                component.units = units;
+            }
+        }
+    }
+
+    for (name, field) in [
+        ("latitude", &mut component.query.latitude),
+        ("longitude", &mut component.query.longitude),
+        ("api_key", &mut component.query.api_key),
+    ] {
+        if let Some(value) = change_map.get(name) {
+            if let Ok(str) = value.value.try_into_string() {
+                *field = str.trim().to_string();
+            }
+        }
+    }
+
+    if let Some(value) = change_map.get("interval") {
+        if let Ok(interval) = value.value.try_into_u32() {
+            if interval < MIN_INTERVAL {
+                return Err(vec![FieldError::new("interval", &format!("Interval can't be less than {} minutes", MIN_INTERVAL))]);
+            }
+
+            component.interval = interval;
+        }
+    }
+
+    Ok(())
+}